@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use super::model::LLMProvider;
+use crate::llm::{estimate_cost, GenerationResponse};
+
+/// Limits on what generation may spend; unset limits don't apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// Estimated cost in USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost: Option<f64>,
+}
+
+/// Tokens and estimated cost (USD) spent so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Spend {
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+impl Spend {
+    /// What a response from `provider` used
+    pub fn of_response(provider: &LLMProvider, response: &GenerationResponse) -> Self {
+        let cost = estimate_cost(
+            provider,
+            &response.model,
+            response.input_tokens.unwrap_or(0),
+            response.output_tokens.unwrap_or(0),
+        );
+        let mut spend = Spend::default();
+        spend.add(response.tokens_used, cost);
+        spend
+    }
+
+    pub fn add(&mut self, tokens: Option<u32>, cost: Option<f64>) {
+        self.tokens += tokens.unwrap_or(0) as u64;
+        self.cost += cost.unwrap_or(0.0);
+    }
+}
+
+impl std::ops::AddAssign for Spend {
+    fn add_assign(&mut self, other: Spend) {
+        self.tokens += other.tokens;
+        self.cost += other.cost;
+    }
+}
+
+impl Budget {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost.is_none()
+    }
+
+    /// Describe the limit `spend` has reached, if any
+    pub fn exceeded(&self, spend: Spend) -> Option<String> {
+        if let Some(max_tokens) = self.max_tokens.filter(|max| spend.tokens >= *max) {
+            return Some(format!("{} of {} tokens used", spend.tokens, max_tokens));
+        }
+        if let Some(max_cost) = self.max_cost.filter(|max| spend.cost >= *max) {
+            return Some(format!("${:.2} of ${:.2} spent", spend.cost, max_cost));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget() {
+        let mut spend = Spend::default();
+        spend.add(Some(900), Some(0.5));
+        spend.add(None, None);
+        assert_eq!(spend, Spend { tokens: 900, cost: 0.5 });
+        let mut total = Spend::default();
+        total += spend;
+        assert_eq!(total, spend);
+
+        assert!(Budget::default().is_unlimited());
+        assert_eq!(Budget::default().exceeded(spend), None);
+
+        let tokens = Budget {
+            max_tokens: Some(1000),
+            max_cost: None,
+        };
+        assert_eq!(tokens.exceeded(spend), None);
+        spend.add(Some(100), None);
+        assert_eq!(tokens.exceeded(spend).unwrap(), "1000 of 1000 tokens used");
+
+        let cost = Budget {
+            max_tokens: None,
+            max_cost: Some(0.5),
+        };
+        assert_eq!(cost.exceeded(spend).unwrap(), "$0.50 of $0.50 spent");
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::model::{CodeEdge, CodeNode, Project, ProjectManifest};
+
+const EVENTS_FILE: &str = ".needlepoint/events.jsonl";
+
+/// A single change to the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Change {
+    /// The project was (re)loaded; clients should fetch it in full
+    ProjectLoaded,
+    ManifestUpdated { manifest: ProjectManifest },
+    NodeAdded { node: CodeNode },
+    NodeUpdated { node: CodeNode },
+    /// The node's generated code changed (the node is included in full)
+    CodeGenerated { node: CodeNode },
+    #[serde(rename_all = "camelCase")]
+    NodeRemoved { node_id: String },
+    EdgeAdded { edge: CodeEdge },
+    EdgeUpdated { edge: CodeEdge },
+    #[serde(rename_all = "camelCase")]
+    EdgeRemoved { edge_id: String },
+}
+
+/// A change as recorded in `.needlepoint/events.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    /// Project revision the change produced
+    pub revision: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+fn events_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(EVENTS_FILE)
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// The changes that turn `before` into `after`
+pub fn diff_projects(before: &Project, after: &Project) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if !same(&before.manifest, &after.manifest) {
+        changes.push(Change::ManifestUpdated {
+            manifest: after.manifest.clone(),
+        });
+    }
+
+    let old_nodes: HashMap<&str, &CodeNode> =
+        before.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    for node in &after.nodes {
+        match old_nodes.get(node.id.as_str()) {
+            None => changes.push(Change::NodeAdded { node: node.clone() }),
+            Some(old) if old.generated_code != node.generated_code => {
+                changes.push(Change::CodeGenerated { node: node.clone() })
+            }
+            Some(old) if !same(*old, node) => {
+                changes.push(Change::NodeUpdated { node: node.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+    for node in &before.nodes {
+        if after.find_node(&node.id).is_none() {
+            changes.push(Change::NodeRemoved {
+                node_id: node.id.clone(),
+            });
+        }
+    }
+
+    let old_edges: HashMap<&str, &CodeEdge> =
+        before.edges.iter().map(|e| (e.id.as_str(), e)).collect();
+    for edge in &after.edges {
+        match old_edges.get(edge.id.as_str()) {
+            None => changes.push(Change::EdgeAdded { edge: edge.clone() }),
+            Some(old) if !same(*old, edge) => {
+                changes.push(Change::EdgeUpdated { edge: edge.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+    for edge in &before.edges {
+        if !after.edges.iter().any(|e| e.id == edge.id) {
+            changes.push(Change::EdgeRemoved {
+                edge_id: edge.id.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Stamp changes with the revision they produced
+pub fn to_events(revision: u64, changes: Vec<Change>) -> Vec<ChangeEvent> {
+    let timestamp = Utc::now();
+    changes
+        .into_iter()
+        .map(|change| ChangeEvent {
+            revision,
+            timestamp,
+            change,
+        })
+        .collect()
+}
+
+/// Append events to the project's event log
+pub fn append_events(project_path: &str, events: &[ChangeEvent]) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let path = events_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+
+    let mut lines = String::new();
+    for event in events {
+        lines.push_str(&serde_json::to_string(event)?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open event log: {:?}", path))?;
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("Failed to write event log: {:?}", path))?;
+    Ok(())
+}
+
+/// Events recorded after revision `since`, oldest first. Unreadable lines are skipped.
+pub fn load_changes(project_path: &str, since: u64) -> Result<Vec<ChangeEvent>> {
+    let path = events_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read event log: {:?}", path))?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ChangeEvent>(line).ok())
+        .filter(|event| event.revision > since)
+        .collect())
+}
+
+/// The highest revision in the project's event log
+pub fn last_revision(project_path: &str) -> Option<u64> {
+    let contents = fs::read_to_string(events_path(project_path)).ok()?;
+    contents
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<ChangeEvent>(line).ok())
+        .map(|event| event.revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_diff_and_log_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let mut before = Project::new(path.clone());
+        let a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let (a_id, b_id) = (a.id.clone(), b.id.clone());
+        before.nodes.extend([a, b]);
+        let edge = CodeEdge::new(a_id.clone(), b_id.clone(), String::new());
+        before.edges.push(edge.clone());
+
+        let mut after = before.clone();
+        after.find_node_mut(&a_id).unwrap().generated_code = Some("code".to_string());
+        after.nodes.retain(|n| n.id != b_id);
+        after.edges.clear();
+        let c = CodeNode::new("C".to_string(), "c.ts".to_string(), Language::TypeScript);
+        after.nodes.push(c);
+
+        let changes = diff_projects(&before, &after);
+        let types: Vec<&str> = changes
+            .iter()
+            .map(|c| match c {
+                Change::CodeGenerated { .. } => "codeGenerated",
+                Change::NodeAdded { .. } => "nodeAdded",
+                Change::NodeRemoved { .. } => "nodeRemoved",
+                Change::EdgeRemoved { .. } => "edgeRemoved",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(types, vec!["codeGenerated", "nodeAdded", "nodeRemoved", "edgeRemoved"]);
+        assert!(diff_projects(&after, &after).is_empty());
+
+        append_events(&path, &to_events(3, vec![Change::ProjectLoaded])).unwrap();
+        append_events(&path, &to_events(4, changes)).unwrap();
+        assert_eq!(last_revision(&path), Some(4));
+        assert_eq!(load_changes(&path, 0).unwrap().len(), 5);
+        let recent = load_changes(&path, 3).unwrap();
+        assert_eq!(recent.len(), 4);
+        assert!(matches!(recent[3].change, Change::EdgeRemoved { ref edge_id } if *edge_id == edge.id));
+    }
+}
@@ -0,0 +1,460 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::llm::{
+    generate_with_timeout, strip_code_blocks, GenerationRequest, GenerationResponse, LLMProvider,
+};
+
+use super::model::{CodeNode, Language, NodeStatus, ProjectManifest};
+use super::variables::resolve_node;
+
+/// A constraint that could be turned into a check on the generated code
+#[derive(Debug, Clone)]
+pub enum ConstraintRule {
+    /// The code must not match the pattern
+    Forbid(Regex),
+    /// The code must match the pattern
+    Require(Regex),
+    /// The code must export a symbol with this name
+    MustExport(String),
+    /// The code must not be longer than this many lines
+    MaxLines(usize),
+}
+
+/// A constraint the generated code doesn't satisfy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintViolation {
+    /// The constraint as written on the node
+    pub constraint: String,
+    pub message: String,
+    /// 1-based line of the first offending match, when there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// `any` used as a type annotation, assertion or generic argument
+const TS_ANY: &str = r"(:\s*any\b|\bas\s+any\b|<any>|\bany\[\])";
+
+/// The quoted part of `` `x` ``, or a bare token that is clearly code (`console.log`,
+/// `eval()`); plain words are too vague to check
+fn code_term(text: &str) -> Option<&str> {
+    let text = text.trim();
+    if let Some(quoted) = text.strip_prefix('`').and_then(|t| t.split('`').next()) {
+        return Some(quoted).filter(|q| !q.is_empty());
+    }
+    let looks_like_code =
+        !text.contains(char::is_whitespace) && text.contains(['.', '(', '_', ':']);
+    Some(text).filter(|_| looks_like_code)
+}
+
+/// A regex matching `term` literally, as a whole word where it starts or ends with one
+fn literal(term: &str) -> Option<Regex> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut pattern = regex::escape(term);
+    if term.starts_with(is_word) {
+        pattern = format!(r"\b{}", pattern);
+    }
+    if term.ends_with(is_word) {
+        pattern.push_str(r"\b");
+    }
+    Regex::new(&pattern).ok()
+}
+
+/// `text` without the first of `prefixes` it starts with, ignoring ASCII case
+fn strip_any_prefix<'a>(text: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes.iter().find_map(|prefix| {
+        let head = text.get(..prefix.len())?;
+        head.eq_ignore_ascii_case(prefix).then(|| &text[prefix.len()..])
+    })
+}
+
+/// `text` without the first of `suffixes` it ends with, ignoring ASCII case
+fn strip_any_suffix<'a>(text: &'a str, suffixes: &[&str]) -> Option<&'a str> {
+    suffixes.iter().find_map(|suffix| {
+        let start = text.len().checked_sub(suffix.len())?;
+        let tail = text.get(start..)?;
+        tail.eq_ignore_ascii_case(suffix).then(|| &text[..start])
+    })
+}
+
+/// Turn a free-text constraint into a rule, where it follows one of the
+/// recognised forms:
+///
+/// - `forbid: <regex>` / `require: <regex>`
+/// - ``no `x` `` (also "avoid", "do not use", "never use", ...)
+/// - ``must use `x` `` (also "must contain", "must include")
+/// - `no any type` (TypeScript and JavaScript)
+/// - `must export [function|class|...] Name`
+/// - `max N lines` (also "at most", "no more than", "under")
+///
+/// Anything else is left to the model.
+pub fn parse_constraint(constraint: &str) -> Option<ConstraintRule> {
+    let text = constraint.trim().trim_end_matches('.');
+
+    if let Some(pattern) = strip_any_prefix(text, &["forbid:"]) {
+        return Regex::new(pattern.trim()).ok().map(ConstraintRule::Forbid);
+    }
+    if let Some(pattern) = strip_any_prefix(text, &["require:"]) {
+        return Regex::new(pattern.trim()).ok().map(ConstraintRule::Require);
+    }
+
+    let any_type = ["no any", "no `any`", "avoid any", "avoid `any`", "never use any"];
+    let without_type = strip_any_suffix(text, &[" types", " type"]).unwrap_or(text);
+    if any_type.iter().any(|p| without_type.eq_ignore_ascii_case(p)) {
+        return Regex::new(TS_ANY).ok().map(ConstraintRule::Forbid);
+    }
+
+    if let Some(rest) = strip_any_prefix(text, &["must export "]) {
+        let name = rest
+            .split_whitespace()
+            .map(|w| w.trim_matches('`').trim_end_matches("()"))
+            .rfind(|w| !w.is_empty())?;
+        let valid = name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+        return Some(ConstraintRule::MustExport(name.to_string())).filter(|_| valid);
+    }
+
+    // "under 200 lines" allows at most 199
+    for (prefixes, exclusive) in [
+        (&["max ", "at most ", "no more than ", "maximum of "][..], false),
+        (&["under ", "fewer than ", "less than "][..], true),
+    ] {
+        let Some(rest) = strip_any_prefix(text, prefixes) else {
+            continue;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(count), Some(unit)) = (words.next(), words.next()) else {
+            continue;
+        };
+        if !unit.eq_ignore_ascii_case("lines") {
+            continue;
+        }
+        let count: usize = count.parse().ok()?;
+        return Some(ConstraintRule::MaxLines(if exclusive {
+            count.saturating_sub(1)
+        } else {
+            count
+        }));
+    }
+
+    let forbid = ["no ", "avoid ", "do not use ", "don't use ", "never use ", "must not use "];
+    if let Some(rest) = strip_any_prefix(text, &forbid) {
+        return literal(code_term(rest)?).map(ConstraintRule::Forbid);
+    }
+
+    let require = ["must use ", "must contain ", "must include ", "always use "];
+    if let Some(rest) = strip_any_prefix(text, &require) {
+        return literal(code_term(rest)?).map(ConstraintRule::Require);
+    }
+
+    None
+}
+
+/// How a top-level `name` is exported in the node's language. Languages without a
+/// reliable marker only need the name to be declared somewhere.
+fn export_pattern(language: &Language, name: &str) -> Option<Regex> {
+    let name = regex::escape(name);
+    let pattern = match language {
+        Language::TypeScript | Language::JavaScript => format!(
+            r"(?m)^\s*export\b[^\n]*\b{0}\b|export\s*\{{[^}}]*\b{0}\b|\bmodule\.exports\b[^;]*\b{0}\b|\bexports\.{0}\b",
+            name
+        ),
+        Language::Python => format!(r"(?m)^(async\s+def|def|class)\s+{0}\b|^{0}\s*[:=]", name),
+        Language::Rust => format!(
+            r"\bpub(\([^)]*\))?\s+(async\s+)?(fn|struct|enum|trait|type|const|static|mod)\s+{}\b",
+            name
+        ),
+        Language::Go => format!(
+            r"(?m)^(func\s+(\([^)]*\)\s*)?|type\s+|var\s+|const\s+)?{0}\b|^\s+{0}\s*(=|[A-Za-z\[*])",
+            name
+        ),
+        Language::Bash => format!(r"(?m)^\s*(function\s+)?{0}\s*\(\)|^\s*function\s+{0}\b", name),
+        _ => format!(
+            r"(?m)\b(class|interface|enum|struct|record|object|trait|protocol|fun|func|function|def|val|var|let|const|typedef|using|namespace|module)\s+{}\b|\b{}\s*\(",
+            name, name
+        ),
+    };
+    Regex::new(&pattern).ok()
+}
+
+/// Whether `code` exports (or, where the language has no marker, declares) `name`
+pub fn is_exported(language: &Language, code: &str, name: &str) -> bool {
+    export_pattern(language, name).is_some_and(|p| p.is_match(code))
+}
+
+/// Names of a node's declared exports that the code doesn't export
+pub fn missing_exports(node: &CodeNode, code: &str) -> Vec<String> {
+    node.exports
+        .iter()
+        .filter(|export| !is_exported(&node.language, code, &export.name))
+        .map(|export| export.name.clone())
+        .collect()
+}
+
+fn line_of(code: &str, offset: usize) -> usize {
+    code[..offset].matches('\n').count() + 1
+}
+
+/// Check code against the node's constraints. Constraints that can't be turned
+/// into a rule are skipped.
+pub fn check_constraints(node: &CodeNode, code: &str) -> Vec<ConstraintViolation> {
+    let mut violations = Vec::new();
+    for constraint in &node.llm_config.constraints {
+        let Some(rule) = parse_constraint(constraint) else {
+            continue;
+        };
+        let violation = |message: String, line: Option<usize>| ConstraintViolation {
+            constraint: constraint.clone(),
+            message,
+            line,
+        };
+        match rule {
+            ConstraintRule::Forbid(pattern) => {
+                if let Some(found) = pattern.find(code) {
+                    violations.push(violation(
+                        format!("Found `{}`", found.as_str().trim()),
+                        Some(line_of(code, found.start())),
+                    ));
+                }
+            }
+            ConstraintRule::Require(pattern) => {
+                if !pattern.is_match(code) {
+                    violations.push(violation(format!("Nothing matches `{}`", pattern), None));
+                }
+            }
+            ConstraintRule::MustExport(name) => {
+                if !is_exported(&node.language, code, &name) {
+                    violations.push(violation(format!("`{}` is not exported", name), None));
+                }
+            }
+            ConstraintRule::MaxLines(max) => {
+                let lines = code.lines().count();
+                if lines > max {
+                    violations.push(violation(
+                        format!("{} lines, {} allowed", lines, max),
+                        Some(max + 1),
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Start of the error message of a node whose code lacks declared exports
+const MISSING_EXPORTS_WARNING: &str = "Missing declared exports: ";
+
+/// Check a node's generated code and record constraint violations and missing
+/// exports on the node, with the spec's placeholders substituted. A complete node
+/// that lacks declared exports becomes a warning, and goes back to complete once
+/// they're all there.
+pub fn verify_generated_code(manifest: &ProjectManifest, node: &mut CodeNode) {
+    let Some(code) = &node.generated_code else {
+        node.constraint_violations.clear();
+        node.missing_exports.clear();
+        return;
+    };
+    let resolved = resolve_node(manifest, node);
+    node.constraint_violations = check_constraints(&resolved, code);
+    node.missing_exports = missing_exports(&resolved, code);
+
+    let warned = node.status == NodeStatus::Warning
+        && node.error_message.as_deref().is_some_and(|e| e.starts_with(MISSING_EXPORTS_WARNING));
+    if !node.missing_exports.is_empty() && (node.status == NodeStatus::Complete || warned) {
+        node.status = NodeStatus::Warning;
+        node.error_message = Some(format!(
+            "{}{}",
+            MISSING_EXPORTS_WARNING,
+            node.missing_exports.join(", ")
+        ));
+    } else if node.missing_exports.is_empty() && warned {
+        node.status = NodeStatus::Complete;
+        node.error_message = None;
+    }
+}
+
+/// Which problems to regenerate once for, from the manifest settings
+#[derive(Debug, Clone, Copy)]
+pub struct FixupOptions {
+    pub constraints: bool,
+    pub exports: bool,
+}
+
+impl FixupOptions {
+    /// `None` when the manifest enables no fix-up regeneration
+    pub fn from_manifest(manifest: &ProjectManifest) -> Option<Self> {
+        let options = Self {
+            constraints: manifest.fix_constraint_violations,
+            exports: manifest.retry_missing_exports,
+        };
+        (options.constraints || options.exports).then_some(options)
+    }
+
+    /// The problems these options cover, as constraint violations and missing exports
+    fn problems(&self, node: &CodeNode, code: &str) -> (Vec<ConstraintViolation>, Vec<String>) {
+        let violations = if self.constraints {
+            check_constraints(node, code)
+        } else {
+            Vec::new()
+        };
+        let missing = if self.exports {
+            missing_exports(node, code)
+        } else {
+            Vec::new()
+        };
+        (violations, missing)
+    }
+}
+
+/// Extend a generation prompt with the previous attempt and what it got wrong
+pub fn build_fixup_prompt(
+    prompt: &str,
+    code: &str,
+    violations: &[ConstraintViolation],
+    missing_exports: &[String],
+) -> String {
+    let mut fixup = format!("{}\n\n## Previous attempt\n```\n{}\n```\n\n", prompt, code);
+    if !violations.is_empty() {
+        fixup.push_str("The previous attempt violates these constraints:\n");
+        for violation in violations {
+            match violation.line {
+                Some(line) => fixup.push_str(&format!(
+                    "- {} (line {}: {})\n",
+                    violation.constraint, line, violation.message
+                )),
+                None => fixup
+                    .push_str(&format!("- {} ({})\n", violation.constraint, violation.message)),
+            }
+        }
+        fixup.push('\n');
+    }
+    if !missing_exports.is_empty() {
+        fixup.push_str(&format!(
+            "You forgot to export {}. Export every declared signature under its exact name.\n\n",
+            missing_exports
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    fixup.push_str(
+        "Rewrite the complete file to fix these problems, changing nothing else. \
+         Output it in the same format as before.",
+    );
+    fixup
+}
+
+/// Regenerate once with the problems spelled out when the code breaks any of the
+/// node's constraints or misses declared exports (as enabled in `options`). The
+/// revision is kept only if it has fewer problems. Returns the code with the fix-up's
+/// response, when one was received, so its usage can be counted.
+pub async fn fix_generated_code(
+    provider: &dyn LLMProvider,
+    node: &CodeNode,
+    request: &GenerationRequest,
+    code: String,
+    options: FixupOptions,
+) -> (String, Option<GenerationResponse>) {
+    let (violations, missing) = options.problems(node, &code);
+    let problems = violations.len() + missing.len();
+    if problems == 0 {
+        return (code, None);
+    }
+
+    let fixup = GenerationRequest {
+        prompt: build_fixup_prompt(&request.prompt, &code, &violations, &missing),
+        ..request.clone()
+    };
+    match generate_with_timeout(provider, fixup).await {
+        Ok(response) => {
+            let revised = strip_code_blocks(&response.content);
+            let (violations, missing) = options.problems(node, &revised);
+            if violations.len() + missing.len() < problems {
+                (revised, Some(response))
+            } else {
+                (code, Some(response))
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Fix-up regeneration failed for node {}: {}", node.id, e);
+            (code, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::ExportSignature;
+
+    #[test]
+    fn test_check_constraints() {
+        let mut node = CodeNode::new("Api".to_string(), "api.ts".to_string(), Language::TypeScript);
+        node.llm_config.constraints = vec![
+            "No `any` type".to_string(),
+            "Must export function createClient".to_string(),
+            "Must export parseResponse".to_string(),
+            "No console.log".to_string(),
+            "At most 5 lines".to_string(),
+            "forbid: TODO".to_string(),
+            "Keep functions small".to_string(),
+        ];
+        assert!(parse_constraint("No globals").is_none());
+        assert!(matches!(parse_constraint("under 10 lines"), Some(ConstraintRule::MaxLines(9))));
+
+        let code = "import { x } from './x';\n\
+                    export function createClient(opts: any) {\n  return x(opts);\n}\n\
+                    function parseResponse() {}\n";
+        node.generated_code = Some(code.to_string());
+        node.status = NodeStatus::Complete;
+        node.exports = vec![
+            ExportSignature {
+                name: "createClient".to_string(),
+                ..Default::default()
+            },
+            ExportSignature {
+                name: "parseResponse".to_string(),
+                ..Default::default()
+            },
+        ];
+        verify_generated_code(&ProjectManifest::default(), &mut node);
+
+        let broken: Vec<(&str, Option<usize>)> = node
+            .constraint_violations
+            .iter()
+            .map(|v| (v.constraint.as_str(), v.line))
+            .collect();
+        assert_eq!(
+            broken,
+            vec![("No `any` type", Some(2)), ("Must export parseResponse", None)]
+        );
+
+        assert_eq!(node.missing_exports, vec!["parseResponse".to_string()]);
+        assert_eq!(node.status, NodeStatus::Warning);
+
+        let prompt = build_fixup_prompt(
+            "Write it.",
+            code,
+            &node.constraint_violations,
+            &node.missing_exports,
+        );
+        assert!(prompt.contains("- No `any` type (line 2: Found `: any`)"));
+        assert!(prompt.contains("You forgot to export `parseResponse`"));
+
+        // A later check that finds every export clears the warning
+        node.generated_code = Some(format!("{}export {{ parseResponse }};\n", code));
+        verify_generated_code(&ProjectManifest::default(), &mut node);
+        assert!(node.missing_exports.is_empty());
+        assert_eq!(node.status, NodeStatus::Complete);
+        assert_eq!(node.error_message, None);
+
+        // Name-level checks per language
+        assert!(is_exported(&Language::Python, "def run():\n    pass\n", "run"));
+        assert!(!is_exported(&Language::Python, "class A:\n    def run(self):\n", "run"));
+        assert!(is_exported(&Language::Rust, "pub(crate) async fn run() {}", "run"));
+        assert!(!is_exported(&Language::Rust, "fn run() {}", "run"));
+        assert!(is_exported(&Language::Go, "func (s *Server) Run() error {", "Run"));
+        assert!(is_exported(&Language::JavaScript, "module.exports = { run };", "run"));
+    }
+}
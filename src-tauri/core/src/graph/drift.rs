@@ -0,0 +1,34 @@
+use sha2::{Digest, Sha256};
+
+use super::model::CodeNode;
+
+/// SHA-256 of file contents, as lowercase hex
+pub fn content_hash(contents: &str) -> String {
+    format!("{:x}", Sha256::digest(contents.as_bytes()))
+}
+
+/// Whether a node's file was edited by hand since Needlepoint last wrote it.
+/// Files Needlepoint never wrote, and files that no longer exist, don't count.
+pub fn is_manually_edited(node: &CodeNode, on_disk: Option<&str>) -> bool {
+    match (&node.written_hash, on_disk) {
+        (Some(hash), Some(contents)) => content_hash(contents) != *hash,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_is_manually_edited() {
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        assert!(!is_manually_edited(&node, Some("anything")));
+
+        node.written_hash = Some(content_hash("export const a = 1;\n"));
+        assert!(!is_manually_edited(&node, Some("export const a = 1;\n")));
+        assert!(is_manually_edited(&node, Some("export const a = 2;\n")));
+        assert!(!is_manually_edited(&node, None));
+    }
+}
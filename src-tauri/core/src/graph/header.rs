@@ -0,0 +1,81 @@
+use super::model::{CodeNode, Project};
+
+/// Header injected at the top of a node's file: the node's override if set,
+/// otherwise the project default. An empty override disables the header.
+pub fn file_header<'a>(project: &'a Project, node: &'a CodeNode) -> Option<&'a str> {
+    node.file_header
+        .as_deref()
+        .or(project.manifest.file_header.as_deref())
+        .filter(|h| !h.is_empty())
+}
+
+/// Footer injected at the end of a node's file, resolved like `file_header`
+pub fn file_footer<'a>(project: &'a Project, node: &'a CodeNode) -> Option<&'a str> {
+    node.file_footer
+        .as_deref()
+        .or(project.manifest.file_footer.as_deref())
+        .filter(|f| !f.is_empty())
+}
+
+/// Ensure a block of text ends with exactly one newline
+fn as_block(text: &str) -> String {
+    format!("{}\n", text.trim_end_matches('\n'))
+}
+
+/// The contents to write to disk for a node: its generated code wrapped in the
+/// effective header and footer
+pub fn wrap_generated_code(project: &Project, node: &CodeNode, code: &str) -> String {
+    let mut contents = String::new();
+    if let Some(header) = file_header(project, node) {
+        contents.push_str(&as_block(header));
+    }
+    contents.push_str(code);
+    if let Some(footer) = file_footer(project, node) {
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push_str(&as_block(footer));
+    }
+    contents
+}
+
+/// Remove a previously injected header and footer from file contents, so the
+/// remainder can be compared with the generated code
+pub fn strip_injected(project: &Project, node: &CodeNode, contents: &str) -> String {
+    let mut body = contents;
+    if let Some(header) = file_header(project, node) {
+        body = body.strip_prefix(as_block(header).as_str()).unwrap_or(body);
+    }
+    if let Some(footer) = file_footer(project, node) {
+        if let Some(stripped) = body.strip_suffix(as_block(footer).as_str()) {
+            body = stripped;
+        }
+    }
+    body.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_wrap_and_strip() {
+        let mut project = Project::new(String::new());
+        project.manifest.file_header = Some("// @generated by Needlepoint".to_string());
+        project.manifest.file_footer = Some("/* eslint-enable */\n".to_string());
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+
+        let code = "export const a = 1;";
+        let contents = wrap_generated_code(&project, &node, code);
+        assert_eq!(
+            contents,
+            "// @generated by Needlepoint\nexport const a = 1;\n/* eslint-enable */\n"
+        );
+        assert_eq!(strip_injected(&project, &node, &contents).trim_end(), code);
+
+        // An empty node override disables the project header
+        node.file_header = Some(String::new());
+        assert!(wrap_generated_code(&project, &node, code).starts_with("export"));
+    }
+}
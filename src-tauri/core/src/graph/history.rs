@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::model::{NodeStatus, Project};
+
+const HISTORY_FILE: &str = ".needlepoint/history.jsonl";
+
+/// A node's status changing, as recorded in `.needlepoint/history.jsonl`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusTransition {
+    pub node_id: String,
+    pub from: NodeStatus,
+    pub to: NodeStatus,
+    pub timestamp: DateTime<Utc>,
+    /// The node's error message, for transitions to an error or warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// A node's status transitions, oldest first, with a summary of its generations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHistory {
+    pub node_id: String,
+    pub transitions: Vec<StatusTransition>,
+    /// When the node last finished generating successfully
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+    pub failures: usize,
+    /// How long each generation took, from entering to leaving `generating`, oldest first
+    pub generation_durations_ms: Vec<u64>,
+}
+
+fn history_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(HISTORY_FILE)
+}
+
+/// The status changes of nodes present in both versions of the project
+pub fn status_transitions(before: &Project, after: &Project) -> Vec<StatusTransition> {
+    let old_statuses: HashMap<&str, &NodeStatus> =
+        before.nodes.iter().map(|n| (n.id.as_str(), &n.status)).collect();
+    let timestamp = Utc::now();
+    after
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let from = *old_statuses.get(node.id.as_str())?;
+            (*from != node.status).then(|| StatusTransition {
+                node_id: node.id.clone(),
+                from: from.clone(),
+                to: node.status.clone(),
+                timestamp,
+                message: matches!(node.status, NodeStatus::Error | NodeStatus::Warning)
+                    .then(|| node.error_message.clone())
+                    .flatten(),
+            })
+        })
+        .collect()
+}
+
+/// Append transitions to the project's status history
+pub fn append_transitions(project_path: &str, transitions: &[StatusTransition]) -> Result<()> {
+    if transitions.is_empty() {
+        return Ok(());
+    }
+
+    let path = history_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+
+    let mut lines = String::new();
+    for transition in transitions {
+        lines.push_str(&serde_json::to_string(transition)?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open status history: {:?}", path))?;
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("Failed to write status history: {:?}", path))?;
+    Ok(())
+}
+
+/// Summarize a node's transitions, given oldest first
+pub fn node_history(node_id: &str, transitions: Vec<StatusTransition>) -> NodeHistory {
+    let mut history = NodeHistory {
+        node_id: node_id.to_string(),
+        transitions: Vec::new(),
+        last_success: None,
+        failures: 0,
+        generation_durations_ms: Vec::new(),
+    };
+    let mut started: Option<DateTime<Utc>> = None;
+    for transition in transitions {
+        if transition.from == NodeStatus::Generating {
+            if let Some(start) = started.take() {
+                let elapsed = (transition.timestamp - start).num_milliseconds();
+                history.generation_durations_ms.push(elapsed.max(0) as u64);
+            }
+            match transition.to {
+                // Warnings are generated code with problems, still a success
+                NodeStatus::Complete | NodeStatus::Warning => {
+                    history.last_success = Some(transition.timestamp)
+                }
+                NodeStatus::Error => history.failures += 1,
+                _ => {}
+            }
+        }
+        if transition.to == NodeStatus::Generating {
+            started = Some(transition.timestamp);
+        }
+        history.transitions.push(transition);
+    }
+    history
+}
+
+/// A node's status history. Unreadable lines are skipped.
+pub fn load_node_history(project_path: &str, node_id: &str) -> Result<NodeHistory> {
+    let path = history_path(project_path);
+    let transitions = if path.exists() {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read status history: {:?}", path))?;
+        contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<StatusTransition>(line).ok())
+            .filter(|t| t.node_id == node_id)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(node_history(node_id, transitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, Language};
+
+    #[test]
+    fn test_node_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        let mut project = Project::new(project_path.clone());
+        project.nodes.push(CodeNode::new(
+            "A".to_string(),
+            "a.ts".to_string(),
+            Language::TypeScript,
+        ));
+        let id = project.nodes[0].id.clone();
+
+        let mut record = |status: NodeStatus, message: Option<&str>| {
+            let before = project.clone();
+            project.nodes[0].status = status;
+            project.nodes[0].error_message = message.map(str::to_string);
+            let transitions = status_transitions(&before, &project);
+            append_transitions(&project_path, &transitions).unwrap();
+        };
+        record(NodeStatus::Generating, None);
+        record(NodeStatus::Error, Some("Rate limited"));
+        record(NodeStatus::Generating, None);
+        record(NodeStatus::Complete, None);
+        // Unchanged status records nothing
+        record(NodeStatus::Complete, None);
+
+        let history = load_node_history(&project_path, &id).unwrap();
+        assert_eq!(history.transitions.len(), 4);
+        assert_eq!(history.transitions[0].from, NodeStatus::Pending);
+        assert_eq!(history.transitions[1].message.as_deref(), Some("Rate limited"));
+        assert_eq!(history.failures, 1);
+        assert_eq!(history.last_success, Some(history.transitions[3].timestamp));
+        assert_eq!(history.generation_durations_ms.len(), 2);
+
+        assert!(load_node_history(&project_path, "other").unwrap().transitions.is_empty());
+    }
+}
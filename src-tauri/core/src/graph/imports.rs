@@ -0,0 +1,308 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::constraints::is_exported;
+use super::model::{CodeNode, Language, Project};
+
+/// A mismatch between a node's generated code and a dependency's
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyIssue {
+    /// ID of the dependency the code disagrees with
+    pub dependency_id: String,
+    pub message: String,
+    /// How to fix it, when one is apparent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// 1-based line of the offending code in the node's file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// An import statement found in generated code
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    /// The module as written, e.g. `./api` or `..models`
+    pub module: String,
+    /// Imported symbols; `default` for a default import, none for a namespace import
+    pub names: Vec<String>,
+    pub line: usize,
+}
+
+/// The name bound by `name`, `name as alias` or `type name`
+fn imported_name(item: &str) -> Option<String> {
+    let item = item.trim();
+    let item = item.strip_prefix("type ").unwrap_or(item).trim();
+    let name = item.split_whitespace().next()?;
+    (name != "*").then(|| name.to_string())
+}
+
+fn ts_names(clause: &str) -> Vec<String> {
+    let (default, named) = match clause.split_once('{') {
+        Some((default, named)) => (default, named.split('}').next().unwrap_or_default()),
+        None => (clause, ""),
+    };
+    let mut names = Vec::new();
+    let default = default.trim().trim_end_matches(',').trim();
+    if !default.is_empty() && !default.starts_with('*') {
+        names.push("default".to_string());
+    }
+    names.extend(named.split(',').filter_map(imported_name));
+    names
+}
+
+fn python_names(list: &str) -> Vec<String> {
+    let list = list.split('#').next().unwrap_or_default();
+    list.trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())
+        .split(',')
+        .filter_map(imported_name)
+        .collect()
+}
+
+fn line_of(code: &str, offset: usize) -> usize {
+    code[..offset].matches('\n').count() + 1
+}
+
+/// Import statements in the code. Only TypeScript, JavaScript and Python imports are
+/// recognised; other languages have none.
+pub fn parse_imports(language: &Language, code: &str) -> Vec<Import> {
+    // Capture groups of the imported names and the module
+    let (pattern, names_group, module_group, names): (_, _, _, fn(&str) -> Vec<String>) =
+        match language {
+            Language::TypeScript | Language::JavaScript => (
+                r#"(?m)^\s*import\s+(?:type\s+)?([^;'"]*?)\s+from\s+['"]([^'"]+)['"]"#,
+                1,
+                2,
+                ts_names,
+            ),
+            Language::Python => (
+                r"(?m)^from\s+([\w.]+)\s+import\s+(\([^)]*\)|[^\n]+)",
+                2,
+                1,
+                python_names,
+            ),
+            _ => return Vec::new(),
+        };
+    let Ok(pattern) = Regex::new(pattern) else {
+        return Vec::new();
+    };
+    pattern
+        .captures_iter(code)
+        .map(|c| Import {
+            module: c[module_group].to_string(),
+            names: names(&c[names_group]),
+            line: line_of(code, c.get(0).map_or(0, |m| m.start())),
+        })
+        .collect()
+}
+
+/// `path` with `.` and `..` segments resolved
+fn normalize(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn parent_dir(file_path: &str) -> &str {
+    file_path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// A file path without its extension and without a trailing `index` or `__init__`, as
+/// a module import names it
+fn module_stem(file_path: &str) -> String {
+    let path = file_path.replace('\\', "/");
+    let path = path.trim_start_matches("./");
+    let stem = match path.rsplit_once('.') {
+        Some((stem, _)) if !stem.ends_with('/') && !path[stem.len()..].contains('/') => stem,
+        _ => path,
+    };
+    let stem = stem
+        .strip_suffix("/index")
+        .or_else(|| stem.strip_suffix("/__init__"))
+        .unwrap_or(stem);
+    stem.to_string()
+}
+
+/// Whether an import in `importer`'s code refers to `dependency`'s file
+pub fn import_resolves_to(import: &Import, importer: &CodeNode, dependency: &CodeNode) -> bool {
+    let target = module_stem(&dependency.file_path);
+    let dir = parent_dir(importer.file_path.trim_start_matches("./"));
+    match importer.language {
+        Language::TypeScript | Language::JavaScript => {
+            let module = import.module.as_str();
+            if module.starts_with('.') {
+                return module_stem(&normalize(&format!("{}/{}", dir, module))) == target;
+            }
+            // Path aliases for the source root
+            match module.strip_prefix("@/").or_else(|| module.strip_prefix("~/")) {
+                Some(rest) => target == rest || target.ends_with(&format!("/{}", rest)),
+                None => false,
+            }
+        }
+        Language::Python => {
+            let module = import.module.as_str();
+            let rest = module.trim_start_matches('.');
+            let path = rest.replace('.', "/");
+            let levels = module.len() - rest.len();
+            if levels == 0 {
+                return target == path || target.ends_with(&format!("/{}", path));
+            }
+            if path.is_empty() {
+                return false;
+            }
+            let up = "../".repeat(levels - 1);
+            normalize(&format!("{}/{}{}", dir, up, path)) == target
+        }
+        _ => false,
+    }
+}
+
+/// Whether `dependency`'s code provides `name` to importers
+fn provides(dependency: &CodeNode, code: &str, name: &str) -> bool {
+    if is_exported(&dependency.language, code, name) {
+        return true;
+    }
+    match dependency.language {
+        // `export * from` may re-export anything
+        Language::TypeScript | Language::JavaScript => code.contains("export *"),
+        // Names a Python module imports can be imported from it in turn
+        Language::Python => parse_imports(&dependency.language, code)
+            .iter()
+            .any(|i| i.names.iter().any(|n| n == name)),
+        _ => false,
+    }
+}
+
+fn suggest_export(dependency: &CodeNode, name: &str) -> String {
+    let declared: Vec<&str> = dependency.exports.iter().map(|e| e.name.as_str()).collect();
+    if let Some(similar) = declared.iter().find(|d| d.eq_ignore_ascii_case(name)) {
+        return format!("Import `{}` instead", similar);
+    }
+    if declared.is_empty() {
+        format!("Export `{}` from {}, or stop importing it", name, dependency.file_path)
+    } else {
+        format!("Import one of its exports instead: {}", declared.join(", "))
+    }
+}
+
+/// Symbols `node` imports from `dependency` that the dependency's generated code
+/// doesn't provide
+pub fn check_imports(node: &CodeNode, dependency: &CodeNode) -> Vec<ConsistencyIssue> {
+    let (Some(code), Some(dependency_code)) = (&node.generated_code, &dependency.generated_code)
+    else {
+        return Vec::new();
+    };
+
+    let mut issues = Vec::new();
+    for import in parse_imports(&node.language, code) {
+        if !import_resolves_to(&import, node, dependency) {
+            continue;
+        }
+        for name in &import.names {
+            if provides(dependency, dependency_code, name) {
+                continue;
+            }
+            let message = if name == "default" {
+                format!("Default import from {}, which has no default export", import.module)
+            } else {
+                format!("Imports `{}` from {}, which doesn't export it", name, import.module)
+            };
+            issues.push(ConsistencyIssue {
+                dependency_id: dependency.id.clone(),
+                message,
+                suggestion: Some(suggest_export(dependency, name)),
+                line: Some(import.line),
+            });
+        }
+    }
+    issues
+}
+
+/// Each generated node paired with each of its generated dependencies, once per pair
+pub fn generated_dependency_pairs(project: &Project) -> Vec<(&CodeNode, &CodeNode)> {
+    let mut pairs: Vec<(&CodeNode, &CodeNode)> = Vec::new();
+    for edge in &project.edges {
+        let (Some(node), Some(dependency)) =
+            (project.find_node(&edge.target), project.find_node(&edge.source))
+        else {
+            continue;
+        };
+        if node.generated_code.is_none() || dependency.generated_code.is_none() {
+            continue;
+        }
+        if !pairs.iter().any(|(n, d)| n.id == node.id && d.id == dependency.id) {
+            pairs.push((node, dependency));
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, ExportSignature};
+
+    #[test]
+    fn test_check_imports() {
+        let mut api =
+            CodeNode::new("Api".to_string(), "src/api.ts".to_string(), Language::TypeScript);
+        api.exports = vec![ExportSignature {
+            name: "fetchUser".to_string(),
+            type_signature: "function".to_string(),
+            description: String::new(),
+            shared_type: None,
+        }];
+        api.generated_code = Some("export async function fetchuser() {}\n".to_string());
+
+        let mut app =
+            CodeNode::new("App".to_string(), "src/app.ts".to_string(), Language::TypeScript);
+        app.generated_code = Some(
+            "import React from 'react';\nimport Api, {\n  fetchuser,\n  type Missing as M,\n} \
+             from './api.js';\n"
+                .to_string(),
+        );
+
+        let imports = parse_imports(&app.language, app.generated_code.as_deref().unwrap());
+        assert_eq!(imports[1].names, vec!["default", "fetchuser", "Missing"]);
+        assert!(import_resolves_to(&imports[1], &app, &api));
+        assert!(!import_resolves_to(&imports[0], &app, &api));
+
+        let issues = check_imports(&app, &api);
+        let lines: Vec<_> = issues.iter().map(|i| i.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(2)]);
+        assert!(issues[0].message.contains("no default export"));
+        assert_eq!(
+            issues[1].suggestion.as_deref(),
+            Some("Import one of its exports instead: fetchUser")
+        );
+
+        let mut project = Project::new(String::new());
+        project.edges.push(CodeEdge::new(api.id.clone(), app.id.clone(), "uses".to_string()));
+        project.edges.push(CodeEdge::new(api.id.clone(), app.id.clone(), "calls".to_string()));
+        project.nodes = vec![api, app];
+        assert_eq!(generated_dependency_pairs(&project).len(), 1);
+
+        let mut models =
+            CodeNode::new("Models".to_string(), "pkg/models.py".to_string(), Language::Python);
+        models.generated_code =
+            Some("from typing import List\n\nclass User:\n    pass\n".to_string());
+        let mut service = CodeNode::new(
+            "Service".to_string(),
+            "pkg/sub/service.py".to_string(),
+            Language::Python,
+        );
+        service.generated_code =
+            Some("from ..models import (User, List,\n    Group)\n".to_string());
+        let issues = check_imports(&service, &models);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("`Group`"));
+    }
+}
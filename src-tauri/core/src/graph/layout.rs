@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use super::model::{Position, Project};
+
+/// Vertical distance between layers
+const LAYER_SPACING: f64 = 220.0;
+/// Horizontal distance between nodes in a layer
+const NODE_SPACING: f64 = 260.0;
+/// Nodes per row when placing nodes that have no edges
+const ISOLATED_ROW_LEN: usize = 6;
+/// Barycenter passes (each one down and back up) used to reduce edge crossings
+const ORDERING_PASSES: usize = 4;
+
+/// Dependencies and dependents of each node, by index into `project.nodes`.
+/// Self-loops, duplicate edges and edges to unknown nodes are dropped.
+pub(crate) fn adjacency(project: &Project) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let ids: HashMap<&str, usize> = project
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let count = project.nodes.len();
+    let mut preds = vec![Vec::new(); count];
+    let mut succs = vec![Vec::new(); count];
+    for edge in &project.edges {
+        let (Some(&source), Some(&target)) =
+            (ids.get(edge.source.as_str()), ids.get(edge.target.as_str()))
+        else {
+            continue;
+        };
+        if source != target && !succs[source].contains(&target) {
+            succs[source].push(target);
+            preds[target].push(source);
+        }
+    }
+    (preds, succs)
+}
+
+/// Assign each node a layer: dependencies sit above the nodes that use them, and
+/// every node is one layer below its deepest dependency. Cycles are broken by
+/// taking the first node still waiting in project order.
+pub(crate) fn assign_layers(preds: &[Vec<usize>], succs: &[Vec<usize>]) -> Vec<usize> {
+    let count = preds.len();
+    let mut indegree: Vec<usize> = preds.iter().map(Vec::len).collect();
+    let mut layer = vec![0; count];
+    let mut done = vec![false; count];
+    let mut queue: Vec<usize> = (0..count).rev().filter(|&i| indegree[i] == 0).collect();
+    let mut processed = 0;
+
+    while processed < count {
+        let node = match queue.pop() {
+            Some(node) => node,
+            // Only nodes on a cycle are left
+            None => (0..count).find(|&i| !done[i]).unwrap(),
+        };
+        if done[node] {
+            continue;
+        }
+        done[node] = true;
+        processed += 1;
+
+        layer[node] = preds[node]
+            .iter()
+            .filter(|&&p| done[p])
+            .map(|&p| layer[p] + 1)
+            .max()
+            .unwrap_or(0);
+
+        for &s in &succs[node] {
+            indegree[s] = indegree[s].saturating_sub(1);
+            if indegree[s] == 0 && !done[s] {
+                queue.push(s);
+            }
+        }
+    }
+
+    layer
+}
+
+/// Reorder each layer by the average position of its neighbours in the
+/// adjacent layers, sweeping down and up
+fn order_layers(layers: &mut [Vec<usize>], preds: &[Vec<usize>], succs: &[Vec<usize>]) {
+    let count = preds.len();
+    let mut index = vec![0.0; count];
+    let update_index = |layers: &[Vec<usize>], index: &mut Vec<f64>| {
+        for layer in layers {
+            for (i, &node) in layer.iter().enumerate() {
+                index[node] = i as f64;
+            }
+        }
+    };
+    update_index(layers, &mut index);
+
+    let reorder = |layer: &mut Vec<usize>, neighbours: &[Vec<usize>], index: &[f64]| {
+        let barycenter = |node: usize, fallback: f64| {
+            let adjacent = &neighbours[node];
+            if adjacent.is_empty() {
+                fallback
+            } else {
+                adjacent.iter().map(|&n| index[n]).sum::<f64>() / adjacent.len() as f64
+            }
+        };
+        let mut keyed: Vec<(f64, usize)> = layer
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (barycenter(node, i as f64), node))
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        *layer = keyed.into_iter().map(|(_, node)| node).collect();
+    };
+
+    for _ in 0..ORDERING_PASSES {
+        for i in 1..layers.len() {
+            reorder(&mut layers[i], preds, &index);
+            update_index(layers, &mut index);
+        }
+        for i in (0..layers.len().saturating_sub(1)).rev() {
+            reorder(&mut layers[i], succs, &index);
+            update_index(layers, &mut index);
+        }
+    }
+}
+
+/// Lay the graph out top to bottom in dependency layers (a Sugiyama-style
+/// layout), overwriting every node's position. Nodes without edges are placed
+/// in rows below the graph.
+pub fn auto_layout(project: &mut Project) {
+    let count = project.nodes.len();
+    let (preds, succs) = adjacency(project);
+
+    let connected: Vec<bool> = (0..count)
+        .map(|i| !preds[i].is_empty() || !succs[i].is_empty())
+        .collect();
+    let layer = assign_layers(&preds, &succs);
+
+    let depth = (0..count)
+        .filter(|&i| connected[i])
+        .map(|i| layer[i] + 1)
+        .max()
+        .unwrap_or(0);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); depth];
+    for i in (0..count).filter(|&i| connected[i]) {
+        layers[layer[i]].push(i);
+    }
+    order_layers(&mut layers, &preds, &succs);
+
+    let mut positions = vec![Position::default(); count];
+    for (row, nodes) in layers.iter().enumerate() {
+        let offset = (nodes.len() as f64 - 1.0) / 2.0;
+        for (i, &node) in nodes.iter().enumerate() {
+            positions[node] = Position {
+                x: (i as f64 - offset) * NODE_SPACING,
+                y: row as f64 * LAYER_SPACING,
+            };
+        }
+    }
+
+    let isolated: Vec<usize> = (0..count).filter(|&i| !connected[i]).collect();
+    for (row, nodes) in isolated.chunks(ISOLATED_ROW_LEN).enumerate() {
+        let offset = (nodes.len() as f64 - 1.0) / 2.0;
+        for (i, &node) in nodes.iter().enumerate() {
+            positions[node] = Position {
+                x: (i as f64 - offset) * NODE_SPACING,
+                y: (depth + row) as f64 * LAYER_SPACING,
+            };
+        }
+    }
+
+    for (node, position) in project.nodes.iter_mut().zip(positions) {
+        node.position = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode, Language};
+
+    #[test]
+    fn test_auto_layout() {
+        let mut project = Project::new(String::new());
+        for name in ["a", "b", "c", "d", "e"] {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.id = name.to_string();
+            project.nodes.push(node);
+        }
+        // a -> b -> c, a -> c, and a cycle d <-> e
+        for (source, target) in [("a", "b"), ("b", "c"), ("a", "c"), ("d", "e"), ("e", "d")] {
+            project.edges.push(CodeEdge::new(
+                source.to_string(),
+                target.to_string(),
+                String::new(),
+            ));
+        }
+        let mut isolated = CodeNode::new("f".to_string(), "f.ts".to_string(), Language::TypeScript);
+        isolated.id = "f".to_string();
+        project.nodes.push(isolated);
+
+        auto_layout(&mut project);
+
+        let y = |id: &str| project.find_node(id).unwrap().position.y;
+        assert_eq!(y("a"), 0.0);
+        assert_eq!(y("b"), LAYER_SPACING);
+        assert_eq!(y("c"), 2.0 * LAYER_SPACING);
+        assert_eq!(y("d"), 0.0);
+        assert_eq!(y("e"), LAYER_SPACING);
+        assert_eq!(y("f"), 3.0 * LAYER_SPACING);
+
+        // Nodes sharing a layer don't overlap
+        let x = |id: &str| project.find_node(id).unwrap().position.x;
+        assert_ne!(x("a"), x("d"));
+        assert_ne!(x("b"), x("e"));
+    }
+}
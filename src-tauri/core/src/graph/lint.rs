@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::constraints::{parse_constraint, ConstraintRule};
+use super::model::{CodeNode, NodeKind, Project};
+use super::suggest::mention;
+
+/// Export types too loose to tell the model what to generate
+const VAGUE_TYPES: &[&str] = &["any", "unknown", "object", "Object", "Function", "function", "{}"];
+
+/// Descriptions shorter than this many words rarely say enough to generate from
+const MIN_DESCRIPTION_WORDS: usize = 4;
+
+/// Constraint openings that forbid what follows, and those that require it
+const NEGATIVE_PREFIXES: &[&str] =
+    &["no ", "never use ", "don't use ", "do not use ", "avoid ", "without "];
+const POSITIVE_PREFIXES: &[&str] = &["always use ", "must use ", "use ", "prefer "];
+
+/// The kind of spec problem a hint points out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpecHintKind {
+    EmptyPurpose,
+    VagueDescription,
+    AmbiguousExport,
+    ContradictoryConstraints,
+    MissingDependency,
+}
+
+/// A likely weakness in a node's spec, with what to do about it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecHint {
+    pub node_id: String,
+    pub node_name: String,
+    pub kind: SpecHintKind,
+    pub message: String,
+    pub suggestion: String,
+}
+
+/// Look for common spec failures in the nodes that get generated: a missing purpose,
+/// descriptions too short to act on, exports without a usable type, constraints that
+/// contradict each other, and files the description mentions without an edge to them
+pub fn lint_specs(project: &Project) -> Vec<SpecHint> {
+    let mut hints = Vec::new();
+    for node in &project.nodes {
+        if node.disabled || node.pinned || node.kind == NodeKind::External {
+            continue;
+        }
+        let mut hint = |kind, message: String, suggestion: String| {
+            hints.push(SpecHint {
+                node_id: node.id.clone(),
+                node_name: node.name.clone(),
+                kind,
+                message,
+                suggestion,
+            })
+        };
+
+        if node.purpose.trim().is_empty() {
+            hint(
+                SpecHintKind::EmptyPurpose,
+                "No purpose is given".to_string(),
+                "Say in one sentence what the file is for and who uses it".to_string(),
+            );
+        }
+
+        let words = node.description.split_whitespace().count();
+        if words < MIN_DESCRIPTION_WORDS {
+            hint(
+                SpecHintKind::VagueDescription,
+                format!("The description is {} word(s) long", words),
+                "Describe the behavior, inputs, outputs and edge cases to handle".to_string(),
+            );
+        }
+
+        let mut seen = HashSet::new();
+        for export in &node.exports {
+            if !seen.insert(export.name.as_str()) {
+                hint(
+                    SpecHintKind::AmbiguousExport,
+                    format!("Export '{}' is declared more than once", export.name),
+                    "Merge the declarations or rename one of them".to_string(),
+                );
+            }
+            let type_signature = export.type_signature.trim();
+            if export.shared_type.is_some() {
+                continue;
+            }
+            if type_signature.is_empty() {
+                hint(
+                    SpecHintKind::AmbiguousExport,
+                    format!("Export '{}' has no type", export.name),
+                    "Give its signature, e.g. `(id: string) => Promise<User>`".to_string(),
+                );
+            } else if VAGUE_TYPES.contains(&type_signature) {
+                hint(
+                    SpecHintKind::AmbiguousExport,
+                    format!("Export '{}' is typed as `{}`", export.name, type_signature),
+                    "Spell out its parameters and return type, or use a shared type"
+                        .to_string(),
+                );
+            }
+        }
+
+        for (first, second) in contradictions(&node.llm_config.constraints) {
+            hint(
+                SpecHintKind::ContradictoryConstraints,
+                format!("Constraints \"{}\" and \"{}\" contradict each other", first, second),
+                "Keep the one that applies and remove the other".to_string(),
+            );
+        }
+
+        for mentioned in mentioned_nodes(project, node) {
+            hint(
+                SpecHintKind::MissingDependency,
+                format!(
+                    "The spec mentions {} ({}) but doesn't depend on it",
+                    mentioned.name, mentioned.file_path
+                ),
+                format!("Add an edge from {} so its exports are in the prompt", mentioned.name),
+            );
+        }
+    }
+    hints
+}
+
+/// Whether a constraint forbids or requires something, and what, when it reads as
+/// "no X" / "use X"
+fn polarity(constraint: &str) -> Option<(bool, String)> {
+    let text = constraint.trim().trim_end_matches('.').to_lowercase();
+    for (prefixes, required) in [(NEGATIVE_PREFIXES, false), (POSITIVE_PREFIXES, true)] {
+        if let Some(subject) = prefixes.iter().find_map(|p| text.strip_prefix(p)) {
+            let subject = subject.trim().trim_matches('`').to_string();
+            return Some((required, subject)).filter(|(_, s)| !s.is_empty());
+        }
+    }
+    None
+}
+
+/// Pairs of constraints that can't both hold: one forbidding what another requires,
+/// or two different line limits
+fn contradictions(constraints: &[String]) -> Vec<(&str, &str)> {
+    let mut pairs = Vec::new();
+    for (i, first) in constraints.iter().enumerate() {
+        for second in &constraints[i + 1..] {
+            let opposed = match (polarity(first), polarity(second)) {
+                (Some((a, x)), Some((b, y))) => a != b && x == y,
+                _ => false,
+            };
+            let conflicting_rules = match (parse_constraint(first), parse_constraint(second)) {
+                (Some(ConstraintRule::Forbid(a)), Some(ConstraintRule::Require(b)))
+                | (Some(ConstraintRule::Require(a)), Some(ConstraintRule::Forbid(b))) => {
+                    a.as_str() == b.as_str()
+                }
+                (Some(ConstraintRule::MaxLines(a)), Some(ConstraintRule::MaxLines(b))) => a != b,
+                _ => false,
+            };
+            if opposed || conflicting_rules {
+                pairs.push((first.as_str(), second.as_str()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Other nodes the description or purpose names (by file path, file name or node
+/// name) that the node has no edge to
+fn mentioned_nodes<'a>(project: &'a Project, node: &CodeNode) -> Vec<&'a CodeNode> {
+    let text = format!("{}\n{}", node.description, node.purpose);
+    project
+        .nodes
+        .iter()
+        .filter(|other| other.id != node.id)
+        .filter(|other| {
+            !project.edges.iter().any(|e| {
+                (e.source == other.id && e.target == node.id)
+                    || (e.source == node.id && e.target == other.id)
+            })
+        })
+        .filter(|other| mention(&text, other).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, ExportSignature, Language};
+
+    fn node(name: &str, path: &str, description: &str) -> CodeNode {
+        let mut node = CodeNode::new(name.to_string(), path.to_string(), Language::TypeScript);
+        node.description = description.to_string();
+        node.purpose = format!("{} for the app", name);
+        node
+    }
+
+    fn kinds(hints: &[SpecHint], node_id: &str) -> Vec<SpecHintKind> {
+        hints.iter().filter(|h| h.node_id == node_id).map(|h| h.kind).collect()
+    }
+
+    #[test]
+    fn test_lint_specs() {
+        let mut project = Project::new(String::new());
+        let types = node("Types", "src/types.ts", "Shared types for users and sessions");
+        let db = node("Database", "src/db.ts", "Connection pool and queries for users");
+        let mut api = node(
+            "UserApi",
+            "src/api.ts",
+            "REST handlers that load users from the Database and validate with src/types.ts",
+        );
+        api.exports.push(ExportSignature {
+            name: "handler".to_string(),
+            type_signature: "any".to_string(),
+            description: String::new(),
+            shared_type: None,
+        });
+        api.llm_config.constraints = ["No classes", "Use classes.", "max 100 lines"]
+            .map(str::to_string)
+            .to_vec();
+        let mut vague = node("Utils", "src/utils.ts", "Helpers");
+        vague.purpose = " ".to_string();
+        project.edges.push(CodeEdge::new(types.id.clone(), db.id.clone(), String::new()));
+        // An edge the other way (the API documents the database) still counts
+        project.edges.push(CodeEdge::new(api.id.clone(), db.id.clone(), String::new()));
+        let (api_id, vague_id, types_id) = (api.id.clone(), vague.id.clone(), types.id.clone());
+        project.nodes.extend([types, db, api, vague]);
+
+        let hints = lint_specs(&project);
+        assert_eq!(
+            kinds(&hints, &api_id),
+            vec![
+                SpecHintKind::AmbiguousExport,
+                SpecHintKind::ContradictoryConstraints,
+                SpecHintKind::MissingDependency,
+            ]
+        );
+        let missing = hints.iter().find(|h| h.kind == SpecHintKind::MissingDependency).unwrap();
+        assert!(missing.message.contains("src/types.ts"));
+        assert_eq!(
+            kinds(&hints, &vague_id),
+            vec![SpecHintKind::EmptyPurpose, SpecHintKind::VagueDescription]
+        );
+        assert!(kinds(&hints, &types_id).is_empty());
+
+        let limits = ["max 100 lines", "under 50 lines"].map(str::to_string);
+        assert_eq!(contradictions(&limits).len(), 1);
+        let unrelated = ["No any", "Use strict mode"].map(str::to_string);
+        assert!(contradictions(&unrelated).is_empty());
+    }
+}
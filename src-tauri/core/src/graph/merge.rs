@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::drift::content_hash;
+use super::model::CodeNode;
+
+/// Snapshots of the generated contents last written for each node, used as the
+/// common ancestor when merging a regeneration with manual edits
+const WRITTEN_DIR: &str = ".needlepoint/written";
+
+/// Result of merging a regeneration into a manually edited file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOutcome {
+    pub contents: String,
+    /// Whether the edits overlapped and conflict markers were written
+    pub conflicts: bool,
+}
+
+/// diff3-style merge: `base` is the previous generation, `manual` the hand-edited
+/// file on disk and `regenerated` the new generation. Overlapping changes are
+/// kept side by side between conflict markers.
+pub fn merge_regeneration(base: &str, manual: &str, regenerated: &str) -> MergeOutcome {
+    let mut options = diffy::MergeOptions::new();
+    options.set_conflict_style(diffy::ConflictStyle::Diff3);
+
+    match options.merge(base, manual, regenerated) {
+        Ok(contents) => MergeOutcome {
+            contents,
+            conflicts: false,
+        },
+        Err(contents) => MergeOutcome {
+            contents,
+            conflicts: true,
+        },
+    }
+}
+
+fn snapshot_path(project_path: &str, node_id: &str) -> Result<PathBuf> {
+    if node_id.is_empty() || node_id.contains(['/', '\\']) || node_id.contains("..") {
+        anyhow::bail!("Invalid node ID: {}", node_id);
+    }
+    Ok(Path::new(project_path).join(WRITTEN_DIR).join(node_id))
+}
+
+/// Remember the generated contents written for a node
+pub fn save_written_snapshot(project_path: &str, node_id: &str, contents: &str) -> Result<()> {
+    let path = snapshot_path(project_path, node_id)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create snapshot directory: {:?}", dir))?;
+    }
+    fs::write(&path, contents).with_context(|| format!("Failed to write snapshot: {:?}", path))
+}
+
+/// The generated contents last written for a node, if a snapshot exists and
+/// still matches the node's `written_hash`
+pub fn load_written_snapshot(project_path: &str, node: &CodeNode) -> Option<String> {
+    let expected = node.written_hash.as_ref()?;
+    let path = snapshot_path(project_path, &node.id).ok()?;
+    let contents = fs::read_to_string(path).ok()?;
+    (content_hash(&contents) == *expected).then_some(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_regeneration() {
+        let base = "import a;\n\nfn one() {}\n\nfn two() {}\n";
+        let manual = "import a;\nimport b;\n\nfn one() {}\n\nfn two() {}\n";
+        let regenerated = "import a;\n\nfn one() {}\n\nfn two() { todo() }\n";
+
+        let clean = merge_regeneration(base, manual, regenerated);
+        assert!(!clean.conflicts);
+        assert_eq!(clean.contents, "import a;\nimport b;\n\nfn one() {}\n\nfn two() { todo() }\n");
+
+        let clashing = "import a;\n\nfn one() {}\n\nfn two() { 2 }\n";
+        let conflicted = merge_regeneration(base, clashing, regenerated);
+        assert!(conflicted.conflicts);
+        assert!(conflicted.contents.contains("<<<<<<<"));
+    }
+}
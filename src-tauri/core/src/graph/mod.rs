@@ -0,0 +1,30 @@
+pub mod budget;
+pub mod changes;
+pub mod constraints;
+pub mod drift;
+pub mod header;
+pub mod history;
+pub mod imports;
+pub mod layout;
+pub mod lint;
+pub mod merge;
+pub mod model;
+pub mod packages;
+pub mod paths;
+pub mod project_merge;
+pub mod query;
+pub mod reconcile;
+pub mod replace;
+pub mod search;
+pub mod serialization;
+pub mod settings;
+pub mod stats;
+pub mod suggest;
+pub mod templates;
+pub mod tree;
+pub mod validation;
+pub mod variables;
+pub mod workspace;
+
+pub use model::*;
+pub use serialization::*;
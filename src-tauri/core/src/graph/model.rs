@@ -0,0 +1,1951 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::constraints::ConstraintViolation;
+use super::imports::ConsistencyIssue;
+use super::validation::would_create_cycle;
+
+/// Status of a code node in the generation pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    #[default]
+    Pending,
+    Generating,
+    Complete,
+    Error,
+    Warning,
+    /// Generated code is out of date relative to its dependencies
+    Stale,
+}
+
+/// Supported LLM providers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LLMProvider {
+    #[default]
+    Anthropic,
+    OpenAI,
+    /// Mistral's API, including Codestral
+    Mistral,
+    Ollama,
+    /// A GGUF model file run in-process; the model is the file's path
+    Local,
+    /// Canned responses without network calls, for tests and demos
+    Mock,
+}
+
+impl LLMProvider {
+    /// Whether the provider's API accepts a sampling seed (Anthropic's doesn't)
+    pub fn supports_seed(&self) -> bool {
+        !matches!(self, LLMProvider::Anthropic)
+    }
+
+    /// Whether requests leave the machine for a third party's API
+    pub fn is_hosted(&self) -> bool {
+        matches!(
+            self,
+            LLMProvider::Anthropic | LLMProvider::OpenAI | LLMProvider::Mistral
+        )
+    }
+
+    /// The name used in project files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LLMProvider::Anthropic => "anthropic",
+            LLMProvider::OpenAI => "openai",
+            LLMProvider::Mistral => "mistral",
+            LLMProvider::Ollama => "ollama",
+            LLMProvider::Local => "local",
+            LLMProvider::Mock => "mock",
+        }
+    }
+}
+
+/// How much reasoning an OpenAI reasoning model does before answering
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+}
+
+/// Supported programming languages. Serialized as a lowercase name; names that
+/// aren't built in become `Custom`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(from = "String", into = "String")]
+pub enum Language {
+    #[default]
+    TypeScript,
+    JavaScript,
+    Python,
+    Rust,
+    Go,
+    Java,
+    CSharp,
+    Cpp,
+    Ruby,
+    Php,
+    Kotlin,
+    Swift,
+    Sql,
+    Html,
+    Css,
+    Bash,
+    /// Any other language, by name
+    Custom(String),
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::TypeScript => write!(f, "typescript"),
+            Language::JavaScript => write!(f, "javascript"),
+            Language::Python => write!(f, "python"),
+            Language::Rust => write!(f, "rust"),
+            Language::Go => write!(f, "go"),
+            Language::Java => write!(f, "java"),
+            Language::CSharp => write!(f, "csharp"),
+            Language::Cpp => write!(f, "cpp"),
+            Language::Ruby => write!(f, "ruby"),
+            Language::Php => write!(f, "php"),
+            Language::Kotlin => write!(f, "kotlin"),
+            Language::Swift => write!(f, "swift"),
+            Language::Sql => write!(f, "sql"),
+            Language::Html => write!(f, "html"),
+            Language::Css => write!(f, "css"),
+            Language::Bash => write!(f, "bash"),
+            Language::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<String> for Language {
+    fn from(name: String) -> Self {
+        Language::ALL
+            .iter()
+            .find(|lang| lang.to_string().eq_ignore_ascii_case(&name))
+            .cloned()
+            .unwrap_or(Language::Custom(name))
+    }
+}
+
+impl From<Language> for String {
+    fn from(language: Language) -> Self {
+        language.to_string()
+    }
+}
+
+impl Language {
+    /// Every built-in language (everything but `Custom`)
+    pub const ALL: &'static [Language] = &[
+        Language::TypeScript,
+        Language::JavaScript,
+        Language::Python,
+        Language::Rust,
+        Language::Go,
+        Language::Java,
+        Language::CSharp,
+        Language::Cpp,
+        Language::Ruby,
+        Language::Php,
+        Language::Kotlin,
+        Language::Swift,
+        Language::Sql,
+        Language::Html,
+        Language::Css,
+        Language::Bash,
+    ];
+
+    /// File extensions (without the dot) expected for the language. Custom
+    /// languages have none unless the manifest maps some.
+    pub fn default_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Language::TypeScript => &["ts", "tsx", "mts", "cts"],
+            Language::JavaScript => &["js", "jsx", "mjs", "cjs"],
+            Language::Python => &["py", "pyi"],
+            Language::Rust => &["rs"],
+            Language::Go => &["go"],
+            Language::Java => &["java"],
+            Language::CSharp => &["cs"],
+            Language::Cpp => &["cpp", "cc", "cxx", "hpp", "hh", "hxx", "h"],
+            Language::Ruby => &["rb"],
+            Language::Php => &["php"],
+            Language::Kotlin => &["kt", "kts"],
+            Language::Swift => &["swift"],
+            Language::Sql => &["sql"],
+            Language::Html => &["html", "htm"],
+            Language::Css => &["css", "scss"],
+            Language::Bash => &["sh", "bash"],
+            Language::Custom(_) => &[],
+        }
+    }
+
+    /// Infer the language from a file extension (without the dot)
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|lang| lang.default_extensions().contains(&ext))
+            .cloned()
+    }
+}
+
+/// What a node's file is, which decides how it is prompted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// Source code that dependents import from
+    #[default]
+    Code,
+    /// Configuration such as `package.json`, `tsconfig.json` or a Dockerfile
+    Config,
+    /// Specifications such as OpenAPI documents, JSON Schema or SQL migrations
+    Schema,
+    /// Documentation that summarizes its dependencies rather than importing them
+    Doc,
+    /// Static files such as SVGs or fixtures
+    Asset,
+    /// A third-party package (see `CodeNode::package`) rather than a file
+    External,
+}
+
+impl NodeKind {
+    pub fn is_code(&self) -> bool {
+        *self == NodeKind::Code
+    }
+
+    /// Whether nodes of this kind are backed by a file in the project
+    pub fn has_file(&self) -> bool {
+        *self != NodeKind::External
+    }
+}
+
+/// Package registry an external dependency comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageEcosystem {
+    #[default]
+    Npm,
+    Crates,
+    Pip,
+}
+
+impl std::fmt::Display for PackageEcosystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackageEcosystem::Npm => write!(f, "npm"),
+            PackageEcosystem::Crates => write!(f, "crates.io"),
+            PackageEcosystem::Pip => write!(f, "PyPI"),
+        }
+    }
+}
+
+/// A third-party package that dependent nodes may use
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalPackage {
+    pub name: String,
+    #[serde(default)]
+    pub ecosystem: PackageEcosystem,
+    /// Version requirement in the ecosystem's syntax (`^1.6`, `1.0`, `>=2.31`)
+    #[serde(default)]
+    pub version: String,
+    /// The parts of the package's API dependents need, included in their prompts
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub docs: String,
+}
+
+impl ExternalPackage {
+    /// `name@version`, or just the name when no version is set
+    pub fn spec(&self) -> String {
+        if self.version.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}@{}", self.name, self.version)
+        }
+    }
+}
+
+/// Position on the graph canvas
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Signature of an exported function/class/variable
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSignature {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_signature: String,
+    pub description: String,
+    /// The manifest's shared type this export is, or is built on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_type: Option<String>,
+}
+
+/// LLM configuration for a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LLMConfig {
+    pub provider: LLMProvider,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub constraints: Vec<String>,
+    /// Few-shot demonstrations of the expected style, after the project's own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<PromptExample>,
+    /// Seconds to wait for one request, overriding the manifest's `requestTimeoutSecs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Tokens Anthropic models may spend on extended thinking before answering (at
+    /// least 1024); unset disables thinking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_budget: Option<u32>,
+    /// Reasoning effort for OpenAI reasoning models (o1, o3, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Generate through Mistral's fill-in-the-middle endpoint (Codestral models), which
+    /// completes the file between code fences after the prompt instead of chatting
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fim: bool,
+    /// Sampling seed, so regenerations are reproducible (best effort) on providers
+    /// that support one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Generate with the manifest's `defaultLlm` provider and model instead of
+    /// `provider` and `model`, so changing the manifest applies to this node
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub use_project_default: bool,
+}
+
+impl Default for LLMConfig {
+    fn default() -> Self {
+        Self {
+            provider: LLMProvider::Anthropic,
+            model: "claude-sonnet-4-20250514".to_string(),
+            system_prompt: None,
+            constraints: Vec::new(),
+            examples: Vec::new(),
+            timeout_secs: None,
+            thinking_budget: None,
+            reasoning_effort: None,
+            fim: false,
+            seed: None,
+            use_project_default: false,
+        }
+    }
+}
+
+impl LLMConfig {
+    /// Config for a new node: the project's default provider and model, following
+    /// later changes to them
+    pub fn project_default(default_llm: &DefaultLLM) -> Self {
+        Self {
+            provider: default_llm.provider.clone(),
+            model: default_llm.model.clone(),
+            use_project_default: true,
+            ..Self::default()
+        }
+    }
+
+    /// The config to generate with: the project's default provider and model when
+    /// `use_project_default` is set
+    pub fn resolve(&self, default_llm: &DefaultLLM) -> LLMConfig {
+        let mut config = self.clone();
+        if config.use_project_default {
+            config.provider = default_llm.provider.clone();
+            config.model = default_llm.model.clone();
+        }
+        config
+    }
+
+    /// The seed sent with requests: the configured one, if the provider accepts it
+    pub fn effective_seed(&self) -> Option<u64> {
+        self.seed.filter(|_| self.provider.supports_seed())
+    }
+}
+
+/// An input/output pair shown to the model as a demonstration of how code should
+/// be written (error handling, logging, naming, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptExample {
+    /// What the example shows, e.g. "Wrapping errors with context"
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// The specification or starting code
+    pub input: String,
+    /// The code written for it
+    pub output: String,
+}
+
+/// Seconds an LLM request may take when neither the node nor the manifest sets a timeout
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// How long a request made with `config` may take: its own timeout, else the manifest's
+pub fn request_timeout(manifest: &ProjectManifest, config: &LLMConfig) -> Duration {
+    let secs = config
+        .timeout_secs
+        .or(manifest.request_timeout_secs)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Output limit for node generation when no profile sets one
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// Sampling temperature for node generation when no profile sets one
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// Named generation settings selectable per run (e.g. a cheap `draft` profile and a
+/// thorough `final` one). Fields that are set override every node's `LLMConfig`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<LLMProvider>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+impl GenerationProfile {
+    /// A node's config with the profile's provider and model swapped in
+    pub fn apply(&self, config: &LLMConfig) -> LLMConfig {
+        LLMConfig {
+            provider: self.provider.clone().unwrap_or_else(|| config.provider.clone()),
+            model: self.model.clone().unwrap_or_else(|| config.model.clone()),
+            ..config.clone()
+        }
+    }
+}
+
+/// The LLM config, max tokens and temperature to generate a node with
+pub fn generation_settings(
+    config: &LLMConfig,
+    profile: Option<&GenerationProfile>,
+) -> (LLMConfig, u32, f32) {
+    match profile {
+        Some(profile) => (
+            profile.apply(config),
+            profile.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            profile.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+        ),
+        None => (config.clone(), DEFAULT_MAX_TOKENS, DEFAULT_TEMPERATURE),
+    }
+}
+
+/// Usage details of the most recent generation for a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationRecord {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_in: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_out: Option<u32>,
+    pub duration_ms: u64,
+    pub timestamp: DateTime<Utc>,
+    /// Seed the generation was made with, to reproduce it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Prose the model wrapped around the code, left out of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// What each post-processing step did to the code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_processing: Vec<PostProcessReport>,
+}
+
+/// The outcome of one post-processing step. A failed step leaves the code as it was.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostProcessReport {
+    pub step: String,
+    pub changed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A node representing a code file in the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeNode {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    pub language: Language,
+    #[serde(default, skip_serializing_if = "NodeKind::is_code")]
+    pub kind: NodeKind,
+    /// Free-form labels (e.g. "backend", "v2") for operating on slices of the graph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Kept in the graph but left out of execution plans and generation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    /// Hand-maintained: its code feeds dependents' context but is never regenerated
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Generation pauses after this node until its code is approved, before any
+    /// dependent is generated
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_approval: bool,
+    /// The package an `External` node stands for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<ExternalPackage>,
+    #[serde(default)]
+    pub status: NodeStatus,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub purpose: String,
+    #[serde(default)]
+    pub exports: Vec<ExportSignature>,
+    /// Project files outside the graph (specs, schemas, ...) included in the prompt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reference_files: Vec<String>,
+    /// Names of the manifest's context snippets included in the prompt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snippets: Vec<String>,
+    /// Names of the manifest's shared types the node uses, besides those its
+    /// exports name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_types: Vec<String>,
+    /// Values for `${NAME}` placeholders in this node's spec, over the manifest's
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    #[serde(default)]
+    pub llm_config: LLMConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub position: Position,
+    /// Dependencies that were only available as stub interfaces when this node was last generated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generated_against_stubs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_generation: Option<GenerationRecord>,
+    /// Checkable constraints the generated code breaks, from the last check
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraint_violations: Vec<ConstraintViolation>,
+    /// Declared exports the generated code doesn't export, from the last check
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_exports: Vec<String>,
+    /// Mismatches with the generated dependencies, from the last consistency check
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub consistency_issues: Vec<ConsistencyIssue>,
+    /// Overrides the project's file header (empty to disable it for this file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_header: Option<String>,
+    /// Overrides the project's file footer (empty to disable it for this file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_footer: Option<String>,
+    /// Hash of the contents last written to disk, used to detect manual edits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub written_hash: Option<String>,
+    /// Name of the sub-project this node was included from; its file belongs to
+    /// that project, and the node isn't saved with this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subproject: Option<String>,
+}
+
+/// Node fields a patch may change; the rest are set by generation and checks
+pub const PATCHABLE_NODE_FIELDS: &[&str] = &[
+    "name",
+    "filePath",
+    "language",
+    "kind",
+    "tags",
+    "disabled",
+    "pinned",
+    "requiresApproval",
+    "package",
+    "status",
+    "description",
+    "purpose",
+    "exports",
+    "referenceFiles",
+    "snippets",
+    "sharedTypes",
+    "variables",
+    "llmConfig",
+    "generatedCode",
+    "errorMessage",
+    "position",
+    "fileHeader",
+    "fileFooter",
+];
+
+/// Reject exports without a name, or with a name declared twice
+pub fn validate_exports(exports: &[ExportSignature]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for export in exports {
+        let name = export.name.trim();
+        if name.is_empty() {
+            return Err("Every export needs a name".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("Export '{}' is declared twice", name));
+        }
+    }
+    Ok(())
+}
+
+/// Reject model names that are empty or contain whitespace. A local model is a file
+/// path, so only surrounding whitespace is rejected there.
+fn validate_model_name(provider: &LLMProvider, model: &str) -> Result<(), String> {
+    let invalid = match provider {
+        LLMProvider::Local => model.trim() != model || model.chars().any(char::is_control),
+        _ => model.chars().any(|c| c.is_whitespace() || c.is_control()),
+    };
+    if model.is_empty() || invalid {
+        return Err(format!("Invalid model name: '{}'", model));
+    }
+    Ok(())
+}
+
+/// Apply a JSON merge patch (RFC 7396): objects are merged key by key, `null`
+/// removes a key and anything else replaces the target
+pub fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let serde_json::Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+    }
+}
+
+impl CodeNode {
+    /// Apply a JSON merge patch of `PATCHABLE_NODE_FIELDS`. The node is left
+    /// unchanged if the patch names other fields or the result isn't a valid node.
+    pub fn apply_patch(&mut self, patch: &serde_json::Value) -> Result<(), String> {
+        let fields = patch
+            .as_object()
+            .ok_or_else(|| "Node update must be a JSON object".to_string())?;
+        if let Some(field) = fields.keys().find(|k| !PATCHABLE_NODE_FIELDS.contains(&k.as_str())) {
+            return Err(format!("Field '{}' can't be changed", field));
+        }
+
+        let mut value = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        merge_patch(&mut value, patch);
+        let mut patched: CodeNode =
+            serde_json::from_value(value).map_err(|e| format!("Invalid node update: {}", e))?;
+        // Picking a provider or model opts the node out of the project default
+        if let Some(llm) = fields.get("llmConfig") {
+            let picks_model = !llm["provider"].is_null() || !llm["model"].is_null();
+            if picks_model && llm.get("useProjectDefault").is_none() {
+                patched.llm_config.use_project_default = false;
+            }
+        }
+
+        if patched.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if patched.file_path.trim().is_empty() {
+            return Err("filePath must not be empty".to_string());
+        }
+        validate_exports(&patched.exports)?;
+        validate_model_name(&patched.llm_config.provider, &patched.llm_config.model)?;
+
+        *self = patched;
+        Ok(())
+    }
+
+    pub fn new(name: String, file_path: String, language: Language) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            file_path,
+            language,
+            kind: NodeKind::Code,
+            tags: Vec::new(),
+            disabled: false,
+            pinned: false,
+            requires_approval: false,
+            package: None,
+            status: NodeStatus::Pending,
+            description: String::new(),
+            purpose: String::new(),
+            exports: Vec::new(),
+            reference_files: Vec::new(),
+            snippets: Vec::new(),
+            shared_types: Vec::new(),
+            variables: HashMap::new(),
+            llm_config: LLMConfig::default(),
+            generated_code: None,
+            error_message: None,
+            position: Position::default(),
+            generated_against_stubs: Vec::new(),
+            last_generation: None,
+            constraint_violations: Vec::new(),
+            missing_exports: Vec::new(),
+            consistency_issues: Vec::new(),
+            file_header: None,
+            file_footer: None,
+            written_hash: None,
+            subproject: None,
+        }
+    }
+
+    /// Names of the shared types the node uses, directly or through its exports, in
+    /// order of first mention
+    pub fn shared_type_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        let exported = self.exports.iter().filter_map(|e| e.shared_type.as_deref());
+        for name in self.shared_types.iter().map(String::as_str).chain(exported) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Whether the node carries the tag, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn uses_shared_type(&self, name: &str) -> bool {
+        self.shared_type_names().contains(&name)
+    }
+
+    /// Whether the node generates a file of this project (not an external package
+    /// or a node included from a sub-project)
+    pub fn owns_file(&self) -> bool {
+        self.kind.has_file() && self.subproject.is_none()
+    }
+}
+
+/// An edge representing a relationship between code nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    /// Human-readable label describing the relationship (e.g., "imports types from", "extends class in")
+    #[serde(default)]
+    pub label: String,
+}
+
+impl CodeEdge {
+    pub fn new(source: String, target: String, label: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            source,
+            target,
+            label,
+        }
+    }
+}
+
+/// Default LLM configuration for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultLLM {
+    pub provider: LLMProvider,
+    pub model: String,
+    pub api_key_env: String,
+}
+
+impl Default for DefaultLLM {
+    fn default() -> Self {
+        Self {
+            provider: LLMProvider::Anthropic,
+            model: "claude-sonnet-4-20250514".to_string(),
+            api_key_env: "ANTHROPIC_API_KEY".to_string(),
+        }
+    }
+}
+
+/// Embedding model used to index nodes for semantic search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingConfig {
+    pub provider: LLMProvider,
+    pub model: String,
+}
+
+impl EmbeddingConfig {
+    /// The manifest's embedding model, or a default for its provider: a local model for
+    /// Ollama and mock projects, the offline bag-of-words vectors for projects on local
+    /// GGUF models, Mistral's for Mistral projects, and OpenAI's otherwise, since
+    /// Anthropic has no embeddings endpoint
+    pub fn for_manifest(manifest: &ProjectManifest) -> Self {
+        if let Some(config) = &manifest.embeddings {
+            return config.clone();
+        }
+        match manifest.default_llm.provider {
+            LLMProvider::Ollama => Self {
+                provider: LLMProvider::Ollama,
+                model: "nomic-embed-text".to_string(),
+            },
+            LLMProvider::Mistral => Self {
+                provider: LLMProvider::Mistral,
+                model: "mistral-embed".to_string(),
+            },
+            LLMProvider::Local | LLMProvider::Mock => Self {
+                provider: LLMProvider::Mock,
+                model: "mock".to_string(),
+            },
+            _ => Self {
+                provider: LLMProvider::OpenAI,
+                model: "text-embedding-3-small".to_string(),
+            },
+        }
+    }
+}
+
+/// Project manifest containing metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<String>,
+    /// Provider and model of new nodes and of nodes with `useProjectDefault`
+    #[serde(default, alias = "defaultLLM")]
+    pub default_llm: DefaultLLM,
+    /// Record every prompt and raw completion under `.needlepoint/transcripts/`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub record_transcripts: bool,
+    /// Text prepended to every written file (license banner, `@generated` marker, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_header: Option<String>,
+    /// Text appended to every written file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_footer: Option<String>,
+    /// Allowed file extensions per language, replacing the defaults for the languages listed
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub language_extensions: HashMap<Language, Vec<String>>,
+    /// Days to keep trashed files before they are purged on load (0 keeps them forever)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u32>,
+    /// Generation profiles by name, selected per run with `profile`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, GenerationProfile>,
+    /// Regenerate once, with the violations in the prompt, when generated code breaks a
+    /// checkable constraint
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fix_constraint_violations: bool,
+    /// Regenerate once, naming the missing exports, when generated code lacks declared exports
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub retry_missing_exports: bool,
+    /// Few-shot demonstrations included in every node's prompt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<PromptExample>,
+    /// Instructions added to every node's system prompt (engineering conventions and
+    /// the like), before the node's own `systemPrompt`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Shared context paragraphs by name, attached to nodes through their `snippets`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub snippets: HashMap<String, String>,
+    /// Type definitions every file must agree on, by name, referenced by nodes'
+    /// `sharedTypes` and exports
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub shared_types: HashMap<String, SharedType>,
+    /// Embedding model for semantic search (see `EmbeddingConfig::for_manifest`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingConfig>,
+    /// Number of semantically related nodes (beyond dependencies) whose exports are
+    /// summarized in each prompt, found through the search index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related_context: Option<usize>,
+    /// Seconds to wait for one LLM request (defaults to `DEFAULT_REQUEST_TIMEOUT_SECS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout_secs: Option<u64>,
+    /// Other Needlepoint projects whose nodes are included in this one's graph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subprojects: Vec<Subproject>,
+    /// Steps run on generated code before it is stored, per language, in order
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub post_processing: HashMap<Language, Vec<PostProcessStep>>,
+    /// Values for `${NAME}` placeholders in node specs (see `graph::variables`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    /// Commands that check the written files (tests, linters, type checkers), run
+    /// in the project directory by `ci`. Only editable in the project file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<ProjectCheck>,
+    /// What to scrub from prompts sent to hosted providers and from transcripts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionRules>,
+    /// The providers and models the project may generate with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<ProviderPolicy>,
+}
+
+/// Restricts which providers and models a project may use, e.g. only a local Ollama
+/// for a confidential codebase. Requests the policy doesn't allow are never sent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPolicy {
+    /// Providers that may be used; empty allows any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_providers: Vec<LLMProvider>,
+    /// Models that may be used, by name or by prefix with a trailing `*` (e.g.
+    /// `llama3*`); empty allows any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_models: Vec<String>,
+}
+
+impl ProviderPolicy {
+    pub fn allows_provider(&self, provider: &LLMProvider) -> bool {
+        self.allowed_providers.is_empty() || self.allowed_providers.contains(provider)
+    }
+
+    pub fn allows_model(&self, model: &str) -> bool {
+        self.allowed_models.is_empty()
+            || self
+                .allowed_models
+                .iter()
+                .any(|allowed| match allowed.strip_suffix('*') {
+                    Some(prefix) => model.starts_with(prefix),
+                    None => model == allowed,
+                })
+    }
+
+    /// Why generating with the provider and model isn't allowed, if it isn't
+    pub fn check(&self, provider: &LLMProvider, model: &str) -> Result<(), String> {
+        if !self.allows_provider(provider) {
+            return Err(format!(
+                "The project's policy doesn't allow the {} provider",
+                provider.as_str()
+            ));
+        }
+        if !self.allows_model(model) {
+            return Err(format!(
+                "The project's policy doesn't allow the model '{}'",
+                model
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Text replaced with a placeholder before prompts are sent to hosted providers and
+/// before transcripts are stored (see `llm::redaction`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRules {
+    /// Regexes whose matches are replaced, e.g. proprietary constants
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// Also replace tokens that look like secrets: long, mixing letters and digits,
+    /// and random enough
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub detect_secrets: bool,
+    /// Shannon entropy (bits per character) from which a token counts as a secret
+    /// (defaults to `DEFAULT_SECRET_ENTROPY`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_entropy: Option<f64>,
+}
+
+/// Entropy from which a token counts as a secret when the rules don't set one
+pub const DEFAULT_SECRET_ENTROPY: f64 = 4.0;
+
+/// A command that checks the project, passing when it exits successfully
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCheck {
+    pub name: String,
+    /// Program and arguments, e.g. `["npm", "test"]`
+    pub command: Vec<String>,
+    /// Seconds the check may run (defaults to `DEFAULT_CHECK_TIMEOUT_SECS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Check timeout used when a check doesn't set one
+pub const DEFAULT_CHECK_TIMEOUT_SECS: u64 = 600;
+
+/// One step of the post-processing chain: a built-in step by name, or an external
+/// command that reads the code on stdin and writes the result to stdout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PostProcessStep {
+    Builtin(BuiltinStep),
+    /// `{file}` in an argument is replaced with the node's file path
+    Command { command: Vec<String> },
+}
+
+/// Post-processing steps that need no configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuiltinStep {
+    /// Drop fence lines left behind in the code
+    StripFences,
+    Prettier,
+    Rustfmt,
+    Black,
+    Gofmt,
+    /// Sort each run of single-line imports, keeping blank-line separated groups apart
+    SortImports,
+    /// Remove trailing whitespace and trailing blank lines
+    TrimWhitespace,
+}
+
+/// A type definition shared across files, with a variant per language
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedType {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// The definition in each language, e.g. an interface for TypeScript and a
+    /// struct for Rust
+    pub variants: HashMap<Language, String>,
+}
+
+impl SharedType {
+    /// The definition for a language, or else one in another language to translate
+    pub fn variant(&self, language: &Language) -> Option<(&Language, &str)> {
+        if let Some((language, definition)) = self.variants.get_key_value(language) {
+            return Some((language, definition.as_str()));
+        }
+        let mut variants: Vec<_> = self.variants.iter().collect();
+        variants.sort_by_key(|(language, _)| language.to_string());
+        variants.first().map(|(language, definition)| (*language, definition.as_str()))
+    }
+}
+
+/// Another project included in this one. Its nodes appear with IDs namespaced as
+/// `<name>::<id>`, so edges can connect them to this project's nodes; they are
+/// pinned here and generated in their own project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subproject {
+    pub name: String,
+    /// Directory of the sub-project, relative to this project's directory
+    pub path: String,
+}
+
+/// Manifest fields a patch may change; snippets and shared types have their own
+/// endpoints, which mark the nodes using them stale
+pub const PATCHABLE_MANIFEST_FIELDS: &[&str] = &[
+    "name",
+    "version",
+    "entryPoint",
+    "defaultLlm",
+    "recordTranscripts",
+    "fileHeader",
+    "fileFooter",
+    "languageExtensions",
+    "trashRetentionDays",
+    "profiles",
+    "fixConstraintViolations",
+    "retryMissingExports",
+    "examples",
+    "systemPrompt",
+    "embeddings",
+    "relatedContext",
+    "requestTimeoutSecs",
+    "subprojects",
+    "postProcessing",
+    "variables",
+    "redaction",
+    "policy",
+];
+
+impl ProjectManifest {
+    /// Apply a JSON merge patch of `PATCHABLE_MANIFEST_FIELDS`. The manifest is left
+    /// unchanged if the patch names other fields or the result isn't valid.
+    pub fn apply_patch(&mut self, patch: &serde_json::Value) -> Result<(), String> {
+        let fields = patch
+            .as_object()
+            .ok_or_else(|| "Manifest update must be a JSON object".to_string())?;
+        let unknown = fields.keys().find(|k| !PATCHABLE_MANIFEST_FIELDS.contains(&k.as_str()));
+        if let Some(field) = unknown {
+            return Err(format!("Field '{}' can't be changed", field));
+        }
+
+        let mut value = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        merge_patch(&mut value, patch);
+        let patched: ProjectManifest =
+            serde_json::from_value(value).map_err(|e| format!("Invalid manifest update: {}", e))?;
+
+        if patched.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if patched.version.trim().is_empty() {
+            return Err("version must not be empty".to_string());
+        }
+        if patched.entry_point.as_ref().is_some_and(|e| e.trim().is_empty()) {
+            return Err("entryPoint must not be empty; remove it with null".to_string());
+        }
+        validate_model_name(&patched.default_llm.provider, &patched.default_llm.model)?;
+        super::workspace::validate_subprojects(&patched.subprojects)?;
+        if let Some(rules) = &patched.redaction {
+            crate::llm::redaction::Redactor::new(rules)?;
+        }
+        // Commands run on every generation, so a patch may only drop or reorder them
+        for (language, steps) in &patched.post_processing {
+            let existing = self.post_processing.get(language);
+            let added = steps.iter().any(|step| {
+                matches!(step, PostProcessStep::Command { .. })
+                    && !existing.is_some_and(|e| e.contains(step))
+            });
+            if added {
+                return Err(
+                    "Command post-processing steps can only be added in the project file"
+                        .to_string(),
+                );
+            }
+        }
+
+        *self = patched;
+        Ok(())
+    }
+}
+
+/// Trash retention used when the manifest doesn't set one
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+impl Default for ProjectManifest {
+    fn default() -> Self {
+        Self {
+            name: "New Project".to_string(),
+            version: "0.1.0".to_string(),
+            entry_point: None,
+            default_llm: DefaultLLM::default(),
+            record_transcripts: false,
+            file_header: None,
+            file_footer: None,
+            language_extensions: HashMap::new(),
+            trash_retention_days: None,
+            profiles: HashMap::new(),
+            fix_constraint_violations: false,
+            retry_missing_exports: false,
+            examples: Vec::new(),
+            system_prompt: None,
+            snippets: HashMap::new(),
+            shared_types: HashMap::new(),
+            embeddings: None,
+            related_context: None,
+            request_timeout_secs: None,
+            subprojects: Vec::new(),
+            post_processing: HashMap::new(),
+            variables: HashMap::new(),
+            checks: Vec::new(),
+            redaction: None,
+            policy: None,
+        }
+    }
+}
+
+/// The complete project structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub manifest: ProjectManifest,
+    #[serde(default)]
+    pub nodes: Vec<CodeNode>,
+    #[serde(default)]
+    pub edges: Vec<CodeEdge>,
+    pub project_path: String,
+}
+
+/// How deleting a node treats the nodes that depend on it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteCascade {
+    /// Refuse to delete while dependents exist
+    Block,
+    /// Delete and mark generated dependents as stale
+    #[default]
+    Stale,
+    /// Delete and connect the node's dependencies directly to its dependents
+    Detach,
+}
+
+/// Changes to an edge; unset fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeUpdate {
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Why an edge could not be updated
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum UpdateEdgeError {
+    #[error("Edge '{0}' not found")]
+    NotFound(String),
+    #[error("Node '{0}' not found")]
+    NodeNotFound(String),
+    #[error("Cannot create an edge from a node to itself")]
+    SelfLoop,
+    #[error("Edge already exists")]
+    Duplicate,
+    #[error("This edge would create a circular dependency")]
+    Cycle,
+}
+
+/// Why a node could not be removed
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum RemoveNodeError {
+    #[error("Node '{0}' not found")]
+    NotFound(String),
+    #[error("Node has dependents: {}", .0.join(", "))]
+    HasDependents(Vec<String>),
+}
+
+impl Project {
+    pub fn new(project_path: String) -> Self {
+        Self {
+            manifest: ProjectManifest::default(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            project_path,
+        }
+    }
+
+    /// A new node generating with the project's default provider and model
+    pub fn new_node(&self, name: String, file_path: String, language: Language) -> CodeNode {
+        let mut node = CodeNode::new(name, file_path, language);
+        node.llm_config = LLMConfig::project_default(&self.manifest.default_llm);
+        node
+    }
+
+    /// Copy the manifest's default provider and model into the nodes that use it,
+    /// after loading or changing the manifest
+    pub fn apply_default_llm(&mut self) {
+        let default_llm = &self.manifest.default_llm;
+        for node in &mut self.nodes {
+            node.llm_config = node.llm_config.resolve(default_llm);
+        }
+    }
+
+    /// Find a node by ID
+    pub fn find_node(&self, id: &str) -> Option<&CodeNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Find a node by ID (mutable)
+    pub fn find_node_mut(&mut self, id: &str) -> Option<&mut CodeNode> {
+        self.nodes.iter_mut().find(|n| n.id == id)
+    }
+
+    /// Get all edges where the given node is the target (dependencies)
+    pub fn get_dependencies(&self, node_id: &str) -> Vec<&CodeEdge> {
+        self.edges.iter().filter(|e| e.target == node_id).collect()
+    }
+
+    /// Get all edges where the given node is the source (dependents)
+    pub fn get_dependents(&self, node_id: &str) -> Vec<&CodeEdge> {
+        self.edges.iter().filter(|e| e.source == node_id).collect()
+    }
+
+    /// Remove a node and its edges, handling its dependents according to `cascade`.
+    /// Returns the IDs of the dependents that were affected.
+    pub fn remove_node(
+        &mut self,
+        node_id: &str,
+        cascade: DeleteCascade,
+    ) -> Result<Vec<String>, RemoveNodeError> {
+        if self.find_node(node_id).is_none() {
+            return Err(RemoveNodeError::NotFound(node_id.to_string()));
+        }
+
+        let mut dependents: Vec<String> = Vec::new();
+        for edge in self.get_dependents(node_id) {
+            if !dependents.contains(&edge.target) {
+                dependents.push(edge.target.clone());
+            }
+        }
+
+        match cascade {
+            DeleteCascade::Block if !dependents.is_empty() => {
+                return Err(RemoveNodeError::HasDependents(dependents));
+            }
+            DeleteCascade::Block => {}
+            DeleteCascade::Stale => {
+                for node in &mut self.nodes {
+                    if dependents.contains(&node.id) && node.status == NodeStatus::Complete {
+                        node.status = NodeStatus::Stale;
+                    }
+                }
+            }
+            DeleteCascade::Detach => {
+                let sources: Vec<String> = self
+                    .get_dependencies(node_id)
+                    .iter()
+                    .map(|e| e.source.clone())
+                    .collect();
+                let outgoing: Vec<CodeEdge> =
+                    self.get_dependents(node_id).into_iter().cloned().collect();
+
+                for source in &sources {
+                    for edge in &outgoing {
+                        let exists = self
+                            .edges
+                            .iter()
+                            .any(|e| e.source == *source && e.target == edge.target);
+                        if !exists && *source != edge.target {
+                            self.edges.push(CodeEdge::new(
+                                source.clone(),
+                                edge.target.clone(),
+                                edge.label.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.nodes.retain(|n| n.id != node_id);
+        self.edges.retain(|e| e.source != node_id && e.target != node_id);
+
+        Ok(dependents)
+    }
+
+    /// Deep-copy a node's spec under a new ID, offset on the canvas. Generated code and
+    /// generation history are not copied. With `copy_incoming_edges`, the clone gets the
+    /// same dependencies as the original.
+    pub fn clone_node(
+        &mut self,
+        node_id: &str,
+        name: Option<String>,
+        file_path: Option<String>,
+        copy_incoming_edges: bool,
+    ) -> Option<CodeNode> {
+        let original = self.find_node(node_id)?;
+
+        let mut clone = CodeNode::new(
+            name.unwrap_or_else(|| format!("{}_copy", original.name)),
+            file_path.unwrap_or_else(|| copy_file_path(&original.file_path)),
+            original.language.clone(),
+        );
+        clone.kind = original.kind;
+        clone.package = original.package.clone();
+        clone.tags = original.tags.clone();
+        clone.requires_approval = original.requires_approval;
+        clone.description = original.description.clone();
+        clone.purpose = original.purpose.clone();
+        clone.exports = original.exports.clone();
+        clone.reference_files = original.reference_files.clone();
+        clone.snippets = original.snippets.clone();
+        clone.shared_types = original.shared_types.clone();
+        clone.variables = original.variables.clone();
+        clone.llm_config = original.llm_config.clone();
+        clone.file_header = original.file_header.clone();
+        clone.file_footer = original.file_footer.clone();
+        clone.position = Position {
+            x: original.position.x + 50.0,
+            y: original.position.y + 50.0,
+        };
+
+        if copy_incoming_edges {
+            let incoming: Vec<CodeEdge> = self
+                .get_dependencies(node_id)
+                .into_iter()
+                .map(|e| CodeEdge::new(e.source.clone(), clone.id.clone(), e.label.clone()))
+                .collect();
+            self.edges.extend(incoming);
+        }
+
+        self.nodes.push(clone.clone());
+        Some(clone)
+    }
+
+    /// Look up a generation profile by name
+    pub fn profile(&self, name: &str) -> Result<&GenerationProfile, String> {
+        self.manifest.profiles.get(name).ok_or_else(|| {
+            let mut names: Vec<&str> = self.manifest.profiles.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            if names.is_empty() {
+                format!("Unknown profile '{}': the manifest defines no profiles", name)
+            } else {
+                format!("Unknown profile '{}' (available: {})", name, names.join(", "))
+            }
+        })
+    }
+
+    /// Mark nodes that were generated against a stub of `dependency_id` as stale,
+    /// now that the real dependency exists. Returns the IDs of the affected nodes.
+    pub fn mark_stub_dependents_stale(&mut self, dependency_id: &str) -> Vec<String> {
+        let mut affected = Vec::new();
+        for node in &mut self.nodes {
+            if node.generated_against_stubs.iter().any(|id| id == dependency_id)
+                && node.status == NodeStatus::Complete
+            {
+                node.status = NodeStatus::Stale;
+                affected.push(node.id.clone());
+            }
+        }
+        affected
+    }
+
+    /// Add or change a context snippet. If its text changed, nodes generated with the
+    /// old text are marked stale; returns the IDs of the affected nodes.
+    pub fn set_snippet(&mut self, name: &str, text: String) -> Vec<String> {
+        if self.manifest.snippets.get(name) == Some(&text) {
+            return Vec::new();
+        }
+        self.manifest.snippets.insert(name.to_string(), text);
+        self.mark_snippet_users_stale(name)
+    }
+
+    /// Remove a context snippet, marking nodes generated with it stale. Returns the
+    /// IDs of the affected nodes, or `None` if there is no such snippet.
+    pub fn remove_snippet(&mut self, name: &str) -> Option<Vec<String>> {
+        self.manifest.snippets.remove(name)?;
+        Some(self.mark_snippet_users_stale(name))
+    }
+
+    /// Reset nodes left generating by a run that was interrupted (e.g. the app was
+    /// closed) to pending. Returns the IDs of the nodes reset.
+    pub fn reset_interrupted_generations(&mut self) -> Vec<String> {
+        let mut reset = Vec::new();
+        for node in &mut self.nodes {
+            if node.status == NodeStatus::Generating {
+                node.status = NodeStatus::Pending;
+                reset.push(node.id.clone());
+            }
+        }
+        reset
+    }
+
+    /// Take the manifest and node specs from an edited copy of the project, keeping
+    /// this project's generated code, statuses and generation records. Nodes and
+    /// edges the copy adds or removes are ignored.
+    pub fn adopt_specs(&mut self, edited: &Project) {
+        self.manifest = edited.manifest.clone();
+        for node in &mut self.nodes {
+            let Some(spec) = edited.find_node(&node.id) else {
+                continue;
+            };
+            node.name = spec.name.clone();
+            node.file_path = spec.file_path.clone();
+            node.language = spec.language.clone();
+            node.kind = spec.kind;
+            node.disabled = spec.disabled;
+            node.pinned = spec.pinned;
+            node.requires_approval = spec.requires_approval;
+            node.tags = spec.tags.clone();
+            node.package = spec.package.clone();
+            node.description = spec.description.clone();
+            node.purpose = spec.purpose.clone();
+            node.exports = spec.exports.clone();
+            node.reference_files = spec.reference_files.clone();
+            node.snippets = spec.snippets.clone();
+            node.shared_types = spec.shared_types.clone();
+            node.variables = spec.variables.clone();
+            node.llm_config = spec.llm_config.clone();
+            node.position = spec.position.clone();
+            node.file_header = spec.file_header.clone();
+            node.file_footer = spec.file_footer.clone();
+        }
+    }
+
+    /// Add or change a shared type. If its definition changed, nodes generated with
+    /// the old one are marked stale; returns the IDs of the affected nodes.
+    pub fn set_shared_type(&mut self, name: &str, shared_type: SharedType) -> Vec<String> {
+        if self.manifest.shared_types.get(name) == Some(&shared_type) {
+            return Vec::new();
+        }
+        self.manifest.shared_types.insert(name.to_string(), shared_type);
+        self.mark_shared_type_users_stale(name)
+    }
+
+    /// Remove a shared type, marking nodes generated with it stale. Returns the IDs
+    /// of the affected nodes, or `None` if there is no such type.
+    pub fn remove_shared_type(&mut self, name: &str) -> Option<Vec<String>> {
+        self.manifest.shared_types.remove(name)?;
+        Some(self.mark_shared_type_users_stale(name))
+    }
+
+    /// Change an edge's label or endpoints, keeping its ID. New endpoints are
+    /// checked like a new edge: they must exist and not close a cycle.
+    pub fn update_edge(
+        &mut self,
+        edge_id: &str,
+        update: EdgeUpdate,
+    ) -> Result<CodeEdge, UpdateEdgeError> {
+        let index = self
+            .edges
+            .iter()
+            .position(|e| e.id == edge_id)
+            .ok_or_else(|| UpdateEdgeError::NotFound(edge_id.to_string()))?;
+        let mut edge = self.edges[index].clone();
+        let rewired = update.source.as_ref().is_some_and(|s| *s != edge.source)
+            || update.target.as_ref().is_some_and(|t| *t != edge.target);
+        edge.source = update.source.unwrap_or(edge.source);
+        edge.target = update.target.unwrap_or(edge.target);
+        edge.label = update.label.unwrap_or(edge.label);
+
+        if rewired {
+            for id in [&edge.source, &edge.target] {
+                if self.find_node(id).is_none() {
+                    return Err(UpdateEdgeError::NodeNotFound(id.clone()));
+                }
+            }
+            if edge.source == edge.target {
+                return Err(UpdateEdgeError::SelfLoop);
+            }
+            let others = || self.edges.iter().filter(|e| e.id != edge_id);
+            if others().any(|e| e.source == edge.source && e.target == edge.target) {
+                return Err(UpdateEdgeError::Duplicate);
+            }
+            // Check the cycle without the edge's old endpoints
+            let old = self.edges.remove(index);
+            let cycle = would_create_cycle(self, &edge.source, &edge.target);
+            self.edges.insert(index, old);
+            if cycle {
+                return Err(UpdateEdgeError::Cycle);
+            }
+        }
+
+        self.edges[index] = edge.clone();
+        Ok(edge)
+    }
+
+    /// Replace a node's declared exports. If they changed, the node and its
+    /// dependents become stale when complete, since their prompts included the old
+    /// exports. Returns the IDs of the affected nodes.
+    pub fn set_exports(&mut self, node_id: &str, exports: Vec<ExportSignature>) -> Vec<String> {
+        let Some(node) = self.find_node_mut(node_id) else {
+            return Vec::new();
+        };
+        if node.exports == exports {
+            return Vec::new();
+        }
+        node.exports = exports;
+
+        let dependents: HashSet<&str> = self
+            .edges
+            .iter()
+            .filter(|e| e.source == node_id)
+            .map(|e| e.target.as_str())
+            .collect();
+        let mut affected = Vec::new();
+        for node in &mut self.nodes {
+            let uses_exports = node.id == node_id || dependents.contains(node.id.as_str());
+            if uses_exports && node.status == NodeStatus::Complete {
+                node.status = NodeStatus::Stale;
+                affected.push(node.id.clone());
+            }
+        }
+        affected
+    }
+
+    fn mark_shared_type_users_stale(&mut self, name: &str) -> Vec<String> {
+        let mut affected = Vec::new();
+        for node in &mut self.nodes {
+            if node.uses_shared_type(name) && node.status == NodeStatus::Complete {
+                node.status = NodeStatus::Stale;
+                affected.push(node.id.clone());
+            }
+        }
+        affected
+    }
+
+    fn mark_snippet_users_stale(&mut self, name: &str) -> Vec<String> {
+        let mut affected = Vec::new();
+        for node in &mut self.nodes {
+            if node.snippets.iter().any(|s| s == name) && node.status == NodeStatus::Complete {
+                node.status = NodeStatus::Stale;
+                affected.push(node.id.clone());
+            }
+        }
+        affected
+    }
+}
+
+/// Insert `_copy` before the file extension (`src/user.ts` -> `src/user_copy.ts`)
+fn copy_file_path(file_path: &str) -> String {
+    let name_start = file_path.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match file_path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let dot = name_start + dot;
+            format!("{}_copy{}", &file_path[..dot], &file_path[dot..])
+        }
+        _ => format!("{}_copy", file_path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> (Project, Vec<String>) {
+        let mut project = Project::new(String::new());
+        for name in ["a", "b", "c"] {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.status = NodeStatus::Complete;
+            project.nodes.push(node);
+        }
+        let ids: Vec<String> = project.nodes.iter().map(|n| n.id.clone()).collect();
+        project.edges = vec![
+            CodeEdge::new(ids[0].clone(), ids[1].clone(), "imports".to_string()),
+            CodeEdge::new(ids[1].clone(), ids[2].clone(), "imports".to_string()),
+        ];
+        (project, ids)
+    }
+
+    #[test]
+    fn test_language_names() {
+        let language: Language = serde_json::from_str("\"CSharp\"").unwrap();
+        assert_eq!(language, Language::CSharp);
+        assert_eq!(serde_json::to_string(&Language::Cpp).unwrap(), "\"cpp\"");
+
+        // Unknown names round-trip as custom languages
+        let language: Language = serde_json::from_str("\"elixir\"").unwrap();
+        assert_eq!(language, Language::Custom("elixir".to_string()));
+        assert_eq!(serde_json::to_string(&language).unwrap(), "\"elixir\"");
+        assert_eq!(Language::from_extension("kt"), Some(Language::Kotlin));
+    }
+
+    #[test]
+    fn test_remove_node_cascades() {
+        let (mut project, ids) = chain();
+        assert!(matches!(
+            project.remove_node(&ids[1], DeleteCascade::Block),
+            Err(RemoveNodeError::HasDependents(_))
+        ));
+
+        let affected = project.remove_node(&ids[1], DeleteCascade::Stale).unwrap();
+        assert_eq!(affected, vec![ids[2].clone()]);
+        assert_eq!(project.find_node(&ids[2]).unwrap().status, NodeStatus::Stale);
+        assert!(project.edges.is_empty());
+
+        let (mut project, ids) = chain();
+        project.remove_node(&ids[1], DeleteCascade::Detach).unwrap();
+        assert_eq!(project.edges.len(), 1);
+        assert_eq!(project.edges[0].source, ids[0]);
+        assert_eq!(project.edges[0].target, ids[2]);
+    }
+
+    #[test]
+    fn test_clone_node() {
+        let (mut project, ids) = chain();
+        let original = project.find_node_mut(&ids[1]).unwrap();
+        original.kind = NodeKind::Config;
+        original.tags = vec!["api".to_string()];
+        original.variables.insert("PORT".to_string(), "8080".to_string());
+        original.requires_approval = true;
+        let clone = project.clone_node(&ids[1], None, None, true).unwrap();
+
+        assert_ne!(clone.id, ids[1]);
+        assert_eq!(clone.kind, NodeKind::Config);
+        assert_eq!(clone.tags, vec!["api".to_string()]);
+        assert_eq!(clone.variables["PORT"], "8080");
+        assert!(clone.requires_approval);
+        assert_eq!(clone.name, "b_copy");
+        assert_eq!(clone.file_path, "b_copy.ts");
+        assert_eq!(clone.status, NodeStatus::Pending);
+        assert!(project
+            .edges
+            .iter()
+            .any(|e| e.source == ids[0] && e.target == clone.id));
+
+        assert_eq!(copy_file_path("src/.env"), "src/.env_copy");
+        assert_eq!(copy_file_path("src/v1.2/Makefile"), "src/v1.2/Makefile_copy");
+    }
+
+    #[test]
+    fn test_generation_profiles() {
+        let mut project = Project::new(String::new());
+        project.manifest.profiles.insert(
+            "draft".to_string(),
+            GenerationProfile {
+                model: Some("claude-3-5-haiku-latest".to_string()),
+                max_tokens: Some(1024),
+                ..Default::default()
+            },
+        );
+        let config = LLMConfig {
+            constraints: vec!["No globals".to_string()],
+            ..LLMConfig::default()
+        };
+
+        let (applied, max_tokens, temperature) =
+            generation_settings(&config, Some(project.profile("draft").unwrap()));
+        assert_eq!(applied.model, "claude-3-5-haiku-latest");
+        assert_eq!(applied.provider, LLMProvider::Anthropic);
+        assert_eq!(applied.constraints, config.constraints);
+        assert_eq!((max_tokens, temperature), (1024, DEFAULT_TEMPERATURE));
+
+        let (unchanged, max_tokens, _) = generation_settings(&config, None);
+        assert_eq!(unchanged.model, config.model);
+        assert_eq!(max_tokens, DEFAULT_MAX_TOKENS);
+        assert!(project.profile("final").unwrap_err().contains("available: draft"));
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let mut manifest = ProjectManifest::default();
+        let mut config = LLMConfig::default();
+        let default = Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS);
+        assert_eq!(request_timeout(&manifest, &config), default);
+
+        manifest.request_timeout_secs = Some(60);
+        assert_eq!(request_timeout(&manifest, &config), Duration::from_secs(60));
+        config.timeout_secs = Some(900);
+        assert_eq!(request_timeout(&manifest, &config), Duration::from_secs(900));
+    }
+
+    #[test]
+    fn test_snippets_mark_users_stale() {
+        let (mut project, ids) = chain();
+        project.nodes[0].snippets = vec!["errors".to_string()];
+        project.nodes[1].snippets = vec!["errors".to_string()];
+        project.nodes[1].status = NodeStatus::Pending;
+
+        // Adding a snippet nodes already attach counts as a change
+        let text = "Wrap errors with context".to_string();
+        assert_eq!(project.set_snippet("errors", text.clone()), vec![ids[0].clone()]);
+        assert_eq!(project.find_node(&ids[0]).unwrap().status, NodeStatus::Stale);
+        assert_eq!(project.find_node(&ids[1]).unwrap().status, NodeStatus::Pending);
+
+        project.nodes[0].status = NodeStatus::Complete;
+        assert!(project.set_snippet("errors", text).is_empty());
+        assert_eq!(project.remove_snippet("errors"), Some(vec![ids[0].clone()]));
+        assert_eq!(project.remove_snippet("errors"), None);
+    }
+
+    #[test]
+    fn test_shared_types_mark_users_stale() {
+        let (mut project, ids) = chain();
+        project.nodes[0].shared_types = vec!["User".to_string()];
+        project.nodes[1].exports = vec![ExportSignature {
+            name: "UserRow".to_string(),
+            shared_type: Some("User".to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(project.nodes[1].shared_type_names(), vec!["User"]);
+
+        let user = SharedType {
+            description: String::new(),
+            variants: HashMap::from([
+                (Language::TypeScript, "interface User { id: string }".to_string()),
+                (Language::Rust, "pub struct User { pub id: String }".to_string()),
+            ]),
+        };
+        assert_eq!(project.set_shared_type("User", user.clone()), ids[..2].to_vec());
+        assert_eq!(project.find_node(&ids[2]).unwrap().status, NodeStatus::Complete);
+        assert_eq!(
+            user.variant(&Language::Python),
+            Some((&Language::Rust, "pub struct User { pub id: String }"))
+        );
+
+        project.nodes[0].status = NodeStatus::Complete;
+        assert!(project.set_shared_type("User", user).is_empty());
+        assert_eq!(project.remove_shared_type("User"), Some(vec![ids[0].clone()]));
+        assert_eq!(project.remove_shared_type("User"), None);
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.generated_code = Some("export {}".to_string());
+
+        node.apply_patch(&serde_json::json!({
+            "language": "python",
+            "llmConfig": { "model": "gpt-4o", "constraints": ["No globals"] },
+            "position": { "x": 10.0 },
+            "generatedCode": null,
+        }))
+        .unwrap();
+        assert_eq!(node.language, Language::Python);
+        assert_eq!(node.llm_config.model, "gpt-4o");
+        assert_eq!(node.llm_config.provider, LLMProvider::Anthropic);
+        assert_eq!(node.llm_config.constraints, vec!["No globals".to_string()]);
+        assert_eq!(node.position.x, 10.0);
+        assert_eq!(node.generated_code, None);
+
+        // Rejected patches leave the node as it was
+        for patch in [
+            serde_json::json!({ "id": "other" }),
+            serde_json::json!({ "language": 5 }),
+            serde_json::json!({ "status": "done" }),
+            serde_json::json!({ "llmConfig": { "model": "gpt 4o" } }),
+            serde_json::json!({ "name": "" }),
+            serde_json::json!(["name"]),
+        ] {
+            assert!(node.apply_patch(&patch).is_err(), "{}", patch);
+        }
+        assert_eq!(node.name, "A");
+        assert_eq!(node.language, Language::Python);
+
+        // A local model is a path, which may contain spaces
+        let patch = serde_json::json!({
+            "llmConfig": { "provider": "local", "model": "models/My Models/coder.gguf" }
+        });
+        node.apply_patch(&patch).unwrap();
+        assert_eq!(node.llm_config.model, "models/My Models/coder.gguf");
+        let patch = serde_json::json!({ "llmConfig": { "model": " coder.gguf" } });
+        assert!(node.apply_patch(&patch).is_err());
+    }
+
+    #[test]
+    fn test_set_exports() {
+        let (mut project, ids) = chain();
+        let exports = vec![ExportSignature {
+            name: "login".to_string(),
+            type_signature: "() => void".to_string(),
+            description: String::new(),
+            shared_type: None,
+        }];
+
+        // The node and its direct dependents were generated against the old exports
+        let affected = project.set_exports(&ids[0], exports.clone());
+        assert_eq!(affected, vec![ids[0].clone(), ids[1].clone()]);
+        assert_eq!(project.find_node(&ids[0]).unwrap().exports, exports);
+        assert!(project.set_exports(&ids[0], exports.clone()).is_empty());
+
+        let twice = [exports.clone(), exports].concat();
+        assert!(validate_exports(&twice).is_err());
+        assert!(validate_exports(&[ExportSignature::default()]).is_err());
+    }
+
+    #[test]
+    fn test_update_edge() {
+        let (mut project, ids) = chain();
+        let edge_id = project.edges[0].id.clone();
+
+        let update = EdgeUpdate {
+            label: Some("extends".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(project.update_edge(&edge_id, update).unwrap().label, "extends");
+
+        // a -> c is fine alongside b -> c; c -> b would close a cycle with it
+        let rewired = EdgeUpdate {
+            target: Some(ids[2].clone()),
+            ..Default::default()
+        };
+        let edge = project.update_edge(&edge_id, rewired).unwrap();
+        assert_eq!((edge.id, edge.target), (edge_id.clone(), ids[2].clone()));
+        let reversed = EdgeUpdate {
+            source: Some(ids[2].clone()),
+            target: Some(ids[1].clone()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            project.update_edge(&edge_id, reversed),
+            Err(UpdateEdgeError::Cycle)
+        ));
+        assert!(matches!(
+            project.update_edge("missing", EdgeUpdate::default()),
+            Err(UpdateEdgeError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_manifest_patch() {
+        let mut manifest = ProjectManifest::default();
+        manifest
+            .apply_patch(&serde_json::json!({
+                "name": "Shop",
+                "entryPoint": "src/main.ts",
+                "defaultLlm": { "provider": "openai", "model": "gpt-4o" },
+            }))
+            .unwrap();
+        assert_eq!(manifest.name, "Shop");
+        assert_eq!(manifest.entry_point.as_deref(), Some("src/main.ts"));
+        assert_eq!(manifest.default_llm.provider, LLMProvider::OpenAI);
+        assert_eq!(manifest.default_llm.api_key_env, "ANTHROPIC_API_KEY");
+
+        manifest.apply_patch(&serde_json::json!({ "entryPoint": null })).unwrap();
+        assert_eq!(manifest.entry_point, None);
+
+        for patch in [
+            serde_json::json!({ "snippets": {} }),
+            serde_json::json!({ "version": " " }),
+            serde_json::json!({ "defaultLlm": { "provider": "acme" } }),
+            serde_json::json!({ "postProcessing": { "typescript": [{ "command": ["sh"] }] } }),
+        ] {
+            assert!(manifest.apply_patch(&patch).is_err(), "{}", patch);
+        }
+        assert_eq!(manifest.name, "Shop");
+
+        let command = PostProcessStep::Command {
+            command: vec!["eslint".to_string(), "--fix".to_string()],
+        };
+        manifest.post_processing.insert(Language::TypeScript, vec![command.clone()]);
+        manifest
+            .apply_patch(&serde_json::json!({
+                "postProcessing": {
+                    "typescript": ["stripFences", { "command": ["eslint", "--fix"] }],
+                },
+            }))
+            .unwrap();
+        assert_eq!(
+            manifest.post_processing[&Language::TypeScript],
+            vec![PostProcessStep::Builtin(BuiltinStep::StripFences), command]
+        );
+    }
+
+    #[test]
+    fn test_project_default_llm() {
+        let mut project = Project::new("/tmp/test".to_string());
+        project.manifest.default_llm.provider = LLMProvider::OpenAI;
+        project.manifest.default_llm.model = "gpt-4o".to_string();
+
+        let node = project.new_node("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        assert_eq!(node.llm_config.provider, LLMProvider::OpenAI);
+        assert!(node.llm_config.use_project_default);
+        let mut pinned = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        pinned.apply_patch(&serde_json::json!({"llmConfig": {"model": "o3"}})).unwrap();
+        project.nodes.extend([node, pinned]);
+
+        // Changing the manifest carries over to the nodes following it
+        project.manifest.default_llm.provider = LLMProvider::Ollama;
+        project.manifest.default_llm.model = "llama3".to_string();
+        let resolved = project.nodes[0].llm_config.resolve(&project.manifest.default_llm);
+        assert_eq!(resolved.model, "llama3");
+        project.apply_default_llm();
+        assert_eq!(project.nodes[0].llm_config.provider, LLMProvider::Ollama);
+        assert_eq!(project.nodes[1].llm_config.model, "o3");
+
+        // Picking a model opts out of the default unless the flag is kept
+        let node = &mut project.nodes[0];
+        let keep = serde_json::json!({"llmConfig": {"model": "x", "useProjectDefault": true}});
+        node.apply_patch(&keep).unwrap();
+        assert!(node.llm_config.use_project_default);
+        node.apply_patch(&serde_json::json!({"llmConfig": {"model": "gpt-4o-mini"}})).unwrap();
+        assert!(!node.llm_config.use_project_default);
+        assert_eq!(node.llm_config.model, "gpt-4o-mini");
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::model::{PackageEcosystem, Project};
+
+/// A generated package manifest for one ecosystem
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageManifest {
+    pub ecosystem: PackageEcosystem,
+    /// Conventional file name (`package.json`, `Cargo.toml`, `requirements.txt`)
+    pub file_name: String,
+    pub contents: String,
+}
+
+/// Package name derived from the project name: lowercase, with runs of other
+/// characters collapsed to `-`
+pub(crate) fn package_name(project: &Project) -> String {
+    let mut name = String::new();
+    for c in project.manifest.name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c);
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    let name = name.trim_end_matches('-');
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Versions of every external package in an ecosystem, keyed by name
+pub(crate) fn external_packages(project: &Project, ecosystem: PackageEcosystem) -> BTreeMap<&str, &str> {
+    project
+        .nodes
+        .iter()
+        .filter(|n| !n.disabled)
+        .filter_map(|n| n.package.as_ref())
+        .filter(|p| p.ecosystem == ecosystem && !p.name.is_empty())
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect()
+}
+
+pub(crate) fn package_json(project: &Project, packages: &BTreeMap<&str, &str>) -> String {
+    let dependencies: BTreeMap<&str, &str> = packages
+        .iter()
+        .map(|(name, version)| (*name, if version.is_empty() { "*" } else { version }))
+        .collect();
+    let manifest = serde_json::json!({
+        "name": package_name(project),
+        "version": project.manifest.version,
+        "private": true,
+        "dependencies": dependencies,
+    });
+    format!("{}\n", serde_json::to_string_pretty(&manifest).unwrap_or_default())
+}
+
+pub(crate) fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn cargo_toml(project: &Project, packages: &BTreeMap<&str, &str>) -> String {
+    let mut contents = String::from("[package]\n");
+    contents.push_str(&format!("name = {}\n", toml_string(&package_name(project))));
+    contents.push_str(&format!("version = {}\n", toml_string(&project.manifest.version)));
+    contents.push_str("edition = \"2021\"\n\n[dependencies]\n");
+    for (name, version) in packages {
+        let version = if version.is_empty() { "*" } else { version };
+        contents.push_str(&format!("{} = {}\n", name, toml_string(version)));
+    }
+    contents
+}
+
+/// A PEP 508 requirement: a bare version pins with `==`, anything else is taken
+/// as a specifier
+pub(crate) fn pip_requirement(name: &str, version: &str) -> String {
+    if version.is_empty() {
+        name.to_string()
+    } else if version.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{}=={}", name, version)
+    } else {
+        format!("{}{}", name, version)
+    }
+}
+
+fn requirements_txt(packages: &BTreeMap<&str, &str>) -> String {
+    packages
+        .iter()
+        .map(|(name, version)| format!("{}\n", pip_requirement(name, version)))
+        .collect()
+}
+
+/// Generate a package manifest for each ecosystem that has external dependency nodes
+pub fn generate_package_manifests(project: &Project) -> Vec<PackageManifest> {
+    [PackageEcosystem::Npm, PackageEcosystem::Crates, PackageEcosystem::Pip]
+        .into_iter()
+        .filter_map(|ecosystem| {
+            let packages = external_packages(project, ecosystem);
+            if packages.is_empty() {
+                return None;
+            }
+            let (file_name, contents) = match ecosystem {
+                PackageEcosystem::Npm => ("package.json", package_json(project, &packages)),
+                PackageEcosystem::Crates => ("Cargo.toml", cargo_toml(project, &packages)),
+                PackageEcosystem::Pip => ("requirements.txt", requirements_txt(&packages)),
+            };
+            Some(PackageManifest {
+                ecosystem,
+                file_name: file_name.to_string(),
+                contents,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, ExternalPackage, Language, NodeKind};
+
+    fn external(name: &str, ecosystem: PackageEcosystem, version: &str) -> CodeNode {
+        let mut node = CodeNode::new(name.to_string(), String::new(), Language::TypeScript);
+        node.kind = NodeKind::External;
+        node.package = Some(ExternalPackage {
+            name: name.to_string(),
+            ecosystem,
+            version: version.to_string(),
+            docs: String::new(),
+        });
+        node
+    }
+
+    #[test]
+    fn test_generate_package_manifests() {
+        let mut project = Project::new(String::new());
+        project.manifest.name = "My App".to_string();
+        project.nodes.push(external("zod", PackageEcosystem::Npm, "^3.22"));
+        project.nodes.push(external("axios", PackageEcosystem::Npm, "1.x"));
+        project.nodes.push(external("requests", PackageEcosystem::Pip, "2.31"));
+
+        let manifests = generate_package_manifests(&project);
+        assert_eq!(manifests.len(), 2);
+
+        let package_json: serde_json::Value = serde_json::from_str(&manifests[0].contents).unwrap();
+        assert_eq!(package_json["name"], "my-app");
+        assert_eq!(package_json["dependencies"]["axios"], "1.x");
+        assert_eq!(package_json["dependencies"]["zod"], "^3.22");
+
+        assert_eq!(manifests[1].file_name, "requirements.txt");
+        assert_eq!(manifests[1].contents, "requests==2.31\n");
+    }
+}
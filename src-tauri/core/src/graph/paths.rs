@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+/// Validate and sanitize a file path to prevent directory traversal attacks
+/// Returns the canonicalized full path if valid, or an error if the path is dangerous
+pub fn validate_path(project_path: &str, file_path: &str) -> Result<PathBuf, String> {
+    // Reject empty paths
+    if file_path.is_empty() {
+        return Err("File path cannot be empty".to_string());
+    }
+
+    // Reject paths with null bytes
+    if file_path.contains('\0') {
+        return Err("File path contains invalid characters".to_string());
+    }
+
+    // Reject absolute paths
+    let file_path_obj = Path::new(file_path);
+    if file_path_obj.is_absolute() {
+        return Err("Absolute paths are not allowed".to_string());
+    }
+
+    // Reject paths containing .. (directory traversal)
+    for component in file_path_obj.components() {
+        if let std::path::Component::ParentDir = component {
+            return Err("Path cannot contain '..' (directory traversal not allowed)".to_string());
+        }
+    }
+
+    // Reject paths starting with . that aren't just a filename starting with .
+    // Allow: ".gitignore", "src/.env" but reject: "../foo", ".."
+    let normalized = file_path.replace('\\', "/");
+    if normalized.starts_with("../") || normalized.contains("/../") || normalized == ".." {
+        return Err("Path cannot traverse outside project directory".to_string());
+    }
+
+    // Build the full path
+    let project_dir = Path::new(project_path);
+    let full_path = project_dir.join(file_path);
+
+    // Canonicalize project path (must exist)
+    let canonical_project = project_dir.canonicalize()
+        .map_err(|e| format!("Invalid project path: {}", e))?;
+
+    // For the full path, we need to handle non-existent files
+    // Canonicalize as much as possible, then check the result
+    let canonical_full = if full_path.exists() {
+        full_path.canonicalize()
+            .map_err(|e| format!("Failed to resolve path: {}", e))?
+    } else {
+        // For non-existent files, canonicalize the parent and append the filename
+        if let Some(parent) = full_path.parent() {
+            if parent.exists() {
+                let canonical_parent = parent.canonicalize()
+                    .map_err(|e| format!("Failed to resolve parent path: {}", e))?;
+                if let Some(file_name) = full_path.file_name() {
+                    canonical_parent.join(file_name)
+                } else {
+                    return Err("Invalid file path".to_string());
+                }
+            } else {
+                // Parent doesn't exist yet - verify the path components don't escape
+                // This is less strict but necessary for creating new directories
+                full_path.clone()
+            }
+        } else {
+            return Err("Invalid file path".to_string());
+        }
+    };
+
+    // Verify the resolved path is within the project directory
+    // Use string comparison after canonicalization for existing paths
+    if canonical_full.exists() {
+        let canonical_str = canonical_full.to_string_lossy();
+        let project_str = canonical_project.to_string_lossy();
+        if !canonical_str.starts_with(project_str.as_ref()) {
+            return Err("Path resolves outside project directory".to_string());
+        }
+    }
+
+    Ok(full_path)
+}
+
+/// Normalize a node file path for comparison with paths found on disk
+pub fn normalize_file_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Walk the project directory, respecting `.gitignore` and skipping `.git/` and
+/// `.needlepoint/`. Returns paths relative to the root, with forward slashes,
+/// and whether each is a directory, in file name order.
+pub fn walk_project_files(root: &Path) -> Result<Vec<(String, bool)>, String> {
+    if !root.is_dir() {
+        return Err(format!("Project directory not found: {}", root.display()));
+    }
+
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|e| e.file_name() != ".git" && e.file_name() != ".needlepoint")
+        .build();
+
+    let mut paths = Vec::new();
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("Failed to read project directory: {}", e))?;
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let relative = normalize_file_path(&relative.to_string_lossy());
+        if relative.is_empty() {
+            continue;
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        paths.push((relative, is_dir));
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_path_rejects_parent_dir() {
+        let result = validate_path("/tmp/project", "../etc/passwd");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("directory traversal"));
+    }
+
+    #[test]
+    fn test_validate_path_rejects_absolute() {
+        let result = validate_path("/tmp/project", "/etc/passwd");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Absolute paths"));
+    }
+
+    #[test]
+    fn test_validate_path_rejects_empty() {
+        let result = validate_path("/tmp/project", "");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("empty"));
+    }
+
+    #[test]
+    fn test_validate_path_accepts_normal_paths() {
+        // Note: This test requires the project path to exist
+        // In real tests, we'd use a temp directory
+        let result = validate_path(".", "src/main.rs");
+        // Should not error on path format (may error if path doesn't exist)
+        if let Err(e) = &result {
+            assert!(!e.contains("traversal"));
+            assert!(!e.contains("Absolute"));
+        }
+    }
+}
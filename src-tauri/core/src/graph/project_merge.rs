@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::model::Project;
+
+/// What a merge conflict is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeTarget {
+    Manifest,
+    Node,
+    Edge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// Both sides changed the field to different values; ours is kept
+    Field,
+    /// One side deleted it while the other changed it; the changed version is kept
+    DeletedAndChanged,
+    /// The edge's source or target was deleted; the edge is dropped
+    DanglingEdge,
+}
+
+/// A change the merge couldn't reconcile
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    pub target: MergeTarget,
+    pub kind: ConflictKind,
+    /// Node or edge ID; `None` for the manifest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Dotted path of the conflicting field, e.g. `llmConfig.model`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+impl MergeConflict {
+    /// One-line description, e.g. `node 1f2e…: llmConfig.model changed on both sides`
+    pub fn describe(&self) -> String {
+        let target = match (self.target, &self.id) {
+            (MergeTarget::Manifest, _) => "manifest".to_string(),
+            (MergeTarget::Node, Some(id)) => format!("node {}", id),
+            (MergeTarget::Edge, Some(id)) => format!("edge {}", id),
+            (_, None) => "unknown".to_string(),
+        };
+        match self.kind {
+            ConflictKind::Field => format!(
+                "{}: {} changed on both sides",
+                target,
+                self.field.as_deref().unwrap_or("value")
+            ),
+            ConflictKind::DeletedAndChanged => {
+                let deleted_by = if self.ours.is_none() { "ours" } else { "theirs" };
+                format!("{}: deleted in {} but changed in the other", target, deleted_by)
+            }
+            ConflictKind::DanglingEdge => format!("{}: refers to a deleted node", target),
+        }
+    }
+}
+
+/// Result of merging two versions of a project
+#[derive(Debug, Clone)]
+pub struct ProjectMerge {
+    pub project: Project,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Three-way merge two versions of a project that diverged from `base`. Nodes and
+/// edges are matched by ID and merged field by field, so changes to different
+/// fields, nodes or edges combine cleanly. Conflicting fields keep our value.
+pub fn merge_projects(base: &Project, ours: &Project, theirs: &Project) -> Result<ProjectMerge> {
+    let base = serde_json::to_value(base).context("Failed to serialize base project")?;
+    let ours_value = serde_json::to_value(ours).context("Failed to serialize our project")?;
+    let theirs = serde_json::to_value(theirs).context("Failed to serialize their project")?;
+    let mut conflicts = Vec::new();
+
+    let mut manifest_conflicts = Vec::new();
+    let manifest = merge_value(
+        "",
+        base.get("manifest"),
+        ours_value.get("manifest"),
+        theirs.get("manifest"),
+        &mut manifest_conflicts,
+    );
+    conflicts.extend(manifest_conflicts.into_iter().map(|(field, (base, ours, theirs))| {
+        MergeConflict {
+            target: MergeTarget::Manifest,
+            kind: ConflictKind::Field,
+            id: None,
+            field: Some(field),
+            base,
+            ours,
+            theirs,
+        }
+    }));
+
+    let versions = (&base, &ours_value, &theirs);
+    let nodes = merge_entities(MergeTarget::Node, "nodes", versions, &mut conflicts);
+    let mut edges = merge_entities(MergeTarget::Edge, "edges", versions, &mut conflicts);
+
+    let node_ids: HashSet<&str> = nodes.iter().filter_map(entity_id).collect();
+    edges.retain(|edge| {
+        let connected = ["source", "target"]
+            .iter()
+            .all(|end| edge[*end].as_str().is_some_and(|id| node_ids.contains(id)));
+        if !connected {
+            conflicts.push(MergeConflict {
+                target: MergeTarget::Edge,
+                kind: ConflictKind::DanglingEdge,
+                id: entity_id(edge).map(str::to_string),
+                field: None,
+                base: None,
+                ours: None,
+                theirs: None,
+            });
+        }
+        connected
+    });
+
+    let mut merged = ours_value.clone();
+    merged["manifest"] = manifest.unwrap_or(Value::Null);
+    merged["nodes"] = Value::Array(nodes);
+    merged["edges"] = Value::Array(edges);
+    let project = serde_json::from_value(merged).context("Merged project is invalid")?;
+
+    Ok(ProjectMerge { project, conflicts })
+}
+
+fn entity_id(value: &Value) -> Option<&str> {
+    value["id"].as_str()
+}
+
+fn find_entity<'a>(list: &'a [Value], id: &str) -> Option<&'a Value> {
+    list.iter().find(|v| entity_id(v) == Some(id))
+}
+
+/// Merge the nodes or edges of three versions, in our order followed by theirs' additions
+fn merge_entities(
+    target: MergeTarget,
+    key: &str,
+    (base, ours, theirs): (&Value, &Value, &Value),
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<Value> {
+    let list = |project: &Value| project[key].as_array().cloned().unwrap_or_default();
+    let (base, ours, theirs) = (list(base), list(ours), list(theirs));
+
+    let mut ids: Vec<&str> = Vec::new();
+    for id in ours.iter().chain(&theirs).chain(&base).filter_map(entity_id) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    let mut merged = Vec::new();
+    for id in ids {
+        let b = find_entity(&base, id);
+        let o = find_entity(&ours, id);
+        let t = find_entity(&theirs, id);
+        let conflict = |kind, field, (base, ours, theirs): FieldVersions| MergeConflict {
+            target,
+            kind,
+            id: Some(id.to_string()),
+            field,
+            base,
+            ours,
+            theirs,
+        };
+
+        match (o, t) {
+            // Deleted on both sides
+            (None, None) => {}
+            // Deleted on one side: gone if the other left it alone, kept if it changed it
+            (None, Some(changed)) | (Some(changed), None) if b.is_some() => {
+                if b != Some(changed) {
+                    let versions = (b.cloned(), o.cloned(), t.cloned());
+                    conflicts.push(conflict(ConflictKind::DeletedAndChanged, None, versions));
+                    merged.push(changed.clone());
+                }
+            }
+            // Added on one side only
+            (None, Some(added)) | (Some(added), None) => merged.push(added.clone()),
+            (Some(_), Some(_)) => {
+                let mut fields = Vec::new();
+                if let Some(value) = merge_value("", b, o, t, &mut fields) {
+                    merged.push(value);
+                }
+                conflicts.extend(fields.into_iter().map(|(field, versions)| {
+                    conflict(ConflictKind::Field, Some(field), versions)
+                }));
+            }
+        }
+    }
+    merged
+}
+
+/// A value in the base, our and their versions; `None` where it's absent
+type FieldVersions = (Option<Value>, Option<Value>, Option<Value>);
+type FieldConflict = (String, FieldVersions);
+
+/// Three-way merge a JSON value, recursing into objects. A field missing from a
+/// version is absent there; conflicts are recorded by dotted path and keep ours.
+fn merge_value(
+    path: &str,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    conflicts: &mut Vec<FieldConflict>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    let empty = Map::new();
+    let base_object = match base {
+        None => Some(&empty),
+        Some(value) => value.as_object(),
+    };
+    if let (Some(b), Some(Value::Object(o)), Some(Value::Object(t))) = (base_object, ours, theirs) {
+        let mut merged = Map::new();
+        for key in o.keys().chain(t.keys().filter(|k| !o.contains_key(*k))) {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            let value = merge_value(&field, b.get(key), o.get(key), t.get(key), conflicts);
+            if let Some(value) = value {
+                merged.insert(key.clone(), value);
+            }
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push((path.to_string(), (base.cloned(), ours.cloned(), theirs.cloned())));
+    ours.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode, Language};
+
+    #[test]
+    fn test_merge_projects() {
+        let mut base = Project::new("/tmp/project".to_string());
+        let a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let c = CodeNode::new("C".to_string(), "c.ts".to_string(), Language::TypeScript);
+        let edge = CodeEdge::new(a.id.clone(), c.id.clone(), "uses".to_string());
+        base.nodes = vec![a.clone(), b.clone(), c.clone()];
+        base.edges = vec![edge.clone()];
+
+        let mut ours = base.clone();
+        ours.nodes[0].description = "Ours".to_string();
+        ours.nodes[0].llm_config.model = "ours-model".to_string();
+        ours.nodes[1].name = "B (ours)".to_string();
+        ours.manifest.name = "Renamed".to_string();
+        ours.nodes.retain(|n| n.id != c.id);
+        ours.edges.clear();
+
+        let mut theirs = base.clone();
+        theirs.nodes[0].purpose = "Theirs".to_string();
+        theirs.nodes[0].llm_config.model = "their-model".to_string();
+        theirs.nodes[1].name = "B (theirs)".to_string();
+        let d = CodeNode::new("D".to_string(), "d.ts".to_string(), Language::TypeScript);
+        theirs.edges.push(CodeEdge::new(a.id.clone(), d.id.clone(), "uses".to_string()));
+        theirs.nodes.push(d.clone());
+
+        let merge = merge_projects(&base, &ours, &theirs).unwrap();
+        let project = merge.project;
+        assert_eq!(project.manifest.name, "Renamed");
+        let ids: Vec<_> = project.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec![a.id.as_str(), b.id.as_str(), d.id.as_str()]);
+        assert_eq!(project.nodes[0].description, "Ours");
+        assert_eq!(project.nodes[0].purpose, "Theirs");
+        assert_eq!(project.edges.len(), 1);
+        assert_eq!(project.edges[0].target, d.id);
+
+        let fields: Vec<_> = merge.conflicts.iter().map(|c| c.field.as_deref()).collect();
+        assert_eq!(fields, vec![Some("llmConfig.model"), Some("name")]);
+        assert_eq!(project.nodes[0].llm_config.model, "ours-model");
+        assert_eq!(
+            merge.conflicts[1].describe(),
+            format!("node {}: name changed on both sides", b.id)
+        );
+
+        // Deleting a node the other side changed keeps the change and reports it
+        let mut changed = base.clone();
+        changed.nodes[2].description = "Still needed".to_string();
+        let merge = merge_projects(&base, &ours, &changed).unwrap();
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].kind, ConflictKind::DeletedAndChanged);
+        assert!(merge.project.find_node(&c.id).is_some());
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+
+use super::model::{CodeNode, Language, NodeStatus};
+
+/// Field to order a node listing by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeSort {
+    #[default]
+    Name,
+    Path,
+    Status,
+    Language,
+}
+
+/// Criteria for listing a subset of the project's nodes.
+/// All set criteria must match; unset ones match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NodeFilter {
+    pub status: Option<NodeStatus>,
+    pub language: Option<Language>,
+    /// Case-insensitive text matched against name, file path, description and purpose
+    pub q: Option<String>,
+    pub path_prefix: Option<String>,
+    pub tag: Option<String>,
+    pub sort: Option<NodeSort>,
+    #[serde(default)]
+    pub desc: bool,
+}
+
+impl NodeFilter {
+    pub fn matches(&self, node: &CodeNode) -> bool {
+        if self.status.as_ref().is_some_and(|s| *s != node.status) {
+            return false;
+        }
+        if self.language.as_ref().is_some_and(|l| *l != node.language) {
+            return false;
+        }
+        if let Some(prefix) = &self.path_prefix {
+            if !node.file_path.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if self.tag.as_deref().is_some_and(|t| !node.has_tag(t)) {
+            return false;
+        }
+        if let Some(q) = self.q.as_deref().filter(|q| !q.is_empty()) {
+            let q = q.to_lowercase();
+            let found = [&node.name, &node.file_path, &node.description, &node.purpose]
+                .iter()
+                .any(|text| text.to_lowercase().contains(&q));
+            if !found {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Filter and sort nodes. Without an explicit sort, project order is kept.
+    pub fn apply<'a>(&self, nodes: &'a [CodeNode]) -> Vec<&'a CodeNode> {
+        let mut result: Vec<&CodeNode> = nodes.iter().filter(|n| self.matches(n)).collect();
+
+        if let Some(sort) = self.sort {
+            result.sort_by(|a, b| match sort {
+                NodeSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                NodeSort::Path => a.file_path.cmp(&b.file_path),
+                NodeSort::Status => status_rank(&a.status).cmp(&status_rank(&b.status)),
+                NodeSort::Language => a.language.to_string().cmp(&b.language.to_string()),
+            });
+        }
+        if self.desc {
+            result.reverse();
+        }
+
+        result
+    }
+}
+
+/// Sparse fieldset and cursor pagination for node listings
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageQuery {
+    /// Comma-separated node fields to include (camelCase, e.g. `name,filePath,status`).
+    /// `id` is always included.
+    pub fields: Option<String>,
+    /// Maximum number of nodes to return
+    pub limit: Option<usize>,
+    /// ID of the last node of the previous page
+    pub cursor: Option<String>,
+}
+
+impl PageQuery {
+    /// Slice out the page after `cursor`, returning it along with the cursor for the next page
+    pub fn paginate<'a>(
+        &self,
+        nodes: Vec<&'a CodeNode>,
+    ) -> Result<(Vec<&'a CodeNode>, Option<String>), String> {
+        let start = match &self.cursor {
+            Some(cursor) => {
+                nodes
+                    .iter()
+                    .position(|n| &n.id == cursor)
+                    .ok_or_else(|| format!("Invalid cursor '{}'", cursor))?
+                    + 1
+            }
+            None => 0,
+        };
+
+        let remaining = &nodes[start..];
+        match self.limit {
+            Some(limit) if limit < remaining.len() => {
+                let page = remaining[..limit].to_vec();
+                let next = page.last().map(|n| n.id.clone());
+                Ok((page, next))
+            }
+            _ => Ok((remaining.to_vec(), None)),
+        }
+    }
+
+    /// Serialize a node, keeping only the requested fields
+    pub fn select(&self, node: &CodeNode) -> serde_json::Value {
+        let value = serde_json::to_value(node).unwrap_or_default();
+        let Some(fields) = &self.fields else {
+            return value;
+        };
+        let wanted: Vec<&str> = fields.split(',').map(str::trim).collect();
+
+        match value {
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| key == "id" || wanted.contains(&key.as_str()))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+/// Order statuses so the ones needing attention come first
+fn status_rank(status: &NodeStatus) -> u8 {
+    match status {
+        NodeStatus::Error => 0,
+        NodeStatus::Warning => 1,
+        NodeStatus::Stale => 2,
+        NodeStatus::Generating => 3,
+        NodeStatus::Pending => 4,
+        NodeStatus::Complete => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_filter() {
+        let node = |name: &str, path: &str, language| {
+            CodeNode::new(name.to_string(), path.to_string(), language)
+        };
+        let mut auth = node("AuthService", "src/api/auth.rs", Language::Rust);
+        auth.status = NodeStatus::Error;
+        auth.tags = vec!["backend".to_string(), "v2".to_string()];
+        let nodes = vec![
+            node("UserApi", "src/api/user.rs", Language::Rust),
+            node("Login", "src/ui/login.ts", Language::TypeScript),
+            auth,
+        ];
+
+        let filter = NodeFilter {
+            path_prefix: Some("src/api".to_string()),
+            sort: Some(NodeSort::Name),
+            ..Default::default()
+        };
+        let names: Vec<&str> = filter.apply(&nodes).iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["AuthService", "UserApi"]);
+
+        let filter = NodeFilter {
+            status: Some(NodeStatus::Error),
+            q: Some("AUTH".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&nodes).len(), 1);
+
+        let filter = NodeFilter {
+            language: Some(Language::TypeScript),
+            q: Some("auth".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.apply(&nodes).is_empty());
+
+        let filter = NodeFilter {
+            tag: Some("Backend".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&nodes)[0].name, "AuthService");
+        assert_eq!(filter.apply(&nodes).len(), 1);
+    }
+
+    #[test]
+    fn test_page_query() {
+        let nodes: Vec<CodeNode> = (0..5)
+            .map(|i| CodeNode::new(format!("N{}", i), format!("n{}.ts", i), Language::TypeScript))
+            .collect();
+        let all: Vec<&CodeNode> = nodes.iter().collect();
+
+        let mut page = PageQuery {
+            limit: Some(2),
+            fields: Some("name, status".to_string()),
+            ..Default::default()
+        };
+        let (first, next) = page.paginate(all.clone()).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(next.as_deref(), Some(nodes[1].id.as_str()));
+
+        page.cursor = next;
+        page.limit = Some(10);
+        let (rest, next) = page.paginate(all.clone()).unwrap();
+        assert_eq!(rest.len(), 3);
+        assert!(next.is_none());
+
+        let selected = page.select(&nodes[0]);
+        let keys: Vec<&String> = selected.as_object().unwrap().keys().collect();
+        assert_eq!(keys.len(), 3);
+        assert!(selected.get("generatedCode").is_none());
+
+        page.cursor = Some("missing".to_string());
+        assert!(page.paginate(all).is_err());
+    }
+}
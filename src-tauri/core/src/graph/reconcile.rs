@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::drift::content_hash;
+use super::header::wrap_generated_code;
+use super::model::{CodeNode, Language, NodeStatus, Project};
+use super::paths::{normalize_file_path, walk_project_files};
+
+/// A node and the file it points at
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeFile {
+    pub node_id: String,
+    pub node_name: String,
+    pub file_path: String,
+}
+
+/// A file on disk that no node generates
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UntrackedFile {
+    pub file_path: String,
+    /// Inferred from the extension; files without one can't become nodes
+    pub language: Option<Language>,
+}
+
+/// A node whose file is missing but whose last written contents were found elsewhere
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedFile {
+    pub node_id: String,
+    pub node_name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Differences between the graph and the project directory
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    /// Nodes whose files don't exist (and weren't found under another name)
+    pub missing: Vec<NodeFile>,
+    pub untracked: Vec<UntrackedFile>,
+    pub renamed: Vec<RenamedFile>,
+}
+
+/// The hash a node's file would have if it was untouched since it was last
+/// written: the recorded hash, or the hash of its wrapped generated code
+fn expected_hash(project: &Project, node: &CodeNode) -> Option<String> {
+    node.written_hash.clone().or_else(|| {
+        node.generated_code
+            .as_deref()
+            .filter(|code| !code.is_empty())
+            .map(|code| content_hash(&wrap_generated_code(project, node, code)))
+    })
+}
+
+fn language_of(file_path: &str) -> Option<Language> {
+    Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Language::from_extension)
+}
+
+/// Cross-check the graph against the project directory. Untracked files whose
+/// contents match a missing node's last written contents are reported as renames.
+pub fn scan_project(project: &Project) -> Result<ReconcileReport> {
+    let root = Path::new(&project.project_path);
+    let on_disk: Vec<String> = walk_project_files(root)
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .filter(|(_, is_dir)| !is_dir)
+        .map(|(path, _)| path)
+        .collect();
+
+    let file_nodes: Vec<&CodeNode> = project.nodes.iter().filter(|n| n.owns_file()).collect();
+    let tracked: HashSet<String> = file_nodes
+        .iter()
+        .map(|n| normalize_file_path(&n.file_path))
+        .collect();
+    let existing: HashSet<&str> = on_disk.iter().map(String::as_str).collect();
+
+    let missing: Vec<&CodeNode> = file_nodes
+        .into_iter()
+        .filter(|n| !existing.contains(normalize_file_path(&n.file_path).as_str()))
+        .collect();
+    let untracked: Vec<&String> = on_disk.iter().filter(|p| !tracked.contains(*p)).collect();
+
+    // Only hash untracked files when there's a missing node they could match
+    let wanted: HashMap<String, &CodeNode> = missing
+        .iter()
+        .filter_map(|n| expected_hash(project, n).map(|hash| (hash, *n)))
+        .collect();
+    let mut renamed_to: HashMap<String, String> = HashMap::new();
+    if !wanted.is_empty() {
+        for path in &untracked {
+            let full_path = root.join(path.as_str());
+            let Ok(contents) = fs::read_to_string(&full_path) else {
+                continue;
+            };
+            if let Some(node) = wanted.get(&content_hash(&contents)) {
+                renamed_to
+                    .entry(node.id.clone())
+                    .or_insert_with(|| path.to_string());
+            }
+        }
+    }
+
+    let mut report = ReconcileReport::default();
+    for node in missing {
+        match renamed_to.get(&node.id) {
+            Some(to) => report.renamed.push(RenamedFile {
+                node_id: node.id.clone(),
+                node_name: node.name.clone(),
+                from: node.file_path.clone(),
+                to: to.clone(),
+            }),
+            None => report.missing.push(NodeFile {
+                node_id: node.id.clone(),
+                node_name: node.name.clone(),
+                file_path: node.file_path.clone(),
+            }),
+        }
+    }
+
+    let renamed_paths: HashSet<&String> = renamed_to.values().collect();
+    report.untracked = untracked
+        .into_iter()
+        .filter(|path| !renamed_paths.contains(path))
+        .map(|path| UntrackedFile {
+            file_path: path.clone(),
+            language: language_of(path),
+        })
+        .collect();
+
+    Ok(report)
+}
+
+/// Point renamed nodes at their new file paths
+pub fn apply_renames(project: &mut Project, renamed: &[RenamedFile]) {
+    for rename in renamed {
+        if let Some(node) = project.find_node_mut(&rename.node_id) {
+            node.file_path = rename.to.clone();
+        }
+    }
+}
+
+/// Create a node for an existing file, taking its contents as the generated code
+pub fn import_file(project: &Project, file: &UntrackedFile) -> Result<Option<CodeNode>> {
+    let Some(language) = file.language.clone() else {
+        return Ok(None);
+    };
+
+    let full_path = Path::new(&project.project_path).join(&file.file_path);
+    let contents = fs::read_to_string(&full_path)
+        .with_context(|| format!("Failed to read file: {:?}", full_path))?;
+
+    let name = Path::new(&file.file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.file_path.clone());
+
+    let mut node = project.new_node(name, file.file_path.clone(), language);
+    node.status = NodeStatus::Complete;
+    node.written_hash = Some(content_hash(&contents));
+    node.generated_code = Some(contents);
+    Ok(Some(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/a.ts"), "export const a = 1;\n").unwrap();
+        fs::write(root.join("src/b2.ts"), "export const b = 2;\n").unwrap();
+        fs::write(root.join("src/d.py"), "d = 4\n").unwrap();
+        fs::write(root.join("README.md"), "# Readme\n").unwrap();
+
+        let mut project = Project::new(root.to_string_lossy().to_string());
+        let a = CodeNode::new("A".to_string(), "src/a.ts".to_string(), Language::TypeScript);
+        let mut b = CodeNode::new("B".to_string(), "src/b.ts".to_string(), Language::TypeScript);
+        b.generated_code = Some("export const b = 2;\n".to_string());
+        let c = CodeNode::new("C".to_string(), "src/c.ts".to_string(), Language::TypeScript);
+        let (b_id, c_id) = (b.id.clone(), c.id.clone());
+        project.nodes.extend([a, b, c]);
+
+        let report = scan_project(&project).unwrap();
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].node_id, c_id);
+        assert_eq!(report.renamed.len(), 1);
+        assert_eq!(report.renamed[0].node_id, b_id);
+        assert_eq!(report.renamed[0].to, "src/b2.ts");
+
+        let untracked: Vec<(&str, Option<Language>)> = report
+            .untracked
+            .iter()
+            .map(|f| (f.file_path.as_str(), f.language.clone()))
+            .collect();
+        assert_eq!(
+            untracked,
+            vec![("README.md", None), ("src/d.py", Some(Language::Python))]
+        );
+
+        apply_renames(&mut project, &report.renamed);
+        assert_eq!(project.find_node(&b_id).unwrap().file_path, "src/b2.ts");
+
+        let node = import_file(&project, &report.untracked[1]).unwrap().unwrap();
+        assert_eq!(node.name, "d");
+        assert!(node.llm_config.use_project_default);
+        assert_eq!(node.generated_code.as_deref(), Some("d = 4\n"));
+        assert!(import_file(&project, &report.untracked[0]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::model::Project;
+
+/// Node spec fields that bulk replace can touch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplaceField {
+    Name,
+    FilePath,
+    Description,
+    Purpose,
+    Constraints,
+}
+
+impl ReplaceField {
+    pub const ALL: [ReplaceField; 5] = [
+        ReplaceField::Name,
+        ReplaceField::FilePath,
+        ReplaceField::Description,
+        ReplaceField::Purpose,
+        ReplaceField::Constraints,
+    ];
+}
+
+/// A single changed value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceMatch {
+    pub node_id: String,
+    pub node_name: String,
+    pub field: ReplaceField,
+    pub before: String,
+    pub after: String,
+}
+
+/// Literal or regex replacement applied to node specs
+enum Matcher {
+    Literal(String),
+    Pattern(Regex),
+}
+
+impl Matcher {
+    fn apply(&self, text: &str, replacement: &str) -> Option<String> {
+        let replaced = match self {
+            Matcher::Literal(find) => {
+                if !text.contains(find.as_str()) {
+                    return None;
+                }
+                text.replace(find.as_str(), replacement)
+            }
+            Matcher::Pattern(re) => {
+                if !re.is_match(text) {
+                    return None;
+                }
+                re.replace_all(text, replacement).into_owned()
+            }
+        };
+        (replaced != text).then_some(replaced)
+    }
+}
+
+/// Replace `find` with `replacement` in the given fields of every node.
+/// With `regex`, `find` is a regular expression and `replacement` may use `$1`-style groups.
+/// With `dry_run`, the project is left untouched and only the would-be changes are returned.
+pub fn replace_in_project(
+    project: &mut Project,
+    find: &str,
+    replacement: &str,
+    regex: bool,
+    fields: &[ReplaceField],
+    dry_run: bool,
+) -> Result<Vec<ReplaceMatch>, String> {
+    if find.is_empty() {
+        return Err("Search text must not be empty".to_string());
+    }
+
+    let matcher = if regex {
+        Matcher::Pattern(Regex::new(find).map_err(|e| format!("Invalid regex: {}", e))?)
+    } else {
+        Matcher::Literal(find.to_string())
+    };
+
+    let mut matches = Vec::new();
+
+    for node in &mut project.nodes {
+        for field in fields {
+            let values: Vec<&mut String> = match field {
+                ReplaceField::Name => vec![&mut node.name],
+                ReplaceField::FilePath => vec![&mut node.file_path],
+                ReplaceField::Description => vec![&mut node.description],
+                ReplaceField::Purpose => vec![&mut node.purpose],
+                ReplaceField::Constraints => node.llm_config.constraints.iter_mut().collect(),
+            };
+
+            for value in values {
+                if let Some(after) = matcher.apply(value, replacement) {
+                    matches.push(ReplaceMatch {
+                        node_id: node.id.clone(),
+                        node_name: String::new(),
+                        field: *field,
+                        before: value.clone(),
+                        after: after.clone(),
+                    });
+                    if !dry_run {
+                        *value = after;
+                    }
+                }
+            }
+        }
+    }
+
+    // Report nodes under their (possibly renamed) current name
+    for m in &mut matches {
+        if let Some(node) = project.find_node(&m.node_id) {
+            m.node_name = node.name.clone();
+        }
+    }
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, Language};
+
+    #[test]
+    fn test_replace_in_project() {
+        let mut project = Project::new(String::new());
+        let mut node = CodeNode::new(
+            "UserApi".to_string(),
+            "src/api/user.ts".to_string(),
+            Language::TypeScript,
+        );
+        node.llm_config.constraints = vec!["Only import from src/api".to_string()];
+        project.nodes.push(node);
+
+        // Dry run reports but doesn't change anything
+        let matches = replace_in_project(
+            &mut project,
+            "src/api",
+            "src/http",
+            false,
+            &ReplaceField::ALL,
+            true,
+        )
+        .unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(project.nodes[0].file_path, "src/api/user.ts");
+
+        replace_in_project(
+            &mut project,
+            r"^src/(\w+)/",
+            "lib/$1/",
+            true,
+            &[ReplaceField::FilePath],
+            false,
+        )
+        .unwrap();
+        assert_eq!(project.nodes[0].file_path, "lib/api/user.ts");
+        assert_eq!(project.nodes[0].llm_config.constraints[0], "Only import from src/api");
+    }
+}
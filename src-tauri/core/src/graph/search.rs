@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::llm::embeddings::embed;
+
+use super::drift::content_hash;
+use super::model::{CodeNode, EmbeddingConfig, Project};
+use super::query::NodeFilter;
+
+const INDEX_FILE: &str = ".needlepoint/index/embeddings.json";
+/// Characters of generated code embedded per node, to stay within embedding input limits
+const MAX_CODE_CHARS: usize = 6000;
+/// Nodes embedded per request
+const EMBED_BATCH_SIZE: usize = 64;
+/// Results returned when the request doesn't set a limit
+pub const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+/// How `POST /search` matches nodes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Case-insensitive substring match
+    #[default]
+    Text,
+    /// Similarity of embeddings, so "password reset" finds `RecoveryTokenService`
+    Semantic,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub node_id: String,
+    pub node_name: String,
+    pub file_path: String,
+    /// Higher is better: cosine similarity in semantic mode, 1 for spec matches
+    /// and 0.5 for matches only in generated code in text mode
+    pub score: f32,
+}
+
+/// Embeddings of the project's nodes, stored under `.needlepoint/index/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndex {
+    /// Model the vectors came from; an index built with another model is discarded
+    pub embedding: EmbeddingConfig,
+    /// Entries by node ID
+    pub entries: HashMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    /// Hash of the text that was embedded, to find nodes that changed since
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// The text embedded for a node: its spec followed by the start of its code
+fn node_text(node: &CodeNode) -> String {
+    let mut text = format!("{}\n{}\n", node.name, node.file_path);
+    for field in [&node.purpose, &node.description] {
+        if !field.is_empty() {
+            text.push_str(field);
+            text.push('\n');
+        }
+    }
+    if let Some(code) = &node.generated_code {
+        let end = code.char_indices().nth(MAX_CODE_CHARS).map_or(code.len(), |(i, _)| i);
+        text.push_str(&code[..end]);
+    }
+    text
+}
+
+fn index_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(INDEX_FILE)
+}
+
+/// The stored index, if one was built
+pub fn load_index(project_path: &str) -> Result<Option<SearchIndex>> {
+    let path = index_path(project_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read search index: {:?}", path))?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .with_context(|| format!("Failed to parse search index: {:?}", path))
+}
+
+pub fn save_index(project_path: &str, index: &SearchIndex) -> Result<()> {
+    let path = index_path(project_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create index directory: {:?}", dir))?;
+    }
+    let contents = serde_json::to_string(index).context("Failed to serialize search index")?;
+    fs::write(&path, contents)
+        .with_context(|| format!("Failed to write search index: {:?}", path))
+}
+
+/// Bring the stored index up to date: embed nodes that are new or changed since they
+/// were indexed and drop removed ones. Switching embedding models rebuilds it.
+pub async fn update_index(project: &Project, api_key: Option<&str>) -> Result<SearchIndex, String> {
+    let config = EmbeddingConfig::for_manifest(&project.manifest);
+    let mut index = match load_index(&project.project_path) {
+        Ok(Some(index)) if index.embedding == config => index,
+        Ok(_) => SearchIndex {
+            embedding: config.clone(),
+            entries: HashMap::new(),
+        },
+        Err(e) => {
+            tracing::warn!("Rebuilding search index: {}", e);
+            SearchIndex {
+                embedding: config.clone(),
+                entries: HashMap::new(),
+            }
+        }
+    };
+
+    let indexed = index.entries.len();
+    index.entries.retain(|id, _| project.find_node(id).is_some());
+    let mut changed = index.entries.len() != indexed;
+
+    let pending: Vec<(&str, String, String)> = project
+        .nodes
+        .iter()
+        .map(|node| {
+            let text = node_text(node);
+            let hash = content_hash(&text);
+            (node.id.as_str(), text, hash)
+        })
+        .filter(|(id, _, hash)| index.entries.get(*id).map_or(true, |e| e.hash != *hash))
+        .collect();
+
+    for batch in pending.chunks(EMBED_BATCH_SIZE) {
+        let inputs: Vec<String> = batch.iter().map(|(_, text, _)| text.clone()).collect();
+        let vectors = embed(&config, api_key, &project.manifest, &inputs)
+            .await
+            .map_err(|e| e.to_string())?;
+        for ((id, _, hash), vector) in batch.iter().zip(vectors) {
+            index.entries.insert(
+                id.to_string(),
+                IndexEntry {
+                    hash: hash.clone(),
+                    vector,
+                },
+            );
+        }
+        changed = true;
+    }
+
+    if changed {
+        save_index(&project.project_path, &index).map_err(|e| e.to_string())?;
+    }
+    Ok(index)
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+fn hit(node: &CodeNode, score: f32) -> SearchHit {
+    SearchHit {
+        node_id: node.id.clone(),
+        node_name: node.name.clone(),
+        file_path: node.file_path.clone(),
+        score,
+    }
+}
+
+/// The indexed nodes most similar to the query's embedding, best first
+pub fn rank(project: &Project, index: &SearchIndex, query: &[f32], limit: usize) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = project
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let entry = index.entries.get(&node.id)?;
+            Some(hit(node, cosine_similarity(query, &entry.vector)))
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+/// The `k` nodes with declared exports most similar to `node_id`, leaving out the
+/// node itself and its direct dependencies (which are already in its prompt)
+pub fn related_nodes<'a>(
+    project: &'a Project,
+    index: &SearchIndex,
+    node_id: &str,
+    k: usize,
+) -> Vec<&'a CodeNode> {
+    let Some(entry) = index.entries.get(node_id) else {
+        return Vec::new();
+    };
+    let dependencies: Vec<&str> = project
+        .get_dependencies(node_id)
+        .iter()
+        .map(|e| e.source.as_str())
+        .collect();
+
+    let mut scored: Vec<(f32, &CodeNode)> = project
+        .nodes
+        .iter()
+        .filter(|n| {
+            n.id != node_id
+                && !dependencies.contains(&n.id.as_str())
+                && n.kind.has_file()
+                && !n.exports.is_empty()
+        })
+        .filter_map(|n| {
+            let other = index.entries.get(&n.id)?;
+            Some((cosine_similarity(&entry.vector, &other.vector), n))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(k).map(|(_, n)| n).collect()
+}
+
+/// Re-embed changed nodes before a generation run when prompts include related
+/// context. Failures are logged: prompts then use whatever the index already has.
+pub async fn refresh_for_related_context(project: &Project, api_key: Option<&str>) {
+    if project.manifest.related_context.unwrap_or(0) == 0 {
+        return;
+    }
+    if let Err(e) = update_index(project, api_key).await {
+        tracing::warn!("Failed to update the search index for related context: {}", e);
+    }
+}
+
+/// Update the index and rank the nodes by similarity to `query`
+pub async fn semantic_search(
+    project: &Project,
+    query: &str,
+    limit: usize,
+    api_key: Option<&str>,
+) -> Result<Vec<SearchHit>, String> {
+    let index = update_index(project, api_key).await?;
+    let vectors = embed(&index.embedding, api_key, &project.manifest, &[query.to_string()])
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rank(project, &index, &vectors[0], limit))
+}
+
+/// Nodes whose spec (name, path, description, purpose) or generated code contains
+/// `query`, spec matches first
+pub fn text_search(project: &Project, query: &str, limit: usize) -> Vec<SearchHit> {
+    let filter = NodeFilter {
+        q: Some(query.to_string()),
+        ..Default::default()
+    };
+    let needle = query.to_lowercase();
+    let mut hits: Vec<SearchHit> = project
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            if filter.matches(node) {
+                Some(hit(node, 1.0))
+            } else if node
+                .generated_code
+                .as_ref()
+                .is_some_and(|code| code.to_lowercase().contains(&needle))
+            {
+                Some(hit(node, 0.5))
+            } else {
+                None
+            }
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_search_ranking() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let mut reset = CodeNode::new(
+            "RecoveryService".to_string(),
+            "src/recovery.ts".to_string(),
+            Language::TypeScript,
+        );
+        reset.generated_code = Some("export function sendPasswordReset() {}".to_string());
+        let mut login =
+            CodeNode::new("Login".to_string(), "src/login.ts".to_string(), Language::TypeScript);
+        login.description = "Password login form".to_string();
+        let (reset_id, login_id) = (reset.id.clone(), login.id.clone());
+        project.nodes.extend([reset, login]);
+
+        let hits = text_search(&project, "password", 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].node_id.as_str(), hits[0].score), (login_id.as_str(), 1.0));
+        assert_eq!((hits[1].node_id.as_str(), hits[1].score), (reset_id.as_str(), 0.5));
+
+        let index = SearchIndex {
+            embedding: EmbeddingConfig::for_manifest(&project.manifest),
+            entries: HashMap::from([
+                (
+                    reset_id.clone(),
+                    IndexEntry {
+                        hash: content_hash(&node_text(&project.nodes[0])),
+                        vector: vec![0.9, 0.1],
+                    },
+                ),
+                (
+                    login_id.clone(),
+                    IndexEntry {
+                        hash: String::new(),
+                        vector: vec![0.1, 0.9],
+                    },
+                ),
+            ]),
+        };
+        let hits = rank(&project, &index, &[1.0, 0.0], 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].node_id, reset_id);
+
+        save_index(&project.project_path, &index).unwrap();
+        let loaded = load_index(&project.project_path).unwrap().unwrap();
+        assert_eq!(loaded.embedding.model, "text-embedding-3-small");
+        assert_eq!(loaded.entries.len(), 2);
+    }
+}
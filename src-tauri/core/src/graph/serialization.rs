@@ -4,21 +4,45 @@ use std::path::Path;
 use anyhow::{Context, Result};
 
 use super::model::Project;
+use super::workspace::{include_subprojects, without_subprojects};
 
-const PROJECT_FILE_NAME: &str = "needlepoint.yaml";
+pub const PROJECT_FILE_NAME: &str = "needlepoint.yaml";
 
-/// Load a project from a YAML file
-pub fn load_project_from_file(path: &Path) -> Result<Project> {
+/// Parse a project file as written, without adjusting it for loading
+pub fn read_project_file(path: &Path) -> Result<Project> {
     let contents = fs::read_to_string(path)
         .with_context(|| format!("Failed to read project file: {:?}", path))?;
 
-    let mut project: Project = serde_yaml::from_str(&contents)
-        .with_context(|| format!("Failed to parse project file: {:?}", path))?;
+    serde_yaml::from_str(&contents)
+        .with_context(|| format!("Failed to parse project file: {:?}", path))
+}
+
+/// Write a project as YAML to the given file, leaving out what it includes from
+/// sub-projects
+pub fn write_project_file(project: &Project, path: &Path) -> Result<()> {
+    let contents = serde_yaml::to_string(&without_subprojects(project))
+        .context("Failed to serialize project")?;
+
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write project file: {:?}", path))
+}
+
+/// Load a project from a YAML file
+pub fn load_project_from_file(path: &Path) -> Result<Project> {
+    let mut project = read_project_file(path)?;
 
     // Ensure project_path is set correctly
     if let Some(parent) = path.parent() {
         project.project_path = parent.to_string_lossy().to_string();
     }
+    project.apply_default_llm();
+    let reset = project.reset_interrupted_generations();
+    if !reset.is_empty() {
+        tracing::warn!("Reset {} nodes left generating by an interrupted run", reset.len());
+    }
+    if let Err(e) = include_subprojects(&mut project) {
+        tracing::warn!("Loading {:?} without its sub-projects: {:#}", path, e);
+    }
 
     Ok(project)
 }
@@ -26,14 +50,7 @@ pub fn load_project_from_file(path: &Path) -> Result<Project> {
 /// Save a project to a YAML file
 pub fn save_project_to_file(project: &Project) -> Result<()> {
     let project_file = Path::new(&project.project_path).join(PROJECT_FILE_NAME);
-
-    let contents = serde_yaml::to_string(project)
-        .context("Failed to serialize project")?;
-
-    fs::write(&project_file, contents)
-        .with_context(|| format!("Failed to write project file: {:?}", project_file))?;
-
-    Ok(())
+    write_project_file(project, &project_file)
 }
 
 /// Create a new project in the given directory
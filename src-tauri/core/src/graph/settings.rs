@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::budget::Budget;
+use super::model::{GenerationProfile, LLMProvider};
+use crate::orchestration::github::validate_repository;
+
+const SETTINGS_FILE: &str = ".needlepoint/settings.yaml";
+/// Keeps the settings out of version control
+const GITIGNORE_FILE: &str = ".needlepoint/.gitignore";
+
+/// Overrides for one checkout of a project, kept in `.needlepoint/settings.yaml`
+/// (git-ignored) rather than in the shared `needlepoint.yaml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSettings {
+    /// Generate with this provider instead of each node's own, e.g. a local Ollama
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<LLMProvider>,
+    /// Generate with this model instead of each node's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Nodes generated at once within a wave; unset generates the whole wave at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Stop starting nodes once a run's estimated cost reaches this (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost: Option<f64>,
+    /// Stop starting nodes once a run has used this many tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// Stop starting nodes once all runs of the project together have cost this (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_max_cost: Option<f64>,
+    /// Stop starting nodes once all runs of the project together have used this
+    /// many tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_max_tokens: Option<u64>,
+    /// Overwrite files edited by hand when writing generated files, instead of
+    /// merging the new generation into them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force_write: Option<bool>,
+    /// Repository (`owner/name`) that pull requests for runs are opened against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_repository: Option<String>,
+    /// Branch pull requests are opened against; unset uses the repository's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_base_branch: Option<String>,
+    /// Start of the branch names created for runs, followed by the run ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_branch_prefix: Option<String>,
+}
+
+impl ProjectSettings {
+    /// The preferred provider and model as a profile, for runs that don't name one
+    pub fn profile(&self) -> Option<GenerationProfile> {
+        if self.provider.is_none() && self.model.is_none() {
+            return None;
+        }
+        Some(GenerationProfile {
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Limits on a single run's spend
+    pub fn run_budget(&self) -> Budget {
+        Budget {
+            max_tokens: self.max_tokens,
+            max_cost: self.max_cost,
+        }
+    }
+
+    /// Limits on the spend of all runs of the project together
+    pub fn project_budget(&self) -> Budget {
+        Budget {
+            max_tokens: self.project_max_tokens,
+            max_cost: self.project_max_cost,
+        }
+    }
+
+    /// Reject values that can't be applied
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrency == Some(0) {
+            return Err("maxConcurrency must be at least 1".to_string());
+        }
+        if self.max_cost.is_some_and(|cost| cost.is_nan() || cost < 0.0) {
+            return Err("maxCost must be zero or more".to_string());
+        }
+        if self.project_max_cost.is_some_and(|cost| cost.is_nan() || cost < 0.0) {
+            return Err("projectMaxCost must be zero or more".to_string());
+        }
+        if self.model.as_ref().is_some_and(|model| model.trim().is_empty()) {
+            return Err("model must not be empty".to_string());
+        }
+        if self.provider == Some(LLMProvider::Local) && self.model.is_none() {
+            return Err(
+                "The local provider needs model set to the path of a GGUF file".to_string(),
+            );
+        }
+        if let Some(repository) = &self.github_repository {
+            validate_repository(repository)?;
+        }
+        Ok(())
+    }
+}
+
+fn settings_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(SETTINGS_FILE)
+}
+
+/// The project's local settings; defaults when there is no settings file
+pub fn load_settings(project_path: &str) -> Result<ProjectSettings> {
+    let path = settings_path(project_path);
+    if project_path.is_empty() || !path.exists() {
+        return Ok(ProjectSettings::default());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read project settings: {:?}", path))?;
+    serde_yaml::from_str(&contents)
+        .with_context(|| format!("Failed to parse project settings: {:?}", path))
+}
+
+/// The project's local settings, falling back to defaults (with a warning) when the
+/// settings file can't be read
+pub fn load_settings_or_default(project_path: &str) -> ProjectSettings {
+    load_settings(project_path).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring project settings: {}", e);
+        ProjectSettings::default()
+    })
+}
+
+/// Write the project's local settings, git-ignoring the file the first time
+pub fn save_settings(project_path: &str, settings: &ProjectSettings) -> Result<()> {
+    let path = settings_path(project_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create settings directory: {:?}", dir))?;
+    }
+
+    let gitignore = Path::new(project_path).join(GITIGNORE_FILE);
+    let ignored = fs::read_to_string(&gitignore).unwrap_or_default();
+    if !ignored.lines().any(|line| line.trim() == "settings.yaml") {
+        let mut contents = ignored;
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push_str("settings.yaml\n");
+        fs::write(&gitignore, contents)
+            .with_context(|| format!("Failed to write {:?}", gitignore))?;
+    }
+
+    let contents = serde_yaml::to_string(settings).context("Failed to serialize settings")?;
+    fs::write(&path, contents)
+        .with_context(|| format!("Failed to write project settings: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        assert_eq!(load_settings(&project_path).unwrap(), ProjectSettings::default());
+        assert!(ProjectSettings::default().profile().is_none());
+
+        let settings = ProjectSettings {
+            provider: Some(LLMProvider::Ollama),
+            max_concurrency: Some(2),
+            ..Default::default()
+        };
+        settings.validate().unwrap();
+        save_settings(&project_path, &settings).unwrap();
+        save_settings(&project_path, &settings).unwrap();
+        assert_eq!(load_settings(&project_path).unwrap(), settings);
+        assert_eq!(settings.profile().unwrap().provider, Some(LLMProvider::Ollama));
+
+        let gitignore = fs::read_to_string(dir.path().join(GITIGNORE_FILE)).unwrap();
+        assert_eq!(gitignore, "settings.yaml\n");
+
+        let invalid = ProjectSettings {
+            max_concurrency: Some(0),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+        let invalid = ProjectSettings {
+            github_repository: Some("shop".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+        let invalid = ProjectSettings {
+            provider: Some(LLMProvider::Local),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let budgeted = ProjectSettings {
+            max_tokens: Some(1000),
+            project_max_cost: Some(5.0),
+            ..Default::default()
+        };
+        assert_eq!(budgeted.run_budget().max_tokens, Some(1000));
+        assert_eq!(budgeted.project_budget().max_cost, Some(5.0));
+        assert!(ProjectSettings::default().run_budget().is_unlimited());
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::llm::{estimate_cost, ContextBuilder};
+
+use super::layout::{adjacency, assign_layers};
+use super::model::{Language, NodeStatus, Project};
+
+/// Rough characters-per-token ratio used for estimates
+const CHARS_PER_TOKEN: usize = 4;
+/// Output assumed for nodes that have never been generated
+const DEFAULT_OUTPUT_TOKENS: u64 = 1500;
+
+/// Size and shape of the graph
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub nodes_by_status: HashMap<NodeStatus, usize>,
+    pub nodes_by_language: HashMap<Language, usize>,
+    pub nodes_by_tag: HashMap<String, usize>,
+    /// Length of the longest dependency chain, in edges
+    pub max_depth: usize,
+    /// Average number of dependencies of nodes that have any
+    pub avg_fan_in: f64,
+    /// Average number of dependents of nodes that have any
+    pub avg_fan_out: f64,
+    /// Nodes with no edges at all
+    pub orphan_count: usize,
+    /// Non-blank lines of generated code
+    pub generated_loc: usize,
+    /// Prompt plus output tokens needed to regenerate every generatable node
+    pub estimated_tokens: u64,
+    /// Cost of those tokens (USD) at each node's model's prices; models without
+    /// known prices count as free
+    pub estimated_cost: f64,
+}
+
+fn average(counts: impl Iterator<Item = usize>) -> f64 {
+    let counts: Vec<usize> = counts.filter(|&c| c > 0).collect();
+    if counts.is_empty() {
+        0.0
+    } else {
+        counts.iter().sum::<usize>() as f64 / counts.len() as f64
+    }
+}
+
+/// Estimated input and output tokens for one generation of a node: the prompt it
+/// would be sent now, and its last output (or a default when it has none)
+pub fn estimate_node_tokens(project: &Project, node_id: &str) -> (u64, u64) {
+    let Some(node) = project.find_node(node_id) else {
+        return (0, 0);
+    };
+    let prompt_chars = ContextBuilder::build_prompt(project, node_id).map_or(0, |p| p.len())
+        + ContextBuilder::build_system_prompt(&project.manifest, node).len();
+    let output = node
+        .last_generation
+        .as_ref()
+        .and_then(|g| g.tokens_out)
+        .map(u64::from)
+        .or_else(|| {
+            node.generated_code
+                .as_ref()
+                .map(|code| (code.len() / CHARS_PER_TOKEN) as u64)
+        })
+        .unwrap_or(DEFAULT_OUTPUT_TOKENS);
+    ((prompt_chars / CHARS_PER_TOKEN) as u64, output)
+}
+
+/// Compute statistics for the project graph
+pub fn project_stats(project: &Project) -> ProjectStats {
+    slice_stats(project, project)
+}
+
+/// Compute statistics for the nodes carrying the tag and the edges between them.
+/// Token estimates still use the whole project, since a node's prompt includes
+/// its dependencies whether or not they carry the tag.
+pub fn tag_stats(project: &Project, tag: &str) -> ProjectStats {
+    let mut slice = project.clone();
+    slice.nodes.retain(|n| n.has_tag(tag));
+    let ids: HashSet<&str> = slice.nodes.iter().map(|n| n.id.as_str()).collect();
+    let edges = project
+        .edges
+        .iter()
+        .filter(|e| ids.contains(e.source.as_str()) && ids.contains(e.target.as_str()))
+        .cloned()
+        .collect();
+    slice.edges = edges;
+    slice_stats(project, &slice)
+}
+
+fn slice_stats(project: &Project, slice: &Project) -> ProjectStats {
+    let (preds, succs) = adjacency(slice);
+    let layers = assign_layers(&preds, &succs);
+
+    let mut nodes_by_status = HashMap::new();
+    let mut nodes_by_language = HashMap::new();
+    let mut nodes_by_tag = HashMap::new();
+    for node in &slice.nodes {
+        *nodes_by_status.entry(node.status.clone()).or_insert(0) += 1;
+        *nodes_by_language.entry(node.language.clone()).or_insert(0) += 1;
+        for tag in &node.tags {
+            *nodes_by_tag.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let generated_loc = slice
+        .nodes
+        .iter()
+        .filter_map(|n| n.generated_code.as_ref())
+        .map(|code| code.lines().filter(|l| !l.trim().is_empty()).count())
+        .sum();
+
+    let mut estimated_tokens = 0;
+    let mut estimated_cost = 0.0;
+    for node in slice
+        .nodes
+        .iter()
+        .filter(|n| !n.disabled && !n.pinned && n.kind.has_file())
+    {
+        let (input, output) = estimate_node_tokens(project, &node.id);
+        let config = node.llm_config.resolve(&project.manifest.default_llm);
+        estimated_tokens += input + output;
+        estimated_cost +=
+            estimate_cost(&config.provider, &config.model, input as u32, output as u32)
+                .unwrap_or(0.0);
+    }
+
+    ProjectStats {
+        node_count: slice.nodes.len(),
+        edge_count: slice.edges.len(),
+        nodes_by_status,
+        nodes_by_language,
+        nodes_by_tag,
+        max_depth: layers.iter().copied().max().unwrap_or(0),
+        avg_fan_in: average(preds.iter().map(Vec::len)),
+        avg_fan_out: average(succs.iter().map(Vec::len)),
+        orphan_count: (0..slice.nodes.len())
+            .filter(|&i| preds[i].is_empty() && succs[i].is_empty())
+            .count(),
+        generated_loc,
+        estimated_tokens,
+        estimated_cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode};
+
+    #[test]
+    fn test_project_stats() {
+        let mut project = Project::new(String::new());
+        let mut a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        a.status = NodeStatus::Complete;
+        a.tags = vec!["backend".to_string()];
+        a.generated_code = Some("export const a = 1;\n\nexport const b = 2;\n".to_string());
+        let b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let mut c = CodeNode::new("C".to_string(), "c.py".to_string(), Language::Python);
+        c.tags = vec!["backend".to_string()];
+        let d = CodeNode::new("D".to_string(), "d.py".to_string(), Language::Python);
+        let (a_id, b_id, c_id) = (a.id.clone(), b.id.clone(), c.id.clone());
+        project.nodes.extend([a, b, c, d]);
+        project.edges.push(CodeEdge::new(a_id.clone(), b_id.clone(), String::new()));
+        project.edges.push(CodeEdge::new(b_id, c_id.clone(), String::new()));
+        project.edges.push(CodeEdge::new(a_id, c_id, String::new()));
+
+        let stats = project_stats(&project);
+        assert_eq!(stats.node_count, 4);
+        assert_eq!(stats.edge_count, 3);
+        assert_eq!(stats.nodes_by_status[&NodeStatus::Complete], 1);
+        assert_eq!(stats.nodes_by_status[&NodeStatus::Pending], 3);
+        assert_eq!(stats.nodes_by_language[&Language::Python], 2);
+        assert_eq!(stats.max_depth, 2);
+        // B and C have 1 and 2 dependencies; A and B have 2 and 1 dependents
+        assert_eq!(stats.avg_fan_in, 1.5);
+        assert_eq!(stats.avg_fan_out, 1.5);
+        assert_eq!(stats.orphan_count, 1);
+        assert_eq!(stats.generated_loc, 2);
+        assert!(stats.estimated_tokens > 3 * DEFAULT_OUTPUT_TOKENS);
+        // Every node uses the default Sonnet model, which has known prices
+        assert!(stats.estimated_cost > 0.0);
+        assert_eq!(stats.nodes_by_tag["backend"], 2);
+
+        let backend = tag_stats(&project, "backend");
+        assert_eq!(backend.node_count, 2);
+        assert_eq!(backend.edge_count, 1);
+        assert_eq!(backend.orphan_count, 0);
+    }
+}
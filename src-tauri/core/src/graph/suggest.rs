@@ -0,0 +1,252 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::imports::{import_resolves_to, parse_imports, Import};
+use super::model::{CodeNode, NodeKind, Project};
+use super::validation::would_create_cycle;
+
+/// Node names shorter than this are too likely to be ordinary words to count as mentions
+const MIN_MENTIONED_NAME_LEN: usize = 3;
+
+/// Export names shorter than this are too generic to point at one node
+const MIN_EXPORT_NAME_LEN: usize = 4;
+
+/// How sure each kind of evidence makes an edge
+const IMPORT_CONFIDENCE: f64 = 0.95;
+const PATH_MENTION_CONFIDENCE: f64 = 0.7;
+const CODE_EXPORT_CONFIDENCE: f64 = 0.6;
+const NAME_MENTION_CONFIDENCE: f64 = 0.5;
+const SPEC_EXPORT_CONFIDENCE: f64 = 0.4;
+
+/// How a node's spec refers to another node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mention {
+    /// By file path, or by file name
+    Path,
+    /// By node name, as a whole word
+    Name,
+}
+
+/// How `text` refers to `node`, if it does
+pub fn mention(text: &str, node: &CodeNode) -> Option<Mention> {
+    mention_with(text, node, name_pattern(node).as_ref())
+}
+
+/// `mention` with the node's name pattern already compiled
+fn mention_with(text: &str, node: &CodeNode, name: Option<&Regex>) -> Option<Mention> {
+    let file_name = node.file_path.rsplit('/').next().unwrap_or(&node.file_path);
+    let names_file = file_name.contains('.') && text.contains(file_name);
+    if text.contains(node.file_path.as_str()) || names_file {
+        return Some(Mention::Path);
+    }
+    name.is_some_and(|re| re.is_match(text)).then_some(Mention::Name)
+}
+
+fn word_pattern(word: &str) -> Option<Regex> {
+    Regex::new(&format!(r"\b{}\b", regex::escape(word))).ok()
+}
+
+/// The node's name as a whole word, unless it's too short to count
+fn name_pattern(node: &CodeNode) -> Option<Regex> {
+    (node.name.chars().count() >= MIN_MENTIONED_NAME_LEN).then(|| word_pattern(&node.name))?
+}
+
+/// A node's name and export names as whole-word patterns, compiled once per node
+/// rather than for every pair
+struct Words<'a> {
+    name: Option<Regex>,
+    exports: Vec<(&'a str, Regex)>,
+}
+
+impl<'a> Words<'a> {
+    fn new(node: &'a CodeNode) -> Self {
+        let exports = node
+            .exports
+            .iter()
+            .map(|e| e.name.as_str())
+            .filter(|name| name.chars().count() >= MIN_EXPORT_NAME_LEN)
+            .filter_map(|name| Some((name, word_pattern(name)?)))
+            .collect();
+        Self {
+            name: name_pattern(node),
+            exports,
+        }
+    }
+}
+
+/// An edge that looks missing, from what the dependent's spec or code refers to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeSuggestion {
+    /// The dependency
+    pub source: String,
+    /// The node that refers to it
+    pub target: String,
+    pub label: String,
+    /// From 0 to 1, combining every piece of evidence
+    pub confidence: f64,
+    /// What suggested the edge, strongest first
+    pub reasons: Vec<String>,
+}
+
+/// Edges the project seems to be missing: pairs of nodes with no edge either way where
+/// one's generated code imports the other's file, or one's description, purpose or
+/// code names the other's file, name or exports. Edges that would create a cycle are
+/// left out. Most confident first.
+pub fn suggest_edges(project: &Project) -> Vec<EdgeSuggestion> {
+    let words: Vec<Words> = project.nodes.iter().map(Words::new).collect();
+    let mut suggestions = Vec::new();
+    for node in &project.nodes {
+        if node.disabled || node.kind == NodeKind::External {
+            continue;
+        }
+        let referrer = Referrer::new(node);
+        for (dependency, words) in project.nodes.iter().zip(&words) {
+            let unrelated = dependency.id == node.id || dependency.disabled;
+            if unrelated || connected(project, node, dependency) {
+                continue;
+            }
+            let evidence = evidence(&referrer, dependency, words);
+            if evidence.is_empty() || would_create_cycle(project, &dependency.id, &node.id) {
+                continue;
+            }
+            let unlikely: f64 = evidence.iter().map(|(c, _)| 1.0 - c).product();
+            suggestions.push(EdgeSuggestion {
+                source: dependency.id.clone(),
+                target: node.id.clone(),
+                label: "depends on".to_string(),
+                confidence: 1.0 - unlikely,
+                reasons: evidence.into_iter().map(|(_, reason)| reason).collect(),
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    suggestions
+}
+
+fn connected(project: &Project, a: &CodeNode, b: &CodeNode) -> bool {
+    project.edges.iter().any(|e| {
+        (e.source == a.id && e.target == b.id) || (e.source == b.id && e.target == a.id)
+    })
+}
+
+/// What a node's spec and code refer to, worked out once per node
+struct Referrer<'a> {
+    node: &'a CodeNode,
+    code: &'a str,
+    imports: Vec<Import>,
+    spec: String,
+}
+
+impl<'a> Referrer<'a> {
+    fn new(node: &'a CodeNode) -> Self {
+        let code = node.generated_code.as_deref().unwrap_or_default();
+        Self {
+            node,
+            code,
+            imports: parse_imports(&node.language, code),
+            spec: format!("{}\n{}", node.description, node.purpose),
+        }
+    }
+}
+
+/// What in the referrer points at `dependency`, with how sure each makes the edge
+fn evidence(referrer: &Referrer, dependency: &CodeNode, words: &Words) -> Vec<(f64, String)> {
+    let mut evidence = Vec::new();
+    let (node, code, spec) = (referrer.node, referrer.code, referrer.spec.as_str());
+
+    let import = referrer.imports.iter().find(|import| {
+        match &dependency.package {
+            Some(package) if dependency.kind == NodeKind::External => {
+                import.module == package.name
+                    || import.module.starts_with(&format!("{}/", package.name))
+            }
+            _ => import_resolves_to(import, node, dependency),
+        }
+    });
+    if let Some(import) = import {
+        let reason = format!("The code imports '{}' on line {}", import.module, import.line);
+        evidence.push((IMPORT_CONFIDENCE, reason));
+    }
+
+    match mention_with(spec, dependency, words.name.as_ref()) {
+        Some(Mention::Path) => evidence.push((
+            PATH_MENTION_CONFIDENCE,
+            format!("The spec mentions {}", dependency.file_path),
+        )),
+        Some(Mention::Name) => evidence.push((
+            NAME_MENTION_CONFIDENCE,
+            format!("The spec mentions {}", dependency.name),
+        )),
+        None => {}
+    }
+
+    for (name, pattern) in &words.exports {
+        if import.is_none() && pattern.is_match(code) {
+            evidence.push((CODE_EXPORT_CONFIDENCE, format!("The code uses {}", name)));
+        } else if pattern.is_match(spec) {
+            evidence.push((SPEC_EXPORT_CONFIDENCE, format!("The spec mentions {}", name)));
+        } else {
+            continue;
+        }
+        // One export is enough to make the point
+        break;
+    }
+
+    evidence.sort_by(|a, b| b.0.total_cmp(&a.0));
+    evidence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, ExportSignature, Language};
+
+    fn node(name: &str, path: &str, description: &str) -> CodeNode {
+        let mut node = CodeNode::new(name.to_string(), path.to_string(), Language::TypeScript);
+        node.description = description.to_string();
+        node
+    }
+
+    #[test]
+    fn test_suggest_edges() {
+        let mut project = Project::new(String::new());
+        let mut db = node("Database", "src/db.ts", "Connection pool");
+        db.exports.push(ExportSignature {
+            name: "queryUsers".to_string(),
+            type_signature: "() => Promise<User[]>".to_string(),
+            description: String::new(),
+            shared_type: None,
+        });
+        let types = node("Types", "src/types.ts", "Shared types");
+        let mut api = node("Api", "src/api.ts", "Handlers using the Database");
+        api.generated_code = Some("import { User } from './types';\n".to_string());
+        let mut report = node("Report", "src/report.ts", "Summarizes the output of queryUsers");
+        report.generated_code = Some("export const report = queryUsers();\n".to_string());
+        // The report already depends on the admin through the CLI, so it can't also be
+        // the admin's dependency
+        let admin = node("Admin", "src/admin.ts", "Wraps src/report.ts");
+        let cli = node("Cli", "src/cli.ts", "");
+        project.edges.push(CodeEdge::new(admin.id.clone(), cli.id.clone(), String::new()));
+        project.edges.push(CodeEdge::new(cli.id.clone(), report.id.clone(), String::new()));
+        let ids: Vec<String> =
+            [&db, &types, &api, &report].iter().map(|n| n.id.clone()).collect();
+        project.nodes.extend([db, types, api, report, admin, cli]);
+
+        let suggestions = suggest_edges(&project);
+        let pairs: Vec<(&str, &str)> =
+            suggestions.iter().map(|s| (s.source.as_str(), s.target.as_str())).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (ids[1].as_str(), ids[2].as_str()),
+                (ids[0].as_str(), ids[3].as_str()),
+                (ids[0].as_str(), ids[2].as_str()),
+            ]
+        );
+        assert_eq!(suggestions[0].confidence, IMPORT_CONFIDENCE);
+        assert_eq!(suggestions[0].reasons, vec!["The code imports './types' on line 1"]);
+        assert_eq!(suggestions[1].reasons, vec!["The code uses queryUsers"]);
+        assert_eq!(suggestions[2].confidence, NAME_MENTION_CONFIDENCE);
+    }
+}
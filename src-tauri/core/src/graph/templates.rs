@@ -0,0 +1,494 @@
+use serde::Serialize;
+
+use super::layout::auto_layout;
+use super::model::{CodeEdge, ExportSignature, Language, NodeKind, Project};
+
+/// A prebuilt graph for a common architecture, to start a project from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    #[serde(skip)]
+    build: fn(&mut TemplateBuilder),
+}
+
+/// Every built-in template
+pub const TEMPLATES: &[ProjectTemplate] = &[
+    ProjectTemplate {
+        id: "rest-api-ts",
+        name: "REST API (TypeScript)",
+        description: "Express server with config, database access, a repository, service \
+                      and routes for one resource, and error handling",
+        build: rest_api_ts,
+    },
+    ProjectTemplate {
+        id: "cli-rust",
+        name: "CLI tool (Rust)",
+        description: "Command-line tool with argument parsing, config loading, commands \
+                      and a shared error type",
+        build: cli_rust,
+    },
+    ProjectTemplate {
+        id: "react-app",
+        name: "React app (TypeScript)",
+        description: "Vite React app with an API client, a data hook, list and form \
+                      components for one resource, and the app shell",
+        build: react_app,
+    },
+];
+
+/// Add a template's nodes and edges to a project that has none yet, with the
+/// resource the template is built around in the `ENTITY` variable
+pub fn apply_template(project: &mut Project, id: &str) -> Result<(), String> {
+    let template = TEMPLATES.iter().find(|t| t.id == id).ok_or_else(|| {
+        let ids: Vec<&str> = TEMPLATES.iter().map(|t| t.id).collect();
+        format!("Unknown template '{}'; available: {}", id, ids.join(", "))
+    })?;
+    if !project.nodes.is_empty() {
+        return Err("Templates can only be applied to an empty project".to_string());
+    }
+
+    let mut builder = TemplateBuilder { project };
+    (template.build)(&mut builder);
+    project
+        .manifest
+        .variables
+        .entry("ENTITY".to_string())
+        .or_insert_with(|| "Item".to_string());
+    auto_layout(project);
+    Ok(())
+}
+
+/// Adds nodes and edges to the project a template is applied to
+struct TemplateBuilder<'a> {
+    project: &'a mut Project,
+}
+
+impl TemplateBuilder<'_> {
+    /// Add a node; `exports` are (name, type, description)
+    fn node(
+        &mut self,
+        name: &str,
+        file_path: &str,
+        language: Language,
+        kind: NodeKind,
+        purpose: &str,
+        exports: &[(&str, &str, &str)],
+    ) {
+        let mut node = self
+            .project
+            .new_node(name.to_string(), file_path.to_string(), language);
+        node.kind = kind;
+        node.purpose = purpose.to_string();
+        node.description = purpose.to_string();
+        node.exports = exports
+            .iter()
+            .map(|(name, type_signature, description)| ExportSignature {
+                name: name.to_string(),
+                type_signature: type_signature.to_string(),
+                description: description.to_string(),
+                shared_type: None,
+            })
+            .collect();
+        self.project.nodes.push(node);
+    }
+
+    /// Connect two nodes added earlier, by name
+    fn edge(&mut self, source: &str, target: &str) {
+        let id_of = |name: &str| {
+            self.project
+                .nodes
+                .iter()
+                .find(|n| n.name == name)
+                .map(|n| n.id.clone())
+                .expect("template edges connect template nodes")
+        };
+        let edge = CodeEdge::new(id_of(source), id_of(target), String::new());
+        self.project.edges.push(edge);
+    }
+
+    fn edges(&mut self, edges: &[(&str, &str)]) {
+        for (source, target) in edges {
+            self.edge(source, target);
+        }
+    }
+}
+
+fn json() -> Language {
+    Language::Custom("json".to_string())
+}
+
+fn markdown() -> Language {
+    Language::Custom("markdown".to_string())
+}
+
+fn rest_api_ts(b: &mut TemplateBuilder) {
+    use Language::TypeScript as Ts;
+    use NodeKind::{Code, Config, Doc};
+
+    b.node(
+        "Package",
+        "package.json",
+        json(),
+        Config,
+        "npm package manifest with express, zod and the TypeScript toolchain",
+        &[],
+    );
+    b.node(
+        "Types",
+        "src/types.ts",
+        Ts,
+        Code,
+        "Domain types for ${ENTITY} resources and the request/response shapes of the API",
+        &[
+            (
+                "${ENTITY}",
+                "interface",
+                "A stored ${ENTITY} with its id and timestamps",
+            ),
+            (
+                "Create${ENTITY}Input",
+                "interface",
+                "Fields accepted when creating a ${ENTITY}",
+            ),
+        ],
+    );
+    b.node(
+        "Config",
+        "src/config.ts",
+        Ts,
+        Code,
+        "Load and validate configuration (port, database URL) from environment variables",
+        &[("config", "const", "The validated configuration")],
+    );
+    b.node(
+        "Errors",
+        "src/errors.ts",
+        Ts,
+        Code,
+        "HTTP error classes and an express error-handling middleware that renders them as JSON",
+        &[
+            (
+                "HttpError",
+                "class",
+                "An error carrying an HTTP status code",
+            ),
+            ("NotFoundError", "class", "404 error for missing resources"),
+            (
+                "errorHandler",
+                "ErrorRequestHandler",
+                "Express middleware rendering errors",
+            ),
+        ],
+    );
+    b.node(
+        "Database",
+        "src/db.ts",
+        Ts,
+        Code,
+        "Database connection pool created from the config, with a query helper",
+        &[("db", "Pool", "The shared connection pool")],
+    );
+    b.node(
+        "Repository",
+        "src/repository.ts",
+        Ts,
+        Code,
+        "Persistence for ${ENTITY} records: find, list, create, update and delete",
+        &[(
+            "${ENTITY}Repository",
+            "class",
+            "Data access for ${ENTITY} records",
+        )],
+    );
+    b.node(
+        "Service",
+        "src/service.ts",
+        Ts,
+        Code,
+        "Business rules for ${ENTITY}s on top of the repository, throwing HTTP errors",
+        &[(
+            "${ENTITY}Service",
+            "class",
+            "Validates input and coordinates the repository",
+        )],
+    );
+    b.node(
+        "Routes",
+        "src/routes.ts",
+        Ts,
+        Code,
+        "Express router with CRUD endpoints for ${ENTITY}s, validating bodies with zod",
+        &[(
+            "create${ENTITY}Router",
+            "(service) => Router",
+            "Build the resource's router",
+        )],
+    );
+    b.node(
+        "Server",
+        "src/server.ts",
+        Ts,
+        Code,
+        "Entry point: wire the repository, service and router into an express app and listen",
+        &[],
+    );
+    b.node(
+        "Readme",
+        "README.md",
+        markdown(),
+        Doc,
+        "Setup, configuration and the API's endpoints",
+        &[],
+    );
+    b.edges(&[
+        ("Types", "Repository"),
+        ("Types", "Service"),
+        ("Types", "Routes"),
+        ("Config", "Database"),
+        ("Config", "Server"),
+        ("Database", "Repository"),
+        ("Errors", "Service"),
+        ("Errors", "Server"),
+        ("Repository", "Service"),
+        ("Service", "Routes"),
+        ("Routes", "Server"),
+        ("Package", "Server"),
+        ("Routes", "Readme"),
+        ("Config", "Readme"),
+    ]);
+    b.project.manifest.entry_point = Some("src/server.ts".to_string());
+}
+
+fn cli_rust(b: &mut TemplateBuilder) {
+    use Language::Rust;
+    use NodeKind::{Code, Config, Doc};
+
+    b.node(
+        "Cargo",
+        "Cargo.toml",
+        Language::Custom("toml".to_string()),
+        Config,
+        "Cargo manifest with clap (derive), serde, toml and anyhow",
+        &[],
+    );
+    b.node(
+        "Error",
+        "src/error.rs",
+        Rust,
+        Code,
+        "The tool's error type, with a variant per failure and user-facing messages",
+        &[
+            ("Error", "enum", "Everything that can go wrong"),
+            ("Result", "type", "Result<T, Error>"),
+        ],
+    );
+    b.node(
+        "Config",
+        "src/config.rs",
+        Rust,
+        Code,
+        "Load the tool's TOML config from the user's config directory, with defaults",
+        &[
+            ("Config", "struct", "The tool's settings"),
+            ("load", "fn() -> Result<Config>", ""),
+        ],
+    );
+    b.node(
+        "Cli",
+        "src/cli.rs",
+        Rust,
+        Code,
+        "Command-line arguments and subcommands, parsed with clap's derive API",
+        &[
+            ("Cli", "struct", "Parsed arguments"),
+            ("Command", "enum", "The subcommands"),
+        ],
+    );
+    b.node(
+        "Commands",
+        "src/commands.rs",
+        Rust,
+        Code,
+        "One function per subcommand, working on ${ENTITY}s",
+        &[(
+            "run",
+            "fn(Command, &Config) -> Result<()>",
+            "Dispatch a parsed command",
+        )],
+    );
+    b.node(
+        "Main",
+        "src/main.rs",
+        Rust,
+        Code,
+        "Entry point: parse arguments, load the config, run the command and report errors",
+        &[],
+    );
+    b.node(
+        "Readme",
+        "README.md",
+        markdown(),
+        Doc,
+        "Installation and usage of each command",
+        &[],
+    );
+    b.edges(&[
+        ("Error", "Config"),
+        ("Error", "Commands"),
+        ("Error", "Main"),
+        ("Config", "Commands"),
+        ("Config", "Main"),
+        ("Cli", "Commands"),
+        ("Cli", "Main"),
+        ("Commands", "Main"),
+        ("Cargo", "Main"),
+        ("Cli", "Readme"),
+    ]);
+    b.project.manifest.entry_point = Some("src/main.rs".to_string());
+}
+
+fn react_app(b: &mut TemplateBuilder) {
+    use Language::TypeScript as Ts;
+    use NodeKind::{Code, Config, Doc};
+
+    b.node(
+        "Package",
+        "package.json",
+        json(),
+        Config,
+        "npm package manifest for a Vite + React + TypeScript app",
+        &[],
+    );
+    b.node(
+        "Types",
+        "src/types.ts",
+        Ts,
+        Code,
+        "The ${ENTITY} type as returned by the backend, and the form's input type",
+        &[
+            ("${ENTITY}", "interface", "A ${ENTITY} from the API"),
+            ("${ENTITY}Input", "interface", "Fields the form edits"),
+        ],
+    );
+    b.node(
+        "ApiClient",
+        "src/api.ts",
+        Ts,
+        Code,
+        "Typed fetch wrappers for the ${ENTITY} endpoints, throwing on error responses",
+        &[
+            ("list${ENTITY}s", "() => Promise<${ENTITY}[]>", ""),
+            (
+                "create${ENTITY}",
+                "(input: ${ENTITY}Input) => Promise<${ENTITY}>",
+                "",
+            ),
+        ],
+    );
+    b.node(
+        "DataHook",
+        "src/hooks.ts",
+        Ts,
+        Code,
+        "React hook loading ${ENTITY}s and creating new ones, with loading and error state",
+        &[(
+            "use${ENTITY}s",
+            "hook",
+            "The list, its loading/error state and a create function",
+        )],
+    );
+    b.node(
+        "List",
+        "src/components/List.tsx",
+        Ts,
+        Code,
+        "Component rendering the ${ENTITY}s, with empty and loading states",
+        &[("List", "React.FC<{ items: ${ENTITY}[] }>", "")],
+    );
+    b.node(
+        "Form",
+        "src/components/Form.tsx",
+        Ts,
+        Code,
+        "Controlled form for creating a ${ENTITY}, with validation messages",
+        &[(
+            "Form",
+            "React.FC<{ onSubmit: (input: ${ENTITY}Input) => void }>",
+            "",
+        )],
+    );
+    b.node(
+        "App",
+        "src/App.tsx",
+        Ts,
+        Code,
+        "App shell: layout, the form and the list, wired to the data hook",
+        &[("App", "React.FC", "The root component")],
+    );
+    b.node(
+        "Main",
+        "src/main.tsx",
+        Ts,
+        Code,
+        "Mount the app into #root",
+        &[],
+    );
+    b.node(
+        "Index",
+        "index.html",
+        Language::Html,
+        Config,
+        "Vite HTML entry with a #root element loading src/main.tsx",
+        &[],
+    );
+    b.node(
+        "Readme",
+        "README.md",
+        markdown(),
+        Doc,
+        "Development setup and scripts",
+        &[],
+    );
+    b.edges(&[
+        ("Types", "ApiClient"),
+        ("Types", "DataHook"),
+        ("Types", "List"),
+        ("Types", "Form"),
+        ("ApiClient", "DataHook"),
+        ("DataHook", "App"),
+        ("List", "App"),
+        ("Form", "App"),
+        ("App", "Main"),
+        ("Main", "Index"),
+        ("Package", "Main"),
+        ("App", "Readme"),
+    ]);
+    b.project.manifest.entry_point = Some("src/main.tsx".to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::validation::validate_project;
+    use crate::graph::variables::unknown_variables;
+
+    #[test]
+    fn test_templates_are_valid() {
+        for template in TEMPLATES {
+            let mut project = Project::new(String::new());
+            apply_template(&mut project, template.id).unwrap();
+            assert!(!project.nodes.is_empty(), "{}", template.id);
+            assert!(validate_project(&project).is_valid(), "{}", template.id);
+            for node in &project.nodes {
+                assert!(unknown_variables(&project.manifest, node).is_empty());
+            }
+            assert!(apply_template(&mut project, template.id).is_err());
+        }
+
+        let err = apply_template(&mut Project::new(String::new()), "nope").unwrap_err();
+        assert!(err.contains("rest-api-ts"));
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::model::{NodeStatus, Project};
+
+/// Which way to walk the graph from a node
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeDirection {
+    /// Towards the node's dependencies
+    #[default]
+    Up,
+    /// Towards the nodes that depend on it
+    Down,
+}
+
+/// A node with its dependencies or dependents, recursively
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyTree {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    pub status: NodeStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DependencyTree>,
+    /// Already expanded elsewhere in the tree, so its children are left out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub repeated: bool,
+    /// Has children that the depth limit left out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// The tree of a node's transitive dependencies (`Up`) or dependents (`Down`), at
+/// most `depth` edges deep. A node reached again, through another path or a
+/// cycle, is listed but not expanded a second time.
+pub fn dependency_tree(
+    project: &Project,
+    node_id: &str,
+    direction: TreeDirection,
+    depth: Option<usize>,
+) -> Option<DependencyTree> {
+    project.find_node(node_id)?;
+    let mut expanded = HashSet::new();
+    Some(build(
+        project,
+        node_id,
+        direction,
+        depth.unwrap_or(usize::MAX),
+        &mut expanded,
+    ))
+}
+
+fn build(
+    project: &Project,
+    node_id: &str,
+    direction: TreeDirection,
+    depth: usize,
+    expanded: &mut HashSet<String>,
+) -> DependencyTree {
+    let node = project.find_node(node_id);
+    let mut tree = DependencyTree {
+        id: node_id.to_string(),
+        name: node.map(|n| n.name.clone()).unwrap_or_default(),
+        file_path: node.map(|n| n.file_path.clone()).unwrap_or_default(),
+        status: node.map(|n| n.status.clone()).unwrap_or_default(),
+        children: Vec::new(),
+        repeated: false,
+        truncated: false,
+    };
+
+    let mut next: Vec<&str> = match direction {
+        TreeDirection::Up => project
+            .get_dependencies(node_id)
+            .into_iter()
+            .map(|e| e.source.as_str())
+            .collect(),
+        TreeDirection::Down => project
+            .get_dependents(node_id)
+            .into_iter()
+            .map(|e| e.target.as_str())
+            .collect(),
+    };
+    if next.is_empty() {
+        return tree;
+    }
+    // Cut off here, so it can still be expanded where it appears higher up
+    if depth == 0 {
+        tree.truncated = true;
+        return tree;
+    }
+    if !expanded.insert(node_id.to_string()) {
+        tree.repeated = true;
+        return tree;
+    }
+
+    next.sort_by_key(|id| (project.find_node(id).map(|n| n.name.to_lowercase()), *id));
+    next.dedup();
+    tree.children = next
+        .into_iter()
+        .map(|id| build(project, id, direction, depth - 1, expanded))
+        .collect();
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode, Language};
+
+    #[test]
+    fn test_dependency_tree() {
+        // A -> B -> D, A -> C -> D, D -> E
+        let mut project = Project::new(String::new());
+        let ids: Vec<String> = ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|name| {
+                let node = CodeNode::new(
+                    name.to_string(),
+                    format!("{}.ts", name),
+                    Language::TypeScript,
+                );
+                let id = node.id.clone();
+                project.nodes.push(node);
+                id
+            })
+            .collect();
+        for (source, target) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)] {
+            project.edges.push(CodeEdge::new(
+                ids[source].clone(),
+                ids[target].clone(),
+                String::new(),
+            ));
+        }
+
+        let up = dependency_tree(&project, &ids[3], TreeDirection::Up, None).unwrap();
+        let names: Vec<&str> = up.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["B", "C"]);
+        assert_eq!(up.children[1].children[0].name, "A");
+
+        // D is expanded under B and only listed under C
+        let down = dependency_tree(&project, &ids[0], TreeDirection::Down, None).unwrap();
+        assert_eq!(down.children[0].children[0].children[0].name, "E");
+        let repeated = &down.children[1].children[0];
+        assert!(repeated.repeated);
+        assert!(repeated.children.is_empty());
+
+        let shallow = dependency_tree(&project, &ids[0], TreeDirection::Down, Some(1)).unwrap();
+        assert_eq!(shallow.children.len(), 2);
+        assert!(shallow.children[0].truncated);
+        assert!(shallow.children[0].children.is_empty());
+
+        // With A -> D, D is cut off under B but still expanded directly under A
+        project.edges.push(CodeEdge::new(ids[0].clone(), ids[3].clone(), String::new()));
+        let limited = dependency_tree(&project, &ids[0], TreeDirection::Down, Some(2)).unwrap();
+        assert!(limited.children[0].children[0].truncated);
+        assert_eq!(limited.children[2].name, "D");
+        assert_eq!(limited.children[2].children[0].name, "E");
+
+        assert!(dependency_tree(&project, "missing", TreeDirection::Up, None).is_none());
+    }
+}
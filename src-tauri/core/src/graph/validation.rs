@@ -0,0 +1,430 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::algo::is_cyclic_directed;
+use petgraph::graph::DiGraph;
+
+use super::model::{EmbeddingConfig, Language, Project};
+use super::variables::{resolve_project, unknown_variables};
+use crate::llm::redaction::Redactor;
+
+/// Validation error types
+#[derive(Debug, Clone)]
+pub enum ValidationError {
+    CyclicDependency(Vec<String>),
+    OrphanNode(String),
+    MissingNode(String),
+    DuplicateFilePath(String, Vec<String>),
+    /// File paths that differ only by case, which collide on Windows and macOS
+    CaseInsensitiveCollision(Vec<String>, Vec<String>),
+    /// The manifest's redaction rules don't compile, so hosted generation is refused
+    InvalidRedaction(String),
+    /// The node generates with a provider or model the manifest's policy forbids
+    PolicyViolation(String, String),
+    /// The project embeds nodes with a provider or model the manifest's policy forbids
+    EmbeddingPolicyViolation(String),
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::CyclicDependency(path) => {
+                write!(f, "Cyclic dependency: {}", path.join(" -> "))
+            }
+            ValidationError::OrphanNode(id) => write!(f, "Orphan node: {}", id),
+            ValidationError::MissingNode(id) => write!(f, "Edge references missing node: {}", id),
+            ValidationError::DuplicateFilePath(path, ids) => {
+                write!(f, "{} nodes share the file path {}", ids.len(), path)
+            }
+            ValidationError::CaseInsensitiveCollision(paths, _) => write!(
+                f,
+                "File paths differ only by case and collide on case-insensitive filesystems: {}",
+                paths.join(", ")
+            ),
+            ValidationError::InvalidRedaction(message) => write!(f, "{}", message),
+            ValidationError::PolicyViolation(id, message) => write!(f, "Node {}: {}", id, message),
+            ValidationError::EmbeddingPolicyViolation(message) => {
+                write!(f, "Embeddings: {}", message)
+            }
+        }
+    }
+}
+
+/// Validation warning types
+#[derive(Debug, Clone)]
+pub enum ValidationWarning {
+    EmptyDescription(String),
+    NoExports(String),
+    UnreachableNode(String),
+    /// The node's file extension doesn't match its language
+    ExtensionMismatch(String, String, Language),
+    /// The node attaches a context snippet the manifest doesn't define
+    UnknownSnippet(String, String),
+    /// The node uses a shared type the manifest doesn't define
+    UnknownSharedType(String, String),
+    /// The node's spec has a `${NAME}` placeholder with no value
+    UnknownVariable(String, String),
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::EmptyDescription(id) => write!(f, "Node {} has no description", id),
+            ValidationWarning::NoExports(id) => write!(f, "Node {} declares no exports", id),
+            ValidationWarning::UnreachableNode(id) => {
+                write!(f, "Node {} has no dependencies or dependents", id)
+            }
+            ValidationWarning::ExtensionMismatch(id, path, language) => write!(
+                f,
+                "Node {}: {} doesn't have a {} file extension",
+                id, path, language
+            ),
+            ValidationWarning::UnknownSnippet(id, name) => {
+                write!(f, "Node {} attaches unknown snippet '{}'", id, name)
+            }
+            ValidationWarning::UnknownSharedType(id, name) => {
+                write!(f, "Node {} uses unknown shared type '{}'", id, name)
+            }
+            ValidationWarning::UnknownVariable(id, name) => {
+                write!(f, "Node {} uses undefined variable '${{{}}}'", id, name)
+            }
+        }
+    }
+}
+
+/// Result of validating a project
+#[derive(Debug, Clone, Default)]
+pub struct ValidationResult {
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationWarning>,
+}
+
+impl ValidationResult {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+/// Validate the project graph structure
+pub fn validate_project(project: &Project) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    if let Some(Err(e)) = Redactor::for_manifest(&project.manifest) {
+        result.errors.push(ValidationError::InvalidRedaction(e));
+    }
+    if let Some(policy) = &project.manifest.policy {
+        for node in project.nodes.iter().filter(|n| n.kind.has_file()) {
+            let config = node.llm_config.resolve(&project.manifest.default_llm);
+            if let Err(message) = policy.check(&config.provider, &config.model) {
+                result
+                    .errors
+                    .push(ValidationError::PolicyViolation(node.id.clone(), message));
+            }
+        }
+        // Embeddings are only sent when configured or used for related context
+        let manifest = &project.manifest;
+        if manifest.embeddings.is_some() || manifest.related_context.unwrap_or(0) > 0 {
+            let config = EmbeddingConfig::for_manifest(manifest);
+            if let Err(message) = policy.check(&config.provider, &config.model) {
+                result.errors.push(ValidationError::EmbeddingPolicyViolation(message));
+            }
+        }
+    }
+    for node in &project.nodes {
+        for name in unknown_variables(&project.manifest, node) {
+            result.warnings.push(ValidationWarning::UnknownVariable(node.id.clone(), name));
+        }
+    }
+    // Everything else is checked against the substituted specs
+    let resolved = resolve_project(project);
+    let project = resolved.as_ref();
+
+    // Build a graph for cycle detection
+    let mut graph = DiGraph::<&str, ()>::new();
+    let mut node_indices: HashMap<&str, petgraph::graph::NodeIndex> = HashMap::new();
+
+    // Add all nodes to the graph
+    for node in &project.nodes {
+        let idx = graph.add_node(node.id.as_str());
+        node_indices.insert(node.id.as_str(), idx);
+    }
+
+    // Add edges
+    for edge in &project.edges {
+        let source_idx = node_indices.get(edge.source.as_str());
+        let target_idx = node_indices.get(edge.target.as_str());
+
+        match (source_idx, target_idx) {
+            (Some(&s), Some(&t)) => {
+                graph.add_edge(s, t, ());
+            }
+            (None, _) => {
+                result
+                    .errors
+                    .push(ValidationError::MissingNode(edge.source.clone()));
+            }
+            (_, None) => {
+                result
+                    .errors
+                    .push(ValidationError::MissingNode(edge.target.clone()));
+            }
+        }
+    }
+
+    // Check for cycles
+    if is_cyclic_directed(&graph) {
+        // TODO: Extract the actual cycle path
+        result.errors.push(ValidationError::CyclicDependency(vec![
+            "Cycle detected in graph".to_string(),
+        ]));
+    }
+
+    // Check for file paths that would overwrite each other
+    result.errors.extend(file_path_conflicts(project));
+
+    // Check for nodes without edges (orphans) - warning only
+    let nodes_in_edges: HashSet<&str> = project
+        .edges
+        .iter()
+        .flat_map(|e| vec![e.source.as_str(), e.target.as_str()])
+        .collect();
+
+    for node in &project.nodes {
+        if !nodes_in_edges.contains(node.id.as_str()) && project.nodes.len() > 1 {
+            result
+                .warnings
+                .push(ValidationWarning::UnreachableNode(node.id.clone()));
+        }
+    }
+
+    result.warnings.extend(extension_mismatches(project));
+
+    // Check for missing descriptions/exports - warnings
+    for node in &project.nodes {
+        if node.description.is_empty() {
+            result
+                .warnings
+                .push(ValidationWarning::EmptyDescription(node.id.clone()));
+        }
+        if node.exports.is_empty() {
+            result
+                .warnings
+                .push(ValidationWarning::NoExports(node.id.clone()));
+        }
+        for name in &node.snippets {
+            if !project.manifest.snippets.contains_key(name) {
+                result
+                    .warnings
+                    .push(ValidationWarning::UnknownSnippet(node.id.clone(), name.clone()));
+            }
+        }
+        for name in node.shared_type_names() {
+            if !project.manifest.shared_types.contains_key(name) {
+                result
+                    .warnings
+                    .push(ValidationWarning::UnknownSharedType(node.id.clone(), name.to_string()));
+            }
+        }
+    }
+
+    result
+}
+
+/// Nodes whose files would overwrite each other: identical paths, or paths that
+/// differ only by case
+pub fn file_path_conflicts(project: &Project) -> Vec<ValidationError> {
+    let resolved = resolve_project(project);
+    let project = resolved.as_ref();
+    let mut conflicts = Vec::new();
+
+    let mut file_paths: HashMap<&str, Vec<&str>> = HashMap::new();
+    for node in project.nodes.iter().filter(|n| n.owns_file()) {
+        file_paths
+            .entry(node.file_path.as_str())
+            .or_default()
+            .push(node.id.as_str());
+    }
+    for (path, ids) in &file_paths {
+        if ids.len() > 1 {
+            conflicts.push(ValidationError::DuplicateFilePath(
+                path.to_string(),
+                ids.iter().map(|s| s.to_string()).collect(),
+            ));
+        }
+    }
+
+    let mut folded: HashMap<String, Vec<&str>> = HashMap::new();
+    for path in file_paths.keys() {
+        folded.entry(path.to_lowercase()).or_default().push(path);
+    }
+    for mut paths in folded.into_values().filter(|p| p.len() > 1) {
+        paths.sort();
+        let ids = paths
+            .iter()
+            .flat_map(|p| file_paths[p].iter().map(|id| id.to_string()))
+            .collect();
+        conflicts.push(ValidationError::CaseInsensitiveCollision(
+            paths.iter().map(|p| p.to_string()).collect(),
+            ids,
+        ));
+    }
+
+    conflicts
+}
+
+/// Extensions allowed for a language: the manifest's mapping if it has one,
+/// otherwise the language's defaults
+fn allowed_extensions(project: &Project, language: &Language) -> Vec<String> {
+    match project.manifest.language_extensions.get(language) {
+        Some(extensions) => extensions
+            .iter()
+            .map(|e| e.trim_start_matches('.').to_lowercase())
+            .collect(),
+        None => language
+            .default_extensions()
+            .iter()
+            .map(|e| e.to_string())
+            .collect(),
+    }
+}
+
+/// Nodes whose file extension doesn't match their language
+pub fn extension_mismatches(project: &Project) -> Vec<ValidationWarning> {
+    project
+        .nodes
+        .iter()
+        .filter(|node| node.owns_file())
+        .filter(|node| {
+            let extension = std::path::Path::new(&node.file_path)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let allowed = allowed_extensions(project, &node.language);
+            // Custom languages without a configured mapping can't be checked
+            !allowed.is_empty() && !allowed.contains(&extension)
+        })
+        .map(|node| {
+            ValidationWarning::ExtensionMismatch(
+                node.id.clone(),
+                node.file_path.clone(),
+                node.language.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Check if adding an edge would create a cycle
+pub fn would_create_cycle(project: &Project, source: &str, target: &str) -> bool {
+    let mut graph = DiGraph::<&str, ()>::new();
+    let mut node_indices: HashMap<&str, petgraph::graph::NodeIndex> = HashMap::new();
+
+    // Add all nodes
+    for node in &project.nodes {
+        let idx = graph.add_node(node.id.as_str());
+        node_indices.insert(node.id.as_str(), idx);
+    }
+
+    // Add existing edges
+    for edge in &project.edges {
+        if let (Some(&s), Some(&t)) = (
+            node_indices.get(edge.source.as_str()),
+            node_indices.get(edge.target.as_str()),
+        ) {
+            graph.add_edge(s, t, ());
+        }
+    }
+
+    // Add the proposed edge
+    if let (Some(&s), Some(&t)) = (node_indices.get(source), node_indices.get(target)) {
+        graph.add_edge(s, t, ());
+        is_cyclic_directed(&graph)
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, LLMProvider, ProviderPolicy};
+
+    #[test]
+    fn test_file_path_checks() {
+        let mut project = Project::new(String::new());
+        for (name, path) in [
+            ("A", "src/User.ts"),
+            ("B", "src/user.ts"),
+            ("C", "src/c.py"),
+            ("D", "src/d.vue"),
+        ] {
+            let node = CodeNode::new(name.to_string(), path.to_string(), Language::TypeScript);
+            project.nodes.push(node);
+        }
+
+        let conflicts = file_path_conflicts(&project);
+        assert_eq!(conflicts.len(), 1);
+        assert!(matches!(
+            &conflicts[0],
+            ValidationError::CaseInsensitiveCollision(paths, ids)
+                if paths == &["src/User.ts", "src/user.ts"] && ids.len() == 2
+        ));
+
+        assert_eq!(extension_mismatches(&project).len(), 2);
+
+        // A configured mapping replaces the defaults for that language
+        project
+            .manifest
+            .language_extensions
+            .insert(Language::TypeScript, vec!["ts".to_string(), ".vue".to_string()]);
+        let mismatches = extension_mismatches(&project);
+        assert_eq!(mismatches.len(), 1);
+        assert!(matches!(
+            &mismatches[0],
+            ValidationWarning::ExtensionMismatch(_, path, _) if path == "src/c.py"
+        ));
+    }
+
+    #[test]
+    fn test_policy_violations() {
+        let mut project = Project::new(String::new());
+        let mut local = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        local.llm_config.use_project_default = false;
+        local.llm_config.provider = LLMProvider::Ollama;
+        local.llm_config.model = "llama3.1".to_string();
+        let hosted = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let hosted_id = hosted.id.clone();
+        project.nodes = vec![local, hosted];
+        assert!(validate_project(&project).is_valid());
+
+        project.manifest.policy = Some(ProviderPolicy {
+            allowed_providers: vec![LLMProvider::Ollama],
+            allowed_models: vec!["llama3*".to_string()],
+        });
+        let errors = validate_project(&project).errors;
+        let violations: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| match e {
+                ValidationError::PolicyViolation(id, _) => Some(id.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(violations, vec![hosted_id.as_str()]);
+
+        // Related context embeds with OpenAI for an Anthropic project
+        project.manifest.related_context = Some(3);
+        let embedding_violation = |project: &Project| {
+            validate_project(project)
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::EmbeddingPolicyViolation(_)))
+        };
+        assert!(embedding_violation(&project));
+        project.manifest.embeddings = Some(EmbeddingConfig {
+            provider: LLMProvider::Ollama,
+            model: "llama3-embed".to_string(),
+        });
+        assert!(!embedding_violation(&project));
+    }
+}
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+
+use super::model::{CodeNode, Project, ProjectManifest};
+
+/// A `${NAME}` placeholder
+const PLACEHOLDER: &str = r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}";
+
+/// The values of a node's placeholders: the built-in `PROJECT_NAME`,
+/// `PROJECT_VERSION` and `NODE_NAME`, then the manifest's variables, then the
+/// node's own, each overriding the ones before
+pub fn node_variables<'a>(
+    manifest: &'a ProjectManifest,
+    node: &'a CodeNode,
+) -> HashMap<&'a str, &'a str> {
+    let mut variables = HashMap::from([
+        ("PROJECT_NAME", manifest.name.as_str()),
+        ("PROJECT_VERSION", manifest.version.as_str()),
+        ("NODE_NAME", node.name.as_str()),
+    ]);
+    for (name, value) in manifest.variables.iter().chain(&node.variables) {
+        variables.insert(name.as_str(), value.as_str());
+    }
+    variables
+}
+
+/// Replace the placeholders with their values. Unknown placeholders are left as
+/// they are, for validation to point out.
+pub fn substitute(text: &str, variables: &HashMap<&str, &str>) -> String {
+    if !text.contains("${") {
+        return text.to_string();
+    }
+    let re = Regex::new(PLACEHOLDER).unwrap();
+    re.replace_all(text, |caps: &Captures| match variables.get(&caps[1]) {
+        Some(value) => value.to_string(),
+        None => caps[0].to_string(),
+    })
+    .into_owned()
+}
+
+/// The spec fields placeholders may appear in
+fn spec_texts(node: &CodeNode) -> impl Iterator<Item = &String> {
+    [&node.description, &node.purpose, &node.file_path]
+        .into_iter()
+        .chain(&node.llm_config.constraints)
+        .chain(
+            node.exports
+                .iter()
+                .flat_map(|e| [&e.name, &e.type_signature, &e.description]),
+        )
+}
+
+/// The node with its description, purpose, file path, constraints and exports
+/// substituted
+pub fn resolve_node(manifest: &ProjectManifest, node: &CodeNode) -> CodeNode {
+    let variables = node_variables(manifest, node);
+    let mut resolved = node.clone();
+    resolved.description = substitute(&node.description, &variables);
+    resolved.purpose = substitute(&node.purpose, &variables);
+    resolved.file_path = substitute(&node.file_path, &variables);
+    for constraint in &mut resolved.llm_config.constraints {
+        *constraint = substitute(constraint, &variables);
+    }
+    for export in &mut resolved.exports {
+        export.name = substitute(&export.name, &variables);
+        export.type_signature = substitute(&export.type_signature, &variables);
+        export.description = substitute(&export.description, &variables);
+    }
+    resolved
+}
+
+/// The project with every node resolved, borrowed when no spec has a placeholder
+pub fn resolve_project(project: &Project) -> Cow<'_, Project> {
+    let has_placeholders = project
+        .nodes
+        .iter()
+        .any(|node| spec_texts(node).any(|text| text.contains("${")));
+    if !has_placeholders {
+        return Cow::Borrowed(project);
+    }
+    let mut resolved = project.clone();
+    for node in &mut resolved.nodes {
+        *node = resolve_node(&project.manifest, node);
+    }
+    Cow::Owned(resolved)
+}
+
+/// Placeholders in the node's spec that have no value, sorted and deduplicated
+pub fn unknown_variables(manifest: &ProjectManifest, node: &CodeNode) -> Vec<String> {
+    let variables = node_variables(manifest, node);
+    let re = Regex::new(PLACEHOLDER).unwrap();
+    let mut unknown: Vec<String> = spec_texts(node)
+        .flat_map(|text| re.captures_iter(text).map(|caps| caps[1].to_string()))
+        .filter(|name| !variables.contains_key(name.as_str()))
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_resolve_node() {
+        let mut project = Project::new(String::new());
+        project.manifest.name = "Shop".to_string();
+        project
+            .manifest
+            .variables
+            .insert("ENTITY".to_string(), "Item".to_string());
+        let mut node = CodeNode::new(
+            "Repo".to_string(),
+            "src/${ENTITY}Repo.ts".to_string(),
+            Language::TypeScript,
+        );
+        node.variables
+            .insert("ENTITY".to_string(), "Order".to_string());
+        node.description = "Stores ${ENTITY}s for ${PROJECT_NAME}".to_string();
+        node.llm_config.constraints = vec!["Must export ${ENTITY}Repo".to_string()];
+        node.purpose = "Uses ${MISSING}".to_string();
+
+        let resolved = resolve_node(&project.manifest, &node);
+        assert_eq!(resolved.file_path, "src/OrderRepo.ts");
+        assert_eq!(resolved.description, "Stores Orders for Shop");
+        assert_eq!(
+            resolved.llm_config.constraints,
+            vec!["Must export OrderRepo"]
+        );
+        assert_eq!(resolved.purpose, "Uses ${MISSING}");
+        assert_eq!(unknown_variables(&project.manifest, &node), vec!["MISSING"]);
+
+        assert!(matches!(resolve_project(&project), Cow::Borrowed(_)));
+        project.nodes.push(node);
+        assert_eq!(
+            resolve_project(&project).nodes[0].file_path,
+            "src/OrderRepo.ts"
+        );
+    }
+}
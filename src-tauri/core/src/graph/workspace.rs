@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::model::{Project, Subproject};
+use super::serialization::{read_project_file, PROJECT_FILE_NAME};
+
+/// Separates a sub-project's name from the original ID in an included node's ID
+pub const SUBPROJECT_SEPARATOR: &str = "::";
+
+/// ID of a sub-project's node or edge as it appears in the including project
+pub fn namespaced_id(subproject: &str, id: &str) -> String {
+    format!("{}{}{}", subproject, SUBPROJECT_SEPARATOR, id)
+}
+
+/// Reject sub-projects without a name or path, or with a name used twice
+pub fn validate_subprojects(subprojects: &[Subproject]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for subproject in subprojects {
+        if subproject.name.trim().is_empty() || subproject.name.contains(SUBPROJECT_SEPARATOR) {
+            return Err(format!("Invalid sub-project name: '{}'", subproject.name));
+        }
+        if subproject.path.trim().is_empty() {
+            return Err(format!("Sub-project '{}' has no path", subproject.name));
+        }
+        if !names.insert(subproject.name.as_str()) {
+            return Err(format!("Sub-project '{}' is declared twice", subproject.name));
+        }
+    }
+    Ok(())
+}
+
+
+/// The project without the nodes and edges included from sub-projects, as saved
+pub fn without_subprojects(project: &Project) -> Project {
+    let mut own = project.clone();
+    own.nodes.retain(|n| n.subproject.is_none());
+    // Edges this project draws to included nodes keep their own IDs
+    own.edges.retain(|e| !e.id.contains(SUBPROJECT_SEPARATOR));
+    own
+}
+
+/// Replace the nodes and edges included from sub-projects with those currently in
+/// their project files. Sub-projects' own sub-projects are included too, with
+/// nested namespaces; a project including itself is an error.
+pub fn include_subprojects(project: &mut Project) -> Result<()> {
+    let mut visiting = vec![canonical(Path::new(&project.project_path))];
+    include_subprojects_from(project, &mut visiting)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn include_subprojects_from(project: &mut Project, visiting: &mut Vec<PathBuf>) -> Result<()> {
+    *project = without_subprojects(project);
+
+    for subproject in project.manifest.subprojects.clone() {
+        let dir = Path::new(&project.project_path).join(&subproject.path);
+        let path = canonical(&dir);
+        if visiting.contains(&path) {
+            anyhow::bail!("Sub-project '{}' includes itself", subproject.name);
+        }
+
+        let mut included = read_project_file(&dir.join(PROJECT_FILE_NAME))
+            .with_context(|| format!("Failed to load sub-project '{}'", subproject.name))?;
+        included.project_path = dir.to_string_lossy().to_string();
+        included.apply_default_llm();
+        visiting.push(path);
+        include_subprojects_from(&mut included, visiting)?;
+        visiting.pop();
+
+        for mut node in included.nodes {
+            node.id = namespaced_id(&subproject.name, &node.id);
+            node.pinned = true;
+            node.subproject = Some(match node.subproject {
+                Some(nested) => namespaced_id(&subproject.name, &nested),
+                None => subproject.name.clone(),
+            });
+            project.nodes.push(node);
+        }
+        for mut edge in included.edges {
+            edge.id = namespaced_id(&subproject.name, &edge.id);
+            edge.source = namespaced_id(&subproject.name, &edge.source);
+            edge.target = namespaced_id(&subproject.name, &edge.target);
+            project.edges.push(edge);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode, Language};
+    use crate::graph::serialization::{load_project_from_file, save_project_to_file};
+
+    #[test]
+    fn test_include_subprojects() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend_dir = dir.path().join("backend");
+        std::fs::create_dir(&backend_dir).unwrap();
+
+        let mut backend = Project::new(backend_dir.to_string_lossy().to_string());
+        let types =
+            CodeNode::new("Types".to_string(), "types.ts".to_string(), Language::TypeScript);
+        let types_id = types.id.clone();
+        backend.nodes.push(types);
+        save_project_to_file(&backend).unwrap();
+
+        let mut frontend = Project::new(dir.path().to_string_lossy().to_string());
+        frontend.manifest.subprojects.push(Subproject {
+            name: "backend".to_string(),
+            path: "backend".to_string(),
+        });
+        let app = CodeNode::new("App".to_string(), "app.ts".to_string(), Language::TypeScript);
+        let included_id = namespaced_id("backend", &types_id);
+        let edge = CodeEdge::new(included_id.clone(), app.id.clone(), "uses".to_string());
+        frontend.edges.push(edge);
+        frontend.nodes.push(app);
+        include_subprojects(&mut frontend).unwrap();
+
+        let included = frontend.find_node(&included_id).unwrap();
+        assert!(included.pinned);
+        assert_eq!(included.subproject.as_deref(), Some("backend"));
+        assert!(!included.owns_file());
+
+        // Only the project's own nodes and edges are saved
+        save_project_to_file(&frontend).unwrap();
+        let saved = read_project_file(&dir.path().join(PROJECT_FILE_NAME)).unwrap();
+        assert_eq!(saved.nodes.len(), 1);
+        assert_eq!(saved.edges.len(), 1);
+        let loaded = load_project_from_file(&dir.path().join(PROJECT_FILE_NAME)).unwrap();
+        assert_eq!(loaded.nodes.len(), 2);
+
+        // A sub-project including its parent is rejected
+        backend.manifest.subprojects.push(Subproject {
+            name: "frontend".to_string(),
+            path: "..".to_string(),
+        });
+        save_project_to_file(&backend).unwrap();
+        assert!(include_subprojects(&mut frontend).is_err());
+
+        assert!(validate_subprojects(&[Subproject {
+            name: "a::b".to_string(),
+            path: "x".to_string(),
+        }])
+        .is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+/// Cache breakpoints Anthropic allows per request; one goes to the system prompt
+const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<Vec<AnthropicTextBlock>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<AnthropicThinking>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<AnthropicTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<AnthropicToolChoice>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicThinking {
+    #[serde(rename = "type")]
+    thinking_type: &'static str,
+    budget_tokens: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicToolChoice {
+    #[serde(rename = "type")]
+    choice_type: &'static str,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicMessage {
+    role: String,
+    content: Vec<AnthropicTextBlock>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicTextBlock {
+    #[serde(rename = "type")]
+    block_type: &'static str,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    cache_type: &'static str,
+}
+
+impl AnthropicTextBlock {
+    fn new(text: String, cached: bool) -> Self {
+        Self {
+            block_type: "text",
+            text,
+            cache_control: cached.then_some(CacheControl {
+                cache_type: "ephemeral",
+            }),
+        }
+    }
+}
+
+/// Split the prompt into text blocks at its cache breakpoints, marking the last few
+/// blocks that end at one for caching. Earlier boundaries are still matched against
+/// the cache, so a prompt sharing only its first dependencies with another reuses them.
+fn prompt_blocks(
+    prompt: String,
+    breakpoints: &[usize],
+    available: usize,
+) -> Vec<AnthropicTextBlock> {
+    let mut offsets: Vec<usize> = breakpoints
+        .iter()
+        .copied()
+        .filter(|&offset| offset > 0 && offset < prompt.len() && prompt.is_char_boundary(offset))
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    if offsets.is_empty() {
+        return vec![AnthropicTextBlock::new(prompt, false)];
+    }
+
+    let first_cached = offsets.len().saturating_sub(available);
+    let mut blocks = Vec::with_capacity(offsets.len() + 1);
+    let mut start = 0;
+    for (i, &end) in offsets.iter().enumerate() {
+        blocks.push(AnthropicTextBlock::new(prompt[start..end].to_string(), i >= first_cached));
+        start = end;
+    }
+    blocks.push(AnthropicTextBlock::new(prompt[start..].to_string(), false));
+    blocks
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+    model: String,
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicContent {
+    Text { text: String },
+    ToolUse { input: serde_json::Value },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicUsage {
+    /// Input tokens after the last cache breakpoint, which were neither read from
+    /// nor written to the cache
+    input_tokens: u32,
+    output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicError {
+    error: AnthropicErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicErrorDetail {
+    message: String,
+}
+
+pub struct AnthropicProvider {
+    api_key: Option<String>,
+    model: String,
+    /// Extended thinking budget in tokens, when enabled
+    thinking_budget: Option<u32>,
+    client: Client,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: Option<String>, model: String, thinking_budget: Option<u32>) -> Self {
+        Self {
+            api_key,
+            model,
+            thinking_budget,
+            client: super::http::client(),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for AnthropicProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        let api_key = self.api_key.as_ref().ok_or(LLMError::InvalidApiKey)?;
+
+        // The system prompt is shared by every node with the same language and settings
+        let system = request
+            .system_prompt
+            .map(|system| vec![AnthropicTextBlock::new(system, true)]);
+        let available = MAX_CACHE_BREAKPOINTS - usize::from(system.is_some());
+        // Structured output: force a call to a tool whose input is the response
+        let (tools, tool_choice) = match request.output_schema {
+            Some(schema) => (
+                vec![AnthropicTool {
+                    name: schema.name.clone(),
+                    description: schema.description,
+                    input_schema: schema.schema,
+                }],
+                Some(AnthropicToolChoice {
+                    choice_type: "tool",
+                    name: schema.name,
+                }),
+            ),
+            None => (Vec::new(), None),
+        };
+        // Thinking can't be combined with a forced tool call or a custom temperature, and
+        // its budget counts toward max_tokens, so the answer keeps the requested room
+        let max_tokens = request.max_tokens.unwrap_or(4096);
+        let (max_tokens, temperature, thinking) = match self.thinking_budget {
+            Some(budget) if tool_choice.is_none() => (
+                max_tokens + budget,
+                None,
+                Some(AnthropicThinking {
+                    thinking_type: "enabled",
+                    budget_tokens: budget,
+                }),
+            ),
+            _ => (max_tokens, request.temperature, None),
+        };
+        let anthropic_request = AnthropicRequest {
+            model: self.model.clone(),
+            max_tokens,
+            messages: vec![AnthropicMessage {
+                role: "user".to_string(),
+                content: prompt_blocks(request.prompt, &request.cache_breakpoints, available),
+            }],
+            system,
+            temperature,
+            thinking,
+            tools,
+            tool_choice,
+        };
+
+        let response = self
+            .client
+            .post(ANTHROPIC_API_URL)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&anthropic_request)
+            .send()
+            .await
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        tracing::debug!("Anthropic request for {} returned {}", self.model, status);
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(LLMError::InvalidApiKey);
+        }
+
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(LLMError::RateLimited);
+        }
+
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            if let Ok(error) = serde_json::from_str::<AnthropicError>(&error_text) {
+                return Err(LLMError::RequestFailed(error.error.message));
+            }
+            return Err(LLMError::RequestFailed(format!(
+                "HTTP {}: {}",
+                status, error_text
+            )));
+        }
+
+        let anthropic_response: AnthropicResponse = response
+            .json()
+            .await
+            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+
+        let content = anthropic_response
+            .content
+            .iter()
+            .find_map(|block| match block {
+                AnthropicContent::Text { text } => Some(text.clone()),
+                AnthropicContent::ToolUse { input } => Some(input.to_string()),
+                AnthropicContent::Other => None,
+            })
+            .unwrap_or_default();
+
+        let usage = anthropic_response.usage;
+        let input_tokens =
+            usage.input_tokens + usage.cache_creation_input_tokens + usage.cache_read_input_tokens;
+        Ok(GenerationResponse {
+            content,
+            model: anthropic_response.model,
+            tokens_used: Some(input_tokens + usage.output_tokens),
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(usage.output_tokens),
+            cached_input_tokens: Some(usage.cache_read_input_tokens),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Anthropic"
+    }
+
+    fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+}
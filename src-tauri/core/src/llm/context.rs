@@ -0,0 +1,768 @@
+use crate::graph::paths::validate_path;
+use crate::graph::search::{load_index, related_nodes};
+use crate::graph::variables::resolve_project;
+use crate::graph::model::{
+    CodeNode, Project, ProjectManifest, ExportSignature, Language, NodeKind, PromptExample,
+    SharedType,
+};
+use crate::llm::extract::strip_code_blocks;
+use serde::de::DeserializeOwned;
+
+/// Characters of reference files included in one prompt, shared by all of a node's
+/// reference files in order
+pub const REFERENCE_FILES_BUDGET: usize = 24_000;
+
+/// Builds context/prompts for code generation based on node and its dependencies
+pub struct ContextBuilder;
+
+impl ContextBuilder {
+    /// Build a complete prompt for generating code for a node
+    pub fn build_prompt(project: &Project, node_id: &str) -> Option<String> {
+        Self::build_prompt_with_breakpoints(project, node_id).map(|(prompt, _)| prompt)
+    }
+
+    /// Build the prompt along with the offsets that end each dependency's section,
+    /// where providers with prompt caching can cache the prompt's prefix
+    pub fn build_prompt_with_breakpoints(
+        project: &Project,
+        node_id: &str,
+    ) -> Option<(String, Vec<usize>)> {
+        let resolved = resolve_project(project);
+        let project = resolved.as_ref();
+        let node = project.find_node(node_id)?;
+
+        let mut prompt = String::new();
+        let mut cache_breakpoints = Vec::new();
+
+        // Header (the same for every node of this kind and language)
+        let language = format_language(&node.language.to_string());
+        let header = match node.kind {
+            NodeKind::Code => format!("You are implementing a {} module.", language),
+            NodeKind::Config => format!("You are writing a {} configuration file.", language),
+            NodeKind::Schema => format!("You are writing a {} schema.", language),
+            NodeKind::Doc => "You are writing documentation.".to_string(),
+            NodeKind::Asset => format!("You are creating a {} asset file.", language),
+            NodeKind::External => "You are describing an external package.".to_string(),
+        };
+        prompt.push_str(&format!("{}\n\n", header));
+
+        let (packages, dependencies): (Vec<_>, Vec<_>) = Self::get_dependencies(project, node_id)
+            .into_iter()
+            .partition(|(dep, _)| !dep.kind.has_file());
+
+        // Dependencies context - include actual generated code from dependencies. It comes
+        // before anything specific to this node because it's the same text in every
+        // dependent's prompt, so providers with prompt caching can reuse it across a wave.
+        if !dependencies.is_empty() {
+            prompt.push_str(match node.kind {
+                NodeKind::Code => "## Dependencies (you can import from these files):\n\n",
+                NodeKind::Doc => {
+                    "## Files to document (summarize what they do and how to use them; \
+                     do not import from them):\n\n"
+                }
+                _ => "## Related files (stay consistent with these):\n\n",
+            });
+            for (dep_node, edge_type) in &dependencies {
+                prompt.push_str(&format!("### {} `{}`\n", edge_type, dep_node.file_path));
+
+                // Include the actual generated code if available
+                if let Some(ref code) = dep_node.generated_code {
+                    prompt.push_str("```\n");
+                    prompt.push_str(code);
+                    if !code.ends_with('\n') {
+                        prompt.push('\n');
+                    }
+                    prompt.push_str("```\n\n");
+                } else if dep_node.exports.is_empty() {
+                    // Nothing concrete to import yet - describe a provisional interface
+                    prompt.push_str(&format_stub_interface(dep_node));
+                } else {
+                    // Fallback to export signatures if code not yet generated
+                    prompt.push_str("Exports:\n");
+                    for export in &dep_node.exports {
+                        prompt.push_str(&format!("- {}: {}\n", export.name, export.type_signature));
+                        if !export.description.is_empty() {
+                            prompt.push_str(&format!("  {}\n", export.description));
+                        }
+                    }
+                    prompt.push('\n');
+                }
+                cache_breakpoints.push(prompt.len());
+            }
+        }
+
+        prompt.push_str(&format!("## File: {}\n", node.file_path));
+
+        if !node.purpose.is_empty() {
+            prompt.push_str(&format!("## Purpose: {}\n\n", node.purpose));
+        }
+
+        if !node.description.is_empty() {
+            prompt.push_str(&format!("## Description\n{}\n\n", node.description));
+        }
+
+        // Shared context snippets attached by name
+        let snippets: Vec<(&String, &String)> = node
+            .snippets
+            .iter()
+            .filter_map(|name| project.manifest.snippets.get_key_value(name))
+            .collect();
+        if !snippets.is_empty() {
+            prompt.push_str("## Shared context\n\n");
+            for (name, text) in snippets {
+                prompt.push_str(&format!("### {}\n{}\n\n", name, text.trim_end()));
+            }
+        }
+
+        // Shared types, so every file that uses one defines it the same way
+        let shared_types: Vec<(&str, &SharedType)> = node
+            .shared_type_names()
+            .into_iter()
+            .filter_map(|name| Some((name, project.manifest.shared_types.get(name)?)))
+            .collect();
+        if !shared_types.is_empty() {
+            prompt.push_str("## Shared types\n");
+            prompt.push_str(
+                "Other files use these exact definitions; use them as they are, without \
+                 changing fields or names.\n\n",
+            );
+            for (name, shared_type) in shared_types {
+                prompt.push_str(&format_shared_type(name, shared_type, &node.language));
+            }
+        }
+
+        // Exports to implement
+        if !node.exports.is_empty() {
+            prompt.push_str(match node.kind {
+                NodeKind::Code => "## You must export:\n",
+                NodeKind::Doc => "## You must cover:\n",
+                _ => "## You must define:\n",
+            });
+            for export in &node.exports {
+                prompt.push_str(&format_export(export));
+            }
+            prompt.push('\n');
+        }
+
+        // Third-party packages the node may use
+        if !packages.is_empty() {
+            prompt.push_str("## Packages you may use:\n\n");
+            for (dep_node, _) in &packages {
+                prompt.push_str(&format_package(dep_node));
+            }
+        }
+
+        // Semantically related nodes that aren't dependencies, for consistency
+        let related = Self::related_context(project, node_id);
+        if !related.is_empty() {
+            prompt.push_str(
+                "## Related modules (not dependencies; do not import from them, but keep \
+                 shared names and types consistent with them):\n\n",
+            );
+            prompt.push_str(&related);
+        }
+
+        // Reference files from the project directory
+        let references = read_reference_files(project, node);
+        if !references.is_empty() {
+            prompt.push_str(
+                "## Reference files (use these as specification, do not import them):\n\n",
+            );
+            prompt.push_str(&references);
+        }
+
+        // Constraints
+        if !node.llm_config.constraints.is_empty() {
+            prompt.push_str("## Constraints:\n");
+            for constraint in &node.llm_config.constraints {
+                prompt.push_str(&format!("- {}\n", constraint));
+            }
+            prompt.push('\n');
+        }
+
+        // Few-shot examples, project-wide ones first
+        let examples: Vec<&PromptExample> = project
+            .manifest
+            .examples
+            .iter()
+            .chain(&node.llm_config.examples)
+            .collect();
+        if !examples.is_empty() {
+            prompt.push_str("## Examples (follow the style of these outputs):\n\n");
+            for (i, example) in examples.iter().enumerate() {
+                prompt.push_str(&format_example(i + 1, example));
+            }
+        }
+
+        if node.kind.is_code() {
+            prompt.push_str("Generate the complete implementation.\n\n");
+            prompt.push_str("IMPORTANT: Output ONLY the raw code. Do NOT wrap the code in markdown code blocks (``` or ```typescript). Do NOT include any explanations, comments about the code, or surrounding text. The output should be directly usable as a source file.");
+        } else {
+            prompt.push_str(if node.kind == NodeKind::Doc {
+                "Write the complete document.\n\n"
+            } else {
+                "Generate the complete file.\n\n"
+            });
+            prompt.push_str("IMPORTANT: Output ONLY the raw file contents. Do NOT wrap them in markdown code blocks. Do NOT include any explanations or surrounding text. The output should be written to the file as is.");
+        }
+
+        Some((prompt, cache_breakpoints))
+    }
+
+    /// Build a system prompt for the LLM: the base prompt for the node's kind, then the
+    /// project's `systemPrompt`, then the node's own, most specific last
+    pub fn build_system_prompt(manifest: &ProjectManifest, node: &CodeNode) -> String {
+        let language = format_language(&node.language.to_string());
+        let base = match node.kind {
+            NodeKind::Code => {
+                let mut base = format!(
+                    "You are an expert {} programmer. Generate clean, well-documented, production-ready code.",
+                    language
+                );
+                if let Some(conventions) = language_conventions(&node.language) {
+                    base.push(' ');
+                    base.push_str(conventions);
+                }
+                base
+            }
+            NodeKind::Config => format!(
+                "You are an expert at {} configuration. Produce valid, minimal configuration \
+                 that matches the code it configures.",
+                language
+            ),
+            NodeKind::Schema => format!(
+                "You are an expert API and data modeller. Produce a precise, valid {} schema \
+                 that is consistent with the code that uses it.",
+                language
+            ),
+            NodeKind::Doc => "You are an expert technical writer. Write clear, accurate \
+                 documentation that summarizes the behavior and public interfaces of the files \
+                 it describes."
+                .to_string(),
+            NodeKind::Asset => format!(
+                "You produce static {} asset files. Output only valid file contents.",
+                language
+            ),
+            NodeKind::External => "You are an expert software engineer.".to_string(),
+        };
+
+        [&manifest.system_prompt, &node.llm_config.system_prompt]
+            .into_iter()
+            .flatten()
+            .filter(|custom| !custom.trim().is_empty())
+            .fold(base, |prompt, custom| format!("{}\n\n{}", prompt, custom))
+    }
+
+    /// Export summaries of the nodes most similar to this one in the search index,
+    /// when the manifest enables `related_context`
+    fn related_context(project: &Project, node_id: &str) -> String {
+        let k = project.manifest.related_context.unwrap_or(0);
+        if k == 0 {
+            return String::new();
+        }
+        let index = match load_index(&project.project_path) {
+            Ok(Some(index)) => index,
+            Ok(None) => return String::new(),
+            Err(e) => {
+                tracing::warn!("Skipping related context: {}", e);
+                return String::new();
+            }
+        };
+
+        let mut result = String::new();
+        for node in related_nodes(project, &index, node_id, k) {
+            result.push_str(&format!("### {} `{}`\n", node.name, node.file_path));
+            for export in &node.exports {
+                result.push_str(&format_export(export));
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    /// IDs of dependencies that would be injected as stub interfaces
+    /// (file-backed, with no generated code and no declared exports)
+    pub fn stubbed_dependencies(project: &Project, node_id: &str) -> Vec<String> {
+        Self::get_dependencies(project, node_id)
+            .into_iter()
+            .filter(|(dep, _)| {
+                dep.kind.has_file() && dep.generated_code.is_none() && dep.exports.is_empty()
+            })
+            .map(|(dep, _)| dep.id.clone())
+            .collect()
+    }
+
+    /// Get all nodes that this node depends on (incoming edges)
+    fn get_dependencies<'a>(project: &'a Project, node_id: &str) -> Vec<(&'a CodeNode, String)> {
+        let mut deps = Vec::new();
+
+        for edge in &project.edges {
+            // Node depends on source of incoming edge (edge points TO this node)
+            if edge.target == node_id {
+                if let Some(source_node) = project.find_node(&edge.source) {
+                    // Use the edge label, or default to "dependency" if empty
+                    let label = if edge.label.is_empty() {
+                        "dependency".to_string()
+                    } else {
+                        edge.label.clone()
+                    };
+                    deps.push((source_node, label));
+                }
+            }
+        }
+
+        deps
+    }
+}
+
+fn format_language(lang: &str) -> String {
+    match lang.to_lowercase().as_str() {
+        "typescript" => "TypeScript".to_string(),
+        "javascript" => "JavaScript".to_string(),
+        "python" => "Python".to_string(),
+        "rust" => "Rust".to_string(),
+        "go" => "Go".to_string(),
+        "java" => "Java".to_string(),
+        "csharp" => "C#".to_string(),
+        "cpp" => "C++".to_string(),
+        "ruby" => "Ruby".to_string(),
+        "php" => "PHP".to_string(),
+        "kotlin" => "Kotlin".to_string(),
+        "swift" => "Swift".to_string(),
+        "sql" => "SQL".to_string(),
+        "html" => "HTML".to_string(),
+        "css" => "CSS".to_string(),
+        "bash" => "Bash".to_string(),
+        _ => lang.to_string(),
+    }
+}
+
+/// Idiomatic conventions the model is asked to follow for a language
+fn language_conventions(lang: &Language) -> Option<&'static str> {
+    let conventions = match lang {
+        Language::TypeScript => {
+            "Use strict types, avoid `any`, and prefer named ES module exports."
+        }
+        Language::JavaScript => {
+            "Use modern ES modules, `const`/`let`, and JSDoc for public functions."
+        }
+        Language::Python => {
+            "Follow PEP 8, add type hints, and write docstrings for public functions."
+        }
+        Language::Rust => {
+            "Write idiomatic, safe Rust; return `Result` for fallible operations instead \
+             of panicking."
+        }
+        Language::Go => {
+            "Follow Effective Go: gofmt formatting, explicit error returns, and doc \
+             comments on exported names."
+        }
+        Language::Java => {
+            "Follow standard Java conventions: one public class per file matching the \
+             file name, and Javadoc on public members."
+        }
+        Language::CSharp => {
+            "Follow .NET naming conventions (PascalCase members), use nullable reference \
+             types, and XML doc comments."
+        }
+        Language::Cpp => {
+            "Use modern C++ (C++17 or later), RAII, smart pointers instead of raw owning \
+             pointers, and include guards or `#pragma once` in headers."
+        }
+        Language::Ruby => {
+            "Follow the community Ruby style guide: two-space indentation, snake_case \
+             methods, and YARD comments."
+        }
+        Language::Php => {
+            "Follow PSR-12, declare `strict_types=1`, and use typed properties and \
+             return types."
+        }
+        Language::Kotlin => {
+            "Follow the Kotlin coding conventions: prefer `val`, null safety over `!!`, \
+             and KDoc on public APIs."
+        }
+        Language::Swift => {
+            "Follow the Swift API Design Guidelines: value types where possible, \
+             optionals over sentinels, and `///` doc comments."
+        }
+        Language::Sql => {
+            "Use uppercase keywords, explicit column lists, and portable ANSI SQL unless \
+             a dialect is specified."
+        }
+        Language::Html => {
+            "Write semantic, accessible HTML5 with proper landmarks, labels, and alt \
+             text."
+        }
+        Language::Css => {
+            "Use class selectors over IDs, CSS custom properties for shared values, and \
+             a mobile-first layout."
+        }
+        Language::Bash => {
+            "Start with `#!/usr/bin/env bash` and `set -euo pipefail`, and quote all \
+             variable expansions."
+        }
+        Language::Custom(_) => return None,
+    };
+    Some(conventions)
+}
+
+/// A shared type's definition in the node's language, or another language's to
+/// translate when it has no variant for it
+fn format_shared_type(name: &str, shared_type: &SharedType, language: &Language) -> String {
+    let mut result = format!("### {}\n", name);
+    if !shared_type.description.is_empty() {
+        result.push_str(&format!("{}\n", shared_type.description));
+    }
+    match shared_type.variant(language) {
+        Some((variant, definition)) => {
+            if variant != language {
+                result.push_str(&format!("Translate this {} definition:\n", variant));
+            }
+            result.push_str(&format!("```{}\n{}\n```\n\n", variant, definition.trim_end()));
+        }
+        None => result.push('\n'),
+    }
+    result
+}
+
+fn format_export(export: &ExportSignature) -> String {
+    let mut result = format!("- {}", export.name);
+
+    if !export.type_signature.is_empty() {
+        result.push_str(&format!(": {}", export.type_signature));
+    }
+    if let Some(shared_type) = &export.shared_type {
+        result.push_str(&format!(" (shared type {})", shared_type));
+    }
+
+    result.push('\n');
+
+    if !export.description.is_empty() {
+        result.push_str(&format!("  {}\n", export.description));
+    }
+
+    result
+}
+
+/// The node's reference files, each in a code block, truncated to share
+/// `REFERENCE_FILES_BUDGET`. Files that can't be read are skipped with a warning.
+fn read_reference_files(project: &Project, node: &CodeNode) -> String {
+    let mut result = String::new();
+    let mut remaining = REFERENCE_FILES_BUDGET;
+
+    for file_path in &node.reference_files {
+        let contents = validate_path(&project.project_path, file_path)
+            .and_then(|path| std::fs::read_to_string(path).map_err(|e| e.to_string()));
+        let contents = match contents {
+            Ok(contents) => contents,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping reference file {} of node {}: {}",
+                    file_path,
+                    node.id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        result.push_str(&format!("### `{}`\n```\n", file_path));
+        let end = contents
+            .char_indices()
+            .nth(remaining)
+            .map_or(contents.len(), |(i, _)| i);
+        result.push_str(&contents[..end]);
+        if !contents[..end].ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str("```\n");
+        if end < contents.len() {
+            result.push_str("(truncated)\n");
+        }
+        result.push('\n');
+
+        remaining -= contents[..end].chars().count();
+        if remaining == 0 {
+            break;
+        }
+    }
+    result
+}
+
+/// Format a few-shot example as an input block and the output written for it
+fn format_example(number: usize, example: &PromptExample) -> String {
+    let mut result = format!("### Example {}", number);
+    if !example.description.is_empty() {
+        result.push_str(&format!(": {}", example.description));
+    }
+    result.push('\n');
+    for (label, text) in [("Input", &example.input), ("Output", &example.output)] {
+        result.push_str(&format!("{}:\n```\n{}", label, text));
+        if !text.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str("```\n");
+    }
+    result.push('\n');
+    result
+}
+
+/// Describe an external package dependency and the parts of its API to use
+fn format_package(dep_node: &CodeNode) -> String {
+    let Some(package) = &dep_node.package else {
+        return format!("### {}\n\n", dep_node.name);
+    };
+
+    let mut result = format!("### `{}` ({})\n", package.spec(), package.ecosystem);
+    if !package.docs.is_empty() {
+        result.push_str("Relevant API:\n");
+        result.push_str(package.docs.trim_end());
+        result.push('\n');
+    }
+    result.push('\n');
+    result
+}
+
+/// Describe a dependency that has neither generated code nor exports, clearly marked as a stub
+fn format_stub_interface(dep_node: &CodeNode) -> String {
+    let mut result = String::from("STUB INTERFACE (not yet generated, no declared exports):\n");
+    result.push_str(
+        "The real file does not exist yet. Infer a minimal interface from the specification below, \
+         keep imports from it to a minimum, and do not rely on any other symbols.\n",
+    );
+    result.push_str(&format!("- Name: {}\n", dep_node.name));
+    if !dep_node.purpose.is_empty() {
+        result.push_str(&format!("- Purpose: {}\n", dep_node.purpose));
+    }
+    if !dep_node.description.is_empty() {
+        result.push_str(&format!("- Description: {}\n", dep_node.description));
+    }
+    result.push_str("END STUB INTERFACE\n\n");
+    result
+}
+
+/// Parse the list out of a response to an `OutputSchema::list` request. Free text from a
+/// provider that ignored the schema is searched for the outermost JSON array.
+pub fn parse_json_list<T: DeserializeOwned>(content: &str, key: &str) -> Result<Vec<T>, String> {
+    let content = strip_code_blocks(content);
+    if let Ok(serde_json::Value::Object(mut object)) = serde_json::from_str(&content) {
+        if let Some(list) = object.remove(key) {
+            return serde_json::from_value(list).map_err(|e| e.to_string());
+        }
+    }
+
+    // Tolerate prose around the JSON array
+    let start = content.find('[').ok_or("No JSON array found")?;
+    let end = content.rfind(']').ok_or("No JSON array found")?;
+    if end < start {
+        return Err("No JSON array found".to_string());
+    }
+    serde_json::from_str(&content[start..=end]).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::CodeEdge;
+
+    #[test]
+    fn test_doc_node_prompt() {
+        let mut project = Project::new(String::new());
+        let api = CodeNode::new("Api".to_string(), "src/api.ts".to_string(), Language::TypeScript);
+        let mut readme = CodeNode::new(
+            "Readme".to_string(),
+            "README.md".to_string(),
+            Language::Custom("markdown".to_string()),
+        );
+        readme.kind = NodeKind::Doc;
+        let readme_id = readme.id.clone();
+        project.edges.push(CodeEdge::new(api.id.clone(), readme_id.clone(), String::new()));
+        project.nodes.extend([api, readme]);
+
+        let prompt = ContextBuilder::build_prompt(&project, &readme_id).unwrap();
+        assert!(prompt.starts_with("You are writing documentation."));
+        assert!(prompt.contains("## Files to document"));
+        assert!(!prompt.contains("you can import from"));
+
+        let system = ContextBuilder::build_system_prompt(
+            &project.manifest,
+            project.find_node(&readme_id).unwrap(),
+        );
+        assert!(system.contains("technical writer"));
+    }
+
+    #[test]
+    fn test_prompt_cache_breakpoints() {
+        let mut project = Project::new(String::new());
+        let mut types =
+            CodeNode::new("Types".to_string(), "src/types.ts".to_string(), Language::TypeScript);
+        types.generated_code = Some("export type Id = string;".to_string());
+        let a = CodeNode::new("A".to_string(), "src/a.ts".to_string(), Language::TypeScript);
+        let b = CodeNode::new("B".to_string(), "src/b.ts".to_string(), Language::TypeScript);
+        let (a_id, b_id) = (a.id.clone(), b.id.clone());
+        for id in [&a_id, &b_id] {
+            project.edges.push(CodeEdge::new(types.id.clone(), id.clone(), String::new()));
+        }
+        project.nodes.extend([types, a, b]);
+
+        let (a_prompt, a_breaks) =
+            ContextBuilder::build_prompt_with_breakpoints(&project, &a_id).unwrap();
+        let (b_prompt, b_breaks) =
+            ContextBuilder::build_prompt_with_breakpoints(&project, &b_id).unwrap();
+        assert_eq!(a_breaks.len(), 1);
+        assert!(a_prompt[..a_breaks[0]].ends_with("export type Id = string;\n```\n\n"));
+        // Dependents of the same node share the prompt up to the breakpoint
+        assert_eq!(a_prompt[..a_breaks[0]], b_prompt[..b_breaks[0]]);
+        assert_ne!(a_prompt, b_prompt);
+        assert_eq!(ContextBuilder::build_prompt(&project, &a_id).unwrap(), a_prompt);
+    }
+
+    #[test]
+    fn test_reference_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/spec.md"), "# API spec\n").unwrap();
+        let long = "x".repeat(REFERENCE_FILES_BUDGET + 10);
+        std::fs::write(dir.path().join("schema.sql"), &long).unwrap();
+
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.reference_files = vec![
+            "docs/spec.md".to_string(),
+            "../outside.md".to_string(),
+            "missing.md".to_string(),
+            "schema.sql".to_string(),
+        ];
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        let prompt = ContextBuilder::build_prompt(&project, &node_id).unwrap();
+        assert!(prompt.contains("### `docs/spec.md`\n```\n# API spec\n```\n"));
+        assert!(!prompt.contains("outside.md") && !prompt.contains("missing.md"));
+        assert!(prompt.contains("(truncated)"));
+        assert!(!prompt.contains(&long));
+    }
+
+    #[test]
+    fn test_related_context() {
+        use crate::graph::model::EmbeddingConfig;
+        use crate::graph::search::{save_index, IndexEntry, SearchIndex};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        project.manifest.related_context = Some(1);
+        let mut entries = std::collections::HashMap::new();
+        let vectors = [("a", [1.0, 0.0]), ("b", [0.9, 0.1]), ("c", [0.0, 1.0]), ("d", [1.0, 0.0])];
+        for (name, vector) in vectors {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.id = name.to_string();
+            node.exports = vec![ExportSignature {
+                name: format!("{}Export", name),
+                ..Default::default()
+            }];
+            project.nodes.push(node);
+            let entry = IndexEntry {
+                hash: String::new(),
+                vector: vector.to_vec(),
+            };
+            entries.insert(name.to_string(), entry);
+        }
+        // d is a dependency, so it's already in the prompt
+        project.edges.push(CodeEdge::new("d".to_string(), "a".to_string(), String::new()));
+        let index = SearchIndex {
+            embedding: EmbeddingConfig::for_manifest(&project.manifest),
+            entries,
+        };
+        save_index(&project.project_path, &index).unwrap();
+
+        let prompt = ContextBuilder::build_prompt(&project, "a").unwrap();
+        assert!(prompt.contains("## Related modules"));
+        assert!(prompt.contains("### b `b.ts`\n- bExport\n"));
+        assert!(!prompt.contains("cExport"));
+    }
+
+    #[test]
+    fn test_prompt_snippets() {
+        let mut project = Project::new(String::new());
+        project.set_snippet("auth model", "Sessions are JWTs.\n".to_string());
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.snippets = vec!["auth model".to_string(), "deleted".to_string()];
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        let prompt = ContextBuilder::build_prompt(&project, &node_id).unwrap();
+        assert!(prompt.contains("## Shared context\n\n### auth model\nSessions are JWTs.\n\n"));
+        assert!(!prompt.contains("deleted"));
+    }
+
+    #[test]
+    fn test_prompt_shared_types() {
+        let mut project = Project::new(String::new());
+        let user = SharedType {
+            description: "A signed-up user".to_string(),
+            variants: [(Language::Rust, "pub struct User { pub id: String }".to_string())].into(),
+        };
+        project.set_shared_type("User", user);
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.exports = vec![ExportSignature {
+            name: "currentUser".to_string(),
+            shared_type: Some("User".to_string()),
+            ..Default::default()
+        }];
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        let prompt = ContextBuilder::build_prompt(&project, &node_id).unwrap();
+        assert!(prompt.contains(
+            "### User\nA signed-up user\nTranslate this rust definition:\n\
+             ```rust\npub struct User { pub id: String }\n```\n"
+        ));
+        assert!(prompt.contains("- currentUser (shared type User)\n"));
+    }
+
+    #[test]
+    fn test_project_system_prompt() {
+        let mut manifest = ProjectManifest::default();
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let base = ContextBuilder::build_system_prompt(&manifest, &node);
+
+        manifest.system_prompt = Some("Use named exports only.".to_string());
+        node.llm_config.system_prompt = Some("Never throw; return a Result.".to_string());
+        let system = ContextBuilder::build_system_prompt(&manifest, &node);
+        assert_eq!(
+            system,
+            format!("{}\n\nUse named exports only.\n\nNever throw; return a Result.", base)
+        );
+
+        // Blank prompts add nothing
+        manifest.system_prompt = Some("  ".to_string());
+        node.llm_config.system_prompt = None;
+        assert_eq!(ContextBuilder::build_system_prompt(&manifest, &node), base);
+    }
+
+    #[test]
+    fn test_prompt_examples() {
+        let mut project = Project::new(String::new());
+        project.manifest.examples.push(PromptExample {
+            description: "Logging".to_string(),
+            input: "Load the config".to_string(),
+            output: "log.info(\"loading config\");".to_string(),
+        });
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.llm_config.examples.push(PromptExample {
+            input: "Parse a number".to_string(),
+            output: "throw new ParseError(input);\n".to_string(),
+            ..Default::default()
+        });
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        let prompt = ContextBuilder::build_prompt(&project, &node_id).unwrap();
+        assert!(prompt.contains("### Example 1: Logging\nInput:\n```\nLoad the config\n```\n"));
+        assert!(prompt.contains("### Example 2\n"));
+        assert!(prompt.contains("Output:\n```\nthrow new ParseError(input);\n```\n"));
+        let examples_end = prompt.find("### Example 2").unwrap();
+        assert!(examples_end < prompt.find("Generate the complete").unwrap());
+    }
+}
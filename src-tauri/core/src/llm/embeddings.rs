@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::graph::model::{EmbeddingConfig, LLMProvider, ProjectManifest};
+
+use super::provider::LLMError;
+use super::redaction::Redactor;
+
+const OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+/// Takes the same requests and returns the same responses as OpenAI's
+const MISTRAL_EMBEDDINGS_URL: &str = "https://api.mistral.ai/v1/embeddings";
+const OLLAMA_EMBED_URL: &str = "http://localhost:11434/api/embed";
+/// Size of the mock provider's bag-of-words vectors
+const MOCK_DIMENSIONS: usize = 64;
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbeddingResponse {
+    data: Vec<OpenAIEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIError {
+    error: OpenAIErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIErrorDetail {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Bag-of-words vector for the mock provider: each word counts towards a bucket
+/// picked by its hash, so texts sharing words come out similar
+fn mock_embedding(input: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; MOCK_DIMENSIONS];
+    for word in input.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let hash = Sha256::digest(word.to_lowercase().as_bytes());
+        vector[hash[0] as usize % MOCK_DIMENSIONS] += 1.0;
+    }
+    vector
+}
+
+/// Embed each input with the configured model, returning one vector per input in order.
+/// Nothing is sent if the manifest's provider policy doesn't allow the model, and
+/// inputs for hosted providers are redacted like prompts.
+pub async fn embed(
+    config: &EmbeddingConfig,
+    api_key: Option<&str>,
+    manifest: &ProjectManifest,
+    inputs: &[String],
+) -> Result<Vec<Vec<f32>>, LLMError> {
+    if let Some(policy) = &manifest.policy {
+        policy
+            .check(&config.provider, &config.model)
+            .map_err(|reason| LLMError::RequestFailed(format!("Not sent: {}", reason)))?;
+    }
+    if inputs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let redacted: Vec<String>;
+    let inputs = match Redactor::for_manifest(manifest) {
+        Some(redactor) if config.provider.is_hosted() => {
+            let redactor =
+                redactor.map_err(|e| LLMError::RequestFailed(format!("Not sent: {}", e)))?;
+            redacted = inputs.iter().map(|input| redactor.redact(input)).collect();
+            &redacted[..]
+        }
+        _ => inputs,
+    };
+    let client = super::http::client();
+    let request = EmbeddingRequest {
+        model: &config.model,
+        input: inputs,
+    };
+
+    let vectors = match config.provider {
+        LLMProvider::Anthropic => {
+            return Err(LLMError::RequestFailed(
+                "Anthropic has no embeddings endpoint; use an OpenAI, Mistral or Ollama embedding model"
+                    .to_string(),
+            ))
+        }
+        LLMProvider::OpenAI | LLMProvider::Mistral => {
+            let api_key = api_key.ok_or(LLMError::InvalidApiKey)?;
+            let url = if config.provider == LLMProvider::Mistral {
+                MISTRAL_EMBEDDINGS_URL
+            } else {
+                OPENAI_EMBEDDINGS_URL
+            };
+            let response = client
+                .post(url)
+                .header("Authorization", format!("Bearer {}", api_key))
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+
+            let status = response.status();
+            tracing::debug!(
+                "{} embeddings for {} returned {}",
+                config.provider.as_str(),
+                config.model,
+                status
+            );
+            if status == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(LLMError::InvalidApiKey);
+            }
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(LLMError::RateLimited);
+            }
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                if let Ok(error) = serde_json::from_str::<OpenAIError>(&error_text) {
+                    return Err(LLMError::RequestFailed(error.error.message));
+                }
+                return Err(LLMError::RequestFailed(format!(
+                    "HTTP {}: {}",
+                    status, error_text
+                )));
+            }
+
+            let mut data = response
+                .json::<OpenAIEmbeddingResponse>()
+                .await
+                .map_err(|e| LLMError::ParseError(e.to_string()))?
+                .data;
+            data.sort_by_key(|d| d.index);
+            data.into_iter().map(|d| d.embedding).collect::<Vec<_>>()
+        }
+        LLMProvider::Ollama => {
+            let response = client
+                .post(OLLAMA_EMBED_URL)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| {
+                    if e.is_connect() {
+                        LLMError::NetworkError(
+                            "Cannot connect to Ollama. Make sure Ollama is running.".to_string(),
+                        )
+                    } else {
+                        LLMError::NetworkError(e.to_string())
+                    }
+                })?;
+
+            let status = response.status();
+            tracing::debug!("Ollama embeddings for {} returned {}", config.model, status);
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Err(LLMError::ModelNotFound(config.model.clone()));
+            }
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(LLMError::RequestFailed(format!(
+                    "HTTP {}: {}",
+                    status, error_text
+                )));
+            }
+
+            response
+                .json::<OllamaEmbedResponse>()
+                .await
+                .map_err(|e| LLMError::ParseError(e.to_string()))?
+                .embeddings
+        }
+        LLMProvider::Local => {
+            return Err(LLMError::RequestFailed(
+                "Local GGUF models can't embed; use an Ollama or mock embedding model"
+                    .to_string(),
+            ))
+        }
+        LLMProvider::Mock => inputs.iter().map(|input| mock_embedding(input)).collect(),
+    };
+
+    if vectors.len() != inputs.len() {
+        return Err(LLMError::ParseError(format!(
+            "Expected {} embeddings, got {}",
+            inputs.len(),
+            vectors.len()
+        )));
+    }
+    Ok(vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::RedactionRules;
+
+    #[tokio::test]
+    async fn test_embed_redaction() {
+        let manifest = ProjectManifest {
+            redaction: Some(RedactionRules {
+                patterns: vec!["(".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let inputs = vec!["UserService".to_string()];
+        let config = |provider: LLMProvider| EmbeddingConfig {
+            provider,
+            model: "mock".to_string(),
+        };
+
+        // Invalid rules block hosted embeddings before anything is sent
+        let error = embed(&config(LLMProvider::OpenAI), Some("sk-key"), &manifest, &inputs)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Not sent"));
+        let vectors = embed(&config(LLMProvider::Mock), None, &manifest, &inputs).await.unwrap();
+        assert_eq!(vectors, vec![mock_embedding("UserService")]);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::graph::model::{Language, NodeKind};
+
+/// A node's file contents pulled out of a model's response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedCode {
+    pub code: String,
+    /// Prose (and any other code blocks) around the code, left out of the file
+    pub note: Option<String>,
+}
+
+/// An opening or closing fence line
+pub(crate) struct Fence<'a> {
+    marker: char,
+    len: usize,
+    info: &'a str,
+}
+
+/// A fenced block, with byte offsets into the trimmed response
+struct Block<'a> {
+    /// The first word of the info string, lowercased
+    hint: String,
+    body: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Strip markdown code blocks from LLM output, keeping the longest block when the
+/// response has several. Responses without a block are returned trimmed.
+pub fn strip_code_blocks(content: &str) -> String {
+    extract(content, None).code
+}
+
+/// Extract a node's file contents from a response. The block whose language hint
+/// matches the node's language wins over the rest, and everything outside it is
+/// returned as a note. Doc nodes keep the whole response unless it is wrapped in
+/// a markdown block, since fences in documentation are part of the content.
+pub fn extract_code(content: &str, language: &Language, kind: NodeKind) -> ExtractedCode {
+    if kind == NodeKind::Doc || is_markdown(&language.to_string()) {
+        return extract_markdown(content);
+    }
+    extract(content, Some(language))
+}
+
+fn extract(content: &str, language: Option<&Language>) -> ExtractedCode {
+    let content = content.trim();
+    let blocks = parse_blocks(content);
+    let dominant = blocks.iter().max_by_key(|block| {
+        let rank = match language {
+            Some(language) if hint_matches(&block.hint, language) => 2,
+            _ if block.hint.is_empty() => 1,
+            Some(_) => 0,
+            None => 1,
+        };
+        (rank, block.body.trim().len())
+    });
+
+    match dominant {
+        Some(block) => split_out(content, block),
+        None => ExtractedCode { code: content.to_string(), note: None },
+    }
+}
+
+/// Unwrap a document only when a single block holds all of it: a markdown block with
+/// at most a paragraph of prose on either side, or a bare block spanning the whole
+/// response. Markdown examples inside a document stay where they are.
+fn extract_markdown(content: &str) -> ExtractedCode {
+    let content = content.trim();
+    let blocks = parse_blocks(content);
+    let wrapper = match blocks.as_slice() {
+        [block] if is_markdown(&block.hint) => {
+            let around = [&content[..block.start], &content[block.end..]];
+            around.iter().all(|text| is_prose(text)).then_some(block)
+        }
+        [block] if block.hint.is_empty() && block.start == 0 && block.end == content.len() => {
+            Some(block)
+        }
+        _ => None,
+    };
+
+    match wrapper {
+        Some(block) => split_out(content, block),
+        None => ExtractedCode { code: content.to_string(), note: None },
+    }
+}
+
+/// Keep the block's body as the code and the rest of the response as the note
+fn split_out(content: &str, block: &Block) -> ExtractedCode {
+    let prose: Vec<&str> = [&content[..block.start], &content[block.end..]]
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    ExtractedCode {
+        code: block.body.trim_start_matches(['\n', '\r']).trim_end().to_string(),
+        note: (!prose.is_empty()).then(|| prose.join("\n\n")),
+    }
+}
+
+/// Find the top-level fenced blocks. Inside a block, a fence with an info string
+/// opens a nested block that the next bare fence closes, so code samples inside a
+/// markdown block don't end it early. A block left open runs to the end, as in a
+/// truncated response.
+fn parse_blocks(content: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    // (opening fence, block start, body start, nesting depth)
+    let mut open: Option<(Fence, usize, usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(fence) = parse_fence(line) else {
+            continue;
+        };
+
+        match open.as_mut() {
+            None => open = Some((fence, line_start, offset, 0)),
+            Some((opening, _, _, depth)) => {
+                if fence.marker != opening.marker {
+                    continue;
+                }
+                if !fence.info.is_empty() {
+                    *depth += 1;
+                } else if *depth > 0 {
+                    *depth -= 1;
+                } else if fence.len >= opening.len {
+                    let (opening, start, body_start, _) = open.take().unwrap();
+                    blocks.push(Block {
+                        hint: hint_of(opening.info),
+                        body: &content[body_start..line_start],
+                        start,
+                        end: offset,
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some((opening, start, body_start, _)) = open {
+        blocks.push(Block {
+            hint: hint_of(opening.info),
+            body: &content[body_start.min(content.len())..],
+            start,
+            end: content.len(),
+        });
+    }
+    blocks
+}
+
+/// Parse a fence line: up to three spaces of indentation, then three or more
+/// backticks or tildes and an optional info string
+pub(crate) fn parse_fence(line: &str) -> Option<Fence<'_>> {
+    let line = line.trim_end();
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.len() - line.trim_start_matches(marker).len();
+    if len < 3 {
+        return None;
+    }
+    let info = line[len..].trim();
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    Some(Fence { marker, len, info })
+}
+
+/// Whether text around a block reads as a remark about it ("Here's the README:")
+/// rather than part of the document
+fn is_prose(text: &str) -> bool {
+    let text = text.trim();
+    !text.contains("\n\n") && !text.starts_with('#')
+}
+
+fn hint_of(info: &str) -> String {
+    info.split_whitespace().next().unwrap_or("").to_lowercase()
+}
+
+fn is_markdown(hint: &str) -> bool {
+    matches!(hint.to_lowercase().as_str(), "markdown" | "md")
+}
+
+/// Whether a block's language hint names the language, by name, file extension or
+/// a common alias
+fn hint_matches(hint: &str, language: &Language) -> bool {
+    if hint.is_empty() {
+        return false;
+    }
+    if hint.eq_ignore_ascii_case(&language.to_string())
+        || language.default_extensions().contains(&hint)
+    {
+        return true;
+    }
+    let aliases: &[&str] = match language {
+        Language::TypeScript => &["typescriptreact"],
+        Language::JavaScript => &["javascriptreact", "node"],
+        Language::Python => &["python3", "py3"],
+        Language::Go => &["golang"],
+        Language::CSharp => &["c#", "cs"],
+        Language::Cpp => &["c++", "c"],
+        Language::Bash => &["shell", "zsh", "console"],
+        Language::Sql => &["postgresql", "postgres", "mysql", "sqlite"],
+        _ => &[],
+    };
+    aliases.contains(&hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_block() {
+        assert_eq!(strip_code_blocks("```typescript\nconst a = 1;\n```"), "const a = 1;");
+        assert_eq!(strip_code_blocks("  ```\nx\n```\n"), "x");
+        assert_eq!(strip_code_blocks("no fences here\n"), "no fences here");
+    }
+
+    #[test]
+    fn test_prose_and_multiple_blocks() {
+        let response = "Here is the module:\n\n```bash\nnpm install zod\n```\n\n\
+                        ```ts\nimport { z } from 'zod';\n\nexport const Id = z.string();\n```\n\n\
+                        Let me know if you need changes.";
+        let extracted = extract_code(response, &Language::TypeScript, NodeKind::Code);
+        assert_eq!(extracted.code, "import { z } from 'zod';\n\nexport const Id = z.string();");
+        let note = extracted.note.unwrap();
+        assert!(note.starts_with("Here is the module:\n\n```bash\nnpm install zod\n```"));
+        assert!(note.ends_with("Let me know if you need changes."));
+    }
+
+    #[test]
+    fn test_truncated_block_runs_to_end() {
+        let extracted =
+            extract_code("```python\ndef f():\n    return 1\n", &Language::Python, NodeKind::Code);
+        assert_eq!(extracted.code, "def f():\n    return 1");
+        assert_eq!(extracted.note, None);
+    }
+
+    #[test]
+    fn test_doc_nodes_keep_fences() {
+        let markdown = Language::Custom("markdown".to_string());
+        let readme = "# Usage\n\n```bash\nnpm start\n```\n\nThen open the app.";
+        let extracted = extract_code(readme, &markdown, NodeKind::Doc);
+        assert_eq!(extracted.code, readme);
+
+        let wrapped = format!("Here's the README:\n\n````markdown\n{}\n````", readme);
+        let extracted = extract_code(&wrapped, &markdown, NodeKind::Doc);
+        assert_eq!(extracted.code, readme);
+        assert_eq!(extracted.note.as_deref(), Some("Here's the README:"));
+
+        // Same-length fences nest inside a markdown block
+        let nested = format!("```md\n{}\n```", readme);
+        assert_eq!(extract_code(&nested, &markdown, NodeKind::Doc).code, readme);
+
+        // A markdown example inside the document isn't taken for a wrapper
+        let example = "# Headers\n\nStart each page with a title:\n\n```md\n# Title\n```\n\n\
+                       Then a summary.";
+        let extracted = extract_code(example, &markdown, NodeKind::Doc);
+        assert_eq!(extracted.code, example);
+        assert_eq!(extracted.note, None);
+        let mixed = format!("{}\n\n```md\n## Usage\n```", readme);
+        assert_eq!(extract_code(&mixed, &markdown, NodeKind::Doc).code, mixed);
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use reqwest::{Client, Proxy};
+
+/// Proxy used when the settings don't set one
+pub const PROXY_ENV: &str = "NEEDLEPOINT_PROXY";
+
+/// Proxy set in the app's settings, taking precedence over the environment
+static PROXY_URL: RwLock<Option<String>> = RwLock::new(None);
+
+/// Route provider requests through `url` (`http://`, `https://`, `socks5://` or
+/// `socks5h://`), or stop using the settings proxy with `None`
+pub fn set_proxy(url: Option<String>) -> Result<(), String> {
+    let url = url.filter(|u| !u.trim().is_empty());
+    if let Some(url) = &url {
+        Proxy::all(url.as_str()).map_err(|e| format!("Invalid proxy URL '{}': {}", url, e))?;
+    }
+    *PROXY_URL.write().unwrap_or_else(|e| e.into_inner()) = url;
+    Ok(())
+}
+
+/// The proxy provider requests go through: the settings proxy, else `NEEDLEPOINT_PROXY`.
+/// Without either, the standard `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY` variables apply.
+pub fn proxy_url() -> Option<String> {
+    PROXY_URL
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .or_else(|| std::env::var(PROXY_ENV).ok().filter(|v| !v.is_empty()))
+}
+
+/// Idle connections kept open per host, enough for a wide wave against one provider
+const POOL_MAX_IDLE_PER_HOST: usize = 32;
+/// How long an idle pooled connection is kept before it's closed
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// TCP keep-alive interval, so proxies and NATs don't drop pooled connections
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// The shared client and the proxy it was built with
+static CLIENT: Mutex<Option<(Option<String>, Client)>> = Mutex::new(None);
+
+fn build_client(proxy_url: Option<&str>) -> reqwest::Result<Client> {
+    let mut builder = Client::builder()
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE);
+    if let Some(url) = proxy_url {
+        // Ollama and other local servers are reached directly
+        let no_proxy = reqwest::NoProxy::from_string("localhost,127.0.0.1,::1");
+        builder = builder.proxy(Proxy::all(url)?.no_proxy(no_proxy));
+    }
+    builder.build()
+}
+
+/// HTTP client for provider requests, with the configured proxy applied. The client
+/// (and its connection pool) is shared by all providers and rebuilt only when the
+/// proxy changes, so a wave of generations reuses connections instead of opening a
+/// TLS session per node.
+pub fn client() -> Client {
+    let proxy_url = proxy_url();
+    let mut cached = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((url, client)) = cached.as_ref() {
+        if *url == proxy_url {
+            return client.clone();
+        }
+    }
+
+    let client = build_client(proxy_url.as_deref()).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring proxy {:?}: {}", proxy_url, e);
+        build_client(None).unwrap_or_default()
+    });
+    *cached = Some((proxy_url, client.clone()));
+    client
+}
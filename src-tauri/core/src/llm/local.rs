@@ -0,0 +1,405 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+/// How a model expects a conversation to be laid out, told apart by the special
+/// tokens its tokenizer knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "local-inference"), allow(dead_code))]
+enum ChatTemplate {
+    /// Qwen, Yi and most recent fine-tunes
+    ChatMl,
+    Llama3,
+    /// Llama 2, Mistral and CodeLlama
+    Instruct,
+}
+
+#[cfg_attr(not(feature = "local-inference"), allow(dead_code))]
+impl ChatTemplate {
+    fn detect(has_token: impl Fn(&str) -> bool) -> Self {
+        if has_token("<|im_start|>") {
+            ChatTemplate::ChatMl
+        } else if has_token("<|start_header_id|>") {
+            ChatTemplate::Llama3
+        } else {
+            ChatTemplate::Instruct
+        }
+    }
+
+    fn format(self, system: Option<&str>, prompt: &str) -> String {
+        match self {
+            ChatTemplate::ChatMl => {
+                let mut text = String::new();
+                if let Some(system) = system {
+                    text.push_str(&format!("<|im_start|>system\n{}<|im_end|>\n", system));
+                }
+                text.push_str(&format!(
+                    "<|im_start|>user\n{}<|im_end|>\n<|im_start|>assistant\n",
+                    prompt
+                ));
+                text
+            }
+            ChatTemplate::Llama3 => {
+                let mut text = String::new();
+                if let Some(system) = system {
+                    text.push_str(&format!(
+                        "<|start_header_id|>system<|end_header_id|>\n\n{}<|eot_id|>",
+                        system
+                    ));
+                }
+                text.push_str(&format!(
+                    "<|start_header_id|>user<|end_header_id|>\n\n{}<|eot_id|>\
+                     <|start_header_id|>assistant<|end_header_id|>\n\n",
+                    prompt
+                ));
+                text
+            }
+            ChatTemplate::Instruct => match system {
+                Some(system) => {
+                    format!("[INST] <<SYS>>\n{}\n<</SYS>>\n\n{} [/INST]", system, prompt)
+                }
+                None => format!("[INST] {} [/INST]", prompt),
+            },
+        }
+    }
+}
+
+/// The tokenizer that goes with a GGUF model: `<model>.tokenizer.json`, or
+/// `tokenizer.json` in the same directory
+fn tokenizer_path(model_path: &Path) -> Option<PathBuf> {
+    let beside = model_path.with_extension("tokenizer.json");
+    if beside.is_file() {
+        return Some(beside);
+    }
+    let shared = model_path.parent()?.join("tokenizer.json");
+    shared.is_file().then_some(shared)
+}
+
+/// Set when the request is dropped, as it is on timeout, so the blocking generation
+/// stops and lets go of the model
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Runs a GGUF model file in-process on the CPU, so generation works offline without
+/// Ollama. The model is the path to the file; Llama and Qwen2 architectures are
+/// supported. Needs the `local-inference` feature.
+pub struct LocalProvider {
+    model_path: PathBuf,
+}
+
+impl LocalProvider {
+    pub fn new(model_path: String) -> Self {
+        Self {
+            model_path: PathBuf::from(model_path),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for LocalProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        if !self.model_path.is_file() {
+            return Err(LLMError::ModelNotFound(
+                self.model_path.display().to_string(),
+            ));
+        }
+        let tokenizer = tokenizer_path(&self.model_path).ok_or_else(|| {
+            LLMError::RequestFailed(format!(
+                "No tokenizer.json found next to {}",
+                self.model_path.display()
+            ))
+        })?;
+
+        // Like Ollama's JSON mode, structured output is the schema spelled out in the
+        // prompt, without constrained decoding
+        let mut request = request;
+        if let Some(schema) = &request.output_schema {
+            request.prompt = format!(
+                "{}\n\nRespond with a JSON object matching this JSON Schema:\n{}",
+                request.prompt, schema.schema
+            );
+        }
+
+        let model_path = self.model_path.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _cancel = CancelOnDrop(cancelled.clone());
+        tokio::task::spawn_blocking(move || {
+            inference::generate(&model_path, &tokenizer, request, &cancelled)
+        })
+        .await
+        .map_err(|e| LLMError::RequestFailed(e.to_string()))?
+    }
+
+    fn name(&self) -> &'static str {
+        "Local"
+    }
+
+    fn is_configured(&self) -> bool {
+        self.model_path.is_file()
+    }
+}
+
+#[cfg(feature = "local-inference")]
+mod inference {
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    use candle_core::quantized::gguf_file;
+    use candle_core::{Device, Tensor};
+    use candle_transformers::generation::LogitsProcessor;
+    use candle_transformers::models::{quantized_llama, quantized_qwen2};
+    use tokenizers::Tokenizer;
+
+    use super::ChatTemplate;
+    use crate::llm::provider::{GenerationRequest, GenerationResponse, LLMError};
+
+    /// Output tokens when the request doesn't set a limit
+    const DEFAULT_MAX_TOKENS: usize = 4096;
+
+    enum Weights {
+        Llama(quantized_llama::ModelWeights),
+        Qwen2(quantized_qwen2::ModelWeights),
+    }
+
+    impl Weights {
+        fn forward(&mut self, input: &Tensor, position: usize) -> candle_core::Result<Tensor> {
+            match self {
+                Weights::Llama(model) => model.forward(input, position),
+                Weights::Qwen2(model) => model.forward(input, position),
+            }
+        }
+    }
+
+    struct LoadedModel {
+        path: PathBuf,
+        weights: Weights,
+        tokenizer: Tokenizer,
+        template: ChatTemplate,
+        /// Most prompt plus output tokens the model can attend to
+        context_length: usize,
+    }
+
+    /// The last model loaded, kept so generating node after node doesn't read the file
+    /// again. Requests take turns with it.
+    static LOADED: Mutex<Option<LoadedModel>> = Mutex::new(None);
+
+    /// Tokens that end the model's turn
+    fn stop_tokens(template: ChatTemplate) -> &'static [&'static str] {
+        match template {
+            ChatTemplate::ChatMl => &["<|im_end|>", "<|endoftext|>"],
+            ChatTemplate::Llama3 => &["<|eot_id|>", "<|end_of_text|>"],
+            ChatTemplate::Instruct => &["</s>"],
+        }
+    }
+
+    fn failed(e: impl std::fmt::Display) -> LLMError {
+        LLMError::RequestFailed(e.to_string())
+    }
+
+    fn load(model_path: &Path, tokenizer_path: &Path) -> Result<LoadedModel, LLMError> {
+        tracing::info!("Loading local model {}", model_path.display());
+        let mut file = std::fs::File::open(model_path).map_err(failed)?;
+        let content = gguf_file::Content::read(&mut file)
+            .map_err(|e| failed(format!("Not a GGUF file: {}", e)))?;
+        let architecture = content
+            .metadata
+            .get("general.architecture")
+            .and_then(|v| v.to_string().ok())
+            .cloned()
+            .unwrap_or_default();
+        let context_length = content
+            .metadata
+            .get(&format!("{}.context_length", architecture))
+            .and_then(|v| v.to_u32().ok())
+            .map(|n| n as usize);
+
+        let device = Device::Cpu;
+        let (weights, context_length) = match architecture.as_str() {
+            "llama" => (
+                Weights::Llama(
+                    quantized_llama::ModelWeights::from_gguf(content, &mut file, &device)
+                        .map_err(failed)?,
+                ),
+                // Its rotary embeddings only cover this many positions
+                context_length
+                    .unwrap_or(quantized_llama::MAX_SEQ_LEN)
+                    .min(quantized_llama::MAX_SEQ_LEN),
+            ),
+            "qwen2" => (
+                Weights::Qwen2(
+                    quantized_qwen2::ModelWeights::from_gguf(content, &mut file, &device)
+                        .map_err(failed)?,
+                ),
+                context_length.unwrap_or(DEFAULT_MAX_TOKENS),
+            ),
+            other => {
+                return Err(failed(format!(
+                    "Unsupported model architecture '{}'; use a Llama or Qwen2 GGUF model",
+                    other
+                )))
+            }
+        };
+
+        let tokenizer = Tokenizer::from_file(tokenizer_path)
+            .map_err(|e| failed(format!("Invalid tokenizer: {}", e)))?;
+        let template = ChatTemplate::detect(|token| tokenizer.token_to_id(token).is_some());
+        Ok(LoadedModel {
+            path: model_path.to_path_buf(),
+            weights,
+            tokenizer,
+            template,
+            context_length,
+        })
+    }
+
+    pub fn generate(
+        model_path: &Path,
+        tokenizer_path: &Path,
+        request: GenerationRequest,
+        cancelled: &AtomicBool,
+    ) -> Result<GenerationResponse, LLMError> {
+        let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+        if loaded.as_ref().map(|m| m.path.as_path()) != Some(model_path) {
+            // Free the previous model before reading the next
+            *loaded = None;
+            *loaded = Some(load(model_path, tokenizer_path)?);
+        }
+        let model = loaded.as_mut().expect("model was just loaded");
+
+        let text = model
+            .template
+            .format(request.system_prompt.as_deref(), &request.prompt);
+        let prompt_tokens = model
+            .tokenizer
+            .encode(text, true)
+            .map_err(failed)?
+            .get_ids()
+            .to_vec();
+        if prompt_tokens.len() >= model.context_length {
+            return Err(failed(format!(
+                "Prompt is {} tokens, more than the model's context of {}",
+                prompt_tokens.len(),
+                model.context_length
+            )));
+        }
+        let max_tokens = request
+            .max_tokens
+            .map_or(DEFAULT_MAX_TOKENS, |n| n as usize)
+            .min(model.context_length - prompt_tokens.len());
+        let stop: Vec<u32> = stop_tokens(model.template)
+            .iter()
+            .filter_map(|token| model.tokenizer.token_to_id(token))
+            .collect();
+
+        let mut sampler = LogitsProcessor::new(
+            request.seed.unwrap_or_default(),
+            request.temperature.map(f64::from),
+            None,
+        );
+        let device = Device::Cpu;
+        let mut output = Vec::new();
+        let mut input = prompt_tokens.clone();
+        let mut position = 0;
+        while output.len() < max_tokens {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(failed("Cancelled"));
+            }
+            let tensor = Tensor::new(input.as_slice(), &device)
+                .and_then(|t| t.unsqueeze(0))
+                .map_err(failed)?;
+            let logits = model
+                .weights
+                .forward(&tensor, position)
+                .and_then(|l| l.squeeze(0))
+                .map_err(failed)?;
+            position += input.len();
+            let next = sampler.sample(&logits).map_err(failed)?;
+            if stop.contains(&next) {
+                break;
+            }
+            output.push(next);
+            input = vec![next];
+        }
+
+        let content = model.tokenizer.decode(&output, true).map_err(failed)?;
+        let input_tokens = prompt_tokens.len() as u32;
+        let output_tokens = output.len() as u32;
+        Ok(GenerationResponse {
+            content,
+            model: model_path.display().to_string(),
+            tokens_used: Some(input_tokens + output_tokens),
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(output_tokens),
+            cached_input_tokens: None,
+        })
+    }
+}
+
+#[cfg(not(feature = "local-inference"))]
+mod inference {
+    use std::path::Path;
+    use std::sync::atomic::AtomicBool;
+
+    use crate::llm::provider::{GenerationRequest, GenerationResponse, LLMError};
+
+    pub fn generate(
+        _model_path: &Path,
+        _tokenizer_path: &Path,
+        _request: GenerationRequest,
+        _cancelled: &AtomicBool,
+    ) -> Result<GenerationResponse, LLMError> {
+        Err(LLMError::RequestFailed(
+            "This build can't run local models; rebuild with the local-inference feature"
+                .to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_model_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("coder-q4.gguf");
+        std::fs::write(&model, b"GGUF").unwrap();
+        assert!(LocalProvider::new(model.to_string_lossy().to_string()).is_configured());
+        assert!(!LocalProvider::new("missing.gguf".to_string()).is_configured());
+
+        assert_eq!(tokenizer_path(&model), None);
+        std::fs::write(dir.path().join("tokenizer.json"), "{}").unwrap();
+        assert_eq!(
+            tokenizer_path(&model),
+            Some(dir.path().join("tokenizer.json"))
+        );
+        std::fs::write(dir.path().join("coder-q4.tokenizer.json"), "{}").unwrap();
+        assert_eq!(
+            tokenizer_path(&model),
+            Some(dir.path().join("coder-q4.tokenizer.json"))
+        );
+
+        let chat_ml = ChatTemplate::detect(|t| t == "<|im_start|>");
+        assert_eq!(chat_ml, ChatTemplate::ChatMl);
+        assert_eq!(
+            chat_ml.format(Some("Be terse"), "Write a.ts"),
+            "<|im_start|>system\nBe terse<|im_end|>\n\
+             <|im_start|>user\nWrite a.ts<|im_end|>\n<|im_start|>assistant\n"
+        );
+        let instruct = ChatTemplate::detect(|_| false);
+        assert_eq!(
+            instruct.format(None, "Write a.ts"),
+            "[INST] Write a.ts [/INST]"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+const MISTRAL_CHAT_URL: &str = "https://api.mistral.ai/v1/chat/completions";
+const MISTRAL_FIM_URL: &str = "https://api.mistral.ai/v1/fim/completions";
+const MISTRAL_MODELS_URL: &str = "https://api.mistral.ai/v1/models";
+
+#[derive(Debug, Serialize)]
+struct MistralChatRequest {
+    model: String,
+    messages: Vec<MistralMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<MistralTool>,
+    /// `"any"` forces a call to one of `tools`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'static str>,
+}
+
+/// Fill-in-the-middle: the model writes what goes between `prompt` and `suffix`
+#[derive(Debug, Serialize)]
+struct MistralFimRequest {
+    model: String,
+    prompt: String,
+    suffix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u64>,
+    /// Stop once the closing fence is written
+    stop: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct MistralTool {
+    #[serde(rename = "type")]
+    tool_type: &'static str,
+    function: MistralFunction,
+}
+
+#[derive(Debug, Serialize)]
+struct MistralFunction {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct MistralMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralResponse {
+    choices: Vec<MistralChoice>,
+    model: String,
+    usage: MistralUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralChoice {
+    message: MistralMessageResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralMessageResponse {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<MistralToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralToolCall {
+    function: MistralFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralFunctionCall {
+    /// The call's arguments as a JSON string
+    arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralUsage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralError {
+    message: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralModelList {
+    data: Vec<MistralModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MistralModelEntry {
+    id: String,
+    #[serde(default)]
+    capabilities: MistralCapabilities,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MistralCapabilities {
+    #[serde(default)]
+    completion_chat: bool,
+    #[serde(default)]
+    completion_fim: bool,
+}
+
+/// A model the API key can use
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MistralModel {
+    pub id: String,
+    /// Takes chat requests
+    pub chat: bool,
+    /// Takes fill-in-the-middle requests (Codestral)
+    pub fim: bool,
+}
+
+pub struct MistralProvider {
+    api_key: Option<String>,
+    model: String,
+    /// Complete through the fill-in-the-middle endpoint instead of chat
+    fim: bool,
+    client: Client,
+}
+
+impl MistralProvider {
+    pub fn new(api_key: Option<String>, model: String, fim: bool) -> Self {
+        Self {
+            api_key,
+            model,
+            fim,
+            client: super::http::client(),
+        }
+    }
+
+    fn chat_request(&self, request: GenerationRequest) -> MistralChatRequest {
+        let mut messages = Vec::new();
+        if let Some(system) = request.system_prompt {
+            messages.push(MistralMessage {
+                role: "system",
+                content: system,
+            });
+        }
+        messages.push(MistralMessage {
+            role: "user",
+            content: request.prompt,
+        });
+
+        // Structured output: force a call to a function whose arguments are the response
+        let (tools, tool_choice) = match request.output_schema {
+            Some(schema) => (
+                vec![MistralTool {
+                    tool_type: "function",
+                    function: MistralFunction {
+                        name: schema.name,
+                        description: schema.description,
+                        parameters: schema.schema,
+                    },
+                }],
+                Some("any"),
+            ),
+            None => (Vec::new(), None),
+        };
+
+        MistralChatRequest {
+            model: self.model.clone(),
+            messages,
+            max_tokens: request.max_tokens,
+            temperature: request.temperature,
+            random_seed: request.seed,
+            tools,
+            tool_choice,
+        }
+    }
+
+    /// The prompt up to an opening code fence, so the model fills in the file between
+    /// it and the closing fence. The endpoint has no system role, so the system prompt
+    /// goes first.
+    fn fim_request(&self, request: GenerationRequest) -> MistralFimRequest {
+        let mut prompt = String::new();
+        if let Some(system) = request.system_prompt {
+            prompt.push_str(&system);
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str(&request.prompt);
+        prompt.push_str("\n\n```\n");
+
+        MistralFimRequest {
+            model: self.model.clone(),
+            prompt,
+            suffix: "\n```\n".to_string(),
+            max_tokens: request.max_tokens,
+            temperature: request.temperature,
+            random_seed: request.seed,
+            stop: vec!["\n```"],
+        }
+    }
+}
+
+/// Send the request with the key, turning error statuses into `LLMError`s
+async fn send(request: RequestBuilder, api_key: &str) -> Result<Response, LLMError> {
+    let response = request
+        .header("Authorization", format!("Bearer {}", api_key))
+        .send()
+        .await
+        .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(LLMError::InvalidApiKey);
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(LLMError::RateLimited);
+    }
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        if let Ok(error) = serde_json::from_str::<MistralError>(&error_text) {
+            let message = match error.message {
+                serde_json::Value::String(message) => message,
+                other => other.to_string(),
+            };
+            return Err(LLMError::RequestFailed(message));
+        }
+        return Err(LLMError::RequestFailed(format!(
+            "HTTP {}: {}",
+            status, error_text
+        )));
+    }
+    Ok(response)
+}
+
+/// The models the API key can use, sorted by ID
+pub async fn list_models(api_key: &str) -> Result<Vec<MistralModel>, LLMError> {
+    let client = super::http::client();
+    let response = send(client.get(MISTRAL_MODELS_URL), api_key).await?;
+    let list: MistralModelList = response
+        .json()
+        .await
+        .map_err(|e| LLMError::ParseError(e.to_string()))?;
+
+    let mut models: Vec<MistralModel> = list
+        .data
+        .into_iter()
+        .map(|m| MistralModel {
+            id: m.id,
+            chat: m.capabilities.completion_chat,
+            fim: m.capabilities.completion_fim,
+        })
+        .collect();
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models.dedup_by(|a, b| a.id == b.id);
+    Ok(models)
+}
+
+#[async_trait]
+impl LLMProvider for MistralProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        let api_key = self.api_key.as_ref().ok_or(LLMError::InvalidApiKey)?;
+
+        // Structured output needs tool calls, which only chat has
+        let fim = self.fim && request.output_schema.is_none();
+        let http_request = if fim {
+            self.client
+                .post(MISTRAL_FIM_URL)
+                .json(&self.fim_request(request))
+        } else {
+            self.client
+                .post(MISTRAL_CHAT_URL)
+                .json(&self.chat_request(request))
+        };
+        let response = send(http_request, api_key).await?;
+        tracing::debug!(
+            "Mistral {} request for {} returned {}",
+            if fim { "FIM" } else { "chat" },
+            self.model,
+            response.status()
+        );
+
+        let mistral_response: MistralResponse = response
+            .json()
+            .await
+            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+
+        let content = mistral_response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| match c.message.tool_calls.into_iter().next() {
+                Some(call) => Some(call.function.arguments),
+                None => c.message.content,
+            })
+            .unwrap_or_default();
+
+        Ok(GenerationResponse {
+            content,
+            model: mistral_response.model,
+            tokens_used: Some(mistral_response.usage.total_tokens),
+            input_tokens: Some(mistral_response.usage.prompt_tokens),
+            output_tokens: Some(mistral_response.usage.completion_tokens),
+            cached_input_tokens: None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Mistral"
+    }
+
+    fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    #[test]
+    fn test_mistral_requests() {
+        let provider = MistralProvider::new(None, "codestral-latest".to_string(), true);
+        let request = GenerationRequest {
+            prompt: "Write src/a.ts".to_string(),
+            system_prompt: Some("Only code".to_string()),
+            max_tokens: Some(512),
+            temperature: None,
+            cache_breakpoints: Vec::new(),
+            output_schema: None,
+            seed: Some(7),
+            timeout: None,
+        };
+
+        let fim = serde_json::to_value(provider.fim_request(request.clone())).unwrap();
+        assert_eq!(fim["prompt"], "Only code\n\nWrite src/a.ts\n\n```\n");
+        assert_eq!(fim["suffix"], "\n```\n");
+        assert_eq!(fim["random_seed"], 7);
+        assert!(fim.get("temperature").is_none());
+
+        let schema = OutputSchema::list("files", "The files", "files", serde_json::json!({}));
+        let chat = serde_json::to_value(provider.chat_request(GenerationRequest {
+            output_schema: Some(schema),
+            ..request
+        }))
+        .unwrap();
+        assert_eq!(chat["messages"][0]["role"], "system");
+        assert_eq!(chat["messages"][1]["content"], "Write src/a.ts");
+        assert_eq!(chat["tools"][0]["function"]["name"], "files");
+        assert_eq!(chat["tool_choice"], "any");
+
+        let list: MistralModelList = serde_json::from_value(serde_json::json!({
+            "data": [{
+                "id": "codestral-latest",
+                "capabilities": { "completion_chat": true, "completion_fim": true }
+            }]
+        }))
+        .unwrap();
+        assert!(list.data[0].capabilities.completion_fim);
+    }
+}
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+/// Delay before each mock response, in milliseconds
+pub const LATENCY_ENV: &str = "NEEDLEPOINT_MOCK_LATENCY_MS";
+/// Fraction of mock generations that fail, from 0 to 1
+pub const FAILURE_RATE_ENV: &str = "NEEDLEPOINT_MOCK_FAILURE_RATE";
+/// Code returned by the mock provider, with `{file}` and `{exports}` placeholders
+pub const TEMPLATE_ENV: &str = "NEEDLEPOINT_MOCK_TEMPLATE";
+
+const DEFAULT_TEMPLATE: &str = "// Mock implementation of {file}\n// Exports: {exports}\n";
+
+/// Headings the prompt lists a node's exports under
+const EXPORT_HEADINGS: [&str; 3] =
+    ["## You must export:", "## You must define:", "## You must cover:"];
+
+/// Provider that answers without network calls, for tests and for trying the app
+/// without API keys. Responses are filled in from a template using the file and
+/// exports named in the prompt.
+pub struct MockProvider {
+    model: String,
+    latency: Duration,
+    failure_rate: f64,
+    template: String,
+}
+
+impl MockProvider {
+    /// A mock that answers immediately and never fails
+    pub fn new(model: String) -> Self {
+        Self {
+            model,
+            latency: Duration::ZERO,
+            failure_rate: 0.0,
+            template: DEFAULT_TEMPLATE.to_string(),
+        }
+    }
+
+    /// A mock with latency, failure rate and template taken from the environment
+    pub fn from_env(model: String) -> Self {
+        let mut provider = Self::new(model);
+        if let Some(ms) = env_setting::<u64>(LATENCY_ENV) {
+            provider = provider.with_latency(Duration::from_millis(ms));
+        }
+        if let Some(rate) = env_setting::<f64>(FAILURE_RATE_ENV) {
+            provider = provider.with_failure_rate(rate);
+        }
+        if let Ok(template) = std::env::var(TEMPLATE_ENV) {
+            provider = provider.with_template(template);
+        }
+        provider
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail this fraction of generations (clamped to 0..=1)
+    pub fn with_failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+
+    /// The template filled in with the file and exports named in the prompt
+    fn render(&self, prompt: &str) -> String {
+        let file = prompt
+            .lines()
+            .find_map(|line| line.strip_prefix("## File: "))
+            .unwrap_or("unknown file");
+
+        let mut exports = Vec::new();
+        let mut in_exports = false;
+        for line in prompt.lines() {
+            if EXPORT_HEADINGS.contains(&line) {
+                in_exports = true;
+            } else if in_exports {
+                match line.strip_prefix("- ") {
+                    Some(export) => exports.push(export.split(':').next().unwrap_or(export)),
+                    None if line.starts_with("  ") => {}
+                    None => in_exports = false,
+                }
+            }
+        }
+
+        self.template
+            .replace("{file}", file)
+            .replace("{exports}", &exports.join(", "))
+    }
+}
+
+fn env_setting<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    let parsed = value.parse().ok();
+    if parsed.is_none() {
+        tracing::warn!("Ignoring invalid {}: {}", name, value);
+    }
+    parsed
+}
+
+#[async_trait]
+impl LLMProvider for MockProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+
+        // With a seed, the same prompt always succeeds or always fails
+        let roll = match request.seed {
+            Some(seed) => {
+                let hash = Sha256::digest(format!("{}:{}", seed, request.prompt).as_bytes());
+                u64::from_le_bytes(hash[..8].try_into().unwrap_or_default()) as f64
+                    / u64::MAX as f64
+            }
+            None => uuid::Uuid::new_v4().as_u128() as f64 / u128::MAX as f64,
+        };
+        if roll < self.failure_rate {
+            return Err(LLMError::RequestFailed(
+                "Simulated failure from the mock provider".to_string(),
+            ));
+        }
+
+        // Structured requests get an empty list under each required key
+        let content = match &request.output_schema {
+            Some(schema) => {
+                let required = schema.schema["required"].as_array();
+                let object: serde_json::Map<String, serde_json::Value> = required
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|key| key.as_str())
+                    .map(|key| (key.to_string(), serde_json::json!([])))
+                    .collect();
+                serde_json::Value::Object(object).to_string()
+            }
+            None => self.render(&request.prompt),
+        };
+
+        // Roughly four characters per token
+        let prompt_chars =
+            request.prompt.len() + request.system_prompt.as_ref().map_or(0, |s| s.len());
+        let input_tokens = (prompt_chars / 4) as u32;
+        let output_tokens = (content.len() / 4) as u32;
+
+        Ok(GenerationResponse {
+            content,
+            model: self.model.clone(),
+            tokens_used: Some(input_tokens + output_tokens),
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(output_tokens),
+            cached_input_tokens: None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Mock"
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::OutputSchema;
+
+    fn request(prompt: &str) -> GenerationRequest {
+        GenerationRequest {
+            prompt: prompt.to_string(),
+            system_prompt: None,
+            max_tokens: None,
+            temperature: None,
+            cache_breakpoints: Vec::new(),
+            output_schema: None,
+            seed: None,
+            timeout: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider() {
+        let prompt = "You are implementing a TypeScript module.\n\n## File: src/auth.ts\n\
+                      ## You must export:\n- login: (user: string) => void\n  Logs in\n\
+                      - logout\n\n## Constraints:\n- Keep it small\n";
+        let provider = MockProvider::new("mock".to_string());
+        let response = provider.generate(request(prompt)).await.unwrap();
+        assert_eq!(
+            response.content,
+            "// Mock implementation of src/auth.ts\n// Exports: login, logout\n"
+        );
+        assert_eq!(response.model, "mock");
+
+        let templated = MockProvider::new("mock".to_string()).with_template("export {exports};");
+        let response = templated.generate(request(prompt)).await.unwrap();
+        assert_eq!(response.content, "export login, logout;");
+
+        let mut structured = request("Review these files");
+        structured.output_schema =
+            Some(OutputSchema::list("report", "Report", "patches", serde_json::json!({})));
+        let response = provider.generate(structured).await.unwrap();
+        assert_eq!(response.content, r#"{"patches":[]}"#);
+
+        let failing = MockProvider::new("mock".to_string()).with_failure_rate(1.0);
+        assert!(failing.generate(request(prompt)).await.is_err());
+
+        // A seed makes simulated failures reproducible
+        let flaky = MockProvider::new("mock".to_string()).with_failure_rate(0.5);
+        let seeded = GenerationRequest {
+            seed: Some(7),
+            ..request(prompt)
+        };
+        let first = flaky.generate(seeded.clone()).await.is_ok();
+        for _ in 0..5 {
+            assert_eq!(flaky.generate(seeded.clone()).await.is_ok(), first);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+pub mod provider;
+pub mod anthropic;
+pub mod openai;
+pub mod mistral;
+pub mod ollama;
+pub mod local;
+pub mod mock;
+pub mod context;
+pub mod embeddings;
+pub mod extract;
+pub mod http;
+pub mod pricing;
+pub mod transcript;
+pub mod redaction;
+pub mod policy;
+
+pub use provider::{
+    generate_with_retries, generate_with_timeout, LLMProvider, GenerationRequest,
+    GenerationResponse, OutputSchema,
+};
+pub use anthropic::AnthropicProvider;
+pub use openai::OpenAIProvider;
+pub use mistral::MistralProvider;
+pub use ollama::OllamaProvider;
+pub use local::LocalProvider;
+pub use mock::MockProvider;
+pub use context::{parse_json_list, ContextBuilder};
+pub use extract::{extract_code, strip_code_blocks, ExtractedCode};
+pub use pricing::estimate_cost;
+pub use transcript::PendingTranscript;
+pub use redaction::Redactor;
+pub use policy::DeniedProvider;
+
+use std::path::Path;
+
+use crate::graph::model::{LLMConfig, Project};
+
+/// Create an LLM provider based on configuration, held to the manifest's provider
+/// policy and redaction rules. A provider the policy doesn't allow fails every request.
+/// Local model paths are relative to the project directory.
+pub fn create_provider(
+    config: &LLMConfig,
+    api_key: Option<String>,
+    project: &Project,
+) -> Box<dyn LLMProvider> {
+    let manifest = &project.manifest;
+    if let Some(policy) = &manifest.policy {
+        if let Err(reason) = policy.check(&config.provider, &config.model) {
+            return Box::new(DeniedProvider::new(reason));
+        }
+    }
+    let provider: Box<dyn LLMProvider> = match config.provider {
+        crate::graph::model::LLMProvider::Anthropic => Box::new(AnthropicProvider::new(
+            api_key,
+            config.model.clone(),
+            config.thinking_budget,
+        )),
+        crate::graph::model::LLMProvider::OpenAI => {
+            Box::new(OpenAIProvider::new(api_key, config.model.clone(), config.reasoning_effort))
+        }
+        crate::graph::model::LLMProvider::Mistral => {
+            Box::new(MistralProvider::new(api_key, config.model.clone(), config.fim))
+        }
+        crate::graph::model::LLMProvider::Ollama => {
+            Box::new(OllamaProvider::new(config.model.clone()))
+        }
+        crate::graph::model::LLMProvider::Local => {
+            let model_path = Path::new(&project.project_path).join(&config.model);
+            Box::new(LocalProvider::new(model_path.to_string_lossy().to_string()))
+        }
+        crate::graph::model::LLMProvider::Mock => {
+            Box::new(MockProvider::from_env(config.model.clone()))
+        }
+    };
+    redaction::with_redaction(provider, &config.provider, manifest)
+}
@@ -13,6 +13,9 @@ struct OllamaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
     stream: bool,
+    /// `"json"` to constrain the output to valid JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'static str>,
     options: OllamaOptions,
 }
 
@@ -22,6 +25,8 @@ struct OllamaOptions {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +48,7 @@ impl OllamaProvider {
     pub fn new(model: String) -> Self {
         Self {
             model,
-            client: Client::new(),
+            client: super::http::client(),
         }
     }
 }
@@ -51,14 +56,29 @@ impl OllamaProvider {
 #[async_trait]
 impl LLMProvider for OllamaProvider {
     async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        // Ollama has no tool choice, so structured output is JSON mode with the schema
+        // spelled out in the prompt
+        let (prompt, format) = match &request.output_schema {
+            Some(schema) => (
+                format!(
+                    "{}\n\nRespond with a JSON object matching this JSON Schema:\n{}",
+                    request.prompt, schema.schema
+                ),
+                Some("json"),
+            ),
+            None => (request.prompt, None),
+        };
+
         let ollama_request = OllamaRequest {
             model: self.model.clone(),
-            prompt: request.prompt,
+            prompt,
             system: request.system_prompt,
             stream: false,
+            format,
             options: OllamaOptions {
                 temperature: request.temperature,
                 num_predict: request.max_tokens,
+                seed: request.seed,
             },
         };
 
@@ -79,6 +99,7 @@ impl LLMProvider for OllamaProvider {
             })?;
 
         let status = response.status();
+        tracing::debug!("Ollama request for {} returned {}", self.model, status);
 
         if status == reqwest::StatusCode::NOT_FOUND {
             return Err(LLMError::ModelNotFound(self.model.clone()));
@@ -101,6 +122,9 @@ impl LLMProvider for OllamaProvider {
             content: ollama_response.response,
             model: ollama_response.model,
             tokens_used: Some(ollama_response.eval_count + ollama_response.prompt_eval_count),
+            input_tokens: Some(ollama_response.prompt_eval_count),
+            output_tokens: Some(ollama_response.eval_count),
+            cached_input_tokens: None,
         })
     }
 
@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::ReasoningEffort;
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+#[derive(Debug, Serialize)]
+struct OpenAIRequest {
+    model: String,
+    messages: Vec<OpenAIMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// Replaces `max_tokens` for reasoning models, covering reasoning and answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OpenAITool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<OpenAITool>,
+}
+
+/// A function tool, or (without `description` and `parameters`) the choice of one
+#[derive(Debug, Serialize)]
+struct OpenAITool {
+    #[serde(rename = "type")]
+    tool_type: &'static str,
+    function: OpenAIFunction,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIFunction {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<OpenAIChoice>,
+    model: String,
+    usage: OpenAIUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIChoice {
+    message: OpenAIMessageResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIMessageResponse {
+    /// Null when the model answered with a tool call
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAIToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIToolCall {
+    function: OpenAIFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIFunctionCall {
+    /// The call's arguments as a JSON string
+    arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+    total_tokens: u32,
+    /// Present when part of the prompt was served from OpenAI's automatic prompt cache
+    #[serde(default)]
+    prompt_tokens_details: Option<OpenAIPromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIError {
+    error: OpenAIErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIErrorDetail {
+    message: String,
+}
+
+pub struct OpenAIProvider {
+    api_key: Option<String>,
+    model: String,
+    /// Set for reasoning models
+    reasoning_effort: Option<ReasoningEffort>,
+    client: Client,
+}
+
+impl OpenAIProvider {
+    pub fn new(
+        api_key: Option<String>,
+        model: String,
+        reasoning_effort: Option<ReasoningEffort>,
+    ) -> Self {
+        Self {
+            api_key,
+            model,
+            reasoning_effort,
+            client: super::http::client(),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OpenAIProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        let api_key = self.api_key.as_ref().ok_or(LLMError::InvalidApiKey)?;
+
+        let mut messages = Vec::new();
+
+        if let Some(system) = request.system_prompt {
+            messages.push(OpenAIMessage {
+                role: "system".to_string(),
+                content: system,
+            });
+        }
+
+        messages.push(OpenAIMessage {
+            role: "user".to_string(),
+            content: request.prompt,
+        });
+
+        // Structured output: force a call to a function whose arguments are the response
+        let (tools, tool_choice) = match request.output_schema {
+            Some(schema) => (
+                vec![OpenAITool {
+                    tool_type: "function",
+                    function: OpenAIFunction {
+                        name: schema.name.clone(),
+                        description: Some(schema.description),
+                        parameters: Some(schema.schema),
+                    },
+                }],
+                Some(OpenAITool {
+                    tool_type: "function",
+                    function: OpenAIFunction {
+                        name: schema.name,
+                        description: None,
+                        parameters: None,
+                    },
+                }),
+            ),
+            None => (Vec::new(), None),
+        };
+
+        // Reasoning models reject max_tokens and temperature
+        let reasoning = self.reasoning_effort.is_some();
+        let openai_request = OpenAIRequest {
+            model: self.model.clone(),
+            messages,
+            max_tokens: request.max_tokens.filter(|_| !reasoning),
+            max_completion_tokens: request.max_tokens.filter(|_| reasoning),
+            temperature: request.temperature.filter(|_| !reasoning),
+            reasoning_effort: self.reasoning_effort.map(|effort| effort.as_str()),
+            seed: request.seed,
+            tools,
+            tool_choice,
+        };
+
+        let response = self
+            .client
+            .post(OPENAI_API_URL)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&openai_request)
+            .send()
+            .await
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        tracing::debug!("OpenAI request for {} returned {}", self.model, status);
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(LLMError::InvalidApiKey);
+        }
+
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(LLMError::RateLimited);
+        }
+
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            if let Ok(error) = serde_json::from_str::<OpenAIError>(&error_text) {
+                return Err(LLMError::RequestFailed(error.error.message));
+            }
+            return Err(LLMError::RequestFailed(format!(
+                "HTTP {}: {}",
+                status, error_text
+            )));
+        }
+
+        let openai_response: OpenAIResponse = response
+            .json()
+            .await
+            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+
+        let content = openai_response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| match c.message.tool_calls.into_iter().next() {
+                Some(call) => Some(call.function.arguments),
+                None => c.message.content,
+            })
+            .unwrap_or_default();
+
+        Ok(GenerationResponse {
+            content,
+            model: openai_response.model,
+            tokens_used: Some(openai_response.usage.total_tokens),
+            input_tokens: Some(openai_response.usage.prompt_tokens),
+            output_tokens: Some(openai_response.usage.completion_tokens),
+            cached_input_tokens: openai_response
+                .usage
+                .prompt_tokens_details
+                .map(|details| details.cached_tokens),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "OpenAI"
+    }
+
+    fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError, LLMProvider};
+
+/// Stands in for a provider the project's policy doesn't allow, failing every request
+/// without sending it
+pub struct DeniedProvider {
+    reason: String,
+}
+
+impl DeniedProvider {
+    pub fn new(reason: String) -> Self {
+        Self { reason }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for DeniedProvider {
+    async fn generate(&self, _request: GenerationRequest) -> Result<GenerationResponse, LLMError> {
+        Err(LLMError::RequestFailed(format!(
+            "Not sent: {}",
+            self.reason
+        )))
+    }
+
+    fn name(&self) -> &'static str {
+        "Denied by policy"
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::model::ProviderPolicy;
+    use crate::graph::model::{LLMConfig, LLMProvider as ProviderKind, Project, ProjectManifest};
+    use crate::llm::{create_provider, GenerationRequest};
+
+    #[tokio::test]
+    async fn test_policy_enforced() {
+        let manifest = ProjectManifest {
+            policy: Some(ProviderPolicy {
+                allowed_providers: vec![ProviderKind::Mock],
+                allowed_models: vec!["local-*".to_string()],
+            }),
+            ..Default::default()
+        };
+        let project = Project {
+            manifest,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            project_path: String::new(),
+        };
+        let config = |provider: ProviderKind, model: &str| LLMConfig {
+            provider,
+            model: model.to_string(),
+            ..LLMConfig::default()
+        };
+        let request = GenerationRequest {
+            prompt: "## File: a.ts".to_string(),
+            system_prompt: None,
+            max_tokens: None,
+            temperature: None,
+            cache_breakpoints: Vec::new(),
+            output_schema: None,
+            seed: None,
+            timeout: None,
+        };
+
+        let allowed = create_provider(&config(ProviderKind::Mock, "local-1"), None, &project);
+        assert!(allowed.generate(request.clone()).await.is_ok());
+
+        let hosted = create_provider(
+            &config(ProviderKind::Anthropic, "local-1"),
+            Some("sk-ant-key".to_string()),
+            &project,
+        );
+        let error = hosted.generate(request.clone()).await.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("doesn't allow the anthropic provider"));
+
+        let other_model = create_provider(&config(ProviderKind::Mock, "mock"), None, &project);
+        assert!(other_model.generate(request).await.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+/// Per-million-token pricing for a model family (USD)
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Known pricing, matched by substring against the model name (first match wins)
+const PRICING_TABLE: &[(&str, ModelPricing)] = &[
+    ("opus", ModelPricing { input_per_million: 15.0, output_per_million: 75.0 }),
+    ("sonnet", ModelPricing { input_per_million: 3.0, output_per_million: 15.0 }),
+    ("haiku", ModelPricing { input_per_million: 0.8, output_per_million: 4.0 }),
+    ("gpt-4o-mini", ModelPricing { input_per_million: 0.15, output_per_million: 0.6 }),
+    ("gpt-4o", ModelPricing { input_per_million: 2.5, output_per_million: 10.0 }),
+    ("gpt-4.1-mini", ModelPricing { input_per_million: 0.4, output_per_million: 1.6 }),
+    ("gpt-4.1", ModelPricing { input_per_million: 2.0, output_per_million: 8.0 }),
+    ("o3-mini", ModelPricing { input_per_million: 1.1, output_per_million: 4.4 }),
+    ("o1", ModelPricing { input_per_million: 15.0, output_per_million: 60.0 }),
+    ("codestral", ModelPricing { input_per_million: 0.3, output_per_million: 0.9 }),
+    ("mistral-large", ModelPricing { input_per_million: 2.0, output_per_million: 6.0 }),
+    ("mistral-medium", ModelPricing { input_per_million: 0.4, output_per_million: 2.0 }),
+    ("mistral-small", ModelPricing { input_per_million: 0.1, output_per_million: 0.3 }),
+];
+
+/// Look up pricing for a model name, if known
+pub fn pricing_for_model(model: &str) -> Option<ModelPricing> {
+    let model = model.to_lowercase();
+    PRICING_TABLE
+        .iter()
+        .find(|(pattern, _)| model.contains(pattern))
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Estimate the cost in USD of a generation
+/// Returns None when the model's pricing is unknown
+pub fn estimate_cost(
+    provider: &crate::graph::model::LLMProvider,
+    model: &str,
+    input_tokens: u32,
+    output_tokens: u32,
+) -> Option<f64> {
+    // Local and mock models are free to run
+    if matches!(
+        provider,
+        crate::graph::model::LLMProvider::Ollama
+            | crate::graph::model::LLMProvider::Local
+            | crate::graph::model::LLMProvider::Mock
+    ) {
+        return Some(0.0);
+    }
+
+    let pricing = pricing_for_model(model)?;
+    Some(
+        input_tokens as f64 / 1_000_000.0 * pricing.input_per_million
+            + output_tokens as f64 / 1_000_000.0 * pricing.output_per_million,
+    )
+}
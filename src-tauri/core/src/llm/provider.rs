@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Request for code generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRequest {
+    pub prompt: String,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    /// Offsets into `prompt` after which its prefix is worth caching (the end of each
+    /// dependency's code), for providers that support prompt caching
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cache_breakpoints: Vec<usize>,
+    /// Ask for a JSON object matching this schema instead of free text, through the
+    /// provider's tool calling or JSON mode. The response's `content` is the JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<OutputSchema>,
+    /// Sampling seed, for providers that support one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Give up on the request after this long (see `generate_with_timeout`)
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+}
+
+/// The shape of a structured response, sent to the provider as a tool definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputSchema {
+    /// Tool name (letters, digits, `_` and `-`)
+    pub name: String,
+    pub description: String,
+    /// JSON Schema of the response; must describe an object
+    pub schema: serde_json::Value,
+}
+
+impl OutputSchema {
+    /// A response holding a single list, `{"<key>": [<items>...]}` (tools can't
+    /// return a bare array)
+    pub fn list(name: &str, description: &str, key: &str, items: serde_json::Value) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": { key: { "type": "array", "items": items } },
+                "required": [key],
+            }),
+        }
+    }
+}
+
+/// Response from code generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationResponse {
+    pub content: String,
+    pub model: String,
+    pub tokens_used: Option<u32>,
+    /// Prompt tokens consumed, when the provider reports them separately
+    #[serde(default)]
+    pub input_tokens: Option<u32>,
+    /// Completion tokens produced, when the provider reports them separately
+    #[serde(default)]
+    pub output_tokens: Option<u32>,
+    /// Of `input_tokens`, those read from the provider's prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_tokens: Option<u32>,
+}
+
+/// Error type for LLM operations
+#[derive(Debug, thiserror::Error)]
+pub enum LLMError {
+    #[error("API request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Invalid API key")]
+    InvalidApiKey,
+
+    #[error("Rate limited")]
+    RateLimited,
+
+    #[error("Model not found: {0}")]
+    ModelNotFound(String),
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    #[error("Request timed out after {0}s")]
+    Timeout(u64),
+}
+
+impl LLMError {
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            LLMError::Timeout(_) | LLMError::RateLimited | LLMError::NetworkError(_)
+        )
+    }
+}
+
+/// Trait for LLM providers
+#[async_trait]
+pub trait LLMProvider: Send + Sync {
+    /// Generate code based on the request
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, LLMError>;
+
+    /// Get the provider name
+    fn name(&self) -> &'static str;
+
+    /// Check if the provider is configured (has API key, etc.)
+    fn is_configured(&self) -> bool;
+}
+
+/// Generate with the request's timeout enforced, so a provider that hangs can't
+/// stall a whole wave
+pub async fn generate_with_timeout(
+    provider: &dyn LLMProvider,
+    request: GenerationRequest,
+) -> Result<GenerationResponse, LLMError> {
+    let Some(timeout) = request.timeout else {
+        return provider.generate(request).await;
+    };
+    tokio::time::timeout(timeout, provider.generate(request))
+        .await
+        .unwrap_or(Err(LLMError::Timeout(timeout.as_secs())))
+}
+
+/// Times a request that failed in a way that may pass (see `LLMError::is_retryable`)
+/// is sent again
+pub const MAX_RETRIES: u32 = 2;
+/// Wait before the first retry, doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Generate with the request's timeout, sending the request again after retryable
+/// failures. Returns the outcome with the number of retries it took.
+pub async fn generate_with_retries(
+    provider: &dyn LLMProvider,
+    request: GenerationRequest,
+) -> (Result<GenerationResponse, LLMError>, u32) {
+    let mut retries = 0;
+    loop {
+        match generate_with_timeout(provider, request.clone()).await {
+            Err(e) if e.is_retryable() && retries < MAX_RETRIES => {
+                tracing::warn!("{} request failed ({}); retrying", provider.name(), e);
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(retries)).await;
+                retries += 1;
+            }
+            result => return (result, retries),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Rate limited on the first request, then answers
+    struct Flaky(AtomicU32);
+
+    #[async_trait]
+    impl LLMProvider for Flaky {
+        async fn generate(
+            &self,
+            _request: GenerationRequest,
+        ) -> Result<GenerationResponse, LLMError> {
+            if self.0.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(LLMError::RateLimited);
+            }
+            Err(LLMError::InvalidApiKey)
+        }
+
+        fn name(&self) -> &'static str {
+            "Flaky"
+        }
+
+        fn is_configured(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_retries() {
+        let request = GenerationRequest {
+            prompt: "## File: a.ts".to_string(),
+            system_prompt: None,
+            max_tokens: None,
+            temperature: None,
+            cache_breakpoints: Vec::new(),
+            output_schema: None,
+            seed: None,
+            timeout: None,
+        };
+        // Only the rate limit is retried, not the invalid key after it
+        let provider = Flaky(AtomicU32::new(0));
+        let (result, retries) = generate_with_retries(&provider, request).await;
+        assert!(matches!(result, Err(LLMError::InvalidApiKey)));
+        assert_eq!(retries, 1);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::{CodeNode, LLMConfig, LLMProvider, Project};
+
+use super::provider::{GenerationRequest, GenerationResponse, LLMError};
+use super::redaction::{Redactor, REDACTED};
+
+const TRANSCRIPTS_DIR: &str = ".needlepoint/transcripts";
+
+/// One prompt sent to a provider and the raw completion it returned,
+/// appended to `.needlepoint/transcripts/<node_id>.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub timestamp: DateTime<Utc>,
+    pub node_id: String,
+    pub node_name: String,
+    pub provider: LLMProvider,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub prompt: String,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    /// Raw completion, before code block stripping
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+    pub duration_ms: u64,
+}
+
+/// A transcript whose request has been sent but whose response hasn't arrived yet
+pub struct PendingTranscript {
+    project_path: String,
+    transcript: Transcript,
+    /// The manifest's redaction rules
+    redactor: Option<Redactor>,
+}
+
+impl PendingTranscript {
+    /// Start a transcript for a generation request sent with `config`. Returns
+    /// `None` unless the project has transcript recording enabled, or when its
+    /// redaction rules are invalid.
+    pub fn begin(
+        project: &Project,
+        node: &CodeNode,
+        config: &LLMConfig,
+        request: &GenerationRequest,
+    ) -> Option<Self> {
+        if !project.manifest.record_transcripts {
+            return None;
+        }
+        let redactor = match Redactor::for_manifest(&project.manifest).transpose() {
+            Ok(redactor) => redactor,
+            Err(e) => {
+                tracing::warn!("Not recording a transcript for node {}: {}", node.id, e);
+                return None;
+            }
+        };
+
+        Some(Self {
+            project_path: project.project_path.clone(),
+            transcript: Transcript {
+                timestamp: Utc::now(),
+                node_id: node.id.clone(),
+                node_name: node.name.clone(),
+                provider: config.provider.clone(),
+                model: config.model.clone(),
+                system_prompt: request.system_prompt.clone(),
+                prompt: request.prompt.clone(),
+                max_tokens: request.max_tokens,
+                temperature: request.temperature,
+                response: None,
+                error: None,
+                input_tokens: None,
+                output_tokens: None,
+                duration_ms: 0,
+            },
+            redactor,
+        })
+    }
+
+    /// Record the outcome, redact `api_key`, anything that looks like a key and
+    /// whatever the redaction rules match, and append the transcript to disk.
+    /// Failures are logged, not returned, so transcripts never break a generation.
+    pub fn finish(
+        self,
+        result: &Result<GenerationResponse, LLMError>,
+        duration_ms: u64,
+        api_key: Option<&str>,
+    ) {
+        let mut transcript = self.transcript;
+        transcript.duration_ms = duration_ms;
+        match result {
+            Ok(response) => {
+                transcript.model = response.model.clone();
+                transcript.response = Some(response.content.clone());
+                transcript.input_tokens = response.input_tokens;
+                transcript.output_tokens = response.output_tokens;
+            }
+            Err(e) => transcript.error = Some(e.to_string()),
+        }
+
+        let transcript = redact_transcript(transcript, api_key, self.redactor.as_ref());
+        if let Err(e) = append_transcript(&self.project_path, &transcript) {
+            tracing::warn!("Failed to record transcript for node {}: {}", transcript.node_id, e);
+        }
+    }
+}
+
+/// Replace the given secret and any API-key-shaped tokens with a placeholder
+pub fn redact(text: &str, secret: Option<&str>) -> String {
+    let mut text = match secret.filter(|s| s.len() >= 8) {
+        Some(secret) => text.replace(secret, REDACTED),
+        None => text.to_string(),
+    };
+
+    // Anthropic (sk-ant-...) and OpenAI (sk-..., sk-proj-...) key formats
+    if let Ok(re) = Regex::new(r"sk-[A-Za-z0-9_-]{16,}") {
+        text = re.replace_all(&text, REDACTED).into_owned();
+    }
+    text
+}
+
+fn redact_transcript(
+    mut transcript: Transcript,
+    secret: Option<&str>,
+    redactor: Option<&Redactor>,
+) -> Transcript {
+    let redact = |text: &str| {
+        let text = redact(text, secret);
+        match redactor {
+            Some(redactor) => redactor.redact(&text),
+            None => text,
+        }
+    };
+    transcript.prompt = redact(&transcript.prompt);
+    transcript.system_prompt = transcript.system_prompt.map(|s| redact(&s));
+    transcript.response = transcript.response.map(|s| redact(&s));
+    transcript.error = transcript.error.map(|s| redact(&s));
+    transcript
+}
+
+/// Get the transcript file for a node, rejecting IDs that could escape the directory
+fn transcript_path(project_path: &str, node_id: &str) -> Result<PathBuf> {
+    if node_id.is_empty() || node_id.contains(['/', '\\']) || node_id.contains("..") {
+        anyhow::bail!("Invalid node ID: {}", node_id);
+    }
+    Ok(Path::new(project_path)
+        .join(TRANSCRIPTS_DIR)
+        .join(format!("{}.jsonl", node_id)))
+}
+
+fn append_transcript(project_path: &str, transcript: &Transcript) -> Result<()> {
+    let path = transcript_path(project_path, &transcript.node_id)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create transcripts directory: {:?}", dir))?;
+    }
+
+    let mut line = serde_json::to_string(transcript).context("Failed to serialize transcript")?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open transcript file: {:?}", path))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to write transcript file: {:?}", path))?;
+
+    Ok(())
+}
+
+/// Load all recorded transcripts for a node, oldest first
+pub fn load_transcripts(project_path: &str, node_id: &str) -> Result<Vec<Transcript>> {
+    let path = transcript_path(project_path, node_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read transcript file: {:?}", path))?;
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .with_context(|| format!("Failed to parse transcript file: {:?}", path))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let text = "key=my-secret-key-123 other=sk-ant-REDACTED end";
+        let redacted = redact(text, Some("my-secret-key-123"));
+        assert_eq!(redacted, "key=[REDACTED] other=[REDACTED] end");
+
+        // Short secrets aren't replaced, to avoid mangling ordinary text
+        assert_eq!(redact("abc", Some("abc")), "abc");
+
+        // The manifest's rules apply to every part of the transcript
+        let rules = crate::graph::model::RedactionRules {
+            patterns: vec!["PRICE_TABLE_[0-9]+".to_string()],
+            ..Default::default()
+        };
+        let redactor = Redactor::new(&rules).unwrap();
+        let transcript = Transcript {
+            timestamp: Utc::now(),
+            node_id: "n1".to_string(),
+            node_name: "N1".to_string(),
+            provider: LLMProvider::Anthropic,
+            model: "claude-sonnet-4-20250514".to_string(),
+            system_prompt: None,
+            prompt: "uses PRICE_TABLE_2024".to_string(),
+            max_tokens: None,
+            temperature: None,
+            response: Some("PRICE_TABLE_2024 with sk-abcdefghijklmnopqrstuv".to_string()),
+            error: None,
+            input_tokens: None,
+            output_tokens: None,
+            duration_ms: 0,
+        };
+        let redacted = redact_transcript(transcript, None, Some(&redactor));
+        assert_eq!(redacted.prompt, "uses [REDACTED]");
+        assert_eq!(redacted.response.unwrap(), "[REDACTED] with [REDACTED]");
+    }
+}
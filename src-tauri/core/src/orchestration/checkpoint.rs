@@ -0,0 +1,191 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::{CodeNode, GenerationRecord, NodeStatus, Project};
+
+const CHECKPOINT_FILE: &str = ".needlepoint/run.json";
+
+/// A node the run has finished with, as it was left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishedNode {
+    pub node_id: String,
+    pub status: NodeStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_generation: Option<GenerationRecord>,
+}
+
+/// Progress of a run, kept in `.needlepoint/run.json` while it runs so it can be
+/// resumed if the app is closed before it finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCheckpoint {
+    pub started_at: DateTime<Utc>,
+    /// Nodes the run set out to generate
+    pub node_ids: Vec<String>,
+    /// Nodes generated so far, with their code
+    #[serde(default)]
+    pub finished: Vec<FinishedNode>,
+}
+
+/// What an interrupted run got through, to offer resuming it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedRun {
+    pub started_at: DateTime<Utc>,
+    pub total_nodes: usize,
+    pub finished_nodes: usize,
+    /// Nodes still to generate
+    pub remaining: Vec<String>,
+}
+
+impl RunCheckpoint {
+    pub fn new(node_ids: Vec<String>) -> Self {
+        Self {
+            started_at: Utc::now(),
+            node_ids,
+            finished: Vec::new(),
+        }
+    }
+
+    /// Record a node the run has finished with, replacing an earlier record of it
+    pub fn record(&mut self, node: &CodeNode) {
+        self.finished.retain(|f| f.node_id != node.id);
+        self.finished.push(FinishedNode {
+            node_id: node.id.clone(),
+            status: node.status.clone(),
+            generated_code: node.generated_code.clone(),
+            error_message: node.error_message.clone(),
+            last_generation: node.last_generation.clone(),
+        });
+    }
+
+    /// Nodes the run hasn't finished with, in the order it planned them
+    pub fn remaining(&self) -> Vec<String> {
+        self.node_ids
+            .iter()
+            .filter(|id| !self.finished.iter().any(|f| &f.node_id == *id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn interrupted(&self) -> InterruptedRun {
+        InterruptedRun {
+            started_at: self.started_at,
+            total_nodes: self.node_ids.len(),
+            finished_nodes: self.finished.len(),
+            remaining: self.remaining(),
+        }
+    }
+
+    /// Put the finished nodes' code and statuses back into the project. Nodes it no
+    /// longer has are ignored; returns the IDs of the nodes restored.
+    pub fn restore(&self, project: &mut Project) -> Vec<String> {
+        let mut restored = Vec::new();
+        for finished in &self.finished {
+            let Some(node) = project.find_node_mut(&finished.node_id) else {
+                continue;
+            };
+            node.status = finished.status.clone();
+            node.generated_code = finished.generated_code.clone();
+            node.error_message = finished.error_message.clone();
+            node.last_generation = finished.last_generation.clone();
+            restored.push(node.id.clone());
+        }
+        restored
+    }
+}
+
+fn checkpoint_path(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(CHECKPOINT_FILE)
+}
+
+/// Write a run's progress to `.needlepoint/run.json`
+pub fn save_checkpoint(project_path: &str, checkpoint: &RunCheckpoint) -> Result<()> {
+    let path = checkpoint_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    let contents =
+        serde_json::to_string_pretty(checkpoint).context("Failed to serialize run checkpoint")?;
+    fs::write(&path, contents)
+        .with_context(|| format!("Failed to write run checkpoint: {:?}", path))
+}
+
+/// The progress of a run that didn't finish, if there is one
+pub fn load_checkpoint(project_path: &str) -> Result<Option<RunCheckpoint>> {
+    let path = checkpoint_path(project_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read run checkpoint: {:?}", path))?;
+    let checkpoint = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse run checkpoint: {:?}", path))?;
+    Ok(Some(checkpoint))
+}
+
+/// Remove the checkpoint of a run that finished or won't be resumed
+pub fn clear_checkpoint(project_path: &str) -> Result<()> {
+    let path = checkpoint_path(project_path);
+    if path.exists() {
+        fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove run checkpoint: {:?}", path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Language;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        let mut project = Project::new(project_path.clone());
+        for name in ["a", "b", "c"] {
+            let node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            project.nodes.push(node);
+        }
+        let ids: Vec<String> = project.nodes.iter().map(|n| n.id.clone()).collect();
+        assert!(load_checkpoint(&project_path).unwrap().is_none());
+
+        let mut checkpoint = RunCheckpoint::new(ids.clone());
+        project.nodes[0].status = NodeStatus::Complete;
+        project.nodes[0].generated_code = Some("export const a = 1;".to_string());
+        checkpoint.record(&project.nodes[0]);
+        checkpoint.record(&project.nodes[0]);
+        save_checkpoint(&project_path, &checkpoint).unwrap();
+
+        // The app closed mid-run: the project on disk never got the generated code
+        let mut reloaded = project.clone();
+        reloaded.nodes[0].status = NodeStatus::Pending;
+        reloaded.nodes[0].generated_code = None;
+        reloaded.nodes[1].status = NodeStatus::Generating;
+        assert_eq!(reloaded.reset_interrupted_generations(), vec![ids[1].clone()]);
+
+        let checkpoint = load_checkpoint(&project_path).unwrap().unwrap();
+        let interrupted = checkpoint.interrupted();
+        assert_eq!((interrupted.total_nodes, interrupted.finished_nodes), (3, 1));
+        assert_eq!(interrupted.remaining, ids[1..].to_vec());
+        assert_eq!(checkpoint.restore(&mut reloaded), vec![ids[0].clone()]);
+        assert_eq!(reloaded.nodes[0].status, NodeStatus::Complete);
+        assert_eq!(reloaded.nodes[0].generated_code, project.nodes[0].generated_code);
+        assert_eq!(reloaded.nodes[1].status, NodeStatus::Pending);
+
+        clear_checkpoint(&project_path).unwrap();
+        assert!(load_checkpoint(&project_path).unwrap().is_none());
+    }
+}
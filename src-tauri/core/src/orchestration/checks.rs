@@ -0,0 +1,131 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::graph::model::{ProjectCheck, DEFAULT_CHECK_TIMEOUT_SECS};
+
+/// Most output kept from a check, from its end, where failures are reported
+const MAX_OUTPUT_CHARS: usize = 8_000;
+
+/// The outcome of one configured check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub name: String,
+    pub success: bool,
+    /// `None` when the command couldn't be run or was stopped
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// The end of its stdout and stderr
+    pub output: String,
+    /// Why the command couldn't be run or didn't finish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run the checks one after another in the project directory. Every check runs,
+/// whether or not the ones before it passed.
+pub async fn run_checks(project_dir: &Path, checks: &[ProjectCheck]) -> Vec<CheckResult> {
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        tracing::info!("Running check {}", check.name);
+        results.push(run_check(project_dir, check).await);
+    }
+    results
+}
+
+async fn run_check(project_dir: &Path, check: &ProjectCheck) -> CheckResult {
+    let started = Instant::now();
+    let mut result = CheckResult {
+        name: check.name.clone(),
+        success: false,
+        exit_code: None,
+        duration_ms: 0,
+        output: String::new(),
+        error: None,
+    };
+
+    let Some((program, args)) = check.command.split_first() else {
+        result.error = Some("Empty command".to_string());
+        return result;
+    };
+    let child = Command::new(program)
+        .args(args)
+        .current_dir(project_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            result.error = Some(format!("Failed to run {}: {}", program, e));
+            return result;
+        }
+    };
+
+    let timeout = Duration::from_secs(check.timeout_secs.unwrap_or(DEFAULT_CHECK_TIMEOUT_SECS));
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            result.output = tail(&text);
+            result.exit_code = output.status.code();
+            result.success = output.status.success();
+        }
+        Ok(Err(e)) => result.error = Some(e.to_string()),
+        Err(_) => result.error = Some(format!("Timed out after {}s", timeout.as_secs())),
+    }
+    result.duration_ms = started.elapsed().as_millis() as u64;
+    result
+}
+
+/// The last `MAX_OUTPUT_CHARS` characters of the output
+fn tail(output: &str) -> String {
+    let output = output.trim_end();
+    let count = output.chars().count();
+    if count <= MAX_OUTPUT_CHARS {
+        return output.to_string();
+    }
+    let kept: String = output.chars().skip(count - MAX_OUTPUT_CHARS).collect();
+    format!("...{}", kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, command: &[&str]) -> ProjectCheck {
+        ProjectCheck {
+            name: name.to_string(),
+            command: command.iter().map(|s| s.to_string()).collect(),
+            timeout_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_checks() {
+        let checks = [
+            check("missing", &["needlepoint-no-such-check"]),
+            check("fails", &["sh", "-c", "echo broken >&2; exit 3"]),
+            check("passes", &["sh", "-c", "echo ok"]),
+        ];
+        let results = run_checks(&std::env::temp_dir(), &checks).await;
+
+        assert!(!results[0].success);
+        assert!(results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Failed to run"));
+        assert!(!results[1].success);
+        assert_eq!(results[1].exit_code, Some(3));
+        assert_eq!(results[1].output, "broken");
+        assert!(results[2].success);
+        assert_eq!(results[2].output, "ok");
+    }
+}
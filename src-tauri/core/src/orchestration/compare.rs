@@ -0,0 +1,274 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::constraints::verify_generated_code;
+use crate::graph::variables::resolve_node;
+use crate::graph::model::{
+    generation_settings, request_timeout, GenerationProfile, GenerationRecord, LLMConfig,
+    NodeStatus, PostProcessReport, Project,
+};
+use crate::llm::{
+    create_provider, estimate_cost, extract_code, generate_with_timeout, ContextBuilder,
+    GenerationRequest, PendingTranscript,
+};
+
+use super::postprocess::PostProcessor;
+use super::report::NodeRunReport;
+
+/// Candidate versions generated by comparisons, one JSON file per node
+const CANDIDATES_DIR: &str = ".needlepoint/candidates";
+
+/// One generation of a node kept aside for comparison, not applied to the node
+/// until it is accepted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub id: String,
+    pub node_id: String,
+    pub created_at: DateTime<Utc>,
+    /// The node's config with the variant's overrides applied
+    pub llm_config: LLMConfig,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    /// Generated code; `None` when generation failed (see `run.error`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Prose the model wrapped around the code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// What each post-processing step did to the code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_processing: Vec<PostProcessReport>,
+    pub run: NodeRunReport,
+    /// Dependencies that were injected as stub interfaces into the prompt
+    #[serde(default)]
+    pub stubbed_dependencies: Vec<String>,
+}
+
+/// Generate a node with a variant of its settings. Failures are recorded in the
+/// candidate rather than returned, so one failing variant doesn't hide the other.
+pub async fn generate_candidate(
+    project: &Project,
+    node_id: &str,
+    variant: &GenerationProfile,
+    api_key: Option<String>,
+) -> Result<Candidate, String> {
+    let node = project
+        .find_node(node_id)
+        .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+    let (prompt, cache_breakpoints) =
+        ContextBuilder::build_prompt_with_breakpoints(project, node_id)
+            .ok_or_else(|| "Failed to build prompt".to_string())?;
+    let (llm_config, max_tokens, temperature) = generation_settings(
+        &node.llm_config.resolve(&project.manifest.default_llm),
+        Some(variant),
+    );
+
+    let created_at = Utc::now();
+    let mut candidate = Candidate {
+        id: uuid::Uuid::new_v4().to_string(),
+        node_id: node_id.to_string(),
+        created_at,
+        llm_config: llm_config.clone(),
+        max_tokens,
+        temperature,
+        code: None,
+        note: None,
+        post_processing: Vec::new(),
+        run: NodeRunReport {
+            node_id: node_id.to_string(),
+            node_name: node.name.clone(),
+            model: llm_config.model.clone(),
+            success: false,
+            duration_ms: 0,
+            input_tokens: None,
+            output_tokens: None,
+            tokens_used: None,
+            cost_estimate: None,
+            fixup: None,
+            retries: 0,
+            error: None,
+        },
+        stubbed_dependencies: ContextBuilder::stubbed_dependencies(project, node_id),
+    };
+
+    let provider = create_provider(&llm_config, api_key.clone(), project);
+    if !provider.is_configured() {
+        candidate.run.error = Some(format!("{} is not configured", provider.name()));
+        return Ok(candidate);
+    }
+
+    let request = GenerationRequest {
+        prompt,
+        system_prompt: Some(ContextBuilder::build_system_prompt(&project.manifest, node)),
+        max_tokens: Some(max_tokens),
+        temperature: Some(temperature),
+        cache_breakpoints,
+        output_schema: None,
+        seed: llm_config.effective_seed(),
+        timeout: Some(request_timeout(&project.manifest, &llm_config)),
+    };
+    let transcript = PendingTranscript::begin(project, node, &llm_config, &request);
+
+    let started = Instant::now();
+    let result = generate_with_timeout(provider.as_ref(), request).await;
+    candidate.run.duration_ms = started.elapsed().as_millis() as u64;
+    if let Some(transcript) = transcript {
+        transcript.finish(&result, candidate.run.duration_ms, api_key.as_deref());
+    }
+
+    match result {
+        Ok(response) => {
+            let extracted = extract_code(&response.content, &node.language, node.kind);
+            let mut code = extracted.code;
+            let resolved = resolve_node(&project.manifest, node);
+            if let Some(processor) = PostProcessor::for_node(project, &resolved) {
+                (code, candidate.post_processing) = processor.run(code).await;
+            }
+            candidate.code = Some(code);
+            candidate.note = extracted.note;
+            candidate.run.success = true;
+            candidate.run.cost_estimate = estimate_cost(
+                &llm_config.provider,
+                &response.model,
+                response.input_tokens.unwrap_or(0),
+                response.output_tokens.unwrap_or(0),
+            );
+            candidate.run.model = response.model;
+            candidate.run.input_tokens = response.input_tokens;
+            candidate.run.output_tokens = response.output_tokens;
+            candidate.run.tokens_used = response.tokens_used;
+        }
+        Err(e) => {
+            tracing::error!("Candidate generation failed for node {}: {}", node_id, e);
+            candidate.run.error = Some(e.to_string());
+        }
+    }
+
+    Ok(candidate)
+}
+
+fn candidates_path(project_path: &str, node_id: &str) -> Result<PathBuf> {
+    if node_id.is_empty() || node_id.contains(['/', '\\']) || node_id.contains("..") {
+        anyhow::bail!("Invalid node ID: {}", node_id);
+    }
+    Ok(Path::new(project_path)
+        .join(CANDIDATES_DIR)
+        .join(format!("{}.json", node_id)))
+}
+
+/// Candidates generated for a node, oldest first
+pub fn load_candidates(project_path: &str, node_id: &str) -> Result<Vec<Candidate>> {
+    let path = candidates_path(project_path, node_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read candidates: {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse candidates: {:?}", path))
+}
+
+/// Add candidates to their node's history
+pub fn save_candidates(project_path: &str, candidates: &[Candidate]) -> Result<()> {
+    for candidate in candidates {
+        let path = candidates_path(project_path, &candidate.node_id)?;
+        let mut history = load_candidates(project_path, &candidate.node_id)?;
+        history.push(candidate.clone());
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create candidates directory: {:?}", dir))?;
+        }
+        let contents =
+            serde_json::to_string_pretty(&history).context("Failed to serialize candidates")?;
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write candidates: {:?}", path))?;
+    }
+    Ok(())
+}
+
+/// Make a candidate the node's generated code. With `adopt_config`, the node also
+/// keeps the candidate's model settings for future generations.
+pub fn accept_candidate(
+    project: &mut Project,
+    candidate: &Candidate,
+    adopt_config: bool,
+) -> Result<(), String> {
+    let code = candidate
+        .code
+        .clone()
+        .ok_or_else(|| "Candidate has no code: its generation failed".to_string())?;
+    let manifest = project.manifest.clone();
+    let node = project
+        .find_node_mut(&candidate.node_id)
+        .ok_or_else(|| format!("Node '{}' not found", candidate.node_id))?;
+
+    node.generated_code = Some(code);
+    node.status = NodeStatus::Complete;
+    node.error_message = None;
+    node.generated_against_stubs = candidate.stubbed_dependencies.clone();
+    node.consistency_issues.clear();
+    node.last_generation = Some(GenerationRecord {
+        model: candidate.run.model.clone(),
+        tokens_in: candidate.run.input_tokens,
+        tokens_out: candidate.run.output_tokens,
+        duration_ms: candidate.run.duration_ms,
+        timestamp: candidate.created_at,
+        seed: candidate.llm_config.effective_seed(),
+        note: candidate.note.clone(),
+        post_processing: candidate.post_processing.clone(),
+    });
+    if adopt_config {
+        node.llm_config = candidate.llm_config.clone();
+    }
+    verify_generated_code(&manifest, node);
+    project.mark_stub_dependents_stale(&candidate.node_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, Language, LLMProvider};
+
+    #[tokio::test]
+    async fn test_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        // Without an API key the candidate records the failure
+        let variant = GenerationProfile {
+            provider: Some(LLMProvider::OpenAI),
+            model: Some("gpt-4o-mini".to_string()),
+            ..Default::default()
+        };
+        let failed = generate_candidate(&project, &node_id, &variant, None).await.unwrap();
+        assert!(!failed.run.success);
+        assert_eq!(failed.llm_config.model, "gpt-4o-mini");
+        assert!(accept_candidate(&mut project, &failed, true).is_err());
+
+        let candidate = Candidate {
+            code: Some("export const a = 1;".to_string()),
+            ..failed.clone()
+        };
+        save_candidates(&project.project_path, &[failed, candidate.clone()]).unwrap();
+        let history = load_candidates(&project.project_path, &node_id).unwrap();
+        assert_eq!(history.len(), 2);
+
+        accept_candidate(&mut project, &history[1], true).unwrap();
+        let node = project.find_node(&node_id).unwrap();
+        assert_eq!(node.generated_code, candidate.code);
+        assert_eq!(node.status, NodeStatus::Complete);
+        assert_eq!(node.llm_config.provider, LLMProvider::OpenAI);
+    }
+}
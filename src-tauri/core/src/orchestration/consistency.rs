@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::imports::{check_imports, generated_dependency_pairs, ConsistencyIssue};
+use crate::graph::model::{request_timeout, CodeNode, LLMConfig, NodeStatus, Project};
+use crate::llm::{
+    create_provider, generate_with_timeout, parse_json_list, GenerationRequest, LLMProvider,
+    OutputSchema,
+};
+
+/// A search/replace edit to a node's generated code suggested by the review pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePatch {
+    pub file_path: String,
+    /// Exact text to find in the current generated code
+    pub search: String,
+    /// Replacement text
+    pub replace: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// A patch that could not be applied, with the reason
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedPatch {
+    pub patch: CodePatch,
+    pub error: String,
+}
+
+/// Outcome of a consistency pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    pub applied: Vec<CodePatch>,
+    pub rejected: Vec<RejectedPatch>,
+    /// IDs of nodes whose code was changed
+    pub patched_node_ids: Vec<String>,
+}
+
+/// Build the review prompt covering all generated files in the project
+pub fn build_review_prompt(project: &Project) -> String {
+    let mut prompt = String::new();
+
+    prompt.push_str("Review the following generated source files for cross-file consistency:\n");
+    prompt.push_str("- import paths that don't match the actual file paths\n");
+    prompt.push_str("- imported symbols that are not exported (or are named differently) by the dependency\n");
+    prompt.push_str("- inconsistent naming of shared concepts across files\n");
+    prompt.push_str("- helpers duplicated in several files that should be imported instead\n\n");
+
+    for node in &project.nodes {
+        if let Some(code) = &node.generated_code {
+            prompt.push_str(&format!("## File: {}\n```\n", node.file_path));
+            prompt.push_str(code);
+            if !code.ends_with('\n') {
+                prompt.push('\n');
+            }
+            prompt.push_str("```\n\n");
+        }
+    }
+
+    prompt.push_str(
+        "Respond with ONLY a JSON object whose \"patches\" array lists the patches (empty if everything is consistent). \
+         Each patch is an object with \"filePath\", \"search\" (an exact, unique excerpt of the current file), \
+         \"replace\" (the replacement text) and \"reason\". Keep patches minimal.",
+    );
+
+    prompt
+}
+
+/// Structured output requested from the review pass
+fn patches_schema() -> OutputSchema {
+    OutputSchema::list(
+        "submit_patches",
+        "Submit the search/replace patches that fix cross-file inconsistencies",
+        "patches",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "filePath": { "type": "string" },
+                "search": { "type": "string" },
+                "replace": { "type": "string" },
+                "reason": { "type": "string" },
+            },
+            "required": ["filePath", "search", "replace"],
+        }),
+    )
+}
+
+/// Parse the patch list out of an LLM response
+pub fn parse_patches(content: &str) -> Result<Vec<CodePatch>, String> {
+    parse_json_list(content, "patches").map_err(|e| format!("Invalid patch list: {}", e))
+}
+
+/// Build the prompt comparing a node's generated code with one of its dependencies'
+pub fn build_pair_review_prompt(node: &CodeNode, dependency: &CodeNode) -> String {
+    let mut prompt = format!(
+        "{} depends on {}. Check that it uses the dependency correctly:\n",
+        node.file_path, dependency.file_path
+    );
+    prompt.push_str("- imported symbols that the dependency doesn't export\n");
+    prompt.push_str("- calls whose arguments don't match the dependency's signatures\n");
+    prompt.push_str("- fields, methods or return values used unlike their definitions\n\n");
+
+    for file in [dependency, node] {
+        prompt.push_str(&format!("## File: {}\n```\n", file.file_path));
+        let code = file.generated_code.as_deref().unwrap_or_default();
+        prompt.push_str(code);
+        if !code.ends_with('\n') {
+            prompt.push('\n');
+        }
+        prompt.push_str("```\n\n");
+    }
+
+    prompt.push_str(&format!(
+        "Respond with ONLY a JSON object whose \"issues\" array lists the mismatches in {} \
+         (empty if it uses {} correctly). Each issue is an object with \"message\", \
+         \"suggestion\" (how to fix it) and \"line\" (the 1-based line in {}).",
+        node.file_path, dependency.file_path, node.file_path
+    ));
+    prompt
+}
+
+/// Structured output requested from a pair review
+fn issues_schema() -> OutputSchema {
+    OutputSchema::list(
+        "submit_issues",
+        "Submit the mismatches between the file and its dependency",
+        "issues",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": { "type": "string" },
+                "suggestion": { "type": "string" },
+                "line": { "type": "integer" },
+            },
+            "required": ["message"],
+        }),
+    )
+}
+
+/// An issue as reported by a pair review
+#[derive(Debug, Deserialize)]
+struct ReviewedIssue {
+    message: String,
+    #[serde(default)]
+    suggestion: Option<String>,
+    #[serde(default)]
+    line: Option<usize>,
+}
+
+/// Parse the issue list out of a pair review's response
+pub fn parse_issues(content: &str, dependency_id: &str) -> Result<Vec<ConsistencyIssue>, String> {
+    let issues: Vec<ReviewedIssue> =
+        parse_json_list(content, "issues").map_err(|e| format!("Invalid issue list: {}", e))?;
+    Ok(issues
+        .into_iter()
+        .map(|issue| ConsistencyIssue {
+            dependency_id: dependency_id.to_string(),
+            message: issue.message,
+            suggestion: issue.suggestion.filter(|s| !s.trim().is_empty()),
+            line: issue.line,
+        })
+        .collect())
+}
+
+/// The project's default LLM, for reviews that span several nodes
+fn review_provider(
+    project: &Project,
+    api_key: Option<String>,
+) -> Result<(LLMConfig, Box<dyn LLMProvider>), String> {
+    let config = LLMConfig {
+        provider: project.manifest.default_llm.provider.clone(),
+        model: project.manifest.default_llm.model.clone(),
+        ..LLMConfig::default()
+    };
+    let provider = create_provider(&config, api_key, project);
+
+    if !provider.is_configured() {
+        return Err(format!(
+            "{} is not configured. Please set your API key in Settings.",
+            provider.name()
+        ));
+    }
+    Ok((config, provider))
+}
+
+/// Check each generated node against the generated dependencies it's connected to and
+/// record the mismatches on the node; complete nodes with any become warnings. Imports
+/// are resolved statically; with `review`, the project's default LLM also compares each
+/// pair of files for mismatched signatures and usage.
+pub async fn check_consistency(
+    project: &mut Project,
+    review: bool,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    let pairs = generated_dependency_pairs(project);
+    let mut found: HashMap<String, Vec<ConsistencyIssue>> = HashMap::new();
+    for (node, dependency) in &pairs {
+        let issues = check_imports(node, dependency);
+        found.entry(node.id.clone()).or_default().extend(issues);
+    }
+
+    if review && !pairs.is_empty() {
+        let (config, provider) = review_provider(project, api_key)?;
+        for (node, dependency) in &pairs {
+            let request = GenerationRequest {
+                prompt: build_pair_review_prompt(node, dependency),
+                system_prompt: Some(
+                    "You are a meticulous senior engineer checking that a file uses its dependency correctly."
+                        .to_string(),
+                ),
+                max_tokens: Some(4096),
+                temperature: Some(0.2),
+                cache_breakpoints: Vec::new(),
+                output_schema: Some(issues_schema()),
+                seed: None,
+                timeout: Some(request_timeout(&project.manifest, &config)),
+            };
+            let response = generate_with_timeout(provider.as_ref(), request)
+                .await
+                .map_err(|e| e.to_string())?;
+            let recorded = found.entry(node.id.clone()).or_default();
+            let issues: Vec<_> = parse_issues(&response.content, &dependency.id)?
+                .into_iter()
+                // Lines the static check already reported
+                .filter(|i| i.line.is_none() || !recorded.iter().any(|r| r.line == i.line))
+                .collect();
+            recorded.extend(issues);
+        }
+    }
+
+    for node in &mut project.nodes {
+        node.consistency_issues = found.remove(&node.id).unwrap_or_default();
+        if !node.consistency_issues.is_empty() && node.status == NodeStatus::Complete {
+            node.status = NodeStatus::Warning;
+            node.error_message = Some(format!(
+                "{} {}",
+                node.consistency_issues.len(),
+                CONSISTENCY_WARNING
+            ));
+        } else if node.consistency_issues.is_empty()
+            && node.status == NodeStatus::Warning
+            && node.error_message.as_deref().is_some_and(|m| m.ends_with(CONSISTENCY_WARNING))
+        {
+            // Fixed since the last check
+            node.status = NodeStatus::Complete;
+            node.error_message = None;
+        }
+    }
+    Ok(())
+}
+
+/// End of the message on nodes the check turned into warnings
+const CONSISTENCY_WARNING: &str = "consistency issue(s) with its dependencies";
+
+/// Apply a single patch to the project, returning the ID of the patched node
+pub fn apply_patch(project: &mut Project, patch: &CodePatch) -> Result<String, String> {
+    let node = project
+        .nodes
+        .iter_mut()
+        .find(|n| n.file_path == patch.file_path)
+        .ok_or_else(|| format!("No node for file '{}'", patch.file_path))?;
+
+    let code = node
+        .generated_code
+        .as_ref()
+        .ok_or_else(|| format!("'{}' has no generated code", patch.file_path))?;
+
+    if patch.search.is_empty() {
+        return Err("Patch has an empty search text".to_string());
+    }
+
+    match code.matches(patch.search.as_str()).count() {
+        0 => return Err("Search text not found".to_string()),
+        1 => {}
+        _ => return Err("Search text is ambiguous (matches more than once)".to_string()),
+    }
+
+    node.generated_code = Some(code.replacen(&patch.search, &patch.replace, 1));
+    Ok(node.id.clone())
+}
+
+/// Run an LLM consistency review over all generated code and apply its patches.
+/// Uses the project's default LLM.
+pub async fn run_consistency_pass(
+    project: &mut Project,
+    api_key: Option<String>,
+) -> Result<ConsistencyReport, String> {
+    let generated = project
+        .nodes
+        .iter()
+        .filter(|n| n.generated_code.is_some())
+        .count();
+
+    // Nothing to cross-check
+    if generated < 2 {
+        return Ok(ConsistencyReport::default());
+    }
+
+    let (config, provider) = review_provider(project, api_key)?;
+
+    let request = GenerationRequest {
+        prompt: build_review_prompt(project),
+        system_prompt: Some(
+            "You are a meticulous senior engineer reviewing a generated codebase for cross-file consistency."
+                .to_string(),
+        ),
+        max_tokens: Some(8192),
+        temperature: Some(0.2),
+        cache_breakpoints: Vec::new(),
+        output_schema: Some(patches_schema()),
+        seed: None,
+        timeout: Some(request_timeout(&project.manifest, &config)),
+    };
+
+    let response = generate_with_timeout(provider.as_ref(), request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let patches = parse_patches(&response.content)?;
+
+    let mut report = ConsistencyReport::default();
+    for patch in patches {
+        match apply_patch(project, &patch) {
+            Ok(node_id) => {
+                if !report.patched_node_ids.contains(&node_id) {
+                    report.patched_node_ids.push(node_id);
+                }
+                report.applied.push(patch);
+            }
+            Err(error) => report.rejected.push(RejectedPatch { patch, error }),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, Language};
+
+    #[test]
+    fn test_parse_and_apply_patches() {
+        let mut project = Project::new(String::new());
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.generated_code = Some("import { foo } from './b';\nfoo();\n".to_string());
+        project.nodes.push(node);
+
+        let response = "Here are the fixes:\n[{\"filePath\": \"a.ts\", \"search\": \"from './b'\", \"replace\": \"from './b.js'\", \"reason\": \"ESM path\"}]";
+        let patches = parse_patches(response).unwrap();
+        assert_eq!(patches.len(), 1);
+
+        apply_patch(&mut project, &patches[0]).unwrap();
+        assert!(project.nodes[0]
+            .generated_code
+            .as_ref()
+            .unwrap()
+            .contains("from './b.js'"));
+
+        // Applying again fails because the search text is gone
+        assert!(apply_patch(&mut project, &patches[0]).is_err());
+
+        // A tool call's arguments hold the list under "patches"
+        let structured = "{\"patches\": [{\"filePath\": \"a.ts\", \"search\": \"foo();\", \"replace\": \"foo(1);\"}]}";
+        assert_eq!(parse_patches(structured).unwrap()[0].replace, "foo(1);");
+    }
+
+    #[tokio::test]
+    async fn test_check_consistency() {
+        let mut project = Project::new(String::new());
+        let mut a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        a.generated_code = Some("export const foo = 1;\n".to_string());
+        let mut b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        b.generated_code = Some("import { foo, bar } from './a';\n".to_string());
+        b.status = NodeStatus::Complete;
+        project.edges.push(CodeEdge::new(a.id.clone(), b.id.clone(), "uses".to_string()));
+        project.nodes = vec![a, b];
+
+        check_consistency(&mut project, false, None).await.unwrap();
+        let b = &project.nodes[1];
+        assert_eq!(b.status, NodeStatus::Warning);
+        assert_eq!(b.consistency_issues.len(), 1);
+        assert_eq!(b.consistency_issues[0].dependency_id, project.nodes[0].id);
+        assert!(project.nodes[0].consistency_issues.is_empty());
+
+        // Fixing the import returns the node to complete
+        project.nodes[1].generated_code = Some("import { foo } from './a';\n".to_string());
+        check_consistency(&mut project, false, None).await.unwrap();
+        assert_eq!(project.nodes[1].status, NodeStatus::Complete);
+        assert_eq!(project.nodes[1].error_message, None);
+
+        let response = "{\"issues\": [{\"message\": \"foo is a number\", \"suggestion\": \"\"}]}";
+        let issues = parse_issues(response, "a").unwrap();
+        assert_eq!(issues[0].suggestion, None);
+    }
+}
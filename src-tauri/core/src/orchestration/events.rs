@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::graph::model::{NodeStatus, Project};
+
+/// Progress update for a single node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProgress {
+    pub node_id: String,
+    pub status: NodeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_code: Option<String>,
+}
+
+/// Events emitted during execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ExecutionEvent {
+    /// Execution has started
+    #[serde(rename_all = "camelCase")]
+    Started {
+        total_nodes: usize,
+        total_waves: usize,
+    },
+
+    /// A new wave has started
+    #[serde(rename_all = "camelCase")]
+    WaveStarted {
+        wave_number: usize,
+        node_ids: Vec<String>,
+    },
+
+    /// A node's status has changed
+    #[serde(rename_all = "camelCase")]
+    NodeUpdate(NodeProgress),
+
+    /// A wave has completed
+    #[serde(rename_all = "camelCase")]
+    WaveCompleted {
+        wave_number: usize,
+        successful: usize,
+        failed: usize,
+    },
+
+    /// A step-mode run is paused after a wave until it's told to continue
+    #[serde(rename_all = "camelCase")]
+    WavePaused {
+        wave_number: usize,
+        remaining_waves: usize,
+        /// The project as generated so far; continue from it, with any spec edits
+        project: Box<Project>,
+    },
+
+    /// A run is paused until the generated code of nodes marked as requiring approval
+    /// is approved or rejected; their dependents are generated only once approved
+    #[serde(rename_all = "camelCase")]
+    ApprovalRequired {
+        wave_number: usize,
+        node_ids: Vec<String>,
+        remaining_waves: usize,
+        /// The project as generated so far
+        project: Box<Project>,
+    },
+
+    /// Execution completed
+    #[serde(rename_all = "camelCase")]
+    Completed {
+        total_successful: usize,
+        total_failed: usize,
+        total_skipped: usize,
+        /// The run reached its maximum duration, leaving nodes it hadn't started pending
+        timed_out: bool,
+    },
+
+    /// The cross-file consistency pass finished
+    #[serde(rename_all = "camelCase")]
+    ConsistencyPassCompleted {
+        applied: usize,
+        rejected: usize,
+    },
+
+    /// A budget ran out; no further nodes are started this run
+    #[serde(rename_all = "camelCase")]
+    BudgetExceeded {
+        message: String,
+        /// Tokens this run used
+        tokens_used: u64,
+        /// Estimated cost of this run (USD)
+        cost_estimate: f64,
+    },
+
+    /// Execution was cancelled
+    Cancelled,
+
+    /// Execution error (not a node error, but system error)
+    #[serde(rename_all = "camelCase")]
+    Error {
+        message: String,
+    },
+}
+
+/// Where an executor reports its progress: the frontend for Tauri commands, a
+/// channel for the HTTP API
+pub trait EventSink: Send + Sync {
+    fn send(&self, event: ExecutionEvent);
+}
+
+impl EventSink for mpsc::UnboundedSender<ExecutionEvent> {
+    fn send(&self, event: ExecutionEvent) {
+        // The receiver going away only means nobody is listening any more
+        let _ = mpsc::UnboundedSender::send(self, event);
+    }
+}
@@ -0,0 +1,1395 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures::StreamExt;
+use tokio::sync::RwLock;
+
+use crate::graph::budget::{Budget, Spend};
+use crate::graph::constraints::{fix_generated_code, verify_generated_code, FixupOptions};
+use crate::graph::model::{
+    generation_settings, request_timeout, EmbeddingConfig, GenerationProfile, GenerationRecord,
+    NodeStatus, PostProcessReport, Project,
+};
+use crate::graph::search::refresh_for_related_context;
+use crate::graph::settings::ProjectSettings;
+use crate::graph::variables::resolve_node;
+use crate::llm::{
+    create_provider, estimate_cost, extract_code, generate_with_retries, ContextBuilder,
+    GenerationRequest, PendingTranscript,
+};
+
+use super::checkpoint::{clear_checkpoint, save_checkpoint, RunCheckpoint};
+use super::consistency::run_consistency_pass;
+use super::events::{EventSink, ExecutionEvent, NodeProgress};
+use super::pilot::PilotReport;
+use super::postprocess::PostProcessor;
+use super::planner::{ExecutionOptions, ExecutionPlan, ExecutionWave};
+use super::report::{project_spend, save_run_report, NodeRunReport, RunReport};
+use super::step::{PausedStep, StepAction, StepControl};
+
+/// Nodes generated at once within a wave when a budget applies and no concurrency
+/// limit is set, so the budget is checked as the wave goes
+const BUDGETED_CONCURRENCY: usize = 4;
+
+/// Error message of the nodes a run left pending because it reached its maximum duration
+pub const NOT_RUN_MESSAGE: &str = "Not generated: the run reached its maximum duration";
+
+/// API keys for different providers
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    pub anthropic: Option<String>,
+    pub openai: Option<String>,
+    pub mistral: Option<String>,
+    pub ollama_base_url: Option<String>,
+}
+
+impl ApiKeys {
+    /// Get the API key for a specific provider
+    pub fn get_for_provider(&self, provider: &crate::graph::model::LLMProvider) -> Option<String> {
+        match provider {
+            crate::graph::model::LLMProvider::Anthropic => self.anthropic.clone(),
+            crate::graph::model::LLMProvider::OpenAI => self.openai.clone(),
+            crate::graph::model::LLMProvider::Mistral => self.mistral.clone(),
+            crate::graph::model::LLMProvider::Ollama
+            | crate::graph::model::LLMProvider::Local
+            | crate::graph::model::LLMProvider::Mock => None, // No API key needed
+        }
+    }
+}
+
+/// Result of generating a single node
+#[derive(Debug, Clone)]
+pub struct NodeResult {
+    pub node_id: String,
+    pub success: bool,
+    pub generated_code: Option<String>,
+    pub error_message: Option<String>,
+    pub model: String,
+    pub duration_ms: u64,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+    pub tokens_used: Option<u32>,
+    pub cost_estimate: Option<f64>,
+    /// Dependencies that were injected as stub interfaces into the prompt
+    pub stubbed_dependencies: Vec<String>,
+    /// Seed sent with the request
+    pub seed: Option<u64>,
+    /// Times the request was sent again after a retryable failure
+    pub retries: u32,
+    /// Tokens and estimated cost of the fix-up request, when one was made
+    pub fixup: Option<Spend>,
+    /// Prose the model wrapped around the code
+    pub note: Option<String>,
+    /// What each post-processing step did to the code
+    pub post_processing: Vec<PostProcessReport>,
+}
+
+impl NodeResult {
+    /// A failed result with no usage information
+    fn failed(node_id: &str, model: String, error_message: String) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            success: false,
+            generated_code: None,
+            error_message: Some(error_message),
+            model,
+            duration_ms: 0,
+            input_tokens: None,
+            output_tokens: None,
+            tokens_used: None,
+            cost_estimate: None,
+            stubbed_dependencies: Vec::new(),
+            seed: None,
+            retries: 0,
+            fixup: None,
+            note: None,
+            post_processing: Vec::new(),
+        }
+    }
+
+    /// Convert into an entry for the run report
+    pub fn to_run_report(&self, node_name: String) -> NodeRunReport {
+        NodeRunReport {
+            node_id: self.node_id.clone(),
+            node_name,
+            model: self.model.clone(),
+            success: self.success,
+            duration_ms: self.duration_ms,
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            tokens_used: self.tokens_used,
+            cost_estimate: self.cost_estimate,
+            fixup: self.fixup,
+            retries: self.retries,
+            error: self.error_message.clone(),
+        }
+    }
+}
+
+/// Executor for running code generation across the graph
+pub struct Executor {
+    sink: Arc<dyn EventSink>,
+    project: Arc<RwLock<Project>>,
+    api_keys: ApiKeys,
+    /// Overrides applied to every node's LLM config for this run
+    profile: Option<GenerationProfile>,
+    /// Nodes generated at once within a wave (all of them when unset, or
+    /// `BUDGETED_CONCURRENCY` when a budget applies)
+    max_concurrency: Option<usize>,
+    /// Limits on this run's spend
+    run_budget: Budget,
+    /// Limits on the spend of all runs of the project, this one included
+    project_budget: Budget,
+    /// What this run has spent so far
+    spent: RwLock<Spend>,
+    /// What earlier runs of the project spent, when it has a budget
+    previous_spend: RwLock<Spend>,
+    /// Set once a budget has run out, so no further node is started
+    budget_exhausted: AtomicBool,
+    /// Pauses the run after each wave, in step mode
+    step: Option<StepControl>,
+    /// Pauses the run for nodes that require approval before their dependents
+    approvals: Option<StepControl>,
+    /// Nodes not to start, as code they depend on was rejected
+    blocked: RwLock<HashSet<String>>,
+    /// How long the run may take before no further nodes are started
+    max_duration: Option<Duration>,
+    /// When the run has to end by, once it has started
+    deadline: RwLock<Option<tokio::time::Instant>>,
+    /// Set once the deadline has passed
+    timed_out: AtomicBool,
+    /// Progress kept on disk while the run runs, so it can be resumed if interrupted
+    checkpoint: RwLock<Option<RunCheckpoint>>,
+    cancelled: Arc<RwLock<bool>>,
+}
+
+impl Executor {
+    pub fn new(sink: impl EventSink + 'static, project: Project, api_keys: ApiKeys) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            project: Arc::new(RwLock::new(project)),
+            api_keys,
+            profile: None,
+            max_concurrency: None,
+            run_budget: Budget::default(),
+            project_budget: Budget::default(),
+            spent: RwLock::new(Spend::default()),
+            previous_spend: RwLock::new(Spend::default()),
+            budget_exhausted: AtomicBool::new(false),
+            step: None,
+            approvals: None,
+            blocked: RwLock::new(HashSet::new()),
+            max_duration: None,
+            deadline: RwLock::new(None),
+            timed_out: AtomicBool::new(false),
+            checkpoint: RwLock::new(None),
+            cancelled: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    /// Generate with the given profile instead of each node's own settings
+    pub fn with_profile(mut self, profile: Option<GenerationProfile>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Pause after each wave until `step` is told to continue
+    pub fn with_step_mode(mut self, step: Option<StepControl>) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Pause after nodes that require approval until `approvals` is told whether their
+    /// code is approved. Without it, such nodes don't hold up the run.
+    pub fn with_approval_gates(mut self, approvals: Option<StepControl>) -> Self {
+        self.approvals = approvals;
+        self
+    }
+
+    /// End the run after `max_duration`: nodes already generating are finished, the
+    /// rest are left pending
+    pub fn with_max_duration(mut self, max_duration: Option<Duration>) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    /// Carry on an interrupted run's checkpoint, so the nodes it finished stay recorded
+    /// should this run be interrupted too
+    pub fn resuming(mut self, checkpoint: RunCheckpoint) -> Self {
+        self.checkpoint = RwLock::new(Some(checkpoint));
+        self
+    }
+
+    /// Apply the project's local settings: its concurrency limit and budgets, and
+    /// its preferred provider and model unless a profile was given
+    pub fn with_settings(mut self, settings: &ProjectSettings) -> Self {
+        self.max_concurrency = settings.max_concurrency.filter(|c| *c > 0);
+        self.run_budget = settings.run_budget();
+        self.project_budget = settings.project_budget();
+        if self.profile.is_none() {
+            self.profile = settings.profile();
+        }
+        self
+    }
+
+    /// Snapshot of the project as the executor currently sees it
+    pub async fn project(&self) -> Project {
+        self.project.read().await.clone()
+    }
+
+    /// Report progress to the executor's sink
+    fn emit(&self, event: ExecutionEvent) {
+        self.sink.send(event);
+    }
+
+    /// Run a wave's generations, at most `max_concurrency` at a time, returning the
+    /// results in the wave's order
+    async fn run_wave<F: Future>(&self, futures: Vec<F>) -> Vec<F::Output> {
+        let budgeted = !self.run_budget.is_unlimited() || !self.project_budget.is_unlimited();
+        let limit = match self.max_concurrency {
+            Some(limit) => limit,
+            None if budgeted => BUDGETED_CONCURRENCY,
+            None => futures.len(),
+        }
+        .max(1);
+        futures::stream::iter(futures).buffered(limit).collect().await
+    }
+
+    /// Add up what earlier runs spent, when the project has a budget
+    async fn start_budget(&self) {
+        if self.project_budget.is_unlimited() {
+            return;
+        }
+        let project_path = self.project.read().await.project_path.clone();
+        let previous = project_spend(&project_path).unwrap_or_else(|e| {
+            tracing::warn!("Failed to add up spend of earlier runs: {}", e);
+            Spend::default()
+        });
+        *self.previous_spend.write().await = previous;
+    }
+
+    /// Save a checkpoint of the run before it generates anything
+    async fn start_checkpoint(&self, plan: &[ExecutionWave]) {
+        let mut checkpoint = self.checkpoint.write().await;
+        let checkpoint = checkpoint.get_or_insert_with(|| {
+            RunCheckpoint::new(plan.iter().flat_map(|w| w.node_ids.clone()).collect())
+        });
+        let project_path = self.project.read().await.project_path.clone();
+        if let Err(e) = save_checkpoint(&project_path, checkpoint) {
+            tracing::warn!("Failed to save run checkpoint: {}", e);
+        }
+    }
+
+    /// Record the wave's finished nodes in the run's checkpoint
+    async fn save_progress(&self, wave: &ExecutionWave) {
+        let mut checkpoint = self.checkpoint.write().await;
+        let Some(checkpoint) = checkpoint.as_mut() else {
+            return;
+        };
+        let project = self.project.read().await;
+        for node_id in &wave.node_ids {
+            let Some(node) = project.find_node(node_id) else {
+                continue;
+            };
+            if matches!(node.status, NodeStatus::Complete | NodeStatus::Warning | NodeStatus::Error)
+            {
+                checkpoint.record(node);
+            }
+        }
+        if let Err(e) = save_checkpoint(&project.project_path, checkpoint) {
+            tracing::warn!("Failed to save run checkpoint: {}", e);
+        }
+    }
+
+    /// Remove the checkpoint of a run that has finished
+    async fn clear_progress(&self) {
+        let project_path = self.project.read().await.project_path.clone();
+        if let Err(e) = clear_checkpoint(&project_path) {
+            tracing::warn!("Failed to remove run checkpoint: {}", e);
+        }
+    }
+
+    /// Start the clock on the run's maximum duration, if it has one
+    async fn start_deadline(&self) {
+        *self.deadline.write().await =
+            self.max_duration.map(|limit| tokio::time::Instant::now() + limit);
+    }
+
+    /// Whether the run's deadline has passed
+    async fn past_deadline(&self) -> bool {
+        if self.timed_out.load(Ordering::SeqCst) {
+            return true;
+        }
+        let Some(deadline) = *self.deadline.read().await else {
+            return false;
+        };
+        if tokio::time::Instant::now() < deadline {
+            return false;
+        }
+        if !self.timed_out.swap(true, Ordering::SeqCst) {
+            tracing::warn!("Run reached its maximum duration; no further nodes will be started");
+        }
+        true
+    }
+
+    /// Leave a node the deadline kept from being generated pending, with
+    /// `NOT_RUN_MESSAGE` as its error so it can be told apart from other pending nodes
+    async fn mark_not_run(&self, node_id: &str) {
+        let message = NOT_RUN_MESSAGE.to_string();
+        self.update_node(node_id, NodeStatus::Pending, None, Some(message.clone())).await;
+        self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+            node_id: node_id.to_string(),
+            status: NodeStatus::Pending,
+            message: Some(message),
+            generated_code: None,
+        }));
+    }
+
+    /// Whether the run's or the project's budget has run out, emitting
+    /// `BudgetExceeded` the first time
+    async fn budget_exceeded(&self) -> bool {
+        if self.budget_exhausted.load(Ordering::SeqCst) {
+            return true;
+        }
+
+        let spent = *self.spent.read().await;
+        let previous = *self.previous_spend.read().await;
+        let project_spent = Spend {
+            tokens: previous.tokens + spent.tokens,
+            cost: previous.cost + spent.cost,
+        };
+        let message = match (
+            self.run_budget.exceeded(spent),
+            self.project_budget.exceeded(project_spent),
+        ) {
+            (Some(reason), _) => format!("Run budget exceeded: {}", reason),
+            (None, Some(reason)) => format!("Project budget exceeded: {}", reason),
+            (None, None) => return false,
+        };
+
+        if !self.budget_exhausted.swap(true, Ordering::SeqCst) {
+            tracing::warn!("{}; no further nodes will be started", message);
+            self.emit(ExecutionEvent::BudgetExceeded {
+                message,
+                tokens_used: spent.tokens,
+                cost_estimate: spent.cost,
+            });
+        }
+        true
+    }
+
+    /// Nodes of the wave that require approval, were generated, and have dependents in
+    /// the remaining waves
+    async fn approval_gates(
+        &self,
+        wave: &ExecutionWave,
+        remaining: &[ExecutionWave],
+    ) -> Vec<String> {
+        let project = self.project.read().await;
+        let pending: HashSet<&String> = remaining.iter().flat_map(|w| &w.node_ids).collect();
+        wave.node_ids
+            .iter()
+            .filter(|id| {
+                let generated = project.find_node(id).is_some_and(|n| {
+                    n.requires_approval
+                        && matches!(n.status, NodeStatus::Complete | NodeStatus::Warning)
+                });
+                generated
+                    && ExecutionPlan::dependent_closure(&project, &HashSet::from([(*id).clone()]))
+                        .iter()
+                        .any(|d| pending.contains(d))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Pause after a wave until told to continue: after every wave in step mode, and
+    /// otherwise when nodes awaiting approval have dependents still to generate. There's
+    /// no pause after the last wave or once a budget or the time ran out, and a pause
+    /// ends at the deadline. The run carries on with the specs it's continued with (see
+    /// `Project::adopt_specs`); returns `false` when it should stop.
+    async fn pause_after_wave(&self, wave: &ExecutionWave, remaining: &[ExecutionWave]) -> bool {
+        if remaining.is_empty()
+            || self.budget_exhausted.load(Ordering::SeqCst)
+            || self.past_deadline().await
+        {
+            return true;
+        }
+        let awaiting_approval = match &self.approvals {
+            Some(_) => self.approval_gates(wave, remaining).await,
+            None => Vec::new(),
+        };
+        let control = if awaiting_approval.is_empty() { &self.step } else { &self.approvals };
+        let Some(control) = control else {
+            return true;
+        };
+
+        let wave_number = wave.wave_number;
+        let remaining_waves = remaining.len();
+        let project = Box::new(self.project().await);
+        let paused = PausedStep {
+            wave_number,
+            remaining_waves,
+            awaiting_approval: awaiting_approval.clone(),
+        };
+        let pause = control.wait(paused, || {
+            self.emit(if awaiting_approval.is_empty() {
+                ExecutionEvent::WavePaused { wave_number, remaining_waves, project }
+            } else {
+                ExecutionEvent::ApprovalRequired {
+                    wave_number,
+                    node_ids: awaiting_approval.clone(),
+                    remaining_waves,
+                    project,
+                }
+            })
+        });
+        let deadline = *self.deadline.read().await;
+        let action = match deadline {
+            // Out of time while paused: the remaining waves are left pending
+            Some(deadline) => match tokio::time::timeout_at(deadline, pause).await {
+                Ok(action) => action,
+                Err(_) => return true,
+            },
+            None => pause.await,
+        };
+        match action {
+            StepAction::Continue(Some(project)) => {
+                self.project.write().await.adopt_specs(&project);
+                true
+            }
+            StepAction::Continue(None) => true,
+            StepAction::Stop => false,
+            StepAction::Reject => {
+                self.reject(&awaiting_approval).await;
+                true
+            }
+        }
+    }
+
+    /// Mark the nodes' code as rejected and keep everything depending on them from
+    /// being generated
+    async fn reject(&self, node_ids: &[String]) {
+        let message = "Generated code was rejected".to_string();
+        for node_id in node_ids {
+            self.update_node(node_id, NodeStatus::Error, None, Some(message.clone())).await;
+            self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                node_id: node_id.clone(),
+                status: NodeStatus::Error,
+                message: Some(message.clone()),
+                generated_code: None,
+            }));
+        }
+
+        let rejected = node_ids.iter().cloned().collect();
+        let dependents = ExecutionPlan::dependent_closure(&*self.project.read().await, &rejected);
+        self.blocked.write().await.extend(dependents);
+    }
+
+    /// Check if execution has been cancelled
+    async fn is_cancelled(&self) -> bool {
+        *self.cancelled.read().await
+    }
+
+    /// Generate code for a single node
+    async fn generate_node(&self, node_id: &str) -> NodeResult {
+        // Get current project state
+        let project = self.project.read().await;
+
+        // The spec as the model sees it, for fix-ups and post-processing
+        let node = match project.find_node(node_id) {
+            Some(n) => resolve_node(&project.manifest, n),
+            None => {
+                return NodeResult::failed(
+                    node_id,
+                    String::new(),
+                    format!("Node '{}' not found", node_id),
+                );
+            }
+        };
+
+        let (llm_config, max_tokens, temperature) = generation_settings(
+            &node.llm_config.resolve(&project.manifest.default_llm),
+            self.profile.as_ref(),
+        );
+
+        // Build prompt
+        let (prompt, cache_breakpoints) =
+            match ContextBuilder::build_prompt_with_breakpoints(&project, node_id) {
+                Some(p) => p,
+                None => {
+                    return NodeResult::failed(
+                        node_id,
+                        llm_config.model.clone(),
+                        "Failed to build prompt".to_string(),
+                    );
+                }
+            };
+
+        let system_prompt = ContextBuilder::build_system_prompt(&project.manifest, &node);
+        let stubbed_dependencies = ContextBuilder::stubbed_dependencies(&project, node_id);
+
+        // Get API key for provider
+        let api_key = self.api_keys.get_for_provider(&llm_config.provider);
+
+        // Create provider
+        let provider = create_provider(&llm_config, api_key.clone(), &project);
+
+        if !provider.is_configured() {
+            return NodeResult::failed(
+                node_id,
+                llm_config.model.clone(),
+                format!(
+                    "{} is not configured. Please set your API key in Settings.",
+                    provider.name()
+                ),
+            );
+        }
+
+        // Generate
+        let request = GenerationRequest {
+            prompt,
+            system_prompt: Some(system_prompt),
+            max_tokens: Some(max_tokens),
+            temperature: Some(temperature),
+            cache_breakpoints,
+            output_schema: None,
+            seed: llm_config.effective_seed(),
+            timeout: Some(request_timeout(&project.manifest, &llm_config)),
+        };
+        let transcript = PendingTranscript::begin(&project, &node, &llm_config, &request);
+        let fixup = FixupOptions::from_manifest(&project.manifest)
+            .map(|options| (options, request.clone()));
+        let post_processor = PostProcessor::for_node(&project, &node);
+
+        // Release the read lock before making async call
+        drop(project);
+
+        let started = Instant::now();
+        let (result, retries) = generate_with_retries(provider.as_ref(), request).await;
+        if let Some(transcript) = transcript {
+            transcript.finish(&result, started.elapsed().as_millis() as u64, api_key.as_deref());
+        }
+
+        match result {
+            Ok(response) => {
+                // Strip markdown code blocks and prose if present
+                let extracted = extract_code(&response.content, &node.language, node.kind);
+                let mut code = extracted.code;
+                let mut fixup_spend = None;
+                if let Some((options, request)) = &fixup {
+                    let fixed =
+                        fix_generated_code(provider.as_ref(), &node, request, code, *options)
+                            .await;
+                    code = fixed.0;
+                    fixup_spend = fixed
+                        .1
+                        .map(|response| Spend::of_response(&llm_config.provider, &response));
+                }
+                let mut post_processing = Vec::new();
+                if let Some(processor) = &post_processor {
+                    (code, post_processing) = processor.run(code).await;
+                }
+                NodeResult {
+                    node_id: node_id.to_string(),
+                    success: true,
+                    generated_code: Some(code),
+                    error_message: None,
+                    cost_estimate: estimate_cost(
+                        &llm_config.provider,
+                        &response.model,
+                        response.input_tokens.unwrap_or(0),
+                        response.output_tokens.unwrap_or(0),
+                    ),
+                    model: response.model,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    input_tokens: response.input_tokens,
+                    output_tokens: response.output_tokens,
+                    tokens_used: response.tokens_used,
+                    stubbed_dependencies,
+                    seed: llm_config.effective_seed(),
+                    retries,
+                    fixup: fixup_spend,
+                    note: extracted.note,
+                    post_processing,
+                }
+            }
+            Err(e) => NodeResult {
+                duration_ms: started.elapsed().as_millis() as u64,
+                retries,
+                ..NodeResult::failed(node_id, llm_config.model.clone(), e.to_string())
+            },
+        }
+    }
+
+    /// Update a node's status and optionally its generated code
+    async fn update_node(&self, node_id: &str, status: NodeStatus, code: Option<String>, error: Option<String>) {
+        let mut project = self.project.write().await;
+        if let Some(node) = project.find_node_mut(node_id) {
+            node.status = status;
+            if let Some(c) = code {
+                node.generated_code = Some(c);
+            }
+            if let Some(e) = error {
+                node.error_message = Some(e);
+            } else {
+                node.error_message = None;
+            }
+        }
+    }
+
+    /// Record usage and the stubs a freshly generated node was built against, and
+    /// mark nodes that were generated against a stub of it as stale. Returns the
+    /// node's status once its code has been verified.
+    async fn record_generation(&self, result: &NodeResult) -> NodeStatus {
+        let mut status = NodeStatus::Complete;
+        let affected = {
+            let mut project = self.project.write().await;
+            let manifest = project.manifest.clone();
+            if let Some(node) = project.find_node_mut(&result.node_id) {
+                node.generated_against_stubs = result.stubbed_dependencies.clone();
+                node.consistency_issues.clear();
+                node.last_generation = Some(GenerationRecord {
+                    model: result.model.clone(),
+                    tokens_in: result.input_tokens,
+                    tokens_out: result.output_tokens,
+                    duration_ms: result.duration_ms,
+                    timestamp: chrono::Utc::now(),
+                    seed: result.seed,
+                    note: result.note.clone(),
+                    post_processing: result.post_processing.clone(),
+                });
+                verify_generated_code(&manifest, node);
+                status = node.status.clone();
+            }
+            project.mark_stub_dependents_stale(&result.node_id)
+        };
+
+        for stale_id in affected {
+            self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                node_id: stale_id,
+                status: NodeStatus::Stale,
+                message: Some("Generated against a stub of a dependency that now exists".to_string()),
+                generated_code: None,
+            }));
+        }
+        status
+    }
+
+    /// Look up a node's display name for reporting
+    async fn node_name(&self, node_id: &str) -> String {
+        self.project
+            .read()
+            .await
+            .find_node(node_id)
+            .map(|n| n.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Bring the search index up to date when prompts include related context
+    async fn refresh_related_context(&self) {
+        let project = self.project.read().await.clone();
+        let provider = EmbeddingConfig::for_manifest(&project.manifest).provider;
+        let api_key = self.api_keys.get_for_provider(&provider);
+        refresh_for_related_context(&project, api_key.as_deref()).await;
+    }
+
+    /// Persist the run report alongside the project
+    async fn write_report(&self, report: &RunReport) {
+        let project_path = self.project.read().await.project_path.clone();
+        if let Err(e) = save_run_report(&project_path, report) {
+            tracing::error!("Failed to write run report: {}", e);
+        }
+    }
+
+    /// Run the LLM consistency review over all generated code and apply its patches
+    pub async fn execute_consistency_pass(&self) -> Project {
+        let mut project = self.project.read().await.clone();
+        let api_key = self
+            .api_keys
+            .get_for_provider(&project.manifest.default_llm.provider);
+
+        match run_consistency_pass(&mut project, api_key).await {
+            Ok(report) => {
+                for node_id in &report.patched_node_ids {
+                    if let Some(node) = project.find_node(node_id) {
+                        self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                            node_id: node_id.clone(),
+                            status: node.status.clone(),
+                            message: Some("Patched by consistency pass".to_string()),
+                            generated_code: node.generated_code.clone(),
+                        }));
+                    }
+                }
+                for rejected in &report.rejected {
+                    tracing::warn!(
+                        "Consistency patch for {} rejected: {}",
+                        rejected.patch.file_path,
+                        rejected.error,
+                    );
+                }
+                self.emit(ExecutionEvent::ConsistencyPassCompleted {
+                    applied: report.applied.len(),
+                    rejected: report.rejected.len(),
+                });
+                *self.project.write().await = project.clone();
+                project
+            }
+            Err(e) => {
+                tracing::error!("Consistency pass failed: {}", e);
+                self.emit(ExecutionEvent::Error { message: format!("Consistency pass failed: {}", e) });
+                project
+            }
+        }
+    }
+
+    /// Generate a wave's nodes, starting each only while the budgets last. Returns
+    /// the number of nodes that succeeded, failed and weren't started.
+    async fn execute_wave(
+        &self,
+        wave: &ExecutionWave,
+        report: &mut RunReport,
+    ) -> (usize, usize, usize) {
+        // Let prompts in this wave see code generated by earlier ones
+        self.refresh_related_context().await;
+
+        self.emit(ExecutionEvent::WaveStarted {
+            wave_number: wave.wave_number,
+            node_ids: wave.node_ids.clone(),
+        });
+
+        let futures: Vec<_> = wave
+            .node_ids
+            .iter()
+            .map(|node_id| {
+                let node_id = node_id.clone();
+                let self_ref = self;
+                async move { self_ref.start_node(&node_id).await }
+            })
+            .collect();
+
+        let results = self.run_wave(futures).await;
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut not_started = 0;
+
+        for result in results {
+            let Some(result) = result else {
+                not_started += 1;
+                continue;
+            };
+            report.record(result.to_run_report(self.node_name(&result.node_id).await));
+
+            if result.success {
+                successful += 1;
+                self.update_node(
+                    &result.node_id,
+                    NodeStatus::Complete,
+                    result.generated_code.clone(),
+                    None,
+                )
+                .await;
+                let status = self.record_generation(&result).await;
+                self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                    node_id: result.node_id.clone(),
+                    status,
+                    message: Some("Generation complete".to_string()),
+                    generated_code: result.generated_code,
+                }));
+            } else {
+                failed += 1;
+                tracing::error!(
+                    "Generation failed for node {}: {}",
+                    result.node_id,
+                    result.error_message.as_deref().unwrap_or("unknown error"),
+                );
+                self.update_node(
+                    &result.node_id,
+                    NodeStatus::Error,
+                    None,
+                    result.error_message.clone(),
+                )
+                .await;
+                self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                    node_id: result.node_id.clone(),
+                    status: NodeStatus::Error,
+                    message: result.error_message,
+                    generated_code: None,
+                }));
+            }
+        }
+
+        self.emit(ExecutionEvent::WaveCompleted {
+            wave_number: wave.wave_number,
+            successful,
+            failed,
+        });
+        (successful, failed, not_started)
+    }
+
+    /// Mark a node as generating and generate it, unless a budget has run out or code
+    /// it depends on was rejected
+    async fn start_node(&self, node_id: &str) -> Option<NodeResult> {
+        if self.past_deadline().await {
+            self.mark_not_run(node_id).await;
+            return None;
+        }
+        if self.blocked.read().await.contains(node_id) {
+            tracing::info!("Not generating {}: code it depends on was rejected", node_id);
+            return None;
+        }
+        if self.budget_exceeded().await {
+            return None;
+        }
+
+        self.update_node(node_id, NodeStatus::Generating, None, None).await;
+        self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+            node_id: node_id.to_string(),
+            status: NodeStatus::Generating,
+            message: Some("Starting generation...".to_string()),
+            generated_code: None,
+        }));
+
+        let result = self.generate_node(node_id).await;
+        let mut spent = self.spent.write().await;
+        spent.add(result.tokens_used, result.cost_estimate);
+        *spent += result.fixup.unwrap_or_default();
+        drop(spent);
+        Some(result)
+    }
+
+    /// Execute generation for all nodes in the project
+    pub async fn execute_all(&self, options: ExecutionOptions) -> Project {
+        let project = self.project.read().await;
+        let mut plan = ExecutionPlan::from_project(&project);
+        if !options.tags.is_empty() || (options.skip_completed && !options.force) {
+            let before = plan.total_nodes;
+            plan.retain_nodes(&options.select_all(&project));
+            tracing::info!("Skipping {} nodes", before - plan.total_nodes);
+        }
+        drop(project);
+
+        // Emit start event
+        self.emit(ExecutionEvent::Started {
+            total_nodes: plan.total_nodes,
+            total_waves: plan.waves.len(),
+        });
+
+        let mut total_successful = 0;
+        let mut total_failed = 0;
+        let mut total_skipped = 0;
+        let mut report = RunReport::new();
+        self.start_budget().await;
+        self.start_deadline().await;
+        self.start_checkpoint(&plan.waves).await;
+
+        // Process each wave
+        for (index, wave) in plan.waves.iter().enumerate() {
+            if self.is_cancelled().await {
+                self.emit(ExecutionEvent::Cancelled);
+                break;
+            }
+            if self.budget_exceeded().await {
+                total_skipped += wave.node_ids.len();
+                continue;
+            }
+            if self.past_deadline().await {
+                for node_id in &wave.node_ids {
+                    self.mark_not_run(node_id).await;
+                }
+                total_skipped += wave.node_ids.len();
+                continue;
+            }
+
+            let (successful, failed, not_started) = self.execute_wave(wave, &mut report).await;
+            self.save_progress(wave).await;
+            total_successful += successful;
+            total_failed += failed;
+            total_skipped += not_started;
+
+            let remaining = &plan.waves[index + 1..];
+            if !self.pause_after_wave(wave, remaining).await {
+                total_skipped += remaining.iter().map(|w| w.node_ids.len()).sum::<usize>();
+                self.emit(ExecutionEvent::Cancelled);
+                break;
+            }
+        }
+
+        // Emit completed
+        total_skipped += plan.skipped_nodes.len();
+        self.emit(ExecutionEvent::Completed {
+            total_successful,
+            total_failed,
+            total_skipped,
+            timed_out: self.timed_out.load(Ordering::SeqCst),
+        });
+
+        report.finish(total_skipped);
+        self.write_report(&report).await;
+        self.clear_progress().await;
+
+        // Return updated project
+        self.project.read().await.clone()
+    }
+
+    /// Generate a single wave as a trial run, stopping at `time_limit` if given.
+    /// Generated code is kept, so a later full run can skip the piloted nodes.
+    pub async fn execute_pilot(
+        &self,
+        wave_number: usize,
+        time_limit: Option<std::time::Duration>,
+    ) -> Result<PilotReport, String> {
+        let project = self.project.read().await;
+        let plan = ExecutionPlan::from_project(&project);
+        drop(project);
+
+        let wave = plan
+            .waves
+            .iter()
+            .find(|w| w.wave_number == wave_number)
+            .ok_or_else(|| format!("Wave {} does not exist", wave_number))?;
+
+        self.emit(ExecutionEvent::Started {
+            total_nodes: wave.node_ids.len(),
+            total_waves: 1,
+        });
+        self.refresh_related_context().await;
+        self.emit(ExecutionEvent::WaveStarted {
+            wave_number,
+            node_ids: wave.node_ids.clone(),
+        });
+
+        self.start_budget().await;
+        let started = Instant::now();
+        let deadline = time_limit.map(|limit| tokio::time::Instant::now() + limit);
+
+        // Each node gets its own deadline so the ones that finish in time are kept.
+        // Nodes a budget keeps from starting are left out.
+        let futures: Vec<_> = wave
+            .node_ids
+            .iter()
+            .map(|node_id| {
+                let node_id = node_id.clone();
+                let self_ref = self;
+                async move {
+                    let generation = self_ref.start_node(&node_id);
+                    match deadline {
+                        Some(deadline) => match tokio::time::timeout_at(deadline, generation).await {
+                            Ok(result) => result.map(|result| (result, false)),
+                            Err(_) => {
+                                let message = "Pilot time limit reached".to_string();
+                                Some((NodeResult::failed(&node_id, String::new(), message), true))
+                            }
+                        },
+                        None => generation.await.map(|result| (result, false)),
+                    }
+                }
+            })
+            .collect();
+
+        let results = self.run_wave(futures).await;
+
+        let mut report = PilotReport::new(wave_number);
+        let mut timed_out = false;
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut not_started = 0;
+
+        for result in results {
+            let Some((result, hit_time_limit)) = result else {
+                not_started += 1;
+                continue;
+            };
+            timed_out |= hit_time_limit;
+            if result.success {
+                successful += 1;
+                self.update_node(
+                    &result.node_id,
+                    NodeStatus::Complete,
+                    result.generated_code.clone(),
+                    None,
+                )
+                .await;
+                let status = self.record_generation(&result).await;
+                self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                    node_id: result.node_id.clone(),
+                    status,
+                    message: Some("Pilot generation complete".to_string()),
+                    generated_code: result.generated_code.clone(),
+                }));
+            } else {
+                failed += 1;
+                self.update_node(
+                    &result.node_id,
+                    NodeStatus::Error,
+                    None,
+                    result.error_message.clone(),
+                )
+                .await;
+                self.emit(ExecutionEvent::NodeUpdate(NodeProgress {
+                    node_id: result.node_id.clone(),
+                    status: NodeStatus::Error,
+                    message: result.error_message.clone(),
+                    generated_code: None,
+                }));
+            }
+
+            let node_name = self.node_name(&result.node_id).await;
+            let project = self.project.read().await;
+            if let Some(node) = project.find_node(&result.node_id) {
+                let node = resolve_node(&project.manifest, node);
+                report.record(&node, result.to_run_report(node_name));
+            }
+        }
+
+        report.finish(started.elapsed().as_millis() as u64, timed_out, plan.total_nodes);
+
+        self.emit(ExecutionEvent::WaveCompleted {
+            wave_number,
+            successful,
+            failed,
+        });
+        self.emit(ExecutionEvent::Completed {
+            total_successful: successful,
+            total_failed: failed,
+            total_skipped: not_started,
+            timed_out,
+        });
+
+        Ok(report)
+    }
+
+    /// Execute generation for specific nodes only
+    /// With `force`, their transitive dependencies are regenerated as well
+    pub async fn execute_nodes(&self, node_ids: Vec<String>, options: ExecutionOptions) -> Project {
+        let project = self.project.read().await;
+        let mut plan = ExecutionPlan::from_project(&project);
+
+        // Filter waves to only include requested nodes
+        let node_set = options.select(&project, node_ids.into_iter().collect());
+        drop(project);
+        plan.retain_nodes(&node_set);
+        let filtered_waves = plan.waves;
+
+        let total_nodes: usize = filtered_waves.iter().map(|w| w.node_ids.len()).sum();
+
+        // Emit start event
+        self.emit(ExecutionEvent::Started {
+            total_nodes,
+            total_waves: filtered_waves.len(),
+        });
+
+        let mut total_successful = 0;
+        let mut total_failed = 0;
+        let mut total_skipped = 0;
+        let mut report = RunReport::new();
+        self.start_budget().await;
+        self.start_deadline().await;
+        self.start_checkpoint(&filtered_waves).await;
+
+        // Process each wave
+        for (index, wave) in filtered_waves.iter().enumerate() {
+            if self.is_cancelled().await {
+                self.emit(ExecutionEvent::Cancelled);
+                break;
+            }
+            if self.budget_exceeded().await {
+                total_skipped += wave.node_ids.len();
+                continue;
+            }
+            if self.past_deadline().await {
+                for node_id in &wave.node_ids {
+                    self.mark_not_run(node_id).await;
+                }
+                total_skipped += wave.node_ids.len();
+                continue;
+            }
+
+            let (successful, failed, not_started) = self.execute_wave(wave, &mut report).await;
+            self.save_progress(wave).await;
+            total_successful += successful;
+            total_failed += failed;
+            total_skipped += not_started;
+
+            let remaining = &filtered_waves[index + 1..];
+            if !self.pause_after_wave(wave, remaining).await {
+                total_skipped += remaining.iter().map(|w| w.node_ids.len()).sum::<usize>();
+                self.emit(ExecutionEvent::Cancelled);
+                break;
+            }
+        }
+
+        // Emit completed
+        self.emit(ExecutionEvent::Completed {
+            total_successful,
+            total_failed,
+            total_skipped,
+            timed_out: self.timed_out.load(Ordering::SeqCst),
+        });
+
+        report.finish(total_skipped);
+        self.write_report(&report).await;
+        self.clear_progress().await;
+
+        // Return updated project
+        self.project.read().await.clone()
+    }
+
+    /// Cancel the current execution
+    pub async fn cancel(&self) {
+        let mut cancelled = self.cancelled.write().await;
+        *cancelled = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, CodeNode, LLMProvider, Language};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_events_reach_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+
+        // Without an API key the node fails, but the run still reports each step
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default());
+        let project = executor.execute_all(ExecutionOptions::default()).await;
+        drop(executor);
+        assert_eq!(project.find_node(&node_id).unwrap().status, NodeStatus::Error);
+
+        let mut statuses = Vec::new();
+        let mut completed = None;
+        while let Some(event) = rx.recv().await {
+            match event {
+                ExecutionEvent::NodeUpdate(progress) => statuses.push(progress.status),
+                ExecutionEvent::Completed { total_failed, .. } => completed = Some(total_failed),
+                _ => {}
+            }
+        }
+        assert_eq!(statuses, vec![NodeStatus::Generating, NodeStatus::Error]);
+        assert_eq!(completed, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_mock_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        project.manifest.default_llm.provider = LLMProvider::Mock;
+        let mut a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let mut b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        a.llm_config.provider = LLMProvider::Mock;
+        b.llm_config.provider = LLMProvider::Mock;
+        let (a_id, b_id) = (a.id.clone(), b.id.clone());
+        project.nodes.extend([a, b]);
+        project.edges.push(CodeEdge::new(a_id.clone(), b_id.clone(), String::new()));
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default());
+        executor.execute_all(ExecutionOptions::default()).await;
+        let project = executor.execute_consistency_pass().await;
+
+        for id in [&a_id, &b_id] {
+            let node = project.find_node(id).unwrap();
+            assert_eq!(node.status, NodeStatus::Complete);
+            assert!(node.generated_code.as_ref().unwrap().starts_with("// Mock implementation"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_step_mode_pauses_after_each_wave() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.llm_config.provider = LLMProvider::Mock;
+            ids.push(node.id.clone());
+            project.nodes.push(node);
+        }
+        project.edges.push(CodeEdge::new(ids[0].clone(), ids[1].clone(), String::new()));
+        project.edges.push(CodeEdge::new(ids[1].clone(), ids[2].clone(), String::new()));
+
+        let step = StepControl::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default())
+            .with_step_mode(Some(step.clone()));
+
+        let drive = async {
+            let mut paused_waves = Vec::new();
+            while let Some(event) = rx.recv().await {
+                if let ExecutionEvent::WavePaused { wave_number, mut project, .. } = event {
+                    assert_eq!(step.paused().unwrap().wave_number, wave_number);
+                    paused_waves.push(wave_number);
+                    let action = if wave_number == 0 {
+                        // Specs edited while paused are used for the next wave
+                        project.nodes[1].description = "Edited while paused".to_string();
+                        project.nodes[1].tags = vec!["api".to_string()];
+                        project.nodes[1].variables.insert("PORT".to_string(), "8080".to_string());
+                        StepAction::Continue(Some(project))
+                    } else {
+                        StepAction::Stop
+                    };
+                    assert!(step.resume(action));
+                }
+                if step.paused().is_none() && paused_waves.len() == 2 {
+                    break;
+                }
+            }
+            paused_waves
+        };
+        let (project, paused_waves) =
+            tokio::join!(executor.execute_all(ExecutionOptions::default()), drive);
+
+        assert_eq!(paused_waves, vec![0, 1]);
+        assert_eq!(project.nodes[1].description, "Edited while paused");
+        assert_eq!(project.nodes[1].tags, vec!["api".to_string()]);
+        assert_eq!(project.nodes[1].variables["PORT"], "8080");
+        assert_eq!(project.nodes[1].status, NodeStatus::Complete);
+        assert_eq!(project.nodes[2].status, NodeStatus::Pending);
+        assert!(!step.resume(StepAction::Continue(None)));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_approval_blocks_dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c", "x", "y"] {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.llm_config.provider = LLMProvider::Mock;
+            node.requires_approval = name == "a";
+            ids.push(node.id.clone());
+            project.nodes.push(node);
+        }
+        for (source, target) in [(0, 1), (1, 2), (3, 4)] {
+            let edge = CodeEdge::new(ids[source].clone(), ids[target].clone(), String::new());
+            project.edges.push(edge);
+        }
+
+        let approvals = StepControl::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default())
+            .with_approval_gates(Some(approvals.clone()));
+
+        let drive = async {
+            let mut gates = Vec::new();
+            while let Some(event) = rx.recv().await {
+                match event {
+                    ExecutionEvent::ApprovalRequired { node_ids, .. } => {
+                        assert_eq!(approvals.paused().unwrap().awaiting_approval, node_ids);
+                        gates.push(node_ids);
+                        assert!(approvals.resume(StepAction::Reject));
+                    }
+                    ExecutionEvent::Completed { total_skipped, .. } => {
+                        assert_eq!(total_skipped, 2);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            gates
+        };
+        let (project, gates) =
+            tokio::join!(executor.execute_all(ExecutionOptions::default()), drive);
+
+        assert_eq!(gates, vec![vec![ids[0].clone()]]);
+        let statuses: Vec<_> = project.nodes.iter().map(|n| n.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                NodeStatus::Error,
+                NodeStatus::Pending,
+                NodeStatus::Pending,
+                NodeStatus::Complete,
+                NodeStatus::Complete,
+            ]
+        );
+        assert!(project.nodes[0].generated_code.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_max_duration_leaves_nodes_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        for name in ["a", "b"] {
+            let mut node =
+                CodeNode::new(name.to_string(), format!("{}.ts", name), Language::TypeScript);
+            node.llm_config.provider = LLMProvider::Mock;
+            node.status = NodeStatus::Error;
+            project.nodes.push(node);
+        }
+        let edge =
+            CodeEdge::new(project.nodes[0].id.clone(), project.nodes[1].id.clone(), String::new());
+        project.edges.push(edge);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default())
+            .with_max_duration(Some(Duration::ZERO));
+        let project = executor.execute_all(ExecutionOptions::default()).await;
+        drop(executor);
+
+        assert!(project.nodes.iter().all(|n| {
+            n.status == NodeStatus::Pending && n.error_message.as_deref() == Some(NOT_RUN_MESSAGE)
+        }));
+        let mut completed = None;
+        while let Some(event) = rx.recv().await {
+            if let ExecutionEvent::Completed { total_skipped, timed_out, .. } = event {
+                completed = Some((total_skipped, timed_out));
+            }
+        }
+        assert_eq!(completed, Some((2, true)));
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new(dir.path().to_string_lossy().to_string());
+        let mut a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let mut b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        a.llm_config.provider = LLMProvider::Mock;
+        b.llm_config.provider = LLMProvider::Mock;
+        let (a_id, b_id) = (a.id.clone(), b.id.clone());
+        project.nodes.extend([a, b]);
+        project.edges.push(CodeEdge::new(a_id.clone(), b_id.clone(), String::new()));
+
+        // The first wave uses up the budget, so the second is never started
+        let settings = ProjectSettings {
+            max_tokens: Some(1),
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(tx, project, ApiKeys::default()).with_settings(&settings);
+        let project = executor.execute_all(ExecutionOptions::default()).await;
+        drop(executor);
+
+        let generated: Vec<_> = [&a_id, &b_id]
+            .iter()
+            .map(|id| project.find_node(id).unwrap().generated_code.is_some())
+            .collect();
+        assert_eq!(generated.iter().filter(|g| **g).count(), 1);
+
+        let mut budget_messages = Vec::new();
+        let mut skipped = None;
+        while let Some(event) = rx.recv().await {
+            match event {
+                ExecutionEvent::BudgetExceeded { message, .. } => budget_messages.push(message),
+                ExecutionEvent::Completed { total_skipped, .. } => skipped = Some(total_skipped),
+                _ => {}
+            }
+        }
+        assert_eq!(budget_messages.len(), 1);
+        assert!(budget_messages[0].starts_with("Run budget exceeded"));
+        assert_eq!(skipped, Some(1));
+    }
+}
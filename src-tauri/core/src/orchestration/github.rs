@@ -0,0 +1,415 @@
+use std::fs;
+use std::path::Path;
+
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::graph::model::Project;
+use crate::graph::settings::ProjectSettings;
+use crate::graph::variables::resolve_node;
+use crate::llm::http;
+
+use super::report::RunReport;
+
+const API_URL: &str = "https://api.github.com";
+/// Branch names are this followed by the run ID, unless the settings set a prefix
+const DEFAULT_BRANCH_PREFIX: &str = "needlepoint/run-";
+/// Environment variable the token is read from when none was set
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+/// A file of a run, as it is on disk, to commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequestFile {
+    /// Path from the root of the repository
+    pub path: String,
+    pub contents: String,
+}
+
+/// A pull request opened for a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: String,
+    pub branch: String,
+    pub base_branch: String,
+    /// Committed files, from the root of the repository
+    pub files: Vec<String>,
+}
+
+/// Check that `repository` has the `owner/name` form
+pub fn validate_repository(repository: &str) -> Result<(), String> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repository.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(()),
+        _ => Err(format!(
+            "GitHub repository must be 'owner/name', not '{}'",
+            repository
+        )),
+    }
+}
+
+/// The project directory's path within its git repository, with a trailing slash,
+/// or an empty string when it is the root or not in a repository
+fn repository_prefix(project_dir: &Path) -> String {
+    let Ok(project_dir) = project_dir.canonicalize() else {
+        return String::new();
+    };
+    let Some(root) = project_dir
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+    else {
+        return String::new();
+    };
+    let relative = project_dir.strip_prefix(root).unwrap_or(Path::new(""));
+    let mut prefix: String = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    if !prefix.is_empty() {
+        prefix.push('/');
+    }
+    prefix
+}
+
+/// The files of the nodes the run generated, as they were written to disk. Nodes
+/// whose files haven't been written are left out.
+pub fn pull_request_files(project: &Project, report: &RunReport) -> Vec<PullRequestFile> {
+    let root = Path::new(&project.project_path);
+    let prefix = repository_prefix(root);
+    let mut files: Vec<PullRequestFile> = report
+        .nodes
+        .iter()
+        .filter(|n| n.success)
+        .filter_map(|n| project.find_node(&n.node_id))
+        .filter(|n| n.owns_file())
+        .filter_map(|node| {
+            let file_path = resolve_node(&project.manifest, node).file_path;
+            let contents = fs::read_to_string(root.join(&file_path)).ok()?;
+            Some(PullRequestFile {
+                path: format!("{}{}", prefix, file_path.replace('\\', "/")),
+                contents,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+    files
+}
+
+/// The pull request description: the regenerated nodes with their models and costs,
+/// and the run's totals
+pub fn pull_request_body(project: &Project, report: &RunReport) -> String {
+    let mut body = format!(
+        "Regenerated by Needlepoint run `{}` of {}.\n\n",
+        report.id, project.manifest.name
+    );
+    let generated: Vec<_> = report.nodes.iter().filter(|n| n.success).collect();
+    if !generated.is_empty() {
+        body.push_str("| Node | File | Model | Tokens | Cost |\n| --- | --- | --- | --- | --- |\n");
+        for node in &generated {
+            let file_path = project
+                .find_node(&node.node_id)
+                .map(|n| resolve_node(&project.manifest, n).file_path)
+                .unwrap_or_default();
+            let tokens = node.tokens_used.map_or("-".to_string(), |t| t.to_string());
+            let cost = node
+                .cost_estimate
+                .map_or("-".to_string(), |c| format!("${:.4}", c));
+            body.push_str(&format!(
+                "| {} | `{}` | {} | {} | {} |\n",
+                node.node_name.replace('|', "\\|"),
+                file_path,
+                node.model,
+                tokens,
+                cost
+            ));
+        }
+        body.push('\n');
+    }
+
+    let failed: Vec<_> = report.nodes.iter().filter(|n| !n.success).collect();
+    if !failed.is_empty() {
+        body.push_str("Failed, and left out of this pull request:\n\n");
+        for node in failed {
+            let error = node.error.as_deref().unwrap_or("Generation failed");
+            let error = error.lines().next().unwrap_or_default();
+            body.push_str(&format!("- {}: {}\n", node.node_name, error));
+        }
+        body.push('\n');
+    }
+
+    body.push_str(&format!(
+        "**Total:** {} generated, {} failed, {} skipped, {} tokens, ${:.4}\n",
+        report.total_successful,
+        report.total_failed,
+        report.total_skipped,
+        report.total_tokens,
+        report.total_cost_estimate
+    ));
+    body
+}
+
+/// Commit the run's files to a new branch of the repository in the settings and open
+/// a pull request for it, through the GitHub API. The local checkout is left alone.
+/// When the run already has a branch, the new one gets a numbered suffix.
+pub async fn open_pull_request(
+    project: &Project,
+    report: &RunReport,
+    settings: &ProjectSettings,
+    token: &str,
+) -> Result<PullRequest, String> {
+    let repository = settings
+        .github_repository
+        .as_deref()
+        .ok_or("No GitHub repository is configured (set githubRepository)")?;
+    validate_repository(repository)?;
+    let files = pull_request_files(project, report);
+    if files.is_empty() {
+        return Err(format!(
+            "Run '{}' has no written files to commit; write the files first",
+            report.id
+        ));
+    }
+
+    let github = GitHub {
+        client: http::client(),
+        repo_url: format!("{}/repos/{}", API_URL, repository),
+        token,
+    };
+    let base_branch = match &settings.github_base_branch {
+        Some(branch) => branch.clone(),
+        None => github
+            .send(github.client.get(&github.repo_url))
+            .await?
+            .get("default_branch")
+            .and_then(Value::as_str)
+            .ok_or("GitHub didn't return the repository's default branch")?
+            .to_string(),
+    };
+    let prefix = settings
+        .github_branch_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_BRANCH_PREFIX);
+    // Checked before anything is committed, so a run opened again gets a branch of
+    // its own rather than failing once its commit is made
+    let mut branch = format!("{}{}", prefix, report.id);
+    let mut attempt = 1;
+    while github.has_branch(&branch).await? {
+        attempt += 1;
+        branch = format!("{}{}-{}", prefix, report.id, attempt);
+    }
+
+    let base = github
+        .send(github.client.get(github.ref_url(&base_branch)?))
+        .await?;
+    let base_sha = sha(&base["object"])?;
+    let base_commit = github.get(&format!("git/commits/{}", base_sha)).await?;
+    let tree_entries: Vec<Value> = files
+        .iter()
+        .map(|file| {
+            json!({
+                "path": file.path,
+                "mode": "100644",
+                "type": "blob",
+                "content": file.contents,
+            })
+        })
+        .collect();
+    let tree = github
+        .post(
+            "git/trees",
+            json!({ "base_tree": sha(&base_commit["tree"])?, "tree": tree_entries }),
+        )
+        .await?;
+
+    let title = format!("Regenerate {} nodes (run {})", files.len(), report.id);
+    let commit = github
+        .post(
+            "git/commits",
+            json!({ "message": title, "tree": sha(&tree)?, "parents": [base_sha] }),
+        )
+        .await?;
+    github
+        .post(
+            "git/refs",
+            json!({ "ref": format!("refs/heads/{}", branch), "sha": sha(&commit)? }),
+        )
+        .await?;
+
+    let pull = github
+        .post(
+            "pulls",
+            json!({
+                "title": title,
+                "head": branch,
+                "base": base_branch,
+                "body": pull_request_body(project, report),
+            }),
+        )
+        .await?;
+    Ok(PullRequest {
+        number: pull["number"].as_u64().unwrap_or_default(),
+        url: pull["html_url"].as_str().unwrap_or_default().to_string(),
+        branch,
+        base_branch,
+        files: files.into_iter().map(|f| f.path).collect(),
+    })
+}
+
+/// Requests to one repository's endpoints
+struct GitHub<'a> {
+    client: Client,
+    repo_url: String,
+    token: &'a str,
+}
+
+impl GitHub<'_> {
+    async fn get(&self, path: &str) -> Result<Value, String> {
+        let url = format!("{}/{}", self.repo_url, path);
+        self.send(self.client.get(url)).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value, String> {
+        let url = format!("{}/{}", self.repo_url, path);
+        self.send(self.client.post(url).json(&body)).await
+    }
+
+    /// URL of a branch's ref, with each part of its name percent-encoded
+    fn ref_url(&self, branch: &str) -> Result<Url, String> {
+        let mut url = Url::parse(&self.repo_url).map_err(|e| e.to_string())?;
+        url.path_segments_mut()
+            .map_err(|_| format!("Invalid repository URL: {}", self.repo_url))?
+            .extend(["git", "ref", "heads"])
+            .extend(branch.split('/'));
+        Ok(url)
+    }
+
+    async fn has_branch(&self, branch: &str) -> Result<bool, String> {
+        let response = self.request(self.client.get(self.ref_url(branch)?)).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        Self::body(response).await.map(|_| true)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Value, String> {
+        Self::body(self.request(request).await?).await
+    }
+
+    async fn request(&self, request: RequestBuilder) -> Result<Response, String> {
+        request
+            .bearer_auth(self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .header("User-Agent", "needlepoint")
+            .send()
+            .await
+            .map_err(|e| format!("GitHub request failed: {}", e))
+    }
+
+    async fn body(response: Response) -> Result<Value, String> {
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            let message = body["message"].as_str().unwrap_or("no details");
+            return Err(format!("GitHub returned {}: {}", status, message));
+        }
+        Ok(body)
+    }
+}
+
+fn sha(object: &Value) -> Result<String, String> {
+    object["sha"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "GitHub returned an object without a SHA".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, Language};
+    use crate::orchestration::report::NodeRunReport;
+
+    fn node_report(node: &CodeNode, success: bool) -> NodeRunReport {
+        NodeRunReport {
+            node_id: node.id.clone(),
+            node_name: node.name.clone(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            success,
+            duration_ms: 10,
+            input_tokens: None,
+            output_tokens: None,
+            tokens_used: Some(120),
+            cost_estimate: Some(0.0123),
+            fixup: None,
+            retries: 0,
+            error: (!success).then(|| "Rate limited\nRetry later".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_pull_request_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let project_dir = dir.path().join("app");
+        fs::create_dir_all(project_dir.join("src")).unwrap();
+        fs::write(project_dir.join("src/a.ts"), "export const a = 1;\n").unwrap();
+
+        let mut project = Project::new(project_dir.to_string_lossy().to_string());
+        project.manifest.name = "Shop".to_string();
+        project.nodes = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                CodeNode::new(
+                    name.to_uppercase(),
+                    format!("src/{}.ts", name),
+                    Language::TypeScript,
+                )
+            })
+            .collect();
+        let mut report = RunReport::new();
+        // B succeeded but its file hasn't been written
+        report.record(node_report(&project.nodes[0], true));
+        report.record(node_report(&project.nodes[1], true));
+        report.record(node_report(&project.nodes[2], false));
+
+        let files = pull_request_files(&project, &report);
+        assert_eq!(
+            files,
+            vec![PullRequestFile {
+                path: "app/src/a.ts".to_string(),
+                contents: "export const a = 1;\n".to_string(),
+            }]
+        );
+
+        let body = pull_request_body(&project, &report);
+        assert!(body.contains("| A | `src/a.ts` | claude-sonnet-4-20250514 | 120 | $0.0123 |"));
+        assert!(body.contains("- C: Rate limited\n"));
+        assert!(body.contains("2 generated, 1 failed"));
+
+        assert!(validate_repository("acme/shop.web").is_ok());
+        assert!(validate_repository("acme").is_err());
+        assert!(validate_repository("acme/../x").is_err());
+
+        let github = GitHub {
+            client: Client::new(),
+            repo_url: format!("{}/repos/acme/shop", API_URL),
+            token: "",
+        };
+        assert_eq!(
+            github.ref_url("release/2.0 #1").unwrap().as_str(),
+            "https://api.github.com/repos/acme/shop/git/ref/heads/release/2.0%20%231"
+        );
+    }
+}
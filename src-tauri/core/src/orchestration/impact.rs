@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::graph::drift::is_manually_edited;
+use crate::graph::model::{CodeNode, NodeStatus, Project};
+use crate::graph::stats::estimate_node_tokens;
+use crate::graph::variables::resolve_node;
+use crate::llm::{estimate_cost, ContextBuilder};
+
+use super::planner::ExecutionPlan;
+
+/// Fields that change what is written to the node's file without a regeneration
+const FILE_FIELDS: &[&str] = &["filePath", "generatedCode", "fileHeader", "fileFooter"];
+
+/// A node that would have to be regenerated
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactedNode {
+    pub id: String,
+    pub name: String,
+    /// Why the node would be regenerated
+    pub reason: String,
+}
+
+/// A file that writing the project would change
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactedFile {
+    pub node_id: String,
+    pub file_path: String,
+    /// Whether the file is already on disk
+    pub exists: bool,
+    /// Edited by hand since it was last written, so the new version would be merged in
+    pub manually_edited: bool,
+}
+
+/// What a proposed change to a node would cost before it is made
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactReport {
+    pub node_id: String,
+    /// Fields of the patch whose value would actually change
+    pub changed_fields: Vec<String>,
+    /// The node itself, when its prompt, system prompt or model would change
+    pub regenerate: Option<ImpactedNode>,
+    /// Complete dependents that would become stale
+    pub stale: Vec<ImpactedNode>,
+    /// Prompt plus output tokens to regenerate the node and its stale dependents
+    pub estimated_tokens: u64,
+    /// Cost of those tokens (USD); models without known prices count as free
+    pub estimated_cost: f64,
+    /// Estimated wall-clock time to regenerate them
+    pub estimated_duration_ms: u64,
+    pub files: Vec<ImpactedFile>,
+}
+
+/// Work out what applying the JSON merge patch (see `CodeNode::apply_patch`) to the
+/// node would set off, without changing the project. A dependent becomes stale
+/// when the change alters its prompt, or when the node is regenerated and the
+/// dependent's prompt included its code, its stub or its exports.
+pub fn analyze_impact(
+    project: &Project,
+    node_id: &str,
+    patch: &serde_json::Value,
+    max_concurrency: Option<usize>,
+) -> Result<ImpactReport, String> {
+    let original = project
+        .find_node(node_id)
+        .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+    let mut node = original.clone();
+    node.apply_patch(patch)?;
+
+    let changed_fields = changed_fields(original, &node);
+    let mut patched = project.clone();
+    if let Some(slot) = patched.nodes.iter_mut().find(|n| n.id == node_id) {
+        *slot = node.clone();
+    }
+
+    let regenerate =
+        (generatable(&node) && generation_changed(project, &patched, node_id)).then(|| {
+            ImpactedNode {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                reason: "Its prompt or model changes".to_string(),
+            }
+        });
+
+    let dependents: HashSet<&str> = project
+        .get_dependents(node_id)
+        .into_iter()
+        .map(|e| e.target.as_str())
+        .collect();
+    let mut stale = Vec::new();
+    for dependent in project
+        .nodes
+        .iter()
+        .filter(|n| dependents.contains(n.id.as_str()))
+    {
+        if dependent.status != NodeStatus::Complete || !generatable(dependent) {
+            continue;
+        }
+        let reason = if prompt(project, &dependent.id) != prompt(&patched, &dependent.id) {
+            "Its prompt includes the changed spec"
+        } else if regenerate.is_some() {
+            "Its prompt includes the regenerated code"
+        } else {
+            continue;
+        };
+        stale.push(ImpactedNode {
+            id: dependent.id.clone(),
+            name: dependent.name.clone(),
+            reason: reason.to_string(),
+        });
+    }
+
+    let to_generate: HashSet<String> = regenerate
+        .iter()
+        .chain(&stale)
+        .map(|n| n.id.clone())
+        .collect();
+    let mut estimated_tokens = 0;
+    let mut estimated_cost = 0.0;
+    for id in &to_generate {
+        let Some(generated) = patched.find_node(id) else {
+            continue;
+        };
+        let (input, output) = estimate_node_tokens(&patched, id);
+        let config = generated.llm_config.resolve(&patched.manifest.default_llm);
+        estimated_tokens += input + output;
+        estimated_cost +=
+            estimate_cost(&config.provider, &config.model, input as u32, output as u32)
+                .unwrap_or(0.0);
+    }
+    let estimated_duration_ms = if to_generate.is_empty() {
+        0
+    } else {
+        ExecutionPlan::for_nodes(&patched, to_generate.clone(), max_concurrency)
+            .estimated_duration_ms
+    };
+
+    let rewrites_file = changed_fields
+        .iter()
+        .any(|field| FILE_FIELDS.contains(&field.as_str()))
+        && node.generated_code.is_some();
+    let root = Path::new(&project.project_path);
+    let files = patched
+        .nodes
+        .iter()
+        .filter(|n| n.owns_file())
+        .filter(|n| to_generate.contains(&n.id) || (n.id == node_id && rewrites_file))
+        .map(|n| {
+            let file_path = resolve_node(&patched.manifest, n).file_path;
+            let on_disk = fs::read_to_string(root.join(&file_path)).ok();
+            ImpactedFile {
+                node_id: n.id.clone(),
+                exists: on_disk.is_some(),
+                manually_edited: is_manually_edited(n, on_disk.as_deref()),
+                file_path,
+            }
+        })
+        .collect();
+
+    Ok(ImpactReport {
+        node_id: node_id.to_string(),
+        changed_fields,
+        regenerate,
+        stale,
+        estimated_tokens,
+        estimated_cost,
+        estimated_duration_ms,
+        files,
+    })
+}
+
+/// Whether generation runs produce the node
+fn generatable(node: &CodeNode) -> bool {
+    !node.disabled && !node.pinned && node.kind.has_file()
+}
+
+fn prompt(project: &Project, node_id: &str) -> Option<String> {
+    ContextBuilder::build_prompt(project, node_id)
+}
+
+/// Whether the node's prompt, system prompt or resolved model differ between the two
+/// versions of the project
+fn generation_changed(before: &Project, after: &Project, node_id: &str) -> bool {
+    let (Some(old), Some(new)) = (before.find_node(node_id), after.find_node(node_id)) else {
+        return true;
+    };
+    let config = |project: &Project, node: &CodeNode| {
+        serde_json::to_value(node.llm_config.resolve(&project.manifest.default_llm)).ok()
+    };
+    prompt(before, node_id) != prompt(after, node_id)
+        || ContextBuilder::build_system_prompt(&before.manifest, old)
+            != ContextBuilder::build_system_prompt(&after.manifest, new)
+        || config(before, old) != config(after, new)
+}
+
+/// Serialized fields that differ between the two versions of the node, sorted
+fn changed_fields(before: &CodeNode, after: &CodeNode) -> Vec<String> {
+    let before = serde_json::to_value(before).unwrap_or_default();
+    let after = serde_json::to_value(after).unwrap_or_default();
+    let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+        return Vec::new();
+    };
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeEdge, Language};
+    use serde_json::json;
+
+    #[test]
+    fn test_analyze_impact() {
+        let mut project = Project::new(std::env::temp_dir().to_string_lossy().to_string());
+        let mut nodes: Vec<CodeNode> = ["A", "B", "C"]
+            .iter()
+            .map(|name| {
+                let mut node = CodeNode::new(
+                    name.to_string(),
+                    format!("needlepoint-impact-{}.ts", name),
+                    Language::TypeScript,
+                );
+                node.status = NodeStatus::Complete;
+                node.generated_code = Some(format!("export const {} = 1;\n", name));
+                node
+            })
+            .collect();
+        nodes[2].pinned = true;
+        let ids: Vec<String> = nodes.iter().map(|n| n.id.clone()).collect();
+        project.nodes = nodes;
+        // B and C depend on A
+        project
+            .edges
+            .push(CodeEdge::new(ids[0].clone(), ids[1].clone(), String::new()));
+        project
+            .edges
+            .push(CodeEdge::new(ids[0].clone(), ids[2].clone(), String::new()));
+
+        let patch = json!({ "description": "Now with caching" });
+        let report = analyze_impact(&project, &ids[0], &patch, None).unwrap();
+        assert_eq!(report.changed_fields, vec!["description"]);
+        assert!(report.regenerate.is_some());
+        // The pinned dependent is never regenerated
+        let stale: Vec<&str> = report.stale.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(stale, vec!["B"]);
+        assert!(report.estimated_tokens > 0);
+        assert!(report.estimated_duration_ms > 0);
+        assert_eq!(report.files.len(), 2);
+        assert!(!report.files[0].exists);
+
+        // Layout and tags change nothing that is generated or written
+        let patch = json!({ "position": { "x": 10.0, "y": 20.0 }, "tags": ["ui"] });
+        let report = analyze_impact(&project, &ids[0], &patch, None).unwrap();
+        assert_eq!(report.changed_fields, vec!["position", "tags"]);
+        assert!(report.regenerate.is_none());
+        assert!(report.stale.is_empty());
+        assert!(report.files.is_empty());
+        assert_eq!(report.estimated_tokens, 0);
+
+        // A new header rewrites the file without a regeneration
+        let patch = json!({ "fileHeader": "// Generated" });
+        let report = analyze_impact(&project, &ids[0], &patch, None).unwrap();
+        assert!(report.regenerate.is_none());
+        assert_eq!(report.files.len(), 1);
+
+        assert!(analyze_impact(&project, &ids[0], &json!({ "id": "x" }), None).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+pub mod planner;
+pub mod executor;
+pub mod events;
+pub mod report;
+pub mod consistency;
+pub mod pilot;
+pub mod scaffold;
+pub mod compare;
+pub mod step;
+pub mod checkpoint;
+pub mod split;
+pub mod postprocess;
+pub mod impact;
+pub mod checks;
+pub mod github;
+
+pub use planner::{ExecutionOptions, ExecutionPlan, ExecutionWave};
+pub use executor::Executor;
+pub use events::{EventSink, ExecutionEvent, NodeProgress};
+pub use report::{NodeRunReport, RunReport, RunSummary};
+pub use consistency::ConsistencyReport;
+pub use pilot::PilotReport;
+pub use step::{PausedStep, StepAction, StepControl};
+pub use checkpoint::{InterruptedRun, RunCheckpoint};
+pub use split::SplitSuggestion;
+pub use postprocess::PostProcessor;
+pub use impact::ImpactReport;
+pub use checks::CheckResult;
+pub use github::PullRequest;
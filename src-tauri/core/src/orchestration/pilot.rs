@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::graph::constraints::missing_exports;
+use crate::graph::model::CodeNode;
+
+use super::report::NodeRunReport;
+
+/// Quality and cost metrics for one node of a pilot run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PilotNodeResult {
+    #[serde(flatten)]
+    pub run: NodeRunReport,
+    pub declared_exports: usize,
+    /// Declared exports that don't appear in the generated code
+    #[serde(default)]
+    pub missing_exports: Vec<String>,
+    /// Dependencies that were only available as stub interfaces
+    #[serde(default)]
+    pub stubbed_dependencies: Vec<String>,
+    pub lines_of_code: usize,
+}
+
+/// Outcome of generating a single wave as a trial before the full graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PilotReport {
+    pub wave_number: usize,
+    pub nodes: Vec<PilotNodeResult>,
+    pub duration_ms: u64,
+    /// Whether the time limit cut the pilot short
+    pub timed_out: bool,
+    pub total_tokens: u64,
+    pub total_cost_estimate: f64,
+    /// Fraction of declared exports found in the generated code (1.0 if none declared)
+    pub export_coverage: f64,
+    /// Pilot cost per node extrapolated to every node in the plan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_total_cost: Option<f64>,
+}
+
+impl PilotReport {
+    pub fn new(wave_number: usize) -> Self {
+        Self {
+            wave_number,
+            nodes: Vec::new(),
+            duration_ms: 0,
+            timed_out: false,
+            total_tokens: 0,
+            total_cost_estimate: 0.0,
+            export_coverage: 1.0,
+            projected_total_cost: None,
+        }
+    }
+
+    /// Verify a node after its pilot generation and record the result
+    pub fn record(&mut self, node: &CodeNode, run: NodeRunReport) {
+        let (missing_exports, lines_of_code) = match (&node.generated_code, run.success) {
+            (Some(code), true) => (missing_exports(node, code), code.lines().count()),
+            _ => (node.exports.iter().map(|e| e.name.clone()).collect(), 0),
+        };
+
+        let spent = run.spend();
+        self.total_tokens += spent.tokens;
+        self.total_cost_estimate += spent.cost;
+        self.nodes.push(PilotNodeResult {
+            run,
+            declared_exports: node.exports.len(),
+            missing_exports,
+            stubbed_dependencies: node.generated_against_stubs.clone(),
+            lines_of_code,
+        });
+    }
+
+    /// Compute the aggregate metrics once all nodes are recorded
+    pub fn finish(&mut self, duration_ms: u64, timed_out: bool, total_plan_nodes: usize) {
+        self.duration_ms = duration_ms;
+        self.timed_out = timed_out;
+
+        let declared: usize = self.nodes.iter().map(|n| n.declared_exports).sum();
+        let missing: usize = self.nodes.iter().map(|n| n.missing_exports.len()).sum();
+        if declared > 0 {
+            self.export_coverage = (declared - missing) as f64 / declared as f64;
+        }
+
+        let successful = self.nodes.iter().filter(|n| n.run.success).count();
+        if successful > 0 {
+            let per_node = self.total_cost_estimate / successful as f64;
+            self.projected_total_cost = Some(per_node * total_plan_nodes as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{ExportSignature, Language};
+
+    #[test]
+    fn test_missing_exports() {
+        let mut node = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        node.exports = vec![
+            ExportSignature { name: "parse".to_string(), ..Default::default() },
+            ExportSignature { name: "format".to_string(), ..Default::default() },
+        ];
+
+        let code = "export function parse(input: string) {}\nconst formatter = 1;\n";
+        assert_eq!(missing_exports(&node, code), vec!["format".to_string()]);
+    }
+}
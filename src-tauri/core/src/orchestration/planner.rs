@@ -0,0 +1,584 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::{NodeStatus, Project};
+
+/// Assumed generation time for nodes that have never been generated
+const DEFAULT_NODE_ESTIMATE_MS: u64 = 30_000;
+
+/// Options controlling which nodes a run (re)generates
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionOptions {
+    /// Leave nodes that are already complete untouched
+    pub skip_completed: bool,
+    /// Regenerate complete nodes too, along with all transitive dependencies of the
+    /// selected nodes
+    pub force: bool,
+    /// Only generate nodes carrying one of these tags; all nodes when empty
+    pub tags: Vec<String>,
+}
+
+impl ExecutionOptions {
+    /// Resolve the set of nodes to generate from an initial selection
+    pub fn select(&self, project: &Project, node_ids: HashSet<String>) -> HashSet<String> {
+        if self.force {
+            return ExecutionPlan::dependency_closure(project, &node_ids);
+        }
+
+        let mut selected = node_ids;
+        if self.skip_completed {
+            selected.retain(|id| {
+                project
+                    .find_node(id)
+                    .map(|n| n.status != NodeStatus::Complete)
+                    .unwrap_or(true)
+            });
+        }
+        selected
+    }
+
+    /// Resolve the set of nodes a whole-project run generates: the tagged nodes when
+    /// tags are set, otherwise every node
+    pub fn select_all(&self, project: &Project) -> HashSet<String> {
+        let nodes = project
+            .nodes
+            .iter()
+            .filter(|n| self.tags.is_empty() || self.tags.iter().any(|t| n.has_tag(t)))
+            .map(|n| n.id.clone())
+            .collect();
+        self.select(project, nodes)
+    }
+}
+
+/// A wave of nodes that can be generated in parallel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWave {
+    /// Wave number (0-indexed)
+    pub wave_number: usize,
+    /// Node IDs in this wave
+    pub node_ids: Vec<String>,
+    /// Estimated wall-clock time for this wave given the concurrency limit
+    #[serde(default)]
+    pub estimated_duration_ms: u64,
+}
+
+/// The complete execution plan showing how nodes will be generated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPlan {
+    /// Ordered waves of execution
+    pub waves: Vec<ExecutionWave>,
+    /// Total number of nodes to generate
+    pub total_nodes: usize,
+    /// Nodes that won't be generated (disabled, pinned, external, or caught in a cycle)
+    pub skipped_nodes: Vec<String>,
+    /// Depth of each node: the length of its longest dependency chain (equals its wave number)
+    #[serde(default)]
+    pub node_depths: HashMap<String, usize>,
+    /// The dependency chain with the largest estimated generation time, in execution order
+    #[serde(default)]
+    pub critical_path: Vec<String>,
+    /// Estimated generation time along the critical path
+    #[serde(default)]
+    pub critical_path_ms: u64,
+    /// Maximum number of nodes generated at once (None = a whole wave at once)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Estimated wall-clock time for the whole plan
+    #[serde(default)]
+    pub estimated_duration_ms: u64,
+}
+
+impl ExecutionPlan {
+    /// Create an execution plan from a project using topological sort
+    pub fn from_project(project: &Project) -> Self {
+        Self::with_concurrency(project, None)
+    }
+
+    /// Create an execution plan, estimating wave times for at most `max_concurrency`
+    /// parallel generations
+    pub fn with_concurrency(project: &Project, max_concurrency: Option<usize>) -> Self {
+        let node_ids: HashSet<String> = project.nodes.iter().map(|n| n.id.clone()).collect();
+        Self::for_nodes(project, node_ids, max_concurrency)
+    }
+
+    /// Create the minimal plan that generates a single node along with all of its
+    /// not-yet-complete transitive dependencies. Returns None if the node doesn't exist.
+    pub fn for_node(project: &Project, node_id: &str) -> Option<Self> {
+        project.find_node(node_id)?;
+
+        let mut included: HashSet<String> = HashSet::new();
+        included.insert(node_id.to_string());
+        let mut stack = vec![node_id.to_string()];
+
+        // Walk up the dependency graph, stopping at nodes that are already complete
+        // or pinned
+        while let Some(current) = stack.pop() {
+            for edge in project.edges.iter().filter(|e| e.target == current) {
+                let complete = project
+                    .find_node(&edge.source)
+                    .map(|n| n.status == NodeStatus::Complete || n.pinned || !n.kind.has_file())
+                    .unwrap_or(true);
+                if !complete && included.insert(edge.source.clone()) {
+                    stack.push(edge.source.clone());
+                }
+            }
+        }
+
+        Some(Self::for_nodes(project, included, None))
+    }
+
+    /// The given nodes plus all of their transitive dependencies
+    pub fn dependency_closure(project: &Project, node_ids: &HashSet<String>) -> HashSet<String> {
+        let mut closure = node_ids.clone();
+        let mut stack: Vec<String> = node_ids.iter().cloned().collect();
+
+        while let Some(current) = stack.pop() {
+            for edge in project.edges.iter().filter(|e| e.target == current) {
+                if closure.insert(edge.source.clone()) {
+                    stack.push(edge.source.clone());
+                }
+            }
+        }
+
+        closure
+    }
+
+    /// The given nodes plus all of their transitive dependents
+    pub fn dependent_closure(project: &Project, node_ids: &HashSet<String>) -> HashSet<String> {
+        let mut closure = node_ids.clone();
+        let mut stack: Vec<String> = node_ids.iter().cloned().collect();
+
+        while let Some(current) = stack.pop() {
+            for edge in project.edges.iter().filter(|e| e.source == current) {
+                if closure.insert(edge.target.clone()) {
+                    stack.push(edge.target.clone());
+                }
+            }
+        }
+
+        closure
+    }
+
+    /// Plan a subset of the project's nodes; edges to nodes outside the subset are ignored.
+    /// Disabled, pinned and external nodes are always skipped, whatever the options.
+    pub(crate) fn for_nodes(
+        project: &Project,
+        mut node_ids: HashSet<String>,
+        max_concurrency: Option<usize>,
+    ) -> Self {
+        let mut excluded: Vec<String> = Vec::new();
+        node_ids.retain(|id| {
+            let exclude = project
+                .find_node(id)
+                .is_some_and(|n| n.disabled || n.pinned || !n.kind.has_file());
+            if exclude {
+                excluded.push(id.clone());
+            }
+            !exclude
+        });
+
+        // Build adjacency list: target -> sources (dependencies)
+        // An edge from A -> B means B depends on A (B is target, A is source)
+        let mut dependencies: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut dependents: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for node_id in &node_ids {
+            dependencies.insert(node_id.clone(), HashSet::new());
+            dependents.insert(node_id.clone(), HashSet::new());
+        }
+
+        for edge in &project.edges {
+            if !node_ids.contains(&edge.source) || !node_ids.contains(&edge.target) {
+                continue;
+            }
+            // target depends on source
+            if let Some(deps) = dependencies.get_mut(&edge.target) {
+                deps.insert(edge.source.clone());
+            }
+            // source has dependent target
+            if let Some(deps) = dependents.get_mut(&edge.source) {
+                deps.insert(edge.target.clone());
+            }
+        }
+
+        // Kahn's algorithm for topological sort with wave detection
+        let mut waves: Vec<ExecutionWave> = Vec::new();
+        let mut in_degree: HashMap<String, usize> = HashMap::new();
+        let mut remaining: HashSet<String> = node_ids.clone();
+
+        // Calculate initial in-degrees
+        for node_id in &node_ids {
+            let degree = dependencies.get(node_id).map(|d| d.len()).unwrap_or(0);
+            in_degree.insert(node_id.clone(), degree);
+        }
+
+        let mut wave_number = 0;
+
+        while !remaining.is_empty() {
+            // Find all nodes with in-degree 0 (no remaining dependencies)
+            let ready: Vec<String> = remaining
+                .iter()
+                .filter(|id| *in_degree.get(*id).unwrap_or(&0) == 0)
+                .cloned()
+                .collect();
+
+            if ready.is_empty() {
+                // No nodes with in-degree 0 means we have a cycle
+                // This shouldn't happen if cycle detection is working, but handle gracefully
+                break;
+            }
+
+            // Add this wave
+            waves.push(ExecutionWave {
+                wave_number,
+                node_ids: ready.clone(),
+                estimated_duration_ms: 0,
+            });
+
+            // Remove processed nodes and update in-degrees
+            for node_id in &ready {
+                remaining.remove(node_id);
+
+                // Decrease in-degree of dependents
+                if let Some(node_dependents) = dependents.get(node_id) {
+                    for dependent in node_dependents {
+                        if let Some(degree) = in_degree.get_mut(dependent) {
+                            *degree = degree.saturating_sub(1);
+                        }
+                    }
+                }
+            }
+
+            wave_number += 1;
+        }
+
+        let total_nodes: usize = waves.iter().map(|w| w.node_ids.len()).sum();
+        let mut skipped_nodes: Vec<String> = remaining.into_iter().collect();
+        skipped_nodes.extend(excluded);
+
+        let mut plan = ExecutionPlan {
+            waves,
+            total_nodes,
+            skipped_nodes,
+            node_depths: HashMap::new(),
+            critical_path: Vec::new(),
+            critical_path_ms: 0,
+            max_concurrency: max_concurrency.filter(|c| *c > 0),
+            estimated_duration_ms: 0,
+        };
+        plan.analyze(project, &dependencies);
+        plan
+    }
+
+    /// Fill in node depths, the critical path and per-wave time estimates
+    fn analyze(&mut self, project: &Project, dependencies: &HashMap<String, HashSet<String>>) {
+        // Past generation time is the best estimate we have for a node
+        let estimate = |node_id: &str| -> u64 {
+            project
+                .find_node(node_id)
+                .and_then(|n| n.last_generation.as_ref())
+                .map(|g| g.duration_ms)
+                .unwrap_or(DEFAULT_NODE_ESTIMATE_MS)
+        };
+
+        // Earliest finish time along any chain ending at each node, with the
+        // predecessor on that chain
+        let mut finish: HashMap<String, u64> = HashMap::new();
+        let mut predecessor: HashMap<String, String> = HashMap::new();
+
+        for wave in &mut self.waves {
+            for node_id in &wave.node_ids {
+                self.node_depths.insert(node_id.clone(), wave.wave_number);
+
+                let slowest_dep = dependencies
+                    .get(node_id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|dep| finish.get(dep).map(|f| (dep, *f)))
+                    .max_by_key(|(_, f)| *f);
+
+                let start = match slowest_dep {
+                    Some((dep, f)) => {
+                        predecessor.insert(node_id.clone(), dep.clone());
+                        f
+                    }
+                    None => 0,
+                };
+                finish.insert(node_id.clone(), start + estimate(node_id));
+            }
+
+            let durations: Vec<u64> = wave.node_ids.iter().map(|id| estimate(id)).collect();
+            wave.estimated_duration_ms = estimate_wave_duration(durations, self.max_concurrency);
+        }
+
+        if let Some((last, total)) = finish.iter().max_by_key(|(_, f)| **f) {
+            self.critical_path_ms = *total;
+
+            let mut path = vec![last.clone()];
+            while let Some(prev) = predecessor.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            self.critical_path = path;
+        }
+
+        self.estimated_duration_ms = self.waves.iter().map(|w| w.estimated_duration_ms).sum();
+    }
+
+    /// Drop all nodes not in `keep` from the plan, removing waves that become empty
+    pub fn retain_nodes(&mut self, keep: &HashSet<String>) {
+        for wave in &mut self.waves {
+            wave.node_ids.retain(|id| keep.contains(id));
+        }
+        self.waves.retain(|w| !w.node_ids.is_empty());
+        self.total_nodes = self.waves.iter().map(|w| w.node_ids.len()).sum();
+    }
+
+    /// Get a flattened list of node IDs in execution order
+    pub fn ordered_node_ids(&self) -> Vec<String> {
+        self.waves
+            .iter()
+            .flat_map(|w| w.node_ids.clone())
+            .collect()
+    }
+
+    /// Check if a specific node ID is in the plan
+    pub fn contains_node(&self, node_id: &str) -> bool {
+        self.waves.iter().any(|w| w.node_ids.contains(&node_id.to_string()))
+    }
+}
+
+/// Estimate a wave's wall-clock time by greedily assigning the longest jobs first
+/// to the least loaded of `max_concurrency` slots
+fn estimate_wave_duration(mut durations: Vec<u64>, max_concurrency: Option<usize>) -> u64 {
+    let slots = match max_concurrency {
+        Some(c) if c < durations.len() => c,
+        _ => return durations.into_iter().max().unwrap_or(0),
+    };
+
+    durations.sort_unstable_by(|a, b| b.cmp(a));
+    let mut loads = vec![0u64; slots];
+    for duration in durations {
+        if let Some(least) = loads.iter_mut().min() {
+            *least += duration;
+        }
+    }
+    loads.into_iter().max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, CodeEdge, Language, ProjectManifest};
+
+    fn create_test_project() -> Project {
+        let mut project = Project {
+            manifest: ProjectManifest::default(),
+            nodes: vec![],
+            edges: vec![],
+            project_path: String::new(),
+        };
+
+        // Create nodes: A, B, C where B depends on A, C depends on B
+        let node_a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let node_b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let node_c = CodeNode::new("C".to_string(), "c.ts".to_string(), Language::TypeScript);
+
+        let id_a = node_a.id.clone();
+        let id_b = node_b.id.clone();
+        let id_c = node_c.id.clone();
+
+        project.nodes = vec![node_a, node_b, node_c];
+
+        // B depends on A (edge from A to B means B imports from A)
+        project.edges = vec![
+            CodeEdge::new(id_a.clone(), id_b.clone(), "imports".to_string()),
+            CodeEdge::new(id_b.clone(), id_c.clone(), "imports".to_string()),
+        ];
+
+        project
+    }
+
+    #[test]
+    fn test_execution_plan_linear() {
+        let project = create_test_project();
+        let plan = ExecutionPlan::from_project(&project);
+
+        assert_eq!(plan.waves.len(), 3);
+        assert_eq!(plan.total_nodes, 3);
+        assert!(plan.skipped_nodes.is_empty());
+
+        // Wave 0: A (no dependencies)
+        // Wave 1: B (depends on A)
+        // Wave 2: C (depends on B)
+        assert_eq!(plan.waves[0].node_ids.len(), 1);
+        assert_eq!(plan.waves[1].node_ids.len(), 1);
+        assert_eq!(plan.waves[2].node_ids.len(), 1);
+    }
+
+    #[test]
+    fn test_execution_plan_skips_disabled() {
+        let mut project = create_test_project();
+        let id_b = project.nodes[1].id.clone();
+        project.nodes[1].disabled = true;
+
+        let plan = ExecutionPlan::from_project(&project);
+        assert_eq!(plan.total_nodes, 2);
+        assert_eq!(plan.skipped_nodes, vec![id_b]);
+        // With B parked, C no longer waits on it
+        assert_eq!(plan.waves.len(), 1);
+    }
+
+    #[test]
+    fn test_forced_plan_skips_pinned() {
+        let mut project = create_test_project();
+        let id_a = project.nodes[0].id.clone();
+        let id_c = project.nodes[2].id.clone();
+        project.nodes[0].pinned = true;
+
+        let options = ExecutionOptions {
+            skip_completed: false,
+            force: true,
+            ..Default::default()
+        };
+        let selected = options.select(&project, HashSet::from([id_c]));
+        let plan = ExecutionPlan::for_nodes(&project, selected, None);
+        assert_eq!(plan.total_nodes, 2);
+        assert_eq!(plan.skipped_nodes, vec![id_a]);
+    }
+
+    #[test]
+    fn test_execution_plan_parallel() {
+        let mut project = Project {
+            manifest: ProjectManifest::default(),
+            nodes: vec![],
+            edges: vec![],
+            project_path: String::new(),
+        };
+
+        // Create nodes: A, B, C, D where C depends on A and B, D depends on C
+        let node_a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let node_b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let node_c = CodeNode::new("C".to_string(), "c.ts".to_string(), Language::TypeScript);
+        let node_d = CodeNode::new("D".to_string(), "d.ts".to_string(), Language::TypeScript);
+
+        let id_a = node_a.id.clone();
+        let id_b = node_b.id.clone();
+        let id_c = node_c.id.clone();
+        let id_d = node_d.id.clone();
+
+        project.nodes = vec![node_a, node_b, node_c, node_d];
+        project.edges = vec![
+            CodeEdge::new(id_a.clone(), id_c.clone(), "imports".to_string()),
+            CodeEdge::new(id_b.clone(), id_c.clone(), "imports".to_string()),
+            CodeEdge::new(id_c.clone(), id_d.clone(), "imports".to_string()),
+        ];
+
+        let plan = ExecutionPlan::from_project(&project);
+
+        assert_eq!(plan.waves.len(), 3);
+        assert_eq!(plan.total_nodes, 4);
+
+        // Wave 0: A and B (no dependencies) - can run in parallel
+        assert_eq!(plan.waves[0].node_ids.len(), 2);
+        // Wave 1: C (depends on A and B)
+        assert_eq!(plan.waves[1].node_ids.len(), 1);
+        // Wave 2: D (depends on C)
+        assert_eq!(plan.waves[2].node_ids.len(), 1);
+    }
+
+    #[test]
+    fn test_critical_path_and_estimates() {
+        let mut project = Project {
+            manifest: ProjectManifest::default(),
+            nodes: vec![],
+            edges: vec![],
+            project_path: String::new(),
+        };
+
+        // A -> C, B -> C, where B is slower than A
+        let node_a = CodeNode::new("A".to_string(), "a.ts".to_string(), Language::TypeScript);
+        let mut node_b = CodeNode::new("B".to_string(), "b.ts".to_string(), Language::TypeScript);
+        let node_c = CodeNode::new("C".to_string(), "c.ts".to_string(), Language::TypeScript);
+        node_b.last_generation = Some(crate::graph::model::GenerationRecord {
+            model: "test".to_string(),
+            tokens_in: None,
+            tokens_out: None,
+            duration_ms: 50_000,
+            timestamp: chrono::Utc::now(),
+            seed: None,
+            note: None,
+            post_processing: Vec::new(),
+        });
+
+        let id_a = node_a.id.clone();
+        let id_b = node_b.id.clone();
+        let id_c = node_c.id.clone();
+
+        project.nodes = vec![node_a, node_b, node_c];
+        project.edges = vec![
+            CodeEdge::new(id_a.clone(), id_c.clone(), "imports".to_string()),
+            CodeEdge::new(id_b.clone(), id_c.clone(), "imports".to_string()),
+        ];
+
+        let plan = ExecutionPlan::with_concurrency(&project, Some(1));
+
+        assert_eq!(plan.node_depths[&id_a], 0);
+        assert_eq!(plan.node_depths[&id_c], 1);
+        assert_eq!(plan.critical_path, vec![id_b, id_c]);
+        assert_eq!(plan.critical_path_ms, 50_000 + DEFAULT_NODE_ESTIMATE_MS);
+
+        // With one slot, A and B run back to back
+        assert_eq!(plan.waves[0].estimated_duration_ms, 50_000 + DEFAULT_NODE_ESTIMATE_MS);
+        assert_eq!(
+            ExecutionPlan::from_project(&project).waves[0].estimated_duration_ms,
+            50_000
+        );
+    }
+
+    #[test]
+    fn test_plan_for_node_skips_complete_dependencies() {
+        let mut project = create_test_project();
+        let id_a = project.nodes[0].id.clone();
+        let id_b = project.nodes[1].id.clone();
+        let id_c = project.nodes[2].id.clone();
+
+        // Everything is pending: C needs A and B first
+        let plan = ExecutionPlan::for_node(&project, &id_c).unwrap();
+        assert_eq!(plan.ordered_node_ids(), vec![id_a.clone(), id_b.clone(), id_c.clone()]);
+
+        // Once B is complete, A is no longer needed for C
+        project.nodes[1].status = NodeStatus::Complete;
+        let plan = ExecutionPlan::for_node(&project, &id_c).unwrap();
+        assert_eq!(plan.ordered_node_ids(), vec![id_c]);
+
+        assert!(ExecutionPlan::for_node(&project, "missing").is_none());
+    }
+
+    #[test]
+    fn test_execution_options_select() {
+        let mut project = create_test_project();
+        let id_a = project.nodes[0].id.clone();
+        let id_c = project.nodes[2].id.clone();
+        project.nodes[0].status = NodeStatus::Complete;
+
+        let all: HashSet<String> = project.nodes.iter().map(|n| n.id.clone()).collect();
+
+        let skip = ExecutionOptions { skip_completed: true, force: false, ..Default::default() };
+        assert!(!skip.select(&project, all.clone()).contains(&id_a));
+
+        // Forcing a single node pulls in its whole dependency chain, complete or not
+        let force = ExecutionOptions { skip_completed: true, force: true, ..Default::default() };
+        let selected = force.select(&project, HashSet::from([id_c.clone()]));
+        assert_eq!(selected, all);
+
+        // Tags narrow a whole-project run; forcing still brings their dependencies
+        project.nodes[2].tags = vec!["backend".to_string()];
+        let tagged = ExecutionOptions { tags: vec!["backend".to_string()], ..Default::default() };
+        assert_eq!(tagged.select_all(&project), HashSet::from([id_c.clone()]));
+        let tagged = ExecutionOptions { force: true, ..tagged };
+        assert_eq!(tagged.select_all(&project), all);
+    }
+}
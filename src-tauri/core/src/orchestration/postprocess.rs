@@ -0,0 +1,254 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use regex::Regex;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::graph::model::{
+    BuiltinStep, CodeNode, Language, PostProcessReport, PostProcessStep, Project,
+};
+use crate::llm::extract::parse_fence;
+
+/// How long an external formatter may run on one file
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The post-processing chain for one node, captured from the manifest so it can
+/// run without holding the project
+#[derive(Debug, Clone)]
+pub struct PostProcessor {
+    steps: Vec<PostProcessStep>,
+    language: Language,
+    file_path: String,
+    project_dir: PathBuf,
+}
+
+impl PostProcessor {
+    /// `None` when the manifest configures no steps for the node's language
+    pub fn for_node(project: &Project, node: &CodeNode) -> Option<Self> {
+        let steps = project.manifest.post_processing.get(&node.language)?;
+        (!steps.is_empty()).then(|| Self {
+            steps: steps.clone(),
+            language: node.language.clone(),
+            file_path: node.file_path.clone(),
+            project_dir: PathBuf::from(&project.project_path),
+        })
+    }
+
+    /// Run every step in order. A step that fails leaves the code as it was and the
+    /// chain carries on, so a missing formatter doesn't lose the generation.
+    pub async fn run(&self, mut code: String) -> (String, Vec<PostProcessReport>) {
+        let mut reports = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let result = match step {
+                PostProcessStep::Builtin(BuiltinStep::StripFences) => Ok(strip_fences(&code)),
+                PostProcessStep::Builtin(BuiltinStep::SortImports) => {
+                    Ok(sort_imports(&code, &self.language))
+                }
+                PostProcessStep::Builtin(BuiltinStep::TrimWhitespace) => Ok(trim_whitespace(&code)),
+                PostProcessStep::Builtin(formatter) => {
+                    let command = formatter_command(*formatter);
+                    self.run_command(&command, &code).await
+                }
+                PostProcessStep::Command { command } => self.run_command(command, &code).await,
+            };
+
+            let step = step_name(step);
+            match result {
+                Ok(processed) => {
+                    reports.push(PostProcessReport {
+                        step,
+                        changed: processed != code,
+                        error: None,
+                    });
+                    code = processed;
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "Post-processing step {} failed for {}: {}",
+                        step,
+                        self.file_path,
+                        error
+                    );
+                    reports.push(PostProcessReport {
+                        step,
+                        changed: false,
+                        error: Some(error),
+                    });
+                }
+            }
+        }
+        (code, reports)
+    }
+
+    /// Pipe the code through an external command run in the project directory
+    async fn run_command(&self, command: &[String], code: &str) -> Result<String, String> {
+        let (program, args) = command.split_first().ok_or("Empty command")?;
+        let args: Vec<String> = args
+            .iter()
+            .map(|a| a.replace("{file}", &self.file_path))
+            .collect();
+        let mut child = Command::new(program)
+            .args(&args)
+            .current_dir(&self.project_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+        let mut stdin = child.stdin.take().ok_or("Failed to open stdin")?;
+        let input = code.to_string();
+        // Written concurrently with reading, so a large file can't fill both pipes
+        let writer = tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+        let output = tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| format!("{} timed out after {}s", program, COMMAND_TIMEOUT.as_secs()))?
+            .map_err(|e| e.to_string())?;
+        let _ = writer.await;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            return Err(format!(
+                "{} exited with {}: {}",
+                program, output.status, message
+            ));
+        }
+        String::from_utf8(output.stdout).map_err(|_| format!("{} printed invalid UTF-8", program))
+    }
+}
+
+/// The command line of a built-in formatter
+fn formatter_command(formatter: BuiltinStep) -> Vec<String> {
+    let command: &[&str] = match formatter {
+        BuiltinStep::Prettier => &["prettier", "--stdin-filepath", "{file}"],
+        BuiltinStep::Rustfmt => &["rustfmt", "--edition", "2021"],
+        BuiltinStep::Black => &["black", "--quiet", "-"],
+        BuiltinStep::Gofmt => &["gofmt"],
+        _ => &[],
+    };
+    command.iter().map(|s| s.to_string()).collect()
+}
+
+/// The step's name in reports
+fn step_name(step: &PostProcessStep) -> String {
+    match step {
+        PostProcessStep::Builtin(builtin) => serde_json::to_value(builtin)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        PostProcessStep::Command { command } => command.join(" "),
+    }
+}
+
+/// Drop lines that are markdown fences
+fn strip_fences(code: &str) -> String {
+    code.split_inclusive('\n')
+        .filter(|line| parse_fence(line).is_none())
+        .collect()
+}
+
+/// Remove trailing whitespace from every line and blank lines at the end
+fn trim_whitespace(code: &str) -> String {
+    let lines: Vec<&str> = code.lines().map(str::trim_end).collect();
+    let mut trimmed = lines.join("\n").trim_end_matches('\n').to_string();
+    if code.ends_with('\n') && !trimmed.is_empty() {
+        trimmed.push('\n');
+    }
+    trimmed
+}
+
+/// A single-line import statement in the language, where there is a form to sort
+fn import_pattern(language: &Language) -> Option<Regex> {
+    let pattern = match language {
+        Language::TypeScript | Language::JavaScript => r#"^import\s.*['"];?$"#,
+        Language::Python => r"^(from\s+\S+\s+)?import\s+[^(\\]+$",
+        Language::Rust => r"^(pub(\([^)]*\))?\s+)?use\s+.+;$",
+        Language::Java | Language::Kotlin => r"^import\s+(static\s+)?[\w.*]+;?$",
+        Language::CSharp => r"^using\s+[\w.]+;$",
+        _ => return None,
+    };
+    Regex::new(pattern).ok()
+}
+
+/// Sort each run of consecutive single-line imports. Blank lines and anything else
+/// end a run, so existing groups stay apart.
+fn sort_imports(code: &str, language: &Language) -> String {
+    let Some(pattern) = import_pattern(language) else {
+        return code.to_string();
+    };
+    let mut lines: Vec<&str> = code.lines().collect();
+    let mut start = 0;
+    while start < lines.len() {
+        let len = lines[start..]
+            .iter()
+            .take_while(|l| pattern.is_match(l))
+            .count();
+        lines[start..start + len].sort_unstable();
+        start += len.max(1);
+    }
+
+    let mut sorted = lines.join("\n");
+    if code.ends_with('\n') {
+        sorted.push('\n');
+    }
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processor(steps: Vec<PostProcessStep>) -> PostProcessor {
+        PostProcessor {
+            steps,
+            language: Language::TypeScript,
+            file_path: "src/a.ts".to_string(),
+            project_dir: std::env::temp_dir(),
+        }
+    }
+
+    #[test]
+    fn test_sort_imports_keeps_groups() {
+        let code = "import { b } from 'b';\nimport { a } from 'a';\n\nimport { d } from './d';\n\
+                    import { c } from './c';\n\nexport const x = 1;\n";
+        assert_eq!(
+            sort_imports(code, &Language::TypeScript),
+            "import { a } from 'a';\nimport { b } from 'b';\n\nimport { c } from './c';\n\
+             import { d } from './d';\n\nexport const x = 1;\n"
+        );
+        assert_eq!(sort_imports("b\na", &Language::Bash), "b\na");
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_each_step() {
+        let processor = processor(vec![
+            PostProcessStep::Builtin(BuiltinStep::StripFences),
+            PostProcessStep::Command {
+                command: vec!["needlepoint-no-such-formatter".to_string()],
+            },
+            PostProcessStep::Builtin(BuiltinStep::TrimWhitespace),
+        ]);
+        let (code, reports) = processor
+            .run("```ts\nconst a = 1;   \n```\n\n".to_string())
+            .await;
+        assert_eq!(code, "const a = 1;\n");
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].step, "stripFences");
+        assert!(reports[0].changed);
+        assert!(reports[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Failed to run"));
+        assert!(!reports[1].changed);
+        assert_eq!(reports[2].step, "trimWhitespace");
+        assert!(reports[2].changed);
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::budget::{Budget, Spend};
+
+const RUNS_DIR: &str = ".needlepoint/runs";
+
+/// Outcome of a single node within a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRunReport {
+    pub node_id: String,
+    pub node_name: String,
+    pub model: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_used: Option<u32>,
+    /// Estimated cost in USD (None if the model's pricing is unknown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<f64>,
+    /// Tokens and estimated cost of the fix-up regeneration, when one was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixup: Option<Spend>,
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl NodeRunReport {
+    /// What generating the node spent, its fix-up included
+    pub fn spend(&self) -> Spend {
+        let mut spent = self.fixup.unwrap_or_default();
+        spent.add(self.tokens_used, self.cost_estimate);
+        spent
+    }
+}
+
+/// Summary of a complete execution run, persisted to `.needlepoint/runs/<id>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    /// Run ID (timestamp based, also used as the file name)
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: u64,
+    pub total_successful: usize,
+    pub total_failed: usize,
+    pub total_skipped: usize,
+    pub total_tokens: u64,
+    pub total_cost_estimate: f64,
+    #[serde(default)]
+    pub nodes: Vec<NodeRunReport>,
+}
+
+/// Lightweight listing entry for a past run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub total_successful: usize,
+    pub total_failed: usize,
+    pub total_skipped: usize,
+    pub total_tokens: u64,
+    pub total_cost_estimate: f64,
+}
+
+impl RunReport {
+    /// Start a new report timestamped now
+    pub fn new() -> Self {
+        let started_at = Utc::now();
+        Self {
+            id: started_at.format("%Y%m%d_%H%M%S_%3f").to_string(),
+            started_at,
+            finished_at: None,
+            duration_ms: 0,
+            total_successful: 0,
+            total_failed: 0,
+            total_skipped: 0,
+            total_tokens: 0,
+            total_cost_estimate: 0.0,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Record the outcome of a node
+    pub fn record(&mut self, node: NodeRunReport) {
+        if node.success {
+            self.total_successful += 1;
+        } else {
+            self.total_failed += 1;
+        }
+        let spent = node.spend();
+        self.total_tokens += spent.tokens;
+        self.total_cost_estimate += spent.cost;
+        self.nodes.push(node);
+    }
+
+    /// Mark the run as finished
+    pub fn finish(&mut self, total_skipped: usize) {
+        let finished_at = Utc::now();
+        self.duration_ms = (finished_at - self.started_at).num_milliseconds().max(0) as u64;
+        self.finished_at = Some(finished_at);
+        self.total_skipped = total_skipped;
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            id: self.id.clone(),
+            started_at: self.started_at,
+            duration_ms: self.duration_ms,
+            total_successful: self.total_successful,
+            total_failed: self.total_failed,
+            total_skipped: self.total_skipped,
+            total_tokens: self.total_tokens,
+            total_cost_estimate: self.total_cost_estimate,
+        }
+    }
+}
+
+impl Default for RunReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the run reports directory for a project
+fn get_runs_dir(project_path: &str) -> PathBuf {
+    Path::new(project_path).join(RUNS_DIR)
+}
+
+/// Write a run report to `.needlepoint/runs/<id>.json`
+pub fn save_run_report(project_path: &str, report: &RunReport) -> Result<PathBuf> {
+    let runs_dir = get_runs_dir(project_path);
+    fs::create_dir_all(&runs_dir)
+        .with_context(|| format!("Failed to create runs directory: {:?}", runs_dir))?;
+
+    let report_path = runs_dir.join(format!("{}.json", report.id));
+    let contents = serde_json::to_string_pretty(report).context("Failed to serialize run report")?;
+
+    fs::write(&report_path, contents)
+        .with_context(|| format!("Failed to write run report: {:?}", report_path))?;
+
+    Ok(report_path)
+}
+
+/// Load a single run report by ID
+pub fn load_run_report(project_path: &str, run_id: &str) -> Result<RunReport> {
+    // Run IDs are plain file stems; reject anything that could escape the runs directory
+    if run_id.is_empty() || run_id.contains(['/', '\\']) || run_id.contains("..") {
+        anyhow::bail!("Invalid run ID: {}", run_id);
+    }
+
+    let report_path = get_runs_dir(project_path).join(format!("{}.json", run_id));
+    let contents = fs::read_to_string(&report_path)
+        .with_context(|| format!("Failed to read run report: {:?}", report_path))?;
+
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse run report: {:?}", report_path))
+}
+
+/// What the project's recorded runs spent altogether
+pub fn project_spend(project_path: &str) -> Result<Spend> {
+    let mut spent = Spend::default();
+    for run in list_run_reports(project_path)? {
+        spent.tokens += run.total_tokens;
+        spent.cost += run.total_cost_estimate;
+    }
+    Ok(spent)
+}
+
+/// Refuse to generate once the project's recorded runs have spent its budget
+pub fn check_project_budget(project_path: &str, budget: Budget) -> Result<(), String> {
+    if budget.is_unlimited() {
+        return Ok(());
+    }
+    let spent = project_spend(project_path).map_err(|e| e.to_string())?;
+    match budget.exceeded(spent) {
+        Some(reason) => Err(format!("Project budget exceeded: {}", reason)),
+        None => Ok(()),
+    }
+}
+
+/// List all past runs, newest first
+pub fn list_run_reports(project_path: &str) -> Result<Vec<RunSummary>> {
+    let runs_dir = get_runs_dir(project_path);
+
+    if !runs_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&runs_dir)
+        .with_context(|| format!("Failed to read runs directory: {:?}", runs_dir))?;
+
+    let mut runs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        // Skip unreadable or malformed reports rather than failing the whole listing
+        if let Ok(contents) = fs::read_to_string(&path) {
+            if let Ok(report) = serde_json::from_str::<RunReport>(&contents) {
+                runs.push(report.summary());
+            }
+        }
+    }
+
+    runs.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn node_report(success: bool, tokens: u32, cost: f64) -> NodeRunReport {
+        NodeRunReport {
+            node_id: "n1".to_string(),
+            node_name: "N1".to_string(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            success,
+            duration_ms: 10,
+            input_tokens: None,
+            output_tokens: None,
+            tokens_used: Some(tokens),
+            cost_estimate: Some(cost),
+            fixup: None,
+            retries: 0,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_list_run_reports() {
+        let dir = tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+
+        let mut report = RunReport::new();
+        report.record(node_report(true, 100, 0.5));
+        report.record(node_report(false, 50, 0.25));
+        report.finish(1);
+        save_run_report(&project_path, &report).unwrap();
+
+        let runs = list_run_reports(&project_path).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].total_successful, 1);
+        assert_eq!(runs[0].total_failed, 1);
+        assert_eq!(runs[0].total_skipped, 1);
+        assert_eq!(runs[0].total_tokens, 150);
+
+        let loaded = load_run_report(&project_path, &report.id).unwrap();
+        assert_eq!(loaded.nodes.len(), 2);
+        assert!(load_run_report(&project_path, "../needlepoint").is_err());
+
+        assert_eq!(project_spend(&project_path).unwrap(), Spend { tokens: 150, cost: 0.75 });
+        let mut fixed = node_report(true, 100, 0.5);
+        fixed.fixup = Some(Spend { tokens: 40, cost: 0.1 });
+        assert_eq!(fixed.spend(), Spend { tokens: 140, cost: 0.6 });
+        let budget = |max_tokens| Budget {
+            max_tokens: Some(max_tokens),
+            max_cost: None,
+        };
+        assert!(check_project_budget(&project_path, budget(200)).is_ok());
+        assert_eq!(
+            check_project_budget(&project_path, budget(150)).unwrap_err(),
+            "Project budget exceeded: 150 of 150 tokens used"
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::HashSet;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::{request_timeout, Language, LLMConfig, PackageEcosystem, Project};
+use crate::graph::packages::{
+    cargo_toml, external_packages, package_name, pip_requirement, toml_string,
+};
+use crate::graph::paths::{normalize_file_path, validate_path};
+use crate::llm::{
+    create_provider, generate_with_timeout, parse_json_list, GenerationRequest, OutputSchema,
+};
+
+/// A generated build file at the project root (`package.json`, `tsconfig.json`,
+/// `Cargo.toml` or `pyproject.toml`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldFile {
+    pub file_path: String,
+    pub contents: String,
+}
+
+/// Outcome of writing scaffold files to disk
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldWrite {
+    pub written: Vec<String>,
+    /// Files left alone because they already exist
+    pub skipped: Vec<String>,
+}
+
+fn uses_language(project: &Project, languages: &[Language]) -> bool {
+    project
+        .nodes
+        .iter()
+        .any(|n| !n.disabled && n.kind.is_code() && languages.contains(&n.language))
+}
+
+fn scaffold_package_json(project: &Project) -> String {
+    let typescript = uses_language(project, &[Language::TypeScript]);
+    let mut manifest = serde_json::json!({
+        "name": package_name(project),
+        "version": project.manifest.version,
+        "private": true,
+        "type": "module",
+    });
+    if let Some(entry_point) = &project.manifest.entry_point {
+        manifest["main"] = serde_json::json!(entry_point);
+    }
+    if typescript {
+        manifest["scripts"] = serde_json::json!({ "build": "tsc" });
+    }
+    let dependencies: serde_json::Map<String, serde_json::Value> =
+        external_packages(project, PackageEcosystem::Npm)
+            .into_iter()
+            .map(|(name, version)| {
+                let version = if version.is_empty() { "*" } else { version };
+                (name.to_string(), serde_json::json!(version))
+            })
+            .collect();
+    if !dependencies.is_empty() {
+        manifest["dependencies"] = serde_json::Value::Object(dependencies);
+    }
+    if typescript {
+        manifest["devDependencies"] = serde_json::json!({ "typescript": "^5.4.0" });
+    }
+    format!("{}\n", serde_json::to_string_pretty(&manifest).unwrap_or_default())
+}
+
+fn scaffold_tsconfig() -> String {
+    let config = serde_json::json!({
+        "compilerOptions": {
+            "target": "ES2022",
+            "module": "ESNext",
+            "moduleResolution": "bundler",
+            "strict": true,
+            "esModuleInterop": true,
+            "skipLibCheck": true,
+            "outDir": "dist",
+        },
+        "include": ["**/*.ts", "**/*.tsx"],
+        "exclude": ["node_modules", "dist"],
+    });
+    format!("{}\n", serde_json::to_string_pretty(&config).unwrap_or_default())
+}
+
+fn scaffold_pyproject(project: &Project) -> String {
+    let mut contents = String::from("[project]\n");
+    contents.push_str(&format!("name = {}\n", toml_string(&package_name(project))));
+    contents.push_str(&format!("version = {}\n", toml_string(&project.manifest.version)));
+    contents.push_str("requires-python = \">=3.10\"\ndependencies = [\n");
+    for (name, version) in external_packages(project, PackageEcosystem::Pip) {
+        contents.push_str(&format!("    {},\n", toml_string(&pip_requirement(name, version))));
+    }
+    contents.push_str("]\n\n[build-system]\n");
+    contents.push_str("requires = [\"setuptools>=61\"]\n");
+    contents.push_str("build-backend = \"setuptools.build_meta\"\n");
+    contents
+}
+
+/// Paths of files a node generates; the scaffolder never replaces them
+fn node_owned_paths(project: &Project) -> HashSet<String> {
+    project
+        .nodes
+        .iter()
+        .filter(|n| n.owns_file())
+        .map(|n| normalize_file_path(&n.file_path))
+        .collect()
+}
+
+/// Derive build files from the languages in the graph and its external package
+/// nodes. Files generated by a node are left out.
+pub fn scaffold_files(project: &Project) -> Vec<ScaffoldFile> {
+    let has_packages = |ecosystem| !external_packages(project, ecosystem).is_empty();
+    let uses_npm = uses_language(project, &[Language::TypeScript, Language::JavaScript])
+        || has_packages(PackageEcosystem::Npm);
+    let uses_crates =
+        uses_language(project, &[Language::Rust]) || has_packages(PackageEcosystem::Crates);
+    let uses_pip =
+        uses_language(project, &[Language::Python]) || has_packages(PackageEcosystem::Pip);
+
+    let mut files = Vec::new();
+    if uses_npm {
+        files.push(("package.json", scaffold_package_json(project)));
+    }
+    if uses_language(project, &[Language::TypeScript]) {
+        files.push(("tsconfig.json", scaffold_tsconfig()));
+    }
+    if uses_crates {
+        let packages = external_packages(project, PackageEcosystem::Crates);
+        files.push(("Cargo.toml", cargo_toml(project, &packages)));
+    }
+    if uses_pip {
+        files.push(("pyproject.toml", scaffold_pyproject(project)));
+    }
+
+    let owned = node_owned_paths(project);
+    files
+        .into_iter()
+        .filter(|(path, _)| !owned.contains(*path))
+        .map(|(path, contents)| ScaffoldFile {
+            file_path: path.to_string(),
+            contents,
+        })
+        .collect()
+}
+
+/// Build the prompt asking the LLM to refine the deterministic drafts
+pub fn build_scaffold_prompt(project: &Project, drafts: &[ScaffoldFile]) -> String {
+    let mut prompt = format!(
+        "Write the build files for the project \"{}\" (version {}).\n\n",
+        project.manifest.name, project.manifest.version
+    );
+
+    if let Some(entry_point) = &project.manifest.entry_point {
+        prompt.push_str(&format!("Entry point: {}\n\n", entry_point));
+    }
+
+    prompt.push_str("## Source files\n");
+    for node in project.nodes.iter().filter(|n| !n.disabled && n.owns_file()) {
+        prompt.push_str(&format!("- {} ({})", node.file_path, node.language));
+        if !node.purpose.is_empty() {
+            prompt.push_str(&format!(": {}", node.purpose));
+        }
+        prompt.push('\n');
+    }
+
+    let packages: Vec<_> = project
+        .nodes
+        .iter()
+        .filter(|n| !n.disabled)
+        .filter_map(|n| n.package.as_ref())
+        .collect();
+    if !packages.is_empty() {
+        prompt.push_str("\n## Packages\n");
+        for package in packages {
+            prompt.push_str(&format!("- {} ({})\n", package.spec(), package.ecosystem));
+        }
+    }
+
+    prompt.push_str("\n## Drafts\n");
+    for draft in drafts {
+        prompt.push_str(&format!("### {}\n```\n{}```\n\n", draft.file_path, draft.contents));
+    }
+
+    prompt.push_str(
+        "Improve the drafts so the project builds and runs: add scripts, entry points, \
+         targets and tool settings the source files need. Keep every listed package and \
+         don't add packages the source files don't use. Respond with ONLY a JSON object whose \
+         \"files\" array holds objects with \"filePath\" (one of the draft paths) and \
+         \"contents\" (the complete file).",
+    );
+
+    prompt
+}
+
+/// Structured output requested from the scaffold pass
+fn files_schema() -> OutputSchema {
+    OutputSchema::list(
+        "submit_build_files",
+        "Submit the improved build files",
+        "files",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "filePath": { "type": "string" },
+                "contents": { "type": "string" },
+            },
+            "required": ["filePath", "contents"],
+        }),
+    )
+}
+
+/// Parse the file list out of an LLM response, keeping only the drafted paths
+pub fn parse_scaffold_files(
+    content: &str,
+    drafts: &[ScaffoldFile],
+) -> Result<Vec<ScaffoldFile>, String> {
+    let refined: Vec<ScaffoldFile> = parse_json_list(content, "files")
+        .map_err(|e| format!("Invalid scaffold file list: {}", e))?;
+
+    // Fall back to the draft for any file the model left out
+    Ok(drafts
+        .iter()
+        .map(|draft| {
+            refined
+                .iter()
+                .find(|f| f.file_path == draft.file_path && !f.contents.trim().is_empty())
+                .cloned()
+                .unwrap_or_else(|| draft.clone())
+        })
+        .collect())
+}
+
+/// Derive build files and have the project's default LLM refine them
+pub async fn run_scaffold_pass(
+    project: &Project,
+    api_key: Option<String>,
+) -> Result<Vec<ScaffoldFile>, String> {
+    let drafts = scaffold_files(project);
+    if drafts.is_empty() {
+        return Ok(drafts);
+    }
+
+    let config = LLMConfig {
+        provider: project.manifest.default_llm.provider.clone(),
+        model: project.manifest.default_llm.model.clone(),
+        ..LLMConfig::default()
+    };
+    let provider = create_provider(&config, api_key, project);
+
+    if !provider.is_configured() {
+        return Err(format!(
+            "{} is not configured. Please set your API key in Settings.",
+            provider.name()
+        ));
+    }
+
+    let request = GenerationRequest {
+        prompt: build_scaffold_prompt(project, &drafts),
+        system_prompt: Some(
+            "You are a senior engineer setting up the build configuration for a codebase."
+                .to_string(),
+        ),
+        max_tokens: Some(4096),
+        temperature: Some(0.2),
+        cache_breakpoints: Vec::new(),
+        output_schema: Some(files_schema()),
+        seed: None,
+        timeout: Some(request_timeout(&project.manifest, &config)),
+    };
+
+    let response = generate_with_timeout(provider.as_ref(), request)
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_scaffold_files(&response.content, &drafts)
+}
+
+/// Write scaffold files under the project directory. Existing files are kept
+/// unless `overwrite` is set.
+pub fn write_scaffold_files(
+    project_path: &str,
+    files: &[ScaffoldFile],
+    overwrite: bool,
+) -> Result<ScaffoldWrite, String> {
+    let mut result = ScaffoldWrite::default();
+    for file in files {
+        let full_path = validate_path(project_path, &file.file_path)?;
+        if full_path.exists() && !overwrite {
+            result.skipped.push(file.file_path.clone());
+            continue;
+        }
+        fs::write(&full_path, &file.contents)
+            .map_err(|e| format!("Failed to write {}: {}", file.file_path, e))?;
+        result.written.push(file.file_path.clone());
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{CodeNode, ExternalPackage, NodeKind};
+
+    #[test]
+    fn test_scaffold_files() {
+        let mut project = Project::new(String::new());
+        project.manifest.name = "Demo".to_string();
+        project.nodes.push(CodeNode::new(
+            "Main".to_string(),
+            "src/main.ts".to_string(),
+            Language::TypeScript,
+        ));
+        let mut requests = CodeNode::new("requests".to_string(), String::new(), Language::Python);
+        requests.kind = NodeKind::External;
+        requests.package = Some(ExternalPackage {
+            name: "requests".to_string(),
+            ecosystem: PackageEcosystem::Pip,
+            version: "2.31".to_string(),
+            docs: String::new(),
+        });
+        project.nodes.push(requests);
+        // A node that generates tsconfig.json takes precedence
+        let mut tsconfig = CodeNode::new(
+            "tsconfig".to_string(),
+            "tsconfig.json".to_string(),
+            Language::Custom("json".to_string()),
+        );
+        tsconfig.kind = NodeKind::Config;
+        project.nodes.push(tsconfig);
+
+        let files = scaffold_files(&project);
+        let paths: Vec<&str> = files.iter().map(|f| f.file_path.as_str()).collect();
+        assert_eq!(paths, vec!["package.json", "pyproject.toml"]);
+        assert!(files[0].contents.contains("\"typescript\""));
+        assert!(files[1].contents.contains("\"requests==2.31\""));
+
+        let response = "```json\n[{\"filePath\": \"package.json\", \"contents\": \"{}\"}, \
+                        {\"filePath\": \"setup.py\", \"contents\": \"x\"}]\n```";
+        let refined = parse_scaffold_files(response, &files).unwrap();
+        assert_eq!(refined.len(), 2);
+        assert_eq!(refined[0].contents, "{}");
+        assert_eq!(refined[1].contents, files[1].contents);
+    }
+}
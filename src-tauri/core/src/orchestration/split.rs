@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+
+use crate::graph::model::{request_timeout, CodeNode, LLMConfig, Project};
+use crate::llm::{
+    create_provider, generate_with_timeout, parse_json_list, ContextBuilder, GenerationRequest,
+    OutputSchema,
+};
+
+/// Prompts longer than this many characters leave the model too little room to follow
+/// every part of them
+pub const MAX_PROMPT_CHARS: usize = 48_000;
+
+/// Generated files longer than this many lines are where output quality drops off
+pub const MAX_CODE_LINES: usize = 600;
+
+/// Nodes exporting more than this many symbols are usually doing several jobs
+pub const MAX_EXPORTS: usize = 15;
+
+/// One of the smaller nodes an oversized node could be split into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedNode {
+    pub name: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub purpose: String,
+    /// Names of the original node's exports this node takes over
+    #[serde(default)]
+    pub exports: Vec<String>,
+    /// File paths of the other proposed nodes it depends on
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// How a node measures against the size thresholds, with a proposed split when one
+/// was asked for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitSuggestion {
+    pub node_id: String,
+    /// Whether any threshold is exceeded
+    pub oversized: bool,
+    pub prompt_chars: usize,
+    pub code_lines: usize,
+    pub export_count: usize,
+    /// Each threshold the node exceeds
+    pub reasons: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposal: Vec<ProposedNode>,
+}
+
+/// Measure a node's prompt, generated code and exports against the thresholds
+pub fn measure_node(project: &Project, node_id: &str) -> Option<SplitSuggestion> {
+    let node = project.find_node(node_id)?;
+    let prompt_chars = ContextBuilder::build_prompt(project, node_id)
+        .map_or(0, |prompt| prompt.chars().count());
+    let code_lines = node.generated_code.as_deref().map_or(0, |code| code.lines().count());
+    let export_count = node.exports.len();
+
+    let mut reasons = Vec::new();
+    if prompt_chars > MAX_PROMPT_CHARS {
+        reasons.push(format!(
+            "The prompt is {} characters long (over {})",
+            prompt_chars, MAX_PROMPT_CHARS
+        ));
+    }
+    if code_lines > MAX_CODE_LINES {
+        reasons.push(format!(
+            "The generated code is {} lines long (over {})",
+            code_lines, MAX_CODE_LINES
+        ));
+    }
+    if export_count > MAX_EXPORTS {
+        reasons.push(format!("It has {} exports (over {})", export_count, MAX_EXPORTS));
+    }
+
+    Some(SplitSuggestion {
+        node_id: node.id.clone(),
+        oversized: !reasons.is_empty(),
+        prompt_chars,
+        code_lines,
+        export_count,
+        reasons,
+        proposal: Vec::new(),
+    })
+}
+
+/// Build the prompt asking how to split a node into smaller ones
+pub fn build_split_prompt(project: &Project, node: &CodeNode) -> String {
+    let mut prompt = format!(
+        "{} ({}) has grown too large to generate reliably. Propose how to split it into \
+         2 to 5 smaller, cohesive files.\n\n",
+        node.name, node.file_path
+    );
+    prompt.push_str(&format!("Description: {}\n", node.description));
+    if !node.purpose.trim().is_empty() {
+        prompt.push_str(&format!("Purpose: {}\n", node.purpose));
+    }
+    if !node.exports.is_empty() {
+        prompt.push_str("Exports:\n");
+        for export in &node.exports {
+            prompt.push_str(&format!("- {}: {}\n", export.name, export.type_signature));
+        }
+    }
+
+    let dependents: Vec<&str> = project
+        .get_dependents(&node.id)
+        .iter()
+        .filter_map(|e| project.find_node(&e.target))
+        .map(|n| n.file_path.as_str())
+        .collect();
+    if !dependents.is_empty() {
+        prompt.push_str(&format!("Used by: {}\n", dependents.join(", ")));
+    }
+
+    if let Some(code) = &node.generated_code {
+        prompt.push_str(&format!("\n## Current code\n```\n{}", code));
+        if !code.ends_with('\n') {
+            prompt.push('\n');
+        }
+        prompt.push_str("```\n");
+    }
+
+    prompt.push_str(
+        "\nRespond with ONLY a JSON object whose \"nodes\" array lists the new files. Each \
+         is an object with \"name\", \"filePath\", \"description\", \"purpose\", \"exports\" \
+         (the names of the current exports it takes over; every export goes to exactly one \
+         file) and \"dependsOn\" (the file paths of the other new files it imports).",
+    );
+    prompt
+}
+
+/// Structured output requested from the split proposal
+fn split_schema() -> OutputSchema {
+    OutputSchema::list(
+        "submit_split",
+        "Submit the smaller files the oversized file should be split into",
+        "nodes",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "filePath": { "type": "string" },
+                "description": { "type": "string" },
+                "purpose": { "type": "string" },
+                "exports": { "type": "array", "items": { "type": "string" } },
+                "dependsOn": { "type": "array", "items": { "type": "string" } },
+            },
+            "required": ["name", "filePath", "description"],
+        }),
+    )
+}
+
+/// Parse the proposed nodes out of an LLM response. Dependencies on files outside the
+/// proposal are dropped.
+pub fn parse_split(content: &str) -> Result<Vec<ProposedNode>, String> {
+    let mut nodes: Vec<ProposedNode> =
+        parse_json_list(content, "nodes").map_err(|e| format!("Invalid split proposal: {}", e))?;
+    if nodes.len() < 2 {
+        return Err("The proposal doesn't split the node into several files".to_string());
+    }
+
+    let paths: Vec<String> = nodes.iter().map(|n| n.file_path.clone()).collect();
+    for node in &mut nodes {
+        let own_path = node.file_path.clone();
+        node.depends_on.retain(|path| *path != own_path && paths.contains(path));
+    }
+    Ok(nodes)
+}
+
+/// Ask the project's default LLM how to split a node into smaller nodes with edges
+/// between them
+pub async fn propose_split(
+    project: &Project,
+    node_id: &str,
+    api_key: Option<String>,
+) -> Result<Vec<ProposedNode>, String> {
+    let node = project
+        .find_node(node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))?;
+
+    let config = LLMConfig::project_default(&project.manifest.default_llm);
+    let provider = create_provider(&config, api_key, project);
+
+    if !provider.is_configured() {
+        return Err(format!(
+            "{} is not configured. Please set your API key in Settings.",
+            provider.name()
+        ));
+    }
+
+    let request = GenerationRequest {
+        prompt: build_split_prompt(project, node),
+        system_prompt: Some(
+            "You are a senior engineer breaking an oversized source file into cohesive modules."
+                .to_string(),
+        ),
+        max_tokens: Some(4096),
+        temperature: Some(0.3),
+        cache_breakpoints: Vec::new(),
+        output_schema: Some(split_schema()),
+        seed: None,
+        timeout: Some(request_timeout(&project.manifest, &config)),
+    };
+
+    let response = generate_with_timeout(provider.as_ref(), request)
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_split(&response.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::{ExportSignature, Language};
+
+    #[test]
+    fn test_measure_and_parse_split() {
+        let mut project = Project::new(String::new());
+        let node = |name: &str, path: &str| {
+            CodeNode::new(name.to_string(), path.to_string(), Language::TypeScript)
+        };
+        let mut small = node("Small", "src/small.ts");
+        small.generated_code = Some("export const a = 1;\n".to_string());
+        let mut big = node("Big", "src/big.ts");
+        big.generated_code = Some("const x = 1;\n".repeat(MAX_CODE_LINES + 1));
+        for i in 0..=MAX_EXPORTS {
+            big.exports.push(ExportSignature {
+                name: format!("export{}", i),
+                type_signature: "() => void".to_string(),
+                description: String::new(),
+                shared_type: None,
+            });
+        }
+        let (small_id, big_id) = (small.id.clone(), big.id.clone());
+        project.nodes.extend([small, big]);
+
+        let small = measure_node(&project, &small_id).unwrap();
+        assert!(!small.oversized && small.reasons.is_empty());
+        assert_eq!(small.code_lines, 1);
+        let big = measure_node(&project, &big_id).unwrap();
+        assert!(big.oversized);
+        assert_eq!(big.reasons.len(), 2);
+        assert_eq!(big.export_count, MAX_EXPORTS + 1);
+        assert!(measure_node(&project, "missing").is_none());
+
+        let response = r#"{"nodes": [
+            {"name": "Parser", "filePath": "src/parser.ts", "description": "Parses input",
+             "exports": ["export0"], "dependsOn": ["src/lexer.ts", "src/parser.ts"]},
+            {"name": "Lexer", "filePath": "src/lexer.ts", "description": "Tokenizes input"}
+        ]}"#;
+        let nodes = parse_split(response).unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].depends_on, vec!["src/lexer.ts"]);
+        assert!(nodes[1].exports.is_empty());
+        let single = r#"{"nodes": [{"name": "All", "filePath": "a.ts", "description": "x"}]}"#;
+        assert!(parse_split(single).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::graph::model::Project;
+
+/// What a paused step-mode run does next
+#[derive(Debug)]
+pub enum StepAction {
+    /// Generate the next wave, with the specs of the given project when they were
+    /// edited while paused
+    Continue(Option<Box<Project>>),
+    /// End the run without generating the remaining waves
+    Stop,
+    /// Reject the code of the nodes awaiting approval: they're marked as errors and
+    /// nothing that depends on them is generated
+    Reject,
+}
+
+/// Where a paused run stopped
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PausedStep {
+    /// The wave that just finished
+    pub wave_number: usize,
+    /// Waves still to generate
+    pub remaining_waves: usize,
+    /// Nodes whose generated code must be approved before their dependents are
+    /// generated; empty for a plain step-mode pause
+    pub awaiting_approval: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Pause {
+    step: PausedStep,
+    resume: oneshot::Sender<StepAction>,
+}
+
+/// Pauses a run after a wave (every wave in step mode, or those ending at an approval
+/// gate) until it's told to continue. Clones share the same state, so one can be handed
+/// to the executor and another kept to resume it.
+#[derive(Debug, Clone, Default)]
+pub struct StepControl {
+    pause: Arc<Mutex<Option<Pause>>>,
+}
+
+impl StepControl {
+    fn pause_slot(&self) -> MutexGuard<'_, Option<Pause>> {
+        self.pause.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Where the run is paused, if it is
+    pub fn paused(&self) -> Option<PausedStep> {
+        self.pause_slot().as_ref().map(|p| p.step.clone())
+    }
+
+    /// Let a paused run go on. Returns `false` when no run is paused.
+    pub fn resume(&self, action: StepAction) -> bool {
+        match self.pause_slot().take() {
+            Some(pause) => pause.resume.send(action).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Pause, call `on_paused` (so it can be resumed from then on) and wait until the
+    /// run is resumed
+    pub(crate) async fn wait(&self, step: PausedStep, on_paused: impl FnOnce()) -> StepAction {
+        let (resume, resumed) = oneshot::channel();
+        *self.pause_slot() = Some(Pause { step, resume });
+        on_paused();
+        // A run dropped while paused (e.g. its request was aborted) is no longer paused
+        let _clear = ClearPause(self);
+        resumed.await.unwrap_or(StepAction::Stop)
+    }
+}
+
+struct ClearPause<'a>(&'a StepControl);
+
+impl Drop for ClearPause<'_> {
+    fn drop(&mut self) {
+        self.0.pause_slot().take();
+    }
+}
@@ -1,10 +1,15 @@
+pub mod queue;
 pub mod routes;
 pub mod state;
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::{middleware, Router};
+use tower_http::compression::predicate::{DefaultPredicate, NotForContentType, Predicate};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 
 use state::AppState;
@@ -12,38 +17,103 @@ use state::AppState;
 /// Default port for the HTTP API
 pub const DEFAULT_PORT: u16 = 9999;
 
+/// Address to serve the API on (default 127.0.0.1). Any other address lets
+/// other machines connect as collaborators and requires `TOKEN_ENV`.
+pub const HOST_ENV: &str = "NEEDLEPOINT_HOST";
+
+/// Bearer token clients must send in `Authorization`
+pub const TOKEN_ENV: &str = "NEEDLEPOINT_TOKEN";
+
+/// Largest request body accepted by most routes
+pub const MAX_REQUEST_BYTES: usize = 8 * 1024 * 1024;
+
+/// Largest body for creating or updating a node, which may carry a large
+/// `generatedCode`
+pub const MAX_NODE_REQUEST_BYTES: usize = 64 * 1024 * 1024;
+
+/// Set to `1` or `true` to only serve GET endpoints, so the port can be shared
+/// with dashboards and other tools without risking changes to the graph
+pub const READ_ONLY_ENV: &str = "NEEDLEPOINT_READ_ONLY";
+
 /// Start the HTTP API server
-/// Returns the port it's running on
-pub async fn start_server(state: Arc<AppState>) -> Result<u16, std::io::Error> {
+/// Returns the address it's listening on
+pub async fn start_server(state: Arc<AppState>) -> Result<SocketAddr, std::io::Error> {
+    let host: IpAddr = match std::env::var(HOST_ENV) {
+        Ok(host) if !host.is_empty() => host.parse().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid {}: {}", HOST_ENV, host),
+            )
+        })?,
+        _ => IpAddr::from([127, 0, 0, 1]),
+    };
+    let token = std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty());
+    if !host.is_loopback() && token.is_none() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Serving the API on {} requires {} to be set", host, TOKEN_ENV),
+        ));
+    }
+    *state.access_token.write().await = token;
+    let read_only = std::env::var(READ_ONLY_ENV)
+        .is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+    state.read_only.store(read_only, Ordering::SeqCst);
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers(Any);
 
     let app = Router::new()
-        .nest("/api", routes::create_routes())
+        .nest(
+            "/api",
+            routes::create_routes()
+                .layer(middleware::from_fn_with_state(
+                    Arc::clone(&state),
+                    routes::revision_guard,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    Arc::clone(&state),
+                    routes::read_only_guard,
+                )),
+        )
+        .nest("/api/mcp", crate::mcp::sse_routes())
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            routes::require_token,
+        ))
+        // Bodies are limited by `body_limit_guard` rather than per extractor, where
+        // the default (and its error) depends on the axum version
+        .layer(middleware::from_fn(routes::body_limit_guard))
+        .layer(DefaultBodyLimit::disable())
+        // gzip or brotli, as the client accepts. Event streams and followed logs
+        // (NDJSON) are left uncompressed so each line is sent as soon as it's written.
+        .layer(CompressionLayer::new().compress_when(
+            DefaultPredicate::new().and(NotForContentType::const_new("application/x-ndjson")),
+        ))
         .layer(cors)
         .with_state(Arc::clone(&state));
 
     // Try to bind to default port, fall back to random port
-    let addr = SocketAddr::from(([127, 0, 0, 1], DEFAULT_PORT));
+    let addr = SocketAddr::from((host, DEFAULT_PORT));
     let listener = match tokio::net::TcpListener::bind(addr).await {
         Ok(l) => l,
         Err(_) => {
             // Port in use, try random port
-            tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?
+            tokio::net::TcpListener::bind(SocketAddr::from((host, 0))).await?
         }
     };
 
-    let port = listener.local_addr()?.port();
+    let addr = listener.local_addr()?;
 
     // Store the port in state
-    *state.port.write().await = Some(port);
+    *state.port.write().await = Some(addr.port());
 
     // Spawn the server in a background task
     tokio::spawn(async move {
         axum::serve(listener, app).await.ok();
     });
 
-    Ok(port)
+    Ok(addr)
 }
@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Notify;
+
+/// What a queued request generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GenerationKind {
+    /// One node (`generate/:id` or the MCP `generate_node` tool)
+    Node,
+    /// Every node (`generate-all`)
+    All,
+    /// A trial wave (`pilot`)
+    Pilot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueStatus {
+    Queued,
+    Running,
+}
+
+/// A generation request waiting for, or holding, its turn
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedGeneration {
+    pub id: String,
+    pub kind: GenerationKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub status: QueueStatus,
+}
+
+#[derive(Debug)]
+struct QueueEntry {
+    item: QueuedGeneration,
+    /// Wakes the waiting request when the entry is removed
+    removed: Arc<Notify>,
+}
+
+/// Why a queued generation couldn't be removed
+#[derive(Debug, PartialEq, Eq)]
+pub enum RemoveQueuedError {
+    NotFound,
+    /// It's already generating; cancel it instead
+    Running,
+}
+
+/// Runs generation requests one at a time, first come first served, so two
+/// requests never generate the same node at once or overwrite each other's status
+#[derive(Debug, Default)]
+pub struct GenerationQueue {
+    /// Held by the running generation; tokio's mutex is handed out in request order
+    turn: tokio::sync::Mutex<()>,
+    entries: Mutex<Vec<QueueEntry>>,
+}
+
+/// A request's turn to generate; the next request starts when it's dropped
+pub struct QueueTurn<'a> {
+    _ticket: QueueTicket<'a>,
+    _guard: tokio::sync::MutexGuard<'a, ()>,
+}
+
+/// Takes a request's entry off the queue when it finishes or gives up waiting
+struct QueueTicket<'a> {
+    queue: &'a GenerationQueue,
+    id: String,
+}
+
+impl Drop for QueueTicket<'_> {
+    fn drop(&mut self) {
+        self.queue.entries().retain(|e| e.item.id != self.id);
+    }
+}
+
+impl GenerationQueue {
+    fn entries(&self) -> MutexGuard<'_, Vec<QueueEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Running and waiting requests, the running one first
+    pub fn list(&self) -> Vec<QueuedGeneration> {
+        self.entries().iter().map(|e| e.item.clone()).collect()
+    }
+
+    /// Queue a request and wait for its turn. Fails if the request is removed from
+    /// the queue while waiting.
+    pub async fn wait_turn(
+        &self,
+        kind: GenerationKind,
+        node_id: Option<String>,
+    ) -> Result<QueueTurn<'_>, String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let removed = Arc::new(Notify::new());
+        self.entries().push(QueueEntry {
+            item: QueuedGeneration {
+                id: id.clone(),
+                kind,
+                node_id,
+                queued_at: Utc::now(),
+                status: QueueStatus::Queued,
+            },
+            removed: Arc::clone(&removed),
+        });
+        let ticket = QueueTicket { queue: self, id };
+
+        tokio::select! {
+            guard = self.turn.lock() => {
+                let mut entries = self.entries();
+                // It may have been removed just as its turn came
+                let entry = entries
+                    .iter_mut()
+                    .find(|e| e.item.id == ticket.id)
+                    .ok_or_else(removed_error)?;
+                entry.item.status = QueueStatus::Running;
+                drop(entries);
+                Ok(QueueTurn { _ticket: ticket, _guard: guard })
+            }
+            _ = removed.notified() => Err(removed_error()),
+        }
+    }
+
+    /// Drop a request that is still waiting; it fails without generating anything
+    pub fn remove(&self, id: &str) -> Result<(), RemoveQueuedError> {
+        let mut entries = self.entries();
+        let index = entries
+            .iter()
+            .position(|e| e.item.id == id)
+            .ok_or(RemoveQueuedError::NotFound)?;
+        if entries[index].item.status == QueueStatus::Running {
+            return Err(RemoveQueuedError::Running);
+        }
+        entries.remove(index).removed.notify_one();
+        Ok(())
+    }
+}
+
+fn removed_error() -> String {
+    "Removed from the generation queue".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generation_queue() {
+        let queue = GenerationQueue::default();
+        let first = queue.wait_turn(GenerationKind::All, None).await.unwrap();
+
+        let second = queue.wait_turn(GenerationKind::Node, Some("a".to_string()));
+        let third = queue.wait_turn(GenerationKind::Pilot, None);
+        tokio::pin!(second, third);
+        // Let both requests join the queue behind the running one
+        assert!(futures::poll!(second.as_mut()).is_pending());
+        assert!(futures::poll!(third.as_mut()).is_pending());
+
+        let items = queue.list();
+        let statuses: Vec<_> = items.iter().map(|i| i.status).collect();
+        assert_eq!(
+            statuses,
+            vec![QueueStatus::Running, QueueStatus::Queued, QueueStatus::Queued]
+        );
+        assert_eq!(queue.remove(&items[0].id), Err(RemoveQueuedError::Running));
+        assert_eq!(queue.remove("missing"), Err(RemoveQueuedError::NotFound));
+
+        queue.remove(&items[1].id).unwrap();
+        assert!(second.await.is_err());
+
+        drop(first);
+        let _turn = third.await.unwrap();
+        let items = queue.list();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, GenerationKind::Pilot);
+        assert_eq!(items[0].status, QueueStatus::Running);
+    }
+}
@@ -1,19 +1,125 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    body::Body,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
+use futures::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 
-use crate::graph::model::{CodeEdge, CodeNode, Language, Project, ProjectManifest};
-use crate::graph::{load_project_from_file, save_project_to_file};
-use crate::llm::{create_provider, strip_code_blocks, ContextBuilder, GenerationRequest};
-use crate::orchestration::ExecutionPlan;
+use crate::graph::model::{
+    request_timeout, validate_exports, CodeEdge, CodeNode, DeleteCascade, EdgeUpdate,
+    EmbeddingConfig, ExportSignature, ExternalPackage, GenerationProfile, GenerationRecord,
+    Language, NodeKind, NodeStatus, Project, ProjectManifest, RemoveNodeError, SharedType,
+    UpdateEdgeError,
+};
+use crate::commands::filesystem::{
+    auto_purge_trash, build_file_tree, read_project_file, write_generated_file, FileContents,
+    NodeFileWrite, ProjectFiles, ReadFileError,
+};
+use crate::graph::budget::Spend;
+use crate::graph::changes::{load_changes, ChangeEvent};
+use crate::graph::constraints::{
+    fix_generated_code, verify_generated_code, ConstraintViolation, FixupOptions,
+};
+use crate::graph::history::{load_node_history, NodeHistory};
+use crate::graph::templates::{apply_template, ProjectTemplate, TEMPLATES};
+use crate::graph::tree::{dependency_tree, DependencyTree, TreeDirection};
+use crate::graph::variables::{resolve_node, resolve_project};
+use crate::graph::imports::ConsistencyIssue;
+use crate::graph::layout::auto_layout;
+use crate::graph::lint::{lint_specs, SpecHint};
+use crate::graph::packages::{generate_package_manifests, PackageManifest};
+use crate::graph::query::{NodeFilter, PageQuery};
+use crate::graph::reconcile::{
+    apply_renames, import_file, scan_project, NodeFile, ReconcileReport,
+};
+use crate::graph::replace::{replace_in_project, ReplaceField, ReplaceMatch};
+use crate::graph::settings::{save_settings, ProjectSettings};
+use crate::graph::search::{
+    refresh_for_related_context, semantic_search, text_search, SearchHit, SearchMode,
+    DEFAULT_SEARCH_LIMIT,
+};
+use crate::graph::stats::{project_stats, tag_stats, ProjectStats};
+use crate::graph::suggest::{suggest_edges, EdgeSuggestion};
+use crate::graph::validation::{file_path_conflicts, validate_project};
+use crate::graph::workspace::include_subprojects;
+use crate::graph::{load_project_from_file, save_project_to_file, PROJECT_FILE_NAME};
+use crate::logging;
+use crate::llm::mistral::{list_models, MistralModel};
+use crate::llm::transcript::{load_transcripts, Transcript};
+use crate::llm::{
+    create_provider, estimate_cost, extract_code, generate_with_retries, ContextBuilder,
+    GenerationRequest, PendingTranscript,
+};
+use crate::orchestration::compare::{
+    accept_candidate, generate_candidate, load_candidates, save_candidates, Candidate,
+};
+use crate::orchestration::consistency::check_consistency;
+use crate::orchestration::scaffold::{
+    run_scaffold_pass, scaffold_files, write_scaffold_files, ScaffoldFile, ScaffoldWrite,
+};
+use crate::orchestration::checkpoint::{clear_checkpoint, load_checkpoint};
+use crate::orchestration::split::{measure_node, propose_split, SplitSuggestion};
+use crate::orchestration::impact::{analyze_impact, ImpactReport};
+use crate::orchestration::checks::{run_checks, CheckResult};
+use crate::orchestration::github::{open_pull_request, PullRequest};
+use crate::recent::{self, RecentProjects};
+use crate::orchestration::report::{check_project_budget, list_run_reports, load_run_report};
+use crate::orchestration::{
+    ExecutionEvent, ExecutionOptions, ExecutionPlan, Executor, InterruptedRun, NodeProgress,
+    NodeRunReport, PausedStep, PilotReport, PostProcessor, RunReport, RunSummary, StepAction,
+};
 
-use super::state::{ApiKeys, AppState};
+use super::{MAX_NODE_REQUEST_BYTES, MAX_REQUEST_BYTES};
+use super::queue::{GenerationKind, QueueTurn, QueuedGeneration, RemoveQueuedError};
+use super::state::{ApiKeys, AppState, RevisionCondition, IF_MATCH};
+
+/// Response header carrying the cursor for the next page of nodes
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Mutations that don't need a matching `If-Match`: they replace the project
+/// wholesale or don't touch it
+const REVISION_EXEMPT_PATHS: &[&str] = &[
+    "/project/new",
+    "/project/load",
+    "/project/save",
+    "/project/close",
+    "/project/settings",
+    "/api-keys",
+    "/search",
+    "/project/checks",
+    "/execution/continue",
+    "/execution/approve",
+    "/execution/interrupted",
+    "/recent-projects/auto-load",
+];
+
+/// Prefixes of mutations exempt from `If-Match`, for routes with a path parameter
+const REVISION_EXEMPT_PREFIXES: &[&str] = &["/queue/", "/runs/"];
+
+/// Reads that depend only on the project, answered with 304 Not Modified when
+/// `If-None-Match` carries the current revision
+const CACHEABLE_PATHS: &[&str] = &["/project", "/nodes", "/edges"];
+
+/// Non-GET routes that only read the project, allowed while the API is read-only
+const READ_ONLY_SAFE_PATHS: &[&str] = &["/search"];
+
+/// Whether a non-GET route only reads the project, including those with a path
+/// parameter
+fn only_reads(path: &str) -> bool {
+    READ_ONLY_SAFE_PATHS.contains(&path)
+        || (path.starts_with("/nodes/") && path.ends_with("/impact"))
+}
 
 /// Create all API routes
 pub fn create_routes() -> Router<Arc<AppState>> {
@@ -23,25 +129,90 @@ pub fn create_routes() -> Router<Arc<AppState>> {
         // Project
         .route("/project", get(get_project))
         .route("/project/new", post(new_project))
+        .route("/templates", get(list_templates))
         .route("/project/load", post(load_project))
         .route("/project/save", post(save_project))
+        .route("/project/close", post(close_project))
+        .route("/recent-projects", get(get_recent_projects))
+        .route("/recent-projects/auto-load", put(set_auto_load_project))
+        .route("/project/replace", post(replace_in_specs))
+        .route("/project/write-files", post(write_project_files))
+        .route("/project/files", get(list_project_files))
+        .route("/project/reconcile", post(reconcile_project))
+        .route("/project/validate", get(validate_project_graph))
+        .route("/project/checks", post(run_project_checks))
+        .route("/project/lint-specs", get(lint_project_specs))
+        .route("/project/suggest-edges", get(suggest_project_edges))
+        .route("/project/package-manifests", get(get_package_manifests))
+        .route("/project/scaffold", post(scaffold_project))
+        .route("/project/layout", post(layout_project))
+        .route("/project/stats", get(get_project_stats))
+        .route("/project/settings", get(get_project_settings).put(set_project_settings))
+        .route("/project/manifest", get(get_manifest).put(update_manifest))
+        .route("/project/check-constraints", post(check_project_constraints))
+        .route("/project/check-consistency", post(check_project_consistency))
+        .route("/project/changes", get(get_project_changes))
+        .route("/project/changes/stream", get(stream_project_changes))
+        // Files
+        .route("/files", get(read_project_file_contents))
+        // Search
+        .route("/search", post(search_nodes))
         // Nodes
         .route("/nodes", get(list_nodes))
         .route("/nodes", post(create_node))
         .route("/nodes/:id", get(get_node))
         .route("/nodes/:id", put(update_node))
         .route("/nodes/:id", delete(delete_node))
+        .route("/nodes/:id/clone", post(clone_node))
+        .route("/nodes/:id/exports", put(set_node_exports))
+        .route("/nodes/:id/transcript", get(get_node_transcript))
+        .route("/nodes/:id/history", get(get_node_history))
+        .route("/nodes/:id/tree", get(get_node_tree))
+        .route("/nodes/:id/impact", post(analyze_node_impact))
+        .route("/nodes/:id/generate-compare", post(generate_compare))
+        .route("/nodes/:id/suggest-split", post(suggest_node_split))
+        .route("/nodes/:id/candidates", get(list_node_candidates))
+        .route(
+            "/nodes/:id/candidates/:candidate_id/accept",
+            post(accept_node_candidate),
+        )
         // Edges
         .route("/edges", get(list_edges))
         .route("/edges", post(create_edge))
+        .route("/edges/:id", put(update_edge))
         .route("/edges/:id", delete(delete_edge))
+        // Context snippets
+        .route("/snippets", get(list_snippets))
+        .route("/snippets/:name", put(set_snippet))
+        .route("/snippets/:name", delete(delete_snippet))
+        // Shared types
+        .route("/shared-types", get(list_shared_types))
+        .route("/shared-types/:name", put(set_shared_type))
+        .route("/shared-types/:name", delete(delete_shared_type))
         // Generation
         .route("/generate/:id", post(generate_node))
         .route("/generate-all", post(generate_all))
+        .route("/pilot", post(generate_pilot))
+        .route("/queue", get(list_queue))
+        .route("/queue/:id", delete(remove_queued))
+        .route("/execution/paused", get(get_paused_step))
+        .route("/execution/continue", post(continue_execution))
+        .route("/execution/approve", post(approve_generation))
+        .route("/execution/interrupted", get(get_interrupted_run))
+        .route("/execution/interrupted", delete(discard_interrupted_run))
+        .route("/execution/resume", post(resume_interrupted_run))
         .route("/execution-plan", get(get_execution_plan))
+        .route("/execution-plan/:id", get(get_node_execution_plan))
         .route("/prompt/:id", get(preview_prompt))
+        // Run reports
+        .route("/runs", get(list_runs))
+        .route("/runs/:id", get(get_run))
+        .route("/runs/:id/pull-request", post(open_run_pull_request))
         // API Keys
         .route("/api-keys", post(set_api_keys))
+        .route("/providers/:provider/models", get(list_provider_models))
+        // Logs
+        .route("/logs", get(get_logs))
 }
 
 // === Response Types ===
@@ -52,11 +223,15 @@ struct StatusResponse {
     version: String,
     project_loaded: bool,
     project_name: Option<String>,
+    revision: u64,
+    read_only: bool,
+    /// The project changed since it was loaded or last saved
+    unsaved_changes: bool,
 }
 
 #[derive(Serialize)]
-struct ErrorResponse {
-    error: String,
+pub(crate) struct ErrorResponse {
+    pub(crate) error: String,
 }
 
 #[derive(Deserialize)]
@@ -64,6 +239,9 @@ struct NewProjectRequest {
     path: String,
     #[serde(default = "default_project_name")]
     name: String,
+    /// ID of a built-in template to start from (see `GET /templates`)
+    #[serde(default)]
+    template: Option<String>,
 }
 
 fn default_project_name() -> String {
@@ -75,20 +253,110 @@ struct LoadProjectRequest {
     path: String,
 }
 
+#[derive(Deserialize, Default)]
+struct CloseProjectRequest {
+    /// Save unsaved changes before closing
+    #[serde(default)]
+    save: bool,
+    /// Close even though there are unsaved changes, losing them
+    #[serde(default)]
+    discard: bool,
+}
+
+#[derive(Deserialize)]
+struct AutoLoadRequest {
+    enabled: bool,
+}
+
 #[derive(Deserialize)]
 struct CreateNodeRequest {
     name: String,
     file_path: String,
     #[serde(default)]
     language: Option<Language>,
+    #[serde(default)]
+    kind: NodeKind,
+    /// Required for external nodes
+    #[serde(default)]
+    package: Option<ExternalPackage>,
 }
 
+/// A JSON merge patch of the node's fields (see `CodeNode::apply_patch`)
 #[derive(Deserialize)]
 struct UpdateNodeRequest {
     #[serde(flatten)]
     updates: serde_json::Value,
 }
 
+#[derive(Deserialize)]
+struct ReplaceRequest {
+    find: String,
+    replace: String,
+    /// Treat `find` as a regular expression
+    #[serde(default)]
+    regex: bool,
+    /// Fields to search (defaults to all)
+    #[serde(default)]
+    fields: Option<Vec<ReplaceField>>,
+    /// Only report what would change
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct CompareRequest {
+    /// Overrides for the first candidate (provider, model, max_tokens, temperature)
+    a: GenerationProfile,
+    /// Overrides for the second candidate
+    b: GenerationProfile,
+}
+
+#[derive(Deserialize, Default)]
+struct AcceptCandidateRequest {
+    /// Also switch the node to the candidate's provider and model
+    #[serde(default)]
+    adopt_config: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct CloneNodeRequest {
+    /// Name of the copy (defaults to `<name>_copy`)
+    #[serde(default)]
+    name: Option<String>,
+    /// File path of the copy (defaults to `_copy` before the extension)
+    #[serde(default)]
+    file_path: Option<String>,
+    /// Give the copy the same dependencies as the original
+    #[serde(default)]
+    copy_edges: bool,
+}
+
+#[derive(Deserialize)]
+struct SearchRequest {
+    query: String,
+    #[serde(default)]
+    mode: SearchMode,
+    /// Maximum number of results (defaults to `DEFAULT_SEARCH_LIMIT`)
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SetExportsRequest {
+    exports: Vec<ExportSignature>,
+}
+
+#[derive(Deserialize)]
+struct SetSnippetRequest {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct DeleteNodeQuery {
+    /// What to do with nodes that depend on the deleted one
+    #[serde(default)]
+    cascade: DeleteCascade,
+}
+
 #[derive(Deserialize)]
 struct CreateEdgeRequest {
     source: String,
@@ -103,18 +371,197 @@ struct GenerateRequest {
     api_key: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct GenerateQuery {
+    /// Generate the node's not-yet-complete dependencies first
+    #[serde(default)]
+    with_deps: bool,
+    /// Return the existing code instead of regenerating a complete node
+    #[serde(default)]
+    skip_completed: bool,
+    /// Regenerate all transitive dependencies fresh before the node, complete or not
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct GenerateAllRequest {
+    /// Run an LLM review of cross-file consistency after generation
+    #[serde(default)]
+    consistency_pass: bool,
+    /// Leave nodes that are already complete untouched
+    #[serde(default)]
+    skip_completed: bool,
+    /// Regenerate every node, overriding `skip_completed`
+    #[serde(default)]
+    force: bool,
+    /// Only generate nodes carrying one of these tags (with `force`, along with their
+    /// dependencies)
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Manifest profile overriding every node's model for this run
+    #[serde(default)]
+    profile: Option<String>,
+    /// Pause after each wave until `POST /execution/continue`
+    #[serde(default)]
+    step: bool,
+    /// Stop starting nodes after this many seconds, leaving the rest pending
+    #[serde(default)]
+    max_duration_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+struct ContinueRequest {
+    /// End the run instead of generating the next wave
+    #[serde(default)]
+    stop: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct ApproveRequest {
+    /// Reject the code instead, so nothing depending on it is generated
+    #[serde(default)]
+    reject: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuggestEdgesQuery {
+    /// Only suggest dependencies of this node
+    node: Option<String>,
+    /// Leave out suggestions less confident than this (0 to 1)
+    #[serde(default)]
+    min_confidence: f64,
+}
+
+#[derive(Deserialize)]
+struct ChangesQuery {
+    /// Only return changes after this revision
+    #[serde(default)]
+    since: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeViolations {
+    node_id: String,
+    node_name: String,
+    violations: Vec<ConstraintViolation>,
+    missing_exports: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangesResponse {
+    /// Current revision; pass it as `since` on the next poll
+    revision: u64,
+    changes: Vec<ChangeEvent>,
+}
+
+#[derive(Deserialize, Default)]
+struct ConsistencyCheckRequest {
+    /// Also have the project's default LLM review each pair of dependent files
+    #[serde(default)]
+    llm: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeConsistency {
+    node_id: String,
+    node_name: String,
+    issues: Vec<ConsistencyIssue>,
+}
+
+#[derive(Deserialize, Default)]
+struct SuggestSplitRequest {
+    /// Have the project's default LLM propose the smaller nodes and their edges
+    #[serde(default)]
+    llm: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct ScaffoldRequest {
+    /// Have the project's default LLM refine the derived files
+    #[serde(default)]
+    llm: bool,
+    /// Return the files without writing them
+    #[serde(default)]
+    dry_run: bool,
+    /// Replace build files that already exist
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScaffoldResponse {
+    files: Vec<ScaffoldFile>,
+    #[serde(flatten)]
+    write: ScaffoldWrite,
+}
+
+#[derive(Deserialize, Default)]
+struct PilotRequest {
+    /// Wave to generate (defaults to the first)
+    #[serde(default)]
+    wave: usize,
+    /// Stop waiting for generations after this many seconds
+    #[serde(default)]
+    time_limit_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ExecutionPlanQuery {
+    /// Concurrency limit used for wall-clock estimates
+    #[serde(default)]
+    concurrency: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct LogsQuery {
+    /// Number of most recent lines to return
+    #[serde(default = "default_log_lines")]
+    lines: usize,
+    /// Only return entries written after this RFC 3339 timestamp (overrides `lines`)
+    #[serde(default)]
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Keep the connection open and stream new lines as they are written
+    #[serde(default)]
+    follow: bool,
+}
+
+fn default_log_lines() -> usize {
+    100
+}
+
+#[derive(Deserialize)]
+struct ReadFileQuery {
+    /// File path relative to the project root
+    path: String,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ApiKeysRequest {
     anthropic: Option<String>,
     openai: Option<String>,
+    mistral: Option<String>,
     ollama_base_url: Option<String>,
+    /// Token pull requests for runs are opened with
+    github: Option<String>,
+    /// Proxy for provider requests; an empty string clears it so the server's
+    /// environment applies, and leaving it out keeps the current one
+    proxy_url: Option<String>,
 }
 
 #[derive(Serialize)]
 struct GenerateResponse {
     code: String,
     node_id: String,
+    /// Dependencies generated first because of `?with_deps=true`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    generated_dependencies: Vec<String>,
 }
 
 // === Handlers ===
@@ -126,24 +573,238 @@ async fn get_status(State(state): State<Arc<AppState>>) -> Json<StatusResponse>
         version: env!("CARGO_PKG_VERSION").to_string(),
         project_loaded: project.is_some(),
         project_name: project.map(|p| p.manifest.name),
+        revision: state.revision(),
+        read_only: state.read_only(),
+        unsaved_changes: state.has_unsaved_changes().await,
     })
 }
 
-async fn get_project(
+/// Check an `If-Match` header against the current revision. `*` matches any
+/// revision; a missing header is refused so stale clients can't clobber edits.
+fn check_if_match(if_match: Option<&str>, revision: u64) -> Result<(), (StatusCode, String)> {
+    let Some(if_match) = if_match else {
+        return Err((
+            StatusCode::PRECONDITION_REQUIRED,
+            format!(
+                "Missing If-Match header; send the project revision ({}) or * to force",
+                revision
+            ),
+        ));
+    };
+
+    if etag_matches(Some(if_match), revision) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Project was changed by another client (revision {}, expected {}); reload and retry",
+                revision, if_match
+            ),
+        ))
+    }
+}
+
+/// Whether an `If-Match` or `If-None-Match` header names the revision (or is `*`)
+fn etag_matches(header: Option<&str>, revision: u64) -> bool {
+    header.is_some_and(|tags| {
+        tags.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*" || tag.trim_start_matches("W/").trim_matches('"') == revision.to_string()
+        })
+    })
+}
+
+/// Require an up-to-date `If-Match` on mutations, answer unchanged project reads
+/// with 304, and tag every response with the project revision in `ETag`
+pub async fn revision_guard(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Project>, (StatusCode, Json<ErrorResponse>)> {
-    state
-        .get_project()
-        .await
-        .map(Json)
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mutation = !matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let path = request.uri().path();
+    let exempt = REVISION_EXEMPT_PATHS.contains(&path)
+        || REVISION_EXEMPT_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+        || only_reads(path);
+    let mut condition = None;
+    if mutation && !exempt {
+        let if_match = request
+            .headers()
+            .get(header::IF_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        if let Err((status, error)) = check_if_match(if_match.as_deref(), state.revision()) {
+            return (status, Json(ErrorResponse { error })).into_response();
+        }
+        // Checked again by the handler's first update, under the project lock, in case
+        // another edit landed in between
+        let if_match = if_match.unwrap_or_default();
+        let accepted = if_match.clone();
+        let accepts = move |revision| etag_matches(Some(&accepted), revision);
+        condition = Some((Arc::new(RevisionCondition::new(accepts)), if_match));
+    }
+
+    // Reads are tagged with the revision they started from, so an edit made while
+    // the response is built can't be hidden behind a later 304
+    let read_revision = state.revision();
+    if !mutation && CACHEABLE_PATHS.contains(&path) {
+        let if_none_match = request
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok());
+        if etag_matches(if_none_match, read_revision) {
+            let mut response = StatusCode::NOT_MODIFIED.into_response();
+            if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", read_revision)) {
+                response.headers_mut().insert(header::ETAG, etag);
+            }
+            return response;
+        }
+    }
+
+    let mut response = match &condition {
+        Some((condition, _)) => IF_MATCH.scope(condition.clone(), next.run(request)).await,
+        None => next.run(request).await,
+    };
+    if let Some((condition, if_match)) = &condition {
+        if let Some(revision) = condition.conflict() {
+            if let Err((status, error)) = check_if_match(Some(if_match), revision) {
+                response = (status, Json(ErrorResponse { error })).into_response();
+            }
+        }
+    }
+    let revision = if mutation { state.revision() } else { read_revision };
+    if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", revision)) {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+    response
+}
+
+/// Compare without exiting early on the first differing byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Require `Authorization: Bearer <token>` when an access token is configured
+pub async fn require_token(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let expected = state.access_token.read().await.clone();
+    if let Some(expected) = expected {
+        let given = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if !given.is_some_and(|given| tokens_match(given, &expected)) {
+            return (
+                StatusCode::UNAUTHORIZED,
                 Json(ErrorResponse {
-                    error: "No project loaded".to_string(),
+                    error: "Missing or invalid access token".to_string(),
                 }),
             )
-        })
+                .into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Largest body accepted for a request (paths include the `/api` prefix)
+fn body_limit(method: &Method, path: &str) -> usize {
+    let node_write = (*method == Method::POST && path == "/api/nodes")
+        || (*method == Method::PUT && path.starts_with("/api/nodes/"));
+    if node_write {
+        MAX_NODE_REQUEST_BYTES
+    } else {
+        MAX_REQUEST_BYTES
+    }
+}
+
+fn payload_too_large(limit: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(ErrorResponse {
+            error: format!(
+                "Request body is larger than the {} MB limit",
+                limit / (1024 * 1024)
+            ),
+        }),
+    )
+        .into_response()
+}
+
+/// Cap request bodies, refusing oversized ones with a JSON 413. A declared
+/// `Content-Length` is checked up front; chunked bodies are counted as they are
+/// read, so they're never buffered past the limit.
+pub async fn body_limit_guard(request: Request, next: Next) -> Response {
+    let limit = body_limit(request.method(), request.uri().path());
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared.is_some_and(|length| length > limit) {
+        return payload_too_large(limit);
+    }
+
+    let request = request.map(|body| Body::new(http_body_util::Limited::new(body, limit)));
+    let response = next.run(request).await;
+    // Extractors report a body cut off by the limit as a plain-text 413
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return payload_too_large(limit);
+    }
+    response
+}
+
+/// Refuse anything but reads while the API is read-only
+pub async fn read_only_guard(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let safe = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS)
+        || only_reads(request.uri().path());
+    if state.read_only() && !safe {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "The API is read-only; make changes in the Needlepoint app".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Get the project. `fields`, `limit` and `cursor` apply to its nodes
+/// (see `list_nodes`); edges and the manifest are always included.
+async fn get_project(
+    State(state): State<Arc<AppState>>,
+    Query(page): Query<PageQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let (nodes, next_cursor) = page_nodes(&page, project.nodes.iter().collect())?;
+    let mut body = serde_json::to_value(&project).unwrap_or_default();
+    body["nodes"] = serde_json::Value::Array(nodes);
+
+    Ok(paged_response(body, next_cursor))
 }
 
 async fn new_project(
@@ -165,15 +826,25 @@ async fn new_project(
     }
 
     // Create a new project
-    let mut manifest = ProjectManifest::default();
-    manifest.name = req.name;
+    let manifest = ProjectManifest {
+        name: req.name,
+        ..Default::default()
+    };
 
-    let project = Project {
+    let mut project = Project {
         manifest,
         nodes: Vec::new(),
         edges: Vec::new(),
         project_path: path.to_string_lossy().to_string(),
     };
+    if let Some(template) = &req.template {
+        apply_template(&mut project, template).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    }
 
     // Save the project to disk
     save_project_to_file(&project).map_err(|e| {
@@ -185,10 +856,18 @@ async fn new_project(
         )
     })?;
 
+    auto_purge_trash(&project);
+    recent::record_opened(&project);
     state.set_project(Some(project.clone())).await;
+    state.mark_saved();
     Ok(Json(project))
 }
 
+/// The built-in project templates `POST /project/new` can start from
+async fn list_templates() -> Json<&'static [ProjectTemplate]> {
+    Json(TEMPLATES)
+}
+
 async fn load_project(
     State(state): State<Arc<AppState>>,
     Json(req): Json<LoadProjectRequest>,
@@ -203,173 +882,176 @@ async fn load_project(
         )
     })?;
 
+    auto_purge_trash(&project);
+    recent::record_opened(&project);
     state.set_project(Some(project.clone())).await;
+    // Reloading the project that was already loaded drops its unsaved changes
+    state.mark_saved();
     Ok(Json(project))
 }
 
-async fn save_project(
-    State(state): State<Arc<AppState>>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
-    let project = state.get_project().await.ok_or_else(|| {
+/// Recently opened projects, most recent first
+async fn get_recent_projects() -> Result<Json<RecentProjects>, (StatusCode, Json<ErrorResponse>)>
+{
+    recent::load_recent().map(Json).map_err(|e| {
         (
-            StatusCode::NOT_FOUND,
+            StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: "No project loaded".to_string(),
+                error: e.to_string(),
             }),
         )
-    })?;
+    })
+}
 
-    save_project_to_file(&project).map_err(|e| {
+/// Turn reopening the last project on startup on or off
+async fn set_auto_load_project(
+    Json(req): Json<AutoLoadRequest>,
+) -> Result<Json<RecentProjects>, (StatusCode, Json<ErrorResponse>)> {
+    recent::set_auto_load(req.enabled).map(Json).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: e.to_string(),
+                error: format!("Failed to save recent projects: {}", e),
             }),
         )
-    })?;
-
-    Ok(Json(serde_json::json!({ "saved": true })))
+    })
 }
 
-async fn list_nodes(
+async fn save_project(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<CodeNode>>, (StatusCode, Json<ErrorResponse>)> {
-    let project = state.get_project().await.ok_or_else(|| {
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let saved = state.save_project().await.map_err(|e| {
         (
-            StatusCode::NOT_FOUND,
+            StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: "No project loaded".to_string(),
+                error: e.to_string(),
             }),
         )
     })?;
+    if !saved {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        ));
+    }
 
-    Ok(Json(project.nodes))
+    Ok(Json(serde_json::json!({ "saved": true })))
 }
 
-async fn get_node(
+/// Unload the project. Unsaved changes must be saved or discarded explicitly.
+async fn close_project(
     State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
-    let project = state.get_project().await.ok_or_else(|| {
-        (
+    body: Option<Json<CloseProjectRequest>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    if state.get_project().await.is_none() {
+        return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
                 error: "No project loaded".to_string(),
             }),
-        )
-    })?;
-
-    project
-        .find_node(&id)
-        .cloned()
-        .map(Json)
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Node '{}' not found", id),
-                }),
-            )
-        })
-}
-
-async fn create_node(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<CreateNodeRequest>,
-) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
-    let language = req.language.unwrap_or_default();
-    let node = CodeNode::new(req.name, req.file_path, language);
-    let node_clone = node.clone();
+        ));
+    }
 
-    state
-        .update_project(|p| {
-            p.nodes.push(node);
-        })
-        .await
-        .ok_or_else(|| {
+    if req.save {
+        state.save_project().await.map_err(|e| {
             (
-                StatusCode::NOT_FOUND,
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: "No project loaded".to_string(),
+                    error: format!("Failed to save project: {}", e),
                 }),
             )
         })?;
+    } else if !req.discard && state.has_unsaved_changes().await {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "The project has unsaved changes; save or discard them to close it"
+                    .to_string(),
+            }),
+        ));
+    }
 
-    Ok(Json(node_clone))
+    state.set_project(None).await;
+    Ok(Json(serde_json::json!({ "closed": true, "saved": req.save })))
 }
 
-async fn update_node(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-    Json(req): Json<UpdateNodeRequest>,
-) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
-    let mut updated_node = None;
+#[derive(Deserialize, Default)]
+struct WriteFilesRequest {
+    /// Overwrite files even if they were edited by hand since the last write
+    /// (defaults to the project settings' `forceWrite`)
+    #[serde(default)]
+    force: Option<bool>,
+    /// Only write the files of nodes carrying this tag
+    #[serde(default)]
+    tag: Option<String>,
+}
 
-    state
-        .update_project(|p| {
-            if let Some(node) = p.find_node_mut(&id) {
-                // Apply updates from the JSON
-                if let Some(name) = req.updates.get("name").and_then(|v| v.as_str()) {
-                    node.name = name.to_string();
-                }
-                if let Some(file_path) = req.updates.get("filePath").and_then(|v| v.as_str()) {
-                    node.file_path = file_path.to_string();
-                }
-                if let Some(description) = req.updates.get("description").and_then(|v| v.as_str()) {
-                    node.description = description.to_string();
-                }
-                if let Some(purpose) = req.updates.get("purpose").and_then(|v| v.as_str()) {
-                    node.purpose = purpose.to_string();
-                }
-                if let Some(code) = req.updates.get("generatedCode").and_then(|v| v.as_str()) {
-                    node.generated_code = Some(code.to_string());
-                }
-                updated_node = Some(node.clone());
-            }
-        })
-        .await;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WrittenFile {
+    node_name: String,
+    file_path: String,
+}
 
-    updated_node.map(Json).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Node '{}' not found", id),
-            }),
-        )
-    })
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MergedFile {
+    node_name: String,
+    file_path: String,
+    /// Conflict markers were written and the node flagged as a warning
+    conflicts: bool,
 }
 
-async fn delete_node(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
-    let mut found = false;
+#[derive(Deserialize, Default)]
+struct ReconcileRequest {
+    /// Create nodes for untracked files in a supported language
+    #[serde(default)]
+    create_nodes: bool,
+    /// Point nodes at the files they were renamed to
+    #[serde(default)]
+    apply_renames: bool,
+}
 
-    state
-        .update_project(|p| {
-            let before = p.nodes.len();
-            p.nodes.retain(|n| n.id != id);
-            // Also remove edges connected to this node
-            p.edges.retain(|e| e.source != id && e.target != id);
-            found = p.nodes.len() < before;
-        })
-        .await;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReconcileResponse {
+    #[serde(flatten)]
+    report: ReconcileReport,
+    /// Nodes created for untracked files
+    created: Vec<NodeFile>,
+    renames_applied: bool,
+}
 
-    if found {
-        Ok(Json(serde_json::json!({ "deleted": true })))
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Node '{}' not found", id),
-            }),
-        ))
-    }
+#[derive(Serialize)]
+struct ValidateResponse {
+    valid: bool,
+    errors: Vec<String>,
+    warnings: Vec<String>,
 }
 
-async fn list_edges(
+#[derive(Serialize)]
+struct WriteFilesResponse {
+    written: Vec<WrittenFile>,
+    /// Files with manual edits that the new generation was merged into
+    merged: Vec<MergedFile>,
+    /// Names of nodes without generated code
+    skipped: Vec<String>,
+    /// Files left untouched because they were edited by hand and can't be merged
+    conflicts: Vec<WrittenFile>,
+}
+
+/// Write every node's generated code to disk, with file headers and footers applied.
+/// Files edited by hand since the last write get the new generation merged in
+/// (nodes whose merge conflicts are flagged as warnings); without a previous
+/// generation to merge against they are reported as conflicts. `force` overwrites.
+async fn write_project_files(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<CodeEdge>>, (StatusCode, Json<ErrorResponse>)> {
+    body: Option<Json<WriteFilesRequest>>,
+) -> Result<Json<WriteFilesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
     let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
@@ -378,65 +1060,105 @@ async fn list_edges(
             }),
         )
     })?;
+    let force = match req.force {
+        Some(force) => force,
+        None => state.get_settings().await.force_write.unwrap_or(false),
+    };
+    // Files go to the paths with placeholders substituted
+    let project = resolve_project(&project).into_owned();
 
-    Ok(Json(project.edges))
-}
+    // Files that would overwrite each other can't be written at all
+    let conflicts = file_path_conflicts(&project);
+    if !conflicts.is_empty() {
+        let messages: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: messages.join("; "),
+            }),
+        ));
+    }
 
-async fn create_edge(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<CreateEdgeRequest>,
-) -> Result<Json<CodeEdge>, (StatusCode, Json<ErrorResponse>)> {
-    let edge = CodeEdge::new(req.source, req.target, req.label);
-    let edge_clone = edge.clone();
+    let mut response = WriteFilesResponse {
+        written: Vec::new(),
+        merged: Vec::new(),
+        skipped: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    let mut hashes = Vec::new();
+    let mut conflicted = Vec::new();
 
-    state
-        .update_project(|p| {
-            p.edges.push(edge);
-        })
-        .await
-        .ok_or_else(|| {
+    let nodes = project.nodes.iter().filter(|n| {
+        n.subproject.is_none() && req.tag.as_deref().map_or(true, |tag| n.has_tag(tag))
+    });
+    for node in nodes {
+        let code = match node.generated_code.as_deref() {
+            Some(code) if !code.is_empty() => code,
+            _ => {
+                response.skipped.push(node.name.clone());
+                continue;
+            }
+        };
+
+        let file = WrittenFile {
+            node_name: node.name.clone(),
+            file_path: node.file_path.clone(),
+        };
+        let outcome = write_generated_file(&project, node, code, force).map_err(|e| {
             (
-                StatusCode::NOT_FOUND,
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: "No project loaded".to_string(),
+                    error: format!("Failed to write {}: {}", node.file_path, e),
                 }),
             )
         })?;
 
-    Ok(Json(edge_clone))
-}
-
-async fn delete_edge(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
-    let mut found = false;
+        match outcome {
+            NodeFileWrite::Written(hash) => {
+                hashes.push((node.id.clone(), hash));
+                response.written.push(file);
+            }
+            NodeFileWrite::Merged { hash, conflicts } => {
+                hashes.push((node.id.clone(), hash));
+                if conflicts {
+                    conflicted.push(node.id.clone());
+                }
+                response.merged.push(MergedFile {
+                    node_name: file.node_name,
+                    file_path: file.file_path,
+                    conflicts,
+                });
+            }
+            NodeFileWrite::ManuallyEdited => response.conflicts.push(file),
+        }
+    }
 
     state
         .update_project(|p| {
-            let before = p.edges.len();
-            p.edges.retain(|e| e.id != id);
-            found = p.edges.len() < before;
+            for (id, hash) in hashes {
+                if let Some(node) = p.find_node_mut(&id) {
+                    node.written_hash = Some(hash);
+                }
+            }
+            for id in conflicted {
+                if let Some(node) = p.find_node_mut(&id) {
+                    node.status = NodeStatus::Warning;
+                    node.error_message = Some(format!(
+                        "Merge conflicts with manual edits in {}",
+                        node.file_path
+                    ));
+                }
+            }
         })
         .await;
 
-    if found {
-        Ok(Json(serde_json::json!({ "deleted": true })))
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Edge '{}' not found", id),
-            }),
-        ))
-    }
+    Ok(Json(response))
 }
 
-async fn generate_node(
+/// List the project directory, annotated with the nodes that generate each file
+async fn list_project_files(
     State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-    Json(req): Json<GenerateRequest>,
-) -> Result<Json<GenerateResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<ProjectFiles>, (StatusCode, Json<ErrorResponse>)> {
     let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
@@ -446,87 +1168,67 @@ async fn generate_node(
         )
     })?;
 
-    let node = project.find_node(&id).ok_or_else(|| {
+    build_file_tree(&project).map(Json).map_err(|e| {
         (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Node '{}' not found", id),
-            }),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
         )
-    })?;
+    })
+}
 
-    // Build prompt
-    let prompt = ContextBuilder::build_prompt(&project, &id).ok_or_else(|| {
+/// Check the graph for structural errors and spec warnings
+async fn validate_project_graph(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ValidateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::NOT_FOUND,
             Json(ErrorResponse {
-                error: "Failed to build prompt".to_string(),
+                error: "No project loaded".to_string(),
             }),
         )
     })?;
 
-    let system_prompt = ContextBuilder::build_system_prompt(node);
-
-    // Get API key
-    let api_keys = state.get_api_keys().await;
-    let api_key = req.api_key.or_else(|| match node.llm_config.provider {
-        crate::graph::model::LLMProvider::Anthropic => api_keys.anthropic.clone(),
-        crate::graph::model::LLMProvider::OpenAI => api_keys.openai.clone(),
-        crate::graph::model::LLMProvider::Ollama => None,
-    });
-
-    // Create provider and generate
-    let provider = create_provider(&node.llm_config, api_key);
+    let result = validate_project(&project);
+    Ok(Json(ValidateResponse {
+        valid: result.is_valid(),
+        errors: result.errors.iter().map(|e| e.to_string()).collect(),
+        warnings: result.warnings.iter().map(|w| w.to_string()).collect(),
+    }))
+}
 
-    if !provider.is_configured() {
-        return Err((
-            StatusCode::BAD_REQUEST,
+/// Run the manifest's checks in the project directory, one after another. The
+/// checks are read from the project file on disk rather than the loaded project, so
+/// only commands the user wrote there can run.
+async fn run_project_checks(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CheckResult>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
             Json(ErrorResponse {
-                error: format!(
-                    "{} is not configured. Set API key via POST /api/api-keys or in request body.",
-                    provider.name()
-                ),
+                error: "No project loaded".to_string(),
             }),
-        ));
-    }
-
-    let request = GenerationRequest {
-        prompt,
-        system_prompt: Some(system_prompt),
-        max_tokens: Some(4096),
-        temperature: Some(0.7),
-    };
+        )
+    })?;
 
-    let response = provider.generate(request).await.map_err(|e| {
+    let project_dir = std::path::Path::new(&project.project_path);
+    let project_file = project_dir.join(PROJECT_FILE_NAME);
+    let saved = crate::graph::serialization::read_project_file(&project_file).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: e.to_string(),
+                error: format!("{:#}", e),
             }),
         )
     })?;
-
-    let code = strip_code_blocks(&response.content);
-
-    // Update node with generated code
-    state
-        .update_project(|p| {
-            if let Some(node) = p.find_node_mut(&id) {
-                node.generated_code = Some(code.clone());
-                node.status = crate::graph::model::NodeStatus::Complete;
-            }
-        })
-        .await;
-
-    Ok(Json(GenerateResponse {
-        code,
-        node_id: id,
-    }))
+    Ok(Json(run_checks(project_dir, &saved.manifest.checks).await))
 }
 
-async fn generate_all(
+/// Common spec failures per node, with suggestions to fix them
+async fn lint_project_specs(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Project>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<SpecHint>>, (StatusCode, Json<ErrorResponse>)> {
     let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
@@ -536,66 +1238,15 @@ async fn generate_all(
         )
     })?;
 
-    let api_keys = state.get_api_keys().await;
-
-    // Create executor without AppHandle (no Tauri events in HTTP API)
-    // We'll need to run generation manually for each node in order
-    let plan = ExecutionPlan::from_project(&project);
-    let mut result_project = project;
-
-    for wave in &plan.waves {
-        for node_id in &wave.node_ids {
-            if let Some(node) = result_project.find_node(node_id) {
-                let prompt = match ContextBuilder::build_prompt(&result_project, node_id) {
-                    Some(p) => p,
-                    None => continue,
-                };
-
-                let system_prompt = ContextBuilder::build_system_prompt(node);
-
-                let api_key = match node.llm_config.provider {
-                    crate::graph::model::LLMProvider::Anthropic => api_keys.anthropic.clone(),
-                    crate::graph::model::LLMProvider::OpenAI => api_keys.openai.clone(),
-                    crate::graph::model::LLMProvider::Ollama => None,
-                };
-
-                let provider = create_provider(&node.llm_config, api_key);
-
-                if provider.is_configured() {
-                    let request = GenerationRequest {
-                        prompt,
-                        system_prompt: Some(system_prompt),
-                        max_tokens: Some(4096),
-                        temperature: Some(0.7),
-                    };
-
-                    match provider.generate(request).await {
-                        Ok(response) => {
-                            let code = strip_code_blocks(&response.content);
-                            if let Some(node) = result_project.find_node_mut(node_id) {
-                                node.generated_code = Some(code);
-                                node.status = crate::graph::model::NodeStatus::Complete;
-                            }
-                        }
-                        Err(e) => {
-                            if let Some(node) = result_project.find_node_mut(node_id) {
-                                node.status = crate::graph::model::NodeStatus::Error;
-                                node.error_message = Some(e.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    state.set_project(Some(result_project.clone())).await;
-    Ok(Json(result_project))
+    Ok(Json(lint_specs(&project)))
 }
 
-async fn get_execution_plan(
+/// Edges the project seems to be missing, inferred from specs and generated code, to
+/// accept by creating them through `POST /edges`
+async fn suggest_project_edges(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<ExecutionPlan>, (StatusCode, Json<ErrorResponse>)> {
+    Query(query): Query<SuggestEdgesQuery>,
+) -> Result<Json<Vec<EdgeSuggestion>>, (StatusCode, Json<ErrorResponse>)> {
     let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
@@ -605,13 +1256,18 @@ async fn get_execution_plan(
         )
     })?;
 
-    Ok(Json(ExecutionPlan::from_project(&project)))
+    let suggestions = suggest_edges(&project)
+        .into_iter()
+        .filter(|s| query.node.as_ref().map_or(true, |node| &s.target == node))
+        .filter(|s| s.confidence >= query.min_confidence)
+        .collect();
+    Ok(Json(suggestions))
 }
 
-async fn preview_prompt(
+/// Generate package.json / Cargo.toml / requirements.txt from external dependency nodes
+async fn get_package_manifests(
     State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<PackageManifest>>, (StatusCode, Json<ErrorResponse>)> {
     let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
@@ -621,29 +1277,2350 @@ async fn preview_prompt(
         )
     })?;
 
-    let prompt = ContextBuilder::build_prompt(&project, &id).ok_or_else(|| {
+    Ok(Json(generate_package_manifests(&project)))
+}
+
+/// Derive build files (package.json, tsconfig.json, Cargo.toml, pyproject.toml)
+/// from the graph and write them to the project root
+async fn scaffold_project(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ScaffoldRequest>>,
+) -> Result<Json<ScaffoldResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
         (
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
-                error: format!("Node '{}' not found", id),
+                error: "No project loaded".to_string(),
             }),
         )
     })?;
+    let req = body.map(|Json(b)| b).unwrap_or_default();
 
-    Ok(Json(serde_json::json!({ "prompt": prompt })))
-}
+    let files = if req.llm {
+        let provider = &project.manifest.default_llm.provider;
+        let api_key = state.get_api_keys().await.get_for_provider(provider);
+        run_scaffold_pass(&project, api_key).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?
+    } else {
+        scaffold_files(&project)
+    };
 
-async fn set_api_keys(
+    let write = if req.dry_run {
+        ScaffoldWrite::default()
+    } else {
+        write_scaffold_files(&project.project_path, &files, req.overwrite).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?
+    };
+
+    Ok(Json(ScaffoldResponse { files, write }))
+}
+
+/// Position every node in dependency layers, returning the updated project
+async fn layout_project(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Project>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .update_project(auto_layout)
+        .await
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    /// Only count the nodes carrying this tag and the edges between them
+    tag: Option<String>,
+}
+
+/// Node, edge, depth and size statistics for the graph
+async fn get_project_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<ProjectStats>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(match query.tag.as_deref() {
+        Some(tag) => tag_stats(&project, tag),
+        None => project_stats(&project),
+    }))
+}
+
+async fn get_manifest(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ProjectManifest>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(project.manifest))
+}
+
+/// Change the manifest with a JSON merge patch (see `ProjectManifest::apply_patch`).
+/// Like other edits, it's written to `needlepoint.yaml` on the next save. Changing
+/// `subprojects` reloads the nodes included from them.
+async fn update_manifest(
+    State(state): State<Arc<AppState>>,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<ProjectManifest>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = Err("No project loaded".to_string());
+    state
+        .update_project(|p| {
+            let mut updated = p.clone();
+            result = updated.manifest.apply_patch(&patch).and_then(|()| {
+                updated.apply_default_llm();
+                if patch.get("subprojects").is_some() {
+                    include_subprojects(&mut updated).map_err(|e| format!("{:#}", e))?;
+                }
+                Ok(updated.manifest.clone())
+            });
+            if result.is_ok() {
+                *p = updated;
+            }
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    result
+        .map(Json)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))
+}
+
+/// The loaded project's local settings (`.needlepoint/settings.yaml`)
+async fn get_project_settings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ProjectSettings>, (StatusCode, Json<ErrorResponse>)> {
+    if state.get_project().await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        ));
+    }
+    Ok(Json(state.get_settings().await))
+}
+
+/// Replace the loaded project's local settings and save them
+async fn set_project_settings(
+    State(state): State<Arc<AppState>>,
+    Json(settings): Json<ProjectSettings>,
+) -> Result<Json<ProjectSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    settings
+        .validate()
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
+    save_settings(&project.project_path, &settings).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save project settings: {}", e),
+            }),
+        )
+    })?;
+    state.set_settings(settings.clone()).await;
+    Ok(Json(settings))
+}
+
+/// Re-check every node's generated code against its constraints, e.g. after
+/// constraints were edited, and record the violations on the nodes
+async fn check_project_constraints(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<NodeViolations>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state
+        .update_project(|p| {
+            let manifest = p.manifest.clone();
+            for node in &mut p.nodes {
+                verify_generated_code(&manifest, node);
+            }
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(
+        project
+            .nodes
+            .into_iter()
+            .filter(|n| !n.constraint_violations.is_empty() || !n.missing_exports.is_empty())
+            .map(|n| NodeViolations {
+                node_id: n.id,
+                node_name: n.name,
+                violations: n.constraint_violations,
+                missing_exports: n.missing_exports,
+            })
+            .collect(),
+    ))
+}
+
+/// Check generated files against the generated dependencies they import from and
+/// record the mismatches on the nodes as warnings
+async fn check_project_consistency(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ConsistencyCheckRequest>>,
+) -> Result<Json<Vec<NodeConsistency>>, (StatusCode, Json<ErrorResponse>)> {
+    let mut checked = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+
+    let provider = &checked.manifest.default_llm.provider;
+    let api_key = state.get_api_keys().await.get_for_provider(provider);
+    check_consistency(&mut checked, req.llm, api_key).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    // The LLM review may take a while; only the check's results are copied back, to
+    // nodes whose code hasn't changed since
+    let project = state
+        .update_project(|p| {
+            for node in &mut p.nodes {
+                let result = checked.find_node(&node.id);
+                if let Some(result) = result.filter(|r| r.generated_code == node.generated_code) {
+                    node.consistency_issues = result.consistency_issues.clone();
+                    node.status = result.status.clone();
+                    node.error_message = result.error_message.clone();
+                }
+            }
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(
+        project
+            .nodes
+            .into_iter()
+            .filter(|n| !n.consistency_issues.is_empty())
+            .map(|n| NodeConsistency {
+                node_id: n.id,
+                node_name: n.name,
+                issues: n.consistency_issues,
+            })
+            .collect(),
+    ))
+}
+
+/// Find nodes by text or, in semantic mode, by meaning ("where do we handle password
+/// reset"). Semantic search embeds new and changed nodes into the index first.
+async fn search_nodes(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SearchRequest>,
+) -> Result<Json<Vec<SearchHit>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let limit = req.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let hits = match req.mode {
+        SearchMode::Text => text_search(&project, &req.query, limit),
+        SearchMode::Semantic => {
+            let provider = EmbeddingConfig::for_manifest(&project.manifest).provider;
+            let api_key = state.get_api_keys().await.get_for_provider(&provider);
+            semantic_search(&project, &req.query, limit, api_key.as_deref())
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse { error: e }),
+                    )
+                })?
+        }
+    };
+
+    Ok(Json(hits))
+}
+
+/// Changes recorded in the project's event log after revision `since`
+async fn get_project_changes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ChangesQuery>,
+) -> Result<Json<ChangesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let revision = state.revision();
+
+    let changes = load_changes(&project.project_path, query.since).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    // Leave changes made while reading for the next poll
+    let changes = changes.into_iter().filter(|c| c.revision <= revision).collect();
+
+    Ok(Json(ChangesResponse { revision, changes }))
+}
+
+/// Stream changes as server-sent `change` events: first those recorded after
+/// `since`, then each new change as it happens
+async fn stream_project_changes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ChangesQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)>
+{
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    // Subscribe before reading the log so nothing falls between the two
+    let (tx, rx) = mpsc::unbounded_channel::<ChangeEvent>();
+    state.change_subscribers().push(tx);
+
+    let backlog = load_changes(&project.project_path, query.since).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    let seen = backlog.last().map_or(query.since, |e| e.revision);
+
+    let to_event = |change: ChangeEvent| {
+        let data = serde_json::to_string(&change).unwrap_or_default();
+        Ok(Event::default().event("change").id(change.revision.to_string()).data(data))
+    };
+    let live = stream::unfold(rx, |mut rx| async move {
+        let change = rx.recv().await?;
+        Some((change, rx))
+    })
+    .filter(move |change| std::future::ready(change.revision > seen))
+    .map(to_event);
+
+    let events = stream::iter(backlog).map(to_event).chain(live);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Read a file under the project directory
+async fn read_project_file_contents(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReadFileQuery>,
+) -> Result<Json<FileContents>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    read_project_file(&project.project_path, &query.path)
+        .map(Json)
+        .map_err(|e| {
+            let status = match e {
+                ReadFileError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+                ReadFileError::NotFound(_) => StatusCode::NOT_FOUND,
+                ReadFileError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+                ReadFileError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })
+}
+
+/// Cross-check nodes against the project directory: report missing files, untracked
+/// files and renames, and optionally create nodes and apply renames
+async fn reconcile_project(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ReconcileRequest>>,
+) -> Result<Json<ReconcileResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let report = scan_project(&project).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+
+    let mut new_nodes = Vec::new();
+    if req.create_nodes {
+        for file in &report.untracked {
+            match import_file(&project, file) {
+                Ok(Some(node)) => new_nodes.push(node),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Skipping {}: {}", file.file_path, e),
+            }
+        }
+    }
+
+    let created = new_nodes
+        .iter()
+        .map(|n| NodeFile {
+            node_id: n.id.clone(),
+            node_name: n.name.clone(),
+            file_path: n.file_path.clone(),
+        })
+        .collect();
+
+    if req.apply_renames || !new_nodes.is_empty() {
+        state
+            .update_project(|p| {
+                if req.apply_renames {
+                    apply_renames(p, &report.renamed);
+                }
+                p.nodes.extend(new_nodes);
+            })
+            .await;
+    }
+
+    Ok(Json(ReconcileResponse {
+        report,
+        created,
+        renames_applied: req.apply_renames,
+    }))
+}
+
+/// Find and replace across node specs (names, paths, descriptions, purposes, constraints)
+async fn replace_in_specs(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ReplaceRequest>,
+) -> Result<Json<Vec<ReplaceMatch>>, (StatusCode, Json<ErrorResponse>)> {
+    let fields = req.fields.unwrap_or_else(|| ReplaceField::ALL.to_vec());
+    let no_project = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    };
+    let mut result = Ok(Vec::new());
+
+    if req.dry_run {
+        // Preview on a copy so the project's revision and change log are left alone
+        let mut project = state.get_project().await.ok_or_else(no_project)?;
+        result =
+            replace_in_project(&mut project, &req.find, &req.replace, req.regex, &fields, true);
+    } else {
+        state
+            .update_project(|p| {
+                result = replace_in_project(p, &req.find, &req.replace, req.regex, &fields, false);
+            })
+            .await
+            .ok_or_else(no_project)?;
+    }
+
+    result.map(Json).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        )
+    })
+}
+
+/// List nodes, optionally filtered (`status`, `language`, `q`, `path_prefix`)
+/// and sorted (`sort`, `desc`). `fields` trims each node to the given fields and
+/// `limit`/`cursor` paginate; the next page's cursor is sent in `X-Next-Cursor`.
+async fn list_nodes(
+    State(state): State<Arc<AppState>>,
+    Query(filter): Query<NodeFilter>,
+    Query(page): Query<PageQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let (nodes, next_cursor) = page_nodes(&page, filter.apply(&project.nodes))?;
+    Ok(paged_response(serde_json::Value::Array(nodes), next_cursor))
+}
+
+/// One page of (possibly trimmed) nodes and the cursor for the next page
+type NodePage = (Vec<serde_json::Value>, Option<String>);
+
+/// Paginate a node listing and apply the sparse fieldset
+fn page_nodes(
+    page: &PageQuery,
+    nodes: Vec<&CodeNode>,
+) -> Result<NodePage, (StatusCode, Json<ErrorResponse>)> {
+    let (nodes, next_cursor) = page
+        .paginate(nodes)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    Ok((nodes.into_iter().map(|n| page.select(n)).collect(), next_cursor))
+}
+
+fn paged_response(body: serde_json::Value, next_cursor: Option<String>) -> Response {
+    let mut response = Json(body).into_response();
+    if let Some(cursor) = next_cursor.and_then(|c| header::HeaderValue::from_str(&c).ok()) {
+        response.headers_mut().insert(NEXT_CURSOR_HEADER, cursor);
+    }
+    response
+}
+
+async fn get_node(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    project
+        .find_node(&id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Node '{}' not found", id),
+                }),
+            )
+        })
+}
+
+async fn create_node(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateNodeRequest>,
+) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
+    let language = req.language.unwrap_or_default();
+    let mut created = None;
+
+    state
+        .update_project(|p| {
+            let mut node = p.new_node(req.name, req.file_path, language);
+            node.kind = req.kind;
+            node.package = req.package;
+            created = Some(node.clone());
+            p.nodes.push(node);
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(created.expect("node is created with the project")))
+}
+
+async fn update_node(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateNodeRequest>,
+) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = Err((StatusCode::NOT_FOUND, format!("Node '{}' not found", id)));
+
+    state
+        .update_project(|p| {
+            let Some(index) = p.nodes.iter().position(|n| n.id == id) else {
+                return;
+            };
+            let mut node = p.nodes[index].clone();
+            if let Err(error) = node.apply_patch(&req.updates) {
+                result = Err((StatusCode::BAD_REQUEST, error));
+                return;
+            }
+            let moved = node.file_path != p.nodes[index].file_path;
+            let taken_by = p.nodes.iter().find(|n| n.id != id && n.file_path == node.file_path);
+            if let Some(other) = taken_by.filter(|_| moved) {
+                result = Err((
+                    StatusCode::CONFLICT,
+                    format!("'{}' is already the file of node '{}'", node.file_path, other.name),
+                ));
+                return;
+            }
+
+            p.nodes[index] = node.clone();
+            if req.updates.get("generatedCode").is_some() {
+                p.mark_stub_dependents_stale(&id);
+            }
+            result = Ok(node);
+        })
+        .await;
+
+    result
+        .map(Json)
+        .map_err(|(status, error)| (status, Json(ErrorResponse { error })))
+}
+
+async fn clone_node(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    body: Option<Json<CloneNodeRequest>>,
+) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    let mut cloned = None;
+
+    state
+        .update_project(|p| {
+            cloned = p.clone_node(&id, req.name, req.file_path, req.copy_edges);
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    cloned.map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })
+}
+
+async fn delete_node(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<DeleteNodeQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = Err(RemoveNodeError::NotFound(id.clone()));
+
+    state
+        .update_project(|p| {
+            result = p.remove_node(&id, query.cascade);
+        })
+        .await;
+
+    match result {
+        Ok(affected) => Ok(Json(serde_json::json!({ "deleted": true, "affected": affected }))),
+        Err(e @ RemoveNodeError::NotFound(_)) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )),
+        Err(e @ RemoveNodeError::HasDependents(_)) => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )),
+    }
+}
+
+async fn list_edges(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CodeEdge>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(project.edges))
+}
+
+async fn create_edge(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateEdgeRequest>,
+) -> Result<Json<CodeEdge>, (StatusCode, Json<ErrorResponse>)> {
+    let edge = CodeEdge::new(req.source, req.target, req.label);
+    let edge_clone = edge.clone();
+
+    state
+        .update_project(|p| {
+            p.edges.push(edge);
+        })
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(edge_clone))
+}
+
+/// Change an edge's label, source or target; new endpoints are checked for cycles
+async fn update_edge(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(update): Json<EdgeUpdate>,
+) -> Result<Json<CodeEdge>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = Err(UpdateEdgeError::NotFound(id.clone()));
+    state
+        .update_project(|p| result = p.update_edge(&id, update))
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    result.map(Json).map_err(|e| {
+        let status = match e {
+            UpdateEdgeError::NotFound(_) | UpdateEdgeError::NodeNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            UpdateEdgeError::SelfLoop => StatusCode::BAD_REQUEST,
+            UpdateEdgeError::Duplicate | UpdateEdgeError::Cycle => StatusCode::CONFLICT,
+        };
+        (status, Json(ErrorResponse { error: e.to_string() }))
+    })
+}
+
+async fn delete_edge(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let mut found = false;
+
+    state
+        .update_project(|p| {
+            let before = p.edges.len();
+            p.edges.retain(|e| e.id != id);
+            found = p.edges.len() < before;
+        })
+        .await;
+
+    if found {
+        Ok(Json(serde_json::json!({ "deleted": true })))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Edge '{}' not found", id),
+            }),
+        ))
+    }
+}
+
+async fn list_snippets(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<HashMap<String, String>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(project.manifest.snippets))
+}
+
+/// Replace a node's declared exports; the node and its dependents become stale if
+/// they were generated against the old ones
+async fn set_node_exports(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<SetExportsRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    validate_exports(&req.exports)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
+    let mut result = None;
+    state
+        .update_project(|p| {
+            if p.find_node(&id).is_some() {
+                result = Some(p.set_exports(&id, req.exports.clone()));
+            }
+        })
+        .await;
+
+    let affected = result.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+    Ok(Json(serde_json::json!({ "exports": req.exports, "affected": affected })))
+}
+
+/// Add or change a context snippet; nodes generated with its old text become stale
+async fn set_snippet(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(req): Json<SetSnippetRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    if name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Snippet name cannot be empty".to_string(),
+            }),
+        ));
+    }
+
+    let mut affected = Vec::new();
+    state
+        .update_project(|p| affected = p.set_snippet(&name, req.text))
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({ "affected": affected })))
+}
+
+/// Remove a context snippet; nodes generated with it become stale
+async fn delete_snippet(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = None;
+    state.update_project(|p| result = p.remove_snippet(&name)).await;
+
+    match result {
+        Some(affected) => Ok(Json(serde_json::json!({ "deleted": true, "affected": affected }))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Snippet '{}' not found", name),
+            }),
+        )),
+    }
+}
+
+async fn list_shared_types(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<HashMap<String, SharedType>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(project.manifest.shared_types))
+}
+
+/// Add or change a shared type; nodes generated with its old definition become stale
+async fn set_shared_type(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(shared_type): Json<SharedType>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    if name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Shared type name cannot be empty".to_string(),
+            }),
+        ));
+    }
+
+    let mut affected = Vec::new();
+    state
+        .update_project(|p| affected = p.set_shared_type(&name, shared_type))
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No project loaded".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({ "affected": affected })))
+}
+
+/// Remove a shared type; nodes generated with it become stale
+async fn delete_shared_type(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let mut result = None;
+    state.update_project(|p| result = p.remove_shared_type(&name)).await;
+
+    match result {
+        Some(affected) => Ok(Json(serde_json::json!({ "deleted": true, "affected": affected }))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Shared type '{}' not found", name),
+            }),
+        )),
+    }
+}
+
+async fn generate_node(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<GenerateQuery>,
+    Json(req): Json<GenerateRequest>,
+) -> Result<Json<GenerateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let _turn = wait_generation_turn(&state, GenerationKind::Node, Some(id.clone())).await?;
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let mut plan = ExecutionPlan::for_node(&project, &id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    let node = project.find_node(&id).expect("planned node exists");
+    if query.skip_completed && !query.force && node.status == NodeStatus::Complete {
+        if let Some(code) = &node.generated_code {
+            return Ok(Json(GenerateResponse {
+                code: code.clone(),
+                node_id: id,
+                generated_dependencies: Vec::new(),
+            }));
+        }
+    }
+
+    if query.force {
+        // Every dependency, in dependency order, regardless of status
+        let options = ExecutionOptions {
+            skip_completed: false,
+            force: true,
+            ..Default::default()
+        };
+        plan = ExecutionPlan::from_project(&project);
+        plan.retain_nodes(&options.select(&project, HashSet::from([id.clone()])));
+    }
+
+    let missing_dependencies: Vec<String> = plan
+        .ordered_node_ids()
+        .into_iter()
+        .filter(|node_id| *node_id != id)
+        .collect();
+
+    let mut generated_dependencies = Vec::new();
+    if query.with_deps || query.force {
+        // Dependencies use the stored keys; the request key is meant for this node's provider
+        for dep_id in missing_dependencies {
+            generate_single_node(&state, &dep_id, None).await?;
+            generated_dependencies.push(dep_id);
+        }
+    } else if !missing_dependencies.is_empty() {
+        tracing::warn!(
+            "Generating node {} with {} ungenerated dependencies; use ?with_deps=true to generate them first",
+            id,
+            missing_dependencies.len(),
+        );
+    }
+
+    let (code, _) = generate_single_node(&state, &id, req.api_key).await?;
+
+    Ok(Json(GenerateResponse {
+        code,
+        node_id: id,
+        generated_dependencies,
+    }))
+}
+
+/// Generate one node against the current project state and store the result.
+/// Returns the generated code and its run report entry.
+pub(crate) async fn generate_single_node(
+    state: &Arc<AppState>,
+    id: &str,
+    api_key: Option<String>,
+) -> Result<(String, NodeRunReport), (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let node = project.find_node(id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    if node.disabled || node.pinned {
+        let reason = if node.pinned { "pinned" } else { "disabled" };
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Node '{}' is {}", node.name, reason),
+            }),
+        ));
+    }
+    let budget = state.get_settings().await.project_budget();
+    check_project_budget(&project.project_path, budget)
+        .map_err(|error| (StatusCode::CONFLICT, Json(ErrorResponse { error })))?;
+
+    let api_keys = state.get_api_keys().await;
+    let embedding_provider = EmbeddingConfig::for_manifest(&project.manifest).provider;
+    let embedding_key = api_keys.get_for_provider(&embedding_provider);
+    refresh_for_related_context(&project, embedding_key.as_deref()).await;
+
+    // Build prompt
+    let (prompt, cache_breakpoints) = ContextBuilder::build_prompt_with_breakpoints(&project, id)
+        .ok_or_else(|| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to build prompt".to_string(),
+                }),
+            )
+        })?;
+
+    let system_prompt = ContextBuilder::build_system_prompt(&project.manifest, node);
+    let stubbed_dependencies = ContextBuilder::stubbed_dependencies(&project, id);
+    let llm_config = node.llm_config.resolve(&project.manifest.default_llm);
+
+    // Get API key
+    let api_key = api_key.or_else(|| api_keys.get_for_provider(&llm_config.provider));
+
+    // Create provider and generate
+    let provider = create_provider(&llm_config, api_key.clone(), &project);
+
+    if !provider.is_configured() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "{} is not configured. Set API key via POST /api/api-keys or in request body.",
+                    provider.name()
+                ),
+            }),
+        ));
+    }
+
+    let request = GenerationRequest {
+        prompt,
+        system_prompt: Some(system_prompt),
+        max_tokens: Some(4096),
+        temperature: Some(0.7),
+        cache_breakpoints,
+        output_schema: None,
+        seed: llm_config.effective_seed(),
+        timeout: Some(request_timeout(&project.manifest, &llm_config)),
+    };
+    let transcript = PendingTranscript::begin(&project, node, &llm_config, &request);
+    let fixup = FixupOptions::from_manifest(&project.manifest)
+        .map(|options| (options, request.clone()));
+    // Fix-ups and post-processing see the spec as the model does
+    let resolved = resolve_node(&project.manifest, node);
+    let post_processor = PostProcessor::for_node(&project, &resolved);
+
+    let mut guard = GeneratingGuard {
+        state: state.clone(),
+        node_id: id.to_string(),
+        previous: Some(node.status.clone()),
+    };
+    // Nothing is spent on a generation whose result couldn't be stored
+    if !state.check_if_match().await {
+        guard.previous = None;
+        return Err(update_refused());
+    }
+    set_node_status(state, id, NodeStatus::Generating, None).await;
+    let started = std::time::Instant::now();
+    let (result, retries) = generate_with_retries(provider.as_ref(), request).await;
+    if let Some(transcript) = transcript {
+        transcript.finish(&result, started.elapsed().as_millis() as u64, api_key.as_deref());
+    }
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!("Generation failed for node {}: {}", id, e);
+            set_node_status(state, id, NodeStatus::Error, Some(e.to_string())).await;
+            guard.previous = None;
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            ));
+        }
+    };
+
+    let extracted = extract_code(&response.content, &node.language, node.kind);
+    let mut code = extracted.code;
+    let mut fixup_spend = None;
+    if let Some((options, request)) = &fixup {
+        let fixed = fix_generated_code(provider.as_ref(), &resolved, request, code, *options).await;
+        code = fixed.0;
+        fixup_spend = fixed.1.map(|response| Spend::of_response(&llm_config.provider, &response));
+    }
+    let mut post_processing = Vec::new();
+    if let Some(processor) = &post_processor {
+        (code, post_processing) = processor.run(code).await;
+    }
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let run = NodeRunReport {
+        node_id: id.to_string(),
+        node_name: node.name.clone(),
+        model: response.model.clone(),
+        success: true,
+        duration_ms,
+        input_tokens: response.input_tokens,
+        output_tokens: response.output_tokens,
+        tokens_used: response.tokens_used,
+        cost_estimate: estimate_cost(
+            &llm_config.provider,
+            &response.model,
+            response.input_tokens.unwrap_or(0),
+            response.output_tokens.unwrap_or(0),
+        ),
+        fixup: fixup_spend,
+        retries,
+        error: None,
+    };
+    let record = GenerationRecord {
+        model: response.model.clone(),
+        tokens_in: response.input_tokens,
+        tokens_out: response.output_tokens,
+        duration_ms,
+        timestamp: chrono::Utc::now(),
+        seed: llm_config.effective_seed(),
+        note: extracted.note,
+        post_processing,
+    };
+
+    // Update node with generated code
+    state
+        .update_project(|p| {
+            let manifest = p.manifest.clone();
+            if let Some(node) = p.find_node_mut(id) {
+                node.generated_code = Some(code.clone());
+                node.status = crate::graph::model::NodeStatus::Complete;
+                node.generated_against_stubs = stubbed_dependencies;
+                node.consistency_issues.clear();
+                node.last_generation = Some(record);
+                verify_generated_code(&manifest, node);
+            }
+            p.mark_stub_dependents_stale(id);
+        })
+        .await
+        .ok_or_else(update_refused)?;
+    guard.previous = None;
+
+    Ok((code, run))
+}
+
+/// Puts a node back to the status it had if its generation is dropped before it
+/// finishes, e.g. when the client disconnects or a pilot runs out of time
+struct GeneratingGuard {
+    state: Arc<AppState>,
+    node_id: String,
+    /// Cleared once the generation has stored its outcome
+    previous: Option<NodeStatus>,
+}
+
+impl Drop for GeneratingGuard {
+    fn drop(&mut self) {
+        let Some(previous) = self.previous.take() else {
+            return;
+        };
+        let state = self.state.clone();
+        let node_id = std::mem::take(&mut self.node_id);
+        tokio::spawn(async move {
+            state
+                .update_project(|p| {
+                    if let Some(node) = p.find_node_mut(&node_id) {
+                        if node.status == NodeStatus::Generating {
+                            node.status = previous;
+                        }
+                    }
+                })
+                .await;
+        });
+    }
+}
+
+/// Error for an `update_project` that changed nothing: the request's `If-Match` failed,
+/// or no project is loaded
+fn update_refused() -> (StatusCode, Json<ErrorResponse>) {
+    if IF_MATCH.try_with(|c| c.conflict().is_some()).unwrap_or(false) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Project was changed by another client; reload and retry".to_string(),
+            }),
+        );
+    }
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: "No project loaded".to_string(),
+        }),
+    )
+}
+
+/// Set a node's status on the shared project, with the error message for errors
+async fn set_node_status(state: &AppState, id: &str, status: NodeStatus, message: Option<String>) {
+    state
+        .update_project(|p| {
+            if let Some(node) = p.find_node_mut(id) {
+                if status == NodeStatus::Error {
+                    node.error_message = message;
+                }
+                node.status = status;
+            }
+        })
+        .await;
+}
+
+async fn generate_all(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<GenerateAllRequest>>,
+) -> Result<Json<Project>, (StatusCode, Json<ErrorResponse>)> {
+    let _turn = wait_generation_turn(&state, GenerationKind::All, None).await?;
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let options = body.map(|Json(b)| b).unwrap_or_default();
+    let profile = match &options.profile {
+        Some(name) => Some(
+            project
+                .profile(name)
+                .cloned()
+                .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?,
+        ),
+        None => None,
+    };
+
+    let execution_options = ExecutionOptions {
+        skip_completed: options.skip_completed,
+        force: options.force,
+        tags: options.tags.clone(),
+    };
+
+    let (tx, forwarder) = forward_progress(&state);
+    let executor = Executor::new(tx, project, state.get_api_keys().await.into())
+        .with_profile(profile)
+        .with_settings(&state.get_settings().await)
+        .with_step_mode(options.step.then(|| state.step.clone()))
+        .with_approval_gates(Some(state.step.clone()))
+        .with_max_duration(options.max_duration_secs.map(std::time::Duration::from_secs));
+    let mut result_project = executor.execute_all(execution_options).await;
+    if options.consistency_pass {
+        result_project = executor.execute_consistency_pass().await;
+    }
+    // Dropping the executor closes the channel once the forwarder has caught up
+    drop(executor);
+    let _ = forwarder.await;
+
+    let project = apply_run_snapshot(&state, &result_project, None)
+        .await
+        .ok_or_else(update_refused)?;
+    Ok(Json(project))
+}
+
+/// Apply node progress from an executor run to the shared project as it happens, so
+/// change feed subscribers see nodes go from generating to complete during the run.
+/// The returned task ends once the executor holding the sender is dropped.
+fn forward_progress(
+    state: &Arc<AppState>,
+) -> (mpsc::UnboundedSender<ExecutionEvent>, tokio::task::JoinHandle<()>) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let forwarder = tokio::spawn({
+        let state = state.clone();
+        async move {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    ExecutionEvent::NodeUpdate(progress) => apply_progress(&state, progress).await,
+                    // Spec edits made while paused are applied on continue
+                    ExecutionEvent::WavePaused { project, .. }
+                    | ExecutionEvent::ApprovalRequired { project, .. } => {
+                        apply_run_snapshot(&state, &project, None).await;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+    (tx, forwarder)
+}
+
+/// The run that was generating the project when the app was closed, if any
+async fn get_interrupted_run(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Option<InterruptedRun>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let checkpoint = load_checkpoint(&project.project_path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(checkpoint.map(|c| c.interrupted())))
+}
+
+/// Restore the code an interrupted run generated and generate the nodes it didn't get to
+async fn resume_interrupted_run(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Project>, (StatusCode, Json<ErrorResponse>)> {
+    let _turn = wait_generation_turn(&state, GenerationKind::All, None).await?;
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let checkpoint = load_checkpoint(&project.project_path)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "No interrupted run to resume".to_string(),
+                }),
+            )
+        })?;
+
+    let project = state
+        .update_project(|p| {
+            checkpoint.restore(p);
+        })
+        .await
+        .ok_or_else(update_refused)?;
+    let remaining = checkpoint.remaining();
+
+    let (tx, forwarder) = forward_progress(&state);
+    let executor = Executor::new(tx, project, state.get_api_keys().await.into())
+        .with_settings(&state.get_settings().await)
+        .with_approval_gates(Some(state.step.clone()))
+        .resuming(checkpoint);
+    let result_project = executor
+        .execute_nodes(remaining.clone(), ExecutionOptions::default())
+        .await;
+    drop(executor);
+    let _ = forwarder.await;
+
+    let project = apply_run_snapshot(&state, &result_project, Some(&remaining))
+        .await
+        .ok_or_else(update_refused)?;
+    Ok(Json(project))
+}
+
+/// Forget an interrupted run instead of resuming it
+async fn discard_interrupted_run(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    clear_checkpoint(&project.project_path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(serde_json::json!({ "discarded": true })))
+}
+
+/// Wait until earlier generation requests have finished. The request's `If-Match` is
+/// checked before waiting, since the project may change while it waits.
+pub(crate) async fn wait_generation_turn(
+    state: &AppState,
+    kind: GenerationKind,
+    node_id: Option<String>,
+) -> Result<QueueTurn<'_>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.check_if_match().await {
+        return Err(update_refused());
+    }
+    state
+        .generation_queue
+        .wait_turn(kind, node_id)
+        .await
+        .map_err(|error| (StatusCode::CONFLICT, Json(ErrorResponse { error })))
+}
+
+/// Generation requests, the running one first
+async fn list_queue(State(state): State<Arc<AppState>>) -> Json<Vec<QueuedGeneration>> {
+    Json(state.generation_queue.list())
+}
+
+/// Remove a generation request that hasn't started yet
+async fn remove_queued(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    state.generation_queue.remove(&id).map_err(|e| match e {
+        RemoveQueuedError::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Queued generation '{}' not found", id),
+            }),
+        ),
+        RemoveQueuedError::Running => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Generation is already running".to_string(),
+            }),
+        ),
+    })?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// Where the running generation is paused, in step mode or for approval, or `null`
+async fn get_paused_step(State(state): State<Arc<AppState>>) -> Json<Option<PausedStep>> {
+    Json(state.step.paused())
+}
+
+/// Let a generation paused in step mode generate its next wave, using the project as
+/// edited while paused, or stop it
+async fn continue_execution(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ContinueRequest>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    let action = if req.stop {
+        StepAction::Stop
+    } else {
+        StepAction::Continue(state.get_project().await.map(Box::new))
+    };
+    if !state.step.resume(action) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "No generation is paused".to_string(),
+            }),
+        ));
+    }
+    Ok(Json(serde_json::json!({ "continued": !req.stop })))
+}
+
+/// Approve or reject the generated code of the nodes a generation is paused for;
+/// either way the run goes on, without what depends on rejected code
+async fn approve_generation(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ApproveRequest>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    let awaiting = state.step.paused().is_some_and(|p| !p.awaiting_approval.is_empty());
+    let action = if req.reject {
+        StepAction::Reject
+    } else {
+        StepAction::Continue(state.get_project().await.map(Box::new))
+    };
+    if !awaiting || !state.step.resume(action) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "No generation is awaiting approval".to_string(),
+            }),
+        ));
+    }
+    Ok(Json(serde_json::json!({ "approved": !req.reject })))
+}
+
+/// Mirror a node's progress from an executor run onto the shared project
+async fn apply_progress(state: &AppState, progress: NodeProgress) {
+    state
+        .update_project(|p| {
+            if let Some(node) = p.find_node_mut(&progress.node_id) {
+                if progress.status == NodeStatus::Error {
+                    node.error_message = progress.message;
+                }
+                if progress.generated_code.is_some() {
+                    node.generated_code = progress.generated_code;
+                }
+                node.status = progress.status;
+            }
+        })
+        .await;
+}
+
+/// Copy generation results (statuses, code, errors, generation records and checks) from
+/// a run's project into the shared project, keeping its specs so edits made since the
+/// run started aren't lost. `only` limits it to those nodes. Returns the shared project.
+async fn apply_run_snapshot(
+    state: &AppState,
+    snapshot: &Project,
+    only: Option<&[String]>,
+) -> Option<Project> {
+    state
+        .update_project(|p| {
+            for node in &mut p.nodes {
+                if only.is_some_and(|ids| !ids.contains(&node.id)) {
+                    continue;
+                }
+                if let Some(run_node) = snapshot.find_node(&node.id) {
+                    node.status = run_node.status.clone();
+                    node.generated_code = run_node.generated_code.clone();
+                    node.error_message = run_node.error_message.clone();
+                    node.last_generation = run_node.last_generation.clone();
+                    node.generated_against_stubs = run_node.generated_against_stubs.clone();
+                    node.constraint_violations = run_node.constraint_violations.clone();
+                    node.missing_exports = run_node.missing_exports.clone();
+                    node.consistency_issues = run_node.consistency_issues.clone();
+                }
+            }
+        })
+        .await
+}
+
+/// Generate a single wave as a trial and report quality and cost metrics
+async fn generate_pilot(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<PilotRequest>>,
+) -> Result<Json<PilotReport>, (StatusCode, Json<ErrorResponse>)> {
+    let _turn = wait_generation_turn(&state, GenerationKind::Pilot, None).await?;
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+    let plan = ExecutionPlan::from_project(&project);
+    let wave = plan
+        .waves
+        .iter()
+        .find(|w| w.wave_number == request.wave)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Wave {} does not exist", request.wave),
+                }),
+            )
+        })?;
+
+    let started = std::time::Instant::now();
+    let deadline = request
+        .time_limit_secs
+        .map(|secs| tokio::time::Instant::now() + std::time::Duration::from_secs(secs));
+
+    let mut report = PilotReport::new(request.wave);
+    let mut timed_out = false;
+
+    for node_id in &wave.node_ids {
+        let generation = generate_single_node(&state, node_id, None);
+        let outcome = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, generation).await {
+                Ok(outcome) => outcome.map_err(|(_, Json(e))| e.error),
+                Err(_) => {
+                    timed_out = true;
+                    Err("Pilot time limit reached".to_string())
+                }
+            },
+            None => generation.await.map_err(|(_, Json(e))| e.error),
+        };
+
+        let project = state.get_project().await.unwrap_or(project.clone());
+        let Some(node) = project.find_node(node_id) else {
+            continue;
+        };
+
+        let run = match outcome {
+            Ok((_, run)) => run,
+            Err(error) => NodeRunReport {
+                node_id: node_id.clone(),
+                node_name: node.name.clone(),
+                model: node.llm_config.resolve(&project.manifest.default_llm).model,
+                success: false,
+                duration_ms: 0,
+                input_tokens: None,
+                output_tokens: None,
+                tokens_used: None,
+                cost_estimate: None,
+                fixup: None,
+                retries: 0,
+                error: Some(error),
+            },
+        };
+        report.record(&resolve_node(&project.manifest, node), run);
+
+        if timed_out {
+            break;
+        }
+    }
+
+    report.finish(started.elapsed().as_millis() as u64, timed_out, plan.total_nodes);
+    Ok(Json(report))
+}
+
+async fn get_execution_plan(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExecutionPlanQuery>,
+) -> Result<Json<ExecutionPlan>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let concurrency = match query.concurrency {
+        Some(concurrency) => Some(concurrency),
+        None => state.get_settings().await.max_concurrency,
+    };
+    Ok(Json(ExecutionPlan::with_concurrency(&project, concurrency)))
+}
+
+/// Minimal plan for generating a single node and its missing dependencies
+async fn get_node_execution_plan(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ExecutionPlan>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let plan = ExecutionPlan::for_node(&project, &id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    Ok(Json(plan))
+}
+
+async fn preview_prompt(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let prompt = ContextBuilder::build_prompt(&project, &id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({ "prompt": prompt })))
+}
+
+/// Measure a node's prompt, generated code and exports against the size thresholds
+/// past which generation quality drops, optionally with an LLM-proposed split
+async fn suggest_node_split(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    body: Option<Json<SuggestSplitRequest>>,
+) -> Result<Json<SplitSuggestion>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+
+    let mut suggestion = measure_node(&project, &id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    if req.llm {
+        let provider = &project.manifest.default_llm.provider;
+        let api_key = state.get_api_keys().await.get_for_provider(provider);
+        suggestion.proposal = propose_split(&project, &id, api_key).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    }
+
+    Ok(Json(suggestion))
+}
+
+/// Generate a node with two configurations concurrently and store both outputs as
+/// candidates, leaving the node itself unchanged until one is accepted
+async fn generate_compare(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<CompareRequest>,
+) -> Result<Json<Vec<Candidate>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let node = project.find_node(&id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        )
+    })?;
+
+    let api_keys = state.get_api_keys().await;
+    let llm_config = node.llm_config.resolve(&project.manifest.default_llm);
+    let api_key = |variant: &GenerationProfile| {
+        api_keys.get_for_provider(variant.provider.as_ref().unwrap_or(&llm_config.provider))
+    };
+
+    let (a, b) = tokio::join!(
+        generate_candidate(&project, &id, &req.a, api_key(&req.a)),
+        generate_candidate(&project, &id, &req.b, api_key(&req.b)),
+    );
+    let candidates = vec![a, b]
+        .into_iter()
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error })))?;
+
+    if let Err(e) = save_candidates(&project.project_path, &candidates) {
+        tracing::error!("Failed to store candidates for {}: {}", id, e);
+    }
+
+    Ok(Json(candidates))
+}
+
+/// Candidates generated for a node by comparisons, oldest first
+async fn list_node_candidates(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Candidate>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    load_candidates(&project.project_path, &id)
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })
+}
+
+/// Keep a candidate as the node's generated code
+async fn accept_node_candidate(
+    State(state): State<Arc<AppState>>,
+    Path((id, candidate_id)): Path<(String, String)>,
+    body: Option<Json<AcceptCandidateRequest>>,
+) -> Result<Json<CodeNode>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+    let candidate = load_candidates(&project.project_path, &id)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?
+        .into_iter()
+        .find(|c| c.id == candidate_id)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Candidate '{}' not found", candidate_id),
+                }),
+            )
+        })?;
+
+    let mut result = Ok(());
+    let updated = state
+        .update_project(|p| result = accept_candidate(p, &candidate, request.adopt_config))
+        .await;
+    result.map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
+    updated
+        .and_then(|p| p.find_node(&id).cloned())
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Node '{}' not found", id),
+                }),
+            )
+        })
+}
+
+/// Recorded prompts and raw completions for a node, oldest first
+/// (empty unless the project has `recordTranscripts` enabled)
+async fn get_node_transcript(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Transcript>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    load_transcripts(&project.project_path, &id)
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })
+}
+
+/// A node's status transitions, with when it last succeeded, how often it failed
+/// and how long its generations took
+async fn get_node_history(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<NodeHistory>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    load_node_history(&project.project_path, &id)
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })
+}
+
+#[derive(Deserialize)]
+struct TreeQuery {
+    /// `up` for the node's dependencies, `down` for its dependents
+    #[serde(default)]
+    direction: TreeDirection,
+    /// Maximum number of edges to follow; unlimited when unset
+    depth: Option<usize>,
+}
+
+/// The node's transitive dependencies or dependents as a tree, with their statuses
+async fn get_node_tree(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<TreeQuery>,
+) -> Result<Json<DependencyTree>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    dependency_tree(&project, &id, query.direction, query.depth)
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Node '{}' not found", id),
+                }),
+            )
+        })
+}
+
+/// What applying an update (the same JSON merge patch as `PUT /nodes/:id`) would
+/// make stale, cost to regenerate and rewrite on disk, without applying it
+async fn analyze_node_impact(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateNodeRequest>,
+) -> Result<Json<ImpactReport>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    if project.find_node(&id).is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Node '{}' not found", id),
+            }),
+        ));
+    }
+
+    let concurrency = state.get_settings().await.max_concurrency;
+    analyze_impact(&project, &id, &req.updates, concurrency)
+        .map(Json)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))
+}
+
+async fn list_runs(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<RunSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    list_run_reports(&project.project_path).map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
+async fn get_run(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<RunReport>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+
+    load_run_report(&project.project_path, &id).map(Json).map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Run '{}' not found", id),
+            }),
+        )
+    })
+}
+
+/// Commit the files a run wrote to a new branch and open a pull request for it, in
+/// the repository set in the project settings
+async fn open_run_pull_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<PullRequest>, (StatusCode, Json<ErrorResponse>)> {
+    let project = state.get_project().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No project loaded".to_string(),
+            }),
+        )
+    })?;
+    let report = load_run_report(&project.project_path, &id).map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Run '{}' not found", id),
+            }),
+        )
+    })?;
+
+    let settings = state.get_settings().await;
+    let token = state.get_api_keys().await.github;
+    let (Some(token), Some(_)) = (token, &settings.github_repository) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Pull requests need githubRepository in the project settings and a \
+                        GitHub token (set with the API keys or GITHUB_TOKEN)"
+                    .to_string(),
+            }),
+        ));
+    };
+
+    open_pull_request(&project, &report, &settings, &token)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })
+}
+
+/// The models the provider's API key can use. Only Mistral lists its models.
+async fn list_provider_models(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+) -> Result<Json<Vec<MistralModel>>, (StatusCode, Json<ErrorResponse>)> {
+    if provider != crate::graph::model::LLMProvider::Mistral.as_str() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Listing models isn't supported for '{}'", provider),
+            }),
+        ));
+    }
+    let api_key = state.get_api_keys().await.mistral.ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "No Mistral API key is set (set it with the API keys or MISTRAL_API_KEY)"
+                    .to_string(),
+            }),
+        )
+    })?;
+
+    list_models(&api_key).await.map(Json).map_err(|e| {
+        (
+            StatusCode::BAD_GATEWAY,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
+async fn set_api_keys(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ApiKeysRequest>,
-) -> Json<serde_json::Value> {
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    // Keys for providers the loaded project's policy doesn't allow are refused
+    if let Some(policy) = state.get_project().await.and_then(|p| p.manifest.policy) {
+        let keys = [
+            (crate::graph::model::LLMProvider::Anthropic, &req.anthropic),
+            (crate::graph::model::LLMProvider::OpenAI, &req.openai),
+            (crate::graph::model::LLMProvider::Mistral, &req.mistral),
+            (
+                crate::graph::model::LLMProvider::Ollama,
+                &req.ollama_base_url,
+            ),
+        ];
+        for (provider, key) in keys {
+            if key.as_deref().is_some_and(|k| !k.is_empty()) && !policy.allows_provider(&provider) {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    Json(ErrorResponse {
+                        error: format!(
+                            "The project's policy doesn't allow the {} provider",
+                            provider.as_str()
+                        ),
+                    }),
+                ));
+            }
+        }
+    }
+    if let Some(proxy_url) = req.proxy_url {
+        crate::llm::http::set_proxy(Some(proxy_url))
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    }
     state
         .set_api_keys(ApiKeys {
             anthropic: req.anthropic,
             openai: req.openai,
+            mistral: req.mistral,
             ollama_base_url: req.ollama_base_url,
+            github: req.github,
         })
         .await;
 
-    Json(serde_json::json!({ "updated": true }))
+    Ok(Json(serde_json::json!({ "updated": true })))
+}
+
+/// Return the tail of the backend log (or the entries after `since`) as JSON lines,
+/// optionally following new output
+async fn get_logs(
+    Query(query): Query<LogsQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let recent = match query.since {
+        Some(since) => logging::read_after(since),
+        None => logging::read_tail(query.lines),
+    };
+    let (tail, offset) = recent.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+
+    let headers = [(header::CONTENT_TYPE, "application/x-ndjson")];
+
+    if !query.follow {
+        return Ok((headers, tail).into_response());
+    }
+
+    // Send the tail first, then poll the file for appended data
+    let initial = futures::stream::once(async move { Ok::<_, std::io::Error>(tail.into_bytes()) });
+    let updates = futures::stream::unfold(offset, |offset| async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            match logging::read_since(offset) {
+                Ok((data, new_offset)) if !data.is_empty() => {
+                    return Some((Ok(data), new_offset));
+                }
+                Ok(_) => continue,
+                Err(e) => return Some((Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())), offset)),
+            }
+        }
+    });
+
+    Ok((headers, Body::from_stream(futures::StreamExt::chain(initial, updates))).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_check_if_match() {
+        assert!(check_if_match(Some("\"7\""), 7).is_ok());
+        assert!(check_if_match(Some("W/\"7\""), 7).is_ok());
+        assert!(check_if_match(Some("\"6\", \"7\""), 7).is_ok());
+        assert!(check_if_match(Some("*"), 7).is_ok());
+        assert_eq!(check_if_match(Some("\"6\""), 7).unwrap_err().0, StatusCode::CONFLICT);
+        assert_eq!(
+            check_if_match(None, 7).unwrap_err().0,
+            StatusCode::PRECONDITION_REQUIRED
+        );
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches(Some("\"7\""), 7));
+        assert!(etag_matches(Some("W/\"6\", W/\"7\""), 7));
+        assert!(!etag_matches(Some("\"6\""), 7));
+        assert!(!etag_matches(None, 7));
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_guard() {
+        let app = Router::new()
+            .route("/api/echo", post(|Json(body): Json<serde_json::Value>| async { Json(body) }))
+            .layer(axum::middleware::from_fn(body_limit_guard))
+            .layer(axum::extract::DefaultBodyLimit::disable());
+        let send = |body: Vec<u8>, content_length: Option<usize>| {
+            let mut request =
+                Request::post("/api/echo").header(header::CONTENT_TYPE, "application/json");
+            if let Some(length) = content_length {
+                request = request.header(header::CONTENT_LENGTH, length);
+            }
+            app.clone().oneshot(request.body(Body::from(body)).unwrap())
+        };
+
+        let response = send(b"{}".to_vec(), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Declared too large: refused before the body is read
+        let response = send(b"{}".to_vec(), Some(MAX_REQUEST_BYTES + 1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Undeclared: cut off while reading, with the same JSON error
+        let oversized = format!("\"{}\"", "a".repeat(MAX_REQUEST_BYTES));
+        let response = send(oversized.into_bytes(), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("8 MB"));
+
+        assert_eq!(body_limit(&Method::PUT, "/api/nodes/a"), MAX_NODE_REQUEST_BYTES);
+    }
 }
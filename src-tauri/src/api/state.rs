@@ -1,25 +1,171 @@
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::{mpsc, RwLock};
 
-use crate::graph::model::Project;
+use crate::graph::changes::{
+    append_events, diff_projects, last_revision, to_events, Change, ChangeEvent,
+};
+use crate::graph::history::{append_transitions, status_transitions};
+use crate::graph::model::{LLMProvider, Project, ProjectManifest};
+use crate::graph::save_project_to_file;
+use crate::graph::settings::{load_settings_or_default, ProjectSettings};
+use crate::orchestration::github::GITHUB_TOKEN_ENV;
+use crate::orchestration::StepControl;
+
+use super::queue::GenerationQueue;
 
 /// Shared application state between Tauri and HTTP API
 #[derive(Debug, Default)]
 pub struct AppState {
     /// Current loaded project (if any)
     pub project: RwLock<Option<Project>>,
-    /// API keys for LLM providers
+    /// The loaded project's local settings (`.needlepoint/settings.yaml`)
+    pub settings: RwLock<ProjectSettings>,
+    /// Bumped on every change to the project so clients can detect edits made by others
+    pub revision: AtomicU64,
+    /// Revision of the project as it was last loaded or saved
+    pub saved_revision: AtomicU64,
+    /// API keys for LLM providers: the user's, then the environment's for the loaded
+    /// project
     pub api_keys: RwLock<ApiKeys>,
+    /// API keys set by the user, kept when switching projects
+    pub user_api_keys: RwLock<ApiKeys>,
+    /// Generation requests, run one at a time
+    pub generation_queue: GenerationQueue,
+    /// Resumes the running generation when it's paused in step mode
+    pub step: StepControl,
     /// Port the HTTP server is running on
     pub port: RwLock<Option<u16>>,
+    /// Open MCP SSE sessions, each with a channel for its responses
+    pub mcp_sessions: Mutex<HashMap<String, mpsc::UnboundedSender<String>>>,
+    /// Open change feed streams
+    pub change_subscribers: Mutex<Vec<mpsc::UnboundedSender<ChangeEvent>>>,
+    /// Bearer token clients must send when the API is served beyond localhost
+    pub access_token: RwLock<Option<String>>,
+    /// Refuse changes made over HTTP (including MCP over SSE); Tauri commands are unaffected
+    pub read_only: AtomicBool,
+}
+
+tokio::task_local! {
+    /// The `If-Match` condition of the HTTP request being handled, checked by
+    /// `AppState::update_project`
+    pub static IF_MATCH: Arc<RevisionCondition>;
+}
+
+/// An `If-Match` condition on the project revision. The first update a request makes
+/// checks it while holding the project lock, so no other edit can land in between;
+/// the request's later updates follow that outcome.
+pub struct RevisionCondition {
+    accepts: Box<dyn Fn(u64) -> bool + Send + Sync>,
+    /// `Err` holds the revision the condition failed against
+    outcome: Mutex<Option<Result<(), u64>>>,
+}
+
+impl RevisionCondition {
+    pub fn new(accepts: impl Fn(u64) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            accepts: Box::new(accepts),
+            outcome: Mutex::new(None),
+        }
+    }
+
+    fn check(&self, revision: u64) -> bool {
+        let mut outcome = self.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        let outcome = outcome.get_or_insert_with(|| {
+            if (self.accepts)(revision) {
+                Ok(())
+            } else {
+                Err(revision)
+            }
+        });
+        outcome.is_ok()
+    }
+
+    /// The revision the condition failed against, if an update was refused
+    pub fn conflict(&self) -> Option<u64> {
+        match *self.outcome.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(Err(revision)) => Some(revision),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for RevisionCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RevisionCondition")
+            .field("outcome", &self.outcome)
+            .finish_non_exhaustive()
+    }
 }
 
-/// API keys for LLM providers
+/// API keys for LLM providers, and the token pull requests are opened with
 #[derive(Debug, Clone, Default)]
 pub struct ApiKeys {
     pub anthropic: Option<String>,
     pub openai: Option<String>,
+    pub mistral: Option<String>,
     pub ollama_base_url: Option<String>,
+    /// GitHub personal access token (needs contents and pull requests write access)
+    pub github: Option<String>,
+}
+
+impl ApiKeys {
+    /// Keys from the environment: the manifest's `apiKeyEnv` for its default provider,
+    /// and `ANTHROPIC_API_KEY`, `OPENAI_API_KEY`, `MISTRAL_API_KEY`, `OLLAMA_BASE_URL` and
+    /// `GITHUB_TOKEN` otherwise
+    pub fn from_env(manifest: Option<&ProjectManifest>) -> Self {
+        Self::from_vars(manifest, |name| std::env::var(name).ok())
+    }
+
+    fn from_vars(
+        manifest: Option<&ProjectManifest>,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let var = |name: &str| var(name).filter(|v| !v.is_empty());
+        let mut keys = Self {
+            anthropic: var("ANTHROPIC_API_KEY"),
+            openai: var("OPENAI_API_KEY"),
+            mistral: var("MISTRAL_API_KEY"),
+            ollama_base_url: var("OLLAMA_BASE_URL"),
+            github: var(GITHUB_TOKEN_ENV),
+        };
+        if let Some(default_llm) = manifest.map(|m| &m.default_llm) {
+            let key = var(&default_llm.api_key_env);
+            match default_llm.provider {
+                LLMProvider::Anthropic => keys.anthropic = key.or(keys.anthropic),
+                LLMProvider::OpenAI => keys.openai = key.or(keys.openai),
+                LLMProvider::Mistral => keys.mistral = key.or(keys.mistral),
+                LLMProvider::Ollama | LLMProvider::Local | LLMProvider::Mock => {}
+            }
+        }
+        keys
+    }
+
+    /// The key for a provider (see `executor::ApiKeys::get_for_provider`)
+    pub fn get_for_provider(&self, provider: &LLMProvider) -> Option<String> {
+        crate::orchestration::executor::ApiKeys::from(self.clone()).get_for_provider(provider)
+    }
+
+    /// Take the keys of `other` that aren't set here
+    pub fn fill_missing(&mut self, other: ApiKeys) {
+        self.anthropic = self.anthropic.take().or(other.anthropic);
+        self.openai = self.openai.take().or(other.openai);
+        self.mistral = self.mistral.take().or(other.mistral);
+        self.ollama_base_url = self.ollama_base_url.take().or(other.ollama_base_url);
+        self.github = self.github.take().or(other.github);
+    }
+}
+
+impl From<ApiKeys> for crate::orchestration::executor::ApiKeys {
+    fn from(keys: ApiKeys) -> Self {
+        Self {
+            anthropic: keys.anthropic,
+            openai: keys.openai,
+            mistral: keys.mistral,
+            ollama_base_url: keys.ollama_base_url,
+        }
+    }
 }
 
 impl AppState {
@@ -32,32 +178,322 @@ impl AppState {
         self.project.read().await.clone()
     }
 
-    /// Set the current project
+    /// Set the current project (and send logs to its `.needlepoint/logs`).
+    /// Replacing the project with another copy of itself records the individual
+    /// changes; anything else is recorded as a reload. Switching projects resolves
+    /// the API keys again for the new manifest (see `resolve_api_keys`) and takes
+    /// the new project as saved.
     pub async fn set_project(&self, project: Option<Project>) {
-        *self.project.write().await = project;
+        let mut guard = self.project.write().await;
+        let switched = match (guard.as_ref(), &project) {
+            (Some(previous), Some(p)) => previous.project_path != p.project_path,
+            _ => true,
+        };
+        if switched {
+            *self.settings.write().await = match &project {
+                Some(p) => load_settings_or_default(&p.project_path),
+                None => ProjectSettings::default(),
+            };
+            self.resolve_api_keys(project.as_ref().map(|p| &p.manifest)).await;
+        }
+        match &project {
+            Some(p) => {
+                crate::logging::set_project_dir(&p.project_path);
+                // Continue from the project's event log so revisions keep increasing across restarts
+                let revision = self
+                    .revision()
+                    .max(last_revision(&p.project_path).unwrap_or(0))
+                    + 1;
+                self.revision.store(revision, Ordering::SeqCst);
+                if switched {
+                    self.saved_revision.store(revision, Ordering::SeqCst);
+                }
+                let changes = match guard.as_ref() {
+                    Some(previous) if previous.project_path == p.project_path => {
+                        record_status_history(previous, p);
+                        diff_projects(previous, p)
+                    }
+                    _ => vec![Change::ProjectLoaded],
+                };
+                self.record_changes(p, revision, changes);
+            }
+            None => {
+                self.revision.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        *guard = project;
+    }
+
+    /// Current project revision
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
+    /// Take the project as it is now to match its file, e.g. after reloading it
+    pub fn mark_saved(&self) {
+        self.saved_revision.store(self.revision(), Ordering::SeqCst);
+    }
+
+    /// Whether the loaded project changed since it was loaded or last saved
+    pub async fn has_unsaved_changes(&self) -> bool {
+        let guard = self.project.read().await;
+        guard.is_some() && self.revision() != self.saved_revision.load(Ordering::SeqCst)
+    }
+
+    /// Save the loaded project to its YAML file. Returns `false` when no project is
+    /// loaded.
+    pub async fn save_project(&self) -> anyhow::Result<bool> {
+        // Holding the lock keeps the project at the revision being saved
+        let guard = self.project.read().await;
+        let Some(project) = guard.as_ref() else {
+            return Ok(false);
+        };
+        save_project_to_file(project)?;
+        self.mark_saved();
+        Ok(true)
+    }
+
+    /// Whether the HTTP API only serves reads
+    pub fn read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
     }
 
-    /// Update the project (applies a mutation function)
+    /// Check the request's `If-Match` (see `IF_MATCH`) now instead of at its first
+    /// update, e.g. before it waits its turn to generate. Returns whether it holds.
+    pub async fn check_if_match(&self) -> bool {
+        let _guard = self.project.read().await;
+        IF_MATCH.try_with(|c| c.check(self.revision())).unwrap_or(true)
+    }
+
+    /// Update the project (applies a mutation function), bumping the revision if it
+    /// changed. Within an HTTP request whose `If-Match` no longer holds (see
+    /// `IF_MATCH`), nothing is changed and `None` is returned.
     pub async fn update_project<F>(&self, f: F) -> Option<Project>
     where
         F: FnOnce(&mut Project),
     {
         let mut guard = self.project.write().await;
+        let revision = self.revision();
+        if !IF_MATCH.try_with(|c| c.check(revision)).unwrap_or(true) {
+            return None;
+        }
         if let Some(ref mut project) = *guard {
+            let before = project.clone();
             f(project);
+            record_status_history(&before, project);
+            // A no-op (e.g. a rejected patch) leaves the revision alone, so it conflicts
+            // with no one and doesn't count as an unsaved change
+            let changes = diff_projects(&before, project);
+            if !changes.is_empty() {
+                let revision = self.revision.fetch_add(1, Ordering::SeqCst) + 1;
+                self.record_changes(project, revision, changes);
+            }
             Some(project.clone())
         } else {
             None
         }
     }
 
+    /// Get the loaded project's local settings
+    pub async fn get_settings(&self) -> ProjectSettings {
+        self.settings.read().await.clone()
+    }
+
+    /// Set the loaded project's local settings (without saving them)
+    pub async fn set_settings(&self, settings: ProjectSettings) {
+        *self.settings.write().await = settings;
+    }
+
     /// Get API keys
     pub async fn get_api_keys(&self) -> ApiKeys {
         self.api_keys.read().await.clone()
     }
 
-    /// Set API keys
+    /// Set the user's API keys; keys left unset come from the environment
     pub async fn set_api_keys(&self, keys: ApiKeys) {
+        *self.user_api_keys.write().await = keys;
+        let manifest = self.project.read().await.as_ref().map(|p| p.manifest.clone());
+        self.resolve_api_keys(manifest.as_ref()).await;
+    }
+
+    /// Work out the keys in use from scratch: the user's, then the environment's for
+    /// the manifest (see `ApiKeys::from_env`), so keys another project's `apiKeyEnv`
+    /// named don't carry over
+    async fn resolve_api_keys(&self, manifest: Option<&ProjectManifest>) {
+        let mut keys = self.user_api_keys.read().await.clone();
+        keys.fill_missing(ApiKeys::from_env(manifest));
         *self.api_keys.write().await = keys;
     }
+
+    /// Append changes to the project's event log and push them to change feed
+    /// subscribers. Write failures are logged, not returned, so a read-only
+    /// project directory doesn't block edits.
+    fn record_changes(&self, project: &Project, revision: u64, changes: Vec<Change>) {
+        let events = to_events(revision, changes);
+        if events.is_empty() {
+            return;
+        }
+        if !project.project_path.is_empty() {
+            if let Err(e) = append_events(&project.project_path, &events) {
+                tracing::warn!("Failed to record project changes: {}", e);
+            }
+        }
+        self.change_subscribers()
+            .retain(|subscriber| events.iter().all(|e| subscriber.send(e.clone()).is_ok()));
+    }
+
+    /// Lock the change feed subscriber list
+    pub fn change_subscribers(&self) -> MutexGuard<'_, Vec<mpsc::UnboundedSender<ChangeEvent>>> {
+        self.change_subscribers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the MCP session table
+    pub fn mcp_sessions(&self) -> MutexGuard<'_, HashMap<String, mpsc::UnboundedSender<String>>> {
+        self.mcp_sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Append the nodes' status changes to the project's status history. Write failures
+/// are logged, like those of the event log.
+fn record_status_history(before: &Project, after: &Project) {
+    if after.project_path.is_empty() {
+        return;
+    }
+    if let Err(e) = append_transitions(&after.project_path, &status_transitions(before, after)) {
+        tracing::warn!("Failed to record status history: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_keys_from_env() {
+        let vars = HashMap::from([
+            ("ANTHROPIC_API_KEY", "standard"),
+            ("SHOP_ANTHROPIC_KEY", "shop"),
+            ("OPENAI_API_KEY", ""),
+            ("MISTRAL_API_KEY", "mistral"),
+            ("GITHUB_TOKEN", "ghp_token"),
+        ]);
+        let var = |name: &str| vars.get(name).map(|v| v.to_string());
+
+        let keys = ApiKeys::from_vars(None, var);
+        assert_eq!(keys.anthropic.as_deref(), Some("standard"));
+        assert_eq!(keys.openai, None);
+        assert_eq!(keys.mistral.as_deref(), Some("mistral"));
+        assert_eq!(keys.github.as_deref(), Some("ghp_token"));
+
+        let mut manifest = ProjectManifest::default();
+        manifest.default_llm.api_key_env = "SHOP_ANTHROPIC_KEY".to_string();
+        let mut keys = ApiKeys {
+            openai: Some("set".to_string()),
+            ..Default::default()
+        };
+        keys.fill_missing(ApiKeys::from_vars(Some(&manifest), var));
+        assert_eq!(keys.anthropic.as_deref(), Some("shop"));
+        assert_eq!(keys.openai.as_deref(), Some("set"));
+
+        // An unset manifest variable falls back to the usual one
+        manifest.default_llm.api_key_env = "MISSING".to_string();
+        let keys = ApiKeys::from_vars(Some(&manifest), var);
+        assert_eq!(keys.anthropic.as_deref(), Some("standard"));
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_follow_project() {
+        std::env::set_var("NEEDLEPOINT_TEST_SHOP_KEY", "shop");
+        let state = AppState::new();
+        state
+            .set_api_keys(ApiKeys {
+                openai: Some("set".to_string()),
+                ..Default::default()
+            })
+            .await;
+
+        let (shop_dir, blog_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let mut shop = Project::new(shop_dir.path().to_string_lossy().to_string());
+        shop.manifest.default_llm.api_key_env = "NEEDLEPOINT_TEST_SHOP_KEY".to_string();
+        state.set_project(Some(shop)).await;
+        assert_eq!(state.get_api_keys().await.anthropic.as_deref(), Some("shop"));
+
+        let blog = Project::new(blog_dir.path().to_string_lossy().to_string());
+        state.set_project(Some(blog)).await;
+        let keys = state.get_api_keys().await;
+        assert_ne!(keys.anthropic.as_deref(), Some("shop"));
+        assert_eq!(keys.openai.as_deref(), Some("set"));
+    }
+
+    #[tokio::test]
+    async fn test_unsaved_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::new();
+        assert!(!state.has_unsaved_changes().await);
+
+        let project = Project::new(dir.path().to_string_lossy().to_string());
+        state.set_project(Some(project)).await;
+        assert!(!state.has_unsaved_changes().await);
+
+        state.update_project(|p| p.manifest.name = "Renamed".to_string()).await;
+        assert!(state.has_unsaved_changes().await);
+
+        assert!(state.save_project().await.unwrap());
+        assert!(!state.has_unsaved_changes().await);
+        assert!(dir.path().join(crate::graph::PROJECT_FILE_NAME).exists());
+
+        // Changing nothing isn't a change
+        let revision = state.revision();
+        state.update_project(|p| p.manifest.name = "Renamed".to_string()).await;
+        assert_eq!(state.revision(), revision);
+        assert!(!state.has_unsaved_changes().await);
+
+        state.set_project(None).await;
+        assert!(!state.has_unsaved_changes().await);
+        assert!(!state.save_project().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_if_match_checked_under_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::new();
+        state.set_project(Some(Project::new(dir.path().to_string_lossy().to_string()))).await;
+        let revision = state.revision();
+
+        // The request's first update checks the condition; later ones follow it
+        let condition = Arc::new(RevisionCondition::new(move |r| r == revision));
+        IF_MATCH
+            .scope(condition.clone(), async {
+                let renamed = state.update_project(|p| p.manifest.name = "A".to_string()).await;
+                assert!(renamed.is_some());
+                let bumped = state.update_project(|p| p.manifest.version = "2".to_string()).await;
+                assert!(bumped.is_some());
+            })
+            .await;
+        assert_eq!(condition.conflict(), None);
+
+        // Another edit landed since the condition's revision
+        let stale = Arc::new(RevisionCondition::new(move |r| r == revision));
+        IF_MATCH
+            .scope(stale.clone(), async {
+                let renamed = state.update_project(|p| p.manifest.name = "B".to_string()).await;
+                assert!(renamed.is_none());
+            })
+            .await;
+        assert_eq!(stale.conflict(), Some(revision + 2));
+        assert_eq!(state.get_project().await.unwrap().manifest.name, "A");
+
+        // Checked early, e.g. before queueing, the condition holds through later edits
+        let current = state.revision();
+        let early = Arc::new(RevisionCondition::new(move |r| r == current));
+        IF_MATCH
+            .scope(early.clone(), async {
+                assert!(state.check_if_match().await);
+                state.update_project(|p| p.manifest.name = "C".to_string()).await;
+                let renamed = state.update_project(|p| p.manifest.name = "D".to_string()).await;
+                assert!(renamed.is_some());
+            })
+            .await;
+        assert_eq!(early.conflict(), None);
+    }
 }
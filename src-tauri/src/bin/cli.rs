@@ -1,10 +1,55 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::env::Shells;
+use clap_complete::{ArgValueCandidates, CompleteEnv, CompletionCandidate};
+use needlepoint_core::graph::project_merge::merge_projects;
+use needlepoint_core::graph::serialization::{read_project_file, write_project_file};
+use needlepoint_core::orchestration::executor::NOT_RUN_MESSAGE;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Mutex;
 
 const DEFAULT_PORT: u16 = 9999;
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+/// Last project revision seen in an `ETag`, sent back as `If-Match` on mutations so
+/// they fail if another client changed the project in between
+static REVISION: Mutex<Option<String>> = Mutex::new(None);
+/// Send `If-Match: *` instead of the last seen revision
+static FORCE: AtomicBool = AtomicBool::new(false);
+/// Print results as JSON on stdout and human-readable text on stderr
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+/// Body of the last successful API response, printed as the result in JSON mode
+static LAST_RESPONSE: Mutex<Option<Value>> = Mutex::new(None);
+/// Result printed in JSON mode instead of the last response, for commands that
+/// combine several responses
+static OUTPUT: Mutex<Option<Value>> = Mutex::new(None);
+/// Set by commands that print their result line by line (`watch`, `logs`), which then
+/// print no result object after it
+static STREAMED: AtomicBool = AtomicBool::new(false);
+/// Status the CLI exits with when a command fails
+static EXIT_CODE: AtomicI32 = AtomicI32::new(1);
+
+/// `ci` exit statuses, by the stage that failed first (1 is any other error)
+const EXIT_INVALID: i32 = 2;
+const EXIT_GENERATION_FAILED: i32 = 3;
+const EXIT_WRITE_FAILED: i32 = 4;
+const EXIT_CHECKS_FAILED: i32 = 5;
+
+/// `println!` for human-readable text, which goes to stderr in JSON mode so stdout
+/// only carries JSON
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if JSON_OUTPUT.load(Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
 
 #[derive(Parser)]
 #[command(name = "needlepoint-cli")]
@@ -12,9 +57,25 @@ const DEFAULT_PORT: u16 = 9999;
 #[command(version)]
 struct Cli {
     /// Port where Needlepoint API is running
-    #[arg(short, long, default_value_t = DEFAULT_PORT)]
+    #[arg(short, long, env = "NEEDLEPOINT_PORT", default_value_t = DEFAULT_PORT)]
     port: u16,
 
+    /// Host running the Needlepoint API, for connecting to a shared instance
+    #[arg(long, env = "NEEDLEPOINT_HOST", default_value = DEFAULT_HOST)]
+    host: String,
+
+    /// Access token required by instances served beyond localhost
+    #[arg(long, env = "NEEDLEPOINT_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Apply changes even if another client changed the project since it was last read
+    #[arg(long)]
+    force: bool,
+
+    /// Print results as JSON on stdout, with human-readable text on stderr
+    #[arg(long)]
+    json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,7 +83,11 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Check if the Needlepoint API is running
-    Status,
+    Status {
+        /// Only count the nodes with this tag in the project stats
+        #[arg(long)]
+        tag: Option<String>,
+    },
 
     /// Create a new project
     New {
@@ -32,8 +97,15 @@ enum Commands {
         /// Project name
         #[arg(short, long, default_value = "New Project")]
         name: String,
+
+        /// Start from a built-in template's graph (see `templates`)
+        #[arg(short, long)]
+        template: Option<String>,
     },
 
+    /// List the built-in project templates
+    Templates,
+
     /// Load a project from a YAML file
     Load {
         /// Path to the project YAML file
@@ -43,12 +115,81 @@ enum Commands {
     /// Save the current project
     Save,
 
+    /// Close the current project
+    Close {
+        /// Save unsaved changes first
+        #[arg(long)]
+        save: bool,
+
+        /// Close even with unsaved changes, losing them
+        #[arg(long, conflicts_with = "save")]
+        discard: bool,
+    },
+
+    /// List recently opened projects
+    Recent {
+        /// Turn reopening the last project on startup on or off
+        #[arg(long)]
+        auto_load: Option<bool>,
+    },
+
     /// List all nodes in the project
-    Nodes,
+    Nodes {
+        /// Only nodes with this status (pending, generating, complete, error, warning, stale)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only nodes in this language
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Text to search for in name, path, description and purpose
+        #[arg(short, long)]
+        query: Option<String>,
+
+        /// Only nodes whose file path starts with this prefix
+        #[arg(long)]
+        path_prefix: Option<String>,
+
+        /// Only nodes with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Sort by name, path, status or language
+        #[arg(long)]
+        sort: Option<String>,
+
+        /// Reverse the sort order
+        #[arg(long)]
+        desc: bool,
+
+        /// Maximum number of nodes to list
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Continue a listing from the cursor printed by the previous page
+        #[arg(long)]
+        cursor: Option<String>,
+    },
+
+    /// Find nodes by text, or by meaning with --semantic
+    Search {
+        /// What to look for, e.g. "where do we handle password reset"
+        query: String,
+
+        /// Rank nodes by embedding similarity instead of matching text
+        #[arg(long)]
+        semantic: bool,
+
+        /// Maximum number of results
+        #[arg(long)]
+        limit: Option<usize>,
+    },
 
     /// Get details of a specific node
     Node {
         /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         id: String,
     },
 
@@ -65,6 +206,10 @@ enum Commands {
         #[arg(short, long, default_value = "typescript")]
         language: String,
 
+        /// Kind of file: code, config, schema, doc or asset
+        #[arg(short, long, default_value = "code")]
+        kind: String,
+
         /// Description of what the file does
         #[arg(short, long, default_value = "")]
         description: String,
@@ -73,6 +218,7 @@ enum Commands {
     /// Update a node's properties
     UpdateNode {
         /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         id: String,
 
         /// New description
@@ -86,23 +232,128 @@ enum Commands {
         /// New name
         #[arg(short, long)]
         name: Option<String>,
+
+        /// JSON merge patch for any other fields, e.g. '{"language": "python"}'
+        #[arg(long)]
+        patch: Option<String>,
+
+        /// Report what the change would make stale, cost and rewrite, without making it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Set a node's exports; repeat --name (with its --type and --desc) for each
+    SetExports {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Export name
+        #[arg(long = "name")]
+        names: Vec<String>,
+
+        /// Type signature of the export named at the same position
+        #[arg(long = "type")]
+        types: Vec<String>,
+
+        /// Description of the export named at the same position
+        #[arg(long = "desc")]
+        descriptions: Vec<String>,
+
+        /// Add to the node's exports (replacing any with the same name) instead of
+        /// replacing them all
+        #[arg(long)]
+        append: bool,
     },
 
     /// Delete a node
     DeleteNode {
         /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// How to handle dependents: block, stale (default) or detach
+        #[arg(long)]
+        cascade: Option<String>,
+    },
+
+    /// Find and replace across node names, file paths, descriptions, purposes and constraints
+    Replace {
+        /// Text (or regex with --regex) to find
+        find: String,
+
+        /// Replacement text ($1 etc. refer to regex groups)
+        replace: String,
+
+        /// Treat FIND as a regular expression
+        #[arg(long)]
+        regex: bool,
+
+        /// Only search these fields (name, filePath, description, purpose, constraints)
+        #[arg(long = "field")]
+        fields: Vec<String>,
+
+        /// Show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Copy a node's spec under a new ID
+    CloneNode {
+        /// Node ID to copy
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Name of the copy (default: <name>_copy)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// File path of the copy (default: _copy before the extension)
+        #[arg(long)]
+        file_path: Option<String>,
+
+        /// Copy the original's incoming edges (dependencies) as well
+        #[arg(long)]
+        with_edges: bool,
+    },
+
+    /// Check whether a node is too large to generate well and how to split it
+    SuggestSplit {
+        /// Node ID to check
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         id: String,
+
+        /// Ask the project's default LLM to propose the smaller nodes
+        #[arg(long)]
+        llm: bool,
     },
 
     /// List all edges in the project
     Edges,
 
+    /// Suggest edges that seem to be missing, from what specs and generated code refer to
+    SuggestEdges {
+        /// Only suggest dependencies of this node
+        #[arg(long, add = ArgValueCandidates::new(node_candidates))]
+        node: Option<String>,
+
+        /// Leave out suggestions less confident than this (0 to 1)
+        #[arg(long, default_value_t = 0.0)]
+        min_confidence: f64,
+
+        /// Create the suggested edges
+        #[arg(long)]
+        accept: bool,
+    },
+
     /// Add an edge between two nodes
     AddEdge {
         /// Source node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         source: String,
 
         /// Target node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         target: String,
 
         /// Relationship label
@@ -110,32 +361,399 @@ enum Commands {
         label: String,
     },
 
+    /// Change an edge's label or endpoints, keeping its ID
+    UpdateEdge {
+        /// Edge ID
+        id: String,
+
+        /// New source node ID
+        #[arg(long, add = ArgValueCandidates::new(node_candidates))]
+        source: Option<String>,
+
+        /// New target node ID
+        #[arg(long, add = ArgValueCandidates::new(node_candidates))]
+        target: Option<String>,
+
+        /// New relationship label
+        #[arg(short, long)]
+        label: Option<String>,
+    },
+
     /// Delete an edge
     DeleteEdge {
         /// Edge ID
         id: String,
     },
 
+    /// List the project's shared context snippets
+    Snippets,
+
+    /// Add or change a shared context snippet (nodes generated with its old text become stale)
+    SetSnippet {
+        /// Snippet name, as attached in a node's `snippets`
+        name: String,
+
+        /// Snippet text
+        text: String,
+    },
+
+    /// Remove a shared context snippet
+    DeleteSnippet {
+        /// Snippet name
+        name: String,
+    },
+
+    /// List the project's shared types
+    SharedTypes,
+
+    /// Add or change a shared type (nodes generated with its old definition become stale)
+    SetSharedType {
+        /// Type name, as referenced in a node's `sharedTypes` or an export's `sharedType`
+        name: String,
+
+        /// Definition for a language, read from a file: LANG=FILE (repeatable)
+        #[arg(long = "variant", value_name = "LANG=FILE", required = true)]
+        variants: Vec<String>,
+
+        /// What the type represents
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Remove a shared type
+    DeleteSharedType {
+        /// Type name
+        name: String,
+    },
+
     /// Get the execution plan (dependency order)
-    Plan,
+    Plan {
+        /// Only plan this node and its ungenerated dependencies
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: Option<String>,
+
+        /// Concurrency limit used for wall-clock estimates (default: whole waves at once)
+        #[arg(long)]
+        concurrency: Option<usize>,
+    },
+
+    /// Show recorded prompts and raw completions for a node
+    Transcript {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Only show the most recent N exchanges
+        #[arg(short = 'n', long)]
+        last: Option<usize>,
+    },
+
+    /// Show a node's status transitions and generation statistics
+    History {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Only show the most recent N transitions
+        #[arg(short = 'n', long)]
+        last: Option<usize>,
+    },
+
+    /// Show the tree of a node's dependencies, or with --reverse the nodes that
+    /// depend on it (what a change to it affects)
+    Deps {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Show dependents instead of dependencies
+        #[arg(short, long)]
+        reverse: bool,
+
+        /// Maximum number of levels to show
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+
+    /// Generate a node with two models side by side, keeping both as candidates
+    Compare {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// First model, optionally prefixed with its provider (e.g. openai:gpt-4o-mini)
+        #[arg(long)]
+        a: String,
+
+        /// Second model, optionally prefixed with its provider
+        #[arg(long)]
+        b: String,
+    },
+
+    /// List the candidates generated for a node by comparisons
+    Candidates {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+    },
+
+    /// Keep a candidate as the node's generated code
+    Accept {
+        /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
+        id: String,
+
+        /// Candidate ID (see `candidates`)
+        candidate_id: String,
+
+        /// Also switch the node to the candidate's provider and model
+        #[arg(long)]
+        adopt_config: bool,
+    },
 
     /// Preview the prompt for a node
     Prompt {
         /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         id: String,
     },
 
     /// Generate code for a specific node
     Generate {
         /// Node ID
+        #[arg(add = ArgValueCandidates::new(node_candidates))]
         id: String,
+
+        /// Generate ungenerated dependencies first
+        #[arg(long)]
+        with_deps: bool,
+
+        /// Keep the existing code if the node is already complete
+        #[arg(long)]
+        skip_completed: bool,
+
+        /// Regenerate all dependencies fresh first, even complete ones
+        #[arg(long)]
+        force: bool,
     },
 
     /// Generate code for all nodes in the project
-    GenerateAll,
+    GenerateAll {
+        /// Review cross-file consistency afterwards and apply suggested patches
+        #[arg(long)]
+        consistency_pass: bool,
+
+        /// Leave nodes that are already complete untouched
+        #[arg(long)]
+        skip_completed: bool,
+
+        /// Regenerate every node (overrides --skip-completed)
+        #[arg(long)]
+        force: bool,
+
+        /// Only generate nodes with this tag (repeatable); with --force, their
+        /// dependencies are regenerated too
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Generation profile from the manifest (e.g. draft or final) to use for every node
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Pause after each wave and ask before generating the next
+        #[arg(long)]
+        step: bool,
+
+        /// Stop starting nodes after this many seconds, leaving the rest pending
+        #[arg(long)]
+        max_duration: Option<u64>,
+    },
+
+    /// Resume the generation run that was interrupted when the app was closed
+    Resume {
+        /// Forget the interrupted run instead
+        #[arg(long)]
+        discard: bool,
+    },
+
+    /// Generate a single wave as a trial and report quality and cost metrics
+    Pilot {
+        /// Wave to generate
+        #[arg(long, default_value_t = 0)]
+        wave: usize,
+
+        /// Stop after this many seconds
+        #[arg(long)]
+        time_limit: Option<u64>,
+    },
 
     /// Write generated code to files on disk
-    WriteFiles,
+    WriteFiles {
+        /// Overwrite files that were edited by hand since they were last written
+        #[arg(long)]
+        force: bool,
+
+        /// Only write the files of nodes with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Load a project, validate and plan it, generate every node and write the files,
+    /// failing if any node fails (for CI and scripts)
+    Run {
+        /// Path to the project YAML file
+        path: PathBuf,
+
+        /// Leave nodes that are already complete untouched
+        #[arg(long)]
+        skip_completed: bool,
+
+        /// Generation profile from the manifest to use for every node
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Overwrite files that were edited by hand since they were last written
+        #[arg(long)]
+        force_write: bool,
+
+        /// Save the project, with its generated code, afterwards
+        #[arg(long)]
+        save: bool,
+
+        /// Stop starting nodes after this many seconds, leaving the rest pending (and
+        /// failing the run)
+        #[arg(long)]
+        max_duration: Option<u64>,
+    },
+
+    /// Regenerate what is out of date and check the result, for pull request
+    /// pipelines: validates the project, generates the nodes that aren't complete,
+    /// writes the files and runs the manifest's checks. Exits with 2 on validation
+    /// errors, 3 when nodes fail, 4 when files can't be written cleanly and 5 when
+    /// checks fail. Under GitHub Actions, failures are annotated and summarized.
+    Ci {
+        /// Path to the project YAML file
+        path: PathBuf,
+
+        /// Write a JUnit XML report of every stage to this file
+        #[arg(long)]
+        junit: Option<PathBuf>,
+
+        /// Generation profile from the manifest to use for every node
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Overwrite files that were edited by hand since they were last written
+        #[arg(long)]
+        force_write: bool,
+
+        /// Save the project, with its generated code, afterwards
+        #[arg(long)]
+        save: bool,
+
+        /// Stop starting nodes after this many seconds, leaving the rest pending (and
+        /// failing the run)
+        #[arg(long)]
+        max_duration: Option<u64>,
+
+        /// Don't run the manifest's checks
+        #[arg(long)]
+        no_checks: bool,
+
+        /// When everything passes, open a pull request with the generated files (see
+        /// `pull-request`)
+        #[arg(long)]
+        pull_request: bool,
+    },
+
+    /// Show the project directory tree, marking files that belong to nodes
+    Files,
+
+    /// Check the graph for errors (cycles, colliding file paths) and warnings
+    Validate,
+
+    /// Point out weak node specs (no purpose, vague exports, contradictory constraints,
+    /// mentioned files without an edge) with suggestions
+    LintSpecs,
+
+    /// Three-way merge two divergent project files by node and edge ID, without the API.
+    /// Writes the result over OURS, so it works as a git merge driver:
+    /// `git config merge.needlepoint.driver "needlepoint-cli merge %O %A %B"` with
+    /// `needlepoint.yaml merge=needlepoint` in .gitattributes
+    Merge {
+        /// Common ancestor of both versions
+        base: PathBuf,
+
+        /// Our version; overwritten with the result unless --output is given
+        ours: PathBuf,
+
+        /// Their version
+        theirs: PathBuf,
+
+        /// Write the result here instead of over OURS
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Cross-check nodes against the files on disk
+    Reconcile {
+        /// Create nodes for untracked source files
+        #[arg(long)]
+        create_nodes: bool,
+
+        /// Update nodes whose files were renamed to point at the new paths
+        #[arg(long)]
+        apply_renames: bool,
+    },
+
+    /// Print package.json / Cargo.toml / requirements.txt built from external package nodes
+    PackageManifests {
+        /// Only print the manifest for this ecosystem (npm, crates, pip)
+        #[arg(long)]
+        ecosystem: Option<String>,
+    },
+
+    /// Arrange nodes in dependency layers on the canvas
+    Layout,
+
+    /// Check generated code against the nodes' constraints and list violations
+    CheckConstraints,
+
+    /// Check generated files against the dependencies they import from and list mismatches
+    CheckConsistency {
+        /// Also have the project's default LLM review each pair of dependent files
+        #[arg(long)]
+        llm: bool,
+    },
+
+    /// Show recorded changes to the project
+    Changes {
+        /// Only show changes after this revision
+        #[arg(long, default_value_t = 0)]
+        since: u64,
+    },
+
+    /// Follow changes to the project as they happen
+    Watch {
+        /// Also show changes recorded after this revision (defaults to the current one)
+        #[arg(long)]
+        since: Option<u64>,
+    },
+
+    /// Write build files (package.json, tsconfig.json, Cargo.toml, pyproject.toml) for the project
+    Scaffold {
+        /// Have the project's default LLM refine the derived files
+        #[arg(long)]
+        llm: bool,
+
+        /// Print the files instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Replace build files that already exist
+        #[arg(long)]
+        overwrite: bool,
+    },
 
     /// Set API keys for LLM providers
     SetKeys {
@@ -147,21 +765,181 @@ enum Commands {
         #[arg(long, env = "OPENAI_API_KEY")]
         openai: Option<String>,
 
+        /// Mistral API key (or use MISTRAL_API_KEY env var)
+        #[arg(long, env = "MISTRAL_API_KEY")]
+        mistral: Option<String>,
+
         /// Ollama base URL (or use OLLAMA_BASE_URL env var)
         #[arg(long, env = "OLLAMA_BASE_URL")]
         ollama_url: Option<String>,
+
+        /// Proxy for provider requests, e.g. http://proxy:8080 or socks5://proxy:1080
+        /// (or use NEEDLEPOINT_PROXY env var)
+        #[arg(long, env = "NEEDLEPOINT_PROXY")]
+        proxy: Option<String>,
+
+        /// GitHub token pull requests are opened with (or use GITHUB_TOKEN env var)
+        #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        github: Option<String>,
+    },
+
+    /// List the models a provider's API key can use (mistral)
+    Models {
+        /// Provider to ask
+        #[arg(default_value = "mistral")]
+        provider: String,
+    },
+
+    /// Show or change the project's local settings (.needlepoint/settings.yaml)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
 
     /// Get the full project as JSON
     Project,
+
+    /// Change the project's name, version, entry point, default model or system prompt
+    SetManifest {
+        /// New project name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New project version
+        #[arg(long)]
+        version: Option<String>,
+
+        /// New entry point file
+        #[arg(long)]
+        entry_point: Option<String>,
+
+        /// Default provider for new nodes: anthropic, openai, mistral, ollama, local or mock
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Default model for new nodes
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Instructions added to every node's system prompt ("" removes them)
+        #[arg(long)]
+        system_prompt: Option<String>,
+
+        /// JSON merge patch for any other manifest fields, e.g. '{"relatedContext": 3}'
+        #[arg(long)]
+        patch: Option<String>,
+    },
+
+    /// List running and queued generation requests
+    Queue {
+        /// Remove this queued request instead, before it starts
+        #[arg(long)]
+        remove: Option<String>,
+    },
+
+    /// List past execution runs, or inspect a single run
+    Runs {
+        /// Run ID to inspect (omit to list all runs)
+        id: Option<String>,
+    },
+
+    /// Commit the files a run wrote to a new branch and open a pull request for it,
+    /// in the repository set as githubRepository (see `config`). Needs a GitHub token
+    /// (see `set-keys`).
+    PullRequest {
+        /// Run ID (defaults to the latest run)
+        id: Option<String>,
+    },
+
+    /// Show the backend log
+    Logs {
+        /// Keep streaming new log lines
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Number of recent lines to show
+        #[arg(short = 'n', long, default_value_t = 100)]
+        lines: usize,
+
+        /// Only show entries after this time (RFC 3339, e.g. 2024-05-01T12:00:00Z)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Print a shell completion script, e.g. `source <(needlepoint-cli completions bash)`.
+    /// Node IDs are completed from the running API.
+    Completions {
+        /// Shell to complete in: bash, elvish, fish, powershell or zsh
+        shell: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Show all settings, or one
+    Get {
+        /// Setting to show: provider, model, maxConcurrency, maxCost, maxTokens,
+        /// projectMaxCost, projectMaxTokens or forceWrite
+        key: Option<String>,
+    },
+
+    /// Change a setting, or unset it when no value is given
+    Set {
+        /// Setting to change: provider, model, maxConcurrency, maxCost, maxTokens,
+        /// projectMaxCost, projectMaxTokens or forceWrite
+        key: String,
+
+        /// New value, e.g. ollama, 4, 2.50 or true
+        value: Option<String>,
+    },
 }
 
+/// Keys of the project's local settings
+const CONFIG_KEYS: [&str; 11] = [
+    "provider",
+    "model",
+    "maxConcurrency",
+    "maxCost",
+    "maxTokens",
+    "projectMaxCost",
+    "projectMaxTokens",
+    "forceWrite",
+    "githubRepository",
+    "githubBaseBranch",
+    "githubBranchPrefix",
+];
+
 #[derive(Deserialize)]
 struct StatusResponse {
     status: String,
     version: String,
     project_loaded: bool,
     project_name: Option<String>,
+    #[serde(default)]
+    revision: u64,
+    #[serde(default)]
+    read_only: bool,
+    #[serde(default)]
+    unsaved_changes: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ProjectStats {
+    node_count: usize,
+    edge_count: usize,
+    nodes_by_status: HashMap<String, usize>,
+    nodes_by_language: HashMap<String, usize>,
+    #[serde(default)]
+    nodes_by_tag: HashMap<String, usize>,
+    max_depth: usize,
+    avg_fan_in: f64,
+    avg_fan_out: f64,
+    orphan_count: usize,
+    generated_loc: usize,
+    estimated_tokens: u64,
+    #[serde(default)]
+    estimated_cost: f64,
 }
 
 #[derive(Deserialize)]
@@ -171,346 +949,3077 @@ struct ErrorResponse {
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct Node {
-    id: String,
-    name: String,
-    file_path: String,
-    status: String,
-    description: String,
-    generated_code: Option<String>,
+struct PilotNodeResult {
+    node_name: String,
+    success: bool,
+    duration_ms: u64,
+    tokens_used: Option<u32>,
+    cost_estimate: Option<f64>,
+    declared_exports: usize,
+    missing_exports: Vec<String>,
+    lines_of_code: usize,
+    error: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
-struct Edge {
-    id: String,
-    source: String,
-    target: String,
-    label: String,
+#[serde(rename_all = "camelCase")]
+struct PilotReport {
+    wave_number: usize,
+    nodes: Vec<PilotNodeResult>,
+    duration_ms: u64,
+    timed_out: bool,
+    total_tokens: u64,
+    total_cost_estimate: f64,
+    export_coverage: f64,
+    projected_total_cost: Option<f64>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct ExecutionWave {
-    wave_number: u32,
-    node_ids: Vec<String>,
+struct SpecHint {
+    node_id: String,
+    node_name: String,
+    message: String,
+    suggestion: String,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct ExecutionPlan {
-    waves: Vec<ExecutionWave>,
-    total_nodes: usize,
+struct RecentProject {
+    path: String,
+    name: String,
+    opened_at: String,
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ApiKeysRequest {
-    anthropic: Option<String>,
-    openai: Option<String>,
-    ollama_base_url: Option<String>,
+#[derive(Deserialize, Debug)]
+struct ProjectTemplate {
+    id: String,
+    name: String,
+    description: String,
 }
 
-#[tokio::main]
-async fn main() {
-    let cli = Cli::parse();
-    let client = Client::new();
-    let base_url = format!("http://127.0.0.1:{}/api", cli.port);
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RecentProjects {
+    auto_load: bool,
+    projects: Vec<RecentProject>,
+}
 
-    match run(&client, &base_url, cli.command).await {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct InterruptedRun {
+    started_at: String,
+    total_nodes: usize,
+    finished_nodes: usize,
+    remaining: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PausedStep {
+    wave_number: usize,
+    remaining_waves: usize,
+    awaiting_approval: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ReplaceMatch {
+    node_name: String,
+    field: String,
+    before: String,
+    after: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WrittenFile {
+    node_name: String,
+    file_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WriteFilesResponse {
+    written: Vec<WrittenFile>,
+    merged: Vec<MergedFile>,
+    skipped: Vec<String>,
+    conflicts: Vec<WrittenFile>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MergedFile {
+    node_name: String,
+    file_path: String,
+    conflicts: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FileTreeEntry {
+    name: String,
+    is_dir: bool,
+    node_id: Option<String>,
+    #[serde(default)]
+    children: Vec<FileTreeEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ProjectFiles {
+    entries: Vec<FileTreeEntry>,
+    missing: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ValidateResponse {
+    valid: bool,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ChangeEvent {
+    revision: u64,
+    timestamp: String,
+    #[serde(rename = "type")]
+    change_type: String,
+    #[serde(default)]
+    node: Option<Node>,
+    #[serde(default)]
+    node_id: Option<String>,
+    #[serde(default)]
+    edge_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChangesResponse {
+    revision: u64,
+    changes: Vec<ChangeEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ScaffoldFile {
+    file_path: String,
+    contents: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScaffoldResponse {
+    files: Vec<ScaffoldFile>,
+    written: Vec<String>,
+    skipped: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PackageManifest {
+    ecosystem: String,
+    file_name: String,
+    contents: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ReconciledNode {
+    node_name: String,
+    file_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct UntrackedFile {
+    file_path: String,
+    language: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RenamedFile {
+    node_name: String,
+    from: String,
+    to: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ReconcileResponse {
+    missing: Vec<ReconciledNode>,
+    untracked: Vec<UntrackedFile>,
+    renamed: Vec<RenamedFile>,
+    created: Vec<ReconciledNode>,
+    renames_applied: bool,
+}
+
+fn print_plan(plan: ExecutionPlan) {
+    say!("Execution Plan ({} nodes)", plan.total_nodes);
+    say!("{}", "-".repeat(50));
+    for wave in plan.waves {
+        say!(
+            "\nWave {} (~{:.0}s):",
+            wave.wave_number,
+            wave.estimated_duration_ms as f64 / 1000.0
+        );
+        for node_id in wave.node_ids {
+            say!("  - {}", node_id);
         }
     }
+
+    say!("\nEstimated total: ~{:.0}s", plan.estimated_duration_ms as f64 / 1000.0);
+    if !plan.critical_path.is_empty() {
+        say!(
+            "Critical path ({} nodes, ~{:.0}s):",
+            plan.critical_path.len(),
+            plan.critical_path_ms as f64 / 1000.0
+        );
+        say!("  {}", plan.critical_path.join(" -> "));
+    }
 }
 
-async fn run(client: &Client, base_url: &str, command: Commands) -> Result<(), String> {
-    match command {
-        Commands::Status => {
-            let resp: StatusResponse = get(client, &format!("{}/status", base_url)).await?;
-            println!("Status: {}", resp.status);
-            println!("Version: {}", resp.version);
-            println!(
-                "Project: {}",
-                if resp.project_loaded {
-                    resp.project_name.unwrap_or_else(|| "unnamed".to_string())
-                } else {
-                    "none loaded".to_string()
-                }
+/// Print validation errors and warnings; fails if there are errors
+fn print_validation(result: &ValidateResponse) -> Result<(), String> {
+    for error in &result.errors {
+        say!("  Error: {}", error);
+    }
+    for warning in &result.warnings {
+        say!("  Warning: {}", warning);
+    }
+    say!(
+        "\nErrors: {}, warnings: {}",
+        result.errors.len(),
+        result.warnings.len()
+    );
+    if !result.valid {
+        return Err("Project has validation errors".to_string());
+    }
+    Ok(())
+}
+
+/// Fail with the given exit status
+fn ci_failure(code: i32, message: String) -> String {
+    EXIT_CODE.store(code, Ordering::Relaxed);
+    message
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A JUnit XML report with a test suite per `ci` stage
+fn junit_xml(suites: &[(&str, Vec<JunitCase>)]) -> String {
+    let count = |pick: fn(&JunitCase) -> bool| {
+        suites
+            .iter()
+            .flat_map(|(_, cases)| cases)
+            .filter(|c| pick(c))
+            .count()
+    };
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"needlepoint\" tests=\"{}\" failures=\"{}\">\n",
+        count(|_| true),
+        count(|c| c.failure.is_some())
+    ));
+    for (suite, cases) in suites {
+        let failures = cases.iter().filter(|c| c.failure.is_some()).count();
+        let skipped = cases.iter().filter(|c| c.skipped.is_some()).count();
+        let time_ms: u64 = cases.iter().map(|c| c.time_ms).sum();
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+            suite,
+            cases.len(),
+            failures,
+            skipped,
+            time_ms as f64 / 1000.0
+        ));
+        for case in cases {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                suite,
+                xml_escape(&case.name),
+                case.time_ms as f64 / 1000.0
+            ));
+            match (&case.failure, &case.skipped) {
+                (Some(failure), _) => xml.push_str(&format!(
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    xml_escape(failure.lines().next().unwrap_or_default()),
+                    xml_escape(failure)
+                )),
+                (None, Some(reason)) => xml.push_str(&format!(
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                    xml_escape(reason)
+                )),
+                (None, None) => xml.push_str("/>\n"),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Write the JUnit report and, under GitHub Actions, annotate each failure and add
+/// a summary of the stages to the job
+fn report_ci(suites: &[(&str, Vec<JunitCase>)], junit: Option<&Path>) -> Result<(), String> {
+    if let Some(path) = junit {
+        std::fs::write(path, junit_xml(suites))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        say!("JUnit report written to {}", path.display());
+    }
+    if std::env::var("GITHUB_ACTIONS").as_deref() != Ok("true") {
+        return Ok(());
+    }
+
+    let mut summary = String::from(
+        "### Needlepoint\n\n| Stage | Passed | Failed | Skipped |\n| --- | --- | --- | --- |\n",
+    );
+    let mut failures = String::new();
+    for (suite, cases) in suites {
+        let failed = cases.iter().filter(|c| c.failure.is_some()).count();
+        let skipped = cases.iter().filter(|c| c.skipped.is_some()).count();
+        summary.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            suite,
+            cases.len() - failed - skipped,
+            failed,
+            skipped
+        ));
+        for case in cases {
+            let Some(failure) = &case.failure else {
+                continue;
+            };
+            // Workflow commands take a single line, with newlines percent-encoded
+            let message = failure
+                .replace('%', "%25")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A");
+            say!("::error title={}: {}::{}", suite, case.name, message);
+            let first_line = failure.lines().next().unwrap_or_default();
+            failures.push_str(&format!("- **{}** {}: {}\n", suite, case.name, first_line));
+        }
+    }
+    if !failures.is_empty() {
+        summary.push_str(&format!("\nFailures:\n\n{}", failures));
+    }
+    if let Ok(path) = std::env::var("GITHUB_STEP_SUMMARY") {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        writeln!(file, "{}", summary).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// Print what `write-files` did; fails if files edited by hand were left alone,
+/// naming the flag that overwrites them
+fn print_written_files(result: &WriteFilesResponse, force_flag: &str) -> Result<(), String> {
+    for file in &result.written {
+        say!("  Wrote: {} -> {}", file.node_name, file.file_path);
+    }
+    for file in &result.merged {
+        if file.conflicts {
+            say!(
+                "  Merged with conflicts: {} -> {} (resolve the markers)",
+                file.node_name, file.file_path
             );
+        } else {
+            say!("  Merged manual edits: {} -> {}", file.node_name, file.file_path);
         }
+    }
+    for name in &result.skipped {
+        say!("  Skipped: {} (no generated code)", name);
+    }
 
-        Commands::New { path, name } => {
-            let abs_path = if path.is_absolute() {
-                path.to_string_lossy().to_string()
-            } else {
-                std::env::current_dir()
-                    .map_err(|e| format!("Failed to get current dir: {}", e))?
-                    .join(&path)
-                    .to_string_lossy()
-                    .to_string()
+    for file in &result.conflicts {
+        say!(
+            "  Edited manually, can't merge, not overwritten: {} -> {}",
+            file.node_name, file.file_path
+        );
+    }
+
+    say!(
+        "\nFiles written: {}, merged: {}, skipped: {}",
+        result.written.len(),
+        result.merged.len(),
+        result.skipped.len()
+    );
+    if !result.conflicts.is_empty() {
+        return Err(format!(
+            "{} files were edited manually since they were last written; \
+             rerun with {} to overwrite them",
+            result.conflicts.len(),
+            force_flag
+        ));
+    }
+    Ok(())
+}
+
+/// Print a node's status during `run` when it changes
+fn print_progress(change: &ChangeEvent, statuses: &mut HashMap<String, String>) {
+    let Some(node) = &change.node else {
+        return;
+    };
+    if statuses.get(&node.id) != Some(&node.status) {
+        statuses.insert(node.id.clone(), node.status.clone());
+        say!("  {:<11} {}", node.status, node.name);
+    }
+}
+
+fn print_change(change: &ChangeEvent) {
+    let subject = change
+        .node
+        .as_ref()
+        .map(|n| n.name.clone())
+        .or_else(|| change.node_id.clone())
+        .or_else(|| change.edge_id.clone())
+        .unwrap_or_default();
+    say!(
+        "  [{}] {} {} {}",
+        change.revision, change.timestamp, change.change_type, subject
+    );
+}
+
+/// Providers a comparison variant can name as its `provider:` prefix
+const PROVIDERS: [&str; 6] = ["anthropic", "openai", "mistral", "ollama", "local", "mock"];
+
+/// Request body for one side of a comparison: `model` or `provider:model`
+fn parse_variant(spec: &str) -> Value {
+    match spec.split_once(':') {
+        Some((provider, model)) if PROVIDERS.contains(&provider) => {
+            serde_json::json!({ "provider": provider, "model": model })
+        }
+        _ => serde_json::json!({ "model": spec }),
+    }
+}
+
+fn format_candidate(candidate: &Candidate) -> String {
+    let run = &candidate.run;
+    let mut line = format!("{} {} ({} ms", candidate.id, run.model, run.duration_ms);
+    if let Some(tokens) = run.tokens_used {
+        line.push_str(&format!(", {} tokens", tokens));
+    }
+    if let Some(cost) = run.cost_estimate {
+        line.push_str(&format!(", ${:.4}", cost));
+    }
+    line.push(')');
+    if !run.success {
+        line.push_str(" failed");
+    }
+    line
+}
+
+/// `a 3, b 1` with the largest counts first
+fn format_counts(counts: &HashMap<String, usize>) -> String {
+    let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(name, count)| format!("{} {}", name, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_impact(impact: &ImpactReport) {
+    if impact.changed_fields.is_empty() {
+        say!("The update changes nothing");
+        return;
+    }
+    say!("Changes: {}", impact.changed_fields.join(", "));
+    match &impact.regenerate {
+        Some(node) => say!("Regenerates {}: {}", node.name, node.reason),
+        None => say!("The node itself needs no regeneration"),
+    }
+    if !impact.stale.is_empty() {
+        say!("Stale dependents:");
+        for node in &impact.stale {
+            say!("  {}: {}", node.name, node.reason);
+        }
+    }
+    if impact.regenerate.is_some() || !impact.stale.is_empty() {
+        say!(
+            "Estimated: ~{} tokens, ~${:.2}, ~{}s",
+            impact.estimated_tokens,
+            impact.estimated_cost,
+            impact.estimated_duration_ms / 1000
+        );
+    }
+    if !impact.files.is_empty() {
+        say!("Files rewritten:");
+        for file in &impact.files {
+            let note = match (file.exists, file.manually_edited) {
+                (false, _) => " (new)",
+                (true, true) => " (edited by hand, would be merged)",
+                (true, false) => "",
             };
+            say!("  {}{}", file.file_path, note);
+        }
+    }
+}
+
+fn print_dependency_tree(tree: &DependencyTree, depth: usize) {
+    let marker = match (tree.repeated, tree.truncated) {
+        (true, _) => " (*)",
+        (_, true) => " ...",
+        _ => "",
+    };
+    let indent = "  ".repeat(depth);
+    say!("{}{} [{}]{}", indent, tree.name, tree.status, marker);
+    for child in &tree.children {
+        print_dependency_tree(child, depth + 1);
+    }
+}
 
-            let body = serde_json::json!({ "path": abs_path, "name": name });
-            let _: Value = post(client, &format!("{}/project/new", base_url), &body).await?;
-            println!("Created new project '{}' at: {}", name, abs_path);
+fn print_file_tree(entries: &[FileTreeEntry], depth: usize, node_names: &HashMap<String, String>) {
+    for entry in entries {
+        let indent = "  ".repeat(depth + 1);
+        if entry.is_dir {
+            say!("{}{}/", indent, entry.name);
+            print_file_tree(&entry.children, depth + 1, node_names);
+            continue;
+        }
+        match entry.node_id.as_ref().and_then(|id| node_names.get(id)) {
+            Some(node) => say!("{}{}  [{}]", indent, entry.name, node),
+            None => say!("{}{}", indent, entry.name),
         }
+    }
+}
 
-        Commands::Load { path } => {
-            let abs_path = std::fs::canonicalize(&path)
-                .map_err(|e| format!("Invalid path: {}", e))?
-                .to_string_lossy()
-                .to_string();
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Transcript {
+    timestamp: String,
+    model: String,
+    system_prompt: Option<String>,
+    prompt: String,
+    response: Option<String>,
+    error: Option<String>,
+    duration_ms: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct StatusTransition {
+    from: String,
+    to: String,
+    timestamp: String,
+    message: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CheckResult {
+    name: String,
+    success: bool,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    output: String,
+    error: Option<String>,
+}
+
+/// A test case of the JUnit report written by `ci`
+struct JunitCase {
+    name: String,
+    time_ms: u64,
+    failure: Option<String>,
+    skipped: Option<String>,
+}
+
+impl JunitCase {
+    fn new(name: &str, time_ms: u64, failure: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            time_ms,
+            failure,
+            skipped: None,
+        }
+    }
+}
 
-            let body = serde_json::json!({ "path": abs_path });
-            let _: Value = post(client, &format!("{}/project/load", base_url), &body).await?;
-            println!("Project loaded from: {}", abs_path);
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ImpactedNode {
+    name: String,
+    reason: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ImpactedFile {
+    file_path: String,
+    exists: bool,
+    manually_edited: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ImpactReport {
+    changed_fields: Vec<String>,
+    regenerate: Option<ImpactedNode>,
+    stale: Vec<ImpactedNode>,
+    estimated_tokens: u64,
+    estimated_cost: f64,
+    estimated_duration_ms: u64,
+    files: Vec<ImpactedFile>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct DependencyTree {
+    name: String,
+    status: String,
+    #[serde(default)]
+    children: Vec<DependencyTree>,
+    #[serde(default)]
+    repeated: bool,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NodeHistory {
+    transitions: Vec<StatusTransition>,
+    last_success: Option<String>,
+    failures: usize,
+    generation_durations_ms: Vec<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ConstraintViolation {
+    constraint: String,
+    message: String,
+    line: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SearchHit {
+    node_id: String,
+    node_name: String,
+    file_path: String,
+    score: f32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NodeViolations {
+    node_name: String,
+    violations: Vec<ConstraintViolation>,
+    #[serde(default)]
+    missing_exports: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ConsistencyIssue {
+    message: String,
+    suggestion: Option<String>,
+    line: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NodeConsistency {
+    node_name: String,
+    issues: Vec<ConsistencyIssue>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    id: String,
+    created_at: String,
+    code: Option<String>,
+    run: NodeRunReport,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LogEntry {
+    timestamp: String,
+    level: String,
+    target: String,
+    message: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Node {
+    id: String,
+    name: String,
+    file_path: String,
+    status: String,
+    description: String,
+    generated_code: Option<String>,
+    last_generation: Option<GenerationRecord>,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+impl Node {
+    /// Whether the run's time limit kept the node from being generated, as opposed to
+    /// it being disabled or never run
+    fn left_pending(&self) -> bool {
+        self.status == "pending" && self.error_message.as_deref() == Some(NOT_RUN_MESSAGE)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct GeneratedProject {
+    nodes: Vec<Node>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SharedType {
+    #[serde(default)]
+    description: String,
+    variants: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NodeSummary {
+    id: String,
+    name: String,
+    file_path: String,
+    status: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GenerationRecord {
+    model: String,
+    tokens_in: Option<u32>,
+    tokens_out: Option<u32>,
+    duration_ms: u64,
+    timestamp: String,
+    seed: Option<u64>,
+    note: Option<String>,
+    #[serde(default)]
+    post_processing: Vec<PostProcessReport>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PostProcessReport {
+    step: String,
+    changed: bool,
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Edge {
+    id: String,
+    source: String,
+    target: String,
+    label: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct EdgeSuggestion {
+    source: String,
+    target: String,
+    label: String,
+    confidence: f64,
+    reasons: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SplitSuggestion {
+    oversized: bool,
+    prompt_chars: usize,
+    code_lines: usize,
+    export_count: usize,
+    reasons: Vec<String>,
+    #[serde(default)]
+    proposal: Vec<ProposedNode>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ProposedNode {
+    name: String,
+    file_path: String,
+    description: String,
+    exports: Vec<String>,
+    depends_on: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionWave {
+    wave_number: u32,
+    node_ids: Vec<String>,
+    #[serde(default)]
+    estimated_duration_ms: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionPlan {
+    waves: Vec<ExecutionWave>,
+    total_nodes: usize,
+    #[serde(default)]
+    critical_path: Vec<String>,
+    #[serde(default)]
+    critical_path_ms: u64,
+    #[serde(default)]
+    estimated_duration_ms: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RunSummary {
+    id: String,
+    duration_ms: u64,
+    total_successful: usize,
+    total_failed: usize,
+    total_skipped: usize,
+    total_tokens: u64,
+    total_cost_estimate: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NodeRunReport {
+    node_name: String,
+    model: String,
+    success: bool,
+    duration_ms: u64,
+    tokens_used: Option<u32>,
+    cost_estimate: Option<f64>,
+    retries: u32,
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RunReport {
+    #[serde(flatten)]
+    summary: RunSummary,
+    nodes: Vec<NodeRunReport>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    number: u64,
+    url: String,
+    branch: String,
+    base_branch: String,
+    files: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ProviderModel {
+    id: String,
+    #[serde(default)]
+    chat: bool,
+    #[serde(default)]
+    fim: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiKeysRequest {
+    anthropic: Option<String>,
+    openai: Option<String>,
+    mistral: Option<String>,
+    ollama_base_url: Option<String>,
+    github: Option<String>,
+    proxy_url: Option<String>,
+}
+
+fn main() {
+    // Answer shell completion requests (see `completions`) before anything else
+    CompleteEnv::with_factory(Cli::command).complete();
+    run_cli();
+}
+
+#[tokio::main]
+async fn run_cli() {
+    let cli = Cli::parse();
+    let client = match build_client(cli.token.as_deref()) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let base_url = format!("http://{}:{}/api", cli.host, cli.port);
+    FORCE.store(cli.force, Ordering::Relaxed);
+    JSON_OUTPUT.store(cli.json, Ordering::Relaxed);
+
+    let result = run(&client, &base_url, cli.command).await;
+    if cli.json {
+        print_output(result.is_ok());
+    }
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(EXIT_CODE.load(Ordering::Relaxed));
+        }
+    }
+}
+
+async fn run(client: &Client, base_url: &str, command: Commands) -> Result<(), String> {
+    match command {
+        Commands::Status { tag } => {
+            let resp: StatusResponse = get(client, &format!("{}/status", base_url)).await?;
+            set_output(last_response());
+            say!("Status: {}", resp.status);
+            say!("Version: {}", resp.version);
+            if resp.read_only {
+                say!("Access: read-only");
+            }
+            say!(
+                "Project: {}{}",
+                if resp.project_loaded {
+                    resp.project_name.unwrap_or_else(|| "unnamed".to_string())
+                } else {
+                    "none loaded".to_string()
+                },
+                if resp.unsaved_changes { " (unsaved changes)" } else { "" }
+            );
+
+            if resp.project_loaded {
+                let mut url = reqwest::Url::parse(&format!("{}/project/stats", base_url))
+                    .map_err(|e| format!("Invalid URL: {}", e))?;
+                if let Some(tag) = &tag {
+                    url.query_pairs_mut().append_pair("tag", tag);
+                }
+                let stats: ProjectStats = get(client, url.as_str()).await?;
+                add_output("stats", last_response());
+                say!(
+                    "Nodes{}: {} ({})",
+                    tag.map(|t| format!(" tagged {}", t)).unwrap_or_default(),
+                    stats.node_count,
+                    format_counts(&stats.nodes_by_status)
+                );
+                if !stats.nodes_by_language.is_empty() {
+                    say!("Languages: {}", format_counts(&stats.nodes_by_language));
+                }
+                if !stats.nodes_by_tag.is_empty() {
+                    say!("Tags: {}", format_counts(&stats.nodes_by_tag));
+                }
+                say!(
+                    "Edges: {}, max depth: {}, avg fan-in/out: {:.1}/{:.1}, orphans: {}",
+                    stats.edge_count,
+                    stats.max_depth,
+                    stats.avg_fan_in,
+                    stats.avg_fan_out,
+                    stats.orphan_count
+                );
+                say!(
+                    "Generated: {} lines, ~{} tokens to regenerate",
+                    stats.generated_loc, stats.estimated_tokens
+                );
+            }
+        }
+
+        Commands::New {
+            path,
+            name,
+            template,
+        } => {
+            let abs_path = if path.is_absolute() {
+                path.to_string_lossy().to_string()
+            } else {
+                std::env::current_dir()
+                    .map_err(|e| format!("Failed to get current dir: {}", e))?
+                    .join(&path)
+                    .to_string_lossy()
+                    .to_string()
+            };
+
+            let body = serde_json::json!({ "path": abs_path, "name": name, "template": template });
+            let project: Value = post(client, &format!("{}/project/new", base_url), &body).await?;
+            say!("Created new project '{}' at: {}", name, abs_path);
+            if let Some(template) = template {
+                let nodes = project["nodes"].as_array().map_or(0, Vec::len);
+                say!("Added {} nodes from template '{}'", nodes, template);
+            }
+        }
+
+        Commands::Templates => {
+            let templates: Vec<ProjectTemplate> =
+                get(client, &format!("{}/templates", base_url)).await?;
+            for template in templates {
+                say!("{:<14} {}", template.id, template.name);
+                say!("{:<14} {}", "", template.description);
+            }
+        }
+
+        Commands::Load { path } => {
+            load_project(client, base_url, &path).await?;
+        }
+
+        Commands::Save => {
+            let _: Value = post(client, &format!("{}/project/save", base_url), &serde_json::json!({})).await?;
+            say!("Project saved");
+        }
+
+        Commands::Close { save, discard } => {
+            let _: Value = post(
+                client,
+                &format!("{}/project/close", base_url),
+                &serde_json::json!({ "save": save, "discard": discard }),
+            )
+            .await?;
+            say!("Project {}", if save { "saved and closed" } else { "closed" });
+        }
+
+        Commands::Recent { auto_load } => {
+            let url = format!("{}/recent-projects", base_url);
+            let recent: RecentProjects = match auto_load {
+                Some(enabled) => {
+                    let body = serde_json::json!({ "enabled": enabled });
+                    put(client, &format!("{}/auto-load", url), &body).await?
+                }
+                None => get(client, &url).await?,
+            };
+            if recent.projects.is_empty() {
+                say!("No recent projects");
+            }
+            for (i, project) in recent.projects.iter().enumerate() {
+                say!("{:>2}. {} ({})", i + 1, project.name, project.path);
+                say!("    opened {}", project.opened_at);
+            }
+            say!(
+                "Auto-load last project on startup: {}",
+                if recent.auto_load { "on" } else { "off" }
+            );
+        }
+
+        Commands::Nodes {
+            status,
+            language,
+            query,
+            path_prefix,
+            tag,
+            sort,
+            desc,
+            limit,
+            cursor,
+        } => {
+            let mut params: Vec<(&str, String)> =
+                vec![("fields", "name,filePath,status".to_string())];
+            for (key, value) in [
+                ("status", status),
+                ("language", language),
+                ("q", query),
+                ("path_prefix", path_prefix),
+                ("tag", tag),
+                ("sort", sort),
+                ("limit", limit.map(|l| l.to_string())),
+                ("cursor", cursor),
+            ] {
+                if let Some(value) = value {
+                    params.push((key, value));
+                }
+            }
+            if desc {
+                params.push(("desc", "true".to_string()));
+            }
+
+            let url = reqwest::Url::parse_with_params(&format!("{}/nodes", base_url), &params)
+                .map_err(|e| format!("Invalid URL: {}", e))?;
+            let (nodes, next_cursor): (Vec<NodeSummary>, _) =
+                get_page(client, url.as_str()).await?;
+            if nodes.is_empty() {
+                say!("No nodes in project");
+            } else {
+                say!("{:<36} {:<20} {:<12} {}", "ID", "NAME", "STATUS", "PATH");
+                say!("{}", "-".repeat(80));
+                for node in nodes {
+                    say!(
+                        "{:<36} {:<20} {:<12} {}",
+                        node.id,
+                        truncate(&node.name, 18),
+                        node.status,
+                        node.file_path
+                    );
+                }
+            }
+            if let Some(cursor) = next_cursor {
+                say!("\nMore nodes available: --cursor {}", cursor);
+            }
+        }
+
+        Commands::Search {
+            query,
+            semantic,
+            limit,
+        } => {
+            let hits: Vec<SearchHit> = post(
+                client,
+                &format!("{}/search", base_url),
+                &serde_json::json!({
+                    "query": query,
+                    "mode": if semantic { "semantic" } else { "text" },
+                    "limit": limit,
+                }),
+            )
+            .await?;
+            if hits.is_empty() {
+                say!("No matching nodes");
+            }
+            for hit in hits {
+                say!(
+                    "{:.3}  {:<36} {:<20} {}",
+                    hit.score,
+                    hit.node_id,
+                    truncate(&hit.node_name, 18),
+                    hit.file_path
+                );
+            }
+        }
+
+        Commands::Node { id } => {
+            let node: Node = get(client, &format!("{}/nodes/{}", base_url, id)).await?;
+            say!("ID: {}", node.id);
+            say!("Name: {}", node.name);
+            say!("Path: {}", node.file_path);
+            say!("Status: {}", node.status);
+            say!("Description: {}", node.description);
+            if let Some(gen) = &node.last_generation {
+                say!(
+                    "Last generation: {} at {} ({:.1}s, {} tokens in / {} tokens out)",
+                    gen.model,
+                    gen.timestamp,
+                    gen.duration_ms as f64 / 1000.0,
+                    gen.tokens_in.map(|t| t.to_string()).unwrap_or_else(|| "?".to_string()),
+                    gen.tokens_out.map(|t| t.to_string()).unwrap_or_else(|| "?".to_string())
+                );
+                if let Some(seed) = gen.seed {
+                    say!("Seed: {}", seed);
+                }
+                for report in &gen.post_processing {
+                    match &report.error {
+                        Some(error) => say!("Post-processing {}: failed: {}", report.step, error),
+                        None if report.changed => say!("Post-processing {}: changed", report.step),
+                        None => say!("Post-processing {}: unchanged", report.step),
+                    }
+                }
+                if let Some(note) = &gen.note {
+                    say!("Generation note:\n{}", note);
+                }
+            }
+            if let Some(code) = &node.generated_code {
+                say!("\n--- Generated Code ---\n{}", code);
+            }
+        }
+
+        Commands::AddNode {
+            name,
+            path,
+            language,
+            kind,
+            description,
+        } => {
+            let body = serde_json::json!({
+                "name": name,
+                "file_path": path,
+                "language": language,
+                "kind": kind,
+            });
+            let node: Node = post(client, &format!("{}/nodes", base_url), &body).await?;
+
+            // Update description if provided
+            if !description.is_empty() {
+                let update_body = serde_json::json!({ "description": description });
+                let _: Value = put(client, &format!("{}/nodes/{}", base_url, node.id), &update_body).await?;
+            }
+
+            say!("Created node: {} ({})", node.name, node.id);
+            say!("File path: {}", node.file_path);
+        }
+
+        Commands::UpdateNode {
+            id,
+            description,
+            purpose,
+            name,
+            patch,
+            dry_run,
+        } => {
+            let mut updates = match patch {
+                Some(patch) => match serde_json::from_str(&patch) {
+                    Ok(Value::Object(fields)) => fields,
+                    _ => return Err("--patch must be a JSON object".to_string()),
+                },
+                None => serde_json::Map::new(),
+            };
+            if let Some(d) = description {
+                updates.insert("description".to_string(), serde_json::Value::String(d));
+            }
+            if let Some(p) = purpose {
+                updates.insert("purpose".to_string(), serde_json::Value::String(p));
+            }
+            if let Some(n) = name {
+                updates.insert("name".to_string(), serde_json::Value::String(n));
+            }
+
+            if updates.is_empty() {
+                return Err("No updates specified".to_string());
+            }
+
+            if dry_run {
+                let impact: ImpactReport = post(
+                    client,
+                    &format!("{}/nodes/{}/impact", base_url, id),
+                    &serde_json::Value::Object(updates),
+                )
+                .await?;
+                set_output(last_response());
+                print_impact(&impact);
+                return Ok(());
+            }
+
+            let _: Value = put(
+                client,
+                &format!("{}/nodes/{}", base_url, id),
+                &serde_json::Value::Object(updates),
+            )
+            .await?;
+            say!("Updated node: {}", id);
+        }
+
+        Commands::SetExports {
+            id,
+            names,
+            types,
+            descriptions,
+            append,
+        } => {
+            if types.len() > names.len() || descriptions.len() > names.len() {
+                return Err("Each --type and --desc needs a --name".to_string());
+            }
+            let added: Vec<Value> = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    serde_json::json!({
+                        "name": name,
+                        "type": types.get(i).cloned().unwrap_or_default(),
+                        "description": descriptions.get(i).cloned().unwrap_or_default(),
+                    })
+                })
+                .collect();
+
+            let url = format!("{}/nodes/{}", base_url, id);
+            let mut exports = Vec::new();
+            if append {
+                let node: Value = get(client, &url).await?;
+                exports = node["exports"].as_array().cloned().unwrap_or_default();
+                exports.retain(|e| !names.iter().any(|name| e["name"] == name.as_str()));
+            }
+            exports.extend(added);
+
+            let resp: Value = put(
+                client,
+                &format!("{}/exports", url),
+                &serde_json::json!({ "exports": exports }),
+            )
+            .await?;
+            say!("Set {} exports on node: {}", exports.len(), id);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  now stale: {}", node_id);
+                }
+            }
+        }
+
+        Commands::DeleteNode { id, cascade } => {
+            let url = match cascade {
+                Some(cascade) => format!("{}/nodes/{}?cascade={}", base_url, id, cascade),
+                None => format!("{}/nodes/{}", base_url, id),
+            };
+            let resp: Value = delete(client, &url).await?;
+            say!("Deleted node: {}", id);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  affected dependent: {}", node_id);
+                }
+            }
+        }
+
+        Commands::Replace {
+            find,
+            replace,
+            regex,
+            fields,
+            dry_run,
+        } => {
+            let matches: Vec<ReplaceMatch> = post(
+                client,
+                &format!("{}/project/replace", base_url),
+                &serde_json::json!({
+                    "find": find,
+                    "replace": replace,
+                    "regex": regex,
+                    "fields": if fields.is_empty() { None } else { Some(fields) },
+                    "dry_run": dry_run,
+                }),
+            )
+            .await?;
+
+            for m in &matches {
+                say!("{} [{}]", m.node_name, m.field);
+                say!("  - {}", m.before);
+                say!("  + {}", m.after);
+            }
+            if dry_run {
+                say!("\n{} matches (dry run, nothing changed)", matches.len());
+            } else {
+                say!("\n{} replacements made", matches.len());
+            }
+        }
+
+        Commands::CloneNode {
+            id,
+            name,
+            file_path,
+            with_edges,
+        } => {
+            let node: Node = post(
+                client,
+                &format!("{}/nodes/{}/clone", base_url, id),
+                &serde_json::json!({
+                    "name": name,
+                    "file_path": file_path,
+                    "copy_edges": with_edges,
+                }),
+            )
+            .await?;
+            say!("Cloned node: {} ({}) -> {}", node.name, node.file_path, node.id);
+        }
+
+        Commands::SuggestSplit { id, llm } => {
+            let suggestion: SplitSuggestion = post(
+                client,
+                &format!("{}/nodes/{}/suggest-split", base_url, id),
+                &serde_json::json!({ "llm": llm }),
+            )
+            .await?;
+            set_output(last_response());
+
+            say!(
+                "Prompt: {} chars, code: {} lines, exports: {}",
+                suggestion.prompt_chars,
+                suggestion.code_lines,
+                suggestion.export_count
+            );
+            if suggestion.oversized {
+                say!("The node is oversized:");
+                for reason in &suggestion.reasons {
+                    say!("  - {}", reason);
+                }
+            } else {
+                say!("The node is within the size thresholds");
+            }
+            if !suggestion.proposal.is_empty() {
+                say!("\nProposed split:");
+            }
+            for node in &suggestion.proposal {
+                say!("  {} ({})", node.name, node.file_path);
+                say!("    {}", node.description);
+                if !node.exports.is_empty() {
+                    say!("    Exports: {}", node.exports.join(", "));
+                }
+                if !node.depends_on.is_empty() {
+                    say!("    Depends on: {}", node.depends_on.join(", "));
+                }
+            }
+        }
+
+        Commands::Edges => {
+            let edges: Vec<Edge> = get(client, &format!("{}/edges", base_url)).await?;
+            if edges.is_empty() {
+                say!("No edges in project");
+            } else {
+                say!("{:<36} {:<36} {}", "SOURCE", "TARGET", "LABEL");
+                say!("{}", "-".repeat(90));
+                for edge in edges {
+                    say!(
+                        "{:<36} {:<36} {}",
+                        edge.source,
+                        edge.target,
+                        edge.label
+                    );
+                }
+            }
+        }
+
+        Commands::SuggestEdges {
+            node,
+            min_confidence,
+            accept,
+        } => {
+            let mut url = format!(
+                "{}/project/suggest-edges?minConfidence={}",
+                base_url, min_confidence
+            );
+            if let Some(node) = &node {
+                url.push_str(&format!("&node={}", node));
+            }
+            let suggestions: Vec<EdgeSuggestion> = get(client, &url).await?;
+            if accept {
+                add_output("suggestions", last_response());
+            } else {
+                set_output(last_response());
+            }
+
+            if suggestions.is_empty() {
+                say!("No missing edges found");
+            }
+            let (mut created, mut failed) = (Vec::new(), Vec::new());
+            for suggestion in &suggestions {
+                say!(
+                    "{} -> {} ({:.0}%)",
+                    suggestion.source,
+                    suggestion.target,
+                    suggestion.confidence * 100.0
+                );
+                for reason in &suggestion.reasons {
+                    say!("    {}", reason);
+                }
+                if accept {
+                    let body = serde_json::json!({
+                        "source": suggestion.source,
+                        "target": suggestion.target,
+                        "label": suggestion.label,
+                    });
+                    // An earlier edge can make a later one close a cycle; skip it and go on
+                    match post::<Edge, _>(client, &format!("{}/edges", base_url), &body).await {
+                        Ok(edge) => {
+                            say!("    Created edge {}", edge.id);
+                            created.push(edge.id);
+                        }
+                        Err(error) => {
+                            say!("    Skipped: {}", error);
+                            failed.push(serde_json::json!({
+                                "source": suggestion.source,
+                                "target": suggestion.target,
+                                "error": error,
+                            }));
+                        }
+                    }
+                }
+            }
+            if accept {
+                if !failed.is_empty() {
+                    say!("\n{} of {} edges couldn't be created", failed.len(), suggestions.len());
+                }
+                add_output("created", serde_json::json!(created));
+                add_output("failed", Value::Array(failed));
+            }
+        }
+
+        Commands::AddEdge {
+            source,
+            target,
+            label,
+        } => {
+            let body = serde_json::json!({
+                "source": source,
+                "target": target,
+                "label": label,
+            });
+            let edge: Edge = post(client, &format!("{}/edges", base_url), &body).await?;
+            say!("Created edge: {} -> {} ({})", source, target, edge.id);
+        }
+
+        Commands::UpdateEdge {
+            id,
+            source,
+            target,
+            label,
+        } => {
+            if source.is_none() && target.is_none() && label.is_none() {
+                return Err("No updates specified".to_string());
+            }
+            let body = serde_json::json!({
+                "source": source,
+                "target": target,
+                "label": label,
+            });
+            let edge: Edge = put(client, &format!("{}/edges/{}", base_url, id), &body).await?;
+            say!("Updated edge: {} -> {} ({})", edge.source, edge.target, edge.id);
+        }
+
+        Commands::DeleteEdge { id } => {
+            let _: Value = delete(client, &format!("{}/edges/{}", base_url, id)).await?;
+            say!("Deleted edge: {}", id);
+        }
+
+        Commands::Snippets => {
+            let snippets: HashMap<String, String> =
+                get(client, &format!("{}/snippets", base_url)).await?;
+            if snippets.is_empty() {
+                say!("No snippets");
+            }
+            let mut names: Vec<&String> = snippets.keys().collect();
+            names.sort();
+            for name in names {
+                say!("{}:", name);
+                for line in snippets[name].lines() {
+                    say!("  {}", line);
+                }
+            }
+        }
+
+        Commands::SetSnippet { name, text } => {
+            let resp: Value = put(
+                client,
+                &named_url(base_url, "snippets", &name)?,
+                &serde_json::json!({ "text": text }),
+            )
+            .await?;
+            say!("Saved snippet: {}", name);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  now stale: {}", node_id);
+                }
+            }
+        }
+
+        Commands::DeleteSnippet { name } => {
+            let resp: Value = delete(client, &named_url(base_url, "snippets", &name)?).await?;
+            say!("Deleted snippet: {}", name);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  now stale: {}", node_id);
+                }
+            }
+        }
+
+        Commands::SharedTypes => {
+            let shared_types: HashMap<String, SharedType> =
+                get(client, &format!("{}/shared-types", base_url)).await?;
+            if shared_types.is_empty() {
+                say!("No shared types");
+            }
+            let mut names: Vec<&String> = shared_types.keys().collect();
+            names.sort();
+            for name in names {
+                let shared_type = &shared_types[name];
+                let mut languages: Vec<&String> = shared_type.variants.keys().collect();
+                languages.sort();
+                let languages: Vec<&str> = languages.into_iter().map(String::as_str).collect();
+                say!("{} ({})", name, languages.join(", "));
+                if !shared_type.description.is_empty() {
+                    say!("  {}", shared_type.description);
+                }
+            }
+        }
+
+        Commands::SetSharedType {
+            name,
+            variants,
+            description,
+        } => {
+            let mut definitions = serde_json::Map::new();
+            for variant in &variants {
+                let (language, file) = variant
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected LANG=FILE, got '{}'", variant))?;
+                let definition = std::fs::read_to_string(file)
+                    .map_err(|e| format!("Failed to read {}: {}", file, e))?;
+                definitions.insert(language.to_string(), Value::String(definition));
+            }
+            let resp: Value = put(
+                client,
+                &named_url(base_url, "shared-types", &name)?,
+                &serde_json::json!({
+                    "description": description.unwrap_or_default(),
+                    "variants": definitions,
+                }),
+            )
+            .await?;
+            say!("Saved shared type: {}", name);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  now stale: {}", node_id);
+                }
+            }
+        }
+
+        Commands::DeleteSharedType { name } => {
+            let resp: Value = delete(client, &named_url(base_url, "shared-types", &name)?).await?;
+            say!("Deleted shared type: {}", name);
+            if let Some(affected) = resp.get("affected").and_then(|a| a.as_array()) {
+                for node_id in affected.iter().filter_map(|a| a.as_str()) {
+                    say!("  now stale: {}", node_id);
+                }
+            }
+        }
+
+        Commands::Plan { id, concurrency } => {
+            let url = match (id, concurrency) {
+                (Some(id), _) => format!("{}/execution-plan/{}", base_url, id),
+                (None, Some(c)) => format!("{}/execution-plan?concurrency={}", base_url, c),
+                (None, None) => format!("{}/execution-plan", base_url),
+            };
+            let plan: ExecutionPlan = get(client, &url).await?;
+            print_plan(plan);
+        }
+
+        Commands::Transcript { id, last } => {
+            let transcripts: Vec<Transcript> =
+                get(client, &format!("{}/nodes/{}/transcript", base_url, id)).await?;
+            if transcripts.is_empty() {
+                say!("No transcripts recorded (enable recordTranscripts in the project manifest)");
+            }
+            let skip = last.map(|n| transcripts.len().saturating_sub(n)).unwrap_or(0);
+            for t in transcripts.iter().skip(skip) {
+                say!("=== {} {} ({} ms)", t.timestamp, t.model, t.duration_ms);
+                if let Some(system) = &t.system_prompt {
+                    say!("--- system\n{}", system);
+                }
+                say!("--- prompt\n{}", t.prompt);
+                match (&t.response, &t.error) {
+                    (Some(response), _) => say!("--- response\n{}", response),
+                    (None, Some(error)) => say!("--- error\n{}", error),
+                    (None, None) => {}
+                }
+                say!();
+            }
+        }
+
+        Commands::Deps { id, reverse, depth } => {
+            let (direction, none) = match reverse {
+                true => ("down", "No dependents"),
+                false => ("up", "No dependencies"),
+            };
+            let mut params = vec![("direction", direction.to_string())];
+            if let Some(depth) = depth {
+                params.push(("depth", depth.to_string()));
+            }
+            let url = reqwest::Url::parse_with_params(
+                &format!("{}/nodes/{}/tree", base_url, id),
+                &params,
+            )
+            .map_err(|e| format!("Invalid URL: {}", e))?;
+            let tree: DependencyTree = get(client, url.as_str()).await?;
+            set_output(last_response());
+            print_dependency_tree(&tree, 0);
+            if tree.children.is_empty() {
+                say!("{}", none);
+            }
+        }
+
+        Commands::History { id, last } => {
+            let history: NodeHistory =
+                get(client, &format!("{}/nodes/{}/history", base_url, id)).await?;
+            if history.transitions.is_empty() {
+                say!("No status changes recorded");
+            }
+            let skip = last
+                .map(|n| history.transitions.len().saturating_sub(n))
+                .unwrap_or(0);
+            for t in history.transitions.iter().skip(skip) {
+                match &t.message {
+                    Some(message) => say!("{}  {} -> {}: {}", t.timestamp, t.from, t.to, message),
+                    None => say!("{}  {} -> {}", t.timestamp, t.from, t.to),
+                }
+            }
+            say!(
+                "Last success: {}",
+                history.last_success.as_deref().unwrap_or("never")
+            );
+            say!("Failures: {}", history.failures);
+            let durations = &history.generation_durations_ms;
+            if !durations.is_empty() {
+                let average = durations.iter().sum::<u64>() / durations.len() as u64;
+                say!(
+                    "Generations: {} (average {} ms, last {} ms)",
+                    durations.len(),
+                    average,
+                    durations[durations.len() - 1]
+                );
+            }
+        }
+
+        Commands::Compare { id, a, b } => {
+            say!("Generating {} with {} and {}...", id, a, b);
+            let candidates: Vec<Candidate> = post(
+                client,
+                &format!("{}/nodes/{}/generate-compare", base_url, id),
+                &serde_json::json!({ "a": parse_variant(&a), "b": parse_variant(&b) }),
+            )
+            .await?;
+            for (label, candidate) in ["a", "b"].iter().zip(&candidates) {
+                say!("=== [{}] {}", label, format_candidate(candidate));
+                match (&candidate.code, &candidate.run.error) {
+                    (Some(code), _) => say!("{}", code),
+                    (None, Some(error)) => say!("Error: {}", error),
+                    (None, None) => {}
+                }
+                say!();
+            }
+            say!("Keep one with: needlepoint-cli accept {} <candidate-id>", id);
+        }
+
+        Commands::Candidates { id } => {
+            let candidates: Vec<Candidate> =
+                get(client, &format!("{}/nodes/{}/candidates", base_url, id)).await?;
+            if candidates.is_empty() {
+                say!("No candidates (generate some with `compare`)");
+            }
+            for candidate in &candidates {
+                say!("{}  {}", candidate.created_at, format_candidate(candidate));
+            }
+        }
+
+        Commands::Accept {
+            id,
+            candidate_id,
+            adopt_config,
+        } => {
+            let node: Value = post(
+                client,
+                &format!("{}/nodes/{}/candidates/{}/accept", base_url, id, candidate_id),
+                &serde_json::json!({ "adopt_config": adopt_config }),
+            )
+            .await?;
+            say!(
+                "Accepted candidate {} for {}",
+                candidate_id,
+                node.get("name").and_then(|n| n.as_str()).unwrap_or(&id)
+            );
+        }
+
+        Commands::Prompt { id } => {
+            let resp: Value = get(client, &format!("{}/prompt/{}", base_url, id)).await?;
+            if let Some(prompt) = resp.get("prompt").and_then(|p| p.as_str()) {
+                say!("{}", prompt);
+            }
+        }
+
+        Commands::Generate {
+            id,
+            with_deps,
+            skip_completed,
+            force,
+        } => {
+            say!("Generating code for node {}...", id);
+            let resp: Value = post(
+                client,
+                &format!(
+                    "{}/generate/{}?with_deps={}&skip_completed={}&force={}",
+                    base_url, id, with_deps, skip_completed, force
+                ),
+                &serde_json::json!({}),
+            )
+            .await?;
+            if let Some(deps) = resp.get("generated_dependencies").and_then(|d| d.as_array()) {
+                say!("Generated {} dependencies first", deps.len());
+            }
+            if let Some(code) = resp.get("code").and_then(|c| c.as_str()) {
+                say!("\n--- Generated Code ---\n{}", code);
+            }
+        }
+
+        Commands::GenerateAll {
+            consistency_pass,
+            skip_completed,
+            force,
+            tags,
+            profile,
+            step,
+            max_duration,
+        } => {
+            let nodes = match tags.as_slice() {
+                [] => "all nodes".to_string(),
+                tags => format!("nodes tagged {}", tags.join(", ")),
+            };
+            match &profile {
+                Some(profile) => {
+                    say!("Generating code for {} with profile {}...", nodes, profile)
+                }
+                None => say!("Generating code for {}...", nodes),
+            }
+            let generated: GeneratedProject = generate_all(
+                client,
+                base_url,
+                &serde_json::json!({
+                    "consistency_pass": consistency_pass,
+                    "skip_completed": skip_completed,
+                    "force": force,
+                    "tags": tags,
+                    "profile": profile,
+                    "step": step,
+                    "max_duration_secs": max_duration,
+                }),
+            )
+            .await?;
+            let not_run = generated.nodes.iter().filter(|n| n.left_pending()).count();
+            if not_run > 0 {
+                say!("Time limit reached: {} nodes left pending", not_run);
+            } else {
+                say!("Generation complete!");
+            }
+        }
+
+        Commands::Resume { discard } => {
+            let url = format!("{}/execution/interrupted", base_url);
+            let interrupted: Option<InterruptedRun> = get(client, &url).await?;
+            match interrupted {
+                None => say!("No interrupted run"),
+                Some(interrupted) if discard => {
+                    let _: Value = delete(client, &url).await?;
+                    say!("Discarded the run started at {}", interrupted.started_at);
+                }
+                Some(interrupted) => {
+                    say!(
+                        "Resuming the run started at {}: {} of {} nodes generated, {} to go...",
+                        interrupted.started_at,
+                        interrupted.finished_nodes,
+                        interrupted.total_nodes,
+                        interrupted.remaining.len()
+                    );
+                    let resumed: GeneratedProject = post(
+                        client,
+                        &format!("{}/execution/resume", base_url),
+                        &serde_json::json!({}),
+                    )
+                    .await?;
+                    let failed = resumed.nodes.iter().filter(|n| n.status == "error").count();
+                    say!("Generation complete! ({} failed)", failed);
+                }
+            }
+        }
+
+        Commands::Pilot { wave, time_limit } => {
+            say!("Generating wave {} as a pilot...", wave);
+            let report: PilotReport = post(
+                client,
+                &format!("{}/pilot", base_url),
+                &serde_json::json!({ "wave": wave, "time_limit_secs": time_limit }),
+            )
+            .await?;
+
+            say!(
+                "{:<20} {:<6} {:>8} {:>8} {:>9} {:>7} {:>6}",
+                "NODE", "OK", "TIME", "TOKENS", "COST", "EXPORTS", "LINES"
+            );
+            say!("{}", "-".repeat(72));
+            for node in &report.nodes {
+                say!(
+                    "{:<20} {:<6} {:>7}s {:>8} {:>9} {:>7} {:>6}",
+                    truncate(&node.node_name, 18),
+                    if node.success { "yes" } else { "no" },
+                    node.duration_ms / 1000,
+                    node.tokens_used.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string()),
+                    node.cost_estimate
+                        .map(|c| format!("${:.4}", c))
+                        .unwrap_or_else(|| "-".to_string()),
+                    format!(
+                        "{}/{}",
+                        node.declared_exports - node.missing_exports.len(),
+                        node.declared_exports
+                    ),
+                    node.lines_of_code
+                );
+                if !node.missing_exports.is_empty() {
+                    say!("    missing exports: {}", node.missing_exports.join(", "));
+                }
+                if let Some(error) = &node.error {
+                    say!("    error: {}", error);
+                }
+            }
+
+            say!();
+            say!(
+                "Wave {}: {:.1}s{}",
+                report.wave_number,
+                report.duration_ms as f64 / 1000.0,
+                if report.timed_out { " (time limit reached)" } else { "" }
+            );
+            say!("Export coverage: {:.0}%", report.export_coverage * 100.0);
+            say!("Tokens: {}", report.total_tokens);
+            say!("Estimated cost: ${:.4}", report.total_cost_estimate);
+            if let Some(projected) = report.projected_total_cost {
+                say!("Projected cost for the full graph: ${:.4}", projected);
+            }
+        }
+
+        Commands::WriteFiles { force, tag } => {
+            let result: WriteFilesResponse = post(
+                client,
+                &format!("{}/project/write-files", base_url),
+                &serde_json::json!({ "force": force, "tag": tag }),
+            )
+            .await?;
+            set_output(last_response());
+
+            print_written_files(&result, "--force")?;
+        }
+
+        Commands::Run {
+            path,
+            skip_completed,
+            profile,
+            force_write,
+            save,
+            max_duration,
+        } => {
+            load_project(client, base_url, &path).await?;
+
+            say!("\nValidating...");
+            let validation: ValidateResponse =
+                get(client, &format!("{}/project/validate", base_url)).await?;
+            add_output("validation", last_response());
+            print_validation(&validation)?;
+
+            say!();
+            let plan: ExecutionPlan = get(client, &format!("{}/execution-plan", base_url)).await?;
+            add_output("plan", last_response());
+            let total_nodes = plan.total_nodes;
+            print_plan(plan);
+            let stats: ProjectStats = get(client, &format!("{}/project/stats", base_url)).await?;
+            add_output("stats", last_response());
+            say!(
+                "Estimated cost: ~${:.2} (~{} tokens)",
+                stats.estimated_cost, stats.estimated_tokens
+            );
+
+            // Report progress from the change feed while the nodes generate
+            say!("\nGenerating {} nodes...", total_nodes);
+            let status: StatusResponse = get(client, &format!("{}/status", base_url)).await?;
+            let url = format!("{}/project/changes/stream?since={}", base_url, status.revision);
+            let progress = tokio::spawn({
+                let client = client.clone();
+                async move {
+                    let mut statuses = HashMap::new();
+                    follow_changes(&client, &url, |change, _| {
+                        print_progress(&change, &mut statuses)
+                    })
+                    .await
+                }
+            });
+            let generated: Result<GeneratedProject, String> = generate_all(
+                client,
+                base_url,
+                &serde_json::json!({
+                    "skip_completed": skip_completed,
+                    "profile": profile,
+                    "max_duration_secs": max_duration,
+                }),
+            )
+            .await;
+            progress.abort();
+            let generated = generated?;
+            let nodes = generated.nodes.iter().map(|n| {
+                serde_json::json!({
+                    "id": n.id,
+                    "name": n.name,
+                    "status": n.status,
+                    "errorMessage": n.error_message,
+                })
+            });
+            add_output("nodes", Value::Array(nodes.collect()));
+
+            let failed: Vec<&Node> =
+                generated.nodes.iter().filter(|n| n.status == "error").collect();
+            let complete = generated
+                .nodes
+                .iter()
+                .filter(|n| n.status == "complete" || n.status == "warning")
+                .count();
+            say!("\nComplete: {}, failed: {}", complete, failed.len());
+            let not_run = generated.nodes.iter().filter(|n| n.left_pending()).count();
+            if not_run > 0 {
+                say!("Time limit reached: {} nodes left pending", not_run);
+            }
+
+            say!("\nWriting files...");
+            let written: WriteFilesResponse = post(
+                client,
+                &format!("{}/project/write-files", base_url),
+                &serde_json::json!({ "force": force_write }),
+            )
+            .await?;
+            add_output("files", last_response());
+            print_written_files(&written, "--force-write")?;
+
+            if save {
+                let _: Value =
+                    post(client, &format!("{}/project/save", base_url), &serde_json::json!({}))
+                        .await?;
+                say!("Project saved");
+            }
+
+            if !failed.is_empty() {
+                for node in &failed {
+                    say!(
+                        "  Failed: {}: {}",
+                        node.name,
+                        node.error_message.as_deref().unwrap_or("unknown error")
+                    );
+                }
+                return Err(format!("{} of {} nodes failed", failed.len(), total_nodes));
+            }
+            if not_run > 0 {
+                return Err(format!(
+                    "{} of {} nodes weren't generated in time",
+                    not_run, total_nodes
+                ));
+            }
+        }
+
+        Commands::Ci {
+            path,
+            junit,
+            profile,
+            force_write,
+            save,
+            max_duration,
+            no_checks,
+            pull_request,
+        } => {
+            load_project(client, base_url, &path).await?;
+
+            let mut suites: Vec<(&str, Vec<JunitCase>)> = Vec::new();
+            // Every stage after validation runs, so the report covers them all; the
+            // first one that failed decides the exit status
+            let outcome = async {
+                say!("\nValidating...");
+                let validation: ValidateResponse =
+                    get(client, &format!("{}/project/validate", base_url)).await?;
+                add_output("validation", last_response());
+                let failure = (!validation.valid).then(|| validation.errors.join("\n"));
+                suites.push(("validation", vec![JunitCase::new("validate", 0, failure)]));
+                print_validation(&validation).map_err(|e| ci_failure(EXIT_INVALID, e))?;
+                let mut failed_stage: Option<(i32, String)> = None;
+
+                let before: Vec<NodeSummary> = get(
+                    client,
+                    &format!("{}/nodes?fields=name,filePath,status", base_url),
+                )
+                .await?;
+                let runs_before: Vec<RunSummary> =
+                    get(client, &format!("{}/runs", base_url)).await?;
+                let stale = before.iter().filter(|n| n.status != "complete").count();
+                say!("\nGenerating {} nodes that aren't complete...", stale);
+                let generated: GeneratedProject = generate_all(
+                    client,
+                    base_url,
+                    &serde_json::json!({
+                        "skip_completed": true,
+                        "profile": profile,
+                        "max_duration_secs": max_duration,
+                    }),
+                )
+                .await?;
+                let runs: Vec<RunSummary> = get(client, &format!("{}/runs", base_url)).await?;
+                let new_run = runs
+                    .first()
+                    .filter(|r| !runs_before.iter().any(|b| b.id == r.id));
+                let run: Option<RunReport> = match new_run {
+                    Some(run) => Some(get(client, &format!("{}/runs/{}", base_url, run.id)).await?),
+                    None => None,
+                };
+
+                let mut cases: Vec<JunitCase> = before
+                    .iter()
+                    .filter(|n| n.status == "complete")
+                    .map(|n| JunitCase {
+                        skipped: Some("Up to date".to_string()),
+                        ..JunitCase::new(&n.name, 0, None)
+                    })
+                    .collect();
+                for node in run.iter().flat_map(|r| &r.nodes) {
+                    let failure = match (&node.error, node.success) {
+                        (Some(error), _) => Some(error.clone()),
+                        (None, false) => Some("Generation failed".to_string()),
+                        (None, true) => None,
+                    };
+                    cases.push(JunitCase::new(&node.node_name, node.duration_ms, failure));
+                }
+                if max_duration.is_some() {
+                    for node in generated.nodes.iter().filter(|n| n.left_pending()) {
+                        let failure = Some("Not generated within the time limit".to_string());
+                        cases.push(JunitCase::new(&node.name, 0, failure));
+                    }
+                }
+                let failed = cases.iter().filter(|c| c.failure.is_some()).count();
+                for case in &cases {
+                    if let Some(failure) = &case.failure {
+                        say!("  Failed: {}: {}", case.name, failure);
+                    }
+                }
+                if let Some(run) = &run {
+                    say!(
+                        "Generated {} nodes, {} failed (~{} tokens, ${:.4})",
+                        run.nodes.len(),
+                        run.summary.total_failed,
+                        run.summary.total_tokens,
+                        run.summary.total_cost_estimate
+                    );
+                }
+                suites.push(("generation", cases));
+                if failed > 0 {
+                    let message = format!("{} nodes weren't generated", failed);
+                    failed_stage.get_or_insert((EXIT_GENERATION_FAILED, message));
+                }
+
+                say!("\nWriting files...");
+                let written: WriteFilesResponse = post(
+                    client,
+                    &format!("{}/project/write-files", base_url),
+                    &serde_json::json!({ "force": force_write }),
+                )
+                .await?;
+                add_output("files", last_response());
+                let mut cases: Vec<JunitCase> = written
+                    .written
+                    .iter()
+                    .map(|f| JunitCase::new(&f.file_path, 0, None))
+                    .collect();
+                for file in &written.merged {
+                    let failure = file
+                        .conflicts
+                        .then(|| "Merged with manual edits, with conflicts to resolve".to_string());
+                    cases.push(JunitCase::new(&file.file_path, 0, failure));
+                }
+                for file in &written.conflicts {
+                    let failure =
+                        "Edited manually and can't be merged; not overwritten".to_string();
+                    cases.push(JunitCase::new(&file.file_path, 0, Some(failure)));
+                }
+                let conflicted = cases.iter().any(|c| c.failure.is_some());
+                suites.push(("files", cases));
+                if let Err(message) = print_written_files(&written, "--force-write") {
+                    failed_stage.get_or_insert((EXIT_WRITE_FAILED, message));
+                } else if conflicted {
+                    let message = "Some files were merged with conflicts".to_string();
+                    failed_stage.get_or_insert((EXIT_WRITE_FAILED, message));
+                }
+
+                if !no_checks {
+                    let checks: Vec<CheckResult> = post(
+                        client,
+                        &format!("{}/project/checks", base_url),
+                        &serde_json::json!({}),
+                    )
+                    .await?;
+                    add_output("checks", last_response());
+                    if !checks.is_empty() {
+                        say!("\nChecks:");
+                    }
+                    let mut cases = Vec::new();
+                    for check in &checks {
+                        let time = format!("{:.1}s", check.duration_ms as f64 / 1000.0);
+                        if check.success {
+                            say!("  Passed: {} ({})", check.name, time);
+                            cases.push(JunitCase::new(&check.name, check.duration_ms, None));
+                            continue;
+                        }
+                        let reason = match (&check.error, check.exit_code) {
+                            (Some(error), _) => error.clone(),
+                            (None, Some(code)) => format!("Exited with {}", code),
+                            (None, None) => "Stopped by a signal".to_string(),
+                        };
+                        say!("  Failed: {} ({}): {}", check.name, time, reason);
+                        if !check.output.is_empty() {
+                            say!("{}", check.output);
+                        }
+                        let failure = Some(format!("{}\n{}", reason, check.output));
+                        cases.push(JunitCase::new(&check.name, check.duration_ms, failure));
+                    }
+                    let failed = checks.iter().filter(|c| !c.success).count();
+                    suites.push(("checks", cases));
+                    if failed > 0 {
+                        let message = format!("{} of {} checks failed", failed, checks.len());
+                        failed_stage.get_or_insert((EXIT_CHECKS_FAILED, message));
+                    }
+                }
+
+                if save {
+                    let _: Value = post(
+                        client,
+                        &format!("{}/project/save", base_url),
+                        &serde_json::json!({}),
+                    )
+                    .await?;
+                    say!("Project saved");
+                }
+
+                match failed_stage {
+                    Some((code, message)) => Err(ci_failure(code, message)),
+                    None => Ok(run.map(|r| r.summary.id)),
+                }
+            }
+            .await;
+
+            report_ci(&suites, junit.as_deref())?;
+            let run_id = outcome?;
+            say!("\nCI passed");
+
+            match run_id.filter(|_| pull_request) {
+                Some(id) => {
+                    let url = format!("{}/runs/{}/pull-request", base_url, id);
+                    let pull: PullRequest = post(client, &url, &serde_json::json!({})).await?;
+                    add_output("pullRequest", last_response());
+                    say!("Opened pull request #{}: {}", pull.number, pull.url);
+                }
+                None if pull_request => say!("Nothing was generated, so no pull request"),
+                None => {}
+            }
+        }
+
+        Commands::Files => {
+            let files: ProjectFiles = get(client, &format!("{}/project/files", base_url)).await?;
+            set_output(last_response());
+            let nodes: Vec<NodeSummary> =
+                get(client, &format!("{}/nodes?fields=name,filePath,status", base_url)).await?;
+            let node_names: HashMap<String, String> =
+                nodes.into_iter().map(|n| (n.id, n.name)).collect();
+
+            say!("Project files ([name] = generated by node):\n");
+            print_file_tree(&files.entries, 0, &node_names);
+
+            if !files.missing.is_empty() {
+                say!("\nNode files not on disk:");
+                for path in &files.missing {
+                    say!("  {}", path);
+                }
+            }
+        }
+
+        Commands::Validate => {
+            let result: ValidateResponse =
+                get(client, &format!("{}/project/validate", base_url)).await?;
+            set_output(last_response());
+
+            print_validation(&result)?;
+        }
+
+        Commands::LintSpecs => {
+            let hints: Vec<SpecHint> =
+                get(client, &format!("{}/project/lint-specs", base_url)).await?;
+            set_output(last_response());
+
+            if hints.is_empty() {
+                say!("No spec problems found");
+            }
+            let mut current = None;
+            for hint in &hints {
+                if current != Some(&hint.node_id) {
+                    say!("{}:", hint.node_name);
+                    current = Some(&hint.node_id);
+                }
+                say!("  - {}", hint.message);
+                say!("    {}", hint.suggestion);
+            }
+        }
+
+        Commands::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        } => {
+            let read = |path: &Path| read_project_file(path).map_err(|e| format!("{:#}", e));
+            let merge = merge_projects(&read(&base)?, &read(&ours)?, &read(&theirs)?)
+                .map_err(|e| format!("{:#}", e))?;
+            let output = output.unwrap_or(ours);
+            write_project_file(&merge.project, &output).map_err(|e| format!("{:#}", e))?;
+            set_output(serde_json::json!({
+                "output": output,
+                "conflicts": merge.conflicts,
+            }));
+
+            if merge.conflicts.is_empty() {
+                say!("Merged cleanly into {}", output.display());
+            } else {
+                say!("Merged into {}, keeping our side of:", output.display());
+                for conflict in &merge.conflicts {
+                    say!("  {}", conflict.describe());
+                }
+                return Err(format!("{} merge conflict(s)", merge.conflicts.len()));
+            }
+        }
+
+        Commands::Reconcile {
+            create_nodes,
+            apply_renames,
+        } => {
+            let result: ReconcileResponse = post(
+                client,
+                &format!("{}/project/reconcile", base_url),
+                &serde_json::json!({
+                    "create_nodes": create_nodes,
+                    "apply_renames": apply_renames,
+                }),
+            )
+            .await?;
+
+            for node in &result.missing {
+                say!("  Missing: {} -> {}", node.node_name, node.file_path);
+            }
+            for rename in &result.renamed {
+                let action = if result.renames_applied { "Renamed" } else { "Moved" };
+                say!("  {}: {} ({} -> {})", action, rename.node_name, rename.from, rename.to);
+            }
+            let created: Vec<&str> = result.created.iter().map(|n| n.file_path.as_str()).collect();
+            for file in &result.untracked {
+                if created.contains(&file.file_path.as_str()) {
+                    say!("  Created node: {}", file.file_path);
+                } else if let Some(language) = &file.language {
+                    say!("  Untracked: {} ({})", file.file_path, language);
+                } else {
+                    say!("  Untracked: {}", file.file_path);
+                }
+            }
+
+            say!(
+                "\nMissing: {}, renamed: {}, untracked: {}, nodes created: {}",
+                result.missing.len(),
+                result.renamed.len(),
+                result.untracked.len(),
+                result.created.len()
+            );
+            if !result.renamed.is_empty() && !result.renames_applied {
+                say!("Run with --apply-renames to update the moved nodes");
+            }
+            if !create_nodes && result.untracked.iter().any(|f| f.language.is_some()) {
+                say!("Run with --create-nodes to add nodes for untracked source files");
+            }
+        }
+
+        Commands::PackageManifests { ecosystem } => {
+            let manifests: Vec<PackageManifest> =
+                get(client, &format!("{}/project/package-manifests", base_url)).await?;
+            let manifests: Vec<&PackageManifest> = manifests
+                .iter()
+                .filter(|m| ecosystem.as_deref().map_or(true, |e| m.ecosystem == e))
+                .collect();
+
+            if manifests.is_empty() {
+                say!("No external packages in the project");
+            }
+            for manifest in manifests {
+                say!("# {}\n", manifest.file_name);
+                say!("{}", manifest.contents);
+            }
+        }
+
+        Commands::Layout => {
+            let project: Value =
+                post(client, &format!("{}/project/layout", base_url), &serde_json::json!({})).await?;
+            let count = project
+                .get("nodes")
+                .and_then(|n| n.as_array())
+                .map_or(0, |nodes| nodes.len());
+            say!("Laid out {} nodes", count);
+        }
+
+        Commands::Changes { since } => {
+            let result: ChangesResponse =
+                get(client, &format!("{}/project/changes?since={}", base_url, since)).await?;
+
+            for change in &result.changes {
+                print_change(change);
+            }
+            say!("\nRevision: {} ({} changes)", result.revision, result.changes.len());
         }
 
-        Commands::Save => {
-            let _: Value = post(client, &format!("{}/project/save", base_url), &serde_json::json!({})).await?;
-            println!("Project saved");
+        Commands::CheckConstraints => {
+            let nodes: Vec<NodeViolations> = post(
+                client,
+                &format!("{}/project/check-constraints", base_url),
+                &serde_json::json!({}),
+            )
+            .await?;
+            if nodes.is_empty() {
+                say!("No constraint violations");
+            }
+            for node in &nodes {
+                say!("{}:", node.node_name);
+                for violation in &node.violations {
+                    match violation.line {
+                        Some(line) => say!(
+                            "  {} (line {}: {})",
+                            violation.constraint, line, violation.message
+                        ),
+                        None => say!("  {} ({})", violation.constraint, violation.message),
+                    }
+                }
+                for name in &node.missing_exports {
+                    say!("  missing export: {}", name);
+                }
+            }
         }
 
-        Commands::Nodes => {
-            let nodes: Vec<Node> = get(client, &format!("{}/nodes", base_url)).await?;
+        Commands::CheckConsistency { llm } => {
+            let nodes: Vec<NodeConsistency> = post(
+                client,
+                &format!("{}/project/check-consistency", base_url),
+                &serde_json::json!({ "llm": llm }),
+            )
+            .await?;
             if nodes.is_empty() {
-                println!("No nodes in project");
-            } else {
-                println!("{:<36} {:<20} {:<12} {}", "ID", "NAME", "STATUS", "PATH");
-                println!("{}", "-".repeat(80));
-                for node in nodes {
-                    println!(
-                        "{:<36} {:<20} {:<12} {}",
-                        node.id,
-                        truncate(&node.name, 18),
-                        node.status,
-                        node.file_path
-                    );
+                say!("No consistency issues");
+            }
+            for node in &nodes {
+                say!("{}:", node.node_name);
+                for issue in &node.issues {
+                    match issue.line {
+                        Some(line) => say!("  line {}: {}", line, issue.message),
+                        None => say!("  {}", issue.message),
+                    }
+                    if let Some(suggestion) = &issue.suggestion {
+                        say!("    fix: {}", suggestion);
+                    }
                 }
             }
         }
 
-        Commands::Node { id } => {
-            let node: Node = get(client, &format!("{}/nodes/{}", base_url, id)).await?;
-            println!("ID: {}", node.id);
-            println!("Name: {}", node.name);
-            println!("Path: {}", node.file_path);
-            println!("Status: {}", node.status);
-            println!("Description: {}", node.description);
-            if let Some(code) = &node.generated_code {
-                println!("\n--- Generated Code ---\n{}", code);
+        Commands::Watch { since } => {
+            let since = match since {
+                Some(since) => since,
+                None => {
+                    let status: StatusResponse =
+                        get(client, &format!("{}/status", base_url)).await?;
+                    status.revision
+                }
+            };
+            let url = format!("{}/project/changes/stream?since={}", base_url, since);
+            say!("Watching for changes (Ctrl+C to stop)");
+            STREAMED.store(true, Ordering::Relaxed);
+            follow_changes(client, &url, |change, json| {
+                print_change(&change);
+                print_json_line(json);
+            })
+            .await?;
+        }
+
+        Commands::Scaffold {
+            llm,
+            dry_run,
+            overwrite,
+        } => {
+            let result: ScaffoldResponse = post(
+                client,
+                &format!("{}/project/scaffold", base_url),
+                &serde_json::json!({
+                    "llm": llm,
+                    "dry_run": dry_run,
+                    "overwrite": overwrite,
+                }),
+            )
+            .await?;
+
+            if result.files.is_empty() {
+                say!("No build files to generate");
+            }
+            if dry_run {
+                for file in &result.files {
+                    say!("# {}\n", file.file_path);
+                    say!("{}", file.contents);
+                }
+            } else {
+                for path in &result.written {
+                    say!("  Wrote: {}", path);
+                }
+                for path in &result.skipped {
+                    say!("  Skipped (exists): {}", path);
+                }
+                if !result.skipped.is_empty() {
+                    say!("Run with --overwrite to replace existing files");
+                }
             }
         }
 
-        Commands::AddNode {
-            name,
-            path,
-            language,
-            description,
+        Commands::SetKeys {
+            anthropic,
+            openai,
+            mistral,
+            ollama_url,
+            proxy,
+            github,
         } => {
-            let body = serde_json::json!({
-                "name": name,
-                "file_path": path,
-                "language": language,
-            });
-            let node: Node = post(client, &format!("{}/nodes", base_url), &body).await?;
+            let body = ApiKeysRequest {
+                anthropic,
+                openai,
+                mistral,
+                ollama_base_url: ollama_url,
+                github,
+                proxy_url: proxy,
+            };
+            let _: Value = post(client, &format!("{}/api-keys", base_url), &body).await?;
+            say!("API keys updated");
+        }
 
-            // Update description if provided
-            if !description.is_empty() {
-                let update_body = serde_json::json!({ "description": description });
-                let _: Value = put(client, &format!("{}/nodes/{}", base_url, node.id), &update_body).await?;
+        Commands::Models { provider } => {
+            let url = format!("{}/providers/{}/models", base_url, provider);
+            let models: Vec<ProviderModel> = get(client, &url).await?;
+            for model in &models {
+                let capabilities: Vec<&str> = [(model.chat, "chat"), (model.fim, "fim")]
+                    .into_iter()
+                    .filter_map(|(supported, name)| supported.then_some(name))
+                    .collect();
+                say!("{} ({})", model.id, capabilities.join(", "));
             }
+            set_output(last_response());
+        }
 
-            println!("Created node: {} ({})", node.name, node.id);
-            println!("File path: {}", node.file_path);
+        Commands::Config { action } => {
+            let url = format!("{}/project/settings", base_url);
+            let mut settings: serde_json::Map<String, Value> = get(client, &url).await?;
+            match action {
+                ConfigAction::Get { key: None } => {
+                    for key in CONFIG_KEYS {
+                        let value = settings.get(key).map_or("(unset)".to_string(), |v| match v {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        });
+                        say!("{}: {}", key, value);
+                    }
+                }
+                ConfigAction::Get { key: Some(key) } => {
+                    if !CONFIG_KEYS.contains(&key.as_str()) {
+                        return Err(format!("Unknown setting: {}", key));
+                    }
+                    set_output(settings.get(&key).cloned().unwrap_or(Value::Null));
+                    match settings.get(&key) {
+                        Some(Value::String(s)) => say!("{}", s),
+                        Some(value) => say!("{}", value),
+                        None => say!("(unset)"),
+                    }
+                }
+                ConfigAction::Set { key, value } => {
+                    if !CONFIG_KEYS.contains(&key.as_str()) {
+                        return Err(format!("Unknown setting: {}", key));
+                    }
+                    let message = match value {
+                        Some(value) => {
+                            // Numbers and booleans as such, anything else as a string
+                            let value = serde_json::from_str(&value)
+                                .ok()
+                                .filter(|v: &Value| v.is_number() || v.is_boolean())
+                                .unwrap_or(Value::String(value));
+                            let message = format!("Set {} to {}", key, value);
+                            settings.insert(key, value);
+                            message
+                        }
+                        None => {
+                            let message = format!("Unset {}", key);
+                            settings.remove(&key);
+                            message
+                        }
+                    };
+                    let _: Value = put(client, &url, &settings).await?;
+                    say!("{}", message);
+                }
+            }
         }
 
-        Commands::UpdateNode {
-            id,
-            description,
-            purpose,
+        Commands::SetManifest {
             name,
+            version,
+            entry_point,
+            provider,
+            model,
+            system_prompt,
+            patch,
         } => {
-            let mut updates = serde_json::Map::new();
-            if let Some(d) = description {
-                updates.insert("description".to_string(), serde_json::Value::String(d));
+            let mut updates = match patch {
+                Some(patch) => match serde_json::from_str(&patch) {
+                    Ok(Value::Object(fields)) => fields,
+                    _ => return Err("--patch must be a JSON object".to_string()),
+                },
+                None => serde_json::Map::new(),
+            };
+            let fields = [("name", name), ("version", version), ("entryPoint", entry_point)];
+            for (key, value) in fields {
+                if let Some(value) = value {
+                    updates.insert(key.to_string(), Value::String(value));
+                }
             }
-            if let Some(p) = purpose {
-                updates.insert("purpose".to_string(), serde_json::Value::String(p));
+            let mut default_llm = serde_json::Map::new();
+            if let Some(provider) = provider {
+                default_llm.insert("provider".to_string(), Value::String(provider));
             }
-            if let Some(n) = name {
-                updates.insert("name".to_string(), serde_json::Value::String(n));
+            if let Some(model) = model {
+                default_llm.insert("model".to_string(), Value::String(model));
+            }
+            if !default_llm.is_empty() {
+                updates.insert("defaultLlm".to_string(), Value::Object(default_llm));
+            }
+            if let Some(prompt) = system_prompt {
+                let prompt = if prompt.is_empty() { Value::Null } else { Value::String(prompt) };
+                updates.insert("systemPrompt".to_string(), prompt);
             }
 
             if updates.is_empty() {
                 return Err("No updates specified".to_string());
             }
 
-            let _: Value = put(
+            let manifest: Value = put(
                 client,
-                &format!("{}/nodes/{}", base_url, id),
-                &serde_json::Value::Object(updates),
+                &format!("{}/project/manifest", base_url),
+                &Value::Object(updates),
             )
             .await?;
-            println!("Updated node: {}", id);
+            say!(
+                "Updated manifest: {} {}",
+                manifest["name"].as_str().unwrap_or_default(),
+                manifest["version"].as_str().unwrap_or_default()
+            );
         }
 
-        Commands::DeleteNode { id } => {
-            let _: Value = delete(client, &format!("{}/nodes/{}", base_url, id)).await?;
-            println!("Deleted node: {}", id);
+        Commands::Project => {
+            let project: Value = get(client, &format!("{}/project", base_url)).await?;
+            // JSON mode prints it as the result
+            if !JSON_OUTPUT.load(Ordering::Relaxed) {
+                println!("{}", serde_json::to_string_pretty(&project).unwrap());
+            }
         }
 
-        Commands::Edges => {
-            let edges: Vec<Edge> = get(client, &format!("{}/edges", base_url)).await?;
-            if edges.is_empty() {
-                println!("No edges in project");
+        Commands::Queue { remove: Some(id) } => {
+            let _: Value = delete(client, &format!("{}/queue/{}", base_url, id)).await?;
+            say!("Removed from queue: {}", id);
+        }
+
+        Commands::Queue { remove: None } => {
+            let queue: Vec<Value> = get(client, &format!("{}/queue", base_url)).await?;
+            if queue.is_empty() {
+                say!("No generations queued");
             } else {
-                println!("{:<36} {:<36} {}", "SOURCE", "TARGET", "LABEL");
-                println!("{}", "-".repeat(90));
-                for edge in edges {
-                    println!(
-                        "{:<36} {:<36} {}",
-                        edge.source,
-                        edge.target,
-                        edge.label
+                say!("{:<38} {:<8} {:<8} {:<38}", "ID", "STATUS", "KIND", "NODE");
+                say!("{}", "-".repeat(92));
+                for item in queue {
+                    let field = |key: &str| item[key].as_str().unwrap_or("-").to_string();
+                    say!(
+                        "{:<38} {:<8} {:<8} {:<38}",
+                        field("id"),
+                        field("status"),
+                        field("kind"),
+                        field("nodeId")
                     );
                 }
             }
         }
 
-        Commands::AddEdge {
-            source,
-            target,
-            label,
-        } => {
-            let body = serde_json::json!({
-                "source": source,
-                "target": target,
-                "label": label,
-            });
-            let edge: Edge = post(client, &format!("{}/edges", base_url), &body).await?;
-            println!("Created edge: {} -> {} ({})", source, target, edge.id);
+        Commands::Runs { id: None } => {
+            let runs: Vec<RunSummary> = get(client, &format!("{}/runs", base_url)).await?;
+            if runs.is_empty() {
+                say!("No runs recorded");
+            } else {
+                say!(
+                    "{:<22} {:>8} {:>6} {:>6} {:>7} {:>10} {:>9}",
+                    "RUN", "DURATION", "OK", "FAILED", "SKIPPED", "TOKENS", "COST"
+                );
+                say!("{}", "-".repeat(80));
+                for run in runs {
+                    say!(
+                        "{:<22} {:>7}s {:>6} {:>6} {:>7} {:>10} {:>9}",
+                        run.id,
+                        run.duration_ms / 1000,
+                        run.total_successful,
+                        run.total_failed,
+                        run.total_skipped,
+                        run.total_tokens,
+                        format!("${:.4}", run.total_cost_estimate)
+                    );
+                }
+            }
         }
 
-        Commands::DeleteEdge { id } => {
-            let _: Value = delete(client, &format!("{}/edges/{}", base_url, id)).await?;
-            println!("Deleted edge: {}", id);
+        Commands::Runs { id: Some(id) } => {
+            let run: RunReport = get(client, &format!("{}/runs/{}", base_url, id)).await?;
+            let summary = &run.summary;
+            say!("Run: {}", summary.id);
+            say!("Duration: {:.1}s", summary.duration_ms as f64 / 1000.0);
+            say!(
+                "Nodes: {} successful, {} failed, {} skipped",
+                summary.total_successful, summary.total_failed, summary.total_skipped
+            );
+            say!("Tokens: {}", summary.total_tokens);
+            say!("Estimated cost: ${:.4}", summary.total_cost_estimate);
+            say!();
+            say!(
+                "{:<20} {:<28} {:<6} {:>8} {:>8} {:>9} {:>7}",
+                "NODE", "MODEL", "OK", "TIME", "TOKENS", "COST", "RETRIES"
+            );
+            say!("{}", "-".repeat(92));
+            for node in &run.nodes {
+                say!(
+                    "{:<20} {:<28} {:<6} {:>7}s {:>8} {:>9} {:>7}",
+                    truncate(&node.node_name, 18),
+                    truncate(&node.model, 26),
+                    if node.success { "yes" } else { "no" },
+                    node.duration_ms / 1000,
+                    node.tokens_used.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string()),
+                    node.cost_estimate
+                        .map(|c| format!("${:.4}", c))
+                        .unwrap_or_else(|| "-".to_string()),
+                    node.retries
+                );
+                if let Some(error) = &node.error {
+                    say!("    error: {}", error);
+                }
+            }
         }
 
-        Commands::Plan => {
-            let plan: ExecutionPlan = get(client, &format!("{}/execution-plan", base_url)).await?;
-            println!("Execution Plan ({} nodes)", plan.total_nodes);
-            println!("{}", "-".repeat(50));
-            for wave in plan.waves {
-                println!("\nWave {}:", wave.wave_number);
-                for node_id in wave.node_ids {
-                    println!("  - {}", node_id);
+        Commands::PullRequest { id } => {
+            let id = match id {
+                Some(id) => id,
+                None => {
+                    let runs: Vec<RunSummary> = get(client, &format!("{}/runs", base_url)).await?;
+                    runs.into_iter().next().ok_or("No runs recorded")?.id
                 }
+            };
+            let url = format!("{}/runs/{}/pull-request", base_url, id);
+            let pull: PullRequest = post(client, &url, &serde_json::json!({})).await?;
+            say!(
+                "Opened pull request #{} ({} -> {}): {}",
+                pull.number, pull.branch, pull.base_branch, pull.url
+            );
+            for file in &pull.files {
+                say!("  {}", file);
             }
         }
 
-        Commands::Prompt { id } => {
-            let resp: Value = get(client, &format!("{}/prompt/{}", base_url, id)).await?;
-            if let Some(prompt) = resp.get("prompt").and_then(|p| p.as_str()) {
-                println!("{}", prompt);
+        Commands::Logs {
+            follow,
+            lines,
+            since,
+        } => {
+            let mut params = vec![("lines", lines.to_string()), ("follow", follow.to_string())];
+            if let Some(since) = since {
+                params.push(("since", since));
             }
-        }
+            let url = reqwest::Url::parse_with_params(&format!("{}/logs", base_url), &params)
+                .map_err(|e| format!("Invalid URL: {}", e))?;
+            let mut resp = client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
 
-        Commands::Generate { id } => {
-            println!("Generating code for node {}...", id);
-            let resp: Value = post(
-                client,
-                &format!("{}/generate/{}", base_url, id),
-                &serde_json::json!({}),
-            )
-            .await?;
-            if let Some(code) = resp.get("code").and_then(|c| c.as_str()) {
-                println!("\n--- Generated Code ---\n{}", code);
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.map_err(|e| e.to_string())?;
+                if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
+                    return Err(err.error);
+                }
+                return Err(format!("Request failed: {} - {}", status, body));
+            }
+
+            // Print complete lines as they arrive; keep partial ones buffered
+            STREAMED.store(true, Ordering::Relaxed);
+            let mut buffer = String::new();
+            while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+                while let Some(pos) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=pos).collect();
+                    print_log_line(line.trim_end());
+                }
+            }
+            if !buffer.trim().is_empty() {
+                print_log_line(buffer.trim_end());
             }
         }
 
-        Commands::GenerateAll => {
-            println!("Generating code for all nodes...");
-            let _: Value = post(
-                client,
-                &format!("{}/generate-all", base_url),
-                &serde_json::json!({}),
-            )
-            .await?;
-            println!("Generation complete!");
+        Commands::Completions { shell } => {
+            let shells = Shells::builtins();
+            let completer = shells.completer(&shell).ok_or_else(|| {
+                let names: Vec<_> = shells.names().collect();
+                format!("Unknown shell '{}'; expected one of: {}", shell, names.join(", "))
+            })?;
+            let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+            completer
+                .write_registration(
+                    "COMPLETE",
+                    "needlepoint-cli",
+                    "needlepoint-cli",
+                    &exe.to_string_lossy(),
+                    &mut std::io::stdout(),
+                )
+                .map_err(|e| e.to_string())?;
         }
+    }
 
-        Commands::WriteFiles => {
-            let project: Value = get(client, &format!("{}/project", base_url)).await?;
+    Ok(())
+}
 
-            let project_path = project.get("projectPath")
-                .and_then(|p| p.as_str())
-                .ok_or("No project path found")?;
+/// Node IDs for shell completion, with names as descriptions. Queries the API at
+/// the host and port from the environment; completes nothing if it isn't running.
+fn node_candidates() -> Vec<CompletionCandidate> {
+    let port = std::env::var("NEEDLEPOINT_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let host = std::env::var("NEEDLEPOINT_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string());
+    let token = std::env::var("NEEDLEPOINT_TOKEN").ok();
+    let url = format!("http://{}:{}/api/nodes?fields=name,filePath,status", host, port);
 
-            // Clean up Windows extended path prefix if present
-            let project_path = project_path.trim_start_matches("\\\\?\\");
+    let fetch = async {
+        let client = build_client(token.as_deref()).ok()?;
+        let resp = client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .await
+            .ok()?;
+        resp.json::<Vec<NodeSummary>>().await.ok()
+    };
+    let nodes = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .ok()
+        .and_then(|rt| rt.block_on(fetch))
+        .unwrap_or_default();
 
-            let nodes = project.get("nodes")
-                .and_then(|n| n.as_array())
-                .ok_or("No nodes found")?;
+    nodes
+        .into_iter()
+        .map(|node| {
+            let help = format!("{} ({})", node.name, node.file_path);
+            CompletionCandidate::new(node.id).help(Some(help.into()))
+        })
+        .collect()
+}
 
-            let mut written = 0;
-            let mut skipped = 0;
+/// POST to `/generate-all`, asking on stdin whether to go on whenever the run pauses
+/// after a wave, in step mode or for nodes that require approval
+async fn generate_all<T: for<'de> Deserialize<'de>>(
+    client: &Client,
+    base_url: &str,
+    body: &Value,
+) -> Result<T, String> {
+    let url = format!("{}/generate-all", base_url);
+    let generate = post(client, &url, body);
+    tokio::pin!(generate);
+    loop {
+        tokio::select! {
+            result = &mut generate => return result,
+            _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {}
+        }
+        let paused: Option<PausedStep> =
+            get(client, &format!("{}/execution/paused", base_url)).await?;
+        let Some(paused) = paused else {
+            continue;
+        };
+        if paused.awaiting_approval.is_empty() {
+            eprint!(
+                "Wave {} done, {} waves left. Continue? [Y/n] ",
+                paused.wave_number, paused.remaining_waves
+            );
+        } else {
+            for node_id in &paused.awaiting_approval {
+                let node: Node = get(client, &format!("{}/nodes/{}", base_url, node_id)).await?;
+                eprintln!("\n--- {} ({}) ---", node.name, node.file_path);
+                eprintln!("{}", node.generated_code.as_deref().unwrap_or_default());
+            }
+            eprint!("Approve this code and generate what depends on it? [Y/n] ");
+        }
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).map_err(|e| e.to_string())?;
+        let declined = answer.trim().eq_ignore_ascii_case("n");
+        let (action, choice) = if paused.awaiting_approval.is_empty() {
+            ("continue", serde_json::json!({ "stop": declined }))
+        } else {
+            ("approve", serde_json::json!({ "reject": declined }))
+        };
+        let _: Value =
+            post(client, &format!("{}/execution/{}", base_url, action), &choice).await?;
+    }
+}
 
-            for node in nodes {
-                let file_path = node.get("filePath").and_then(|p| p.as_str());
-                let code = node.get("generatedCode").and_then(|c| c.as_str());
-                let name = node.get("name").and_then(|n| n.as_str()).unwrap_or("unknown");
+/// Load the project file at `path` into the running instance
+async fn load_project(client: &Client, base_url: &str, path: &Path) -> Result<(), String> {
+    let abs_path = std::fs::canonicalize(path)
+        .map_err(|e| format!("Invalid path: {}", e))?
+        .to_string_lossy()
+        .to_string();
 
-                match (file_path, code) {
-                    (Some(rel_path), Some(code)) if !code.is_empty() => {
-                        let full_path = std::path::Path::new(project_path).join(rel_path);
+    let body = serde_json::json!({ "path": abs_path });
+    let _: Value = post(client, &format!("{}/project/load", base_url), &body).await?;
+    say!("Project loaded from: {}", abs_path);
+    Ok(())
+}
 
-                        // Create parent directories if needed
-                        if let Some(parent) = full_path.parent() {
-                            std::fs::create_dir_all(parent)
-                                .map_err(|e| format!("Failed to create directory: {}", e))?;
-                        }
+/// Pass each change from a change feed stream, with its JSON, to `on_change` until
+/// the stream ends
+async fn follow_changes(
+    client: &Client,
+    url: &str,
+    mut on_change: impl FnMut(ChangeEvent, &str),
+) -> Result<(), String> {
+    let mut resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
 
-                        std::fs::write(&full_path, code)
-                            .map_err(|e| format!("Failed to write {}: {}", rel_path, e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.map_err(|e| e.to_string())?;
+        return Err(error_message(status, &body));
+    }
 
-                        println!("  Wrote: {} -> {}", name, rel_path);
-                        written += 1;
-                    }
-                    _ => {
-                        println!("  Skipped: {} (no generated code)", name);
-                        skipped += 1;
-                    }
-                }
+    // Server-sent events: parse the data line of each `change` event
+    let mut buffer = String::new();
+    while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(pos) = buffer.find('\n') {
+            let line: String = buffer.drain(..=pos).collect();
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            if let Ok(change) = serde_json::from_str::<ChangeEvent>(data.trim()) {
+                on_change(change, data.trim());
             }
-
-            println!("\nFiles written: {}, skipped: {}", written, skipped);
         }
+    }
+    Ok(())
+}
 
-        Commands::SetKeys {
-            anthropic,
-            openai,
-            ollama_url,
-        } => {
-            let body = ApiKeysRequest {
-                anthropic,
-                openai,
-                ollama_base_url: ollama_url,
-            };
-            let _: Value = post(client, &format!("{}/api-keys", base_url), &body).await?;
-            println!("API keys updated");
-        }
+fn print_log_line(line: &str) {
+    print_json_line(line);
+    match serde_json::from_str::<LogEntry>(line) {
+        Ok(entry) => say!(
+            "{} {:<5} {}: {}",
+            entry.timestamp,
+            entry.level,
+            entry.target,
+            entry.message
+        ),
+        // Not one of ours; show it verbatim
+        Err(_) => say!("{}", line),
+    }
+}
 
-        Commands::Project => {
-            let project: Value = get(client, &format!("{}/project", base_url)).await?;
-            println!("{}", serde_json::to_string_pretty(&project).unwrap());
+/// HTTP client that sends the access token, if any, with every request
+fn build_client(token: Option<&str>) -> Result<Client, String> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = token.filter(|t| !t.is_empty()) {
+        let value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| "Invalid access token".to_string())?;
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    Client::builder()
+        .default_headers(headers)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a successful response body, keeping it as the result for JSON mode
+fn parse_response<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, String> {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        *LAST_RESPONSE.lock().unwrap_or_else(|e| e.into_inner()) = serde_json::from_str(body).ok();
+    }
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// The body of the last successful response, for combining into a command's result
+fn last_response() -> Value {
+    LAST_RESPONSE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or(Value::Null)
+}
+
+/// Make `value` the result printed in JSON mode
+fn set_output(value: Value) {
+    *OUTPUT.lock().unwrap_or_else(|e| e.into_inner()) = Some(value);
+}
+
+/// Add a field to the result printed in JSON mode
+fn add_output(key: &str, value: Value) {
+    let mut output = OUTPUT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Value::Object(fields) = output.get_or_insert_with(|| serde_json::json!({})) {
+        fields.insert(key.to_string(), value);
+    }
+}
+
+/// Print the command's result in JSON mode: what it set with `set_output` or
+/// `add_output`, or else the last response if it succeeded. Streamed results have
+/// already been printed.
+fn print_output(succeeded: bool) {
+    if STREAMED.load(Ordering::Relaxed) {
+        return;
+    }
+    let output = OUTPUT.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let output = output.or_else(|| {
+        let last = LAST_RESPONSE.lock().unwrap_or_else(|e| e.into_inner()).take();
+        last.filter(|_| succeeded)
+    });
+    if let Some(output) = output {
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+    }
+}
+
+/// Print one line of a streamed result (a change or log entry) to stdout in JSON mode
+fn print_json_line(line: &str) {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        println!("{}", line);
+    }
+}
+
+/// Remember the project revision a response was served at
+fn record_revision(resp: &reqwest::Response) {
+    if let Some(etag) = resp.headers().get(reqwest::header::ETAG).and_then(|v| v.to_str().ok()) {
+        *REVISION.lock().unwrap_or_else(|e| e.into_inner()) = Some(etag.to_string());
+    }
+}
+
+/// `If-Match` value for a mutation: the last revision seen, fetched from
+/// `/status` when this command hasn't read anything yet
+async fn if_match(client: &Client, url: &str) -> Result<String, String> {
+    if FORCE.load(Ordering::Relaxed) {
+        return Ok("*".to_string());
+    }
+    if let Some(revision) = REVISION.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok(revision);
+    }
+
+    let base_url = url.find("/api/").map_or(url, |i| &url[..i + "/api".len()]);
+    let _: Value = get(client, &format!("{}/status", base_url)).await?;
+    REVISION
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| "Server did not report a project revision".to_string())
+}
+
+/// Turn an error response into a message, pointing at `--force` for stale revisions
+fn error_message(status: reqwest::StatusCode, body: &str) -> String {
+    match serde_json::from_str::<ErrorResponse>(body) {
+        Ok(err)
+            if status == reqwest::StatusCode::CONFLICT
+                && err.error.starts_with("Project was changed") =>
+        {
+            format!("{} (or pass --force to apply anyway)", err.error)
         }
+        Ok(err) => err.error,
+        Err(_) => format!("Request failed: {} - {}", status, body),
     }
+}
 
-    Ok(())
+/// URL of a named snippet or shared type, with the name percent-encoded (names may
+/// contain spaces)
+fn named_url(base_url: &str, collection: &str, name: &str) -> Result<String, String> {
+    let mut url = reqwest::Url::parse(base_url).map_err(|e| e.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Invalid server URL: {}", base_url))?
+        .extend([collection, name]);
+    Ok(url.to_string())
 }
 
 async fn get<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) -> Result<T, String> {
@@ -519,18 +4028,44 @@ async fn get<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) -> Result
         .send()
         .await
         .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
+    record_revision(&resp);
 
     let status = resp.status();
     let body = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-            return Err(err.error);
-        }
-        return Err(format!("Request failed: {} - {}", status, body));
+        return Err(error_message(status, &body));
+    }
+
+    parse_response(&body)
+}
+
+/// GET a paginated listing, returning the body and the next page's cursor
+async fn get_page<T: for<'de> Deserialize<'de>>(
+    client: &Client,
+    url: &str,
+) -> Result<(T, Option<String>), String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
+    record_revision(&resp);
+
+    let status = resp.status();
+    let next_cursor = resp
+        .headers()
+        .get("x-next-cursor")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+
+    if !status.is_success() {
+        return Err(error_message(status, &body));
     }
 
-    serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))
+    let value = parse_response(&body)?;
+    Ok((value, next_cursor))
 }
 
 async fn post<T: for<'de> Deserialize<'de>, B: Serialize>(
@@ -538,24 +4073,24 @@ async fn post<T: for<'de> Deserialize<'de>, B: Serialize>(
     url: &str,
     body: &B,
 ) -> Result<T, String> {
+    let if_match = if_match(client, url).await?;
     let resp = client
         .post(url)
+        .header(reqwest::header::IF_MATCH, if_match)
         .json(body)
         .send()
         .await
         .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
+    record_revision(&resp);
 
     let status = resp.status();
     let body = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-            return Err(err.error);
-        }
-        return Err(format!("Request failed: {} - {}", status, body));
+        return Err(error_message(status, &body));
     }
 
-    serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))
+    parse_response(&body)
 }
 
 async fn put<T: for<'de> Deserialize<'de>, B: Serialize>(
@@ -563,50 +4098,51 @@ async fn put<T: for<'de> Deserialize<'de>, B: Serialize>(
     url: &str,
     body: &B,
 ) -> Result<T, String> {
+    let if_match = if_match(client, url).await?;
     let resp = client
         .put(url)
+        .header(reqwest::header::IF_MATCH, if_match)
         .json(body)
         .send()
         .await
         .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
+    record_revision(&resp);
 
     let status = resp.status();
     let body = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-            return Err(err.error);
-        }
-        return Err(format!("Request failed: {} - {}", status, body));
+        return Err(error_message(status, &body));
     }
 
-    serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))
+    parse_response(&body)
 }
 
 async fn delete<T: for<'de> Deserialize<'de>>(client: &Client, url: &str) -> Result<T, String> {
+    let if_match = if_match(client, url).await?;
     let resp = client
         .delete(url)
+        .header(reqwest::header::IF_MATCH, if_match)
         .send()
         .await
         .map_err(|e| format!("Connection failed: {}. Is Needlepoint running?", e))?;
+    record_revision(&resp);
 
     let status = resp.status();
     let body = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-            return Err(err.error);
-        }
-        return Err(format!("Request failed: {} - {}", status, body));
+        return Err(error_message(status, &body));
     }
 
-    serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))
+    parse_response(&body)
 }
 
+/// Shorten to `max_len` characters, ending in `...` when cut
 fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{}...", s.chars().take(max_len - 3).collect::<String>())
     }
 }
@@ -1,89 +1,31 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
 use tauri::command;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::graph::drift::{content_hash, is_manually_edited};
+use crate::graph::header::wrap_generated_code;
+use crate::graph::merge::{load_written_snapshot, merge_regeneration, save_written_snapshot};
+use crate::graph::model::{CodeNode, Project, DEFAULT_TRASH_RETENTION_DAYS};
+use crate::graph::paths::{normalize_file_path, validate_path, walk_project_files};
+use crate::graph::validation::{file_path_conflicts, ValidationError};
+use crate::graph::variables::resolve_node;
 
 const TRASH_DIR: &str = ".needlepoint/trash";
 
-/// Validate and sanitize a file path to prevent directory traversal attacks
-/// Returns the canonicalized full path if valid, or an error if the path is dangerous
-fn validate_path(project_path: &str, file_path: &str) -> Result<PathBuf, String> {
-    // Reject empty paths
-    if file_path.is_empty() {
-        return Err("File path cannot be empty".to_string());
-    }
-
-    // Reject paths with null bytes
-    if file_path.contains('\0') {
-        return Err("File path contains invalid characters".to_string());
-    }
+/// Largest file `read_file` will return, in bytes
+pub const MAX_READ_SIZE: u64 = 2 * 1024 * 1024;
 
-    // Reject absolute paths
-    let file_path_obj = Path::new(file_path);
-    if file_path_obj.is_absolute() {
-        return Err("Absolute paths are not allowed".to_string());
-    }
+/// How many leading bytes are checked for NUL bytes when detecting binary files
+const BINARY_SNIFF_LEN: usize = 8000;
 
-    // Reject paths containing .. (directory traversal)
-    for component in file_path_obj.components() {
-        if let std::path::Component::ParentDir = component {
-            return Err("Path cannot contain '..' (directory traversal not allowed)".to_string());
-        }
-    }
-
-    // Reject paths starting with . that aren't just a filename starting with .
-    // Allow: ".gitignore", "src/.env" but reject: "../foo", ".."
-    let normalized = file_path.replace('\\', "/");
-    if normalized.starts_with("../") || normalized.contains("/../") || normalized == ".." {
-        return Err("Path cannot traverse outside project directory".to_string());
-    }
-
-    // Build the full path
-    let project_dir = Path::new(project_path);
-    let full_path = project_dir.join(file_path);
-
-    // Canonicalize project path (must exist)
-    let canonical_project = project_dir.canonicalize()
-        .map_err(|e| format!("Invalid project path: {}", e))?;
-
-    // For the full path, we need to handle non-existent files
-    // Canonicalize as much as possible, then check the result
-    let canonical_full = if full_path.exists() {
-        full_path.canonicalize()
-            .map_err(|e| format!("Failed to resolve path: {}", e))?
-    } else {
-        // For non-existent files, canonicalize the parent and append the filename
-        if let Some(parent) = full_path.parent() {
-            if parent.exists() {
-                let canonical_parent = parent.canonicalize()
-                    .map_err(|e| format!("Failed to resolve parent path: {}", e))?;
-                if let Some(file_name) = full_path.file_name() {
-                    canonical_parent.join(file_name)
-                } else {
-                    return Err("Invalid file path".to_string());
-                }
-            } else {
-                // Parent doesn't exist yet - verify the path components don't escape
-                // This is less strict but necessary for creating new directories
-                full_path.clone()
-            }
-        } else {
-            return Err("Invalid file path".to_string());
-        }
-    };
-
-    // Verify the resolved path is within the project directory
-    // Use string comparison after canonicalization for existing paths
-    if canonical_full.exists() {
-        let canonical_str = canonical_full.to_string_lossy();
-        let project_str = canonical_project.to_string_lossy();
-        if !canonical_str.starts_with(project_str.as_ref()) {
-            return Err("Path resolves outside project directory".to_string());
-        }
-    }
-
-    Ok(full_path)
-}
+/// Extension of the metadata sidecar written next to each trashed file
+const TRASH_INFO_EXT: &str = "trashinfo";
 
 /// Get the trash directory path for a project
 fn get_trash_dir(project_path: &str) -> PathBuf {
@@ -97,6 +39,55 @@ fn get_trash_filename(original_path: &str) -> String {
     format!("{}_{}", timestamp, safe_name)
 }
 
+/// A trashed file and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub trash_filename: String,
+    /// Path relative to the project root; unknown for files trashed before
+    /// metadata was recorded
+    pub original_path: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    pub size: u64,
+    /// The entry is a directory, stored as a `.tar.gz` archive
+    #[serde(default)]
+    pub is_dir: bool,
+}
+
+/// Path of the metadata sidecar for a trashed file
+fn trash_info_path(trash_dir: &Path, trash_filename: &str) -> PathBuf {
+    trash_dir.join(format!("{}.{}", trash_filename, TRASH_INFO_EXT))
+}
+
+/// Read a trashed file's metadata, falling back to the file's own details
+/// when there is no sidecar
+fn read_trash_entry(trash_dir: &Path, trash_filename: &str) -> Result<TrashEntry, String> {
+    let info_path = trash_info_path(trash_dir, trash_filename);
+    if let Ok(contents) = fs::read_to_string(&info_path) {
+        return serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse trash metadata: {}", e));
+    }
+
+    let metadata = fs::metadata(trash_dir.join(trash_filename))
+        .map_err(|e| format!("Failed to read trashed file: {}", e))?;
+    let deleted_at = metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now());
+    Ok(TrashEntry {
+        trash_filename: trash_filename.to_string(),
+        original_path: None,
+        deleted_at,
+        size: metadata.len(),
+        is_dir: false,
+    })
+}
+
+/// Write the metadata sidecar for a newly trashed file
+fn write_trash_entry(trash_dir: &Path, entry: &TrashEntry) -> Result<(), String> {
+    let info = serde_json::to_string_pretty(entry)
+        .map_err(|e| format!("Failed to serialize trash metadata: {}", e))?;
+    fs::write(trash_info_path(trash_dir, &entry.trash_filename), info)
+        .map_err(|e| format!("Failed to write trash metadata: {}", e))
+}
+
 /// Create a file and its parent directories if they don't exist
 #[command]
 pub fn create_file(project_path: String, file_path: String) -> Result<(), String> {
@@ -130,6 +121,215 @@ pub fn write_file(project_path: String, file_path: String, content: String) -> R
     Ok(())
 }
 
+/// A file read through the validated-path layer
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContents {
+    pub path: String,
+    pub size: u64,
+    /// Binary files are reported without their contents
+    pub binary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A file that couldn't be read in a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReadError {
+    pub path: String,
+    pub error: String,
+}
+
+/// One result of `read_files`
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum FileReadResult {
+    Ok(FileContents),
+    Err(FileReadError),
+}
+
+/// Why a file couldn't be read
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ReadFileError {
+    #[error("{0}")]
+    InvalidPath(String),
+    #[error("File not found: {0}")]
+    NotFound(String),
+    #[error("File is {size} bytes, over the {limit} byte limit")]
+    TooLarge { size: u64, limit: u64 },
+    #[error("Failed to read file: {0}")]
+    Io(String),
+}
+
+/// Files with NUL bytes near the start, or that aren't UTF-8, are treated as binary
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+/// Read a project file, rejecting paths outside the project and files over `MAX_READ_SIZE`
+pub(crate) fn read_project_file(
+    project_path: &str,
+    file_path: &str,
+) -> Result<FileContents, ReadFileError> {
+    let full_path = validate_path(project_path, file_path).map_err(ReadFileError::InvalidPath)?;
+
+    let metadata = fs::metadata(&full_path)
+        .map_err(|_| ReadFileError::NotFound(file_path.to_string()))?;
+    if !metadata.is_file() {
+        return Err(ReadFileError::NotFound(file_path.to_string()));
+    }
+    if metadata.len() > MAX_READ_SIZE {
+        return Err(ReadFileError::TooLarge {
+            size: metadata.len(),
+            limit: MAX_READ_SIZE,
+        });
+    }
+
+    let bytes = fs::read(&full_path).map_err(|e| ReadFileError::Io(e.to_string()))?;
+    let binary = is_binary(&bytes);
+    Ok(FileContents {
+        path: file_path.to_string(),
+        size: bytes.len() as u64,
+        binary,
+        content: if binary { None } else { String::from_utf8(bytes).ok() },
+    })
+}
+
+/// Read a file's contents
+#[command]
+pub fn read_file(project_path: String, file_path: String) -> Result<FileContents, String> {
+    read_project_file(&project_path, &file_path).map_err(|e| e.to_string())
+}
+
+/// Read several files at once; failures are reported per file
+#[command]
+pub fn read_files(project_path: String, file_paths: Vec<String>) -> Vec<FileReadResult> {
+    file_paths
+        .into_iter()
+        .map(|path| match read_project_file(&project_path, &path) {
+            Ok(contents) => FileReadResult::Ok(contents),
+            Err(e) => FileReadResult::Err(FileReadError {
+                path,
+                error: e.to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Outcome of writing a node's generated file
+pub(crate) enum NodeFileWrite {
+    /// Written; carries the hash of the generated contents
+    Written(String),
+    /// The file had manual edits, so the new generation was merged into it
+    Merged { hash: String, conflicts: bool },
+    /// Left alone: the file was edited by hand and there is no snapshot of the
+    /// previous generation to merge against
+    ManuallyEdited,
+}
+
+/// Result of `write_node_file`, for the frontend to store on the node
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeFileWriteResult {
+    pub written_hash: String,
+    pub merged: bool,
+    /// The merge left conflict markers in the file
+    pub conflicts: bool,
+}
+
+/// Write a node's generated code to its file, wrapped in the effective file header
+/// and footer. If the file was edited by hand since Needlepoint last wrote it, the
+/// new generation is three-way merged with the edits instead (unless `force` is set).
+pub(crate) fn write_generated_file(
+    project: &Project,
+    node: &CodeNode,
+    code: &str,
+    force: bool,
+) -> Result<NodeFileWrite, String> {
+    if let Some(subproject) = &node.subproject {
+        return Err(format!(
+            "Node '{}' belongs to sub-project '{}'; write it from there",
+            node.name, subproject
+        ));
+    }
+    let node = &resolve_node(&project.manifest, node);
+    let contents = wrap_generated_code(project, node, code);
+    let hash = content_hash(&contents);
+    let mut outcome = NodeFileWrite::Written(hash.clone());
+    let mut to_write = contents.clone();
+
+    if !force {
+        let full_path = validate_path(&project.project_path, &node.file_path)?;
+        let manual = fs::read_to_string(&full_path)
+            .ok()
+            .filter(|d| *d != contents && is_manually_edited(node, Some(d)));
+        if let Some(manual) = manual {
+            let Some(base) = load_written_snapshot(&project.project_path, node) else {
+                return Ok(NodeFileWrite::ManuallyEdited);
+            };
+            let merged = merge_regeneration(&base, &manual, &contents);
+            to_write = merged.contents;
+            outcome = NodeFileWrite::Merged {
+                hash,
+                conflicts: merged.conflicts,
+            };
+        }
+    }
+
+    write_file(project.project_path.clone(), node.file_path.clone(), to_write)?;
+
+    // The unmerged generation is the common ancestor for the next merge
+    if let Err(e) = save_written_snapshot(&project.project_path, &node.id, &contents) {
+        tracing::warn!("Failed to snapshot {}: {}", node.file_path, e);
+    }
+
+    Ok(outcome)
+}
+
+/// Write a node's generated code to its file, with the file header and footer applied.
+/// Manual edits made since the last write are merged with the new code.
+#[command]
+pub fn write_node_file(
+    project: Project,
+    node_id: String,
+    force: Option<bool>,
+) -> Result<NodeFileWriteResult, String> {
+    let node = project
+        .find_node(&node_id)
+        .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+    let code = node
+        .generated_code
+        .as_deref()
+        .ok_or_else(|| format!("Node '{}' has no generated code", node.name))?;
+
+    // Refuse to write over another node's file
+    let conflict = file_path_conflicts(&project).into_iter().find(|c| match c {
+        ValidationError::DuplicateFilePath(_, ids)
+        | ValidationError::CaseInsensitiveCollision(_, ids) => ids.contains(&node_id),
+        _ => false,
+    });
+    if let Some(conflict) = conflict {
+        return Err(conflict.to_string());
+    }
+
+    match write_generated_file(&project, node, code, force.unwrap_or(false))? {
+        NodeFileWrite::Written(hash) => Ok(NodeFileWriteResult {
+            written_hash: hash,
+            merged: false,
+            conflicts: false,
+        }),
+        NodeFileWrite::Merged { hash, conflicts } => Ok(NodeFileWriteResult {
+            written_hash: hash,
+            merged: true,
+            conflicts,
+        }),
+        NodeFileWrite::ManuallyEdited => Err(format!(
+            "{} was edited manually since it was last written; not overwriting",
+            node.file_path
+        )),
+    }
+}
+
 /// Soft delete a file by moving it to the trash folder
 /// Returns the trash path for potential restoration
 #[command]
@@ -148,9 +348,23 @@ pub fn delete_file(project_path: String, file_path: String) -> Result<String, St
     let trash_filename = get_trash_filename(&file_path);
     let trash_path = trash_dir.join(&trash_filename);
 
+    let size = fs::metadata(&full_path).map(|m| m.len()).unwrap_or(0);
+
     // Move file to trash
     fs::rename(&full_path, &trash_path).map_err(|e| format!("Failed to move file to trash: {}", e))?;
 
+    // Record where it came from so it can be restored by trash filename alone
+    write_trash_entry(
+        &trash_dir,
+        &TrashEntry {
+            trash_filename: trash_filename.clone(),
+            original_path: Some(file_path),
+            deleted_at: Utc::now(),
+            size,
+            is_dir: false,
+        },
+    )?;
+
     Ok(trash_filename)
 }
 
@@ -166,11 +380,107 @@ pub fn delete_file_permanent(project_path: String, file_path: String) -> Result<
     Ok(())
 }
 
-/// Restore a file from trash
+/// Reject directory operations on the project root or Needlepoint's own data
+fn validate_directory_path(project_path: &str, dir_path: &str) -> Result<PathBuf, String> {
+    let full_path = validate_path(project_path, dir_path)?;
+    let relative = normalize_file_path(dir_path);
+    let relative = relative.trim_end_matches('/');
+    if relative.is_empty() || relative == "." {
+        return Err("Cannot operate on the project root".to_string());
+    }
+    if relative == ".needlepoint" || relative.starts_with(".needlepoint/") {
+        return Err("Cannot operate on the .needlepoint directory".to_string());
+    }
+    Ok(full_path)
+}
+
+/// Soft delete a directory by archiving it into the trash folder
+/// Returns the trash filename for potential restoration
 #[command]
-pub fn restore_file(project_path: String, trash_filename: String, original_path: String) -> Result<(), String> {
-    // Validate the original path where we'll restore to
-    let restore_path = validate_path(&project_path, &original_path)?;
+pub fn delete_directory(project_path: String, dir_path: String) -> Result<String, String> {
+    let full_path = validate_directory_path(&project_path, &dir_path)?;
+
+    if !full_path.exists() {
+        return Ok(String::new()); // Directory doesn't exist, nothing to delete
+    }
+    if !full_path.is_dir() {
+        return Err(format!("{} is not a directory", dir_path));
+    }
+
+    let trash_dir = get_trash_dir(&project_path);
+    fs::create_dir_all(&trash_dir).map_err(|e| format!("Failed to create trash directory: {}", e))?;
+
+    let trash_filename = format!("{}.tar.gz", get_trash_filename(&dir_path));
+    let trash_path = trash_dir.join(&trash_filename);
+
+    let archive = fs::File::create(&trash_path)
+        .map_err(|e| format!("Failed to create trash archive: {}", e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(archive, Compression::default()));
+    builder
+        .append_dir_all(".", &full_path)
+        .and_then(|_| builder.into_inner())
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| {
+            let _ = fs::remove_file(&trash_path);
+            format!("Failed to archive directory: {}", e)
+        })?;
+
+    // Only remove the directory once the archive is safely written
+    fs::remove_dir_all(&full_path).map_err(|e| format!("Failed to remove directory: {}", e))?;
+
+    let size = fs::metadata(&trash_path).map(|m| m.len()).unwrap_or(0);
+    write_trash_entry(
+        &trash_dir,
+        &TrashEntry {
+            trash_filename: trash_filename.clone(),
+            original_path: Some(dir_path),
+            deleted_at: Utc::now(),
+            size,
+            is_dir: true,
+        },
+    )?;
+
+    Ok(trash_filename)
+}
+
+/// Move a directory and everything in it within the project
+#[command]
+pub fn move_directory(project_path: String, old_path: String, new_path: String) -> Result<(), String> {
+    let old_full_path = validate_directory_path(&project_path, &old_path)?;
+    let new_full_path = validate_directory_path(&project_path, &new_path)?;
+
+    if !old_full_path.is_dir() {
+        return Err(format!("Directory not found: {}", old_path));
+    }
+    if new_full_path.exists() {
+        return Err(format!("{} already exists", new_path));
+    }
+
+    let old_relative = normalize_file_path(&old_path);
+    let new_relative = normalize_file_path(&new_path);
+    if new_relative.starts_with(&format!("{}/", old_relative.trim_end_matches('/'))) {
+        return Err("Cannot move a directory into itself".to_string());
+    }
+
+    if let Some(parent) = new_full_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directories: {}", e))?;
+    }
+
+    fs::rename(&old_full_path, &new_full_path)
+        .map_err(|e| format!("Failed to move directory: {}", e))
+}
+
+/// Restore a file from trash. Without `original_path` it is restored to the
+/// path recorded when it was trashed.
+#[command]
+pub fn restore_file(
+    project_path: String,
+    trash_filename: String,
+    original_path: Option<String>,
+) -> Result<(), String> {
+    if trash_filename.contains(['/', '\\']) || trash_filename.contains("..") {
+        return Err("Invalid trash filename".to_string());
+    }
 
     let trash_dir = get_trash_dir(&project_path);
     let trash_path = trash_dir.join(&trash_filename);
@@ -179,20 +489,45 @@ pub fn restore_file(project_path: String, trash_filename: String, original_path:
         return Err("File not found in trash".to_string());
     }
 
+    let entry = read_trash_entry(&trash_dir, &trash_filename)?;
+    let original_path = match original_path {
+        Some(path) => path,
+        None => entry
+            .original_path
+            .ok_or_else(|| "Original path of trashed file is unknown".to_string())?,
+    };
+
+    // Validate the original path where we'll restore to
+    let restore_path = validate_path(&project_path, &original_path)?;
+
     // Create parent directories for restore path if needed
     if let Some(parent) = restore_path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directories: {}", e))?;
     }
 
-    // Move file back from trash
-    fs::rename(&trash_path, &restore_path).map_err(|e| format!("Failed to restore file: {}", e))?;
+    if entry.is_dir {
+        // Unpack the archived directory, refusing to merge into an existing one
+        if restore_path.exists() {
+            return Err(format!("{} already exists", original_path));
+        }
+        let archive = fs::File::open(&trash_path)
+            .map_err(|e| format!("Failed to open trash archive: {}", e))?;
+        tar::Archive::new(GzDecoder::new(archive))
+            .unpack(&restore_path)
+            .map_err(|e| format!("Failed to restore directory: {}", e))?;
+        fs::remove_file(&trash_path).map_err(|e| format!("Failed to remove trash archive: {}", e))?;
+    } else {
+        // Move file back from trash
+        fs::rename(&trash_path, &restore_path).map_err(|e| format!("Failed to restore file: {}", e))?;
+    }
+    let _ = fs::remove_file(trash_info_path(&trash_dir, &trash_filename));
 
     Ok(())
 }
 
-/// List files in trash
+/// List files in trash, most recently deleted first
 #[command]
-pub fn list_trash(project_path: String) -> Result<Vec<String>, String> {
+pub fn list_trash(project_path: String) -> Result<Vec<TrashEntry>, String> {
     let trash_dir = get_trash_dir(&project_path);
 
     if !trash_dir.exists() {
@@ -203,44 +538,75 @@ pub fn list_trash(project_path: String) -> Result<Vec<String>, String> {
         .map_err(|e| format!("Failed to read trash directory: {}", e))?;
 
     let mut files = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if let Some(name) = entry.file_name().to_str() {
-                files.push(name.to_string());
-            }
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_some_and(|e| e == TRASH_INFO_EXT) {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            files.push(read_trash_entry(&trash_dir, name)?);
         }
     }
 
+    files.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
     Ok(files)
 }
 
+/// Delete a trashed file and its metadata
+fn remove_trashed(trash_dir: &Path, trash_filename: &str) -> bool {
+    let _ = fs::remove_file(trash_info_path(trash_dir, trash_filename));
+    fs::remove_file(trash_dir.join(trash_filename)).is_ok()
+}
+
 /// Empty the trash (permanently delete all trashed files)
 #[command]
 pub fn empty_trash(project_path: String) -> Result<u32, String> {
     let trash_dir = get_trash_dir(&project_path);
 
-    if !trash_dir.exists() {
-        return Ok(0);
+    let mut deleted_count = 0;
+    for entry in list_trash(project_path)? {
+        if remove_trashed(&trash_dir, &entry.trash_filename) {
+            deleted_count += 1;
+        }
     }
 
-    let entries = fs::read_dir(&trash_dir)
-        .map_err(|e| format!("Failed to read trash directory: {}", e))?;
+    Ok(deleted_count)
+}
+
+/// Permanently delete trashed files older than `max_age_days`
+#[command]
+pub fn purge_trash(project_path: String, max_age_days: u32) -> Result<u32, String> {
+    let trash_dir = get_trash_dir(&project_path);
+    let cutoff = Utc::now() - Duration::days(max_age_days as i64);
 
     let mut deleted_count = 0;
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() {
-                if fs::remove_file(&path).is_ok() {
-                    deleted_count += 1;
-                }
-            }
+    for entry in list_trash(project_path)? {
+        if entry.deleted_at < cutoff && remove_trashed(&trash_dir, &entry.trash_filename) {
+            deleted_count += 1;
         }
     }
 
     Ok(deleted_count)
 }
 
+/// Purge trash older than the project's retention period, logging rather than
+/// failing since it runs as a side effect of loading a project
+pub(crate) fn auto_purge_trash(project: &Project) {
+    let days = project
+        .manifest
+        .trash_retention_days
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+    if days == 0 {
+        return;
+    }
+
+    match purge_trash(project.project_path.clone(), days) {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Purged {} files older than {} days from trash", count, days),
+        Err(e) => tracing::warn!("Failed to purge trash: {}", e),
+    }
+}
+
 /// Rename/move a file within the project
 #[command]
 pub fn rename_file(
@@ -281,40 +647,205 @@ pub fn create_directory(project_path: String, dir_path: String) -> Result<(), St
     Ok(())
 }
 
+/// A file or directory under the project root
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTreeEntry {
+    pub name: String,
+    /// Path relative to the project root, with forward slashes
+    pub path: String,
+    pub is_dir: bool,
+    /// The node that generates this file, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FileTreeEntry>,
+}
+
+/// The project directory as it is on disk, compared with the graph
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFiles {
+    pub entries: Vec<FileTreeEntry>,
+    /// Node file paths that don't exist on disk
+    pub missing: Vec<String>,
+}
+
+/// Insert a path (split into components) into a tree, creating parent directories as needed
+fn insert_tree_entry(
+    entries: &mut Vec<FileTreeEntry>,
+    components: &[&str],
+    parent: &str,
+    is_dir: bool,
+    node_id: Option<String>,
+) {
+    let Some((name, rest)) = components.split_first() else {
+        return;
+    };
+    let path = if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", parent, name)
+    };
+
+    let index = match entries.iter().position(|e| e.name == *name) {
+        Some(i) => i,
+        None => {
+            entries.push(FileTreeEntry {
+                name: name.to_string(),
+                path: path.clone(),
+                is_dir: is_dir || !rest.is_empty(),
+                node_id: None,
+                children: Vec::new(),
+            });
+            entries.len() - 1
+        }
+    };
+
+    if rest.is_empty() {
+        entries[index].node_id = node_id;
+    } else {
+        insert_tree_entry(&mut entries[index].children, rest, &path, is_dir, node_id);
+    }
+}
+
+/// Build the project directory tree and annotate files with the nodes that generate them
+pub(crate) fn build_file_tree(project: &Project) -> Result<ProjectFiles, String> {
+    let node_files: HashMap<String, &str> = project
+        .nodes
+        .iter()
+        .filter(|n| n.owns_file())
+        .map(|n| (normalize_file_path(&n.file_path), n.id.as_str()))
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut found = HashSet::new();
+
+    for (relative, is_dir) in walk_project_files(Path::new(&project.project_path))? {
+        let node_id = node_files.get(&relative).map(|id| id.to_string());
+        if node_id.is_some() {
+            found.insert(relative.clone());
+        }
+
+        let components: Vec<&str> = relative.split('/').collect();
+        insert_tree_entry(&mut entries, &components, "", is_dir, node_id);
+    }
+
+    let mut missing: Vec<String> = node_files
+        .keys()
+        .filter(|path| !found.contains(*path))
+        .cloned()
+        .collect();
+    missing.sort();
+
+    Ok(ProjectFiles { entries, missing })
+}
+
+/// List the files under the project directory, annotated with their nodes
+#[command]
+pub fn list_project_files(project: Project) -> Result<ProjectFiles, String> {
+    build_file_tree(&project)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::model::Language;
 
     #[test]
-    fn test_validate_path_rejects_parent_dir() {
-        let result = validate_path("/tmp/project", "../etc/passwd");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("directory traversal"));
+    fn test_trash_restore_by_filename() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        write_file(project_path.clone(), "src/a.ts".to_string(), "a".to_string()).unwrap();
+
+        let trash_filename = delete_file(project_path.clone(), "src/a.ts".to_string()).unwrap();
+        let trash = list_trash(project_path.clone()).unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].trash_filename, trash_filename);
+        assert_eq!(trash[0].original_path.as_deref(), Some("src/a.ts"));
+        assert_eq!(trash[0].size, 1);
+
+        // Nothing is old enough to purge yet
+        assert_eq!(purge_trash(project_path.clone(), 1).unwrap(), 0);
+
+        restore_file(project_path.clone(), trash_filename, None).unwrap();
+        assert!(dir.path().join("src/a.ts").exists());
+        assert!(list_trash(project_path).unwrap().is_empty());
     }
 
     #[test]
-    fn test_validate_path_rejects_absolute() {
-        let result = validate_path("/tmp/project", "/etc/passwd");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Absolute paths"));
+    fn test_delete_and_restore_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        write_file(project_path.clone(), "src/api/a.ts".to_string(), "a".to_string()).unwrap();
+        write_file(project_path.clone(), "src/api/b/c.ts".to_string(), "c".to_string()).unwrap();
+
+        move_directory(project_path.clone(), "src/api".to_string(), "src/server".to_string())
+            .unwrap();
+        assert!(move_directory(project_path.clone(), "src".to_string(), "src/x".to_string()).is_err());
+        assert!(delete_directory(project_path.clone(), ".".to_string()).is_err());
+
+        let trash_filename = delete_directory(project_path.clone(), "src/server".to_string()).unwrap();
+        assert!(!dir.path().join("src/server").exists());
+        assert!(list_trash(project_path.clone()).unwrap()[0].is_dir);
+
+        restore_file(project_path, trash_filename, None).unwrap();
+        let restored = fs::read_to_string(dir.path().join("src/server/b/c.ts")).unwrap();
+        assert_eq!(restored, "c");
     }
 
     #[test]
-    fn test_validate_path_rejects_empty() {
-        let result = validate_path("/tmp/project", "");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("empty"));
+    fn test_read_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().to_string_lossy().to_string();
+        write_file(project_path.clone(), "a.ts".to_string(), "export {};".to_string()).unwrap();
+        fs::write(dir.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+
+        let files = vec![
+            "a.ts".to_string(),
+            "logo.png".to_string(),
+            "missing.ts".to_string(),
+            "../outside".to_string(),
+        ];
+        let results = read_files(project_path, files);
+        assert!(matches!(&results[0], FileReadResult::Ok(f) if f.content.as_deref() == Some("export {};")));
+        assert!(matches!(&results[1], FileReadResult::Ok(f) if f.binary && f.content.is_none()));
+        assert!(matches!(&results[2], FileReadResult::Err(e) if e.error.contains("not found")));
+        assert!(matches!(&results[3], FileReadResult::Err(e) if e.error.contains("traversal")));
     }
 
     #[test]
-    fn test_validate_path_accepts_normal_paths() {
-        // Note: This test requires the project path to exist
-        // In real tests, we'd use a temp directory
-        let result = validate_path(".", "src/main.rs");
-        // Should not error on path format (may error if path doesn't exist)
-        if let Err(e) = &result {
-            assert!(!e.contains("traversal"));
-            assert!(!e.contains("Absolute"));
-        }
+    fn test_build_file_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("dist")).unwrap();
+        fs::create_dir_all(root.join(".needlepoint/runs")).unwrap();
+        fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+        fs::write(root.join("src/a.ts"), "").unwrap();
+        fs::write(root.join("src/notes.md"), "").unwrap();
+        fs::write(root.join("dist/a.js"), "").unwrap();
+        fs::write(root.join(".needlepoint/runs/r.json"), "").unwrap();
+
+        let mut project = Project::new(root.to_string_lossy().to_string());
+        let node = CodeNode::new("A".to_string(), "src/a.ts".to_string(), Language::TypeScript);
+        let node_id = node.id.clone();
+        project.nodes.push(node);
+        project.nodes.push(CodeNode::new(
+            "B".to_string(),
+            "src/b.ts".to_string(),
+            Language::TypeScript,
+        ));
+
+        let files = build_file_tree(&project).unwrap();
+        let names: Vec<&str> = files.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec![".gitignore", "src"]);
+
+        let src = &files.entries[1];
+        assert!(src.is_dir);
+        assert_eq!(src.children[0].path, "src/a.ts");
+        assert_eq!(src.children[0].node_id.as_deref(), Some(node_id.as_str()));
+        assert_eq!(src.children[1].node_id, None);
+        assert_eq!(files.missing, vec!["src/b.ts".to_string()]);
     }
 }
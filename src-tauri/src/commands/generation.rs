@@ -1,8 +1,20 @@
 use std::env;
+use std::time::Instant;
 use tauri::command;
 
-use crate::graph::model::Project;
-use crate::llm::{create_provider, strip_code_blocks, ContextBuilder, GenerationRequest};
+use crate::api::state::ApiKeys;
+use crate::graph::constraints::{fix_generated_code, FixupOptions};
+use crate::graph::model::{request_timeout, EmbeddingConfig, Project};
+use crate::graph::search::refresh_for_related_context;
+use crate::graph::settings::load_settings_or_default;
+use crate::graph::variables::resolve_node;
+use crate::llm::mistral::{list_models, MistralModel};
+use crate::llm::{
+    create_provider, extract_code, generate_with_timeout, ContextBuilder, GenerationRequest,
+    PendingTranscript,
+};
+use crate::orchestration::report::check_project_budget;
+use crate::orchestration::PostProcessor;
 
 /// Generate code for a specific node
 /// api_key: Optional API key passed from the frontend settings
@@ -16,23 +28,36 @@ pub async fn generate_node(
         .find_node(&node_id)
         .ok_or_else(|| format!("Node '{}' not found", node_id))?;
 
+    if node.disabled {
+        return Err(format!("Node '{}' is disabled", node.name));
+    }
+    if node.pinned {
+        return Err(format!("Node '{}' is pinned; unpin it to regenerate", node.name));
+    }
+    let settings = load_settings_or_default(&project.project_path);
+    check_project_budget(&project.project_path, settings.project_budget())?;
+
+    // Embed changed nodes so the prompt's related context is current
+    let env_keys = ApiKeys::from_env(Some(&project.manifest));
+    let embedding_provider = EmbeddingConfig::for_manifest(&project.manifest).provider;
+    let embedding_key = env_keys.get_for_provider(&embedding_provider);
+    refresh_for_related_context(&project, embedding_key.as_deref()).await;
+
     // Build the prompt from context
-    let prompt = ContextBuilder::build_prompt(&project, &node_id)
-        .ok_or_else(|| "Failed to build prompt".to_string())?;
+    let (prompt, cache_breakpoints) =
+        ContextBuilder::build_prompt_with_breakpoints(&project, &node_id)
+            .ok_or_else(|| "Failed to build prompt".to_string())?;
 
-    let system_prompt = ContextBuilder::build_system_prompt(node);
+    let system_prompt = ContextBuilder::build_system_prompt(&project.manifest, node);
+    let llm_config = node.llm_config.resolve(&project.manifest.default_llm);
 
     // Use provided API key, or fall back to environment variable
-    let effective_api_key = api_key.filter(|k| !k.is_empty()).or_else(|| {
-        match node.llm_config.provider {
-            crate::graph::model::LLMProvider::Anthropic => env::var("ANTHROPIC_API_KEY").ok(),
-            crate::graph::model::LLMProvider::OpenAI => env::var("OPENAI_API_KEY").ok(),
-            crate::graph::model::LLMProvider::Ollama => None, // No API key needed
-        }
-    });
+    let effective_api_key = api_key
+        .filter(|k| !k.is_empty())
+        .or_else(|| env_keys.get_for_provider(&llm_config.provider));
 
     // Create provider and generate
-    let provider = create_provider(&node.llm_config, effective_api_key);
+    let provider = create_provider(&llm_config, effective_api_key.clone(), &project);
 
     if !provider.is_configured() {
         return Err(format!(
@@ -46,15 +71,38 @@ pub async fn generate_node(
         system_prompt: Some(system_prompt),
         max_tokens: Some(4096),
         temperature: Some(0.7),
+        cache_breakpoints,
+        output_schema: None,
+        seed: llm_config.effective_seed(),
+        timeout: Some(request_timeout(&project.manifest, &llm_config)),
     };
+    let transcript = PendingTranscript::begin(&project, node, &llm_config, &request);
+    let fixup = FixupOptions::from_manifest(&project.manifest)
+        .map(|options| (options, request.clone()));
+    // Fix-ups and post-processing see the spec as the model does
+    let resolved = resolve_node(&project.manifest, node);
+    let post_processor = PostProcessor::for_node(&project, &resolved);
 
-    let response = provider
-        .generate(request)
-        .await
-        .map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    let result = generate_with_timeout(provider.as_ref(), request).await;
+    if let Some(transcript) = transcript {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        transcript.finish(&result, duration_ms, effective_api_key.as_deref());
+    }
+    let response = result.map_err(|e| e.to_string())?;
 
-    // Strip markdown code blocks if present
-    Ok(strip_code_blocks(&response.content))
+    // Strip markdown code blocks and prose if present
+    let code = extract_code(&response.content, &node.language, node.kind).code;
+    let code = match &fixup {
+        Some((options, request)) => {
+            fix_generated_code(provider.as_ref(), &resolved, request, code, *options).await.0
+        }
+        None => code,
+    };
+    Ok(match &post_processor {
+        Some(processor) => processor.run(code).await.0,
+        None => code,
+    })
 }
 
 /// Get the prompt that would be used for generation (for preview)
@@ -63,3 +111,20 @@ pub fn preview_prompt(project: Project, node_id: String) -> Result<String, Strin
     ContextBuilder::build_prompt(&project, &node_id)
         .ok_or_else(|| format!("Node '{}' not found", node_id))
 }
+
+/// Route provider requests through the proxy from the app's settings (`None` to
+/// clear it and fall back to the environment)
+#[command]
+pub fn set_proxy(proxy_url: Option<String>) -> Result<(), String> {
+    crate::llm::http::set_proxy(proxy_url)
+}
+
+/// The Mistral models the API key (or `MISTRAL_API_KEY`) can use
+#[command]
+pub async fn list_mistral_models(api_key: Option<String>) -> Result<Vec<MistralModel>, String> {
+    let api_key = api_key
+        .filter(|k| !k.is_empty())
+        .or_else(|| env::var("MISTRAL_API_KEY").ok())
+        .ok_or_else(|| "No Mistral API key is set".to_string())?;
+    list_models(&api_key).await.map_err(|e| e.to_string())
+}
@@ -1,7 +1,9 @@
 use tauri::command;
 use uuid::Uuid;
 
-use crate::graph::{CodeEdge, CodeNode, Project};
+use crate::graph::{CodeEdge, CodeNode, DeleteCascade, EdgeUpdate, Project, SharedType};
+use crate::graph::layout::auto_layout;
+use crate::graph::suggest::{suggest_edges, EdgeSuggestion};
 use crate::graph::validation::would_create_cycle;
 
 /// Add a new node to the project
@@ -26,6 +28,7 @@ pub fn add_node(mut project: Project, node: CodeNode) -> Result<Project, String>
     }
 
     project.nodes.push(new_node);
+    project.apply_default_llm();
     Ok(project)
 }
 
@@ -43,27 +46,32 @@ pub fn update_node(mut project: Project, node_id: String, updates: CodeNode) ->
     node.description = updates.description;
     node.purpose = updates.purpose;
     node.exports = updates.exports;
+    node.reference_files = updates.reference_files;
+    node.snippets = updates.snippets;
+    node.shared_types = updates.shared_types;
     node.llm_config = updates.llm_config;
     node.position = updates.position;
 
+    // Declared exports replace any stub interface dependents were generated against
+    if !node.exports.is_empty() {
+        project.mark_stub_dependents_stale(&node_id);
+    }
+
     Ok(project)
 }
 
 /// Delete a node and its connected edges
+/// `cascade` controls how dependents are handled (defaults to marking them stale)
 #[command]
-pub fn delete_node(mut project: Project, node_id: String) -> Result<Project, String> {
-    // Remove the node
-    let initial_len = project.nodes.len();
-    project.nodes.retain(|n| n.id != node_id);
-
-    if project.nodes.len() == initial_len {
-        return Err(format!("Node '{}' not found", node_id));
-    }
-
-    // Remove connected edges
+pub fn delete_node(
+    mut project: Project,
+    node_id: String,
+    cascade: Option<DeleteCascade>,
+) -> Result<Project, String> {
+    // Remove the node and its edges, handling dependents per the cascade mode
     project
-        .edges
-        .retain(|e| e.source != node_id && e.target != node_id);
+        .remove_node(&node_id, cascade.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
 
     Ok(project)
 }
@@ -109,6 +117,20 @@ pub fn add_edge(
     Ok(project)
 }
 
+/// Change an edge's label or endpoints, keeping its ID
+#[command]
+pub fn update_edge(
+    mut project: Project,
+    edge_id: String,
+    updates: EdgeUpdate,
+) -> Result<Project, String> {
+    project
+        .update_edge(&edge_id, updates)
+        .map_err(|e| e.to_string())?;
+
+    Ok(project)
+}
+
 /// Delete an edge
 #[command]
 pub fn delete_edge(mut project: Project, edge_id: String) -> Result<Project, String> {
@@ -127,3 +149,60 @@ pub fn delete_edge(mut project: Project, edge_id: String) -> Result<Project, Str
 pub fn check_would_create_cycle(project: Project, source: String, target: String) -> bool {
     would_create_cycle(&project, &source, &target)
 }
+
+/// Edges the project seems to be missing, most confident first
+#[command]
+pub fn suggest_project_edges(project: Project) -> Vec<EdgeSuggestion> {
+    suggest_edges(&project)
+}
+
+/// Position every node in dependency layers
+#[command]
+pub fn auto_layout_project(mut project: Project) -> Result<Project, String> {
+    auto_layout(&mut project);
+    Ok(project)
+}
+
+/// Add or change a shared context snippet, marking nodes generated with its old
+/// text as stale
+#[command]
+pub fn set_snippet(mut project: Project, name: String, text: String) -> Result<Project, String> {
+    if name.trim().is_empty() {
+        return Err("Snippet name cannot be empty".to_string());
+    }
+    project.set_snippet(&name, text);
+    Ok(project)
+}
+
+/// Remove a shared context snippet, marking nodes generated with it as stale
+#[command]
+pub fn delete_snippet(mut project: Project, name: String) -> Result<Project, String> {
+    project
+        .remove_snippet(&name)
+        .ok_or_else(|| format!("Snippet '{}' not found", name))?;
+    Ok(project)
+}
+
+/// Add or change a shared type, marking nodes generated with its old definition
+/// as stale
+#[command]
+pub fn set_shared_type(
+    mut project: Project,
+    name: String,
+    shared_type: SharedType,
+) -> Result<Project, String> {
+    if name.trim().is_empty() {
+        return Err("Shared type name cannot be empty".to_string());
+    }
+    project.set_shared_type(&name, shared_type);
+    Ok(project)
+}
+
+/// Remove a shared type, marking nodes generated with it as stale
+#[command]
+pub fn delete_shared_type(mut project: Project, name: String) -> Result<Project, String> {
+    project
+        .remove_shared_type(&name)
+        .ok_or_else(|| format!("Shared type '{}' not found", name))?;
+    Ok(project)
+}
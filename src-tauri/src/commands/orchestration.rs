@@ -1,8 +1,31 @@
-use serde::Deserialize;
-use tauri::{command, AppHandle};
+use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter, State};
+
+use crate::api::state::AppState;
 use crate::graph::model::Project;
-use crate::orchestration::{executor::ApiKeys, ExecutionPlan, Executor};
+use crate::graph::settings::load_settings_or_default;
+use crate::orchestration::checkpoint::{clear_checkpoint, load_checkpoint};
+use crate::orchestration::github::{self, PullRequest};
+use crate::orchestration::report::load_run_report;
+use crate::orchestration::split::{measure_node, propose_split};
+use crate::orchestration::{
+    executor::ApiKeys, EventSink, ExecutionEvent, ExecutionOptions, ExecutionPlan, Executor,
+    InterruptedRun, PilotReport, SplitSuggestion, StepAction,
+};
+
+/// The event channel name for execution events
+pub const EXECUTION_EVENT_CHANNEL: &str = "execution-progress";
+
+/// Sends execution events to the frontend
+struct FrontendEvents(AppHandle);
+
+impl EventSink for FrontendEvents {
+    fn send(&self, event: ExecutionEvent) {
+        let _ = self.0.emit(EXECUTION_EVENT_CHANNEL, &event);
+    }
+}
 
 /// API keys passed from the frontend
 #[derive(Debug, Clone, Deserialize)]
@@ -10,6 +33,7 @@ use crate::orchestration::{executor::ApiKeys, ExecutionPlan, Executor};
 pub struct ApiKeysInput {
     pub anthropic: Option<String>,
     pub openai: Option<String>,
+    pub mistral: Option<String>,
     pub ollama_base_url: Option<String>,
 }
 
@@ -18,11 +42,20 @@ impl From<ApiKeysInput> for ApiKeys {
         ApiKeys {
             anthropic: input.anthropic.filter(|s| !s.is_empty()),
             openai: input.openai.filter(|s| !s.is_empty()),
+            mistral: input.mistral.filter(|s| !s.is_empty()),
             ollama_base_url: input.ollama_base_url.filter(|s| !s.is_empty()),
         }
     }
 }
 
+/// Result of a pilot run: the project with the piloted wave generated, plus metrics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PilotResult {
+    pub project: Project,
+    pub report: PilotReport,
+}
+
 /// Get the execution plan for a project (for preview)
 #[command]
 pub fn get_execution_plan(project: Project) -> ExecutionPlan {
@@ -31,25 +64,212 @@ pub fn get_execution_plan(project: Project) -> ExecutionPlan {
 
 /// Generate all nodes in the project
 /// Returns the updated project with generated code
+/// With `consistency_pass`, an LLM review of cross-file consistency runs afterwards
+/// With `skip_completed`, nodes that are already complete are left as they are
+/// With `tags`, only nodes carrying one of them are generated
+/// With `profile`, the named manifest profile overrides every node's model
+/// With `step`, the run pauses after each wave until `continue_execution`
+/// With `max_duration_secs`, nodes not started in time are left pending
+#[allow(clippy::too_many_arguments)]
 #[command]
 pub async fn generate_all(
     app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
     project: Project,
     api_keys: ApiKeysInput,
+    consistency_pass: Option<bool>,
+    skip_completed: Option<bool>,
+    force: Option<bool>,
+    tags: Option<Vec<String>>,
+    profile: Option<String>,
+    step: Option<bool>,
+    max_duration_secs: Option<u64>,
 ) -> Result<Project, String> {
-    let executor = Executor::new(app_handle, project, api_keys.into());
-    Ok(executor.execute_all().await)
+    let options = ExecutionOptions {
+        skip_completed: skip_completed.unwrap_or(false),
+        force: force.unwrap_or(false),
+        tags: tags.unwrap_or_default(),
+    };
+    let profile = match profile {
+        Some(name) => Some(project.profile(&name)?.clone()),
+        None => None,
+    };
+    let settings = load_settings_or_default(&project.project_path);
+    let executor = Executor::new(FrontendEvents(app_handle), project, api_keys.into())
+        .with_profile(profile)
+        .with_settings(&settings)
+        .with_step_mode(step.unwrap_or(false).then(|| state.step.clone()))
+        .with_approval_gates(Some(state.step.clone()))
+        .with_max_duration(max_duration_secs.map(std::time::Duration::from_secs));
+    let project = executor.execute_all(options).await;
+    if consistency_pass.unwrap_or(false) {
+        return Ok(executor.execute_consistency_pass().await);
+    }
+    Ok(project)
 }
 
 /// Generate specific nodes in the project
 /// Respects dependency order - will generate dependencies first
+/// With `force`, all transitive dependencies are regenerated fresh as well
+/// With `step`, the run pauses after each wave until `continue_execution`
+/// With `max_duration_secs`, nodes not started in time are left pending
+#[allow(clippy::too_many_arguments)]
 #[command]
 pub async fn generate_nodes(
     app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
     project: Project,
     node_ids: Vec<String>,
     api_keys: ApiKeysInput,
+    skip_completed: Option<bool>,
+    force: Option<bool>,
+    step: Option<bool>,
+    max_duration_secs: Option<u64>,
+) -> Result<Project, String> {
+    let options = ExecutionOptions {
+        skip_completed: skip_completed.unwrap_or(false),
+        force: force.unwrap_or(false),
+        ..Default::default()
+    };
+    let settings = load_settings_or_default(&project.project_path);
+    let executor = Executor::new(FrontendEvents(app_handle), project, api_keys.into())
+        .with_settings(&settings)
+        .with_step_mode(step.unwrap_or(false).then(|| state.step.clone()))
+        .with_approval_gates(Some(state.step.clone()))
+        .with_max_duration(max_duration_secs.map(std::time::Duration::from_secs));
+    Ok(executor.execute_nodes(node_ids, options).await)
+}
+
+/// Let a run paused in step mode generate its next wave, with the specs of `project`
+/// when given, or end it with `stop`
+#[command]
+pub fn continue_execution(
+    state: State<'_, Arc<AppState>>,
+    project: Option<Project>,
+    stop: Option<bool>,
+) -> Result<(), String> {
+    let action = if stop.unwrap_or(false) {
+        StepAction::Stop
+    } else {
+        StepAction::Continue(project.map(Box::new))
+    };
+    if state.step.resume(action) {
+        Ok(())
+    } else {
+        Err("No generation is paused".to_string())
+    }
+}
+
+/// Approve the code of the nodes a run is paused for, with the specs of `project` when
+/// given, or `reject` it so nothing depending on it is generated
+#[command]
+pub fn approve_generation(
+    state: State<'_, Arc<AppState>>,
+    project: Option<Project>,
+    reject: Option<bool>,
+) -> Result<(), String> {
+    let awaiting = state.step.paused().is_some_and(|p| !p.awaiting_approval.is_empty());
+    let action = if reject.unwrap_or(false) {
+        StepAction::Reject
+    } else {
+        StepAction::Continue(project.map(Box::new))
+    };
+    if awaiting && state.step.resume(action) {
+        Ok(())
+    } else {
+        Err("No generation is awaiting approval".to_string())
+    }
+}
+
+/// The run that was generating the project when the app was closed, if any
+#[command]
+pub fn get_interrupted_run(project_path: String) -> Result<Option<InterruptedRun>, String> {
+    let checkpoint = load_checkpoint(&project_path).map_err(|e| e.to_string())?;
+    Ok(checkpoint.map(|c| c.interrupted()))
+}
+
+/// Restore the code an interrupted run generated and generate the nodes it didn't get to
+#[command]
+pub async fn resume_interrupted_run(
+    app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    mut project: Project,
+    api_keys: ApiKeysInput,
 ) -> Result<Project, String> {
-    let executor = Executor::new(app_handle, project, api_keys.into());
-    Ok(executor.execute_nodes(node_ids).await)
+    let checkpoint = load_checkpoint(&project.project_path)
+        .map_err(|e| e.to_string())?
+        .ok_or("No interrupted run to resume")?;
+    checkpoint.restore(&mut project);
+    let remaining = checkpoint.remaining();
+    let settings = load_settings_or_default(&project.project_path);
+    let executor = Executor::new(FrontendEvents(app_handle), project, api_keys.into())
+        .with_settings(&settings)
+        .with_approval_gates(Some(state.step.clone()))
+        .resuming(checkpoint);
+    Ok(executor.execute_nodes(remaining, ExecutionOptions::default()).await)
+}
+
+/// Forget an interrupted run instead of resuming it
+#[command]
+pub fn discard_interrupted_run(project_path: String) -> Result<(), String> {
+    clear_checkpoint(&project_path).map_err(|e| e.to_string())
+}
+
+/// Generate a single wave (wave 0 by default) as a trial run and report
+/// quality and cost metrics before committing to the full graph
+#[command]
+pub async fn generate_pilot(
+    app_handle: AppHandle,
+    project: Project,
+    api_keys: ApiKeysInput,
+    wave_number: Option<usize>,
+    time_limit_secs: Option<u64>,
+) -> Result<PilotResult, String> {
+    let settings = load_settings_or_default(&project.project_path);
+    let executor = Executor::new(FrontendEvents(app_handle), project, api_keys.into())
+        .with_settings(&settings);
+    let report = executor
+        .execute_pilot(
+            wave_number.unwrap_or(0),
+            time_limit_secs.map(std::time::Duration::from_secs),
+        )
+        .await?;
+    Ok(PilotResult {
+        project: executor.project().await,
+        report,
+    })
+}
+
+/// Measure a node against the size thresholds past which generation quality drops.
+/// With `api_keys`, the project's default LLM also proposes how to split it.
+#[command]
+pub async fn suggest_node_split(
+    project: Project,
+    node_id: String,
+    api_keys: Option<ApiKeysInput>,
+) -> Result<SplitSuggestion, String> {
+    let mut suggestion = measure_node(&project, &node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))?;
+    if let Some(api_keys) = api_keys {
+        let provider = &project.manifest.default_llm.provider;
+        let api_key = ApiKeys::from(api_keys).get_for_provider(provider);
+        suggestion.proposal = propose_split(&project, &node_id, api_key).await?;
+    }
+    Ok(suggestion)
+}
+
+/// Commit the files a run wrote to a new branch and open a pull request for it, in
+/// the repository set in the project settings
+#[command]
+pub async fn open_pull_request(
+    project: Project,
+    run_id: String,
+    github_token: String,
+) -> Result<PullRequest, String> {
+    if github_token.is_empty() {
+        return Err("Set a GitHub token in the settings first".to_string());
+    }
+    let report = load_run_report(&project.project_path, &run_id).map_err(|e| e.to_string())?;
+    let settings = load_settings_or_default(&project.project_path);
+    github::open_pull_request(&project, &report, &settings, &github_token).await
 }
@@ -1,21 +1,90 @@
 use std::path::Path;
+use std::sync::Arc;
 
-use tauri::command;
+use tauri::{command, State};
 
+use crate::api::state::AppState;
 use crate::graph::{
     load_project_from_file, save_project_to_file, Project,
 };
+use crate::recent::{self, RecentProjects};
 
 /// Load a project from a YAML file
 #[command]
 pub fn load_project(path: String) -> Result<Project, String> {
     let path = Path::new(&path);
 
-    load_project_from_file(path).map_err(|e| e.to_string())
+    let project = load_project_from_file(path).map_err(|e| e.to_string())?;
+    crate::logging::set_project_dir(&project.project_path);
+    crate::commands::filesystem::auto_purge_trash(&project);
+    recent::record_opened(&project);
+    Ok(project)
 }
 
-/// Save a project to its YAML file
+/// Unload the project the HTTP API and MCP serve. Unsaved changes must be saved or
+/// discarded explicitly.
 #[command]
-pub fn save_project(project: Project) -> Result<(), String> {
-    save_project_to_file(&project).map_err(|e| e.to_string())
+pub async fn close_project(
+    state: State<'_, Arc<AppState>>,
+    save: Option<bool>,
+    discard: Option<bool>,
+) -> Result<(), String> {
+    if state.get_project().await.is_none() {
+        return Err("No project loaded".to_string());
+    }
+    if save.unwrap_or(false) {
+        state
+            .save_project()
+            .await
+            .map_err(|e| format!("Failed to save project: {}", e))?;
+    } else if !discard.unwrap_or(false) && state.has_unsaved_changes().await {
+        return Err(
+            "The project has unsaved changes; save or discard them to close it".to_string(),
+        );
+    }
+    state.set_project(None).await;
+    Ok(())
+}
+
+/// The project auto-loaded on startup, if any
+#[command]
+pub async fn get_startup_project(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Option<Project>, String> {
+    Ok(state.get_project().await)
+}
+
+/// Recently opened projects, most recent first
+#[command]
+pub fn get_recent_projects() -> Result<RecentProjects, String> {
+    recent::load_recent().map_err(|e| e.to_string())
+}
+
+/// Turn reopening the last project on startup on or off
+#[command]
+pub fn set_auto_load_project(enabled: bool) -> Result<RecentProjects, String> {
+    recent::set_auto_load(enabled).map_err(|e| e.to_string())
+}
+
+/// Save a project to its YAML file. When it is the project the HTTP API and MCP serve,
+/// it replaces the loaded one and is saved through the state, so it no longer counts as
+/// unsaved.
+#[command]
+pub async fn save_project(
+    state: State<'_, Arc<AppState>>,
+    project: Project,
+) -> Result<(), String> {
+    let loaded = state
+        .get_project()
+        .await
+        .is_some_and(|p| p.project_path == project.project_path);
+    if !loaded {
+        return save_project_to_file(&project).map_err(|e| e.to_string());
+    }
+    state.update_project(|p| *p = project).await;
+    state
+        .save_project()
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to save project: {}", e))
 }
@@ -1,6 +0,0 @@
-pub mod model;
-pub mod serialization;
-pub mod validation;
-
-pub use model::*;
-pub use serialization::*;
@@ -1,5 +1,7 @@
 pub mod api;
 pub mod commands;
-pub mod graph;
-pub mod llm;
-pub mod orchestration;
+pub mod logging;
+pub mod mcp;
+pub mod recent;
+
+pub use needlepoint_core::{graph, llm, orchestration};
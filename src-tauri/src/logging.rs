@@ -0,0 +1,199 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+const LOGS_DIR: &str = ".needlepoint/logs";
+const LOG_FILE_NAME: &str = "needlepoint.log";
+
+/// Directory of the loaded project's logs (`None` until a project is loaded)
+static PROJECT_LOG_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Serializes appends so concurrent writers don't interleave lines
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Severity of a log entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A single line of the backend log (stored as JSON lines)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    /// Module that produced the entry (e.g. "needlepoint::orchestration::executor")
+    pub target: String,
+    pub message: String,
+}
+
+/// Install the global `tracing` subscriber: human-readable output on stderr and
+/// JSON lines in the log file. `RUST_LOG` overrides the default `info` filter.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let file_layer = tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_writer(LogFileWriter);
+
+    let stderr_layer = tracing_subscriber::fmt::layer().with_writer(io::stderr);
+
+    // Ignore the error if a subscriber is already installed (e.g. in tests)
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init();
+}
+
+/// Write logs to `<project>/.needlepoint/logs` from now on. Until a project is
+/// loaded, logs go to the OS-specific local data directory.
+pub fn set_project_dir(project_path: &str) {
+    let dir = Path::new(project_path).join(LOGS_DIR);
+    *PROJECT_LOG_DIR.write().unwrap_or_else(|e| e.into_inner()) = Some(dir);
+}
+
+/// Directory currently receiving the backend log
+pub fn log_dir() -> PathBuf {
+    if let Some(dir) = PROJECT_LOG_DIR.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        return dir;
+    }
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("needlepoint")
+        .join("logs")
+}
+
+/// Path of the backend log file
+pub fn log_file_path() -> PathBuf {
+    log_dir().join(LOG_FILE_NAME)
+}
+
+/// `MakeWriter` appending each formatted event to the current log file
+struct LogFileWriter;
+
+impl<'a> MakeWriter<'a> for LogFileWriter {
+    type Writer = LogFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogFileWriter
+    }
+}
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Logging must never take the app down, so failures only go to stderr
+        if let Err(e) = append(buf) {
+            eprintln!("Failed to write log file: {}", e);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn append(buf: &[u8]) -> Result<()> {
+    let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let dir = log_dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create log directory: {:?}", dir))?;
+
+    let path = dir.join(LOG_FILE_NAME);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open log file: {:?}", path))?;
+    file.write_all(buf)
+        .with_context(|| format!("Failed to write log file: {:?}", path))?;
+
+    Ok(())
+}
+
+/// Read the last `lines` lines of the log file, along with the file length
+/// (the offset to follow from)
+pub fn read_tail(lines: usize) -> Result<(String, u64)> {
+    let path = log_file_path();
+    if !path.exists() {
+        return Ok((String::new(), 0));
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read log file: {:?}", path))?;
+
+    let all: Vec<&str> = contents.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Ok((join_lines(&all[start..]), contents.len() as u64))
+}
+
+/// Read the log lines written after `since`, along with the file length
+pub fn read_after(since: DateTime<Utc>) -> Result<(String, u64)> {
+    let path = log_file_path();
+    if !path.exists() {
+        return Ok((String::new(), 0));
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read log file: {:?}", path))?;
+
+    let lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| {
+            serde_json::from_str::<LogEntry>(line)
+                .map(|entry| entry.timestamp > since)
+                .unwrap_or(false)
+        })
+        .collect();
+
+    Ok((join_lines(&lines), contents.len() as u64))
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Read whatever was appended to the log file since `offset`, returning the new
+/// data and the new offset. Starts over if the file was truncated.
+pub fn read_since(offset: u64) -> Result<(Vec<u8>, u64)> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let path = log_file_path();
+    let mut file = match fs::File::open(&path) {
+        Ok(f) => f,
+        Err(_) => return Ok((Vec::new(), 0)),
+    };
+
+    let len = file.metadata()?.len();
+    let offset = if len < offset { 0 } else { offset };
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .with_context(|| format!("Failed to read log file: {:?}", path))?;
+
+    let new_offset = offset + buf.len() as u64;
+    Ok((buf, new_offset))
+}
@@ -3,14 +3,28 @@
 
 mod api;
 mod commands;
-mod graph;
-mod llm;
-mod orchestration;
+mod logging;
+mod mcp;
+mod recent;
 
+use needlepoint_core::{graph, llm, orchestration};
 use std::sync::Arc;
 use api::state::AppState;
 
 fn main() {
+    logging::init();
+
+    // Headless MCP server over stdio: `needlepoint --mcp [project.yaml]`
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--mcp") {
+        let project_path = args.get(pos + 1).map(std::path::PathBuf::from);
+        if let Err(e) = tauri::async_runtime::block_on(run_mcp_stdio(project_path)) {
+            tracing::error!("MCP server failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Create shared state for HTTP API
     let app_state = AppState::new();
     let app_state_clone = Arc::clone(&app_state);
@@ -21,15 +35,26 @@ fn main() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .manage(app_state)
         .setup(move |_app| {
-            // Start HTTP API server in background
             let state = app_state_clone;
+            // Reopen the last project before the window asks for it (`get_startup_project`)
+            // and before serving, so API clients find it loaded. Keys come from the
+            // environment, including the manifest's `apiKeyEnv` once loaded.
+            tauri::async_runtime::block_on(async {
+                state.set_api_keys(api::state::ApiKeys::default()).await;
+                if let Some(project) = recent::startup_project() {
+                    commands::filesystem::auto_purge_trash(&project);
+                    state.set_project(Some(project)).await;
+                }
+            });
+
+            // Start HTTP API server in background
             tauri::async_runtime::spawn(async move {
                 match api::start_server(state).await {
-                    Ok(port) => {
-                        println!("Needlepoint HTTP API started on http://127.0.0.1:{}", port);
+                    Ok(addr) => {
+                        tracing::info!("Needlepoint HTTP API started on http://{}", addr);
                     }
                     Err(e) => {
-                        eprintln!("Failed to start HTTP API server: {}", e);
+                        tracing::error!("Failed to start HTTP API server: {}", e);
                     }
                 }
             });
@@ -38,29 +63,85 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::project::load_project,
             commands::project::save_project,
+            commands::project::close_project,
+            commands::project::get_startup_project,
+            commands::project::get_recent_projects,
+            commands::project::set_auto_load_project,
             commands::graph::add_node,
             commands::graph::update_node,
             commands::graph::delete_node,
             commands::graph::add_edge,
+            commands::graph::update_edge,
             commands::graph::delete_edge,
             commands::graph::check_would_create_cycle,
+            commands::graph::suggest_project_edges,
+            commands::graph::auto_layout_project,
+            commands::graph::set_snippet,
+            commands::graph::delete_snippet,
+            commands::graph::set_shared_type,
+            commands::graph::delete_shared_type,
             commands::generation::generate_node,
             commands::generation::preview_prompt,
+            commands::generation::set_proxy,
+            commands::generation::list_mistral_models,
             commands::orchestration::get_execution_plan,
             commands::orchestration::generate_all,
             commands::orchestration::generate_nodes,
+            commands::orchestration::continue_execution,
+            commands::orchestration::approve_generation,
+            commands::orchestration::get_interrupted_run,
+            commands::orchestration::resume_interrupted_run,
+            commands::orchestration::discard_interrupted_run,
+            commands::orchestration::generate_pilot,
+            commands::orchestration::suggest_node_split,
+            commands::orchestration::open_pull_request,
             commands::filesystem::create_file,
             commands::filesystem::write_file,
+            commands::filesystem::read_file,
+            commands::filesystem::read_files,
+            commands::filesystem::write_node_file,
             commands::filesystem::delete_file,
             commands::filesystem::delete_file_permanent,
             commands::filesystem::restore_file,
             commands::filesystem::list_trash,
             commands::filesystem::empty_trash,
+            commands::filesystem::purge_trash,
             commands::filesystem::rename_file,
+            commands::filesystem::delete_directory,
+            commands::filesystem::move_directory,
             commands::filesystem::file_exists,
             commands::filesystem::create_directory,
+            commands::filesystem::list_project_files,
             commands::api::get_api_port,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Run the MCP server on stdin/stdout without opening a window. Without a project
+/// path, the last project is loaded when auto-loading is on.
+async fn run_mcp_stdio(project_path: Option<std::path::PathBuf>) -> Result<(), String> {
+    let state = AppState::new();
+
+    let project = match project_path {
+        Some(path) => {
+            let project = graph::load_project_from_file(&path).map_err(|e| e.to_string())?;
+            recent::record_opened(&project);
+            Some(project)
+        }
+        None => recent::startup_project(),
+    };
+    if let Some(project) = &project {
+        commands::filesystem::auto_purge_trash(project);
+    }
+    // Keys come from the environment, including the manifest's `apiKeyEnv` once loaded
+    state.set_api_keys(api::state::ApiKeys::default()).await;
+    if project.is_some() {
+        state.set_project(project).await;
+    }
+
+    tracing::info!("Serving MCP over stdio");
+    mcp::serve_stdio(mcp::McpServer::new(state))
+        .await
+        .map_err(|e| e.to_string())
+}
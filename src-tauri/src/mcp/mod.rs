@@ -0,0 +1,230 @@
+//! Model Context Protocol server exposing graph operations to external agents.
+//!
+//! Speaks JSON-RPC 2.0 over either stdio (`needlepoint --mcp [project.yaml]`) or
+//! SSE on the HTTP API (`GET /api/mcp/sse`), sharing `AppState` with the rest of the app.
+
+pub mod tools;
+pub mod transport;
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::api::state::AppState;
+
+pub use transport::{serve_stdio, sse_routes};
+
+/// MCP protocol revision this server implements
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Incoming JSON-RPC message. Requests carry an `id`; notifications don't.
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcRequest {
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Outgoing JSON-RPC response
+#[derive(Debug, Serialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl JsonRpcResponse {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn failure(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// Dispatches MCP requests against the shared application state
+#[derive(Clone)]
+pub struct McpServer {
+    state: Arc<AppState>,
+    /// Only offer tools that don't change the project
+    read_only: bool,
+}
+
+impl McpServer {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self {
+            state,
+            read_only: false,
+        }
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Handle one raw message. Returns the response to send back, if any
+    /// (notifications get none).
+    pub async fn handle_message(&self, raw: &str) -> Option<JsonRpcResponse> {
+        let value: Value = match serde_json::from_str(raw) {
+            Ok(v) => v,
+            Err(e) => {
+                return Some(JsonRpcResponse::failure(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Parse error: {}", e),
+                ))
+            }
+        };
+
+        let request: JsonRpcRequest = match serde_json::from_value(value) {
+            Ok(r) => r,
+            Err(e) => {
+                return Some(JsonRpcResponse::failure(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!("Invalid request: {}", e),
+                ))
+            }
+        };
+
+        self.handle_request(request).await
+    }
+
+    pub async fn handle_request(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        // Notifications (e.g. notifications/initialized) need no reply
+        let id = request.id?;
+
+        let result = match request.method.as_str() {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": "needlepoint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => {
+                let mut definitions = tools::definitions();
+                if self.read_only {
+                    if let Some(list) = definitions.as_array_mut() {
+                        list.retain(|t| t["name"].as_str().is_some_and(tools::is_read_only));
+                    }
+                }
+                Ok(json!({ "tools": definitions }))
+            }
+            "tools/call" => self.call_tool(request.params).await,
+            other => Err(JsonRpcResponse::failure(
+                id.clone(),
+                METHOD_NOT_FOUND,
+                format!("Method '{}' not found", other),
+            )),
+        };
+
+        Some(match result {
+            Ok(value) => JsonRpcResponse::success(id, value),
+            Err(mut response) => {
+                response.id = id;
+                response
+            }
+        })
+    }
+
+    async fn call_tool(&self, params: Value) -> Result<Value, JsonRpcResponse> {
+        let name = params
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or_else(|| {
+                JsonRpcResponse::failure(Value::Null, INVALID_PARAMS, "Missing tool name")
+            })?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        // Tool failures are reported in the result so the agent can see them
+        let result = if self.read_only && !tools::is_read_only(name) {
+            Err(format!("Tool '{}' is unavailable: the API is read-only", name))
+        } else {
+            tools::call(&self.state, name, arguments).await
+        };
+        let (text, is_error) = match result {
+            Ok(value) => (
+                serde_json::to_string_pretty(&value).unwrap_or_default(),
+                false,
+            ),
+            Err(e) => (e, true),
+        };
+
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::model::Project;
+
+    #[tokio::test]
+    async fn test_mcp_tool_roundtrip() {
+        let state = AppState::new();
+        state.set_project(Some(Project::new(String::new()))).await;
+        let server = McpServer::new(state.clone());
+
+        // Notifications get no response
+        let init = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(server.handle_message(init).await.is_none());
+
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{
+            "name":"create_node","arguments":{"name":"Auth","file_path":"src/auth.ts"}}}"#;
+        let response = server.handle_message(call).await.unwrap();
+        assert_eq!(response.result.unwrap()["isError"], false);
+        assert_eq!(state.get_project().await.unwrap().nodes.len(), 1);
+
+        let unknown = r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#;
+        let response = server.handle_message(unknown).await.unwrap();
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+
+        // A read-only server neither lists nor runs tools that change the project
+        let server = server.with_read_only(true);
+        let list = r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#;
+        let tools = server.handle_message(list).await.unwrap().result.unwrap();
+        assert_eq!(tools["tools"].as_array().unwrap().len(), 3);
+        let response = server.handle_message(call).await.unwrap();
+        assert_eq!(response.result.unwrap()["isError"], true);
+        assert_eq!(state.get_project().await.unwrap().nodes.len(), 1);
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::api::queue::GenerationKind;
+use crate::api::routes::{generate_single_node, wait_generation_turn};
+use crate::api::state::AppState;
+use crate::graph::model::{CodeEdge, Language, Project};
+use crate::graph::query::{NodeFilter, PageQuery};
+use crate::graph::validation::would_create_cycle;
+use crate::llm::ContextBuilder;
+
+/// Fields returned per node by `list_nodes`, to keep listings small
+const LIST_FIELDS: &str = "name,filePath,language,status,tags,description";
+
+/// Tools that don't change the project, offered when the API is read-only
+const READ_TOOLS: &[&str] = &["list_nodes", "get_node", "get_prompt"];
+
+/// Whether a tool only reads the project
+pub fn is_read_only(name: &str) -> bool {
+    READ_TOOLS.contains(&name)
+}
+
+/// Tool descriptors returned by `tools/list`
+pub fn definitions() -> Value {
+    json!([
+        {
+            "name": "list_nodes",
+            "description": "List the nodes (files) in the Needlepoint graph, optionally filtered.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string", "description": "pending, generating, complete, error, warning or stale" },
+                    "language": { "type": "string" },
+                    "q": { "type": "string", "description": "Text to search for in name, path, description and purpose" },
+                    "path_prefix": { "type": "string" },
+                    "tag": { "type": "string" }
+                }
+            }
+        },
+        {
+            "name": "get_node",
+            "description": "Get the full spec of a node, including any generated code.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "get_prompt",
+            "description": "Show the prompt that would be sent to the LLM to generate a node.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "create_node",
+            "description": "Add a node (a file to generate) to the graph.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "file_path": { "type": "string", "description": "Path relative to the project root" },
+                    "language": { "type": "string", "description": "e.g. typescript, python, java, csharp, cpp or bash; other names are custom" },
+                    "description": { "type": "string" },
+                    "purpose": { "type": "string" }
+                },
+                "required": ["name", "file_path"]
+            }
+        },
+        {
+            "name": "add_dependency",
+            "description": "Make one node depend on another, so the dependency is generated first and its code is given as context.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "description": "The dependent node" },
+                    "depends_on": { "type": "string", "description": "The node it depends on" },
+                    "label": { "type": "string" }
+                },
+                "required": ["node_id", "depends_on"]
+            }
+        },
+        {
+            "name": "generate_node",
+            "description": "Generate code for a node with its configured LLM, using its dependencies as context.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "save_project",
+            "description": "Save the project to its YAML file.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+#[derive(Deserialize)]
+struct NodeIdArgs {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct CreateNodeArgs {
+    name: String,
+    file_path: String,
+    #[serde(default)]
+    language: Option<Language>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    purpose: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AddDependencyArgs {
+    node_id: String,
+    depends_on: String,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+fn parse_args<T: for<'de> Deserialize<'de>>(arguments: Value) -> Result<T, String> {
+    serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments: {}", e))
+}
+
+async fn require_project(state: &AppState) -> Result<Project, String> {
+    state
+        .get_project()
+        .await
+        .ok_or_else(|| "No project loaded".to_string())
+}
+
+/// Run a tool by name, returning its JSON result or an error message
+pub async fn call(state: &Arc<AppState>, name: &str, arguments: Value) -> Result<Value, String> {
+    match name {
+        "list_nodes" => {
+            let filter: NodeFilter = parse_args(arguments)?;
+            let project = require_project(state).await?;
+            let page = PageQuery {
+                fields: Some(LIST_FIELDS.to_string()),
+                ..Default::default()
+            };
+            let nodes: Vec<Value> = filter
+                .apply(&project.nodes)
+                .into_iter()
+                .map(|n| page.select(n))
+                .collect();
+            Ok(Value::Array(nodes))
+        }
+
+        "get_node" => {
+            let args: NodeIdArgs = parse_args(arguments)?;
+            let project = require_project(state).await?;
+            let node = project
+                .find_node(&args.id)
+                .ok_or_else(|| format!("Node '{}' not found", args.id))?;
+            serde_json::to_value(node).map_err(|e| e.to_string())
+        }
+
+        "get_prompt" => {
+            let args: NodeIdArgs = parse_args(arguments)?;
+            let project = require_project(state).await?;
+            let prompt = ContextBuilder::build_prompt(&project, &args.id)
+                .ok_or_else(|| format!("Node '{}' not found", args.id))?;
+            Ok(json!({ "prompt": prompt }))
+        }
+
+        "create_node" => {
+            let args: CreateNodeArgs = parse_args(arguments)?;
+            let mut created = Value::Null;
+            state
+                .update_project(|p| {
+                    let language = args.language.unwrap_or_default();
+                    let mut node = p.new_node(args.name, args.file_path, language);
+                    if let Some(description) = args.description {
+                        node.description = description;
+                    }
+                    if let Some(purpose) = args.purpose {
+                        node.purpose = purpose;
+                    }
+                    created = json!(node);
+                    p.nodes.push(node);
+                })
+                .await
+                .ok_or_else(|| "No project loaded".to_string())?;
+            Ok(created)
+        }
+
+        "add_dependency" => {
+            let args: AddDependencyArgs = parse_args(arguments)?;
+            let project = require_project(state).await?;
+            for id in [&args.node_id, &args.depends_on] {
+                if project.find_node(id).is_none() {
+                    return Err(format!("Node '{}' not found", id));
+                }
+            }
+            if would_create_cycle(&project, &args.depends_on, &args.node_id) {
+                return Err("Adding this dependency would create a cycle".to_string());
+            }
+
+            let edge = CodeEdge::new(
+                args.depends_on,
+                args.node_id,
+                args.label.unwrap_or_else(|| "depends on".to_string()),
+            );
+            let created = serde_json::to_value(&edge).map_err(|e| e.to_string())?;
+
+            state
+                .update_project(|p| p.edges.push(edge))
+                .await
+                .ok_or_else(|| "No project loaded".to_string())?;
+            Ok(created)
+        }
+
+        "generate_node" => {
+            let args: NodeIdArgs = parse_args(arguments)?;
+            let _turn = wait_generation_turn(state, GenerationKind::Node, Some(args.id.clone()))
+                .await
+                .map_err(|(_, body)| body.0.error)?;
+            let (code, run) = generate_single_node(state, &args.id, None)
+                .await
+                .map_err(|(_, body)| body.0.error)?;
+            Ok(json!({ "code": code, "run": run }))
+        }
+
+        "save_project" => {
+            if !state.save_project().await.map_err(|e| e.to_string())? {
+                return Err("No project loaded".to_string());
+            }
+            Ok(json!({ "saved": true }))
+        }
+
+        other => Err(format!("Unknown tool '{}'", other)),
+    }
+}
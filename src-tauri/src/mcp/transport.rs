@@ -0,0 +1,106 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Router,
+};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+
+use crate::api::state::AppState;
+
+use super::McpServer;
+
+/// Serve MCP over stdin/stdout, one JSON-RPC message per line, until stdin closes
+pub async fn serve_stdio(server: McpServer) -> std::io::Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_message(&line).await {
+            let mut out = serde_json::to_string(&response)?;
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the session when its SSE stream is dropped (client disconnected)
+struct SessionGuard {
+    state: Arc<AppState>,
+    session_id: String,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.state.mcp_sessions().remove(&self.session_id);
+    }
+}
+
+/// Routes for the HTTP+SSE transport: clients open `GET /sse`, receive an
+/// `endpoint` event, and POST their messages there. Responses arrive on the stream.
+pub fn sse_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sse", get(open_session))
+        .route("/messages", post(post_message))
+}
+
+async fn open_session(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = mpsc::unbounded_channel::<String>();
+    state.mcp_sessions().insert(session_id.clone(), tx);
+
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("/api/mcp/messages?sessionId={}", session_id));
+
+    let guard = SessionGuard { state, session_id };
+    let messages = stream::unfold((rx, guard), |(mut rx, guard)| async move {
+        let message = rx.recv().await?;
+        Some((Ok(Event::default().event("message").data(message)), (rx, guard)))
+    });
+
+    Sse::new(stream::once(async move { Ok(endpoint) }).chain(messages))
+        .keep_alive(KeepAlive::default())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageQuery {
+    session_id: String,
+}
+
+async fn post_message(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<MessageQuery>,
+    body: String,
+) -> StatusCode {
+    let sender = state.mcp_sessions().get(&query.session_id).cloned();
+    let Some(sender) = sender else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    let read_only = state.read_only();
+    let server = McpServer::new(state).with_read_only(read_only);
+    if let Some(response) = server.handle_message(&body).await {
+        if let Ok(message) = serde_json::to_string(&response) {
+            let _ = sender.send(message);
+        }
+    }
+
+    StatusCode::ACCEPTED
+}
@@ -1,7 +0,0 @@
-pub mod planner;
-pub mod executor;
-pub mod events;
-
-pub use planner::{ExecutionPlan, ExecutionWave};
-pub use executor::Executor;
-pub use events::{ExecutionEvent, NodeProgress};
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::{load_project_from_file, Project, PROJECT_FILE_NAME};
+
+const RECENT_FILE_NAME: &str = "recent-projects.json";
+
+/// How many projects the list keeps
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// A project that was opened, as remembered across sessions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+    /// The project's `needlepoint.yaml`
+    pub path: String,
+    pub name: String,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// Recently opened projects, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProjects {
+    /// Open the most recent project when the app starts
+    #[serde(default)]
+    pub auto_load: bool,
+    #[serde(default)]
+    pub projects: Vec<RecentProject>,
+}
+
+impl RecentProjects {
+    /// Move a project to the front of the list, dropping the oldest past the limit
+    pub fn add(&mut self, project: &Project) {
+        let path = Path::new(&project.project_path)
+            .join(PROJECT_FILE_NAME)
+            .to_string_lossy()
+            .to_string();
+        self.projects.retain(|p| p.path != path);
+        self.projects.insert(
+            0,
+            RecentProject {
+                path,
+                name: project.manifest.name.clone(),
+                opened_at: Utc::now(),
+            },
+        );
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+}
+
+/// `recent-projects.json` in the app's data directory, next to the app logs
+fn recent_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("needlepoint")
+        .join(RECENT_FILE_NAME)
+}
+
+fn read_recent(path: &Path) -> Result<RecentProjects> {
+    if !path.exists() {
+        return Ok(RecentProjects::default());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read recent projects: {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse recent projects: {:?}", path))
+}
+
+fn write_recent(path: &Path, recent: &RecentProjects) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    let contents =
+        serde_json::to_string_pretty(recent).context("Failed to serialize recent projects")?;
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write recent projects: {:?}", path))
+}
+
+/// The recently opened projects (an empty list when none were remembered)
+pub fn load_recent() -> Result<RecentProjects> {
+    read_recent(&recent_path())
+}
+
+/// Turn opening the most recent project on startup on or off
+pub fn set_auto_load(enabled: bool) -> Result<RecentProjects> {
+    let path = recent_path();
+    let mut recent = read_recent(&path)?;
+    recent.auto_load = enabled;
+    write_recent(&path, &recent)?;
+    Ok(recent)
+}
+
+/// Remember that a project was opened. Failing to is logged rather than failing the
+/// load itself.
+pub fn record_opened(project: &Project) {
+    let path = recent_path();
+    let result = read_recent(&path).and_then(|mut recent| {
+        recent.add(project);
+        write_recent(&path, &recent)
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to remember recent project: {:#}", e);
+    }
+}
+
+/// The most recent project, when auto-loading is on and it still loads
+pub fn startup_project() -> Option<Project> {
+    let recent = match load_recent() {
+        Ok(recent) => recent,
+        Err(e) => {
+            tracing::warn!("{:#}", e);
+            return None;
+        }
+    };
+    let last = recent.projects.first().filter(|_| recent.auto_load)?;
+    match load_project_from_file(Path::new(&last.path)) {
+        Ok(project) => {
+            tracing::info!("Auto-loaded last project {:?}", last.path);
+            Some(project)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to auto-load last project {:?}: {:#}", last.path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_projects_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RECENT_FILE_NAME);
+        let mut recent = read_recent(&path).unwrap();
+        assert!(recent.projects.is_empty() && !recent.auto_load);
+
+        for i in 0..12 {
+            let mut project = Project::new(format!("/projects/p{}", i));
+            project.manifest.name = format!("P{}", i);
+            recent.add(&project);
+        }
+        // Reopening moves a project to the front instead of listing it twice
+        let mut reopened = Project::new("/projects/p5".to_string());
+        reopened.manifest.name = "P5".to_string();
+        recent.add(&reopened);
+        recent.auto_load = true;
+        write_recent(&path, &recent).unwrap();
+
+        let recent = read_recent(&path).unwrap();
+        assert!(recent.auto_load);
+        assert_eq!(recent.projects.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(recent.projects[0].name, "P5");
+        assert_eq!(recent.projects[1].name, "P11");
+        assert_eq!(recent.projects.iter().filter(|p| p.name == "P5").count(), 1);
+        assert!(recent.projects[0].path.ends_with(PROJECT_FILE_NAME));
+    }
+}
@@ -9,12 +9,14 @@ import SettingsPanel from './components/settings/SettingsPanel';
 import ExecutionMonitor from './components/execution/ExecutionMonitor';
 import ToastContainer from './components/ui/Toast';
 import { useSettingsStore } from './stores/settingsStore';
+import { useProjectStore } from './stores/projectStore';
 import { useKeyboardShortcuts } from './hooks/useKeyboardShortcuts';
 
 function App() {
   const [showSettings, setShowSettings] = useState(false);
   const [showExecutionMonitor, setShowExecutionMonitor] = useState(false);
   const { loadSettings } = useSettingsStore();
+  const { loadStartupProject } = useProjectStore();
 
   // Initialize global keyboard shortcuts
   useKeyboardShortcuts();
@@ -24,6 +26,11 @@ function App() {
     loadSettings();
   }, [loadSettings]);
 
+  // Pick up the last project when it was reopened on startup
+  useEffect(() => {
+    loadStartupProject();
+  }, [loadStartupProject]);
+
   return (
     <ReactFlowProvider>
       <div className="flex h-screen w-full bg-canvas-bg">
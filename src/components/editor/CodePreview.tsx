@@ -3,19 +3,30 @@ import Editor from '@monaco-editor/react';
 import { Eye } from 'lucide-react';
 import { useProjectStore } from '../../stores/projectStore';
 import { previewPrompt } from '../../lib/tauri';
-import type { CodeNode, Language } from '../../lib/types';
+import type { CodeNode, BuiltinLanguage } from '../../lib/types';
 
 interface CodePreviewProps {
   node: CodeNode;
 }
 
 // Map our language types to Monaco language IDs
-const languageToMonaco: Record<Language, string> = {
+const languageToMonaco: Record<BuiltinLanguage, string> = {
   typescript: 'typescript',
   javascript: 'javascript',
   python: 'python',
   rust: 'rust',
   go: 'go',
+  java: 'java',
+  csharp: 'csharp',
+  cpp: 'cpp',
+  ruby: 'ruby',
+  php: 'php',
+  kotlin: 'kotlin',
+  swift: 'swift',
+  sql: 'sql',
+  html: 'html',
+  css: 'css',
+  bash: 'shell',
 };
 
 type ViewMode = 'code' | 'prompt';
@@ -75,7 +86,7 @@ export default function CodePreview({ node }: CodePreviewProps) {
           <Editor
             key="code-editor"
             height="100%"
-            language={languageToMonaco[node.language]}
+            language={languageToMonaco[node.language as BuiltinLanguage] ?? 'plaintext'}
             value={node.generatedCode || '// No code generated yet\n// Click "Generate" to create code for this node'}
             onChange={handleCodeChange}
             theme="vs-dark"
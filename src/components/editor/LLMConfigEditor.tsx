@@ -1,9 +1,14 @@
+import { useEffect, useState } from 'react';
 import { Plus, Trash2 } from 'lucide-react';
 import type { LLMConfig, LLMProvider } from '../../lib/types';
+import { listMistralModels } from '../../lib/tauri';
+import { useSettingsStore } from '../../stores/settingsStore';
 
 interface LLMConfigEditorProps {
   config: LLMConfig;
   onChange: (config: LLMConfig) => void;
+  // The manifest's default provider and model, applied while useProjectDefault is set
+  projectDefault?: { provider: LLMProvider; model: string };
 }
 
 const providerModels: Record<LLMProvider, string[]> = {
@@ -19,6 +24,14 @@ const providerModels: Record<LLMProvider, string[]> = {
     'gpt-4-turbo',
     'gpt-4',
     'gpt-3.5-turbo',
+    'o3-mini',
+    'o1',
+  ],
+  mistral: [
+    'codestral-latest',
+    'devstral-medium-latest',
+    'mistral-large-latest',
+    'mistral-small-latest',
   ],
   ollama: [
     'llama3.2',
@@ -27,9 +40,16 @@ const providerModels: Record<LLMProvider, string[]> = {
     'deepseek-coder',
     'qwen2.5-coder',
   ],
+  // The model is the path to a GGUF file, typed in
+  local: [],
+  mock: ['mock'],
 };
 
-export default function LLMConfigEditor({ config, onChange }: LLMConfigEditorProps) {
+export default function LLMConfigEditor({
+  config,
+  onChange,
+  projectDefault,
+}: LLMConfigEditorProps) {
   const updateConfig = <K extends keyof LLMConfig>(key: K, value: LLMConfig[K]) => {
     onChange({ ...config, [key]: value });
   };
@@ -51,16 +71,55 @@ export default function LLMConfigEditor({ config, onChange }: LLMConfigEditorPro
     );
   };
 
-  const models = providerModels[config.provider] || [];
+  // Mistral's models come from its API when the key allows, the built-in list otherwise
+  const mistralApiKey = useSettingsStore((state) => state.settings.mistralApiKey);
+  const [mistralModels, setMistralModels] = useState<string[] | null>(null);
+  useEffect(() => {
+    if (config.provider !== 'mistral' || mistralModels) return;
+    listMistralModels(mistralApiKey || undefined)
+      .then((listed) => setMistralModels(listed.filter((m) => m.chat).map((m) => m.id)))
+      .catch(() => setMistralModels(providerModels.mistral));
+  }, [config.provider, mistralApiKey, mistralModels]);
+
+  const models =
+    config.provider === 'mistral' && mistralModels?.length
+      ? mistralModels
+      : providerModels[config.provider] || [];
+
+  const usesDefault = config.useProjectDefault ?? false;
 
   return (
     <div className="space-y-4">
+      {projectDefault && (
+        <label className="flex items-center gap-2 text-sm text-gray-300">
+          <input
+            type="checkbox"
+            checked={usesDefault}
+            onChange={(e) =>
+              onChange(
+                e.target.checked
+                  ? {
+                      ...config,
+                      provider: projectDefault.provider,
+                      model: projectDefault.model,
+                      useProjectDefault: true,
+                    }
+                  : { ...config, useProjectDefault: false }
+              )
+            }
+            className="rounded border-gray-700 bg-gray-800"
+          />
+          Use project default ({projectDefault.model})
+        </label>
+      )}
+
       <div>
         <label className="block text-sm font-medium text-gray-300 mb-1">
           Provider
         </label>
         <select
           value={config.provider}
+          disabled={usesDefault}
           onChange={(e) => {
             const provider = e.target.value as LLMProvider;
             const defaultModel = providerModels[provider]?.[0] || '';
@@ -70,7 +129,10 @@ export default function LLMConfigEditor({ config, onChange }: LLMConfigEditorPro
         >
           <option value="anthropic">Anthropic</option>
           <option value="openai">OpenAI</option>
+          <option value="mistral">Mistral</option>
           <option value="ollama">Ollama (Local)</option>
+          <option value="local">GGUF File (In-Process)</option>
+          <option value="mock">Mock (Demo, no API key)</option>
         </select>
       </div>
 
@@ -78,19 +140,115 @@ export default function LLMConfigEditor({ config, onChange }: LLMConfigEditorPro
         <label className="block text-sm font-medium text-gray-300 mb-1">
           Model
         </label>
-        <select
-          value={config.model}
-          onChange={(e) => updateConfig('model', e.target.value)}
-          className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
-        >
-          {models.map((model) => (
-            <option key={model} value={model}>
-              {model}
-            </option>
-          ))}
-        </select>
+        {config.provider === 'local' ? (
+          <input
+            type="text"
+            value={config.model}
+            disabled={usesDefault}
+            onChange={(e) => updateConfig('model', e.target.value)}
+            placeholder="/models/qwen2.5-coder-7b-instruct-q4_k_m.gguf"
+            className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+          />
+        ) : (
+          <select
+            value={config.model}
+            disabled={usesDefault}
+            onChange={(e) => updateConfig('model', e.target.value)}
+            className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+          >
+            {models.map((model) => (
+              <option key={model} value={model}>
+                {model}
+              </option>
+            ))}
+          </select>
+        )}
+        {config.provider === 'local' && (
+          <p className="mt-1 text-xs text-gray-500">
+            Path to a Llama or Qwen2 GGUF file, with its tokenizer.json next to it
+          </p>
+        )}
       </div>
 
+      {config.provider === 'anthropic' && (
+        <div>
+          <label className="block text-sm font-medium text-gray-300 mb-1">
+            Thinking Budget (Optional)
+          </label>
+          <input
+            type="number"
+            min={1024}
+            step={1024}
+            value={config.thinkingBudget ?? ''}
+            onChange={(e) =>
+              updateConfig('thinkingBudget', e.target.value ? Number(e.target.value) : undefined)
+            }
+            placeholder="Off"
+            className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+          />
+          <p className="mt-1 text-xs text-gray-500">
+            Tokens the model may spend thinking before it writes the file (at least 1024)
+          </p>
+        </div>
+      )}
+
+      {config.provider === 'openai' && (
+        <div>
+          <label className="block text-sm font-medium text-gray-300 mb-1">
+            Reasoning Effort
+          </label>
+          <select
+            value={config.reasoningEffort ?? ''}
+            onChange={(e) =>
+              updateConfig(
+                'reasoningEffort',
+                (e.target.value || undefined) as LLMConfig['reasoningEffort']
+              )
+            }
+            className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+          >
+            <option value="">Default (non-reasoning models)</option>
+            <option value="low">Low</option>
+            <option value="medium">Medium</option>
+            <option value="high">High</option>
+          </select>
+        </div>
+      )}
+
+      {config.provider === 'mistral' && (
+        <label className="flex items-center gap-2 text-sm text-gray-300">
+          <input
+            type="checkbox"
+            checked={config.fim ?? false}
+            onChange={(e) => updateConfig('fim', e.target.checked || undefined)}
+            className="rounded border-gray-700 bg-gray-800"
+          />
+          Fill-in-the-middle (Codestral models)
+        </label>
+      )}
+
+      {config.provider !== 'anthropic' && (
+        <div>
+          <label className="block text-sm font-medium text-gray-300 mb-1">
+            Seed (Optional)
+          </label>
+          <input
+            type="number"
+            min={0}
+            step={1}
+            value={config.seed ?? ''}
+            onChange={(e) =>
+              updateConfig('seed', e.target.value ? Number(e.target.value) : undefined)
+            }
+            placeholder="Random"
+            className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+          />
+          <p className="mt-1 text-xs text-gray-500">
+            Reuse a seed to make regenerations reproducible when debugging flaky output
+          </p>
+        </div>
+      )}
+
       <div>
         <label className="block text-sm font-medium text-gray-300 mb-1">
           System Prompt (Optional)
@@ -1,11 +1,25 @@
 import { useState, useMemo, useEffect } from 'react';
-import { AlertCircle } from 'lucide-react';
+import { AlertCircle, Plus } from 'lucide-react';
 import { useProjectStore } from '../../stores/projectStore';
-import { validateFilePath } from '../../lib/tauri';
-import type { CodeNode } from '../../lib/types';
+import { useToastStore } from '../../stores/toastStore';
+import { currentApiKeys } from '../../stores/executionStore';
+import { validateFilePath, suggestEdges, suggestNodeSplit } from '../../lib/tauri';
+import type {
+  CodeNode,
+  EdgeSuggestion,
+  ExternalPackage,
+  PackageEcosystem,
+  SplitSuggestion,
+} from '../../lib/types';
 import LLMConfigEditor from './LLMConfigEditor';
 import CodePreview from './CodePreview';
 
+// Languages offered in the dropdown; a custom language is shown as an extra option
+const BUILTIN_LANGUAGES: string[] = [
+  'typescript', 'javascript', 'python', 'rust', 'go', 'java', 'csharp', 'cpp',
+  'ruby', 'php', 'kotlin', 'swift', 'sql', 'html', 'css', 'bash',
+];
+
 /**
  * Check if a file path is a duplicate (exists in another node)
  */
@@ -22,8 +36,11 @@ interface NodeEditorProps {
 type Tab = 'general' | 'llm' | 'code';
 
 export default function NodeEditor({ node }: NodeEditorProps) {
-  const { project, updateNode } = useProjectStore();
+  const { project, updateNode, addEdge } = useProjectStore();
   const [activeTab, setActiveTab] = useState<Tab>('general');
+  const [suggestions, setSuggestions] = useState<EdgeSuggestion[] | null>(null);
+  const [split, setSplit] = useState<SplitSuggestion | null>(null);
+  const [proposingSplit, setProposingSplit] = useState(false);
   const [pathError, setPathError] = useState<string | null>(null);
   const [filePathInput, setFilePathInput] = useState(node.filePath);
 
@@ -36,6 +53,45 @@ export default function NodeEditor({ node }: NodeEditorProps) {
     setPathError(null);
   }, [node.filePath]);
 
+  // Suggestions are for one node
+  useEffect(() => {
+    setSuggestions(null);
+    setSplit(null);
+  }, [node.id]);
+
+  const checkSize = async (propose: boolean) => {
+    if (!project) return;
+    setProposingSplit(propose);
+    try {
+      const apiKeys = propose ? currentApiKeys() : undefined;
+      setSplit(await suggestNodeSplit(project, node.id, apiKeys));
+    } catch (error) {
+      useToastStore.getState().addToast(`Failed to check node size: ${error}`, 'error');
+    } finally {
+      setProposingSplit(false);
+    }
+  };
+
+  const findMissingDependencies = async () => {
+    if (!project) return;
+    try {
+      const all = await suggestEdges(project);
+      setSuggestions(all.filter((s) => s.target === node.id));
+    } catch (error) {
+      useToastStore.getState().addToast(`Failed to suggest edges: ${error}`, 'error');
+    }
+  };
+
+  const acceptSuggestion = (suggestion: EdgeSuggestion) => {
+    const { source, target, label } = suggestion;
+    const result = addEdge({ source, target, label });
+    if (!result.success) {
+      useToastStore.getState().addToast(result.error ?? 'Failed to add edge', 'error');
+      return;
+    }
+    setSuggestions((current) => current?.filter((s) => s !== suggestion) ?? null);
+  };
+
   const handleChange = (field: keyof CodeNode, value: string) => {
     // Validate file path before updating
     if (field === 'filePath') {
@@ -48,6 +104,11 @@ export default function NodeEditor({ node }: NodeEditorProps) {
     updateNode(node.id, { [field]: value });
   };
 
+  const handlePackageChange = (changes: Partial<ExternalPackage>) => {
+    const current: ExternalPackage = node.package ?? { name: '', ecosystem: 'npm', version: '' };
+    updateNode(node.id, { package: { ...current, ...changes } });
+  };
+
   const handleFilePathChange = (value: string) => {
     // Always update the input display
     setFilePathInput(value);
@@ -147,9 +208,110 @@ export default function NodeEditor({ node }: NodeEditorProps) {
               <option value="python">Python</option>
               <option value="rust">Rust</option>
               <option value="go">Go</option>
+              <option value="java">Java</option>
+              <option value="csharp">C#</option>
+              <option value="cpp">C++</option>
+              <option value="ruby">Ruby</option>
+              <option value="php">PHP</option>
+              <option value="kotlin">Kotlin</option>
+              <option value="swift">Swift</option>
+              <option value="sql">SQL</option>
+              <option value="html">HTML</option>
+              <option value="css">CSS</option>
+              <option value="bash">Bash</option>
+              {!BUILTIN_LANGUAGES.includes(node.language) && (
+                <option value={node.language}>{node.language}</option>
+              )}
             </select>
           </div>
 
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-1">
+              Kind
+            </label>
+            <select
+              value={node.kind ?? 'code'}
+              onChange={(e) => handleChange('kind', e.target.value)}
+              className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="code">Code</option>
+              <option value="config">Config</option>
+              <option value="schema">Schema</option>
+              <option value="doc">Documentation</option>
+              <option value="asset">Asset</option>
+              <option value="external">External package</option>
+            </select>
+          </div>
+
+          {node.kind === 'external' && (
+            <div className="space-y-2">
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={node.package?.name ?? ''}
+                  onChange={(e) => handlePackageChange({ name: e.target.value })}
+                  placeholder="Package name"
+                  className="flex-1 px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                />
+                <select
+                  value={node.package?.ecosystem ?? 'npm'}
+                  onChange={(e) =>
+                    handlePackageChange({ ecosystem: e.target.value as PackageEcosystem })
+                  }
+                  className="px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                >
+                  <option value="npm">npm</option>
+                  <option value="crates">crates.io</option>
+                  <option value="pip">PyPI</option>
+                </select>
+                <input
+                  type="text"
+                  value={node.package?.version ?? ''}
+                  onChange={(e) => handlePackageChange({ version: e.target.value })}
+                  placeholder="Version"
+                  className="w-24 px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                />
+              </div>
+              <textarea
+                value={node.package?.docs ?? ''}
+                onChange={(e) => handlePackageChange({ docs: e.target.value })}
+                rows={4}
+                placeholder="API notes included in dependents' prompts"
+                className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500 resize-none"
+              />
+            </div>
+          )}
+
+          <label className="flex items-center gap-2 text-sm text-gray-300">
+            <input
+              type="checkbox"
+              checked={node.disabled ?? false}
+              onChange={(e) => updateNode(node.id, { disabled: e.target.checked })}
+              className="rounded border-gray-700 bg-gray-800"
+            />
+            Disabled (skip during generation)
+          </label>
+
+          <label className="flex items-center gap-2 text-sm text-gray-300">
+            <input
+              type="checkbox"
+              checked={node.pinned ?? false}
+              onChange={(e) => updateNode(node.id, { pinned: e.target.checked })}
+              className="rounded border-gray-700 bg-gray-800"
+            />
+            Pinned (hand-written, never regenerated)
+          </label>
+
+          <label className="flex items-center gap-2 text-sm text-gray-300">
+            <input
+              type="checkbox"
+              checked={node.requiresApproval ?? false}
+              onChange={(e) => updateNode(node.id, { requiresApproval: e.target.checked })}
+              className="rounded border-gray-700 bg-gray-800"
+            />
+            Requires approval (review its code before dependents are generated)
+          </label>
+
           <div>
             <label className="block text-sm font-medium text-gray-300 mb-1">
               Description
@@ -175,6 +337,88 @@ export default function NodeEditor({ node }: NodeEditorProps) {
               className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 resize-none"
             />
           </div>
+
+          <div>
+            <button
+              onClick={findMissingDependencies}
+              className="text-sm text-blue-400 hover:text-blue-300"
+            >
+              Find missing dependencies
+            </button>
+            {suggestions && suggestions.length === 0 && (
+              <p className="mt-1 text-xs text-gray-500">No missing dependencies found</p>
+            )}
+            {suggestions && suggestions.length > 0 && (
+              <ul className="mt-2 space-y-1">
+                {suggestions.map((suggestion) => {
+                  const source = allNodes.find((n) => n.id === suggestion.source);
+                  return (
+                    <li
+                      key={suggestion.source}
+                      className="flex items-center gap-2 text-sm text-gray-300"
+                      title={suggestion.reasons.join('\n')}
+                    >
+                      <span className="flex-1 truncate">
+                        {source?.name ?? suggestion.source}
+                      </span>
+                      <span className="text-xs text-gray-500">
+                        {Math.round(suggestion.confidence * 100)}%
+                      </span>
+                      <button
+                        onClick={() => acceptSuggestion(suggestion)}
+                        className="p-1 rounded hover:bg-gray-800 text-gray-400 hover:text-white"
+                        title="Add edge"
+                      >
+                        <Plus size={14} />
+                      </button>
+                    </li>
+                  );
+                })}
+              </ul>
+            )}
+          </div>
+
+          <div>
+            <button
+              onClick={() => checkSize(false)}
+              className="text-sm text-blue-400 hover:text-blue-300"
+            >
+              Check size
+            </button>
+            {split && (
+              <div className="mt-1 space-y-1 text-xs text-gray-400">
+                <p>
+                  {split.promptChars.toLocaleString()} prompt characters, {split.codeLines} lines
+                  of code, {split.exportCount} exports
+                </p>
+                {!split.oversized && <p className="text-gray-500">Small enough to generate well</p>}
+                {split.reasons.map((reason) => (
+                  <p key={reason} className="text-yellow-400">{reason}</p>
+                ))}
+                {split.oversized && (
+                  <button
+                    onClick={() => checkSize(true)}
+                    disabled={proposingSplit}
+                    className="text-blue-400 hover:text-blue-300 disabled:opacity-50"
+                  >
+                    {proposingSplit ? 'Proposing a split...' : 'Propose a split'}
+                  </button>
+                )}
+                {split.proposal && split.proposal.length > 0 && (
+                  <ul className="space-y-1">
+                    {split.proposal.map((part) => (
+                      <li key={part.filePath} className="text-gray-300" title={part.description}>
+                        {part.name} <span className="text-gray-500">({part.filePath})</span>
+                        {part.dependsOn.length > 0 && (
+                          <span className="text-gray-500"> depends on {part.dependsOn.join(', ')}</span>
+                        )}
+                      </li>
+                    ))}
+                  </ul>
+                )}
+              </div>
+            )}
+          </div>
         </div>
       )}
 
@@ -182,6 +426,7 @@ export default function NodeEditor({ node }: NodeEditorProps) {
         <div className="flex-1 overflow-y-auto p-4">
           <LLMConfigEditor
             config={node.llmConfig}
+            projectDefault={project?.manifest.defaultLlm}
             onChange={(llmConfig) => updateNode(node.id, { llmConfig })}
           />
         </div>
@@ -1,5 +1,16 @@
 import { useRef, useEffect } from 'react';
-import { X, Play, Square, Trash2, CheckCircle, XCircle, AlertCircle, Loader2 } from 'lucide-react';
+import {
+  X,
+  Play,
+  Pause,
+  SkipForward,
+  Square,
+  Trash2,
+  CheckCircle,
+  XCircle,
+  AlertCircle,
+  Loader2,
+} from 'lucide-react';
 import { useExecutionStore, type ExecutionStatus } from '../../stores/executionStore';
 
 interface ExecutionMonitorProps {
@@ -14,9 +25,16 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
     completedNodes,
     totalNodes,
     failedNodes,
+    awaitingApproval,
+    interruptedRun,
     logs,
     error,
     startExecution,
+    continueExecution,
+    approveExecution,
+    checkInterruptedRun,
+    resumeInterruptedRun,
+    discardInterruptedRun,
     cancelExecution,
     clearLogs,
     reset,
@@ -24,6 +42,11 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
 
   const logsEndRef = useRef<HTMLDivElement>(null);
 
+  // Offer to resume a run the app was closed during
+  useEffect(() => {
+    checkInterruptedRun();
+  }, [checkInterruptedRun]);
+
   // Auto-scroll to bottom when new logs appear
   useEffect(() => {
     logsEndRef.current?.scrollIntoView({ behavior: 'smooth' });
@@ -38,6 +61,8 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
       case 'planning':
       case 'running':
         return <Loader2 className="w-4 h-4 animate-spin" />;
+      case 'paused':
+        return <Pause className="w-4 h-4 text-blue-400" />;
       case 'completed':
         return <CheckCircle className="w-4 h-4 text-green-500" />;
       case 'cancelled':
@@ -55,6 +80,10 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
         return 'Planning...';
       case 'running':
         return `Running (Wave ${currentWave}/${totalWaves})`;
+      case 'paused':
+        return awaitingApproval.length > 0
+          ? `Awaiting approval of ${awaitingApproval.length} node(s)`
+          : `Paused after wave ${currentWave}/${totalWaves}`;
       case 'completed':
         return 'Completed';
       case 'cancelled':
@@ -122,6 +151,31 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
           )}
         </div>
 
+        {/* Interrupted run */}
+        {interruptedRun && status === 'idle' && (
+          <div className="flex items-center justify-between gap-3 px-4 py-3 border-b border-gray-700 bg-yellow-900/20">
+            <p className="text-sm text-yellow-300">
+              A run was interrupted after generating {interruptedRun.finishedNodes} of{' '}
+              {interruptedRun.totalNodes} nodes.
+            </p>
+            <div className="flex items-center gap-2">
+              <button
+                onClick={discardInterruptedRun}
+                className="px-3 py-1 text-sm text-gray-300 hover:text-white border border-gray-600 rounded transition-colors"
+              >
+                Discard
+              </button>
+              <button
+                onClick={resumeInterruptedRun}
+                className="flex items-center gap-1 px-3 py-1 text-sm bg-green-600 hover:bg-green-700 text-white rounded transition-colors"
+              >
+                <Play className="w-3 h-3" />
+                Resume
+              </button>
+            </div>
+          </div>
+        )}
+
         {/* Logs */}
         <div className="flex-1 overflow-y-auto p-4 min-h-[300px] max-h-[400px] font-mono text-sm">
           {logs.length === 0 ? (
@@ -172,7 +226,42 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
           </button>
 
           <div className="flex items-center gap-2">
-            {isRunning ? (
+            {status === 'paused' && awaitingApproval.length > 0 ? (
+              <>
+                <button
+                  onClick={() => approveExecution(true)}
+                  title="Skip everything that depends on this code"
+                  className="flex items-center gap-2 px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded transition-colors"
+                >
+                  <XCircle className="w-4 h-4" />
+                  Reject
+                </button>
+                <button
+                  onClick={() => approveExecution()}
+                  className="flex items-center gap-2 px-4 py-2 bg-green-600 hover:bg-green-700 text-white rounded transition-colors"
+                >
+                  <CheckCircle className="w-4 h-4" />
+                  Approve
+                </button>
+              </>
+            ) : status === 'paused' ? (
+              <>
+                <button
+                  onClick={() => continueExecution(true)}
+                  className="flex items-center gap-2 px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded transition-colors"
+                >
+                  <Square className="w-4 h-4" />
+                  Stop
+                </button>
+                <button
+                  onClick={() => continueExecution()}
+                  className="flex items-center gap-2 px-4 py-2 bg-green-600 hover:bg-green-700 text-white rounded transition-colors"
+                >
+                  <Play className="w-4 h-4" />
+                  Continue
+                </button>
+              </>
+            ) : isRunning ? (
               <button
                 onClick={cancelExecution}
                 className="flex items-center gap-2 px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded transition-colors"
@@ -191,7 +280,15 @@ export default function ExecutionMonitor({ onClose }: ExecutionMonitorProps) {
                   </button>
                 )}
                 <button
-                  onClick={startExecution}
+                  onClick={() => startExecution({ step: true })}
+                  title="Pause after each wave to inspect its output"
+                  className="flex items-center gap-2 px-4 py-2 text-gray-300 hover:text-white border border-gray-600 rounded transition-colors"
+                >
+                  <SkipForward className="w-4 h-4" />
+                  Step
+                </button>
+                <button
+                  onClick={() => startExecution()}
                   className="flex items-center gap-2 px-4 py-2 bg-green-600 hover:bg-green-700 text-white rounded transition-colors"
                 >
                   <Play className="w-4 h-4" />
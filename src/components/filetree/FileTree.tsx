@@ -22,6 +22,8 @@ function getStatusColor(status?: NodeStatus): string {
       return 'text-red-400';
     case 'warning':
       return 'text-yellow-400';
+    case 'stale':
+      return 'text-orange-400';
     default:
       return 'text-gray-400';
   }
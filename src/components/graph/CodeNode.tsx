@@ -8,6 +8,9 @@ import {
   AlertTriangle,
   Circle,
   Unlink,
+  RefreshCw,
+  Pin,
+  ShieldCheck,
 } from 'lucide-react';
 import type { CodeNode as CodeNodeType, NodeStatus } from '../../lib/types';
 
@@ -17,6 +20,7 @@ const statusIcons: Record<NodeStatus, React.ReactNode> = {
   complete: <CheckCircle size={12} className="text-green-500" />,
   error: <XCircle size={12} className="text-red-500" />,
   warning: <AlertTriangle size={12} className="text-yellow-500" />,
+  stale: <RefreshCw size={12} className="text-orange-400" />,
 };
 
 const statusColors: Record<NodeStatus, string> = {
@@ -25,6 +29,7 @@ const statusColors: Record<NodeStatus, string> = {
   complete: 'border-green-500 bg-green-900/30',
   error: 'border-red-500 bg-red-900/30',
   warning: 'border-yellow-500 bg-yellow-900/30',
+  stale: 'border-orange-400 bg-orange-900/30',
 };
 
 const languageIcons: Record<string, string> = {
@@ -77,6 +82,7 @@ function CodeNodeComponent({ data, selected }: CodeNodeComponentProps) {
       className={`
         min-w-[180px] rounded-lg border-2 shadow-lg transition-all relative
         ${statusColors[data.status]}
+        ${data.disabled ? 'opacity-40 border-dashed' : ''}
         ${selected ? 'ring-2 ring-blue-400 ring-offset-2 ring-offset-gray-900' : ''}
       `}
     >
@@ -106,6 +112,16 @@ function CodeNodeComponent({ data, selected }: CodeNodeComponentProps) {
           {languageIcons[data.language] || String(data.language).toUpperCase()}
         </span>
         <div className="flex-1" />
+        {data.pinned && (
+          <span title="Pinned: never regenerated">
+            <Pin size={12} className="text-blue-400" />
+          </span>
+        )}
+        {data.requiresApproval && (
+          <span title="Requires approval before dependents are generated">
+            <ShieldCheck size={12} className="text-amber-400" />
+          </span>
+        )}
         {statusIcons[data.status]}
       </div>
 
@@ -14,7 +14,7 @@ import {
 } from '@xyflow/react';
 import type { Node, Edge } from '@xyflow/react';
 import '@xyflow/react/dist/style.css';
-import { Plus, Trash2, Copy, Play, Settings } from 'lucide-react';
+import { Plus, Trash2, Copy, Play, Settings, Network } from 'lucide-react';
 
 import { useProjectStore } from '../../stores/projectStore';
 import CodeNode from './CodeNode';
@@ -83,6 +83,7 @@ function GraphCanvasInner() {
     addEdge: addProjectEdge,
     deleteEdge,
     addNode,
+    autoLayout,
   } = useProjectStore();
 
   const [contextMenu, setContextMenu] = useState<ContextMenuState | null>(null);
@@ -249,9 +250,10 @@ function GraphCanvasInner() {
         purpose: '',
         exports: [],
         llmConfig: {
-          provider: 'anthropic',
-          model: 'claude-sonnet-4-20250514',
+          provider: project.manifest.defaultLlm.provider,
+          model: project.manifest.defaultLlm.model,
           constraints: [],
+          useProjectDefault: true,
         },
         position,
       });
@@ -337,6 +339,11 @@ function GraphCanvasInner() {
           icon: <Plus size={14} />,
           onClick: () => addNodeAtPosition(contextMenu.x, contextMenu.y),
         },
+        {
+          label: 'Auto Layout',
+          icon: <Network size={14} />,
+          onClick: () => autoLayout(),
+        },
       ];
     }
 
@@ -394,7 +401,7 @@ function GraphCanvasInner() {
     }
 
     return [];
-  }, [contextMenu, addNodeAtPosition, duplicateNode, deleteNode, deleteSelectedNodes, deleteEdge, setSelectedNodes, selectedNodeIds]);
+  }, [contextMenu, addNodeAtPosition, autoLayout, duplicateNode, deleteNode, deleteSelectedNodes, deleteEdge, setSelectedNodes, selectedNodeIds]);
 
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   const getNodeColor = (node: any) => {
@@ -408,6 +415,8 @@ function GraphCanvasInner() {
         return '#dc2626';
       case 'warning':
         return '#d97706';
+      case 'stale':
+        return '#fb923c';
       default:
         return '#374151';
     }
@@ -1,4 +1,4 @@
-import { FolderOpen, Save, Plus, Play, Settings, ChevronLeft, ChevronRight, Terminal } from 'lucide-react';
+import { FolderOpen, Save, Plus, Play, Settings, ChevronLeft, ChevronRight, Terminal, History } from 'lucide-react';
 import { useState, useCallback, useEffect, useRef } from 'react';
 import { open } from '@tauri-apps/plugin-shell';
 import { useProjectStore } from '../../stores/projectStore';
@@ -16,9 +16,19 @@ const MAX_WIDTH = 400;
 const DEFAULT_WIDTH = 256;
 
 export default function LeftPanel({ onOpenSettings, onOpenExecutionMonitor }: LeftPanelProps) {
-  const { project, loadProject, saveProject, createProject } = useProjectStore();
+  const {
+    project,
+    recentProjects,
+    loadProject,
+    loadRecentProjects,
+    openRecentProject,
+    setAutoLoadProject,
+    saveProject,
+    createProject,
+  } = useProjectStore();
   const { addToast } = useToastStore();
   const [isCollapsed, setIsCollapsed] = useState(false);
+  const [showRecent, setShowRecent] = useState(false);
   const [width, setWidth] = useState(DEFAULT_WIDTH);
   const [isResizing, setIsResizing] = useState(false);
   const panelRef = useRef<HTMLDivElement>(null);
@@ -40,6 +50,11 @@ export default function LeftPanel({ onOpenSettings, onOpenExecutionMonitor }: Le
     }
   }, [project, addToast]);
 
+  const toggleRecent = useCallback(() => {
+    if (!showRecent) loadRecentProjects();
+    setShowRecent(!showRecent);
+  }, [showRecent, loadRecentProjects]);
+
   const startResizing = useCallback((e: React.MouseEvent) => {
     e.preventDefault();
     setIsResizing(true);
@@ -163,6 +178,16 @@ export default function LeftPanel({ onOpenSettings, onOpenExecutionMonitor }: Le
           <FolderOpen size={16} />
         </button>
 
+        <button
+          onClick={toggleRecent}
+          className={`p-1.5 rounded hover:bg-gray-800 transition-colors ${
+            showRecent ? 'text-white bg-gray-800' : 'text-gray-400 hover:text-white'
+          }`}
+          title="Recent Projects"
+        >
+          <History size={16} />
+        </button>
+
         <button
           onClick={saveProject}
           disabled={!project}
@@ -183,6 +208,38 @@ export default function LeftPanel({ onOpenSettings, onOpenExecutionMonitor }: Le
         </button>
       </div>
 
+      {/* Recent Projects */}
+      {showRecent && (
+        <div className="border-b border-gray-800 py-1">
+          {recentProjects && recentProjects.projects.length > 0 ? (
+            recentProjects.projects.map((recent) => (
+              <button
+                key={recent.path}
+                onClick={() => {
+                  setShowRecent(false);
+                  openRecentProject(recent.path);
+                }}
+                className="w-full text-left px-3 py-1 hover:bg-gray-800 transition-colors"
+                title={recent.path}
+              >
+                <div className="text-sm text-gray-300 truncate">{recent.name}</div>
+                <div className="text-xs text-gray-500 truncate">{recent.path}</div>
+              </button>
+            ))
+          ) : (
+            <div className="px-3 py-1 text-xs text-gray-500">No recent projects</div>
+          )}
+          <label className="flex items-center gap-2 px-3 py-1.5 text-xs text-gray-400">
+            <input
+              type="checkbox"
+              checked={recentProjects?.autoLoad ?? false}
+              onChange={(e) => setAutoLoadProject(e.target.checked)}
+            />
+            Reopen the last project on startup
+          </label>
+        </div>
+      )}
+
       {/* File Tree */}
       <div className="flex-1 overflow-y-auto">
         <div className="px-3 py-2">
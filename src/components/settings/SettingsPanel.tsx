@@ -11,10 +11,15 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
 
   const [anthropicKey, setAnthropicKey] = useState('');
   const [openaiKey, setOpenaiKey] = useState('');
+  const [mistralKey, setMistralKey] = useState('');
   const [ollamaUrl, setOllamaUrl] = useState('');
+  const [proxyUrl, setProxyUrl] = useState('');
+  const [githubToken, setGithubToken] = useState('');
 
   const [showAnthropicKey, setShowAnthropicKey] = useState(false);
   const [showOpenaiKey, setShowOpenaiKey] = useState(false);
+  const [showMistralKey, setShowMistralKey] = useState(false);
+  const [showGithubToken, setShowGithubToken] = useState(false);
 
   const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved' | 'error'>('idle');
 
@@ -22,7 +27,10 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
     if (isLoaded) {
       setAnthropicKey(settings.anthropicApiKey);
       setOpenaiKey(settings.openaiApiKey);
+      setMistralKey(settings.mistralApiKey);
       setOllamaUrl(settings.ollamaBaseUrl);
+      setProxyUrl(settings.proxyUrl);
+      setGithubToken(settings.githubToken);
     }
   }, [isLoaded, settings]);
 
@@ -32,7 +40,10 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
       await updateSettings({
         anthropicApiKey: anthropicKey,
         openaiApiKey: openaiKey,
+        mistralApiKey: mistralKey,
         ollamaBaseUrl: ollamaUrl,
+        proxyUrl,
+        githubToken,
       });
       setSaveStatus('saved');
       setTimeout(() => setSaveStatus('idle'), 2000);
@@ -45,7 +56,10 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
   const hasChanges =
     anthropicKey !== settings.anthropicApiKey ||
     openaiKey !== settings.openaiApiKey ||
-    ollamaUrl !== settings.ollamaBaseUrl;
+    mistralKey !== settings.mistralApiKey ||
+    ollamaUrl !== settings.ollamaBaseUrl ||
+    proxyUrl !== settings.proxyUrl ||
+    githubToken !== settings.githubToken;
 
   return (
     <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
@@ -131,6 +145,40 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
             </p>
           </div>
 
+          {/* Mistral API Key */}
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-2">
+              Mistral API Key
+            </label>
+            <div className="relative">
+              <input
+                type={showMistralKey ? 'text' : 'password'}
+                value={mistralKey}
+                onChange={(e) => setMistralKey(e.target.value)}
+                placeholder="Mistral or Codestral key"
+                className="w-full px-3 py-2 pr-10 bg-gray-800 border border-gray-700 rounded-md text-white text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+              <button
+                type="button"
+                onClick={() => setShowMistralKey(!showMistralKey)}
+                className="absolute right-2 top-1/2 -translate-y-1/2 text-gray-400 hover:text-white"
+              >
+                {showMistralKey ? <EyeOff size={16} /> : <Eye size={16} />}
+              </button>
+            </div>
+            <p className="mt-1 text-xs text-gray-500">
+              Get your API key from{' '}
+              <a
+                href="https://console.mistral.ai/api-keys"
+                target="_blank"
+                rel="noopener noreferrer"
+                className="text-blue-400 hover:underline"
+              >
+                console.mistral.ai
+              </a>
+            </p>
+          </div>
+
           {/* Ollama Base URL */}
           <div>
             <label className="block text-sm font-medium text-gray-300 mb-2">
@@ -147,6 +195,51 @@ export default function SettingsPanel({ onClose }: SettingsPanelProps) {
               Default: http://localhost:11434 (local Ollama server)
             </p>
           </div>
+
+          {/* Proxy */}
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-2">
+              Proxy
+            </label>
+            <input
+              type="text"
+              value={proxyUrl}
+              onChange={(e) => setProxyUrl(e.target.value)}
+              placeholder="http://proxy.example.com:8080"
+              className="w-full px-3 py-2 bg-gray-800 border border-gray-700 rounded-md text-white text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <p className="mt-1 text-xs text-gray-500">
+              HTTP(S) or socks5:// proxy for provider requests. Empty uses NEEDLEPOINT_PROXY
+              or HTTPS_PROXY from the environment.
+            </p>
+          </div>
+
+          {/* GitHub Token */}
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-2">
+              GitHub Token
+            </label>
+            <div className="relative">
+              <input
+                type={showGithubToken ? 'text' : 'password'}
+                value={githubToken}
+                onChange={(e) => setGithubToken(e.target.value)}
+                placeholder="github_pat_..."
+                className="w-full px-3 py-2 pr-10 bg-gray-800 border border-gray-700 rounded-md text-white text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+              <button
+                type="button"
+                onClick={() => setShowGithubToken(!showGithubToken)}
+                className="absolute right-2 top-1/2 -translate-y-1/2 text-gray-400 hover:text-white"
+              >
+                {showGithubToken ? <EyeOff size={16} /> : <Eye size={16} />}
+              </button>
+            </div>
+            <p className="mt-1 text-xs text-gray-500">
+              Personal access token with contents and pull requests write access, used to
+              open pull requests for runs in the project's githubRepository.
+            </p>
+          </div>
         </div>
 
         {/* Footer */}
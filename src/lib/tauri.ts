@@ -1,7 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
-import type { Project, ExecutionPlan, ExecutionEvent, ApiKeysInput } from './types';
+import type { Project, ExecutionPlan, ExecutionEvent, ApiKeysInput, PilotReport, NodeFileWriteResult, ProjectFiles, TrashEntry, FileContents, FileReadResult, SharedType, InterruptedRun, RecentProjects, EdgeSuggestion, SplitSuggestion, PullRequest, MistralModel } from './types';
 
 /**
  * Open a folder selection dialog and return the selected path
@@ -34,9 +34,37 @@ export async function loadProjectFromPath(): Promise<Project | null> {
   const path = await selectProjectFile();
   if (!path) return null;
 
+  return await loadProjectFile(path);
+}
+
+/**
+ * Load the project in a YAML file without asking for it
+ */
+export async function loadProjectFile(path: string): Promise<Project> {
   return await invoke<Project>('load_project', { path });
 }
 
+/**
+ * The last project, when it was reopened on startup
+ */
+export async function getStartupProject(): Promise<Project | null> {
+  return await invoke<Project | null>('get_startup_project');
+}
+
+/**
+ * Recently opened projects, most recent first
+ */
+export async function getRecentProjects(): Promise<RecentProjects> {
+  return await invoke<RecentProjects>('get_recent_projects');
+}
+
+/**
+ * Turn reopening the last project on startup on or off
+ */
+export async function setAutoLoadProject(enabled: boolean): Promise<RecentProjects> {
+  return await invoke<RecentProjects>('set_auto_load_project', { enabled });
+}
+
 /**
  * Save a project to the filesystem
  */
@@ -65,6 +93,20 @@ export async function previewPrompt(
   return await invoke<string>('preview_prompt', { project, nodeId });
 }
 
+/**
+ * Route LLM provider requests through a proxy (empty to use the environment's)
+ */
+export async function setProxy(proxyUrl?: string): Promise<void> {
+  return await invoke('set_proxy', { proxyUrl });
+}
+
+/**
+ * List the Mistral models the API key (or MISTRAL_API_KEY) can use
+ */
+export async function listMistralModels(apiKey?: string): Promise<MistralModel[]> {
+  return await invoke<MistralModel[]>('list_mistral_models', { apiKey });
+}
+
 /**
  * Get the execution plan for a project (for preview)
  */
@@ -72,15 +114,122 @@ export async function getExecutionPlan(project: Project): Promise<ExecutionPlan>
   return await invoke<ExecutionPlan>('get_execution_plan', { project });
 }
 
+/**
+ * Position every node in dependency layers
+ */
+export async function autoLayout(project: Project): Promise<Project> {
+  return await invoke<Project>('auto_layout_project', { project });
+}
+
+/**
+ * Edges the project seems to be missing, most confident first
+ */
+export async function suggestEdges(project: Project): Promise<EdgeSuggestion[]> {
+  return await invoke<EdgeSuggestion[]>('suggest_project_edges', { project });
+}
+
+/**
+ * Check whether a node is too large to generate well; with API keys, the project's
+ * default LLM also proposes how to split it
+ */
+export async function suggestNodeSplit(
+  project: Project,
+  nodeId: string,
+  apiKeys?: ApiKeysInput
+): Promise<SplitSuggestion> {
+  return await invoke<SplitSuggestion>('suggest_node_split', { project, nodeId, apiKeys });
+}
+
+/**
+ * Commit the files a run wrote to a new branch and open a pull request for it, in the
+ * repository set in the project settings (githubRepository)
+ */
+export async function openPullRequest(
+  project: Project,
+  runId: string,
+  githubToken: string
+): Promise<PullRequest> {
+  return await invoke<PullRequest>('open_pull_request', { project, runId, githubToken });
+}
+
+/**
+ * Add or change a shared context snippet (nodes generated with its old text become stale)
+ */
+export async function setSnippet(
+  project: Project,
+  name: string,
+  text: string
+): Promise<Project> {
+  return await invoke<Project>('set_snippet', { project, name, text });
+}
+
+/**
+ * Remove a shared context snippet (nodes generated with it become stale)
+ */
+export async function deleteSnippet(project: Project, name: string): Promise<Project> {
+  return await invoke<Project>('delete_snippet', { project, name });
+}
+
+/**
+ * Add or change a shared type (nodes generated with its old definition become stale)
+ */
+export async function setSharedType(
+  project: Project,
+  name: string,
+  sharedType: SharedType
+): Promise<Project> {
+  return await invoke<Project>('set_shared_type', { project, name, sharedType });
+}
+
+/**
+ * Remove a shared type (nodes generated with it become stale)
+ */
+export async function deleteSharedType(project: Project, name: string): Promise<Project> {
+  return await invoke<Project>('delete_shared_type', { project, name });
+}
+
+/**
+ * Options for a generation run
+ */
+export interface GenerationOptions {
+  /** Leave nodes that are already complete untouched */
+  skipCompleted?: boolean;
+  /** Regenerate complete nodes and, for node selections, all of their dependencies */
+  force?: boolean;
+  /** Pause after each wave until `continueExecution` */
+  step?: boolean;
+  /** Stop starting nodes after this many seconds, leaving the rest pending */
+  maxDurationSecs?: number;
+}
+
 /**
  * Generate code for all nodes in dependency order
  * Returns the updated project with generated code
  */
 export async function generateAll(
   project: Project,
-  apiKeys: ApiKeysInput
+  apiKeys: ApiKeysInput,
+  options: GenerationOptions & {
+    consistencyPass?: boolean;
+    profile?: string;
+    /** Only generate nodes carrying one of these tags */
+    tags?: string[];
+  } = {}
 ): Promise<Project> {
-  return await invoke<Project>('generate_all', { project, apiKeys });
+  return await invoke<Project>('generate_all', { project, apiKeys, ...options });
+}
+
+/**
+ * Generate a single wave (wave 0 by default) as a trial run
+ * Returns the updated project and quality/cost metrics for the wave
+ */
+export async function generatePilot(
+  project: Project,
+  apiKeys: ApiKeysInput,
+  waveNumber?: number,
+  timeLimitSecs?: number
+): Promise<{ project: Project; report: PilotReport }> {
+  return await invoke('generate_pilot', { project, apiKeys, waveNumber, timeLimitSecs });
 }
 
 /**
@@ -90,9 +239,50 @@ export async function generateAll(
 export async function generateNodes(
   project: Project,
   nodeIds: string[],
+  apiKeys: ApiKeysInput,
+  options: GenerationOptions = {}
+): Promise<Project> {
+  return await invoke<Project>('generate_nodes', { project, nodeIds, apiKeys, ...options });
+}
+
+/**
+ * Let a run paused in step mode generate its next wave, using the specs of `project`
+ * (edited while paused), or end it with `stop`
+ */
+export async function continueExecution(project?: Project, stop = false): Promise<void> {
+  await invoke('continue_execution', { project, stop });
+}
+
+/**
+ * Approve the generated code a run is paused for, with the specs of `project`, or
+ * `reject` it so nothing depending on it is generated
+ */
+export async function approveGeneration(project?: Project, reject = false): Promise<void> {
+  await invoke('approve_generation', { project, reject });
+}
+
+/**
+ * The run that was still generating the project when the app was closed, if any
+ */
+export async function getInterruptedRun(projectPath: string): Promise<InterruptedRun | null> {
+  return await invoke<InterruptedRun | null>('get_interrupted_run', { projectPath });
+}
+
+/**
+ * Restore the code an interrupted run generated and generate the nodes it didn't get to
+ */
+export async function resumeInterruptedRun(
+  project: Project,
   apiKeys: ApiKeysInput
 ): Promise<Project> {
-  return await invoke<Project>('generate_nodes', { project, nodeIds, apiKeys });
+  return await invoke<Project>('resume_interrupted_run', { project, apiKeys });
+}
+
+/**
+ * Forget an interrupted run instead of resuming it
+ */
+export async function discardInterruptedRun(projectPath: string): Promise<void> {
+  await invoke('discard_interrupted_run', { projectPath });
 }
 
 /**
@@ -124,6 +314,29 @@ export async function writeFile(projectPath: string, filePath: string, content:
   await invoke('write_file', { projectPath, filePath, content });
 }
 
+/**
+ * Write a node's generated code to its file, with the file header and footer applied.
+ * Manual edits made since the last write are three-way merged with the new code;
+ * `force` overwrites them instead.
+ */
+export async function writeNodeFile(project: Project, nodeId: string, force?: boolean): Promise<NodeFileWriteResult> {
+  return await invoke<NodeFileWriteResult>('write_node_file', { project, nodeId, force });
+}
+
+/**
+ * Read a file's contents (limited to 2 MB; binary files come without content)
+ */
+export async function readFile(projectPath: string, filePath: string): Promise<FileContents> {
+  return await invoke<FileContents>('read_file', { projectPath, filePath });
+}
+
+/**
+ * Read several files at once; failures are reported per file
+ */
+export async function readFiles(projectPath: string, filePaths: string[]): Promise<FileReadResult[]> {
+  return await invoke<FileReadResult[]>('read_files', { projectPath, filePaths });
+}
+
 /**
  * Soft delete a file (moves to trash)
  * Returns the trash filename for potential restoration
@@ -139,18 +352,34 @@ export async function deleteFilePermanent(projectPath: string, filePath: string)
   await invoke('delete_file_permanent', { projectPath, filePath });
 }
 
+/**
+ * Soft delete a directory (archived into trash)
+ * Returns the trash filename for potential restoration
+ */
+export async function deleteDirectory(projectPath: string, dirPath: string): Promise<string> {
+  return await invoke<string>('delete_directory', { projectPath, dirPath });
+}
+
+/**
+ * Move a directory and its contents
+ */
+export async function moveDirectory(projectPath: string, oldPath: string, newPath: string): Promise<void> {
+  await invoke('move_directory', { projectPath, oldPath, newPath });
+}
+
 /**
  * Restore a file from trash
+ * Without originalPath, the file goes back to where it was deleted from
  */
-export async function restoreFile(projectPath: string, trashFilename: string, originalPath: string): Promise<void> {
+export async function restoreFile(projectPath: string, trashFilename: string, originalPath?: string): Promise<void> {
   await invoke('restore_file', { projectPath, trashFilename, originalPath });
 }
 
 /**
- * List files in trash
+ * List files in trash, most recently deleted first
  */
-export async function listTrash(projectPath: string): Promise<string[]> {
-  return await invoke<string[]>('list_trash', { projectPath });
+export async function listTrash(projectPath: string): Promise<TrashEntry[]> {
+  return await invoke<TrashEntry[]>('list_trash', { projectPath });
 }
 
 /**
@@ -161,6 +390,14 @@ export async function emptyTrash(projectPath: string): Promise<number> {
   return await invoke<number>('empty_trash', { projectPath });
 }
 
+/**
+ * Permanently delete trashed files older than maxAgeDays
+ * Returns the number of files deleted
+ */
+export async function purgeTrash(projectPath: string, maxAgeDays: number): Promise<number> {
+  return await invoke<number>('purge_trash', { projectPath, maxAgeDays });
+}
+
 /**
  * Rename/move a file
  */
@@ -182,6 +419,13 @@ export async function createDirectory(projectPath: string, dirPath: string): Pro
   await invoke('create_directory', { projectPath, dirPath });
 }
 
+/**
+ * List the project directory (respecting .gitignore), annotated with node files
+ */
+export async function listProjectFiles(project: Project): Promise<ProjectFiles> {
+  return await invoke<ProjectFiles>('list_project_files', { project });
+}
+
 // ============================================================================
 // API & Agent Integration
 // ============================================================================
@@ -1,16 +1,58 @@
 // Core data types matching Rust backend structs
 
-export type NodeStatus = 'pending' | 'generating' | 'complete' | 'error' | 'warning';
+export type NodeStatus = 'pending' | 'generating' | 'complete' | 'error' | 'warning' | 'stale';
 
-export type LLMProvider = 'anthropic' | 'openai' | 'ollama';
+export type LLMProvider = 'anthropic' | 'openai' | 'mistral' | 'ollama' | 'local' | 'mock';
 
 
-export type Language = 'typescript' | 'javascript' | 'python' | 'rust' | 'go';
+export type BuiltinLanguage =
+  | 'typescript'
+  | 'javascript'
+  | 'python'
+  | 'rust'
+  | 'go'
+  | 'java'
+  | 'csharp'
+  | 'cpp'
+  | 'ruby'
+  | 'php'
+  | 'kotlin'
+  | 'swift'
+  | 'sql'
+  | 'html'
+  | 'css'
+  | 'bash';
+
+// Any other name is treated as a custom language
+export type Language = BuiltinLanguage | (string & {});
+
+// What a node's file is; decides how it is prompted. External nodes stand
+// for third-party packages and have no file
+export type NodeKind = 'code' | 'config' | 'schema' | 'doc' | 'asset' | 'external';
+
+export type PackageEcosystem = 'npm' | 'crates' | 'pip';
+
+export interface ExternalPackage {
+  name: string;
+  ecosystem: PackageEcosystem;
+  // Version requirement in the ecosystem's syntax
+  version: string;
+  // API notes included in dependents' prompts
+  docs?: string;
+}
 
 export interface ExportSignature {
   name: string;
   type: string;
   description: string;
+  // The manifest's shared type this export is, or is built on
+  sharedType?: string;
+}
+
+// A type definition shared across files, with a variant per language
+export interface SharedType {
+  description?: string;
+  variants: Partial<Record<Language, string>>;
 }
 
 export interface LLMConfig {
@@ -18,6 +60,55 @@ export interface LLMConfig {
   model: string;
   systemPrompt?: string;
   constraints: string[];
+  // Few-shot demonstrations, shown after the project's examples
+  examples?: PromptExample[];
+  // Seconds to wait for one request, overriding the project's requestTimeoutSecs
+  timeoutSecs?: number;
+  // Extended thinking tokens for Anthropic models (at least 1024)
+  thinkingBudget?: number;
+  // Reasoning effort for OpenAI reasoning models
+  reasoningEffort?: 'low' | 'medium' | 'high';
+  // Complete the file through Mistral's fill-in-the-middle endpoint (Codestral models)
+  fim?: boolean;
+  // Sampling seed for reproducible regenerations (not supported by Anthropic)
+  seed?: number;
+  // Generate with the project's defaultLlm provider and model, following changes to it
+  useProjectDefault?: boolean;
+}
+
+export interface PromptExample {
+  description?: string;
+  input: string;
+  output: string;
+}
+
+export interface GenerationRecord {
+  model: string;
+  tokensIn?: number;
+  tokensOut?: number;
+  durationMs: number;
+  timestamp: string;
+  seed?: number;
+  /** Prose the model wrapped around the code, left out of the file */
+  note?: string;
+  /** What each post-processing step did to the code */
+  postProcessing?: PostProcessReport[];
+}
+
+export type PostProcessStep =
+  | 'stripFences'
+  | 'prettier'
+  | 'rustfmt'
+  | 'black'
+  | 'gofmt'
+  | 'sortImports'
+  | 'trimWhitespace'
+  | { command: string[] };
+
+export interface PostProcessReport {
+  step: string;
+  changed: boolean;
+  error?: string;
 }
 
 export interface CodeNode {
@@ -25,15 +116,64 @@ export interface CodeNode {
   name: string;
   filePath: string;
   language: Language;
+  // Defaults to 'code'
+  kind?: NodeKind;
+  // Free-form labels for operating on slices of the graph
+  tags?: string[];
+  // Left out of execution plans and generation
+  disabled?: boolean;
+  // Hand-written: feeds dependents' context but is never regenerated
+  pinned?: boolean;
+  // Generation pauses until its code is approved, before any dependent is generated
+  requiresApproval?: boolean;
+  // Set on external nodes
+  package?: ExternalPackage;
   status: NodeStatus;
   description: string;
   purpose: string;
   exports: ExportSignature[];
+  // Project files outside the graph included in the prompt as specification
+  referenceFiles?: string[];
+  // Names of the manifest's context snippets included in the prompt
+  snippets?: string[];
+  // Names of the manifest's shared types the node uses, besides those its exports name
+  sharedTypes?: string[];
+  // Values for ${NAME} placeholders in this node's spec, over the manifest's
+  variables?: Record<string, string>;
   llmConfig: LLMConfig;
   generatedCode?: string;
   errorMessage?: string;
   // Position for ReactFlow
   position: { x: number; y: number };
+  /** Dependency IDs that were only available as stub interfaces at last generation */
+  generatedAgainstStubs?: string[];
+  lastGeneration?: GenerationRecord;
+  /** Checkable constraints the generated code breaks */
+  constraintViolations?: ConstraintViolation[];
+  missingExports?: string[];
+  /** Mismatches with generated dependencies, from the last consistency check */
+  consistencyIssues?: ConsistencyIssue[];
+  fileHeader?: string;
+  fileFooter?: string;
+  writtenHash?: string;
+  /** Sub-project the node was included from (read-only here) */
+  subproject?: string;
+}
+
+export interface ConstraintViolation {
+  constraint: string;
+  message: string;
+  /** 1-based line of the first offending match */
+  line?: number;
+}
+
+export interface ConsistencyIssue {
+  /** The dependency the code disagrees with */
+  dependencyId: string;
+  message: string;
+  suggestion?: string;
+  /** 1-based line in the node's file */
+  line?: number;
 }
 
 export interface CodeEdge {
@@ -44,11 +184,124 @@ export interface CodeEdge {
   label: string;
 }
 
+// An edge that looks missing, from what the target's spec or code refers to
+export interface EdgeSuggestion {
+  source: string;
+  target: string;
+  label: string;
+  // 0 to 1
+  confidence: number;
+  reasons: string[];
+}
+
+// One of the smaller nodes an oversized node could be split into
+export interface ProposedNode {
+  name: string;
+  filePath: string;
+  description: string;
+  purpose: string;
+  // Names of the original node's exports this node takes over
+  exports: string[];
+  // File paths of the other proposed nodes it depends on
+  dependsOn: string[];
+}
+
+// How a node measures against the size thresholds, with a proposed split if asked for
+export interface SplitSuggestion {
+  nodeId: string;
+  oversized: boolean;
+  promptChars: number;
+  codeLines: number;
+  exportCount: number;
+  reasons: string[];
+  proposal?: ProposedNode[];
+}
+
+// A pull request opened for a run's files
+export interface PullRequest {
+  number: number;
+  url: string;
+  branch: string;
+  baseBranch: string;
+  // Committed files, from the root of the repository
+  files: string[];
+}
+
+// Overrides applied to every node's LLM config for a run
+export interface GenerationProfile {
+  provider?: LLMProvider;
+  model?: string;
+  maxTokens?: number;
+  temperature?: number;
+}
+
+export interface ProjectCheck {
+  name: string;
+  // Program and arguments, run without a shell
+  command: string[];
+  // Seconds before the check is stopped (default 600)
+  timeoutSecs?: number;
+}
+
+export interface RedactionRules {
+  // Regexes whose matches are replaced with [REDACTED]
+  patterns?: string[];
+  // Also replace long, random-looking tokens that mix letters and digits
+  detectSecrets?: boolean;
+  // Bits of entropy per character from which a token counts as a secret (default 4.0)
+  minEntropy?: number;
+}
+
+export interface ProviderPolicy {
+  // Providers nodes may use; empty allows all
+  allowedProviders?: LLMProvider[];
+  // Models nodes may use; a trailing * matches as a prefix, empty allows all
+  allowedModels?: string[];
+}
+
 export interface ProjectManifest {
   name: string;
   version: string;
   entryPoint?: string;
-  defaultLLM: {
+  recordTranscripts?: boolean;
+  fileHeader?: string;
+  fileFooter?: string;
+  // Allowed file extensions per language, replacing the defaults for those listed
+  languageExtensions?: Partial<Record<Language, string[]>>;
+  // Days to keep trashed files (default 30, 0 keeps them forever)
+  trashRetentionDays?: number;
+  // Named generation profiles, selectable per run
+  profiles?: Record<string, GenerationProfile>;
+  // Regenerate once with the violations in the prompt when a checkable constraint is broken
+  fixConstraintViolations?: boolean;
+  retryMissingExports?: boolean;
+  examples?: PromptExample[];
+  // Added to every node's system prompt, before the node's own
+  systemPrompt?: string;
+  // Shared context paragraphs by name
+  snippets?: Record<string, string>;
+  // Type definitions every file must agree on, by name
+  sharedTypes?: Record<string, SharedType>;
+  // Embedding model for semantic search (defaults to OpenAI, or a local model for Ollama)
+  embeddings?: { provider: LLMProvider; model: string };
+  // Number of semantically related nodes whose exports are summarized in each prompt
+  relatedContext?: number;
+  // Seconds to wait for one LLM request (default 300)
+  requestTimeoutSecs?: number;
+  // Other projects whose nodes are included, with IDs namespaced as `<name>::<id>`
+  subprojects?: { name: string; path: string }[];
+  // Steps run on generated code before it is stored, per language, in order
+  postProcessing?: Partial<Record<Language, PostProcessStep[]>>;
+  // Values for ${NAME} placeholders in node specs (PROJECT_NAME, PROJECT_VERSION and
+  // NODE_NAME are built in)
+  variables?: Record<string, string>;
+  // Commands `needlepoint-cli ci` runs in the project directory after writing files
+  checks?: ProjectCheck[];
+  // Scrubbed from prompts sent to hosted providers and from transcripts
+  redaction?: RedactionRules;
+  // Providers and models generation is allowed to send the project's code to
+  policy?: ProviderPolicy;
+  defaultLlm: {
     provider: LLMProvider;
     model: string;
     apiKeyEnv: string;
@@ -72,7 +325,7 @@ export const DEFAULT_LLM_CONFIG: LLMConfig = {
 export const DEFAULT_PROJECT_MANIFEST: ProjectManifest = {
   name: 'New Project',
   version: '0.1.0',
-  defaultLLM: {
+  defaultLlm: {
     provider: 'anthropic',
     model: 'claude-sonnet-4-20250514',
     apiKeyEnv: 'ANTHROPIC_API_KEY',
@@ -84,12 +337,68 @@ export const DEFAULT_PROJECT_MANIFEST: ProjectManifest = {
 export interface ExecutionWave {
   waveNumber: number;
   nodeIds: string[];
+  estimatedDurationMs: number;
 }
 
 export interface ExecutionPlan {
   waves: ExecutionWave[];
   totalNodes: number;
   skippedNodes: string[];
+  nodeDepths: Record<string, number>;
+  criticalPath: string[];
+  criticalPathMs: number;
+  maxConcurrency?: number;
+  estimatedDurationMs: number;
+}
+
+export interface PilotNodeResult {
+  nodeId: string;
+  nodeName: string;
+  model: string;
+  success: boolean;
+  durationMs: number;
+  tokensUsed?: number;
+  costEstimate?: number;
+  error?: string;
+  declaredExports: number;
+  missingExports: string[];
+  stubbedDependencies: string[];
+  linesOfCode: number;
+}
+
+// A project opened in an earlier session
+export interface RecentProject {
+  // The project's needlepoint.yaml
+  path: string;
+  name: string;
+  openedAt: string;
+}
+
+export interface RecentProjects {
+  // Reopen the most recent project on startup
+  autoLoad: boolean;
+  // Most recent first
+  projects: RecentProject[];
+}
+
+// A run that was still generating when the app was closed
+export interface InterruptedRun {
+  startedAt: string;
+  totalNodes: number;
+  finishedNodes: number;
+  // Nodes still to generate
+  remaining: string[];
+}
+
+export interface PilotReport {
+  waveNumber: number;
+  nodes: PilotNodeResult[];
+  durationMs: number;
+  timedOut: boolean;
+  totalTokens: number;
+  totalCostEstimate: number;
+  exportCoverage: number;
+  projectedTotalCost?: number;
 }
 
 export interface NodeProgress {
@@ -104,12 +413,88 @@ export type ExecutionEvent =
   | { type: 'waveStarted'; waveNumber: number; nodeIds: string[] }
   | { type: 'nodeUpdate' } & NodeProgress
   | { type: 'waveCompleted'; waveNumber: number; successful: number; failed: number }
-  | { type: 'completed'; totalSuccessful: number; totalFailed: number; totalSkipped: number }
+  | {
+      type: 'completed';
+      totalSuccessful: number;
+      totalFailed: number;
+      totalSkipped: number;
+      // The run reached its maximum duration; nodes it hadn't started were left pending
+      timedOut: boolean;
+    }
+  | { type: 'consistencyPassCompleted'; applied: number; rejected: number }
+  | { type: 'budgetExceeded'; message: string; tokensUsed: number; costEstimate: number }
+  | { type: 'wavePaused'; waveNumber: number; remainingWaves: number; project: Project }
+  | {
+      type: 'approvalRequired';
+      waveNumber: number;
+      nodeIds: string[];
+      remainingWaves: number;
+      project: Project;
+    }
   | { type: 'cancelled' }
   | { type: 'error'; message: string };
 
 export interface ApiKeysInput {
   anthropic?: string;
   openai?: string;
+  mistral?: string;
   ollamaBaseUrl?: string;
 }
+
+// A model a Mistral API key can use
+export interface MistralModel {
+  id: string;
+  chat: boolean;
+  // Takes fill-in-the-middle requests
+  fim: boolean;
+}
+
+// Result of writing a node's file
+export interface NodeFileWriteResult {
+  writtenHash: string;
+  merged: boolean;
+  conflicts: boolean;
+}
+
+// A file read through the backend (binary files come without content)
+export interface FileContents {
+  path: string;
+  size: number;
+  binary: boolean;
+  content?: string;
+}
+
+// A file that couldn't be read in a batch
+export interface FileReadError {
+  path: string;
+  error: string;
+}
+
+export type FileReadResult = FileContents | FileReadError;
+
+// A file in the project trash
+export interface TrashEntry {
+  trashFilename: string;
+  // Unknown for files trashed before metadata was recorded
+  originalPath?: string;
+  deletedAt: string;
+  size: number;
+  // Directories are stored as .tar.gz archives
+  isDir: boolean;
+}
+
+// A file or directory under the project root, as found on disk
+export interface ProjectFileEntry {
+  name: string;
+  path: string;
+  isDir: boolean;
+  // The node that generates this file, if any
+  nodeId?: string;
+  children?: ProjectFileEntry[];
+}
+
+export interface ProjectFiles {
+  entries: ProjectFileEntry[];
+  // Node file paths that don't exist on disk
+  missing: string[];
+}
@@ -1,10 +1,32 @@
 import { create } from 'zustand';
-import type { ExecutionEvent, ExecutionPlan, NodeProgress, ApiKeysInput } from '../lib/types';
-import { generateAll, getExecutionPlan, onExecutionProgress } from '../lib/tauri';
+import type {
+  ExecutionEvent,
+  ExecutionPlan,
+  NodeProgress,
+  ApiKeysInput,
+  InterruptedRun,
+} from '../lib/types';
+import {
+  approveGeneration,
+  continueExecution,
+  discardInterruptedRun,
+  generateAll,
+  getExecutionPlan,
+  getInterruptedRun,
+  onExecutionProgress,
+  resumeInterruptedRun,
+} from '../lib/tauri';
 import { useProjectStore } from './projectStore';
 import { useSettingsStore } from './settingsStore';
 
-export type ExecutionStatus = 'idle' | 'planning' | 'running' | 'completed' | 'cancelled' | 'error';
+export type ExecutionStatus =
+  | 'idle'
+  | 'planning'
+  | 'running'
+  | 'paused'
+  | 'completed'
+  | 'cancelled'
+  | 'error';
 
 interface ExecutionLog {
   timestamp: Date;
@@ -21,11 +43,25 @@ interface ExecutionState {
   completedNodes: number;
   totalNodes: number;
   failedNodes: string[];
+  /** Nodes whose generated code the paused run waits to have approved */
+  awaitingApproval: string[];
+  /** A run the app was closed during, which can be resumed */
+  interruptedRun: InterruptedRun | null;
   logs: ExecutionLog[];
   error: string | null;
 
   // Actions
-  startExecution: () => Promise<void>;
+  /** With `step`, the run pauses after each wave until `continueExecution` */
+  startExecution: (options?: { step?: boolean }) => Promise<void>;
+  /** Resume a paused run with the current specs, or end it with `stop` */
+  continueExecution: (stop?: boolean) => Promise<void>;
+  /** Approve the code the run is paused for, or `reject` it so its dependents are skipped */
+  approveExecution: (reject?: boolean) => Promise<void>;
+  /** Look for a run of the loaded project that was interrupted */
+  checkInterruptedRun: () => Promise<void>;
+  /** Restore what the interrupted run generated and generate the rest */
+  resumeInterruptedRun: () => Promise<void>;
+  discardInterruptedRun: () => Promise<void>;
   cancelExecution: () => void;
   clearLogs: () => void;
   reset: () => void;
@@ -39,12 +75,13 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
   completedNodes: 0,
   totalNodes: 0,
   failedNodes: [],
+  awaitingApproval: [],
+  interruptedRun: null,
   logs: [],
   error: null,
 
-  startExecution: async () => {
+  startExecution: async (options = {}) => {
     const projectStore = useProjectStore.getState();
-    const settingsStore = useSettingsStore.getState();
 
     if (!projectStore.project) {
       set({ error: 'No project loaded', status: 'error' });
@@ -60,6 +97,7 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
       completedNodes: 0,
       totalNodes: 0,
       failedNodes: [],
+      awaitingApproval: [],
       logs: [],
       error: null,
     });
@@ -114,15 +152,10 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
       // Start execution
       set({ status: 'running' });
 
-      // Prepare API keys
-      const apiKeys: ApiKeysInput = {
-        anthropic: settingsStore.settings.anthropicApiKey || undefined,
-        openai: settingsStore.settings.openaiApiKey || undefined,
-        ollamaBaseUrl: settingsStore.settings.ollamaBaseUrl || undefined,
-      };
-
       // Execute
-      const updatedProject = await generateAll(projectStore.project, apiKeys);
+      const updatedProject = await generateAll(projectStore.project, currentApiKeys(), {
+        step: options.step,
+      });
 
       // Update project in store
       projectStore.setProject(updatedProject);
@@ -147,6 +180,81 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
     }
   },
 
+  continueExecution: async (stop = false) => {
+    const project = useProjectStore.getState().project ?? undefined;
+    try {
+      await continueExecution(project, stop);
+      set({ status: 'running', awaitingApproval: [] });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
+  checkInterruptedRun: async () => {
+    const project = useProjectStore.getState().project;
+    if (!project) return;
+    try {
+      set({ interruptedRun: await getInterruptedRun(project.projectPath) });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
+  resumeInterruptedRun: async () => {
+    const projectStore = useProjectStore.getState();
+    const { interruptedRun } = get();
+    if (!projectStore.project || !interruptedRun) return;
+
+    set({
+      status: 'running',
+      interruptedRun: null,
+      currentWave: 0,
+      completedNodes: 0,
+      failedNodes: [],
+      awaitingApproval: [],
+      logs: [
+        {
+          timestamp: new Date(),
+          message: `Resuming interrupted run: ${interruptedRun.finishedNodes} of ${interruptedRun.totalNodes} nodes were generated`,
+          type: 'info',
+        },
+      ],
+      error: null,
+    });
+
+    try {
+      const unlisten = await onExecutionProgress((event: ExecutionEvent) => {
+        handleExecutionEvent(event, set, get);
+      });
+      const updatedProject = await resumeInterruptedRun(projectStore.project, currentApiKeys());
+      projectStore.setProject(updatedProject);
+      unlisten();
+    } catch (error) {
+      set({ status: 'error', error: String(error) });
+    }
+  },
+
+  discardInterruptedRun: async () => {
+    const project = useProjectStore.getState().project;
+    if (!project) return;
+    try {
+      await discardInterruptedRun(project.projectPath);
+      set({ interruptedRun: null });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
+  approveExecution: async (reject = false) => {
+    const project = useProjectStore.getState().project ?? undefined;
+    try {
+      await approveGeneration(project, reject);
+      set({ status: 'running', awaitingApproval: [] });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
   cancelExecution: () => {
     // Note: Cancel functionality would require maintaining a reference to the executor
     // For now, we just update the UI state
@@ -176,12 +284,23 @@ export const useExecutionStore = create<ExecutionState>((set, get) => ({
       completedNodes: 0,
       totalNodes: 0,
       failedNodes: [],
+      awaitingApproval: [],
       logs: [],
       error: null,
     });
   },
 }));
 
+export function currentApiKeys(): ApiKeysInput {
+  const { settings } = useSettingsStore.getState();
+  return {
+    anthropic: settings.anthropicApiKey || undefined,
+    openai: settings.openaiApiKey || undefined,
+    mistral: settings.mistralApiKey || undefined,
+    ollamaBaseUrl: settings.ollamaBaseUrl || undefined,
+  };
+}
+
 function handleExecutionEvent(
   event: ExecutionEvent,
   set: (partial: Partial<ExecutionState> | ((state: ExecutionState) => Partial<ExecutionState>)) => void,
@@ -238,6 +357,53 @@ function handleExecutionEvent(
         message: `Execution complete: ${event.totalSuccessful} successful, ${event.totalFailed} failed, ${event.totalSkipped} skipped`,
         type: event.totalFailed > 0 ? 'warning' : 'success',
       });
+      if (event.timedOut) {
+        addLog({
+          timestamp: new Date(),
+          message: 'Time limit reached; nodes not yet started were left pending',
+          type: 'warning',
+        });
+      }
+      break;
+
+    case 'consistencyPassCompleted':
+      addLog({
+        timestamp: new Date(),
+        message: `Consistency pass: ${event.applied} patches applied, ${event.rejected} rejected`,
+        type: event.rejected > 0 ? 'warning' : 'success',
+      });
+      break;
+
+    case 'budgetExceeded':
+      addLog({
+        timestamp: new Date(),
+        message: `${event.message}; no further nodes will be started`,
+        type: 'warning',
+      });
+      break;
+
+    case 'wavePaused':
+      // Show the wave's output so specs can be checked and edited before continuing
+      set({ status: 'paused' });
+      useProjectStore.getState().setProject(event.project);
+      addLog({
+        timestamp: new Date(),
+        message: `Paused after wave ${event.waveNumber + 1}; ${event.remainingWaves} waves remaining`,
+        type: 'info',
+      });
+      break;
+
+    case 'approvalRequired':
+      set({ status: 'paused', awaitingApproval: event.nodeIds });
+      useProjectStore.getState().setProject(event.project);
+      for (const nodeId of event.nodeIds) {
+        addLog({
+          timestamp: new Date(),
+          nodeId,
+          message: 'Generated code awaits approval before its dependents are generated',
+          type: 'warning',
+        });
+      }
       break;
 
     case 'cancelled':
@@ -3,8 +3,9 @@ import type {
   Project,
   CodeNode,
   CodeEdge,
+  RecentProjects,
 } from '../lib/types';
-import { loadProjectFromPath, saveProjectToPath, selectProjectFolder, createFile, writeFile, deleteFile, renameFile, restoreFile } from '../lib/tauri';
+import { loadProjectFromPath, loadProjectFile, getStartupProject, getRecentProjects, setAutoLoadProject, getInterruptedRun, saveProjectToPath, selectProjectFolder, createFile, writeNodeFile, deleteFile, renameFile, restoreFile, autoLayout } from '../lib/tauri';
 import { useToastStore } from './toastStore';
 import { useUndoStore, type DeletedNodeInfo } from './undoStore';
 
@@ -127,8 +128,20 @@ function wouldCreateCycle(
   return false;
 }
 
+/**
+ * Point out a generation run the project was left with
+ */
+async function notifyInterruptedRun(project: Project): Promise<void> {
+  if (await getInterruptedRun(project.projectPath)) {
+    useToastStore
+      .getState()
+      .addToast('A generation run was interrupted; resume it from the Execution Monitor', 'info');
+  }
+}
+
 interface ProjectState {
   project: Project | null;
+  recentProjects: RecentProjects | null;
   selectedNodeIds: string[];
   selectedEdgeId: string | null;
   isLoading: boolean;
@@ -140,7 +153,12 @@ interface ProjectState {
   setSelectedEdge: (edgeId: string | null) => void;
   createProject: () => Promise<void>;
   loadProject: () => Promise<void>;
+  loadStartupProject: () => Promise<void>;
+  loadRecentProjects: () => Promise<void>;
+  openRecentProject: (path: string) => Promise<void>;
+  setAutoLoadProject: (enabled: boolean) => Promise<void>;
   saveProject: () => Promise<void>;
+  autoLayout: () => Promise<void>;
   addNode: (node: Omit<CodeNode, 'id'>) => void;
   updateNode: (id: string, updates: Partial<CodeNode>) => void;
   deleteNode: (id: string) => void;
@@ -154,6 +172,7 @@ interface ProjectState {
 
 export const useProjectStore = create<ProjectState>((set, get) => ({
   project: null,
+  recentProjects: null,
   selectedNodeIds: [],
   selectedEdgeId: null,
   isLoading: false,
@@ -174,7 +193,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
         manifest: {
           name: 'New Project',
           version: '0.1.0',
-          defaultLLM: {
+          defaultLlm: {
             provider: 'anthropic',
             model: 'claude-sonnet-4-20250514',
             apiKeyEnv: 'ANTHROPIC_API_KEY',
@@ -197,6 +216,7 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
       const project = await loadProjectFromPath();
       if (project) {
         set({ project, selectedNodeIds: [] });
+        await notifyInterruptedRun(project);
       }
     } catch (error) {
       set({ error: String(error) });
@@ -205,6 +225,48 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
     }
   },
 
+  loadStartupProject: async () => {
+    try {
+      const project = await getStartupProject();
+      if (project && !get().project) {
+        set({ project, selectedNodeIds: [] });
+        await notifyInterruptedRun(project);
+      }
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
+  loadRecentProjects: async () => {
+    try {
+      set({ recentProjects: await getRecentProjects() });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
+  openRecentProject: async (path) => {
+    set({ isLoading: true, error: null });
+    try {
+      const project = await loadProjectFile(path);
+      set({ project, selectedNodeIds: [] });
+      await notifyInterruptedRun(project);
+    } catch (error) {
+      set({ error: String(error) });
+      useToastStore.getState().addToast('Failed to open project', 'error');
+    } finally {
+      set({ isLoading: false });
+    }
+  },
+
+  setAutoLoadProject: async (enabled) => {
+    try {
+      set({ recentProjects: await setAutoLoadProject(enabled) });
+    } catch (error) {
+      set({ error: String(error) });
+    }
+  },
+
   saveProject: async () => {
     const { project } = get();
     if (!project) return;
@@ -221,6 +283,18 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
     }
   },
 
+  autoLayout: async () => {
+    const { project } = get();
+    if (!project) return;
+
+    try {
+      set({ project: await autoLayout(project) });
+    } catch (error) {
+      set({ error: String(error) });
+      useToastStore.getState().addToast('Failed to lay out graph', 'error');
+    }
+  },
+
   addNode: (nodeData) => {
     const { project } = get();
     if (!project) return;
@@ -286,10 +360,36 @@ export const useProjectStore = create<ProjectState>((set, get) => ({
 
     // Write generated code to file when it's updated
     if (updates.generatedCode !== undefined && updates.generatedCode !== oldNode.generatedCode) {
-      writeFile(project.projectPath, newNode.filePath, updates.generatedCode).catch((err) => {
-        console.error('Failed to write file:', err);
-        useToastStore.getState().addToast(`Failed to write generated code`, 'error');
-      });
+      writeNodeFile(get().project ?? project, id)
+        .then(({ writtenHash, merged, conflicts }) => {
+          const current = get().project;
+          if (!current) return;
+          set({
+            project: {
+              ...current,
+              nodes: current.nodes.map((node) => {
+                if (node.id !== id) return node;
+                return conflicts
+                  ? {
+                      ...node,
+                      writtenHash,
+                      status: 'warning' as const,
+                      errorMessage: `Merge conflicts with manual edits in ${node.filePath}`,
+                    }
+                  : { ...node, writtenHash };
+              }),
+            },
+          });
+          if (conflicts) {
+            useToastStore.getState().addToast(`Merge conflicts in ${newNode.filePath}`, 'error');
+          } else if (merged) {
+            useToastStore.getState().addToast(`Merged manual edits in ${newNode.filePath}`, 'info');
+          }
+        })
+        .catch((err) => {
+          console.error('Failed to write file:', err);
+          useToastStore.getState().addToast(`Failed to write generated code: ${err}`, 'error');
+        });
     }
   },
 
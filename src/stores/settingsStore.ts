@@ -1,10 +1,16 @@
 import { create } from 'zustand';
 import { load, Store } from '@tauri-apps/plugin-store';
+import { setProxy } from '../lib/tauri';
 
 export interface Settings {
   anthropicApiKey: string;
   openaiApiKey: string;
+  mistralApiKey: string;
   ollamaBaseUrl: string;
+  // HTTP(S) or SOCKS proxy for provider requests; empty uses the environment's
+  proxyUrl: string;
+  // Personal access token pull requests for runs are opened with
+  githubToken: string;
 }
 
 interface SettingsState {
@@ -15,13 +21,16 @@ interface SettingsState {
   // Actions
   loadSettings: () => Promise<void>;
   updateSettings: (updates: Partial<Settings>) => Promise<void>;
-  getApiKey: (provider: 'anthropic' | 'openai' | 'ollama') => string;
+  getApiKey: (provider: 'anthropic' | 'openai' | 'mistral' | 'ollama' | 'local' | 'mock') => string;
 }
 
 const DEFAULT_SETTINGS: Settings = {
   anthropicApiKey: '',
   openaiApiKey: '',
+  mistralApiKey: '',
   ollamaBaseUrl: 'http://localhost:11434',
+  proxyUrl: '',
+  githubToken: '',
 };
 
 let store: Store | null = null;
@@ -43,10 +52,21 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       const s = await getStore();
       const anthropicApiKey = await s.get<string>('anthropicApiKey') ?? '';
       const openaiApiKey = await s.get<string>('openaiApiKey') ?? '';
+      const mistralApiKey = await s.get<string>('mistralApiKey') ?? '';
       const ollamaBaseUrl = await s.get<string>('ollamaBaseUrl') ?? 'http://localhost:11434';
+      const proxyUrl = await s.get<string>('proxyUrl') ?? '';
+      const githubToken = await s.get<string>('githubToken') ?? '';
+      await setProxy(proxyUrl || undefined);
 
       set({
-        settings: { anthropicApiKey, openaiApiKey, ollamaBaseUrl },
+        settings: {
+          anthropicApiKey,
+          openaiApiKey,
+          mistralApiKey,
+          ollamaBaseUrl,
+          proxyUrl,
+          githubToken,
+        },
         isLoaded: true,
       });
     } catch (error) {
@@ -69,9 +89,19 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       if (updates.openaiApiKey !== undefined) {
         await s.set('openaiApiKey', updates.openaiApiKey);
       }
+      if (updates.mistralApiKey !== undefined) {
+        await s.set('mistralApiKey', updates.mistralApiKey);
+      }
       if (updates.ollamaBaseUrl !== undefined) {
         await s.set('ollamaBaseUrl', updates.ollamaBaseUrl);
       }
+      if (updates.proxyUrl !== undefined) {
+        await setProxy(updates.proxyUrl || undefined);
+        await s.set('proxyUrl', updates.proxyUrl);
+      }
+      if (updates.githubToken !== undefined) {
+        await s.set('githubToken', updates.githubToken);
+      }
 
       await s.save();
       set({ settings: newSettings });
@@ -83,15 +113,19 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     }
   },
 
-  getApiKey: (provider: 'anthropic' | 'openai' | 'ollama') => {
+  getApiKey: (provider: 'anthropic' | 'openai' | 'mistral' | 'ollama' | 'local' | 'mock') => {
     const { settings } = get();
     switch (provider) {
       case 'anthropic':
         return settings.anthropicApiKey;
       case 'openai':
         return settings.openaiApiKey;
+      case 'mistral':
+        return settings.mistralApiKey;
       case 'ollama':
-        return ''; // Ollama doesn't need an API key
+      case 'local':
+      case 'mock':
+        return ''; // Local models and the mock provider don't need an API key
       default:
         return '';
     }